@@ -8,7 +8,8 @@ use std::time::{Duration, Instant};
 use anyhow::{Context, Result};
 use serde::Deserialize;
 use serde_json::{json, Value};
-use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use pm_encoder::jsonrpc::AsyncFramed;
+use tokio::io::BufReader;
 use tokio::process::{Child, ChildStdin, ChildStdout, Command};
 use tokio::time::timeout;
 
@@ -17,10 +18,11 @@ use crate::comparison::{Symbol, SymbolKind};
 /// LSP client for communicating with rust-analyzer
 pub struct LspClient {
     child: Child,
-    stdin: ChildStdin,
-    stdout: BufReader<ChildStdout>,
+    stdin: AsyncFramed<ChildStdin>,
+    stdout: AsyncFramed<BufReader<ChildStdout>>,
     next_id: u64,
     initialized: bool,
+    request_timeout: Duration,
 }
 
 /// LSP DocumentSymbol response structure
@@ -101,15 +103,22 @@ impl LspClient {
 
     /// Create a new LSP client by spawning rust-analyzer
     pub async fn new() -> Result<Self> {
-        let mut child = Command::new("rust-analyzer")
+        Self::spawn(Command::new("rust-analyzer"))
+            .context("Failed to spawn rust-analyzer. Is it installed?")
+    }
+
+    /// Create a client for any stdio LSP server (e.g. the `mock_lsp` test double)
+    pub fn spawn(mut command: Command) -> Result<Self> {
+        let mut child = command
             .stdin(std::process::Stdio::piped())
             .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .kill_on_drop(true)
             .spawn()
-            .context("Failed to spawn rust-analyzer. Is it installed?")?;
+            .context("Failed to spawn LSP server")?;
 
-        let stdin = child.stdin.take().context("Failed to open stdin")?;
-        let stdout = BufReader::new(child.stdout.take().context("Failed to open stdout")?);
+        let stdin = AsyncFramed::new(child.stdin.take().context("Failed to open stdin")?);
+        let stdout = AsyncFramed::new(BufReader::new(child.stdout.take().context("Failed to open stdout")?));
 
         Ok(Self {
             child,
@@ -117,9 +126,16 @@ impl LspClient {
             stdout,
             next_id: 1,
             initialized: false,
+            request_timeout: Duration::from_secs(30),
         })
     }
 
+    /// Timeout for requests after initialization (default 30s)
+    pub fn with_request_timeout(mut self, request_timeout: Duration) -> Self {
+        self.request_timeout = request_timeout;
+        self
+    }
+
     /// Initialize the LSP server with the given root path
     pub async fn initialize(&mut self, root_path: &Path) -> Result<Duration> {
         let start = Instant::now();
@@ -228,7 +244,7 @@ impl LspClient {
         });
         self.next_id += 1;
 
-        let response = timeout(Duration::from_secs(30), self.send_request(&request))
+        let response = timeout(self.request_timeout, self.send_request(&request))
             .await
             .context("Symbol request timeout")??;
         metrics.symbol_request_time = symbol_start.elapsed();
@@ -252,6 +268,30 @@ impl LspClient {
         Ok((symbols, metrics))
     }
 
+    /// Send an arbitrary request (e.g. `textDocument/hover`) and return its `result`
+    ///
+    /// JSON-RPC errors are returned as `Err`.
+    pub async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        if !self.initialized {
+            anyhow::bail!("LSP client not initialized");
+        }
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id,
+            "method": method,
+            "params": params
+        });
+        self.next_id += 1;
+
+        let response = timeout(self.request_timeout, self.send_request(&request))
+            .await
+            .with_context(|| format!("{} request timeout", method))??;
+        if let Some(error) = response.get("error") {
+            anyhow::bail!("{} failed: {}", method, error);
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+
     /// Shutdown the LSP server gracefully
     pub async fn shutdown(mut self) -> Result<()> {
         // Send shutdown request
@@ -281,56 +321,29 @@ impl LspClient {
 
     /// Send a JSON-RPC request and wait for response
     async fn send_request(&mut self, request: &Value) -> Result<Value> {
-        let message = serde_json::to_string(request)?;
-        let frame = format!("Content-Length: {}\r\n\r\n{}", message.len(), message);
-
-        self.stdin.write_all(frame.as_bytes()).await?;
-        self.stdin.flush().await?;
+        self.stdin.write_json(request).await?;
 
         // Read response
-        self.read_response().await
+        self.read_response(&request["id"]).await
     }
 
     /// Send a JSON-RPC notification (no response expected)
     async fn send_notification(&mut self, notification: &Value) -> Result<()> {
-        let message = serde_json::to_string(notification)?;
-        let frame = format!("Content-Length: {}\r\n\r\n{}", message.len(), message);
-
-        self.stdin.write_all(frame.as_bytes()).await?;
-        self.stdin.flush().await?;
-
+        self.stdin.write_json(notification).await?;
         Ok(())
     }
 
-    /// Read a JSON-RPC response from the server
-    async fn read_response(&mut self) -> Result<Value> {
+    /// Read the JSON-RPC response to request `id` from the server
+    async fn read_response(&mut self, id: &Value) -> Result<Value> {
         loop {
-            // Read headers
-            let mut content_length: Option<usize> = None;
-            loop {
-                let mut line = String::new();
-                self.stdout.read_line(&mut line).await?;
-                let line = line.trim();
-
-                if line.is_empty() {
-                    break;
-                }
-
-                if let Some(value) = line.strip_prefix("Content-Length: ") {
-                    content_length = Some(value.parse()?);
-                }
-            }
-
-            let content_length = content_length.context("Missing Content-Length header")?;
-
-            // Read body
-            let mut body = vec![0u8; content_length];
-            self.stdout.read_exact(&mut body).await?;
-
-            let response: Value = serde_json::from_slice(&body)?;
-
-            // Skip notifications (no id field) - we only want responses
-            if response.get("id").is_some() {
+            let response = self.stdout
+                .read_json()
+                .await?
+                .context("LSP server closed its stdout")?;
+
+            // Skip notifications, server-initiated requests and stale replies
+            // to requests that already timed out
+            if response.get("method").is_none() && response.get("id") == Some(id) {
                 return Ok(response);
             }
         }
@@ -9,8 +9,10 @@
 //! - `metrics`: Latency and accuracy measurement infrastructure
 //! - `comparison`: Regex vs LSP symbol extraction comparison
 //! - `client`: Production-ready LSP client for rust-analyzer
+//! - `mock`: Scriptable mock LSP server with fault injection (test double)
 
 pub mod protocol;
 pub mod metrics;
 pub mod comparison;
 pub mod client;
+pub mod mock;
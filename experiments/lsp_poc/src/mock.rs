@@ -0,0 +1,469 @@
+//! Scriptable mock LSP server (conformance test double)
+//!
+//! Serves the subset of LSP that the client uses — `initialize`,
+//! `textDocument/documentSymbol`, `textDocument/hover`,
+//! `textDocument/definition`, `shutdown`/`exit` and document sync
+//! notifications — so clients can be integration-tested without
+//! rust-analyzer installed.
+//!
+//! By default answers are derived from the documents the client opened
+//! (Rust-like `fn`/`struct`/... declarations). A [`MockScript`] overrides
+//! them per method with canned results or errors, and injects faults:
+//! slow responses, malformed or truncated frames, silence and crashes.
+//!
+//! ```json
+//! {
+//!   "responses": {
+//!     "textDocument/hover": [
+//!       { "delay_ms": 500, "result": { "contents": "slow" } },
+//!       { "fault": { "type": "malformed" } }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! Steps are consumed in order; the last one repeats.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use pm_encoder::jsonrpc::{FrameError, Framed};
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+
+/// Environment variable holding the path of a script for the `mock_lsp` binary
+pub const SCRIPT_ENV: &str = "MOCK_LSP_SCRIPT";
+
+/// Exit status the `mock_lsp` binary uses for an injected crash
+pub const CRASH_EXIT_CODE: i32 = 101;
+
+static DECLARATION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:async\s+)?(fn|struct|enum|trait|mod|const|type)\s+([A-Za-z_][A-Za-z0-9_]*)")
+        .unwrap()
+});
+
+/// A fault to inject instead of a normal reply
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Fault {
+    /// Reply with a well-framed body that is not JSON
+    Malformed,
+    /// Announce a longer body than is sent, then exit
+    Truncated,
+    /// Never reply to this request
+    Silent,
+    /// Exit immediately without replying
+    Crash,
+}
+
+/// JSON-RPC error to reply with
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ScriptedError {
+    pub code: i64,
+    pub message: String,
+}
+
+/// One scripted reply
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Step {
+    /// Result to return (`null` is a valid result; absent = default handler)
+    #[serde(default, deserialize_with = "present")]
+    pub result: Option<Value>,
+    #[serde(default)]
+    pub error: Option<ScriptedError>,
+    /// Wait before replying
+    #[serde(default)]
+    pub delay_ms: u64,
+    #[serde(default)]
+    pub fault: Option<Fault>,
+}
+
+/// Distinguish `"result": null` from a missing `result`
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// Scripted behaviour, keyed by method name
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MockScript {
+    #[serde(default)]
+    pub responses: HashMap<String, Vec<Step>>,
+    /// Replaces the advertised server capabilities
+    #[serde(default)]
+    pub capabilities: Option<Value>,
+}
+
+impl MockScript {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid mock script: {}", e))
+    }
+
+    /// Script at `$MOCK_LSP_SCRIPT`, or the empty script
+    pub fn from_env() -> Result<Self, String> {
+        match std::env::var_os(SCRIPT_ENV) {
+            Some(path) => {
+                let json = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read {}: {}", path.to_string_lossy(), e))?;
+                Self::from_json(&json)
+            }
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Add a step for `method`
+    pub fn step(mut self, method: &str, step: Step) -> Self {
+        self.responses.entry(method.to_string()).or_default().push(step);
+        self
+    }
+}
+
+/// Why [`MockServer::serve`] returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// The client sent `exit`
+    Exited,
+    /// The input stream ended
+    Eof,
+    /// A crash fault fired
+    Crashed,
+}
+
+/// The mock server state: script queues and open documents
+#[derive(Debug, Default)]
+pub struct MockServer {
+    capabilities: Option<Value>,
+    queues: HashMap<String, VecDeque<Step>>,
+    documents: HashMap<String, String>,
+    shutdown_requested: bool,
+}
+
+impl MockServer {
+    pub fn new(script: MockScript) -> Self {
+        Self {
+            capabilities: script.capabilities,
+            queues: script.responses.into_iter().map(|(m, steps)| (m, steps.into())).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Serve framed messages from `reader` until exit, end of input or a crash fault
+    pub fn serve<R: BufRead, W: Write>(&mut self, reader: R, writer: W) -> Result<Exit, FrameError> {
+        let mut reader = Framed::new(reader);
+        let mut writer = Framed::new(writer);
+        loop {
+            let message = match reader.read_json() {
+                Ok(Some(message)) => message,
+                Ok(None) => return Ok(Exit::Eof),
+                Err(FrameError::InvalidJson(_)) => {
+                    writer.write_json(&error_response(Value::Null, -32700, "Parse error"))?;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            let Some(method) = message["method"].as_str() else {
+                // Replies to server-initiated requests are ignored; anything else is invalid
+                if message.get("result").is_none() && message.get("error").is_none() {
+                    let id = message.get("id").cloned().unwrap_or(Value::Null);
+                    writer.write_json(&error_response(id, -32600, "Invalid Request"))?;
+                }
+                continue;
+            };
+            let params = &message["params"];
+            let Some(id) = message.get("id").cloned() else {
+                if method == "exit" {
+                    return Ok(Exit::Exited);
+                }
+                self.notification(method, params);
+                continue;
+            };
+
+            let step = self.next_step(method);
+            if step.delay_ms > 0 {
+                std::thread::sleep(Duration::from_millis(step.delay_ms));
+            }
+            match step.fault {
+                Some(Fault::Crash) => return Ok(Exit::Crashed),
+                Some(Fault::Silent) => continue,
+                Some(Fault::Malformed) => {
+                    writer.write_message(b"{not json")?;
+                    continue;
+                }
+                Some(Fault::Truncated) => {
+                    let inner = writer.get_mut();
+                    inner.write_all(b"Content-Length: 1000\r\n\r\n{\"jsonrpc\":\"2.0\"")?;
+                    inner.flush()?;
+                    return Ok(Exit::Crashed);
+                }
+                None => {}
+            }
+
+            let response = if let Some(error) = step.error {
+                error_response(id, error.code, &error.message)
+            } else if let Some(result) = step.result {
+                json!({ "jsonrpc": "2.0", "id": id, "result": result })
+            } else {
+                match self.handle(method, params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err((code, message)) => error_response(id, code, message),
+                }
+            };
+            writer.write_json(&response)?;
+        }
+    }
+
+    fn next_step(&mut self, method: &str) -> Step {
+        match self.queues.get_mut(method) {
+            Some(queue) if queue.len() > 1 => queue.pop_front().unwrap_or_default(),
+            Some(queue) => queue.front().cloned().unwrap_or_default(),
+            None => Step::default(),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default().to_string();
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri, text.to_string());
+            }
+            "textDocument/didChange" => {
+                // Full sync only (textDocumentSync = 1)
+                if let Some(text) = params["contentChanges"].as_array().and_then(|c| c.last()).and_then(|c| c["text"].as_str()) {
+                    self.documents.insert(uri, text.to_string());
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+            }
+            _ => {}
+        }
+    }
+
+    /// Default (unscripted) handlers
+    fn handle(&mut self, method: &str, params: &Value) -> Result<Value, (i64, &'static str)> {
+        if self.shutdown_requested && method != "shutdown" {
+            return Err((-32600, "Server is shutting down"));
+        }
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": self.capabilities.clone().unwrap_or_else(|| json!({
+                    "textDocumentSync": 1,
+                    "documentSymbolProvider": true,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "foldingRangeProvider": true
+                })),
+                "serverInfo": { "name": "mock-lsp", "version": env!("CARGO_PKG_VERSION") }
+            })),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/documentSymbol" => {
+                let text = self.document(params)?;
+                Ok(Value::Array(
+                    text.lines()
+                        .enumerate()
+                        .filter_map(|(line, source)| {
+                            let caps = DECLARATION.captures(source)?;
+                            let name = caps.get(2)?;
+                            let selection = range(line, name.start(), name.end());
+                            Some(json!({
+                                "name": name.as_str(),
+                                "kind": symbol_kind(&caps[1]),
+                                "range": range(line, 0, source.len()),
+                                "selectionRange": selection
+                            }))
+                        })
+                        .collect(),
+                ))
+            }
+            "textDocument/hover" => {
+                let text = self.document(params)?;
+                Ok(match word_at(text, &params["position"]) {
+                    Some((_, line)) => json!({
+                        "contents": { "kind": "markdown", "value": format!("```rust\n{}\n```", line.trim()) }
+                    }),
+                    None => Value::Null,
+                })
+            }
+            "textDocument/definition" => {
+                let text = self.document(params)?;
+                let Some((word, _)) = word_at(text, &params["position"]) else { return Ok(Value::Null) };
+                let word = word.to_string();
+                let mut uris: Vec<&String> = self.documents.keys().collect();
+                uris.sort();
+                for uri in uris {
+                    for (line, source) in self.documents[uri].lines().enumerate() {
+                        if let Some(name) = DECLARATION.captures(source).and_then(|c| c.get(2)) {
+                            if name.as_str() == word {
+                                return Ok(json!({ "uri": uri, "range": range(line, name.start(), name.end()) }));
+                            }
+                        }
+                    }
+                }
+                Ok(Value::Null)
+            }
+            _ => Err((-32601, "Method not found")),
+        }
+    }
+
+    fn document(&self, params: &Value) -> Result<&str, (i64, &'static str)> {
+        params["textDocument"]["uri"]
+            .as_str()
+            .and_then(|uri| self.documents.get(uri))
+            .map(String::as_str)
+            .ok_or((-32602, "Document is not open"))
+    }
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn range(line: usize, start: usize, end: usize) -> Value {
+    json!({
+        "start": { "line": line, "character": start },
+        "end": { "line": line, "character": end }
+    })
+}
+
+/// LSP SymbolKind for a declaration keyword
+fn symbol_kind(keyword: &str) -> u32 {
+    match keyword {
+        "fn" => 12,
+        "struct" => 23,
+        "enum" => 10,
+        "trait" => 11,
+        "mod" => 2,
+        "const" => 14,
+        _ => 26,
+    }
+}
+
+/// Identifier under an LSP position, with its line
+fn word_at<'a>(text: &'a str, position: &Value) -> Option<(&'a str, &'a str)> {
+    let line = text.lines().nth(position["line"].as_u64()? as usize)?;
+    let column = (position["character"].as_u64()? as usize).min(line.len());
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let start = line.get(..column)?
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_ident(*c))
+        .last()
+        .map_or(column, |(i, _)| i);
+    let end = column + line[column..].chars().take_while(|c| is_ident(*c)).map(char::len_utf8).sum::<usize>();
+    let word = &line[start..end];
+    (!word.is_empty()).then_some((word, line))
+}
+
+/// Run the mock over stdin/stdout (used by the `mock_lsp` binary)
+pub fn run_stdio(script: MockScript) -> Result<Exit, FrameError> {
+    let stdin = io::stdin();
+    MockServer::new(script).serve(stdin.lock(), io::stdout().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pm_encoder::jsonrpc::encode_frame;
+    use std::io::Cursor;
+
+    fn frames(messages: &[Value]) -> Vec<u8> {
+        messages.iter().flat_map(|m| encode_frame(m.to_string().as_bytes())).collect()
+    }
+
+    fn replies(output: Vec<u8>) -> Vec<Value> {
+        let mut reader = Framed::new(Cursor::new(output));
+        std::iter::from_fn(|| reader.read_json().ok().flatten()).collect()
+    }
+
+    const SOURCE: &str = "pub struct Config;\n\nfn load() -> Config {\n    Config\n}\n";
+
+    fn open() -> Value {
+        json!({"jsonrpc": "2.0", "method": "textDocument/didOpen",
+               "params": {"textDocument": {"uri": "file:///a.rs", "text": SOURCE}}})
+    }
+
+    fn request(id: u64, method: &str, params: Value) -> Value {
+        json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params})
+    }
+
+    #[test]
+    fn test_default_handlers() {
+        let doc = json!({"textDocument": {"uri": "file:///a.rs"}});
+        let at = |line: u32, character: u32| json!({"textDocument": {"uri": "file:///a.rs"}, "position": {"line": line, "character": character}});
+        let input = frames(&[
+            request(1, "initialize", json!({})),
+            open(),
+            request(2, "textDocument/documentSymbol", doc),
+            request(3, "textDocument/hover", at(2, 4)),
+            request(4, "textDocument/definition", at(2, 15)),
+            request(5, "unknown/method", json!({})),
+            json!({"jsonrpc": "2.0", "method": "exit"}),
+        ]);
+        let mut output = Vec::new();
+        let exit = MockServer::new(MockScript::default()).serve(Cursor::new(input), &mut output).unwrap();
+        assert_eq!(exit, Exit::Exited);
+
+        let replies = replies(output);
+        assert_eq!(replies[0]["result"]["serverInfo"]["name"], "mock-lsp");
+        let symbols = replies[1]["result"].as_array().unwrap();
+        assert_eq!(symbols.len(), 2);
+        assert_eq!((symbols[1]["name"].as_str(), symbols[1]["kind"].as_u64()), (Some("load"), Some(12)));
+        assert!(replies[2]["result"]["contents"]["value"].as_str().unwrap().contains("fn load()"));
+        assert_eq!(replies[3]["result"]["range"]["start"]["line"], 0, "Config is declared on line 0");
+        assert_eq!(replies[4]["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn test_scripted_steps_and_faults() {
+        let script = MockScript::from_json(r#"{
+            "responses": {
+                "textDocument/hover": [
+                    { "result": null },
+                    { "error": { "code": -32801, "message": "content modified" } },
+                    { "fault": { "type": "malformed" } }
+                ],
+                "textDocument/definition": [{ "fault": { "type": "silent" } }],
+                "shutdown": [{ "fault": { "type": "crash" } }]
+            }
+        }"#).unwrap();
+        let input = frames(&[
+            request(1, "textDocument/hover", json!({})),
+            request(2, "textDocument/hover", json!({})),
+            request(3, "textDocument/definition", json!({})),
+            request(4, "textDocument/hover", json!({})),
+            request(5, "textDocument/hover", json!({})),
+            request(6, "shutdown", json!(null)),
+            request(7, "initialize", json!({})),
+        ]);
+        let mut output = Vec::new();
+        let exit = MockServer::new(script).serve(Cursor::new(input), &mut output).unwrap();
+        assert_eq!(exit, Exit::Crashed);
+
+        let mut reader = Framed::new(Cursor::new(output));
+        let first = reader.read_json().unwrap().unwrap();
+        assert!(first["result"].is_null() && first.get("result").is_some());
+        assert_eq!(reader.read_json().unwrap().unwrap()["error"]["code"], -32801);
+        // The last step repeats; the silent definition produced nothing
+        assert!(matches!(reader.read_json(), Err(FrameError::InvalidJson(_))));
+        assert!(matches!(reader.read_json(), Err(FrameError::InvalidJson(_))));
+        assert!(reader.read_json().unwrap().is_none(), "crash before replying to shutdown");
+    }
+
+    #[test]
+    fn test_invalid_messages() {
+        let mut input = encode_frame(b"{oops");
+        input.extend(frames(&[json!({"not": "valid"})]));
+        let mut output = Vec::new();
+        MockServer::default().serve(Cursor::new(input), &mut output).unwrap();
+        let replies = replies(output);
+        assert_eq!(replies[0]["error"]["code"], -32700);
+        assert_eq!(replies[1]["error"]["code"], -32600);
+    }
+}
@@ -0,0 +1,104 @@
+//! Conformance tests against the scriptable mock LSP server
+//!
+//! Exercise `LspClient` end to end (spawned process, framing, timeouts and
+//! error paths) without rust-analyzer installed.
+
+use std::time::Duration;
+
+use lsp_poc::client::LspClient;
+use lsp_poc::mock::SCRIPT_ENV;
+use serde_json::json;
+use tokio::process::Command;
+
+/// Spawn `mock_lsp`, optionally with a script, and initialize a client
+async fn client(script: Option<&str>) -> (LspClient, tempfile::TempDir) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut command = Command::new(env!("CARGO_BIN_EXE_mock_lsp"));
+    if let Some(script) = script {
+        let path = dir.path().join("script.json");
+        std::fs::write(&path, script).unwrap();
+        command.env(SCRIPT_ENV, path);
+    }
+    let mut client = LspClient::spawn(command)
+        .unwrap()
+        .with_request_timeout(Duration::from_millis(500));
+    client.initialize(dir.path()).await.expect("initialize");
+    (client, dir)
+}
+
+#[tokio::test]
+async fn test_document_symbol_hover_definition() {
+    let (mut client, dir) = client(None).await;
+    let file = dir.path().join("lib.rs");
+    std::fs::write(&file, "pub struct Config;\n\npub fn load() -> Config {\n    Config\n}\n").unwrap();
+
+    let (symbols, _) = client.document_symbol(&file).await.expect("documentSymbol");
+    let names: Vec<_> = symbols.iter().map(|s| (s.name.as_str(), s.line)).collect();
+    assert_eq!(names, vec![("Config", 1), ("load", 3)]);
+
+    // document_symbol closes the file again, so position queries are rejected
+    let uri = format!("file://{}", file.canonicalize().unwrap().display());
+    client.request("textDocument/hover", json!({"textDocument": {"uri": &uri}, "position": {"line": 0, "character": 0}}))
+        .await
+        .expect_err("hover on a closed document is an error");
+    client.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_scripted_responses() {
+    let script = r#"{
+        "responses": {
+            "textDocument/hover": [{ "result": { "contents": "scripted" } }],
+            "textDocument/definition": [
+                { "error": { "code": -32801, "message": "content modified" } },
+                { "result": null }
+            ]
+        }
+    }"#;
+    let (mut client, _dir) = client(Some(script)).await;
+
+    let hover = client.request("textDocument/hover", json!({})).await.unwrap();
+    assert_eq!(hover["contents"], "scripted");
+    let error = client.request("textDocument/definition", json!({})).await.unwrap_err();
+    assert!(error.to_string().contains("content modified"));
+    assert!(client.request("textDocument/definition", json!({})).await.unwrap().is_null());
+}
+
+#[tokio::test]
+async fn test_slow_response_times_out_and_client_recovers() {
+    let script = r#"{
+        "responses": {
+            "textDocument/hover": [
+                { "delay_ms": 1500, "result": "late" },
+                { "result": "on time" }
+            ]
+        }
+    }"#;
+    let (mut client, _dir) = client(Some(script)).await;
+
+    let error = client.request("textDocument/hover", json!({})).await.unwrap_err();
+    assert!(error.to_string().contains("timeout"));
+    // The late reply to the first request is skipped, not returned here
+    let hover = client
+        .with_request_timeout(Duration::from_secs(5))
+        .request("textDocument/hover", json!({}))
+        .await
+        .unwrap();
+    assert_eq!(hover, "on time");
+}
+
+#[tokio::test]
+async fn test_malformed_frame_and_crash() {
+    let script = r#"{
+        "responses": {
+            "textDocument/hover": [{ "fault": { "type": "malformed" } }],
+            "textDocument/definition": [{ "fault": { "type": "crash" } }]
+        }
+    }"#;
+    let (mut client, _dir) = client(Some(script)).await;
+
+    let error = client.request("textDocument/hover", json!({})).await.unwrap_err();
+    assert!(error.to_string().contains("Invalid JSON"), "{}", error);
+    let error = client.request("textDocument/definition", json!({})).await.unwrap_err();
+    assert!(error.to_string().contains("closed"), "{}", error);
+}
@@ -0,0 +1,111 @@
+//! End-to-end pipeline benchmarks
+//!
+//! Covers parse throughput per language, full-project indexing, token
+//! budgeting, and serialization on the bundled synthetic corpus
+//! (`pm_encoder::core::bench::SyntheticCorpus`). `vo --bench` runs the same
+//! cases without criterion and emits a JSON report for baseline comparison.
+//!
+//! Run with: `cargo bench --bench pipeline`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use pm_encoder::budgeting::apply_token_budget;
+use pm_encoder::core::ast_bridge::AstBridge;
+use pm_encoder::core::bench::SyntheticCorpus;
+use pm_encoder::core::serialization::{PlusMinusSerializer, Serializer};
+use pm_encoder::LensManager;
+use voyager_ast::{AstProvider, IndexOptions, TreeSitterProvider};
+
+const FILES_PER_LANGUAGE: usize = 20;
+const FUNCTIONS_PER_FILE: usize = 25;
+
+fn corpus() -> SyntheticCorpus {
+    SyntheticCorpus::generate(FILES_PER_LANGUAGE, FUNCTIONS_PER_FILE)
+}
+
+// =============================================================================
+// Parsing
+// =============================================================================
+
+fn bench_parse_per_language(c: &mut Criterion) {
+    let corpus = corpus();
+    let bridge = AstBridge::new();
+    let mut group = c.benchmark_group("parse");
+
+    for language in corpus.languages() {
+        let files: Vec<_> = corpus.files_for(language).collect();
+        let bytes: u64 = files.iter().map(|e| e.size).sum();
+        group.throughput(Throughput::Bytes(bytes));
+        group.bench_with_input(BenchmarkId::from_parameter(language.name()), &files, |b, files| {
+            b.iter(|| {
+                for entry in files {
+                    black_box(bridge.analyze_file(&entry.content, language));
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+// =============================================================================
+// Indexing
+// =============================================================================
+
+fn bench_index_project(c: &mut Criterion) {
+    let corpus = corpus();
+    let dir = tempfile::TempDir::new().unwrap();
+    corpus.write_to(dir.path()).unwrap();
+    let provider = TreeSitterProvider::new();
+    let options = IndexOptions::default();
+
+    let mut group = c.benchmark_group("index");
+    group.throughput(Throughput::Bytes(corpus.total_bytes()));
+    group.bench_function("project", |b| {
+        b.iter(|| black_box(provider.index_project(dir.path(), &options).unwrap()))
+    });
+    group.finish();
+}
+
+// =============================================================================
+// Budgeting and Serialization
+// =============================================================================
+
+fn bench_budgeting(c: &mut Criterion) {
+    let corpus = corpus();
+    let lens_manager = LensManager::new();
+    let pairs: Vec<(String, String)> = corpus
+        .entries()
+        .map(|e| (e.path.clone(), e.content.clone()))
+        .collect();
+
+    let mut group = c.benchmark_group("budget");
+    group.throughput(Throughput::Bytes(corpus.total_bytes()));
+    for strategy in ["drop", "truncate", "hybrid"] {
+        group.bench_with_input(BenchmarkId::from_parameter(strategy), strategy, |b, strategy| {
+            b.iter(|| black_box(apply_token_budget(pairs.clone(), 50_000, &lens_manager, strategy)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_serialization(c: &mut Criterion) {
+    let corpus = corpus();
+    let processed = corpus.processed();
+    let serializer = PlusMinusSerializer::new();
+
+    let mut group = c.benchmark_group("serialize");
+    group.throughput(Throughput::Bytes(corpus.total_bytes()));
+    group.bench_function("plusminus", |b| {
+        b.iter(|| black_box(serializer.serialize_files(&processed)))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_parse_per_language,
+    bench_index_project,
+    bench_budgeting,
+    bench_serialization,
+);
+criterion_main!(benches);
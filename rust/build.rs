@@ -0,0 +1,11 @@
+//! Build script: compiles the gRPC service definition when the `grpc`
+//! feature is enabled (requires `protoc` on the PATH)
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/pm_encoder.proto");
+        tonic_build::compile_protos("proto/pm_encoder.proto")
+            .expect("failed to compile proto/pm_encoder.proto");
+    }
+}
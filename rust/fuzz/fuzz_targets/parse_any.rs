@@ -0,0 +1,12 @@
+#![no_main]
+
+//! First byte selects the language, so one corpus exercises every adapter
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some((selector, source)) = data.split_first() {
+        let languages = voyager_fuzz::LANGUAGES;
+        voyager_fuzz::check_parse(languages[*selector as usize % languages.len()], source);
+    }
+});
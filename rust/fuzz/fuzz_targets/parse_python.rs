@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use voyager_ast::LanguageId;
+
+fuzz_target!(|data: &[u8]| {
+    voyager_fuzz::check_parse(LanguageId::Python, data);
+});
@@ -0,0 +1,12 @@
+const path = require("path");
+
+class Counter {
+  constructor() { this.count = 0; }
+  increment() { return ++this.count; }
+}
+
+function run(items) {
+  return items.map((x) => x * 2).filter(Boolean);
+}
+
+module.exports = { Counter, run };
@@ -0,0 +1,21 @@
+import os
+from typing import List
+
+
+class Greeter:
+    """Says hello."""
+
+    def __init__(self, name: str = "world"):
+        self.name = name
+
+    @property
+    def greeting(self) -> str:
+        return f"Hello, {self.name}"
+
+
+def main(args: List[str]) -> int:
+    for arg in args:
+        if arg.startswith("-"):
+            continue
+        print(Greeter(arg).greeting)
+    return 0
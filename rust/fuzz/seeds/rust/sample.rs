@@ -0,0 +1,11 @@
+/// A documented struct
+pub struct Point { pub x: i32, y: i32 }
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Self { Self { x, y } }
+    fn norm(&self) -> f64 { ((self.x * self.x + self.y * self.y) as f64).sqrt() }
+}
+
+pub trait Shape { fn area(&self) -> f64; }
+
+mod inner { pub(crate) const LIMIT: usize = 10; }
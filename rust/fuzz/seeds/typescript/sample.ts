@@ -0,0 +1,13 @@
+import { readFile } from "fs";
+
+export interface User { id: number; name: string }
+
+export class Repo<T extends User> {
+  private items: Map<number, T> = new Map();
+  add(item: T): void { this.items.set(item.id, item); }
+  find = (id: number): T | undefined => this.items.get(id);
+}
+
+export function greet(user: User): string {
+  return `Hello ${user.name}`;
+}
@@ -0,0 +1,57 @@
+//! Shared checks for the fuzz targets
+//!
+//! Run a target with (nightly + cargo-fuzz):
+//!
+//! ```text
+//! cd rust/fuzz
+//! cargo +nightly fuzz run parse_rust seeds/rust
+//! ```
+//!
+//! `seeds/` holds small real sources per language; libFuzzer mutates them,
+//! which reaches far more grammar states than random bytes alone.
+
+use voyager_ast::{AstProvider, File, LanguageId, TreeSitterProvider};
+
+/// Languages with tree-sitter adapters, indexed by `parse_any`'s selector byte
+pub const LANGUAGES: &[LanguageId] = &[
+    LanguageId::Rust,
+    LanguageId::Python,
+    LanguageId::TypeScript,
+    LanguageId::JavaScript,
+];
+
+thread_local! {
+    static PROVIDER: TreeSitterProvider = TreeSitterProvider::new();
+}
+
+/// Parse `data` as `language` and assert the IR invariants
+///
+/// - parsing never panics (non-UTF-8 input is skipped: callers always decode first)
+/// - every span lies within the source and has `start <= end`
+/// - the IR survives a serde JSON round trip unchanged
+pub fn check_parse(language: LanguageId, data: &[u8]) {
+    let Ok(source) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    let file = PROVIDER.with(|provider| match provider.parse_file(source, language) {
+        Ok(file) => Some(file),
+        Err(e) => e.take_partial(),
+    });
+    let Some(file) = file else {
+        return;
+    };
+
+    let violations = file.span_violations(source.len());
+    assert!(violations.is_empty(), "span invariants violated: {:#?}", violations);
+
+    check_round_trip(&file);
+}
+
+/// Assert that `file` serializes to the same JSON after a round trip
+pub fn check_round_trip(file: &File) {
+    let json = serde_json::to_string(file).expect("IR must serialize");
+    let back: File = serde_json::from_str(&json).expect("IR must deserialize");
+    let again = serde_json::to_string(&back).expect("IR must re-serialize");
+    assert_eq!(json, again, "IR changed across a serde round trip");
+}
@@ -0,0 +1,57 @@
+//! pm_encoder gRPC Server
+//!
+//! Serves the index, zoom, context and search APIs over gRPC for backend
+//! services (see `proto/pm_encoder.proto`).
+//!
+//! Build: cargo build --features grpc --bin pm_encoder_grpc
+//! Run:   ./target/debug/pm_encoder_grpc [PROJECT_ROOT] [--addr 127.0.0.1:50051]
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Parser;
+use pm_encoder::server::grpc::{serve, DEFAULT_GRPC_ADDR};
+use pm_encoder::server::limits::ServerLimits;
+
+#[derive(Parser, Debug)]
+#[command(name = "pm_encoder_grpc", version, about = "pm_encoder gRPC server")]
+struct Args {
+    /// Default project root for requests that omit `path`
+    #[arg(default_value = ".")]
+    root: PathBuf,
+
+    /// Address to listen on
+    #[arg(long, default_value = DEFAULT_GRPC_ADDR)]
+    addr: SocketAddr,
+
+    /// Maximum RPCs doing index/context work at once [default: CPU count]
+    #[arg(long)]
+    max_jobs: Option<usize>,
+
+    /// RPCs allowed to wait for a job slot before callers get RESOURCE_EXHAUSTED
+    #[arg(long, default_value_t = 16)]
+    max_queue: usize,
+
+    /// Seconds a queued RPC waits for a job slot
+    #[arg(long, default_value_t = 30)]
+    queue_timeout: u64,
+
+    /// Requests per minute per client IP (0 = unlimited)
+    #[arg(long, default_value_t = 0)]
+    rate_limit: u32,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let defaults = ServerLimits::default();
+    let limits = ServerLimits {
+        max_jobs: args.max_jobs.unwrap_or(defaults.max_jobs),
+        max_queue: args.max_queue,
+        queue_timeout: Duration::from_secs(args.queue_timeout),
+        requests_per_minute: args.rate_limit,
+    };
+    serve(args.addr, args.root, limits).await?;
+    Ok(())
+}
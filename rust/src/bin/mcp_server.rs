@@ -7,15 +7,16 @@
 //! Run:   ./target/debug/pm_encoder_mcp
 
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use pm_encoder::{
     ContextEngine, EncoderConfig, LensManager,
     parse_token_budget, apply_token_budget,
 };
 use pm_encoder::core::{
-    ContextEngine as CoreContextEngine,
-    ZoomConfig, ZoomTarget, ZoomDepth,
-    ContextStore, DEFAULT_ALPHA,
+    Anchor, ZoomConfig, ZoomTarget, ZoomDepth,
+    ContextStore, DEFAULT_ALPHA, normalize_relative_path,
 };
+use pm_encoder::server::async_api::{generate_context_async, zoom_async, BlockingPool, TaskError};
 use rmcp::{
     schemars,
     schemars::JsonSchema,
@@ -30,6 +31,20 @@ use rmcp::{
 use serde::Deserialize;
 use tokio::io::{stdin, stdout};
 
+/// Blocking pool shared by all tool calls, so long scans never run on runtime threads
+fn pool() -> &'static BlockingPool {
+    static POOL: OnceLock<BlockingPool> = OnceLock::new();
+    POOL.get_or_init(BlockingPool::default)
+}
+
+/// Map an async job failure to a tool error
+fn task_error(error: TaskError) -> rmcp::ErrorData {
+    match error {
+        TaskError::Busy(busy) => rmcp::ErrorData::internal_error(busy.to_string(), Some(busy.to_json())),
+        TaskError::Failed(message) => rmcp::ErrorData::invalid_params(message, None),
+    }
+}
+
 /// MCP Server for pm_encoder
 #[derive(Clone)]
 struct PmEncoderServer {
@@ -50,7 +65,7 @@ struct GetContextParams {
     /// Maximum token budget (e.g., "100000", "100k", "2M")
     #[serde(default)]
     token_budget: Option<String>,
-    /// Budget strategy: "drop", "truncate", or "hybrid"
+    /// Budget strategy: "drop", "truncate", "hybrid", or "summarize"
     #[serde(default)]
     budget_strategy: Option<String>,
 }
@@ -73,9 +88,9 @@ struct ListLensesParams {}
 struct ZoomContextParams {
     /// Root directory to search in
     root: String,
-    /// Zoom target type: "fn", "class", "mod", or "file"
+    /// Zoom target type: "fn", "class", "mod", "section", "anchor", or "file"
     target_type: String,
-    /// Target name (function name, class name, module name, or file path)
+    /// Target name (function, class or module name, section heading, anchor, or file path)
     target_name: String,
     /// Optional line range for file zoom (e.g., "10-50")
     #[serde(default)]
@@ -183,7 +198,9 @@ impl PmEncoderServer {
                 };
 
                 // Generate context
-                let context = engine.generate_context(&selected_files);
+                let context = generate_context_async(pool(), engine, selected_files)
+                    .await
+                    .map_err(task_error)?;
 
                 Ok(CallToolResult::success(vec![Content::text(context)]))
             })
@@ -242,6 +259,11 @@ impl PmEncoderServer {
                     "fn" | "function" => ZoomTarget::Function(params.target_name.clone()),
                     "class" | "struct" => ZoomTarget::Class(params.target_name.clone()),
                     "mod" | "module" => ZoomTarget::Module(params.target_name.clone()),
+                    "section" | "heading" => ZoomTarget::Section(params.target_name.clone()),
+                    "anchor" | "id" => ZoomTarget::Anchor(
+                        Anchor::parse(&params.target_name)
+                            .map_err(|e| rmcp::ErrorData::invalid_params(e.to_string(), None))?,
+                    ),
                     "file" => {
                         // Parse optional line range
                         let (start, end) = if let Some(ref range) = params.line_range {
@@ -264,7 +286,7 @@ impl PmEncoderServer {
                     _ => {
                         return Err(rmcp::ErrorData::invalid_params(
                             format!(
-                                "Invalid target_type '{}'. Use: fn, class, mod, or file",
+                                "Invalid target_type '{}'. Use: fn, class, mod, file, section, or anchor",
                                 params.target_type
                             ),
                             None,
@@ -285,18 +307,15 @@ impl PmEncoderServer {
                     budget: params.token_budget,
                     depth,
                     include_tests: false,
+                    include_docs: false,
                     context_lines: 5,
                 };
 
-                // Create core engine and perform zoom
-                let engine = CoreContextEngine::new();
-                match engine.zoom(&params.root, &zoom_config) {
-                    Ok(content) => Ok(CallToolResult::success(vec![Content::text(content)])),
-                    Err(e) => Err(rmcp::ErrorData::invalid_params(
-                        format!("Zoom failed: {}", e),
-                        None,
-                    )),
-                }
+                // Perform zoom on the blocking pool
+                let content = zoom_async(pool(), params.root, zoom_config)
+                    .await
+                    .map_err(task_error)?;
+                Ok(CallToolResult::success(vec![Content::text(content)]))
             })
         })
     }
@@ -325,16 +344,22 @@ impl PmEncoderServer {
                     ));
                 }
 
-                // Load or create context store
+                // Load, update and save the context store on the blocking pool
                 let root_path = PathBuf::from(&params.root);
                 let store_path = ContextStore::default_path(&root_path);
-                let mut store = ContextStore::load_from_file(&store_path);
-
-                // Report the utility
-                store.report_utility(&params.path, params.utility, DEFAULT_ALPHA);
-
-                // Save the store
-                store.save_to_file(&store_path).map_err(|e| {
+                let key = normalize_relative_path(&params.path);
+                let (path, utility) = (key.clone(), params.utility);
+                let update_path = store_path.clone();
+                let (store, saved) = pool()
+                    .run(move || {
+                        let mut store = ContextStore::load_from_file(&update_path);
+                        store.report_utility(&path, utility, DEFAULT_ALPHA);
+                        let saved = store.save_to_file(&update_path).map_err(|e| e.to_string());
+                        (store, saved)
+                    })
+                    .await
+                    .map_err(task_error)?;
+                saved.map_err(|e| {
                     rmcp::ErrorData::internal_error(
                         format!("Failed to save context store: {}", e),
                         None,
@@ -343,7 +368,7 @@ impl PmEncoderServer {
 
                 // Format response
                 let reason = params.reason.unwrap_or_else(|| "MCP feedback".to_string());
-                let current_score = store.get_utility_score(&params.path);
+                let current_score = store.get_utility_score(&key);
                 let response = format!(
                     "Utility reported:\n  File: {}\n  Score: {:.2} → {:.2}\n  Reason: {}\n  Store: {}",
                     params.path,
@@ -15,15 +15,24 @@
 #![cfg_attr(tarpaulin, ignore)]
 
 use clap::{Parser, ValueEnum};
-use pm_encoder::{self, EncoderConfig, LensManager, OutputFormat, parse_token_budget, apply_token_budget};
+use pm_encoder::{self, EncoderConfig, LensManager, OutputFormat, parse_token_budget, apply_token_budget_with, PrioritizerRegistry};
 use pm_encoder::core::{
-    ContextEngine, ZoomConfig, ZoomTarget, ContextStore, DEFAULT_ALPHA, SkeletonMode,
+    ContextEngine, FailureKind, ErrorReport, ZoomConfig, ZoomTarget, ContextStore, DEFAULT_ALPHA, SkeletonMode,
     SemanticDepth, DetailLevel, ObserversJournal,
-    IntelligentPresenter,
+    IntelligentPresenter, BenchConfig, BenchReport, BundleFile, ContextBundle,
+    BlobStore, GcPolicy, FocusHints, FocusPrioritizer, FocusProvider, GitView, Lod, LodPlan,
 };
 use pm_encoder::server::McpServer;
+use pm_encoder::server::limits::{Limiter, ServerLimits};
+use pm_encoder::server::prefetch::PrefetchConfig;
+use pm_encoder::server::workspaces::Workspaces;
+use pm_encoder::init::InstructionDrift;
 use std::path::PathBuf;
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by --json-errors: `fail` prints JSON instead of text
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
 
 /// 🌌 Voyager Observatory: Navigate the code galaxy with ease.
 ///
@@ -90,6 +99,10 @@ struct Cli {
     #[arg(long = "exclude", value_name = "PATTERN", num_args = 0.., help_heading = "🔍 LENS FILTERS")]
     exclude: Vec<String>,
 
+    /// Match include/exclude patterns ignoring case [default: on Windows only]
+    #[arg(long = "case-insensitive-paths", value_name = "BOOL", num_args = 0..=1, default_missing_value = "true", help_heading = "🔍 LENS FILTERS")]
+    case_insensitive_paths: Option<bool>,
+
     /// Analysis depth [quick, balanced, deep]
     #[arg(long = "semantic-depth", value_enum, default_value = "balanced", help_heading = "🔍 LENS FILTERS")]
     semantic_depth: SemanticDepthArg,
@@ -106,14 +119,70 @@ struct Cli {
     #[arg(short = 'c', long = "config", value_name = "FILE", help_heading = "🔍 LENS FILTERS")]
     config: Option<PathBuf>,
 
+    /// Serialize the project as of a git revision, read from the object database (no checkout)
+    #[arg(long = "at-commit", value_name = "REV", help_heading = "🔍 LENS FILTERS")]
+    at_commit: Option<String>,
+
+    /// Serialize the index: HEAD plus staged changes, exactly what would be committed
+    #[arg(long = "staged", conflicts_with_all = ["at_commit", "index_only", "working_tree"], help_heading = "🔍 LENS FILTERS")]
+    staged: bool,
+
+    /// Serialize only the files with staged changes, as staged
+    #[arg(long = "index-only", conflicts_with_all = ["at_commit", "working_tree"], help_heading = "🔍 LENS FILTERS")]
+    index_only: bool,
+
+    /// Serialize tracked files as on disk, including unstaged edits (untracked files excluded)
+    #[arg(long = "working-tree", conflicts_with = "at_commit", help_heading = "🔍 LENS FILTERS")]
+    working_tree: bool,
+
     // ═══════════════════════════════════════════════════════════════════════════
     // 🔬 MAGNIFICATION (Zoom Control)
     // ═══════════════════════════════════════════════════════════════════════════
 
-    /// Zoom into target: fn=name, class=name, file=path[:lines]
+    /// Zoom into target: fn=name, class=name, section=heading, anchor=path#Outer.inner[@id], file=path[:lines], bookmark=name
     #[arg(long = "zoom", value_name = "TARGET", help_heading = "🔬 MAGNIFICATION")]
     zoom: Option<String>,
 
+    /// With --zoom, also include the tests that exercise the target
+    #[arg(long = "zoom-tests", help_heading = "🔬 MAGNIFICATION")]
+    zoom_tests: bool,
+
+    /// With --zoom, also include doc sections and docstrings that mention the target
+    #[arg(long = "zoom-docs", help_heading = "🔬 MAGNIFICATION")]
+    zoom_docs: bool,
+
+    /// Serialize Markdown docs at heading depth N, collapsing deeper sections
+    #[arg(long = "outline-depth", value_name = "N", help_heading = "🔬 MAGNIFICATION")]
+    outline_depth: Option<usize>,
+
+    /// Replace each file's imports with one summary line (std / internal / external)
+    #[arg(long = "import-rollup", help_heading = "🔬 MAGNIFICATION")]
+    import_rollup: bool,
+
+    /// Collapse license headers, long import preambles and generated-code banners into markers
+    #[arg(long = "strip-boilerplate", help_heading = "🔬 MAGNIFICATION")]
+    strip_boilerplate: bool,
+
+    /// Open generated and vendored files with a note on where to edit instead (e.g. the .proto source)
+    #[arg(long = "provenance", help_heading = "🔬 MAGNIFICATION")]
+    provenance: bool,
+
+    /// Render at level of detail 0-3 (file list, declarations, signatures, full)
+    #[arg(long = "lod", value_name = "LEVEL", help_heading = "🔬 MAGNIFICATION")]
+    lod: Option<String>,
+
+    /// Level of detail for a subtree, e.g. src/core=3 (repeatable, implies --lod 3 elsewhere)
+    #[arg(long = "lod-path", value_name = "PREFIX=LEVEL", num_args = 1.., help_heading = "🔬 MAGNIFICATION")]
+    lod_path: Vec<String>,
+
+    /// Token cap per function at level 3, e.g. 2k: larger ones drop to signatures, then outline lines
+    #[arg(long = "lod-symbol-budget", value_name = "TOKENS", help_heading = "🔬 MAGNIFICATION")]
+    lod_symbol_budget: Option<String>,
+
+    /// Token cap per function body, e.g. 500: larger bodies are replaced by an extractive summary
+    #[arg(long = "symbol-budget", value_name = "TOKENS", help_heading = "🔬 MAGNIFICATION")]
+    symbol_budget: Option<String>,
+
     /// Show skeleton only (signatures without bodies)
     #[arg(long = "skeleton", value_name = "MODE", default_value = "auto", help_heading = "🔬 MAGNIFICATION")]
     skeleton: String,
@@ -122,7 +191,7 @@ struct Cli {
     #[arg(long = "truncate", value_name = "LINES", default_value = "0", help_heading = "🔬 MAGNIFICATION")]
     truncate: usize,
 
-    /// Truncation mode [simple, smart, structure]
+    /// Truncation mode [simple, smart, structure, window]
     #[arg(long = "truncate-mode", value_enum, default_value = "simple", help_heading = "🔬 MAGNIFICATION")]
     truncate_mode: TruncateMode,
 
@@ -130,6 +199,10 @@ struct Cli {
     #[arg(long = "truncate-exclude", value_name = "PATTERN", num_args = 0.., help_heading = "🔬 MAGNIFICATION")]
     truncate_exclude: Vec<String>,
 
+    /// With simple truncation, cut at the nearest line that leaves no string or block open
+    #[arg(long = "safe-cuts", help_heading = "🔬 MAGNIFICATION")]
+    safe_cuts: bool,
+
     // ═══════════════════════════════════════════════════════════════════════════
     // 🔋 POWER GRID (Token Budget)
     // ═══════════════════════════════════════════════════════════════════════════
@@ -138,10 +211,42 @@ struct Cli {
     #[arg(long = "token-budget", value_name = "BUDGET", help_heading = "🔋 POWER GRID")]
     token_budget: Option<String>,
 
-    /// Budget strategy [drop, truncate, hybrid]
+    /// With --token-budget, fail (exit code 4) when the project does not fit instead of dropping files
+    #[arg(long = "hard-budget", requires = "token_budget", help_heading = "🔋 POWER GRID")]
+    hard_budget: bool,
+
+    /// Sample a representative slice (entry points, central files, one file per directory) within N tokens
+    #[arg(long = "sample", value_name = "TOKENS", help_heading = "🔋 POWER GRID")]
+    sample: Option<String>,
+
+    /// Seed for --sample; the same seed reproduces the same slice
+    #[arg(long = "seed", value_name = "N", default_value = "0", help_heading = "🔋 POWER GRID")]
+    seed: u64,
+
+    /// Budget strategy [drop, truncate, hybrid, summarize]
     #[arg(long = "budget-strategy", value_enum, default_value = "drop", help_heading = "🔋 POWER GRID")]
     budget_strategy: BudgetStrategy,
 
+    /// Focus on a GitHub issue (owner/repo#123 or URL) to pick the lens and budget priorities
+    #[arg(long = "issue", value_name = "REF", help_heading = "🔋 POWER GRID")]
+    issue: Option<String>,
+
+    /// Focus on an issue or task description read from a file
+    #[arg(long = "issue-file", value_name = "FILE", help_heading = "🔋 POWER GRID")]
+    issue_file: Option<PathBuf>,
+
+    /// JUnit XML reports from CI runs; prioritizes code exercised by flaky tests
+    #[arg(long = "junit", value_name = "FILE", num_args = 1.., help_heading = "🔋 POWER GRID")]
+    junit: Vec<PathBuf>,
+
+    /// Offline OSV advisory database (JSON file or directory); flags vulnerable dependencies
+    #[arg(long = "osv-db", value_name = "PATH", help_heading = "🔋 POWER GRID")]
+    osv_db: Option<PathBuf>,
+
+    /// Query an OSV API for advisories (network access; e.g. https://api.osv.dev)
+    #[arg(long = "osv-api", value_name = "URL", help_heading = "🔋 POWER GRID")]
+    osv_api: Option<String>,
+
     // ═══════════════════════════════════════════════════════════════════════════
     // 💡 OBSERVATION LOGS (Intelligence)
     // ═══════════════════════════════════════════════════════════════════════════
@@ -162,6 +267,18 @@ struct Cli {
     #[arg(long = "health", help_heading = "💡 EXPLORATION")]
     health: bool,
 
+    /// Re-check the output and report truncated snippets left syntactically broken
+    #[arg(long = "self-check", help_heading = "💡 EXPLORATION")]
+    self_check: bool,
+
+    /// Append a JSON index of all zoom affordances (ids, targets, token costs)
+    #[arg(long = "affordance-index", help_heading = "🔬 MAGNIFICATION")]
+    affordance_index: bool,
+
+    /// Open the context with a table of contents of files and declarations, with anchors
+    #[arg(long = "toc", help_heading = "🔬 MAGNIFICATION")]
+    toc: bool,
+
     /// Include test files in exploration
     #[arg(long = "explore-tests", help_heading = "💡 EXPLORATION")]
     explore_tests: bool,
@@ -174,10 +291,25 @@ struct Cli {
     // ⚙️ ADVANCED OPTIONS
     // ═══════════════════════════════════════════════════════════════════════════
 
-    /// Manage zoom sessions [create:name, load:name, list, delete:name, show]
+    /// Manage zoom sessions [create:name, load:name, list, delete:name, show, replay[:name]].
+    /// replay re-serializes every step of a session (the active one by default); add --at-commit REV to replay at a ref
     #[arg(long = "zoom-session", value_name = "ACTION:NAME", help_heading = "⚙️ ADVANCED")]
     zoom_session: Option<String>,
 
+    /// Manage bookmarks, named zoom targets saved in .pm_encoder/bookmarks.json
+    /// [add:NAME=TARGET, list, resolve:NAME, remove:NAME]; zoom into one with --zoom bookmark=NAME
+    #[arg(long = "bookmark", value_name = "ACTION:NAME", help_heading = "⚙️ ADVANCED")]
+    bookmark: Option<String>,
+
+    /// Note saved with --bookmark add
+    #[arg(long = "bookmark-note", value_name = "TEXT", requires = "bookmark", help_heading = "⚙️ ADVANCED")]
+    bookmark_note: Option<String>,
+
+    /// Record an investigation in .pm_encoder/investigations.json [start:NAME, zoom:TARGET,
+    /// search:SYMBOL, note:TEXT, report[:NAME], list, delete:NAME]; report renders it as Markdown
+    #[arg(long = "investigate", value_name = "ACTION:ARG", help_heading = "⚙️ ADVANCED")]
+    investigate: Option<String>,
+
     /// Undo last zoom action
     #[arg(long = "zoom-undo", help_heading = "⚙️ ADVANCED")]
     zoom_undo: bool,
@@ -206,10 +338,14 @@ struct Cli {
     #[arg(long = "sort-order", value_enum, default_value = "asc", help_heading = "⚙️ ADVANCED")]
     sort_order: SortOrder,
 
-    /// Enable streaming mode (lower latency)
+    /// Enable streaming mode (lower latency; with -o, sorted output in bounded memory)
     #[arg(long = "stream", help_heading = "⚙️ ADVANCED")]
     stream: bool,
 
+    /// Print fatal errors to stderr as one JSON object: {"error":{"kind","exit_code","message"}}
+    #[arg(long = "json-errors", help_heading = "⚙️ ADVANCED")]
+    json_errors: bool,
+
     /// Follow symbolic links (default: skip broken symlinks silently)
     #[arg(long = "follow-symlinks", help_heading = "⚙️ ADVANCED")]
     follow_symlinks: bool,
@@ -266,6 +402,26 @@ struct Cli {
     #[arg(long = "no-cache", help_heading = "📊 CENSUS")]
     no_cache: bool,
 
+    /// Per-language statistics: files, lines, symbols, comment density [table, json]
+    #[arg(long = "stats", value_name = "FORMAT", num_args = 0..=1, default_missing_value = "table", help_heading = "📊 CENSUS")]
+    stats: Option<StatsFormat>,
+
+    /// Largest files listed by --stats
+    #[arg(long = "stats-top", value_name = "N", default_value_t = 10, help_heading = "📊 CENSUS")]
+    stats_top: usize,
+
+    /// Per-directory heatmap of symbols, lines, tokens and complexity [table, json]
+    #[arg(long = "heatmap", value_name = "FORMAT", num_args = 0..=1, default_missing_value = "table", help_heading = "📊 CENSUS")]
+    heatmap: Option<StatsFormat>,
+
+    /// Directory depth for --heatmap grouping
+    #[arg(long = "heatmap-depth", value_name = "N", default_value_t = 2, help_heading = "📊 CENSUS")]
+    heatmap_depth: usize,
+
+    /// Flaky tests from --junit reports and the symbols they exercise [table, json]
+    #[arg(long = "flaky-report", value_name = "FORMAT", num_args = 0..=1, default_missing_value = "table", requires = "junit", help_heading = "📊 CENSUS")]
+    flaky_report: Option<StatsFormat>,
+
     // ═══════════════════════════════════════════════════════════════════════════
     // 🚀 SPECIAL MODES
     // ═══════════════════════════════════════════════════════════════════════════
@@ -274,10 +430,126 @@ struct Cli {
     #[arg(long = "server", help_heading = "🚀 SPECIAL MODES")]
     server: bool,
 
+    /// Run as a daemon with a warm index (JSON-RPC 2.0 over a Unix socket)
+    #[arg(long = "daemon", help_heading = "🚀 SPECIAL MODES")]
+    daemon: bool,
+
+    /// Socket path for --daemon [default: <project>/.pm_encoder/daemon.sock]
+    #[arg(long = "socket", value_name = "PATH", requires = "daemon", help_heading = "🚀 SPECIAL MODES")]
+    socket: Option<PathBuf>,
+
+    /// Expose Prometheus /metrics and /healthz for --server/--daemon (e.g. 127.0.0.1:9464)
+    #[arg(long = "metrics-addr", value_name = "ADDR", help_heading = "🚀 SPECIAL MODES")]
+    metrics_addr: Option<String>,
+
+    /// Maximum concurrent index/context jobs for --server/--daemon [default: CPU count]
+    #[arg(long = "max-jobs", value_name = "N", help_heading = "🚀 SPECIAL MODES")]
+    max_jobs: Option<usize>,
+
+    /// Jobs allowed to wait for a slot before clients get a busy error
+    #[arg(long = "max-queue", value_name = "N", default_value_t = 16, help_heading = "🚀 SPECIAL MODES")]
+    max_queue: usize,
+
+    /// Seconds a queued job waits for a slot before giving up
+    #[arg(long = "queue-timeout", value_name = "SECS", default_value_t = 30, help_heading = "🚀 SPECIAL MODES")]
+    queue_timeout: u64,
+
+    /// Requests per minute per client for --server/--daemon (0 = unlimited)
+    #[arg(long = "rate-limit", value_name = "N", default_value_t = 0, help_heading = "🚀 SPECIAL MODES")]
+    rate_limit: u32,
+
+    /// Serve a named workspace with --server (NAME=PATH, repeatable); tools must then name one
+    #[arg(long = "workspace", value_name = "NAME=PATH", requires = "server", help_heading = "🚀 SPECIAL MODES")]
+    workspace: Vec<String>,
+
+    /// Preview which files the budget would include (full/truncated/summarized/dropped) without generating content
+    #[arg(long = "preview", help_heading = "🚀 SPECIAL MODES")]
+    preview: bool,
+
+    /// Also package the context into a .pmctx bundle (context, manifest, instructions)
+    #[arg(long = "bundle", value_name = "FILE", conflicts_with = "stream", help_heading = "🚀 SPECIAL MODES")]
+    bundle: Option<PathBuf>,
+
+    /// Include the Planetarium index in the --bundle archive
+    #[arg(long = "bundle-index", requires = "bundle", help_heading = "🚀 SPECIAL MODES")]
+    bundle_index: bool,
+
+    /// Re-open a .pmctx bundle: print its context, or serve it with --server
+    #[arg(long = "import-bundle", value_name = "FILE", help_heading = "🚀 SPECIAL MODES")]
+    import_bundle: Option<PathBuf>,
+
+    /// Run the built-in benchmark suite and print a JSON report
+    #[arg(long = "bench", help_heading = "🚀 SPECIAL MODES")]
+    bench: bool,
+
+    /// Garbage-collect the shared blob cache (parses, contexts) and exit
+    #[arg(long = "blob-gc", help_heading = "🚀 SPECIAL MODES")]
+    blob_gc: bool,
+
+    /// With --blob-gc: remove blobs unused for this many days
+    #[arg(long = "blob-max-age", value_name = "DAYS", default_value_t = 30, requires = "blob_gc", help_heading = "🚀 SPECIAL MODES")]
+    blob_max_age: u64,
+
+    /// With --blob-gc: then shrink the cache to this many megabytes
+    #[arg(long = "blob-max-mb", value_name = "MB", requires = "blob_gc", help_heading = "🚀 SPECIAL MODES")]
+    blob_max_mb: Option<u64>,
+
+    /// Do not read or write the shared blob cache
+    #[arg(long = "no-blob-cache", help_heading = "🚀 SPECIAL MODES")]
+    no_blob_cache: bool,
+
+    /// Capture all files before indexing so the index matches one point in time; reports files edited mid-scan
+    #[arg(long = "snapshot", help_heading = "🚀 SPECIAL MODES")]
+    snapshot: bool,
+
+    /// Serialize the project (or the synthetic corpus) in every format and budget: tokens and symbols retained per depth [table, json]
+    #[arg(long = "compare-formats", value_name = "FORMAT", num_args = 0..=1, default_missing_value = "table", help_heading = "🚀 SPECIAL MODES")]
+    compare_formats: Option<StatsFormat>,
+
+    /// Budgets compared by --compare-formats, besides unlimited (default: 32000,8000,2000)
+    #[arg(long = "compare-budgets", value_name = "TOKENS", value_delimiter = ',', requires = "compare_formats", help_heading = "🚀 SPECIAL MODES")]
+    compare_budgets: Vec<usize>,
+
+    /// Compare --bench results against a previous JSON report (exit 1 on regression)
+    #[arg(long = "bench-baseline", value_name = "FILE", requires = "bench", help_heading = "🚀 SPECIAL MODES")]
+    bench_baseline: Option<PathBuf>,
+
+    /// Allowed slowdown vs. the baseline, in percent
+    #[arg(long = "bench-threshold", value_name = "PCT", default_value_t = 20.0, help_heading = "🚀 SPECIAL MODES")]
+    bench_threshold: f64,
+
+    /// Print a JSON Schema and exit [ir: File/Declaration/PlanetariumModel/MicroscopeModel]
+    #[arg(long = "schema", value_enum, value_name = "KIND", help_heading = "🚀 SPECIAL MODES")]
+    schema: Option<SchemaKind>,
+
+    /// List the languages with AST analysis and what each extracts, then exit
+    #[arg(long = "languages", help_heading = "🚀 SPECIAL MODES")]
+    languages: bool,
+
+    /// Print what this build supports (languages, features, lenses, limits) as JSON, then exit
+    #[arg(long = "capabilities", help_heading = "🚀 SPECIAL MODES")]
+    capabilities: bool,
+
+    /// With --languages: also list what each language's analysis does not extract
+    #[arg(long = "detailed", requires = "languages", help_heading = "🚀 SPECIAL MODES")]
+    detailed: bool,
+
     /// Generate AI instruction files and exit
     #[arg(long = "init-prompt", help_heading = "🚀 SPECIAL MODES")]
     init_prompt: bool,
 
+    /// Exit 1 if the generated section of the instruction file is stale (CI / pre-commit)
+    #[arg(long = "check-instructions", help_heading = "🚀 SPECIAL MODES")]
+    check_instructions: bool,
+
+    /// With --check-instructions: rewrite the stale section instead of failing
+    #[arg(long = "write", requires = "check_instructions", help_heading = "🚀 SPECIAL MODES")]
+    write: bool,
+
+    /// With --init-prompt/--check-instructions: write zstd-compressed CONTEXT.txt.zst
+    #[arg(long = "compress", help_heading = "🚀 SPECIAL MODES")]
+    compress: bool,
+
     /// Lens for init-prompt
     #[arg(long = "init-lens", value_name = "LENS", default_value = "architecture", help_heading = "🚀 SPECIAL MODES")]
     init_lens: String,
@@ -285,12 +557,61 @@ struct Cli {
     /// Target AI [claude, gemini]
     #[arg(long = "target", value_enum, default_value = "claude", help_heading = "🚀 SPECIAL MODES")]
     target: TargetAI,
+
+    /// PR review pack for BASE..HEAD: checklist, changed declarations, callers, diff
+    #[arg(long = "review", value_name = "BASE..HEAD", help_heading = "🚀 SPECIAL MODES")]
+    review: Option<String>,
+
+    /// Summarize staged changes per declaration, as input for a commit message
+    #[arg(long = "commit-pack", conflicts_with = "review", help_heading = "🚀 SPECIAL MODES")]
+    commit_pack: bool,
+
+    /// Add Conventional Commits hints (feat/fix/refactor...) to --commit-pack
+    #[arg(long = "conventional", requires = "commit_pack", help_heading = "🚀 SPECIAL MODES")]
+    conventional: bool,
+
+    /// Generate an API reference: public declarations by module, no bodies [markdown, json]
+    #[arg(long = "api-reference", value_name = "FORMAT", num_args = 0..=1, default_missing_value = "markdown", help_heading = "🚀 SPECIAL MODES")]
+    api_reference: Option<ApiReferenceFormat>,
+
+    /// Generate evaluation questions (definitions, callers, return types) with ground-truth locations [json, markdown]
+    #[arg(long = "question-pack", value_name = "FORMAT", num_args = 0..=1, default_missing_value = "json", help_heading = "🚀 SPECIAL MODES")]
+    question_pack: Option<QuestionPackFormat>,
+
+    /// Questions of each kind in --question-pack
+    #[arg(long = "question-limit", value_name = "N", default_value_t = pm_encoder::core::DEFAULT_QUESTIONS_PER_KIND, help_heading = "🚀 SPECIAL MODES")]
+    question_limit: usize,
+
+    /// Export one symbol with the types of its signature and the imports they use as a standalone snippet
+    /// (function=NAME, class=NAME or anchor=PATH#NAME)
+    #[arg(long = "snippet", value_name = "TARGET", help_heading = "🚀 SPECIAL MODES")]
+    snippet: Option<String>,
+
+    /// Generate empty test files, one test per public function, in each language's harness.
+    /// Optionally narrowed to comma-separated paths, names or anchors; with -o, files are written under that directory
+    #[arg(long = "test-scaffold", value_name = "SELECTION", num_args = 0..=1, default_missing_value = "", help_heading = "🚀 SPECIAL MODES")]
+    test_scaffold: Option<String>,
+
+    /// Export TODO/FIXME comments with file, line, enclosing symbol and blame age [md, csv, json]
+    #[arg(long = "todos", value_name = "FORMAT", num_args = 0..=1, default_missing_value = "md", help_heading = "🚀 SPECIAL MODES")]
+    todos: Option<TodoFormat>,
+
+    /// List regions the parsers could not interpret, by language and reason, with zoom commands [text, json]
+    #[arg(long = "unknowns", value_name = "FORMAT", num_args = 0..=1, default_missing_value = "text", help_heading = "🚀 SPECIAL MODES")]
+    unknowns: Option<UnknownsFormat>,
 }
 
 // =============================================================================
 // New Enums for Telescope UX
 // =============================================================================
 
+/// Schemas available via --schema.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SchemaKind {
+    /// voyager-ast IR (index and zoom output)
+    Ir,
+}
+
 /// Semantic analysis depth.
 #[derive(Debug, Clone, Copy, ValueEnum, Default)]
 enum SemanticDepthArg {
@@ -365,6 +686,7 @@ enum TruncateMode {
     Simple,
     Smart,
     Structure,
+    Window,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -378,6 +700,8 @@ enum BudgetStrategy {
     Drop,
     Truncate,
     Hybrid,
+    /// Keep files that don't fit as signatures and doc comments (LOD2) or outlines (LOD1)
+    Summarize,
 }
 
 /// Survey mode for --survey flag
@@ -391,6 +715,43 @@ enum SurveyMode {
     Evolution,
 }
 
+/// Output format for --stats and --heatmap
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum StatsFormat {
+    Table,
+    Json,
+}
+
+/// Output format for --api-reference
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ApiReferenceFormat {
+    Markdown,
+    Json,
+}
+
+/// Output format for --question-pack
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum QuestionPackFormat {
+    Json,
+    Markdown,
+}
+
+/// Output format for --todos
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum TodoFormat {
+    #[value(alias = "markdown")]
+    Md,
+    Csv,
+    Json,
+}
+
+/// Output format for --unknowns
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum UnknownsFormat {
+    Text,
+    Json,
+}
+
 /// Grouping level for survey output
 #[derive(Debug, Clone, Copy, ValueEnum, Default)]
 enum SurveyGrouping {
@@ -479,6 +840,7 @@ fn parse_report_utility(s: &str) -> Result<(String, f64, String), String> {
 ///   fn=<name>           - Zoom to function
 ///   class=<name>        - Zoom to class/struct
 ///   mod=<name>          - Zoom to module
+///   section=<heading>   - Zoom to a Markdown section (optionally README.md#<heading>)
 ///   file=<path>         - Zoom to entire file
 ///   file=<path>:L1-L2   - Zoom to file lines L1 to L2
 fn parse_zoom_target(s: &str) -> Result<ZoomConfig, String> {
@@ -497,6 +859,8 @@ fn parse_zoom_target(s: &str) -> Result<ZoomConfig, String> {
         "fn" | "function" => ZoomTarget::Function(target_str.to_string()),
         "class" | "struct" => ZoomTarget::Class(target_str.to_string()),
         "mod" | "module" => ZoomTarget::Module(target_str.to_string()),
+        "section" | "heading" => ZoomTarget::Section(target_str.trim_matches('"').to_string()),
+        "anchor" | "id" => ZoomTarget::Anchor(pm_encoder::core::Anchor::parse(target_str).map_err(|e| e.to_string())?),
         "file" => {
             // Check for line range: file=path:L1-L2
             if let Some(colon_pos) = target_str.rfind(':') {
@@ -536,7 +900,7 @@ fn parse_zoom_target(s: &str) -> Result<ZoomConfig, String> {
         }
         _ => {
             return Err(format!(
-                "Unknown zoom type: '{}'. Valid types: fn, class, mod, file",
+                "Unknown zoom type: '{}'. Valid types: fn, class, mod, file, section, anchor",
                 zoom_type
             ));
         }
@@ -547,6 +911,7 @@ fn parse_zoom_target(s: &str) -> Result<ZoomConfig, String> {
         budget: None,
         depth: pm_encoder::core::ZoomDepth::Full,
         include_tests: false,
+        include_docs: false,
         context_lines: 5,
     })
 }
@@ -575,8 +940,7 @@ fn run_survey(root: &PathBuf, mode: SurveyMode, grouping: SurveyGrouping, cli: &
     ) {
         Ok(e) => e,
         Err(e) => {
-            eprintln!("Error walking directory: {}", e);
-            std::process::exit(1);
+            fail(FailureKind::Error, format!("Error walking directory: {}", e));
         }
     };
 
@@ -636,7 +1000,7 @@ fn run_survey(root: &PathBuf, mode: SurveyMode, grouping: SurveyGrouping, cli: &
                         if let Some(abs_root) = abs_root {
                             abs_root.strip_prefix(&git_root)
                                 .ok()
-                                .map(|p| p.to_string_lossy().to_string())
+                                .map(pm_encoder::core::relative_key)
                                 .filter(|s| !s.is_empty())
                         } else {
                             None
@@ -690,8 +1054,7 @@ fn run_survey(root: &PathBuf, mode: SurveyMode, grouping: SurveyGrouping, cli: &
             match serde_json::to_string_pretty(&galaxy) {
                 Ok(json) => println!("{}", json),
                 Err(e) => {
-                    eprintln!("Error serializing JSON: {}", e);
-                    std::process::exit(1);
+                    fail(FailureKind::Error, format!("Error serializing JSON: {}", e));
                 }
             }
         }
@@ -2015,6 +2378,262 @@ fn print_context_health(output: &str, file_count: usize) {
     eprintln!("======================");
 }
 
+/// Core engine for the CLI config; git views (`--at-commit`, `--staged`, ...) walk the repository
+fn context_engine(config: &EncoderConfig) -> ContextEngine {
+    let engine = ContextEngine::with_config(core_config(config));
+    match &config.git_view {
+        #[cfg(feature = "temporal")]
+        Some(view) => engine.with_walker(pm_encoder::core::GitWalker::with_view(view.clone())),
+        #[cfg(not(feature = "temporal"))]
+        Some(view) => {
+            fail(FailureKind::Error, format!("Error: reading the {} requires a build with the `temporal` feature", view));
+        }
+        None => engine,
+    }
+}
+
+/// Git view selected on the command line, if any
+fn git_view(cli: &Cli) -> Option<GitView> {
+    if let Some(revision) = &cli.at_commit {
+        Some(GitView::Commit(revision.clone()))
+    } else if cli.index_only {
+        Some(GitView::StagedChanges)
+    } else if cli.staged {
+        Some(GitView::Staged)
+    } else if cli.working_tree {
+        Some(GitView::WorkingTree)
+    } else {
+        None
+    }
+}
+
+/// Convert the CLI configuration into the core engine configuration
+fn core_config(config: &EncoderConfig) -> pm_encoder::core::EncoderConfig {
+    pm_encoder::core::EncoderConfig {
+        ignore_patterns: config.ignore_patterns.clone(),
+        include_patterns: config.include_patterns.clone(),
+        max_file_size: config.max_file_size,
+        truncate_lines: config.truncate_lines,
+        truncate_mode: config.truncate_mode.clone(),
+        sort_by: config.sort_by.clone(),
+        sort_order: config.sort_order.clone(),
+        stream: config.stream,
+        truncate_summary: config.truncate_summary,
+        truncate_exclude: config.truncate_exclude.clone(),
+        truncate_stats: config.truncate_stats,
+        output_format: match config.output_format {
+            OutputFormat::PlusMinus => pm_encoder::core::OutputFormat::PlusMinus,
+            OutputFormat::Xml => pm_encoder::core::OutputFormat::Xml,
+            OutputFormat::Markdown => pm_encoder::core::OutputFormat::Markdown,
+            OutputFormat::ClaudeXml => pm_encoder::core::OutputFormat::ClaudeXml,
+        },
+        frozen: config.frozen,
+        allow_sensitive: config.allow_sensitive,
+        active_lens: config.active_lens.clone(),
+        token_budget: config.token_budget,
+        skeleton_mode: config.skeleton_mode,
+        metadata_mode: config.metadata_mode,
+        follow_symlinks: config.follow_symlinks,
+        symbol_budget: config.symbol_budget,
+        language_rules: config.language_rules.clone(),
+        symbol_rules: Vec::new(),
+        table_of_contents: config.table_of_contents,
+        provenance_annotations: config.provenance_annotations,
+        case_insensitive_paths: config.case_insensitive_paths,
+    }
+}
+
+/// Start the opt-in metrics endpoint for a server (exits on failure)
+fn start_metrics(addr: &str, metrics: &std::sync::Arc<pm_encoder::server::metrics::Metrics>) {
+    if let Err(e) = pm_encoder::server::metrics::serve(addr, std::sync::Arc::clone(metrics)) {
+        fail(FailureKind::Error, format!("Error: Cannot serve metrics on {}: {}", addr, e));
+    }
+}
+
+/// The shared blob cache, unless disabled or unavailable
+fn blob_store(disabled: bool) -> Option<BlobStore> {
+    if disabled {
+        return None;
+    }
+    BlobStore::open_default()
+        .map_err(|e| eprintln!("Warning: blob cache disabled: {}", e))
+        .ok()
+}
+
+/// The `prefetch` section of the project's config file, if any
+///
+/// Stays silent on a broken config: server stdio must stay clean, and the
+/// tools that read the config report it.
+fn prefetch_config(project_root: &std::path::Path) -> Option<PrefetchConfig> {
+    pm_encoder::load_config(&project_root.to_string_lossy()).ok().and_then(|config| config.prefetch)
+}
+
+/// Workspaces of the config file, overridden by --workspace (exits on failure)
+fn workspaces(project_root: &std::path::Path, args: &[String]) -> Workspaces {
+    let mut configs = pm_encoder::load_config(&project_root.to_string_lossy())
+        .map(|config| config.workspaces)
+        .unwrap_or_default();
+    let parsed = args.iter().map(|arg| Workspaces::parse_arg(arg)).collect::<Result<Vec<_>, _>>();
+    let workspaces = parsed.and_then(|parsed| {
+        configs.extend(parsed);
+        Workspaces::new(configs)
+    });
+    workspaces.unwrap_or_else(|e| {
+        fail(FailureKind::Error, format!("Error: {}", e));
+    })
+}
+
+/// CI test history from --junit reports (exits on failure)
+fn load_ci_history(paths: &[PathBuf]) -> pm_encoder::core::CiHistory {
+    pm_encoder::core::CiHistory::load(paths).unwrap_or_else(|e| {
+        fail(FailureKind::ParseFailed, format!("Error: {}", e));
+    })
+}
+
+/// Match dependencies against --osv-db / --osv-api advisories (exits on failure)
+fn load_advisories(
+    db_path: Option<&std::path::Path>,
+    api: Option<&str>,
+    files: &[(String, String)],
+) -> pm_encoder::core::AdvisoryReport {
+    use pm_encoder::core::{AdvisoryDb, AdvisoryReport, DependencyModel};
+
+    let model = DependencyModel::from_files(files);
+    let mut db = match db_path {
+        Some(path) => AdvisoryDb::load(path).unwrap_or_else(|e| {
+            fail(FailureKind::ParseFailed, format!("Error: {}", e));
+        }),
+        None => AdvisoryDb::default(),
+    };
+    let online = match api {
+        #[cfg(feature = "osv")]
+        Some(api) => AdvisoryDb::query_api(api, &model.dependencies).unwrap_or_else(|e| {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        }),
+        #[cfg(not(feature = "osv"))]
+        Some(api) => {
+            fail(FailureKind::Error, format!("Error: --osv-api {} requires a build with the `osv` feature", api));
+        }
+        None => AdvisoryDb::default(),
+    };
+    db.advisories.extend(online.advisories);
+    AdvisoryReport::check(&model, &db, files)
+}
+
+/// Focus hints from --issue / --issue-file (exits on failure)
+fn load_focus_hints(issue: Option<&str>, issue_file: Option<&std::path::Path>) -> Option<FocusHints> {
+    let mut providers: Vec<Box<dyn FocusProvider>> = Vec::new();
+    if let Some(path) = issue_file {
+        let text = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            fail(FailureKind::Error, format!("Error: Failed to read {}: {}", path.display(), e));
+        });
+        let mut hints = FocusHints::from_text(&text);
+        hints.source = Some(path.display().to_string());
+        providers.push(Box::new(hints));
+    }
+    if let Some(reference) = issue {
+        #[cfg(feature = "github")]
+        match pm_encoder::core::focus::github::GitHubIssue::parse(reference) {
+            Ok(issue) => providers.push(Box::new(issue)),
+            Err(e) => {
+                fail(FailureKind::Error, format!("Error: {}", e));
+            }
+        }
+        #[cfg(not(feature = "github"))]
+        {
+            fail(FailureKind::Error, format!("Error: --issue {} requires a build with the `github` feature", reference));
+        }
+    }
+    if providers.is_empty() {
+        return None;
+    }
+
+    let (hints, errors) = pm_encoder::core::focus::collect_hints(&providers);
+    if !errors.is_empty() {
+        let messages: Vec<String> = errors.iter().map(|e| format!("Error: {}", e)).collect();
+        fail(FailureKind::Error, messages.join("\n"));
+    }
+    Some(hints)
+}
+
+/// Index options parsing through the blob cache, from a --snapshot if requested
+fn cached_index_options(no_blob_cache: bool, snapshot: bool) -> voyager_ast::IndexOptions {
+    voyager_ast::IndexOptions {
+        parse_cache: blob_store(no_blob_cache)
+            .map(|store| std::sync::Arc::new(store) as std::sync::Arc<dyn voyager_ast::ParseCache>),
+        snapshot,
+        ..Default::default()
+    }
+}
+
+/// Index options for --bundle-index
+fn bundle_index_options(include_index: bool, no_blob_cache: bool, snapshot: bool) -> Option<voyager_ast::IndexOptions> {
+    include_index.then(|| cached_index_options(no_blob_cache, snapshot))
+}
+
+/// Warn about files edited while a --snapshot index was built
+fn warn_changed_during_scan(model: &voyager_ast::PlanetariumModel) {
+    let changed = &model.stats.changed_during_scan;
+    if !changed.is_empty() {
+        eprintln!(
+            "Warning: {} file(s) changed during the scan, indexed as they were at scan start: {}",
+            changed.len(),
+            changed.join(", ")
+        );
+    }
+}
+
+/// Write a .pmctx bundle for the generated context (exits on failure)
+#[allow(clippy::too_many_arguments)]
+fn write_bundle(
+    bundle_path: &std::path::Path,
+    lens: Option<&str>,
+    index_options: Option<voyager_ast::IndexOptions>,
+    project_root: &std::path::Path,
+    config: &EncoderConfig,
+    output: &str,
+    files: Vec<BundleFile>,
+    sample_seed: Option<u64>,
+) {
+    let project_name = project_root.canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().and_then(|n| n.to_str()).map(str::to_string))
+        .unwrap_or_else(|| "project".to_string());
+    let format = format!("{:?}", config.output_format).to_lowercase();
+
+    let mut bundle = ContextBundle::new(project_name, format, output.to_string())
+        .with_lens(lens)
+        .with_token_budget(config.token_budget)
+        .with_sample_seed(sample_seed)
+        .with_files(files);
+
+    for filename in ["CLAUDE.md", "GEMINI_INSTRUCTIONS.txt"] {
+        if let Ok(content) = std::fs::read_to_string(project_root.join(filename)) {
+            bundle = bundle.with_instructions(filename, content);
+            break;
+        }
+    }
+
+    if let Some(index_options) = index_options {
+        use voyager_ast::AstProvider;
+        let provider = voyager_ast::TreeSitterProvider::new();
+        let model = provider.index_project(project_root, &index_options);
+        if let Ok(model) = &model {
+            warn_changed_during_scan(model);
+        }
+        match model.map(|model| serde_json::to_value(&model)) {
+            Ok(Ok(value)) => bundle = bundle.with_planetarium(value),
+            Ok(Err(e)) => eprintln!("Warning: Planetarium index not bundled: {}", e),
+            Err(e) => eprintln!("Warning: Planetarium index not bundled: {}", e),
+        }
+    }
+
+    if let Err(e) = bundle.write_to(bundle_path) {
+        fail(FailureKind::Error, format!("Error: {}", e));
+    }
+    eprintln!("Bundle written to: {}", bundle_path.display());
+}
+
 /// Find project root by looking for common markers (git, Cargo.toml, package.json, etc.)
 /// Used by Microscope Auto-Focus to find the correct project root when given a file path.
 fn find_project_root(start: &PathBuf) -> Option<PathBuf> {
@@ -2145,7 +2764,32 @@ pub fn run() {
         }
     }
 
-    let cli = Cli::parse();
+    let mut cli = Cli::try_parse().unwrap_or_else(|e| {
+        // Usage errors get the JSON treatment too; --help and --version print as usual
+        if e.use_stderr() && std::env::args().any(|a| a == "--json-errors") {
+            JSON_ERRORS.store(true, Ordering::Relaxed);
+            let rendered = e.render().to_string();
+            fail(FailureKind::Usage, rendered.lines().next().unwrap_or_default().trim_start_matches("error: "));
+        }
+        e.exit()
+    });
+    JSON_ERRORS.store(cli.json_errors, Ordering::Relaxed);
+
+    if cli.metrics_addr.is_some() && !cli.server && !cli.daemon {
+        fail(FailureKind::Error, "Error: --metrics-addr requires --server or --daemon");
+    }
+    let server_limits = ServerLimits {
+        max_jobs: cli.max_jobs.unwrap_or_else(|| ServerLimits::default().max_jobs),
+        max_queue: cli.max_queue,
+        queue_timeout: std::time::Duration::from_secs(cli.queue_timeout),
+        requests_per_minute: cli.rate_limit,
+    };
+
+    // Handle --capabilities (no project root needed); limits as --server would apply them
+    if cli.capabilities {
+        println!("{}", pm_encoder::server::capabilities::Capabilities::detect(Some(&server_limits)).to_json());
+        return;
+    }
 
     // Handle MCP Server Mode (v2.3.0)
     // When --server is set, run as JSON-RPC server over stdio
@@ -2155,16 +2799,220 @@ pub fn run() {
             None => std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
         };
 
-        if !project_root.exists() || !project_root.is_dir() {
-            eprintln!("Error: Project root '{}' must be a valid directory", project_root.display());
-            std::process::exit(1);
+        // A bundle is self-contained: the original checkout may be absent
+        if cli.import_bundle.is_none() && (!project_root.exists() || !project_root.is_dir()) {
+            fail(FailureKind::Error, format!("Error: Project root '{}' must be a valid directory", project_root.display()));
         }
 
         // Note: No startup logs here - MCP clients expect clean stdio
-        let mut server = McpServer::new(project_root);
+        let limiter = std::sync::Arc::new(Limiter::new(server_limits));
+        let prefetch = prefetch_config(&project_root);
+        let workspaces = workspaces(&project_root, &cli.workspace);
+        let mut server = McpServer::new(project_root).with_limiter(limiter).with_workspaces(workspaces);
+        if let Some(store) = blob_store(cli.no_blob_cache) {
+            server = server.with_blob_store(store);
+        }
+        if let Some(prefetch) = prefetch {
+            server = server.with_prefetch(prefetch);
+        }
+        if let Some(bundle_path) = &cli.import_bundle {
+            match ContextBundle::read_from(bundle_path) {
+                Ok(bundle) => server = server.with_bundle(bundle),
+                Err(e) => {
+                    fail(FailureKind::Error, format!("Error: {}", e));
+                }
+            }
+        }
+        if let Some(addr) = &cli.metrics_addr {
+            start_metrics(addr, server.metrics());
+        }
         if let Err(e) = server.run() {
-            eprintln!("MCP server error: {}", e);
-            std::process::exit(1);
+            fail(FailureKind::Error, format!("MCP server error: {}", e));
+        }
+        return;
+    }
+
+    // Handle --daemon: keep the index warm and serve over a Unix socket
+    if cli.daemon {
+        let project_root = match &cli.project_root {
+            Some(path) => path.clone(),
+            None => std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        };
+
+        if !project_root.exists() || !project_root.is_dir() {
+            fail(FailureKind::Error, format!("Error: Project root '{}' must be a valid directory", project_root.display()));
+        }
+
+        #[cfg(unix)]
+        {
+            use pm_encoder::server::daemon::{default_socket_path, Daemon};
+
+            let socket = cli.socket.clone().unwrap_or_else(|| default_socket_path(&project_root));
+            let prefetch = prefetch_config(&project_root);
+            let daemon = match Daemon::open(project_root, blob_store(cli.no_blob_cache)) {
+                Ok(daemon) => {
+                    let daemon = daemon.with_limits(server_limits);
+                    std::sync::Arc::new(match prefetch {
+                        Some(prefetch) => daemon.with_prefetch(prefetch),
+                        None => daemon,
+                    })
+                }
+                Err(e) => {
+                    fail(FailureKind::Error, format!("Daemon error: {}", e));
+                }
+            };
+            if let Some(addr) = &cli.metrics_addr {
+                start_metrics(addr, daemon.metrics());
+            }
+            if let Err(e) = daemon.run(&socket) {
+                fail(FailureKind::Error, format!("Daemon error: {}", e));
+            }
+            return;
+        }
+        #[cfg(not(unix))]
+        {
+            fail(FailureKind::Error, "Error: --daemon requires Unix domain sockets (not supported on this platform)");
+        }
+    }
+
+    // Handle --import-bundle (no project root needed)
+    if let Some(bundle_path) = &cli.import_bundle {
+        let bundle = match ContextBundle::read_from(bundle_path) {
+            Ok(bundle) => bundle,
+            Err(e) => {
+                fail(FailureKind::Error, format!("Error: {}", e));
+            }
+        };
+        eprintln!("{}", bundle.summary());
+        if let Some(output_path) = &cli.output {
+            if let Err(e) = std::fs::write(output_path, &bundle.context) {
+                fail(FailureKind::Error, format!("Error writing output: {}", e));
+            }
+            eprintln!("Output written to: {}", output_path.display());
+        } else {
+            print!("{}", bundle.context);
+        }
+        return;
+    }
+
+    // Handle --schema (no project root needed)
+    if let Some(kind) = cli.schema {
+        let schema = match kind {
+            SchemaKind::Ir => voyager_ast::ir_schema(),
+        };
+        println!("{}", serde_json::to_string_pretty(&schema).unwrap_or_default());
+        return;
+    }
+
+    // Handle --languages (no project root needed)
+    if cli.languages {
+        let bridge = pm_encoder::core::AstBridge::new();
+        for language in bridge.languages() {
+            let capabilities = bridge.capabilities(language);
+            let extracts: Vec<&str> = capabilities.supported().map(|c| c.label()).collect();
+            println!("{} (.{})", language.name(), language.extension());
+            println!("  extracts: {}", extracts.join(", "));
+            if cli.detailed {
+                let missing: Vec<&str> = capabilities.missing().map(|c| c.label()).collect();
+                println!("  missing: {}", if missing.is_empty() { "-".to_string() } else { missing.join(", ") });
+            }
+        }
+        return;
+    }
+
+    // Handle --blob-gc (shared cache, no project root needed)
+    if cli.blob_gc {
+        let store = match BlobStore::open_default() {
+            Ok(store) => store,
+            Err(e) => {
+                fail(FailureKind::Error, format!("Error: {}", e));
+            }
+        };
+        let report = store.gc(&GcPolicy {
+            max_age: Some(std::time::Duration::from_secs(cli.blob_max_age * 86_400)),
+            max_bytes: cli.blob_max_mb.map(|mb| mb * 1024 * 1024),
+        });
+        eprintln!(
+            "Blob cache {}: removed {} blobs ({} bytes), {} refs; {} blobs ({} bytes) remain",
+            store.root().display(),
+            report.removed_blobs,
+            report.freed_bytes,
+            report.removed_refs,
+            report.remaining.blobs,
+            report.remaining.bytes
+        );
+        return;
+    }
+
+    // Handle --bench (synthetic corpus, no project root needed)
+    if cli.bench {
+        let report = match pm_encoder::core::run_suite(&BenchConfig::default()) {
+            Ok(report) => report,
+            Err(e) => {
+                fail(FailureKind::Error, format!("Benchmark error: {}", e));
+            }
+        };
+        println!("{}", report.to_json());
+
+        if let Some(baseline_path) = &cli.bench_baseline {
+            let baseline = match std::fs::read_to_string(baseline_path)
+                .map_err(|e| format!("Failed to read {}: {}", baseline_path.display(), e))
+                .and_then(|json| BenchReport::from_json(&json))
+            {
+                Ok(baseline) => baseline,
+                Err(e) => {
+                    fail(FailureKind::Error, format!("Error: {}", e));
+                }
+            };
+            let regressions = report.regressions(&baseline, cli.bench_threshold / 100.0);
+            if !regressions.is_empty() {
+                for r in &regressions {
+                    eprintln!(
+                        "Regression: {} {:.2}ms -> {:.2}ms ({:+.0}%)",
+                        r.name,
+                        r.baseline_ns as f64 / 1e6,
+                        r.current_ns as f64 / 1e6,
+                        (r.ratio - 1.0) * 100.0
+                    );
+                }
+                std::process::exit(1);
+            }
+            eprintln!("No regressions beyond {}% of baseline", cli.bench_threshold);
+        }
+        return;
+    }
+
+    // Handle --compare-formats (project root, or the synthetic corpus without one)
+    if let Some(format) = cli.compare_formats {
+        use pm_encoder::core::{FormatComparison, SyntheticCorpus, DEFAULT_COMPARISON_BUDGETS};
+
+        let defaults = pm_encoder::core::EncoderConfig::default();
+        let base = pm_encoder::core::EncoderConfig {
+            include_patterns: cli.include.clone(),
+            ignore_patterns: defaults.ignore_patterns.iter().chain(&cli.exclude).cloned().collect(),
+            ..defaults
+        };
+        let budgets: Vec<Option<usize>> = if cli.compare_budgets.is_empty() {
+            DEFAULT_COMPARISON_BUDGETS.to_vec()
+        } else {
+            std::iter::once(None).chain(cli.compare_budgets.iter().map(|&b| Some(b))).collect()
+        };
+        let comparison = match &cli.project_root {
+            Some(root) => FormatComparison::run(&root.to_string_lossy(), &base, &budgets),
+            None => {
+                let bench = BenchConfig::default();
+                let corpus = SyntheticCorpus::generate(bench.files_per_language, bench.functions_per_file);
+                FormatComparison::run_synthetic(&corpus, &base, &budgets)
+            }
+        };
+        match comparison {
+            Ok(comparison) => match format {
+                StatsFormat::Table => print!("{}", comparison.render_table()),
+                StatsFormat::Json => println!("{}", comparison.to_json()),
+            },
+            Err(e) => {
+                fail(FailureKind::Error, format!("Comparison error: {}", e));
+            }
         }
         return;
     }
@@ -2195,8 +3043,7 @@ pub fn run() {
                 eprintln!("   Path: {}", ObserversJournal::default_path(&journal_root).display());
             }
             Err(e) => {
-                eprintln!("Error clearing journal: {}", e);
-                std::process::exit(1);
+                fail(FailureKind::Error, format!("Error clearing journal: {}", e));
             }
         }
         return;
@@ -2227,8 +3074,7 @@ pub fn run() {
                 eprintln!("   Total bright stars: {}", bright_count);
             }
             Err(e) => {
-                eprintln!("Error saving journal: {}", e);
-                std::process::exit(1);
+                fail(FailureKind::Error, format!("Error saving journal: {}", e));
             }
         }
         return;
@@ -2244,8 +3090,7 @@ pub fn run() {
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
 
         if !survey_root.exists() || !survey_root.is_dir() {
-            eprintln!("Error: Survey path '{}' must be a valid directory", survey_root.display());
-            std::process::exit(1);
+            fail(FailureKind::Error, format!("Error: Survey path '{}' must be a valid directory", survey_root.display()));
         }
 
         // Run the survey
@@ -2253,21 +3098,116 @@ pub fn run() {
         return;
     }
 
-    // If no project root provided, show usage
-    let project_root = match cli.project_root {
-        Some(path) => path,
-        None => {
-            eprintln!("Error: PROJECT_ROOT argument is required");
-            eprintln!("Usage: pm_encoder <PROJECT_ROOT>");
-            eprintln!("\nTry 'pm_encoder --help' for more information.");
-            std::process::exit(1);
-        }
+    // Handle --stats / --heatmap / --flaky-report (aggregations of the index)
+    if cli.stats.is_some() || cli.heatmap.is_some() || cli.flaky_report.is_some() {
+        use voyager_ast::AstProvider;
+
+        let stats_root = cli.project_root.clone()
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+        if !stats_root.is_dir() {
+            fail(FailureKind::Error, format!("Error: Stats path '{}' must be a valid directory", stats_root.display()));
+        }
+
+        let mut options = cached_index_options(cli.no_blob_cache, cli.snapshot);
+        options.include_patterns = cli.include.clone();
+        options.exclude_patterns = cli.exclude.clone();
+        let model = match voyager_ast::TreeSitterProvider::new().index_project(&stats_root, &options) {
+            Ok(model) => {
+                warn_changed_during_scan(&model);
+                model
+            }
+            Err(e) => {
+                fail(FailureKind::Error, format!("Error indexing {}: {}", stats_root.display(), e));
+            }
+        };
+
+        if let Some(format) = cli.stats {
+            let report = pm_encoder::core::LanguageStatsReport::from_model(&model, cli.stats_top);
+            match format {
+                StatsFormat::Table => print!("{}", report.render_table()),
+                StatsFormat::Json => println!("{}", report.to_json()),
+            }
+        }
+        if let Some(format) = cli.heatmap {
+            let heatmap = pm_encoder::core::Heatmap::from_model(&model, cli.heatmap_depth);
+            match format {
+                StatsFormat::Table => print!("{}", heatmap.render_text()),
+                StatsFormat::Json => println!("{}", heatmap.to_json()),
+            }
+        }
+        if let Some(format) = cli.flaky_report {
+            let report = pm_encoder::core::FlakyReport::from_model(&model, &load_ci_history(&cli.junit));
+            match format {
+                StatsFormat::Table => print!("{}", report.render_text()),
+                StatsFormat::Json => println!("{}", report.to_json()),
+            }
+        }
+        return;
+    }
+
+    // Handle --review / --commit-pack (git changes, defaults to the current directory)
+    if cli.review.is_some() || cli.commit_pack {
+        let git_root = cli.project_root.clone()
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
+        let document = match &cli.review {
+            Some(range) => pm_encoder::core::ReviewPack::build(&git_root, range, &Default::default())
+                .map(|pack| pack.render()),
+            None => pm_encoder::core::CommitPack::staged(&git_root)
+                .map(|pack| pack.render(cli.conventional)),
+        };
+        let document = match document {
+            Ok(document) => document,
+            Err(e) => {
+                fail(FailureKind::Error, format!("Error: {}", e));
+            }
+        };
+        if let Some(output_path) = &cli.output {
+            if let Err(e) = std::fs::write(output_path, &document) {
+                fail(FailureKind::Error, format!("Error writing output: {}", e));
+            }
+            eprintln!("Output written to: {}", output_path.display());
+        } else {
+            print!("{}", document);
+        }
+        return;
+    }
+
+    // Resolved before the CLI fields below are moved into the config
+    let view = git_view(&cli);
+
+    // If no project root provided, show usage
+    let project_root = match cli.project_root {
+        Some(path) => path,
+        None => {
+            fail_with_hint(
+                FailureKind::Error,
+                "Error: PROJECT_ROOT argument is required",
+                "Usage: pm_encoder <PROJECT_ROOT>\n\nTry 'pm_encoder --help' for more information.",
+            );
+        }
     };
 
     // Validate project root exists
     if !project_root.exists() {
-        eprintln!("Error: Path '{}' does not exist", project_root.display());
-        std::process::exit(1);
+        fail(FailureKind::Error, format!("Error: Path '{}' does not exist", project_root.display()));
+    }
+
+    // Focus hints from an issue pick the lens (unless given) and bias the budget
+    let focus_hints = load_focus_hints(cli.issue.as_deref(), cli.issue_file.as_deref());
+    if let Some(hints) = &focus_hints {
+        eprintln!(
+            "[FOCUS] {}: {} paths, {} symbols, {} keywords",
+            hints.source.as_deref().unwrap_or("issue"),
+            hints.paths.len(),
+            hints.symbols.len(),
+            hints.keywords.len()
+        );
+        if cli.lens.is_none() {
+            if let Some(lens) = hints.suggest_lens() {
+                eprintln!("[FOCUS] Using suggested lens: {}", lens);
+                cli.lens = Some(lens.to_string());
+            }
+        }
     }
 
     // ═══════════════════════════════════════════════════════════════════════════
@@ -2290,9 +3230,7 @@ pub fn run() {
         let actual_root = find_project_root(&parent).unwrap_or(parent);
 
         // Calculate relative path from project root to file
-        let relative_path = file_path.strip_prefix(&actual_root)
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_else(|_| file_path.display().to_string());
+        let relative_path = pm_encoder::core::ProjectPaths::new(&actual_root).relative(&file_path);
 
         eprintln!("🔬 Microscope Auto-Focus: Detected file path, zooming in...");
         eprintln!("   File: {}", relative_path);
@@ -2305,14 +3243,15 @@ pub fn run() {
     };
 
     if !project_root.is_dir() {
-        eprintln!("Error: Path '{}' is not a directory", project_root.display());
-        std::process::exit(1);
+        fail(FailureKind::Error, format!("Error: Path '{}' is not a directory", project_root.display()));
     }
 
     // Handle --report-utility command (Context Store v2.2.0)
     if let Some(utility_str) = &cli.report_utility {
         match parse_report_utility(utility_str) {
             Ok((path, score, reason)) => {
+                let path = pm_encoder::core::normalize_relative_path(&path);
+
                 // Load or create context store
                 let store_path = ContextStore::default_path(&project_root);
                 let mut store = if cli.store_privacy {
@@ -2333,15 +3272,13 @@ pub fn run() {
                         eprintln!("Store saved to: {}", store_path.display());
                     }
                     Err(e) => {
-                        eprintln!("Error saving context store: {}", e);
-                        std::process::exit(1);
+                        fail(FailureKind::Error, format!("Error saving context store: {}", e));
                     }
                 }
                 return;
             }
             Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+                fail(FailureKind::Error, format!("Error: {}", e));
             }
         }
     }
@@ -2354,9 +3291,11 @@ pub fn run() {
         let intent: ExplorationIntent = match intent_str.parse() {
             Ok(i) => i,
             Err(e) => {
-                eprintln!("Error: {}", e);
-                eprintln!("Valid intents: business-logic, debugging, onboarding, security, migration");
-                std::process::exit(1);
+                fail_with_hint(
+                    FailureKind::Error,
+                    format!("Error: {}", e),
+                    "Valid intents: business-logic, debugging, onboarding, security, migration",
+                );
             }
         };
 
@@ -2384,8 +3323,7 @@ pub fn run() {
                     match std::fs::write(output_path, &output) {
                         Ok(_) => eprintln!("Exploration output written to: {}", output_path.display()),
                         Err(e) => {
-                            eprintln!("Error writing output: {}", e);
-                            std::process::exit(1);
+                            fail(FailureKind::Error, format!("Error writing output: {}", e));
                         }
                     }
                 } else {
@@ -2394,8 +3332,7 @@ pub fn run() {
                 return;
             }
             Err(e) => {
-                eprintln!("Exploration error: {}", e);
-                std::process::exit(1);
+                fail(FailureKind::Error, format!("Exploration error: {}", e));
             }
         }
     }
@@ -2425,7 +3362,11 @@ pub fn run() {
     }
 
     if !cli.exclude.is_empty() {
-        config.ignore_patterns.extend(cli.exclude);
+        config.ignore_patterns.extend(cli.exclude.clone());
+    }
+
+    if let Some(case_insensitive) = cli.case_insensitive_paths {
+        config.case_insensitive_paths = case_insensitive;
     }
 
     config.sort_by = match cli.sort_by {
@@ -2441,6 +3382,10 @@ pub fn run() {
 
     config.stream = cli.stream;
     config.follow_symlinks = cli.follow_symlinks;
+    config.outline_depth = cli.outline_depth;
+    config.import_rollup = cli.import_rollup;
+    config.strip_boilerplate |= cli.strip_boilerplate;
+    config.provenance_annotations = cli.provenance;
 
     // Apply truncation settings
     config.truncate_lines = cli.truncate;
@@ -2448,10 +3393,23 @@ pub fn run() {
         TruncateMode::Simple => "simple".to_string(),
         TruncateMode::Smart => "smart".to_string(),
         TruncateMode::Structure => "structure".to_string(),
+        TruncateMode::Window => "window".to_string(),
     };
     config.truncate_summary = cli.truncate_summary && !cli.no_truncate_summary;
     config.truncate_exclude = cli.truncate_exclude.clone();
     config.truncate_stats = cli.truncate_stats;
+    if let Some(budget_str) = &cli.symbol_budget {
+        match parse_token_budget(budget_str) {
+            Ok(budget) => config.symbol_budget = Some(budget),
+            Err(e) => {
+                fail(FailureKind::Error, format!("Error: Invalid --symbol-budget: {}", e));
+            }
+        }
+    }
+    config.safe_cuts = cli.safe_cuts;
+    config.affordance_index = cli.affordance_index;
+    config.table_of_contents = cli.toc;
+    config.git_view = view;
 
     // Apply output format
     config.output_format = match cli.format {
@@ -2465,6 +3423,12 @@ pub fn run() {
     config.frozen = cli.frozen;
     config.allow_sensitive = cli.allow_sensitive;
     config.active_lens = cli.lens.clone();
+    // The lens's language rules apply under those of the config file
+    if let Some(lens) = cli.lens.as_deref().and_then(|name| LensManager::new().get_lens(name).cloned()) {
+        for (language, rule) in lens.languages {
+            config.language_rules.entry(language).or_insert(rule);
+        }
+    }
 
     // Apply skeleton mode (v2.2.0)
     config.skeleton_mode = SkeletonMode::parse(&cli.skeleton).unwrap_or(SkeletonMode::Auto);
@@ -2486,11 +3450,6 @@ pub fn run() {
         pm_encoder::MetadataMode::Auto
     };
 
-    // Streaming mode warning for file output
-    if cli.stream && cli.output.is_some() {
-        eprintln!("Warning: --stream mode writes directly to stdout, ignoring -o/--output");
-    }
-
     // ═══════════════════════════════════════════════════════════════════════════
     // FRACTAL PROTOCOL v2: Zoom Session Management (v1.1.0)
     // ═══════════════════════════════════════════════════════════════════════════
@@ -2519,8 +3478,7 @@ pub fn run() {
                         eprintln!("Use --zoom to add targets, --zoom-session show to view");
                     }
                     Err(e) => {
-                        eprintln!("Error creating session: {}", e);
-                        std::process::exit(1);
+                        fail(FailureKind::Error, format!("Error creating session: {}", e));
                     }
                 }
                 return;
@@ -2534,12 +3492,10 @@ pub fn run() {
                         eprintln!("Loaded zoom session: {}", name);
                     }
                     Ok(Err(e)) => {
-                        eprintln!("Error: {}", e);
-                        std::process::exit(1);
+                        fail(FailureKind::Error, format!("Error: {}", e));
                     }
                     Err(e) => {
-                        eprintln!("Error loading sessions: {}", e);
-                        std::process::exit(1);
+                        fail(FailureKind::Error, format!("Error loading sessions: {}", e));
                     }
                 }
                 return;
@@ -2560,8 +3516,7 @@ pub fn run() {
                         }
                     }
                     Err(e) => {
-                        eprintln!("Error loading sessions: {}", e);
-                        std::process::exit(1);
+                        fail(FailureKind::Error, format!("Error loading sessions: {}", e));
                     }
                 }
                 return;
@@ -2594,8 +3549,7 @@ pub fn run() {
                         }
                     }
                     Err(e) => {
-                        eprintln!("Error loading sessions: {}", e);
-                        std::process::exit(1);
+                        fail(FailureKind::Error, format!("Error loading sessions: {}", e));
                     }
                 }
                 return;
@@ -2604,9 +3558,7 @@ pub fn run() {
                 let name = match name {
                     Some(n) => n,
                     None => {
-                        eprintln!("Error: delete requires session name");
-                        eprintln!("Usage: --zoom-session delete:<name>");
-                        std::process::exit(1);
+                        fail_with_hint(FailureKind::Error, "Error: delete requires session name", "Usage: --zoom-session delete:<name>");
                     }
                 };
                 match ZoomSessionStore::with_persistence(&session_store_path, |store| {
@@ -2616,22 +3568,279 @@ pub fn run() {
                         eprintln!("Deleted session: {}", name);
                     }
                     Ok(Err(e)) => {
-                        eprintln!("Error: {}", e);
-                        std::process::exit(1);
+                        fail(FailureKind::Error, format!("Error: {}", e));
                     }
                     Err(e) => {
-                        eprintln!("Error: {}", e);
-                        std::process::exit(1);
+                        fail(FailureKind::Error, format!("Error: {}", e));
                     }
                 }
                 return;
             }
+            "replay" => {
+                let store = ZoomSessionStore::load(&session_store_path).unwrap_or_else(|e| {
+                    fail(FailureKind::Error, format!("Error loading sessions: {}", e));
+                });
+                let session = match name {
+                    Some(name) => store.get_session(name),
+                    None => store.active(),
+                };
+                let Some(session) = session else {
+                    fail_with_hint(
+                        FailureKind::Error,
+                        format!("Error: no session {}", name.map_or("active".to_string(), |n| format!("'{}'", n))),
+                        "Use --zoom-session list to see saved sessions",
+                    );
+                };
+                let engine = context_engine(&config);
+                let replay = pm_encoder::core::SessionReplay::replay(session, &engine, project_root.to_str().unwrap());
+                let document = replay.render();
+                if let Some(output_path) = cli.output.clone() {
+                    if let Err(e) = std::fs::write(&output_path, &document) {
+                        fail(FailureKind::Error, format!("Error writing output: {}", e));
+                    }
+                    eprintln!("Output written to: {}", output_path.display());
+                } else {
+                    print!("{}", document);
+                }
+                let failed = replay.steps.iter().filter(|s| s.error.is_some()).count();
+                eprintln!("Replayed {} steps ({} not reproducible)", replay.steps.len(), failed);
+                return;
+            }
             _ => {
-                eprintln!("Unknown zoom-session action: {}", action);
-                eprintln!("Valid actions: create, load, list, delete, show");
-                std::process::exit(1);
+                fail_with_hint(
+                    FailureKind::Error,
+                    format!("Unknown zoom-session action: {}", action),
+                    "Valid actions: create, load, list, delete, show, replay",
+                );
+            }
+        }
+    }
+
+    if let Some(bookmark_cmd) = &cli.bookmark {
+        use pm_encoder::core::{BookmarkStore, SymbolResolver};
+
+        let store_path = BookmarkStore::default_path(&project_root);
+        let mut store = BookmarkStore::load(&store_path).unwrap_or_else(|e| {
+            fail(e.failure_kind(), format!("Error loading bookmarks: {}", e));
+        });
+
+        // Parse action:name format
+        let (action, name) = match bookmark_cmd.split_once(':') {
+            Some((action, name)) => (action, Some(name)),
+            None => (bookmark_cmd.as_str(), None),
+        };
+        let Some(name) = name.or((action == "list").then_some("")) else {
+            fail_with_hint(
+                FailureKind::Usage,
+                format!("Error: --bookmark {} needs a name", action),
+                "Use --bookmark add:NAME=TARGET, resolve:NAME or remove:NAME",
+            );
+        };
+
+        match action {
+            "add" => {
+                let Some((name, target)) = name.split_once('=') else {
+                    fail_with_hint(
+                        FailureKind::Usage,
+                        format!("Error: missing target in '{}'", bookmark_cmd),
+                        "Use --bookmark add:NAME=TARGET, e.g. add:payment-flow=function=process_payment",
+                    );
+                };
+                if let Err(e) = store.add(&project_root, name, target, cli.bookmark_note.clone()) {
+                    fail(e.failure_kind(), format!("Error: {}", e));
+                }
+                if let Err(e) = store.save() {
+                    fail(FailureKind::Error, format!("Error saving bookmarks: {}", e));
+                }
+                eprintln!("Bookmarked {} -> {}", name, target);
+                eprintln!("Use --zoom bookmark={} to zoom into it", name);
+            }
+            "list" => {
+                if store.is_empty() {
+                    eprintln!("No bookmarks found.");
+                    eprintln!("Use --bookmark add:<name>=<target> to create one");
+                }
+                for (name, bookmark) in &store.bookmarks {
+                    match &bookmark.note {
+                        Some(note) => println!("{}\t{}\t{}", name, bookmark.target, note),
+                        None => println!("{}\t{}", name, bookmark.target),
+                    }
+                }
+            }
+            "resolve" => {
+                let resolver = SymbolResolver::new().with_ignore(config.ignore_patterns.clone());
+                match store.resolve(name, &project_root, &resolver) {
+                    Ok(resolved) => match resolved.lines {
+                        Some((start, end)) => println!("{}:{}-{}", resolved.path, start, end),
+                        None => println!("{}", resolved.path),
+                    },
+                    Err(e) => {
+                        warn_zoom_near_miss(&format!("bookmark={}", name), name, &store.names());
+                        fail(e.failure_kind(), format!("Error: {}", e));
+                    }
+                }
+            }
+            "remove" => {
+                if !store.remove(name) {
+                    fail_with_hint(
+                        FailureKind::Error,
+                        format!("Error: no bookmark '{}'", name),
+                        "Use --bookmark list to see saved bookmarks",
+                    );
+                }
+                if let Err(e) = store.save() {
+                    fail(FailureKind::Error, format!("Error saving bookmarks: {}", e));
+                }
+                eprintln!("Removed bookmark: {}", name);
+            }
+            _ => {
+                fail_with_hint(
+                    FailureKind::Usage,
+                    format!("Unknown bookmark action: {}", action),
+                    "Valid actions: add, list, resolve, remove",
+                );
+            }
+        }
+        return;
+    }
+
+    if let Some(investigate_cmd) = &cli.investigate {
+        use pm_encoder::core::{InvestigationStore, SymbolResolver, ZoomDepth, ZoomSessionStore, ZoomTarget};
+
+        let store_path = InvestigationStore::default_path(&project_root);
+        let mut store = InvestigationStore::load(&store_path).unwrap_or_else(|e| {
+            fail(e.failure_kind(), format!("Error loading investigations: {}", e));
+        });
+
+        // Parse action:arg format
+        let (action, arg) = match investigate_cmd.split_once(':') {
+            Some((action, arg)) => (action, Some(arg)),
+            None => (investigate_cmd.as_str(), None),
+        };
+        let required = |what: &str| -> &str {
+            arg.filter(|a| !a.is_empty()).unwrap_or_else(|| {
+                fail_with_hint(
+                    FailureKind::Usage,
+                    format!("Error: --investigate {} needs {}", action, what),
+                    &format!("Use --investigate {}:<{}>", action, what),
+                );
+            })
+        };
+        let no_active = || -> ! {
+            fail_with_hint(
+                FailureKind::Error,
+                "Error: no active investigation",
+                "Use --investigate start:<name> to start one",
+            );
+        };
+
+        match action {
+            "start" => {
+                let name = required("name");
+                let resumed = store.get(name).is_some();
+                store.start(name, None);
+                // Zooms are also recorded in the zoom session of the same name, for --zoom-session replay
+                if let Err(e) = ZoomSessionStore::with_persistence(&ZoomSessionStore::default_path(&project_root), |sessions| {
+                    if sessions.get_session(name).is_none() {
+                        sessions.create_session_with_desc(name, "Investigation");
+                    }
+                }) {
+                    eprintln!("Warning: Could not create zoom session: {}", e);
+                }
+                eprintln!("{} investigation: {}", if resumed { "Resumed" } else { "Started" }, name);
+                eprintln!("Record steps with --investigate zoom:<target>, search:<symbol> or note:<text>");
+            }
+            "zoom" => {
+                let target = required("target");
+                let engine = context_engine(&config);
+                let Some(investigation) = store.active_mut() else { no_active() };
+                match investigation.zoom(&engine, &project_root, target) {
+                    Ok(entry) => print!("{}", entry.context().unwrap_or_default()),
+                    Err(e) => fail(e.failure_kind(), format!("Error: {}", e)),
+                }
+                let name = investigation.name.clone();
+                if let Ok(parsed) = ZoomTarget::parse(&pm_encoder::core::bookmarks::expand_target(&project_root, target).unwrap_or_default()) {
+                    let _ = ZoomSessionStore::with_persistence(&ZoomSessionStore::default_path(&project_root), |sessions| {
+                        if let Some(session) = sessions.get_session_mut(&name) {
+                            session.add_zoom(parsed, ZoomDepth::Full);
+                        }
+                    });
+                }
+            }
+            "search" => {
+                let symbol = required("symbol");
+                let resolver = SymbolResolver::new().with_ignore(config.ignore_patterns.clone());
+                let Some(investigation) = store.active_mut() else { no_active() };
+                let hits = investigation.search(&resolver, &project_root, symbol).hits();
+                for hit in hits {
+                    let role = if hit.definition { " (definition)" } else { "" };
+                    println!("{}:{}{}: {}", hit.path, hit.line, role, hit.snippet);
+                }
+                eprintln!("Recorded {} hits for '{}'", hits.len(), symbol);
+            }
+            "note" => {
+                let text = required("text");
+                let Some(investigation) = store.active_mut() else { no_active() };
+                investigation.note(text);
+                eprintln!("Noted in investigation: {}", investigation.name);
+            }
+            "report" => {
+                let investigation = match arg {
+                    Some(name) => store.get(name),
+                    None => store.active(),
+                };
+                let Some(investigation) = investigation else {
+                    fail_with_hint(
+                        FailureKind::Error,
+                        format!("Error: no investigation {}", arg.map_or("active".to_string(), |n| format!("'{}'", n))),
+                        "Use --investigate list to see saved investigations",
+                    );
+                };
+                let document = investigation.render();
+                if let Some(output_path) = cli.output.clone() {
+                    if let Err(e) = std::fs::write(&output_path, &document) {
+                        fail(FailureKind::Error, format!("Error writing output: {}", e));
+                    }
+                    eprintln!("Output written to: {}", output_path.display());
+                } else {
+                    print!("{}", document);
+                }
+                return;
+            }
+            "list" => {
+                if store.investigations.is_empty() {
+                    eprintln!("No investigations found.");
+                    eprintln!("Use --investigate start:<name> to start one");
+                }
+                for (name, investigation) in &store.investigations {
+                    let marker = if store.active.as_deref() == Some(name.as_str()) { " *" } else { "" };
+                    println!("{}{}\t{} entries", name, marker, investigation.entries.len());
+                }
+                return;
+            }
+            "delete" => {
+                let name = required("name");
+                if !store.remove(name) {
+                    fail_with_hint(
+                        FailureKind::Error,
+                        format!("Error: no investigation '{}'", name),
+                        "Use --investigate list to see saved investigations",
+                    );
+                }
+                eprintln!("Deleted investigation: {}", name);
+            }
+            _ => {
+                fail_with_hint(
+                    FailureKind::Usage,
+                    format!("Unknown investigate action: {}", action),
+                    "Valid actions: start, zoom, search, note, report, list, delete",
+                );
             }
         }
+        if let Err(e) = store.save() {
+            fail(FailureKind::Error, format!("Error saving investigations: {}", e));
+        }
+        return;
     }
 
     // Zoom undo/redo (Fractal v2)
@@ -2714,8 +3923,7 @@ pub fn run() {
                 return;
             }
             Err(e) => {
-                eprintln!("Error parsing collapse target: {}", e);
-                std::process::exit(1);
+                fail(FailureKind::Error, format!("Error parsing collapse target: {}", e));
             }
         }
     }
@@ -2724,13 +3932,15 @@ pub fn run() {
     // Includes Microscope Auto-Focus (v1.2.0) - auto-zoom when path is a file
     let effective_zoom = cli.zoom.as_ref().or(auto_zoom_target.as_ref());
     if let Some(zoom_str) = effective_zoom {
+        let zoom_str = &expand_bookmark(&project_root, zoom_str);
         let mut zoom_config = match parse_zoom_target(zoom_str) {
             Ok(config) => config,
             Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+                fail(FailureKind::Error, format!("Error: {}", e));
             }
         };
+        zoom_config.include_tests = cli.zoom_tests;
+        zoom_config.include_docs = cli.zoom_docs;
 
         // ═══════════════════════════════════════════════════════════════════════════
         // FRACTAL PROTOCOL v2: Cross-File Symbol Resolution
@@ -2741,6 +3951,7 @@ pub fn run() {
         // Track the original symbol name for excluding from suggestions
         let original_symbol_name: Option<String> = match &zoom_config.target {
             ZoomTarget::Function(name) | ZoomTarget::Class(name) => Some(name.clone()),
+            ZoomTarget::Anchor(anchor) => Some(anchor.name().to_string()),
             _ => None,
         };
 
@@ -2763,8 +3974,9 @@ pub fn run() {
                         Some(loc.path)
                     }
                     Err(e) => {
-                        eprintln!("Symbol resolution failed: {}", e);
-                        std::process::exit(1);
+                        let names = resolver.symbol_names(pm_encoder::core::SymbolType::Function, &project_root);
+                        warn_zoom_near_miss(zoom_str, name, &names);
+                        fail(FailureKind::Error, format!("Symbol resolution failed: {}", e));
                     }
                 }
             }
@@ -2786,8 +3998,9 @@ pub fn run() {
                         Some(loc.path)
                     }
                     Err(e) => {
-                        eprintln!("Symbol resolution failed: {}", e);
-                        std::process::exit(1);
+                        let names = resolver.symbol_names(pm_encoder::core::SymbolType::Class, &project_root);
+                        warn_zoom_near_miss(zoom_str, name, &names);
+                        fail(FailureKind::Error, format!("Symbol resolution failed: {}", e));
                     }
                 }
             }
@@ -2802,36 +4015,21 @@ pub fn run() {
                 eprintln!("Module zoom: Looking for files matching {:?}", module_patterns);
                 None // Keep as-is, engine will handle module zoom
             }
+            ZoomTarget::Section(target) => {
+                // Sections are located by the engine; only a scoped target names its file
+                pm_encoder::core::outline::split_section_target(target).0.map(str::to_string)
+            }
+            ZoomTarget::Anchor(anchor) => Some(anchor.path.clone()),
             ZoomTarget::File { path, .. } => Some(path.clone()),
         };
 
         // Build engine with current config
-        let engine = ContextEngine::with_config(pm_encoder::core::EncoderConfig {
-            ignore_patterns: config.ignore_patterns.clone(),
-            include_patterns: config.include_patterns.clone(),
-            max_file_size: config.max_file_size,
-            truncate_lines: config.truncate_lines,
-            truncate_mode: config.truncate_mode.clone(),
-            sort_by: config.sort_by.clone(),
-            sort_order: config.sort_order.clone(),
-            stream: config.stream,
-            truncate_summary: config.truncate_summary,
-            truncate_exclude: config.truncate_exclude.clone(),
-            truncate_stats: config.truncate_stats,
-            output_format: match config.output_format {
-                OutputFormat::PlusMinus => pm_encoder::core::OutputFormat::PlusMinus,
-                OutputFormat::Xml => pm_encoder::core::OutputFormat::Xml,
-                OutputFormat::Markdown => pm_encoder::core::OutputFormat::Markdown,
-                OutputFormat::ClaudeXml => pm_encoder::core::OutputFormat::ClaudeXml,
-            },
-            frozen: config.frozen,
-            allow_sensitive: config.allow_sensitive,
-            active_lens: config.active_lens.clone(),
-            token_budget: config.token_budget,
-            skeleton_mode: config.skeleton_mode,
-            metadata_mode: config.metadata_mode,
-            follow_symlinks: config.follow_symlinks,
-        });
+        let engine = context_engine(&config);
+
+        let required = zoom_config.required_capabilities();
+        for warning in engine.capability_warnings(project_root.to_str().unwrap(), &required).unwrap_or_default() {
+            eprintln!("Warning: {}", warning);
+        }
 
         match engine.zoom(project_root.to_str().unwrap(), &zoom_config) {
             Ok(output) => {
@@ -2844,7 +4042,8 @@ pub fn run() {
                         let mut store = ContextStore::load_from_file(&store_path);
 
                         const ZOOM_BUMP: f64 = 0.05;
-                        store.bump_utility(file_path, ZOOM_BUMP, DEFAULT_ALPHA);
+                        let key = pm_encoder::core::normalize_relative_path(file_path);
+                        store.bump_utility(&key, ZOOM_BUMP, DEFAULT_ALPHA);
 
                         if let Err(e) = store.save_to_file(&store_path) {
                             eprintln!("Warning: Could not save zoom utility bump: {}", e);
@@ -2896,8 +4095,7 @@ pub fn run() {
                     match std::fs::write(&output_path, &final_output) {
                         Ok(_) => eprintln!("Zoom output written to: {}", output_path.display()),
                         Err(e) => {
-                            eprintln!("Error writing output: {}", e);
-                            std::process::exit(1);
+                            fail(FailureKind::Error, format!("Error writing output: {}", e));
                         }
                     }
                 } else {
@@ -2905,50 +4103,359 @@ pub fn run() {
                 }
             }
             Err(e) => {
-                eprintln!("Zoom error: {}", e);
+                if let ZoomTarget::File { path, .. } = &zoom_config.target {
+                    let paths: Vec<String> = pm_encoder::walk_project(project_root.to_str().unwrap(), &config)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|e| e.path)
+                        .collect();
+                    warn_zoom_near_miss(zoom_str, path, &paths);
+                }
+                fail(e.failure_kind(), format!("Zoom error: {}", e));
+            }
+        }
+        return;
+    }
+
+    let target_str = match cli.target {
+        TargetAI::Claude => "claude",
+        TargetAI::Gemini => "gemini",
+    };
+
+    // Instruction drift check - regenerate in memory, compare the generated section
+    if cli.check_instructions {
+        let root = project_root.to_str().unwrap();
+        if cli.write {
+            match pm_encoder::init::write_instructions(root, &cli.init_lens, target_str, cli.compress) {
+                Ok((path, true)) => eprintln!("Updated: {}", path.display()),
+                Ok((path, false)) => eprintln!("Up to date: {}", path.display()),
+                Err(e) => {
+                    fail(FailureKind::Error, format!("Error: {}", e));
+                }
+            }
+            return;
+        }
+
+        match pm_encoder::init::check_instructions(root, &cli.init_lens, target_str, cli.compress) {
+            Ok((path, InstructionDrift::UpToDate)) => eprintln!("Up to date: {}", path.display()),
+            Ok((path, drift)) => {
+                match drift {
+                    InstructionDrift::Missing => eprintln!("Missing: {}", path.display()),
+                    InstructionDrift::NoMarkers => eprintln!(
+                        "No generated section in {} (expected {} ... {})",
+                        path.display(),
+                        pm_encoder::init::GENERATED_BEGIN,
+                        pm_encoder::init::GENERATED_END,
+                    ),
+                    InstructionDrift::Stale { line, expected, found } => {
+                        eprintln!("Stale: {} (generated section line {})", path.display(), line);
+                        eprintln!("  expected: {}", expected);
+                        eprintln!("  found:    {}", found);
+                    }
+                    InstructionDrift::UpToDate => {}
+                }
+                eprintln!("Run with --check-instructions --write to regenerate");
                 std::process::exit(1);
             }
+            Err(e) => {
+                fail(FailureKind::Error, format!("Error: {}", e));
+            }
         }
         return;
     }
 
     // Init-prompt mode (v0.9.0) - Generate CLAUDE.md/GEMINI_INSTRUCTIONS.txt + CONTEXT.txt
     if cli.init_prompt {
-        let target_str = match cli.target {
-            TargetAI::Claude => "claude",
-            TargetAI::Gemini => "gemini",
-        };
 
         match pm_encoder::init::init_prompt(
             project_root.to_str().unwrap(),
             &cli.init_lens,
             target_str,
+            cli.compress,
         ) {
             Ok((instruction_path, context_path)) => {
                 eprintln!("Generated: {}", instruction_path);
                 eprintln!("Generated: {}", context_path);
             }
             Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+                fail(FailureKind::Error, format!("Error: {}", e));
+            }
+        }
+        return;
+    }
+
+    // Level-of-detail mode: per-subtree LOD, filled breadth-first under a budget
+    if cli.lod.is_some() || !cli.lod_path.is_empty() || cli.lod_symbol_budget.is_some() {
+        let default = match cli.lod.as_deref().map(Lod::parse) {
+            None => Lod::Full,
+            Some(Some(lod)) => lod,
+            Some(None) => {
+                fail(FailureKind::Error, "Error: Invalid --lod level. Valid levels: 0-3 (files, declarations, signatures, full)");
+            }
+        };
+        let mut plan = LodPlan::new(default);
+        for spec in &cli.lod_path {
+            match LodPlan::parse_subtree(spec) {
+                Ok((prefix, lod)) => plan = plan.with_subtree(&prefix, lod),
+                Err(e) => {
+                    fail(FailureKind::Error, format!("Error: {}", e));
+                }
+            }
+        }
+        if let Some(budget_str) = &cli.lod_symbol_budget {
+            match parse_token_budget(budget_str) {
+                Ok(budget) => plan = plan.with_symbol_budget(budget),
+                Err(e) => {
+                    fail(FailureKind::Error, format!("Error: Invalid --lod-symbol-budget: {}", e));
+                }
+            }
+        }
+        if let Some(budget_str) = &cli.token_budget {
+            match parse_token_budget(budget_str) {
+                Ok(budget) => config.token_budget = Some(budget),
+                Err(e) => {
+                    fail(FailureKind::Error, format!("Error: {}", e));
+                }
+            }
+        }
+
+        let engine = context_engine(&config);
+        match engine.render_lod(project_root.to_str().unwrap(), &plan) {
+            Ok(output) => print!("{}", output),
+            Err(e) => {
+                fail(FailureKind::Error, format!("Error: {}", e));
+            }
+        }
+        return;
+    }
+
+    // API reference mode: public declarations by module, as a standalone document
+    if let Some(format) = cli.api_reference {
+        let entries = pm_encoder::walk_project(project_root.to_str().unwrap(), &config)
+        .unwrap_or_else(|e| {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        });
+        let pairs: Vec<(String, String)> = entries.into_iter().map(|e| (e.path, e.content)).collect();
+        let reference = pm_encoder::core::ApiReference::build(&pairs);
+        let document = match format {
+            ApiReferenceFormat::Markdown => reference.to_markdown(),
+            ApiReferenceFormat::Json => format!("{}\n", reference.to_json()),
+        };
+        if let Some(output_path) = cli.output.clone() {
+            if let Err(e) = std::fs::write(&output_path, &document) {
+                fail(FailureKind::Error, format!("Error writing output: {}", e));
+            }
+            eprintln!("Output written to: {}", output_path.display());
+        } else {
+            print!("{}", document);
+        }
+        eprintln!("API reference: {} declarations in {} modules", reference.item_count(), reference.modules.len());
+        return;
+    }
+
+    // Question pack mode: evaluation questions with ground truth, from the index
+    if let Some(format) = cli.question_pack {
+        let entries = pm_encoder::walk_project(project_root.to_str().unwrap(), &config)
+        .unwrap_or_else(|e| {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        });
+        let pairs: Vec<(String, String)> = entries.into_iter().map(|e| (e.path, e.content)).collect();
+        let pack = pm_encoder::core::QuestionPack::generate(&pairs, cli.question_limit);
+        let document = match format {
+            QuestionPackFormat::Json => format!("{}\n", pack.to_json()),
+            QuestionPackFormat::Markdown => pack.to_markdown(),
+        };
+        if let Some(output_path) = cli.output.clone() {
+            if let Err(e) = std::fs::write(&output_path, &document) {
+                fail(FailureKind::Error, format!("Error writing output: {}", e));
+            }
+            eprintln!("Output written to: {}", output_path.display());
+        } else {
+            print!("{}", document);
+        }
+        eprintln!("Question pack: {} questions", pack.len());
+        return;
+    }
+
+    // Snippet mode: one symbol as a standalone source file
+    if let Some(target) = cli.snippet.as_deref() {
+        let target = parse_zoom_target(&expand_bookmark(&project_root, target)).unwrap_or_else(|e| {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        });
+        let entries = pm_encoder::walk_project(project_root.to_str().unwrap(), &config)
+        .unwrap_or_else(|e| {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        });
+        let pairs: Vec<(String, String)> = entries.into_iter().map(|e| (e.path, e.content)).collect();
+        let snippet = pm_encoder::core::Snippet::extract(&pairs, &target.target).unwrap_or_else(|e| {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        });
+        if let Some(output_path) = cli.output.clone() {
+            if let Err(e) = std::fs::write(&output_path, &snippet.text) {
+                fail(FailureKind::Error, format!("Error writing output: {}", e));
+            }
+            eprintln!("Output written to: {}", output_path.display());
+        } else {
+            print!("{}", snippet.text);
+        }
+        eprintln!("Snippet: {} with {} types, {} imports", snippet.target, snippet.types.len(), snippet.imports.len());
+        return;
+    }
+
+    // Test scaffold mode: empty tests framed from public signatures
+    if let Some(selection) = cli.test_scaffold.as_deref() {
+        let entries = pm_encoder::walk_project(project_root.to_str().unwrap(), &config)
+        .unwrap_or_else(|e| {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        });
+        let pairs: Vec<(String, String)> = entries.into_iter().map(|e| (e.path, e.content)).collect();
+        let selection: Vec<String> =
+            selection.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect();
+        let scaffolds = pm_encoder::core::scaffold_tests(&pairs, &selection);
+        if let Some(output_dir) = cli.output.clone() {
+            for scaffold in &scaffolds {
+                let path = output_dir.join(&scaffold.path);
+                let written = path
+                    .parent()
+                    .map_or(Ok(()), std::fs::create_dir_all)
+                    .and_then(|_| std::fs::write(&path, &scaffold.text));
+                if let Err(e) = written {
+                    fail(FailureKind::Error, format!("Error writing {}: {}", path.display(), e));
+                }
+            }
+            eprintln!("Output written to: {}", output_dir.display());
+        } else {
+            for (i, scaffold) in scaffolds.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                print!("{}", scaffold.text);
+            }
+        }
+        let tests: usize = scaffolds.iter().map(|s| s.tests.len()).sum();
+        eprintln!("Test scaffold: {} tests in {} files", tests, scaffolds.len());
+        return;
+    }
+
+    // TODO export mode: issue-ready TODO/FIXME list
+    if let Some(format) = cli.todos {
+        let entries = pm_encoder::walk_project(project_root.to_str().unwrap(), &config)
+        .unwrap_or_else(|e| {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        });
+        let pairs: Vec<(String, String)> = entries.into_iter().map(|e| (e.path, e.content)).collect();
+        let todos = pm_encoder::core::TodoList::collect(&pairs);
+        #[cfg(feature = "temporal")]
+        let todos = todos.with_blame(&project_root, &pairs);
+        let document = match format {
+            TodoFormat::Md => todos.to_markdown(),
+            TodoFormat::Csv => todos.to_csv(),
+            TodoFormat::Json => format!("{}\n", todos.to_json()),
+        };
+        if let Some(output_path) = cli.output.clone() {
+            if let Err(e) = std::fs::write(&output_path, &document) {
+                fail(FailureKind::Error, format!("Error writing output: {}", e));
             }
+            eprintln!("Output written to: {}", output_path.display());
+        } else {
+            print!("{}", document);
         }
+        eprintln!("TODOs: {} markers", todos.len());
         return;
     }
 
-    // Token budgeting mode (v0.7.0)
-    if let Some(budget_str) = &cli.token_budget {
-        // Parse budget
-        let budget = match parse_token_budget(budget_str) {
+    // Unknowns mode: where the parsers' blind spots are
+    if let Some(format) = cli.unknowns {
+        let entries = pm_encoder::walk_project(project_root.to_str().unwrap(), &config)
+        .unwrap_or_else(|e| {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        });
+        let pairs: Vec<(String, String)> = entries.into_iter().map(|e| (e.path, e.content)).collect();
+        let report = pm_encoder::core::UnknownsReport::build(&pairs);
+        let document = match format {
+            UnknownsFormat::Text => report.to_text(),
+            UnknownsFormat::Json => format!("{}\n", report.to_json()),
+        };
+        if let Some(output_path) = cli.output.clone() {
+            if let Err(e) = std::fs::write(&output_path, &document) {
+                fail(FailureKind::Error, format!("Error writing output: {}", e));
+            }
+            eprintln!("Output written to: {}", output_path.display());
+        } else {
+            print!("{}", document);
+        }
+        return;
+    }
+
+    // Sampling mode: a seeded, representative slice within a token budget
+    if let Some(sample_str) = &cli.sample {
+        let budget = match parse_token_budget(sample_str) {
             Ok(b) => b,
             Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+                fail(FailureKind::Error, format!("Error: {}", e));
+            }
+        };
+        let entries = pm_encoder::walk_project(project_root.to_str().unwrap(), &config)
+        .unwrap_or_else(|e| {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        });
+        let pairs: Vec<(String, String)> = entries.iter().map(|e| (e.path.clone(), e.content.clone())).collect();
+        let sample = pm_encoder::core::sample::sample(&pairs, budget, cli.seed);
+
+        let manifest = sample.to_markdown();
+        let mut selected = vec![pm_encoder::FileEntry {
+            path: pm_encoder::core::SAMPLE_FILE_NAME.to_string(),
+            md5: pm_encoder::calculate_md5(&manifest),
+            size: manifest.len() as u64,
+            content: manifest,
+            mtime: 0,
+            ctime: 0,
+        }];
+        for file in &sample.files {
+            if let Some(entry) = entries.iter().find(|e| e.path == file.path) {
+                selected.push(entry.clone());
+            }
+        }
+
+        let mut output = String::new();
+        for entry in &selected {
+            let (truncate_lines, truncate_mode) = pm_encoder::truncation_for(entry, &config);
+            output.push_str(&pm_encoder::serialize_file_with_format(
+                entry,
+                truncate_lines,
+                truncate_mode,
+                config.output_format,
+            ));
+        }
+        if let Some(output_path) = cli.output.clone() {
+            if let Err(e) = std::fs::write(&output_path, &output) {
+                fail(FailureKind::Error, format!("Error writing output: {}", e));
+            }
+            eprintln!("Output written to: {}", output_path.display());
+        } else {
+            print!("{}", output);
+        }
+        if let Some(bundle_path) = &cli.bundle {
+            let files = selected.iter().map(|e| BundleFile::new(e.path.clone(), &e.content)).collect();
+            write_bundle(bundle_path, cli.lens.as_deref(), bundle_index_options(cli.bundle_index, cli.no_blob_cache, cli.snapshot), &project_root, &config, &output, files, Some(cli.seed));
+        }
+        eprintln!("Sampled {} of {} files (~{} tokens, seed {})", sample.files.len(), sample.total_files, sample.used_tokens(), sample.seed);
+        return;
+    }
+
+    // Token budgeting mode (v0.7.0); --preview stops after the budget decisions
+    if cli.token_budget.is_some() || cli.preview {
+        // Parse budget (a preview without one drops nothing)
+        let budget = match cli.token_budget.as_deref().map(parse_token_budget) {
+            None => usize::MAX,
+            Some(Ok(b)) => b,
+            Some(Err(e)) => {
+                fail(FailureKind::Error, format!("Error: {}", e));
             }
         };
 
         // Store token budget in config for metadata injection (v2.0.0)
-        config.token_budget = Some(budget);
+        config.token_budget = cli.token_budget.is_some().then_some(budget);
 
         // Budgeting requires batch mode
         if cli.stream {
@@ -2957,6 +4464,7 @@ pub fn run() {
 
         // Get lens manager for priority resolution
         let mut lens_manager = LensManager::new();
+        let unfiltered_config = config.clone();
 
         // Apply CLI lens if present (for priority groups)
         if let Some(lens_name) = &cli.lens {
@@ -2973,26 +4481,37 @@ pub fn run() {
                     eprintln!("[LENS: {}] Priority groups active", lens_name);
                 }
                 Err(e) => {
-                    eprintln!("Error: {}", e);
-                    std::process::exit(1);
+                    fail(FailureKind::Error, format!("Error: {}", e));
                 }
             }
         }
 
         // Walk directory and collect files
-        let entries = match pm_encoder::walk_directory(
-            project_root.to_str().unwrap(),
-            &config.ignore_patterns,
-            &config.include_patterns,
-            config.max_file_size,
-        ) {
+        let mut entries = match pm_encoder::walk_project(project_root.to_str().unwrap(), &config) {
             Ok(e) => e,
             Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+                fail(FailureKind::Error, format!("Error: {}", e));
             }
         };
 
+        // An empty walk under a lens: was it the lens?
+        if let (true, Some(lens_name)) = (entries.is_empty(), &cli.lens) {
+            let files = pm_encoder::walk_project(project_root.to_str().unwrap(), &unfiltered_config)
+                .map_or(0, |e| e.len());
+            if let Some(warning) = pm_encoder::core::preflight::check_lens(lens_name, files, 0) {
+                eprintln!("Warning: {}", warning);
+                config.warnings.push(warning);
+            }
+        }
+        for warning in pm_encoder::preflight(&config, &unfiltered_config.include_patterns, &entries) {
+            eprintln!("Warning: {}", warning);
+            config.warnings.push(warning);
+        }
+        let matched_nothing = no_files_matched(&entries, cli.lens.as_deref(), &unfiltered_config.include_patterns, &cli.exclude);
+
+        // Rewrite the entries first, so the budget sees what will be serialized
+        pm_encoder::prepare_entries(&mut entries, &config, &pm_encoder::core::ExtractiveSummarizer::new());
+
         // Convert to (path, content) tuples
         let files: Vec<(String, String)> = entries
             .into_iter()
@@ -3004,14 +4523,49 @@ pub fn run() {
             BudgetStrategy::Drop => "drop",
             BudgetStrategy::Truncate => "truncate",
             BudgetStrategy::Hybrid => "hybrid",
+            BudgetStrategy::Summarize => "summarize",
         };
-        let (selected, report) = apply_token_budget(files, budget, &lens_manager, strategy_str);
-
-        // Print budget report to stderr
-        report.print_report();
+        let mut prioritizers = PrioritizerRegistry::new();
+        if let Some(hints) = &focus_hints {
+            prioritizers.register(Box::new(FocusPrioritizer::new(hints)));
+        }
+        if !cli.junit.is_empty() {
+            use voyager_ast::AstProvider;
+            let history = load_ci_history(&cli.junit);
+            match voyager_ast::TreeSitterProvider::new().index_project(&project_root, &cached_index_options(cli.no_blob_cache, cli.snapshot)) {
+                Ok(model) => {
+                    warn_changed_during_scan(&model);
+                    let flaky = pm_encoder::core::FlakyReport::from_model(&model, &history);
+                    eprintln!("Flaky areas: {} symbols from {} flaky tests", flaky.areas.len(), flaky.tests.len());
+                    prioritizers.register(Box::new(pm_encoder::core::FlakyPrioritizer::new(&flaky)));
+                }
+                Err(e) => eprintln!("Warning: Could not index for flaky areas: {}", e),
+            }
+        }
+        if let Ok(bookmarks) = pm_encoder::core::BookmarkStore::load(&pm_encoder::core::BookmarkStore::default_path(&project_root)) {
+            // Bookmarked entry points stay in the context under every lens
+            if !bookmarks.is_empty() {
+                let resolver = pm_encoder::core::SymbolResolver::new().with_ignore(config.ignore_patterns.clone());
+                let resolved = bookmarks.resolve_all(&project_root, &resolver);
+                prioritizers.register(Box::new(pm_encoder::core::BookmarkPrioritizer::new(&resolved)));
+            }
+        }
+        if cli.osv_db.is_some() || cli.osv_api.is_some() {
+            let advisories = load_advisories(cli.osv_db.as_deref(), cli.osv_api.as_deref(), &files);
+            eprint!("{}", advisories.render_text());
+            // The security lens looks at code using vulnerable dependencies first
+            if cli.lens.as_deref() == Some("security") {
+                prioritizers.register(Box::new(pm_encoder::core::AdvisoryPrioritizer::new(&advisories)));
+            }
+        }
+        let needed: usize = files
+            .iter()
+            .map(|(path, content)| pm_encoder::TokenEstimator::estimate_file_tokens(std::path::Path::new(path), content))
+            .sum();
+        let (selected, mut report) = apply_token_budget_with(files, budget, &lens_manager, strategy_str, &prioritizers);
 
         // Build file entries for serialization
-        let entries: Vec<pm_encoder::FileEntry> = selected
+        let mut entries: Vec<pm_encoder::FileEntry> = selected
             .iter()
             .map(|(path, content)| pm_encoder::FileEntry {
                 path: path.clone(),
@@ -3022,6 +4576,17 @@ pub fn run() {
                 ctime: 0,
             })
             .collect();
+        if cli.preview {
+            pm_encoder::record_serialize_truncation(&mut report, &entries, &config);
+            print!("{}", report.render_decisions());
+            return;
+        }
+
+        // Print budget report to stderr
+        report.print_report();
+        pm_encoder::prepend_tour(&mut entries, &config);
+        pm_encoder::prepend_table_of_contents(&mut entries, &config);
+        pm_encoder::prepend_config_warnings(&mut entries, &config.warnings, config.output_format);
 
         // Serialize selected files with configured format and truncation
         let output = if config.output_format == OutputFormat::ClaudeXml {
@@ -3029,40 +4594,57 @@ pub fn run() {
             // This includes hotspots/coldspots in attention_map from BudgetReport
             pm_encoder::serialize_entries_claude_xml_with_report(&config, &entries, &report)
                 .unwrap_or_else(|e| {
-                    eprintln!("Error serializing XML: {}", e);
-                    std::process::exit(1);
+                    fail(FailureKind::Error, format!("Error serializing XML: {}", e));
                 })
         } else {
             // Use standard serialization for other formats
             let mut output = String::new();
             for entry in &entries {
+                let (truncate_lines, truncate_mode) = pm_encoder::truncation_for(entry, &config);
                 output.push_str(&pm_encoder::serialize_file_with_format(
                     entry,
-                    config.truncate_lines,
-                    &config.truncate_mode,
+                    truncate_lines,
+                    truncate_mode,
                     config.output_format,
                 ));
             }
             output
         };
+        let output = pm_encoder::append_affordance_index(output, &config, &entries);
+
+        // A hard budget is a limit, not a target: nothing is written when it does not fit
+        let used = needed.max(pm_encoder::TokenEstimator::estimate_tokens(&output));
+        if cli.hard_budget && used > budget {
+            let error = pm_encoder::core::EncoderError::BudgetExceeded { used, budget };
+            fail_with_hint(error.failure_kind(), format!("Error: {}", error), "Raise --token-budget, narrow the selection, or drop --hard-budget");
+        }
 
         // Write output
         if let Some(output_path) = cli.output.clone() {
             match std::fs::write(&output_path, &output) {
                 Ok(_) => eprintln!("Output written to: {}", output_path.display()),
                 Err(e) => {
-                    eprintln!("Error writing output: {}", e);
-                    std::process::exit(1);
+                    fail(FailureKind::Error, format!("Error writing output: {}", e));
                 }
             }
         } else {
             print!("{}", output);
         }
 
+        if let Some(bundle_path) = &cli.bundle {
+            let files = entries.iter()
+                .map(|e| BundleFile::new(e.path.clone(), &e.content))
+                .collect();
+            write_bundle(bundle_path, cli.lens.as_deref(), bundle_index_options(cli.bundle_index, cli.no_blob_cache, cli.snapshot), &project_root, &config, &output, files, None);
+        }
+
         // Print Context Health if requested
         if cli.health {
             print_context_health(&output, entries.len());
         }
+        if cli.self_check {
+            eprint!("{}", pm_encoder::core::selfcheck::check_context(&output).render_text());
+        }
 
         // Print Voyager Mission Log (to stderr)
         let project_name = project_root.file_name()
@@ -3077,16 +4659,65 @@ pub fn run() {
             token_budget_parsed,
             entries.len(),
         );
+        if let Some(message) = matched_nothing {
+            fail(FailureKind::NoFilesMatched, message);
+        }
         return;
     }
 
-    // Serialize the project (non-budgeted mode)
-    match pm_encoder::serialize_project_with_config(project_root.to_str().unwrap(), &config) {
-        Ok(output) => {
-            // In streaming mode, output was already written directly to stdout
-            if cli.stream {
-                // Nothing more to do - streaming already wrote to stdout
-                return;
+    // Streaming to a file: write file by file instead of building the context in memory
+    if let (true, Some(output_path)) = (cli.stream, &cli.output) {
+        let written = std::fs::File::create(output_path)
+            .map_err(|e| e.to_string())
+            .and_then(|file| pm_encoder::serialize_project_to(project_root.to_str().unwrap(), &config, file));
+        match written {
+            Ok((bytes, warnings)) => {
+                for warning in &warnings {
+                    eprintln!("Warning: {}", warning);
+                }
+                eprintln!("Output written to: {} ({} bytes)", output_path.display(), bytes);
+            }
+            Err(e) => {
+                fail(FailureKind::Error, format!("Error writing output: {}", e));
+            }
+        }
+        return;
+    }
+
+    // Streaming to stdout writes each file as it is found, before a table of contents could be
+    if cli.stream && cli.toc && config.git_view.is_none() {
+        fail_with_hint(
+            FailureKind::Usage,
+            "Error: --toc cannot be combined with --stream to stdout",
+            "Add -o FILE to stream into a file with a table of contents, or drop --stream",
+        );
+    }
+
+    // Streaming to stdout: each file is written as it is found
+    // (git views are read through the index in batch mode)
+    if cli.stream && config.git_view.is_none() {
+        if let Err(e) = pm_encoder::serialize_project_streaming(project_root.to_str().unwrap(), &config) {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        }
+        return;
+    }
+
+    // Serialize the project (non-budgeted mode); the one walk also feeds the bundle
+    let entries = match pm_encoder::walk_project(project_root.to_str().unwrap(), &config) {
+        Ok(e) => e,
+        Err(e) => {
+            fail(FailureKind::Error, format!("Error: {}", e));
+        }
+    };
+    let matched_nothing = no_files_matched(&entries, cli.lens.as_deref(), &config.include_patterns, &cli.exclude);
+    let bundle_files: Vec<BundleFile> = match cli.bundle {
+        Some(_) => entries.iter().map(|e| BundleFile::new(e.path.clone(), &e.content)).collect(),
+        None => Vec::new(),
+    };
+    match pm_encoder::serialize_walked_entries(entries, &config, &pm_encoder::core::ExtractiveSummarizer::new()) {
+        Ok((output, warnings)) => {
+            for warning in &warnings {
+                eprintln!("Warning: {}", warning);
             }
 
             // Batch mode: write to file or stdout
@@ -3096,20 +4727,39 @@ pub fn run() {
                         eprintln!("Output written to: {}", output_path.display());
                     }
                     Err(e) => {
-                        eprintln!("Error writing output: {}", e);
-                        std::process::exit(1);
+                        fail(FailureKind::Error, format!("Error writing output: {}", e));
                     }
                 }
             } else {
                 print!("{}", output);
             }
 
+            if let Some(bundle_path) = &cli.bundle {
+                write_bundle(bundle_path, cli.lens.as_deref(), bundle_index_options(cli.bundle_index, cli.no_blob_cache, cli.snapshot), &project_root, &config, &output, bundle_files, None);
+            }
+
             // Print Context Health if requested
             if cli.health {
                 // Count files in output (each file starts with "++++++++++ ")
                 let file_count = output.matches("++++++++++ ").count();
                 print_context_health(&output, file_count);
             }
+            if cli.self_check {
+                eprint!("{}", pm_encoder::core::selfcheck::check_context(&output).render_text());
+            }
+            if cli.osv_db.is_some() || cli.osv_api.is_some() {
+                let files: Vec<(String, String)> = pm_encoder::walk_directory(
+                    project_root.to_str().unwrap(),
+                    &config.ignore_patterns,
+                    &[],
+                    config.max_file_size,
+                )
+                .unwrap_or_default()
+                .into_iter()
+                .map(|e| (e.path, e.content))
+                .collect();
+                eprint!("{}", load_advisories(cli.osv_db.as_deref(), cli.osv_api.as_deref(), &files).render_text());
+            }
 
             // Print Voyager Mission Log (to stderr)
             let project_name = project_root.file_name()
@@ -3125,14 +4775,89 @@ pub fn run() {
                 token_budget_parsed,
                 file_count,
             );
+            if let Some(message) = matched_nothing {
+                fail(FailureKind::NoFilesMatched, message);
+            }
         }
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            fail(FailureKind::Error, format!("Error: {}", e));
         }
     }
 }
 
+/// The `NoFilesMatched` failure message when filters left no `entries`
+///
+/// An empty project without include, exclude or lens filters is not a
+/// filter mistake, so it gets `None`.
+fn no_files_matched(
+    entries: &[pm_encoder::FileEntry],
+    lens: Option<&str>,
+    include_patterns: &[String],
+    exclude_patterns: &[String],
+) -> Option<String> {
+    if !entries.is_empty() {
+        return None;
+    }
+    let quoted = |patterns: &[String]| patterns.iter().map(|p| format!("'{}'", p)).collect::<Vec<_>>();
+    let mut filters = quoted(include_patterns);
+    if let Some(lens) = lens {
+        filters.push(format!("lens '{}'", lens));
+    }
+    if !filters.is_empty() {
+        Some(format!("Error: no files matched {}", filters.join(", ")))
+    } else if !exclude_patterns.is_empty() {
+        Some(format!("Error: no files left after excluding {}", quoted(exclude_patterns).join(", ")))
+    } else {
+        None
+    }
+}
+
+/// Print a fatal error (as JSON with --json-errors) and exit with the kind's code
+fn fail(kind: FailureKind, message: impl std::fmt::Display) -> ! {
+    report_failure(ErrorReport::new(kind, message.to_string()))
+}
+
+/// `fail` with a follow-up line suggesting what to try instead
+fn fail_with_hint(kind: FailureKind, message: impl std::fmt::Display, hint: &str) -> ! {
+    report_failure(ErrorReport::new(kind, message.to_string()).with_hint(hint))
+}
+
+fn report_failure(mut report: ErrorReport) -> ! {
+    if JSON_ERRORS.load(Ordering::Relaxed) {
+        if let Some(message) = report.message.strip_prefix("Error: ") {
+            report.message = message.to_string();
+        }
+        eprintln!("{}", report.to_json());
+    } else {
+        eprintln!("{}", report.message);
+        if let Some(hint) = &report.hint {
+            eprintln!("{}", hint);
+        }
+    }
+    std::process::exit(report.exit_code)
+}
+
+/// The target saved under a `bookmark=NAME` zoom target, or `target` itself
+fn expand_bookmark(project_root: &std::path::Path, target: &str) -> String {
+    use pm_encoder::core::bookmarks::{expand_target, BOOKMARK_PREFIX};
+    use pm_encoder::core::BookmarkStore;
+
+    expand_target(project_root, target).unwrap_or_else(|e| {
+        if let Some(name) = target.strip_prefix(BOOKMARK_PREFIX) {
+            let names = BookmarkStore::load(&BookmarkStore::default_path(project_root)).map(|s| s.names()).unwrap_or_default();
+            warn_zoom_near_miss(target, name, &names);
+        }
+        fail_with_hint(e.failure_kind(), format!("Error: {}", e), "Use --bookmark list to see saved bookmarks");
+    })
+}
+
+/// Print a near-miss suggestion for a zoom target that was not found
+fn warn_zoom_near_miss(target: &str, name: &str, candidates: &[String]) {
+    if let Some(warning) = pm_encoder::core::preflight::check_zoom_target(target, name, candidates) {
+        eprintln!("Warning: {}", warning);
+    }
+}
+
 /// Binary entry point - delegates to run().
 #[allow(dead_code)]  // Used as entry point for vo binary, but appears unused when included as module
 fn main() {
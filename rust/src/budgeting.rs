@@ -10,11 +10,18 @@
 //! 2. Config (Cargo.toml, package.json) - High value/token ratio
 //! 3. Tests (tests/, examples/) - If budget remains
 //! 4. Other (docs, scripts) - Lowest priority
+//!
+//! Within a tier, files are ordered by lens priority plus the scores of any
+//! registered [`FilePrioritizer`]s (see [`apply_token_budget_with`]).
 
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::path::Path;
 use crate::lenses::LensManager;
 use crate::truncate_structure;
-use crate::core::engine::FileTier;
+use crate::core::engine::{detect_language, FileTier};
+use crate::core::lod::{self, Lod};
 
 /// Threshold for hybrid strategy: files > 10% of budget get auto-truncated
 const HYBRID_THRESHOLD: f64 = 0.10;
@@ -91,6 +98,116 @@ pub fn parse_token_budget(value: &str) -> Result<usize, String> {
     Ok(number * multiplier)
 }
 
+/// What a [`FilePrioritizer`] knows about a file
+#[derive(Debug, Clone, Copy)]
+pub struct PriorityInput<'a> {
+    /// Relative path
+    pub path: &'a str,
+    /// Budget tier (Core, Config, Tests, Other)
+    pub tier: FileTier,
+    /// Language detected from the extension ("rust", "python", ...)
+    pub language: &'a str,
+    /// Priority from the lens groups
+    pub lens_priority: i32,
+    /// Estimated tokens (after group truncation)
+    pub tokens: usize,
+    /// Line count
+    pub lines: usize,
+}
+
+/// Custom file prioritization for token budgeting
+///
+/// Scores are added to the lens priority, so positive values move a file
+/// ahead of its tier peers and negative values behind them. Tiers themselves
+/// keep their order. Typical uses: boost files named in the current ticket,
+/// demote generated code.
+pub trait FilePrioritizer: Send + Sync {
+    /// Short name for reports and debugging
+    fn name(&self) -> &str;
+
+    /// Priority adjustment for a file
+    fn score(&self, file: &PriorityInput<'_>) -> i32;
+}
+
+/// Registry of prioritizers consulted by [`apply_token_budget_with`]
+#[derive(Default)]
+pub struct PrioritizerRegistry {
+    prioritizers: Vec<Box<dyn FilePrioritizer>>,
+}
+
+impl PrioritizerRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a prioritizer
+    pub fn register(&mut self, prioritizer: Box<dyn FilePrioritizer>) {
+        self.prioritizers.push(prioritizer);
+    }
+
+    /// Get all registered prioritizers
+    pub fn prioritizers(&self) -> &[Box<dyn FilePrioritizer>] {
+        &self.prioritizers
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prioritizers.is_empty()
+    }
+
+    /// Sum of all prioritizer scores for a file
+    pub fn score(&self, file: &PriorityInput<'_>) -> i32 {
+        self.prioritizers
+            .iter()
+            .fold(0i32, |total, p| total.saturating_add(p.score(file)))
+    }
+}
+
+impl std::fmt::Debug for PrioritizerRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: Vec<&str> = self.prioritizers.iter().map(|p| p.name()).collect();
+        f.debug_struct("PrioritizerRegistry").field("prioritizers", &names).finish()
+    }
+}
+
+/// Boosts files by path: exact matches, or everything under a directory
+#[derive(Debug, Clone)]
+pub struct PathBoost {
+    paths: Vec<String>,
+    boost: i32,
+}
+
+impl PathBoost {
+    pub fn new(paths: impl IntoIterator<Item = String>, boost: i32) -> Self {
+        let paths = paths
+            .into_iter()
+            .map(|p| p.replace('\\', "/").trim_start_matches("./").trim_end_matches('/').to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        Self { paths, boost }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        self.paths.iter().any(|p| {
+            path == p || (path.starts_with(p.as_str()) && path.as_bytes().get(p.len()) == Some(&b'/'))
+        })
+    }
+}
+
+impl FilePrioritizer for PathBoost {
+    fn name(&self) -> &str {
+        "path_boost"
+    }
+
+    fn score(&self, file: &PriorityInput<'_>) -> i32 {
+        if self.matches(file.path) {
+            self.boost
+        } else {
+            0
+        }
+    }
+}
+
 /// File data for budget selection
 #[derive(Debug, Clone)]
 pub struct FileData {
@@ -98,13 +215,13 @@ pub struct FileData {
     pub path: String,
     /// File content
     pub content: String,
-    /// Priority from lens config
+    /// Priority from lens config plus prioritizer scores
     pub priority: i32,
     /// Estimated token count
     pub tokens: usize,
     /// Original token count (before any truncation)
     pub original_tokens: usize,
-    /// Inclusion method: "full" or "truncated"
+    /// Inclusion method: "full", "truncated" or "summarized"
     pub method: String,
 }
 
@@ -129,6 +246,8 @@ pub struct BudgetReport {
     pub included_files: Vec<(String, i32, usize, String)>,
     /// Count of auto-truncated files
     pub truncated_count: usize,
+    /// Why each truncated, summarized or dropped file got its decision, by path
+    pub reasons: HashMap<String, String>,
 }
 
 impl BudgetReport {
@@ -162,8 +281,16 @@ impl BudgetReport {
         let full_count = self.included_files.iter()
             .filter(|(_, _, _, m)| m == "full")
             .count();
-        eprintln!("Files included: {} ({} full, {} truncated)",
-            self.selected_count, full_count, self.truncated_count);
+        let summarized_count = self.included_files.iter()
+            .filter(|(_, _, _, m)| m == "summarized")
+            .count();
+        if summarized_count > 0 {
+            eprintln!("Files included: {} ({} full, {} truncated, {} summarized)",
+                self.selected_count, full_count, self.truncated_count, summarized_count);
+        } else {
+            eprintln!("Files included: {} ({} full, {} truncated)",
+                self.selected_count, full_count, self.truncated_count);
+        }
         eprintln!("Files dropped:  {} (lowest priority first)", self.dropped_count);
 
         if self.truncated_count > 0 {
@@ -195,6 +322,68 @@ impl BudgetReport {
 
         eprintln!("{}", "=".repeat(70));
     }
+
+    /// Every file's decision (full, truncated, summarized, dropped) as a table
+    ///
+    /// Used by `--preview`, which stops after budgeting: included files in
+    /// context order, then dropped files. A budget of `usize::MAX` is shown
+    /// as unlimited.
+    pub fn render_decisions(&self) -> String {
+        let count = |method: &str| self.included_files.iter().filter(|(_, _, _, m)| m == method).count();
+        let mut out = format!(
+            "Budget preview: {} files ({} full, {} truncated, {} summarized, {} dropped)\n",
+            self.selected_count + self.dropped_count,
+            count("full"),
+            count("truncated"),
+            count("summarized"),
+            self.dropped_count,
+        );
+        out.push_str(&format!("Strategy: {}\n", self.strategy));
+        if self.budget == usize::MAX {
+            out.push_str(&format!("Estimated tokens: {} (no budget)\n\n", format_number(self.used)));
+        } else {
+            out.push_str(&format!(
+                "Estimated tokens: {} / {} budget\n\n",
+                format_number(self.used),
+                format_number(self.budget)
+            ));
+        }
+
+        let decisions: Vec<(&str, i32, usize, &str)> = self
+            .included_files
+            .iter()
+            .map(|(path, priority, tokens, method)| (path.as_str(), *priority, *tokens, method.as_str()))
+            .chain(self.dropped_files.iter().map(|(path, priority, tokens)| (path.as_str(), *priority, *tokens, "dropped")))
+            .collect();
+        out.push_str(&format!("{:<10} {:>8} {:>8}  PATH (REASON)\n", "ACTION", "TOKENS", "PRIORITY"));
+        for (path, priority, tokens, action) in decisions {
+            out.push_str(&format!("{:<10} {:>8} {:>8}  {}", action, tokens, priority, path));
+            if let Some(reason) = self.reasons.get(path) {
+                out.push_str(&format!(" ({})", reason));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Record that serialization cuts the included `path` down to `tokens`
+    ///
+    /// `--truncate` and per-language rules apply after budgeting, so
+    /// `--preview` records them here to show what will actually be written.
+    pub fn record_truncation(&mut self, path: &str, tokens: usize, reason: String) {
+        let Some(file) = self.included_files.iter_mut().find(|(p, _, _, _)| p == path) else { return };
+        self.used = self.used.saturating_sub(file.2) + tokens;
+        file.2 = tokens;
+        if file.3 == "full" {
+            file.3 = "truncated".to_string();
+            self.truncated_count += 1;
+        }
+        let reason = match self.reasons.remove(path) {
+            Some(earlier) => format!("{}, then {}", earlier, reason),
+            None => reason,
+        };
+        self.reasons.insert(path.to_string(), reason);
+    }
 }
 
 /// Format a number with thousand separators
@@ -217,6 +406,43 @@ fn try_truncate_to_structure(path: &str, content: &str) -> (String, bool) {
     truncate_structure(content, path)
 }
 
+/// Total order of files competing for a budget: tier (Core first), then
+/// priority (highest first), then path
+///
+/// Paths are classified and compared `/`-separated, so walks of the same
+/// tree on Windows and Unix order identically; the raw path settles the
+/// remaining ties.
+pub fn selection_order(a: (&str, i32), b: (&str, i32)) -> Ordering {
+    let (a_path, b_path) = (portable_path(a.0), portable_path(b.0));
+    let tier = |path: &str| FileTier::classify(path, None) as u8;
+    tier(&a_path)
+        .cmp(&tier(&b_path))
+        .then_with(|| b.1.cmp(&a.1))
+        .then_with(|| a_path.cmp(&b_path))
+        .then_with(|| a.0.cmp(b.0))
+}
+
+/// Path with `/` separators
+fn portable_path(path: &str) -> Cow<'_, str> {
+    if path.contains('\\') {
+        Cow::Owned(path.replace('\\', "/"))
+    } else {
+        Cow::Borrowed(path)
+    }
+}
+
+/// Render a file at the most detailed outline level that fits in `remaining` tokens
+///
+/// Tries signatures with doc comments (LOD2), then the declaration outline
+/// (LOD1). Returns the rendering and its tokens, or `None` if neither fits.
+fn summarize(path: &str, content: &str, remaining: usize) -> Option<(String, usize)> {
+    [Lod::Signatures, Lod::Declarations].into_iter().find_map(|level| {
+        let summary = lod::render(path, content, level);
+        let tokens = TokenEstimator::estimate_file_tokens(Path::new(path), &summary);
+        (tokens <= remaining).then_some((summary, tokens))
+    })
+}
+
 /// Apply token budget to select files based on priority
 ///
 /// # Arguments
@@ -224,13 +450,16 @@ fn try_truncate_to_structure(path: &str, content: &str) -> (String, bool) {
 /// * `files` - List of (path, content) tuples
 /// * `budget` - Maximum tokens allowed
 /// * `lens_manager` - LensManager for priority resolution
-/// * `strategy` - Budget strategy: "drop", "truncate", or "hybrid"
+/// * `strategy` - Budget strategy: "drop", "truncate", "hybrid" or "summarize"
 ///
 /// # Strategies
 ///
 /// * `drop` - Exclude files that don't fit (default)
 /// * `truncate` - Force structure mode on files that don't fit
 /// * `hybrid` - Auto-truncate files consuming >10% of budget, then apply truncate logic
+/// * `summarize` - Render files that don't fit as signatures and doc comments
+///   (LOD2), or as their declaration outline (LOD1) if that is still too large,
+///   so every file stays visible in some form
 ///
 /// # Returns
 ///
@@ -240,8 +469,23 @@ pub fn apply_token_budget(
     budget: usize,
     lens_manager: &LensManager,
     strategy: &str,
+) -> (Vec<(String, String)>, BudgetReport) {
+    apply_token_budget_with(files, budget, lens_manager, strategy, &PrioritizerRegistry::new())
+}
+
+/// Apply token budget with custom prioritizers
+///
+/// Like [`apply_token_budget`], but each file's lens priority is adjusted by
+/// the scores of `prioritizers` before files are ordered within their tier.
+pub fn apply_token_budget_with(
+    files: Vec<(String, String)>,
+    budget: usize,
+    lens_manager: &LensManager,
+    strategy: &str,
+    prioritizers: &PrioritizerRegistry,
 ) -> (Vec<(String, String)>, BudgetReport) {
     // Step 1: Calculate tokens and get priorities, applying group-based truncation
+    let mut reasons: HashMap<String, String> = HashMap::new();
     let mut file_data: Vec<FileData> = files.into_iter()
         .map(|(path, content)| {
             let path_obj = Path::new(&path);
@@ -255,6 +499,7 @@ pub fn apply_token_budget(
                 if mode == "structure" {
                     let (truncated, was_truncated) = try_truncate_to_structure(&path, &content);
                     if was_truncated {
+                        reasons.insert(path.clone(), "lens group truncates to structure".to_string());
                         (truncated, "truncated".to_string())
                     } else {
                         (content, "full".to_string())
@@ -268,10 +513,25 @@ pub fn apply_token_budget(
 
             let tokens = TokenEstimator::estimate_file_tokens(path_obj, &final_content);
 
+            let priority = if prioritizers.is_empty() {
+                group_config.priority
+            } else {
+                let language = detect_language(&path);
+                let input = PriorityInput {
+                    path: &path,
+                    tier: FileTier::classify(&portable_path(&path), None),
+                    language: &language,
+                    lens_priority: group_config.priority,
+                    tokens,
+                    lines: final_content.lines().count(),
+                };
+                group_config.priority.saturating_add(prioritizers.score(&input))
+            };
+
             FileData {
                 path,
                 content: final_content,
-                priority: group_config.priority,
+                priority,
                 tokens,
                 original_tokens,
                 method,
@@ -280,21 +540,10 @@ pub fn apply_token_budget(
         .collect();
 
     // Step 2: Sort by tier (ASC), then priority (DESC), then path (ASC) for determinism
-    // Tiered allocation ensures Core files get budget before Config, Tests, Other
+    // Tiered allocation ensures Core files get budget before Config, Tests, Other.
+    // Content settles duplicate paths, so the input order never matters.
     file_data.sort_by(|a, b| {
-        let tier_a = FileTier::classify(&a.path, None) as u8;
-        let tier_b = FileTier::classify(&b.path, None) as u8;
-
-        match tier_a.cmp(&tier_b) {
-            std::cmp::Ordering::Equal => {
-                // Within same tier, sort by priority (highest first)
-                match b.priority.cmp(&a.priority) {
-                    std::cmp::Ordering::Equal => a.path.cmp(&b.path),
-                    other => other,
-                }
-            }
-            other => other,
-        }
+        selection_order((&a.path, a.priority), (&b.path, b.priority)).then_with(|| a.content.cmp(&b.content))
     });
 
     // Step 3: For hybrid strategy, pre-truncate large files (>10% of budget)
@@ -309,6 +558,7 @@ pub fn apply_token_budget(
                     fd.content = truncated_content;
                     fd.tokens = new_tokens;
                     fd.method = "truncated".to_string();
+                    reasons.insert(fd.path.clone(), "over 10% of the budget, truncated to structure".to_string());
                 }
             }
         }
@@ -341,6 +591,7 @@ pub fn apply_token_budget(
                     if total_tokens + new_tokens <= budget {
                         // Truncated version fits!
                         truncated_count += 1;
+                        reasons.insert(fd.path.clone(), "truncated to structure to fit the budget".to_string());
                         included_files.push((fd.path.clone(), fd.priority, new_tokens, "truncated".to_string()));
                         selected.push((fd.path, truncated_content));
                         total_tokens += new_tokens;
@@ -348,7 +599,22 @@ pub fn apply_token_budget(
                     }
                 }
             }
+            if strategy == "summarize" {
+                if let Some((summary, tokens)) = summarize(&fd.path, &fd.content, budget - total_tokens) {
+                    reasons.insert(fd.path.clone(), "summarized to fit the remaining budget".to_string());
+                    included_files.push((fd.path.clone(), fd.priority, tokens, "summarized".to_string()));
+                    selected.push((fd.path, summary));
+                    total_tokens += tokens;
+                    continue;
+                }
+            }
             // File still doesn't fit after truncation attempt (or drop strategy)
+            let reason = if fd.tokens > budget {
+                "budget: larger than the whole budget".to_string()
+            } else {
+                format!("priority cut: {} tokens left after higher-priority files", format_number(budget - total_tokens))
+            };
+            reasons.insert(fd.path.clone(), reason);
             dropped.push((fd.path, fd.priority, fd.original_tokens));
         }
     }
@@ -364,6 +630,7 @@ pub fn apply_token_budget(
         strategy: strategy.to_string(),
         included_files,
         truncated_count,
+        reasons,
     };
 
     (selected, report)
@@ -424,6 +691,7 @@ mod tests {
             strategy: "drop".to_string(),
             included_files: vec![],
             truncated_count: 0,
+            reasons: HashMap::new(),
         };
         assert!((report.used_percentage() - 50.0).abs() < 0.1);
         assert_eq!(report.remaining(), 500);
@@ -564,6 +832,7 @@ mod tests {
             strategy: "drop".to_string(),
             included_files: vec![],
             truncated_count: 0,
+            reasons: HashMap::new(),
         };
         // Remaining should be 0 when over budget, not negative
         assert_eq!(report.remaining(), 0);
@@ -581,6 +850,7 @@ mod tests {
             strategy: "drop".to_string(),
             included_files: vec![],
             truncated_count: 0,
+            reasons: HashMap::new(),
         };
         // Should handle zero budget gracefully
         assert_eq!(report.used_percentage(), 0.0);
@@ -603,11 +873,52 @@ mod tests {
                 ("file3.py".to_string(), 60, 300, "full".to_string()),
             ],
             truncated_count: 1,
+            reasons: HashMap::new(),
         };
         // Just verify print_report doesn't panic
         report.print_report();
     }
 
+    #[test]
+    fn test_budget_report_renders_every_decision() {
+        let report = BudgetReport {
+            budget: 1000,
+            used: 500,
+            selected_count: 2,
+            dropped_count: 1,
+            dropped_files: vec![("big.md".to_string(), 10, 900)],
+            estimation_method: "Heuristic".to_string(),
+            strategy: "hybrid".to_string(),
+            included_files: vec![
+                ("main.py".to_string(), 100, 300, "full".to_string()),
+                ("util.py".to_string(), 80, 200, "truncated".to_string()),
+            ],
+            truncated_count: 1,
+            reasons: HashMap::from([
+                ("util.py".to_string(), "truncated to structure to fit the budget".to_string()),
+                ("big.md".to_string(), "priority cut: 500 tokens left after higher-priority files".to_string()),
+            ]),
+        };
+        let text = report.render_decisions();
+        assert!(text.starts_with("Budget preview: 3 files (1 full, 1 truncated, 0 summarized, 1 dropped)\nStrategy: hybrid\n"));
+        assert!(text.contains("Estimated tokens: 500 / 1,000 budget"));
+        let rows: Vec<&str> = text.lines().skip_while(|l| !l.starts_with("ACTION")).skip(1).collect();
+        assert_eq!(rows, vec![
+            "full            300      100  main.py",
+            "truncated       200       80  util.py (truncated to structure to fit the budget)",
+            "dropped         900       10  big.md (priority cut: 500 tokens left after higher-priority files)",
+        ]);
+
+        let mut unlimited = BudgetReport { budget: usize::MAX, ..report };
+        assert!(unlimited.render_decisions().contains("Estimated tokens: 500 (no budget)"));
+
+        // Serialization truncation after budgeting shows up in the preview
+        unlimited.record_truncation("main.py", 100, "truncated to 3 lines, simple mode".to_string());
+        let text = unlimited.render_decisions();
+        assert!(text.contains("Estimated tokens: 300 (no budget)"));
+        assert!(text.contains("truncated       100      100  main.py (truncated to 3 lines, simple mode)"));
+    }
+
     #[test]
     fn test_budget_report_print_many_dropped() {
         let mut dropped_files = Vec::new();
@@ -624,6 +935,7 @@ mod tests {
             strategy: "drop".to_string(),
             included_files: vec![],
             truncated_count: 0,
+            reasons: HashMap::new(),
         };
         // Should show "... and X more" for >10 dropped files
         report.print_report();
@@ -645,6 +957,7 @@ mod tests {
             strategy: "hybrid".to_string(),
             included_files,
             truncated_count: 10,
+            reasons: HashMap::new(),
         };
         // Should show "... and X more" for >5 truncated files
         report.print_report();
@@ -696,6 +1009,82 @@ mod tests {
         assert!(!selected.is_empty());
     }
 
+    #[test]
+    fn test_prioritizer_reorders_within_tier() {
+        let lens_manager = LensManager::new();
+        let files = vec![
+            ("src/a.rs".to_string(), "a".repeat(400)),
+            ("src/ticket/b.rs".to_string(), "b".repeat(400)),
+            ("tests/c.rs".to_string(), "c".repeat(400)),
+        ];
+        let budget = TokenEstimator::estimate_file_tokens(Path::new("src/ticket/b.rs"), &"b".repeat(400));
+
+        let (selected, _) = apply_token_budget(files.clone(), budget, &lens_manager, "drop");
+        assert_eq!(selected[0].0, "src/a.rs");
+
+        let mut prioritizers = PrioritizerRegistry::new();
+        prioritizers.register(Box::new(PathBoost::new(["src/ticket".to_string(), "tests/c.rs".to_string()], 100)));
+        let (selected, report) = apply_token_budget_with(files, budget, &lens_manager, "drop", &prioritizers);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].0, "src/ticket/b.rs");
+        assert!(report.dropped_files.iter().any(|(p, priority, _)| p == "tests/c.rs" && *priority >= 100));
+    }
+
+    #[test]
+    fn test_summarize_strategy_keeps_outline_of_oversized() {
+        let lens_manager = LensManager::new();
+        let body = "        total = total + 1\n".repeat(60);
+        let content = format!(
+            "def load(path):\n    \"\"\"Load a file.\"\"\"\n{}    return total\n\n\ndef save(path):\n{}    return total\n",
+            body, body
+        );
+        let files = vec![("src/io.py".to_string(), content)];
+
+        let (dropped, _) = apply_token_budget(files.clone(), 200, &lens_manager, "drop");
+        assert!(dropped.is_empty());
+
+        let (selected, report) = apply_token_budget(files, 200, &lens_manager, "summarize");
+        assert_eq!(report.strategy, "summarize");
+        assert_eq!(report.dropped_count, 0);
+        assert_eq!(report.included_files[0].3, "summarized");
+        assert!(report.used <= 200);
+        let summary = &selected[0].1;
+        assert!(summary.contains("def load(path):\n    \"\"\"Load a file.\"\"\""), "{}", summary);
+        assert!(summary.contains("def save(path):"));
+        assert!(!summary.contains("total = total + 1"));
+    }
+
+    #[test]
+    fn test_selection_ignores_input_order() {
+        let lens_manager = LensManager::new();
+        let files: Vec<(String, String)> = ["src/d.rs", "src/a.rs", "src/c.rs", "src/b.rs", "docs/x.md"]
+            .iter()
+            .map(|path| (path.to_string(), "x".repeat(400)))
+            .collect();
+        let budget = TokenEstimator::estimate_file_tokens(Path::new("src/a.rs"), &"x".repeat(400)) * 2;
+
+        let (expected, expected_report) = apply_token_budget(files.clone(), budget, &lens_manager, "drop");
+        assert_eq!(expected.iter().map(|(p, _)| p.as_str()).collect::<Vec<_>>(), ["src/a.rs", "src/b.rs"]);
+        for rotation in 1..files.len() {
+            let mut rotated = files.clone();
+            rotated.rotate_left(rotation);
+            rotated.reverse();
+            let (selected, report) = apply_token_budget(rotated, budget, &lens_manager, "drop");
+            assert_eq!(selected, expected);
+            assert_eq!(report.dropped_files, expected_report.dropped_files);
+        }
+    }
+
+    #[test]
+    fn test_selection_order_is_platform_independent() {
+        // `\` sorts after `_` but `/` sorts before it: compare as `/`
+        assert_eq!(selection_order(("src\\a.rs", 50), ("src_b.rs", 50)), selection_order(("src/a.rs", 50), ("src_b.rs", 50)));
+        // Separators also decide the tier
+        assert_eq!(selection_order(("tests\\a.rs", 50), ("src\\b.rs", 50)), Ordering::Greater);
+        assert_eq!(selection_order(("src/a.rs", 10), ("src/b.rs", 90)), Ordering::Greater);
+        assert_eq!(selection_order(("src/a.rs", 50), ("src/a.rs", 50)), Ordering::Equal);
+    }
+
     #[test]
     fn test_format_number_edge_cases() {
         assert_eq!(format_number(0), "0");
@@ -0,0 +1,457 @@
+//! Security Advisories - Flag vulnerable dependencies from OSV data
+//!
+//! Matches the direct dependencies of a [`DependencyModel`] against
+//! [OSV](https://osv.dev) advisories. Advisories come from:
+//!
+//! - an **offline database**: a JSON file (an array of OSV records, an OSV
+//!   `{"vulns": [...]}` response, or a single record) or a directory of
+//!   per-advisory `*.json` files as found in the OSV data dumps
+//! - an **OSV API** (the public one or a local mirror), only with the `osv`
+//!   feature and only when explicitly requested
+//!
+//! A requirement such as `^1.2` or `>=4.2` is checked at the lowest version it
+//! allows; dependencies without a version are reported as possibly affected.
+//! [`AdvisoryPrioritizer`] moves files importing vulnerable dependencies
+//! ahead of their tier peers (used with the security lens).
+
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::budgeting::{FilePrioritizer, PathBoost, PriorityInput};
+use crate::core::manifests::{Dependency, DependencyModel, Ecosystem};
+
+/// Priority boost for files importing a vulnerable dependency
+pub const ADVISORY_BOOST: i32 = 80;
+
+/// Public OSV API endpoint
+pub const OSV_API: &str = "https://api.osv.dev";
+
+/// An OSV advisory (the fields used for matching)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsvAdvisory {
+    pub id: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub affected: Vec<OsvAffected>,
+}
+
+/// A package affected by an advisory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsvAffected {
+    pub package: OsvPackage,
+    #[serde(default)]
+    pub ranges: Vec<OsvRange>,
+    /// Explicitly enumerated affected versions
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsvPackage {
+    pub ecosystem: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsvRange {
+    /// `SEMVER`, `ECOSYSTEM` or `GIT` (git ranges are not evaluated)
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub events: Vec<OsvEvent>,
+}
+
+/// One event of a range; exactly one field is set
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OsvEvent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub introduced: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_affected: Option<String>,
+}
+
+impl OsvEvent {
+    fn version(&self) -> &str {
+        self.introduced
+            .as_deref()
+            .or(self.fixed.as_deref())
+            .or(self.last_affected.as_deref())
+            .unwrap_or("0")
+    }
+}
+
+impl OsvAffected {
+    /// Check whether `version` is affected
+    pub fn affects(&self, version: &str) -> bool {
+        self.versions.iter().any(|v| v == version)
+            || self.ranges.iter().filter(|r| r.kind != "GIT").any(|r| range_affects(&r.events, version))
+    }
+
+    /// Lowest fixed version above `version`, if any
+    pub fn fixed_after(&self, version: &str) -> Option<&str> {
+        self.ranges
+            .iter()
+            .flat_map(|r| &r.events)
+            .filter_map(|e| e.fixed.as_deref())
+            .filter(|fixed| compare_versions(fixed, version) == Ordering::Greater)
+            .min_by(|a, b| compare_versions(a, b))
+    }
+}
+
+/// OSV range evaluation: walk events in version order, toggling on
+/// `introduced` and off on `fixed`/`last_affected`
+fn range_affects(events: &[OsvEvent], version: &str) -> bool {
+    let mut events: Vec<&OsvEvent> = events.iter().collect();
+    events.sort_by(|a, b| compare_versions(a.version(), b.version()));
+    let mut affected = false;
+    for event in events {
+        let at = compare_versions(event.version(), version);
+        if event.introduced.is_some() && at != Ordering::Greater {
+            affected = true;
+        } else if (event.fixed.is_some() && at != Ordering::Greater)
+            || (event.last_affected.is_some() && at == Ordering::Less)
+        {
+            affected = false;
+        }
+    }
+    affected
+}
+
+/// Compare dotted versions component-wise (numeric where both are numbers)
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<String> {
+        v.trim_start_matches('v').split(['.', '-', '+']).map(str::to_string).collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+    for i in 0..a.len().max(b.len()) {
+        let x = a.get(i).map_or("0", String::as_str);
+        let y = b.get(i).map_or("0", String::as_str);
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            // A pre-release tag sorts before the release (`1.0.0-rc1` < `1.0.0`)
+            (Ok(_), Err(_)) => Ordering::Greater,
+            (Err(_), Ok(_)) => Ordering::Less,
+            (Err(_), Err(_)) => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Lowest version a requirement allows (`^1.2` → `1.2`, `>=4.2,<5` → `4.2`)
+pub fn lowest_version(requirement: &str) -> Option<String> {
+    let first = requirement.split(',').next()?.trim();
+    if first.starts_with('<') {
+        return None;
+    }
+    let version = first.trim_start_matches(|c: char| "^~=>!v ".contains(c));
+    let version = version.split_whitespace().next()?.trim_end_matches(".*");
+    version.starts_with(|c: char| c.is_ascii_digit()).then(|| version.to_string())
+}
+
+/// OSV ecosystem name of a manifest ecosystem
+pub fn osv_ecosystem(ecosystem: Ecosystem) -> &'static str {
+    match ecosystem {
+        Ecosystem::Cargo => "crates.io",
+        Ecosystem::Npm => "npm",
+        Ecosystem::Python => "PyPI",
+        Ecosystem::Go => "Go",
+        Ecosystem::Maven => "Maven",
+        Ecosystem::Ruby => "RubyGems",
+    }
+}
+
+fn same_package(dependency: &Dependency, package: &OsvPackage) -> bool {
+    if package.ecosystem != osv_ecosystem(dependency.ecosystem) {
+        return false;
+    }
+    match dependency.ecosystem {
+        // PEP 503 normalization
+        Ecosystem::Python => {
+            let normalize = |n: &str| n.to_lowercase().replace(['_', '.'], "-");
+            normalize(&dependency.name) == normalize(&package.name)
+        }
+        _ => dependency.name == package.name,
+    }
+}
+
+/// A set of advisories to match against
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AdvisoryDb {
+    pub advisories: Vec<OsvAdvisory>,
+}
+
+impl AdvisoryDb {
+    /// Parse a JSON document: an array, a `{"vulns": [...]}` object or one record
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        let value: serde_json::Value =
+            serde_json::from_str(text).map_err(|e| format!("Invalid advisory JSON: {}", e))?;
+        let records = match value {
+            serde_json::Value::Array(records) => records,
+            // The API answers `{}` when nothing matches
+            serde_json::Value::Object(object) if object.is_empty() => Vec::new(),
+            serde_json::Value::Object(mut object) if object.contains_key("vulns") => match object.remove("vulns") {
+                Some(serde_json::Value::Array(records)) => records,
+                _ => Vec::new(),
+            },
+            record => vec![record],
+        };
+        let advisories = records
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<Result<Vec<OsvAdvisory>, _>>()
+            .map_err(|e| format!("Invalid OSV record: {}", e))?;
+        Ok(Self { advisories })
+    }
+
+    /// Load an advisory file or a directory of `*.json` advisories
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let read = |path: &Path| {
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+        };
+        if !path.is_dir() {
+            return Self::from_json(&read(path)?);
+        }
+
+        let mut files: Vec<_> = std::fs::read_dir(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+        let mut db = Self::default();
+        for file in files {
+            let parsed = Self::from_json(&read(&file)?).map_err(|e| format!("{}: {}", file.display(), e))?;
+            db.advisories.extend(parsed.advisories);
+        }
+        Ok(db)
+    }
+
+    /// Query an OSV API (`/v1/query`) for each dependency with a known version
+    #[cfg(feature = "osv")]
+    pub fn query_api(api: &str, dependencies: &[Dependency]) -> Result<Self, String> {
+        let agent = ureq::AgentBuilder::new().timeout(std::time::Duration::from_secs(15)).build();
+        let url = format!("{}/v1/query", api.trim_end_matches('/'));
+        let mut db = Self::default();
+        let mut seen = BTreeSet::new();
+        for dependency in dependencies {
+            let Some(version) = dependency.version.as_deref().and_then(lowest_version) else {
+                continue;
+            };
+            let query = serde_json::json!({
+                "version": version,
+                "package": { "name": dependency.name, "ecosystem": osv_ecosystem(dependency.ecosystem) },
+            });
+            let body = agent
+                .post(&url)
+                .set("Content-Type", "application/json")
+                .set("User-Agent", concat!("pm_encoder/", env!("CARGO_PKG_VERSION")))
+                .send_string(&query.to_string())
+                .map_err(|e| format!("OSV query for {} failed: {}", dependency.name, e))?
+                .into_string()
+                .map_err(|e| format!("OSV query for {} failed: {}", dependency.name, e))?;
+            for advisory in Self::from_json(&body)?.advisories {
+                if seen.insert(advisory.id.clone()) {
+                    db.advisories.push(advisory);
+                }
+            }
+        }
+        Ok(db)
+    }
+
+    pub fn len(&self) -> usize {
+        self.advisories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+}
+
+/// A dependency matched by an advisory
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VulnerableDependency {
+    pub dependency: Dependency,
+    pub advisory: String,
+    pub summary: String,
+    /// Version the requirement was checked at; `None` means possibly affected
+    pub checked_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed: Option<String>,
+    /// Source files importing the dependency
+    pub files: Vec<String>,
+}
+
+/// Vulnerable dependencies of a project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AdvisoryReport {
+    /// Dependencies checked
+    pub checked: usize,
+    pub findings: Vec<VulnerableDependency>,
+}
+
+impl AdvisoryReport {
+    /// Match every dependency against the database and find its importers
+    pub fn check(model: &DependencyModel, db: &AdvisoryDb, files: &[(String, String)]) -> Self {
+        let mut findings = Vec::new();
+        for dependency in &model.dependencies {
+            let version = dependency.version.as_deref().and_then(lowest_version);
+            for advisory in &db.advisories {
+                let Some(affected) = advisory.affected.iter().find(|a| {
+                    same_package(dependency, &a.package) && version.as_deref().is_none_or(|v| a.affects(v))
+                }) else {
+                    continue;
+                };
+                findings.push(VulnerableDependency {
+                    dependency: dependency.clone(),
+                    advisory: advisory.id.clone(),
+                    summary: advisory.summary.clone(),
+                    fixed: version.as_deref().and_then(|v| affected.fixed_after(v)).map(str::to_string),
+                    checked_version: version.clone(),
+                    files: model.users_of(&dependency.name, files),
+                });
+            }
+        }
+        Self { checked: model.len(), findings }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Files importing any vulnerable dependency
+    pub fn files(&self) -> BTreeSet<&str> {
+        self.findings.iter().flat_map(|f| f.files.iter().map(String::as_str)).collect()
+    }
+
+    /// Human-readable report (stderr)
+    pub fn render_text(&self) -> String {
+        let packages: BTreeSet<&str> = self.findings.iter().map(|f| f.dependency.name.as_str()).collect();
+        let mut out = format!(
+            "[ADVISORIES] {} vulnerable dependencies ({} advisories) of {} checked\n",
+            packages.len(),
+            self.findings.len(),
+            self.checked
+        );
+        for finding in &self.findings {
+            let version = finding.checked_version.as_deref().unwrap_or("version unknown");
+            out.push_str(&format!(
+                "  {} {} {} ({})",
+                finding.advisory, finding.dependency.name, version, finding.dependency.manifest
+            ));
+            if !finding.summary.is_empty() {
+                out.push_str(&format!(" - {}", finding.summary));
+            }
+            if let Some(fixed) = &finding.fixed {
+                out.push_str(&format!("; fixed in {}", fixed));
+            }
+            if !finding.files.is_empty() {
+                out.push_str(&format!("; imported by {} file(s)", finding.files.len()));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Budget prioritizer boosting files that import vulnerable dependencies
+#[derive(Debug, Clone)]
+pub struct AdvisoryPrioritizer {
+    paths: PathBoost,
+}
+
+impl AdvisoryPrioritizer {
+    pub fn new(report: &AdvisoryReport) -> Self {
+        let paths = report.files().into_iter().map(str::to_string);
+        Self { paths: PathBoost::new(paths, ADVISORY_BOOST) }
+    }
+}
+
+impl FilePrioritizer for AdvisoryPrioritizer {
+    fn name(&self) -> &str {
+        "advisories"
+    }
+
+    fn score(&self, file: &PriorityInput<'_>) -> i32 {
+        self.paths.score(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DB: &str = r#"[
+        {"id": "RUSTSEC-2023-0001", "summary": "Data race in channel",
+         "affected": [{"package": {"ecosystem": "crates.io", "name": "tokio"},
+                       "ranges": [{"type": "SEMVER", "events": [{"introduced": "0.2.0"}, {"fixed": "1.18.5"}]}]}]},
+        {"id": "PYSEC-2024-1", "summary": "SQL injection",
+         "affected": [{"package": {"ecosystem": "PyPI", "name": "django"}, "versions": ["4.2.0"]}]},
+        {"id": "GHSA-old", "affected": [{"package": {"ecosystem": "crates.io", "name": "serde"},
+                       "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"last_affected": "0.9.0"}]}]}]}
+    ]"#;
+
+    #[test]
+    fn test_versions_and_ranges() {
+        assert_eq!(compare_versions("1.10.0", "1.9.3"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0.0-rc1", "1.0.0"), Ordering::Less);
+        assert_eq!(lowest_version("^1.2"), Some("1.2".to_string()));
+        assert_eq!(lowest_version(">=4.2,<5"), Some("4.2".to_string()));
+        assert_eq!(lowest_version("v1.9.1"), Some("1.9.1".to_string()));
+        assert_eq!(lowest_version("<2"), None);
+        assert_eq!(lowest_version("*"), None);
+
+        let db = AdvisoryDb::from_json(DB).unwrap();
+        let tokio = &db.advisories[0].affected[0];
+        assert!(tokio.affects("1.0"));
+        assert!(!tokio.affects("1.18.5"));
+        assert!(!tokio.affects("0.1.9"));
+        assert_eq!(tokio.fixed_after("1.0"), Some("1.18.5"));
+        let serde = &db.advisories[2].affected[0];
+        assert!(serde.affects("0.9.0"));
+        assert!(!serde.affects("1.0"));
+    }
+
+    #[test]
+    fn test_report_and_prioritizer() {
+        let files = vec![
+            ("Cargo.toml".to_string(), "[dependencies]\ntokio = \"1.0\"\nserde = \"1\"\n".to_string()),
+            ("requirements.txt".to_string(), "Django\n".to_string()),
+            ("src/main.rs".to_string(), "#[tokio::main]\nasync fn main() {}\n".to_string()),
+            ("src/lib.rs".to_string(), "use serde::Serialize;\n".to_string()),
+        ];
+        let model = DependencyModel::from_files(&files);
+        let db = AdvisoryDb::from_json(DB).unwrap();
+        let report = AdvisoryReport::check(&model, &db, &files);
+
+        assert_eq!(report.checked, 3);
+        let ids: Vec<&str> = report.findings.iter().map(|f| f.advisory.as_str()).collect();
+        assert_eq!(ids, vec!["RUSTSEC-2023-0001", "PYSEC-2024-1"]);
+        assert_eq!(report.findings[0].fixed.as_deref(), Some("1.18.5"));
+        assert_eq!(report.findings[0].files, vec!["src/main.rs"]);
+        assert_eq!(report.findings[1].checked_version, None);
+        assert!(report.render_text().starts_with("[ADVISORIES] 2 vulnerable dependencies (2 advisories) of 3 checked\n"));
+
+        let prioritizer = AdvisoryPrioritizer::new(&report);
+        let score = |path| {
+            prioritizer.score(&PriorityInput { path, tier: crate::core::FileTier::Core, language: "rust", lens_priority: 0, tokens: 10, lines: 1 })
+        };
+        assert_eq!(score("src/main.rs"), ADVISORY_BOOST);
+        assert_eq!(score("src/lib.rs"), 0);
+
+        let single = AdvisoryDb::from_json(r#"{"vulns": [{"id": "X"}]}"#).unwrap();
+        assert_eq!(single.len(), 1);
+        assert!(AdvisoryDb::from_json("not json").is_err());
+    }
+}
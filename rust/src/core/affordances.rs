@@ -0,0 +1,204 @@
+//! Affordance Index - Machine-readable list of the zoom affordances in a context
+//!
+//! Truncation markers, collapsed sections and summaries each embed an inline
+//! `ZOOM_AFFORDANCE` comment. Tool-using models should not have to regex
+//! those out of the context, so this module collects them into an index:
+//!
+//! ```text
+//! ========== AFFORDANCE_INDEX ==========
+//! {"affordances":[{"id":"zoom-1a2b3c4d","kind":"function","target":"apply_budget",...}]}
+//! ========== END AFFORDANCE_INDEX ==========
+//! ```
+//!
+//! Ids are derived from the zoom target, so they are stable across runs and
+//! identical wherever the same target is offered. Declarations addressed by
+//! anchor keep their anchor as id, the same one the table of contents lists.
+
+use std::collections::BTreeMap;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::budgeting::TokenEstimator;
+use crate::core::zoom::ZoomTarget;
+
+lazy_static! {
+    static ref AFFORDANCE: Regex = Regex::new(
+        r#"ZOOM_AFFORDANCE: pm_encoder --zoom (\w+)=(?:"([^"]*)"|([^\s*]+))(?:\s+--budget[ =](\d+))?(?:\s*\|\s*children available: ([^*>\n]*[^*>\s]))?"#
+    )
+    .unwrap();
+}
+
+/// A zoom affordance offered somewhere in a context
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Affordance {
+    /// Stable id derived from the target (`zoom-` + 8 hex digits), or the
+    /// declaration's anchor for `anchor` targets
+    pub id: String,
+    /// Target kind: `function`, `class`, `module`, `section`, `anchor` or `file`
+    pub kind: String,
+    /// Target value as passed to `--zoom kind=value`
+    pub target: String,
+    /// Command that expands the affordance
+    pub command: String,
+    /// Estimated token cost of expanding it, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_tokens: Option<usize>,
+    /// Children a container offers ("3 methods, 1 struct")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<String>,
+    /// Output line of the first occurrence (1-indexed)
+    pub line: usize,
+    /// Number of times the affordance appears
+    pub occurrences: usize,
+}
+
+/// All zoom affordances of a context, in order of first appearance
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AffordanceIndex {
+    pub affordances: Vec<Affordance>,
+}
+
+impl AffordanceIndex {
+    /// Collect the affordances embedded in serialized output
+    pub fn from_output(output: &str) -> Self {
+        let mut affordances: Vec<Affordance> = Vec::new();
+        let mut by_id: BTreeMap<String, usize> = BTreeMap::new();
+
+        for (number, line) in output.lines().enumerate() {
+            for caps in AFFORDANCE.captures_iter(line) {
+                let kind = caps[1].to_string();
+                let value = caps.get(2).or_else(|| caps.get(3)).map_or("", |m| m.as_str());
+                let Ok(target) = ZoomTarget::parse(&format!("{}={}", kind, value)) else {
+                    continue;
+                };
+                let id = affordance_id(&target);
+                if let Some(&existing) = by_id.get(&id) {
+                    affordances[existing].occurrences += 1;
+                    continue;
+                }
+                let budget = caps.get(4).and_then(|b| b.as_str().parse().ok());
+                by_id.insert(id.clone(), affordances.len());
+                affordances.push(Affordance {
+                    id,
+                    kind: target_kind(&target).to_string(),
+                    target: value.to_string(),
+                    command: target.to_command(budget),
+                    estimated_tokens: budget,
+                    children: caps.get(5).map(|c| c.as_str().to_string()),
+                    line: number + 1,
+                    occurrences: 1,
+                });
+            }
+        }
+        Self { affordances }
+    }
+
+    /// Fill in missing costs of file affordances from the full file contents
+    pub fn estimate_files(&mut self, files: &[(String, String)]) {
+        for affordance in self.affordances.iter_mut().filter(|a| a.estimated_tokens.is_none() && a.kind == "file") {
+            if let Some((_, content)) = files.iter().find(|(path, _)| *path == affordance.target) {
+                affordance.estimated_tokens = Some(TokenEstimator::estimate_tokens(content));
+            }
+        }
+    }
+
+    /// Look up an affordance by id
+    pub fn get(&self, id: &str) -> Option<&Affordance> {
+        self.affordances.iter().find(|a| a.id == id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.affordances.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.affordances.is_empty()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// Render the trailing index section (CDATA element for XML outputs)
+    pub fn render_section(&self, xml: bool) -> String {
+        if xml {
+            format!("<affordance_index format=\"json\"><![CDATA[{}]]></affordance_index>\n", self.to_json())
+        } else {
+            format!(
+                "========== AFFORDANCE_INDEX ==========\n{}\n========== END AFFORDANCE_INDEX ==========\n",
+                self.to_json()
+            )
+        }
+    }
+}
+
+/// Stable id of a zoom target: the anchor for anchor targets
+pub fn affordance_id(target: &ZoomTarget) -> String {
+    if let ZoomTarget::Anchor(anchor) = target {
+        return anchor.to_string();
+    }
+    let digest = format!("{:x}", md5::compute(target.to_string().as_bytes()));
+    format!("zoom-{}", &digest[..8])
+}
+
+fn target_kind(target: &ZoomTarget) -> &'static str {
+    match target {
+        ZoomTarget::Function(_) => "function",
+        ZoomTarget::Class(_) => "class",
+        ZoomTarget::Module(_) => "module",
+        ZoomTarget::Section(_) => "section",
+        ZoomTarget::Anchor(_) => "anchor",
+        ZoomTarget::File { .. } => "file",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUTPUT: &str = "fn a() {\n/* ZOOM_AFFORDANCE: pm_encoder --zoom function=apply_budget --budget 1000 */\n\
+                          /* ZOOM_AFFORDANCE: pm_encoder --zoom file=src/lib.rs */\n\
+                          /* ZOOM_AFFORDANCE: pm_encoder --zoom class=Engine --budget 150 | children available: 2 methods */\n\
+                          <!-- ZOOM_AFFORDANCE: pm_encoder --zoom section=\"README.md#Getting Started\" --budget 500 -->\n\
+                          # END SUMMARY - ZOOM_AFFORDANCE: pm_encoder --zoom function=apply_budget\n";
+
+    #[test]
+    fn test_index_from_output() {
+        let mut index = AffordanceIndex::from_output(OUTPUT);
+        assert_eq!(index.len(), 4);
+
+        let function = &index.affordances[0];
+        assert_eq!((function.kind.as_str(), function.target.as_str()), ("function", "apply_budget"));
+        assert_eq!((function.estimated_tokens, function.line, function.occurrences), (Some(1000), 2, 2));
+        assert_eq!(function.id, affordance_id(&ZoomTarget::Function("apply_budget".to_string())));
+        assert_eq!(index.get(&function.id).unwrap().command, "pm_encoder --zoom function=apply_budget --budget 1000");
+
+        let container = &index.affordances[2];
+        assert_eq!((container.estimated_tokens, container.children.as_deref()), (Some(150), Some("2 methods")));
+        assert_eq!(index.affordances[0].children, None);
+
+        let section = &index.affordances[3];
+        assert_eq!(section.target, "README.md#Getting Started");
+        assert_eq!(section.command, "pm_encoder --zoom section=\"README.md#Getting Started\" --budget 500");
+
+        assert_eq!(index.affordances[1].estimated_tokens, None);
+        index.estimate_files(&[("src/lib.rs".to_string(), "x".repeat(400))]);
+        assert_eq!(index.affordances[1].estimated_tokens, Some(100));
+
+        let anchored = AffordanceIndex::from_output("/* ZOOM_AFFORDANCE: pm_encoder --zoom anchor=src/lib.rs#Engine.run@1a2b3c4d --budget 50 */");
+        assert_eq!(anchored.affordances[0].id, "src/lib.rs#Engine.run@1a2b3c4d");
+        assert_eq!(anchored.affordances[0].kind, "anchor");
+    }
+
+    #[test]
+    fn test_render_section() {
+        let index = AffordanceIndex::from_output(OUTPUT);
+        let text = index.render_section(false);
+        assert!(text.starts_with("========== AFFORDANCE_INDEX ==========\n{\"affordances\":["));
+        assert!(!text.contains("ZOOM_AFFORDANCE"));
+        assert!(index.render_section(true).starts_with("<affordance_index format=\"json\"><![CDATA["));
+        assert!(AffordanceIndex::from_output("no markers").is_empty());
+    }
+}
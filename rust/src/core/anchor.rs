@@ -0,0 +1,177 @@
+//! Anchors - Stable ids of declarations
+//!
+//! An anchor addresses one declaration of one file as
+//! `path#Outer.inner@1a2b3c4d`: the file path, the dotted qualified name, and
+//! a fingerprint of the declaration header (first 8 hex digits of its MD5).
+//! The qualified name keeps anchors readable and stable while bodies change;
+//! the fingerprint tells apart declarations sharing a name, such as a Rust
+//! struct and its `impl` block or overloads in Java.
+//!
+//! The same anchor is listed in the table of contents, given as the `id` of
+//! API reference items, used as the target of declaration affordances, and
+//! accepted by `--zoom anchor=...`. A fingerprint that no longer matches
+//! (the header was edited) still resolves by qualified name.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use voyager_ast::Declaration;
+
+use crate::core::apiref::signature;
+use crate::core::ast_bridge::AstBridge;
+use crate::core::error::{EncoderError, Result};
+
+/// Hex digits of the header MD5 kept in an anchor
+const FINGERPRINT_LEN: usize = 8;
+
+/// Stable id of a declaration
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Anchor {
+    pub path: String,
+    /// Names of the enclosing declarations and the declaration, dot-separated
+    pub qualified_name: String,
+    /// Header fingerprint, absent when an anchor is written by hand
+    pub fingerprint: Option<String>,
+}
+
+impl Anchor {
+    /// Anchor of `decl`, declared in `source` at `path` as `qualified_name`
+    pub fn for_declaration(path: &str, qualified_name: &str, decl: &Declaration, source: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            qualified_name: qualified_name.to_string(),
+            fingerprint: Some(fingerprint(decl, source)),
+        }
+    }
+
+    /// Parse `path#Outer.inner` or `path#Outer.inner@fingerprint`
+    pub fn parse(s: &str) -> Result<Self> {
+        let invalid = || EncoderError::InvalidZoomTarget { target: s.to_string() };
+        let (path, name) = s.rsplit_once('#').ok_or_else(invalid)?;
+        let (qualified_name, fingerprint) = match name.rsplit_once('@') {
+            Some((name, hex)) if hex.len() == FINGERPRINT_LEN && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                (name, Some(hex.to_ascii_lowercase()))
+            }
+            _ => (name, None),
+        };
+        if path.is_empty() || qualified_name.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            path: path.to_string(),
+            qualified_name: qualified_name.to_string(),
+            fingerprint,
+        })
+    }
+
+    /// Unqualified name of the declaration
+    pub fn name(&self) -> &str {
+        self.qualified_name.rsplit('.').next().unwrap_or(&self.qualified_name)
+    }
+
+    /// Find the declaration in the file's `content`
+    ///
+    /// Among declarations with the qualified name, the one whose fingerprint
+    /// matches wins; otherwise the first is taken.
+    pub fn locate(&self, content: &str) -> Option<Declaration> {
+        let language = AstBridge::detect_language(Path::new(&self.path));
+        let file = AstBridge::new().analyze_file(content, language)?;
+        let mut candidates = Vec::new();
+        for (qualified, decl, _) in qualified_declarations(&file.declarations, usize::MAX) {
+            if qualified == self.qualified_name {
+                candidates.push(decl);
+            }
+        }
+        candidates
+            .iter()
+            .find(|decl| self.fingerprint.as_deref().is_some_and(|hex| fingerprint(decl, content) == hex))
+            .or(candidates.first())
+            .map(|decl| (*decl).clone())
+    }
+}
+
+impl fmt::Display for Anchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}#{}", self.path, self.qualified_name)?;
+        if let Some(hex) = &self.fingerprint {
+            write!(f, "@{}", hex)?;
+        }
+        Ok(())
+    }
+}
+
+/// Fingerprint of a declaration: MD5 of its one-line header, shortened
+pub fn fingerprint(decl: &Declaration, source: &str) -> String {
+    let digest = format!("{:x}", md5::compute(signature(decl, source).as_bytes()));
+    digest[..FINGERPRINT_LEN].to_string()
+}
+
+/// Anchors of the declarations of `source`, outermost first, with their
+/// nesting depth (0 = top level), down to `max_depth` levels
+pub fn anchors<'a>(
+    path: &str,
+    declarations: &'a [Declaration],
+    source: &str,
+    max_depth: usize,
+) -> Vec<(Anchor, &'a Declaration, usize)> {
+    qualified_declarations(declarations, max_depth)
+        .into_iter()
+        .map(|(qualified, decl, depth)| (Anchor::for_declaration(path, &qualified, decl, source), decl, depth))
+        .collect()
+}
+
+/// Declarations in source order with their qualified names and depths
+fn qualified_declarations(declarations: &[Declaration], max_depth: usize) -> Vec<(String, &Declaration, usize)> {
+    fn walk<'a>(
+        decls: &'a [Declaration],
+        prefix: &str,
+        depth: usize,
+        max_depth: usize,
+        out: &mut Vec<(String, &'a Declaration, usize)>,
+    ) {
+        if depth >= max_depth {
+            return;
+        }
+        for decl in decls {
+            let qualified = if prefix.is_empty() { decl.name.clone() } else { format!("{}.{}", prefix, decl.name) };
+            out.push((qualified.clone(), decl, depth));
+            walk(&decl.children, &qualified, depth + 1, max_depth, out);
+        }
+    }
+    let mut out = Vec::new();
+    walk(declarations, "", 0, max_depth, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "pub struct Engine;\n\nimpl Engine {\n    pub fn run(&self) {\n        work();\n    }\n}\n";
+
+    #[test]
+    fn test_anchor_round_trip_and_locate() {
+        let file = AstBridge::new().analyze_file(SOURCE, voyager_ast::LanguageId::Rust).unwrap();
+        let all = anchors("src/engine.rs", &file.declarations, SOURCE, usize::MAX);
+        let ids: Vec<String> = all.iter().map(|(anchor, _, _)| anchor.to_string()).collect();
+
+        // The struct and its impl share a name but not an id
+        assert!(ids[0].starts_with("src/engine.rs#Engine@"));
+        assert!(ids[1].starts_with("src/engine.rs#Engine@"));
+        assert_ne!(ids[0], ids[1]);
+        let run = all.iter().find(|(anchor, _, _)| anchor.qualified_name == "Engine.run").unwrap();
+        assert_eq!(run.2, 1);
+
+        let parsed = Anchor::parse(&ids[1]).unwrap();
+        assert_eq!(parsed, all[1].0);
+        assert_eq!(parsed.locate(SOURCE).unwrap().span.start_line, 3);
+
+        // Without (or with a stale) fingerprint the first declaration of the name is taken
+        let by_name = Anchor::parse("src/engine.rs#Engine.run").unwrap();
+        assert_eq!(by_name.fingerprint, None);
+        assert_eq!(by_name.name(), "run");
+        assert_eq!(by_name.locate(SOURCE).unwrap().span.start_line, 4);
+        assert!(Anchor::parse("src/engine.rs").is_err());
+    }
+}
@@ -0,0 +1,261 @@
+//! API Reference - Public declarations by module, signatures and doc summaries only
+//!
+//! Unlike a serialized context, which carries file contents, the API
+//! reference is a standalone document: one section per module, each public
+//! declaration as its signature plus the first line of its doc comment, with
+//! public members nested under their type. No bodies, no private items.
+//! Rendered as Markdown it can be embedded in a docs site; it also makes a
+//! very compact primer for an LLM that only needs to know what it can call.
+//!
+//! "Public" follows each language: `pub` in Rust (not `pub(crate)`), exported
+//! declarations in TypeScript/JavaScript, names without a leading underscore
+//! in Python. Members of public traits and interfaces are always listed, as
+//! are TypeScript class members without an access modifier.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+use voyager_ast::{Declaration, DeclarationKind, LanguageId, Visibility};
+
+use crate::core::anchor::Anchor;
+use crate::core::ast_bridge::AstBridge;
+
+/// A public declaration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiItem {
+    /// Anchor of the declaration (see [`Anchor`])
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    /// Declaration header on one line (`pub fn run(&self) -> Result<()>`)
+    pub signature: String,
+    /// First line of the doc comment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Line of the declaration (1-indexed)
+    pub line: usize,
+    /// Public members (methods, fields, variants)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<ApiItem>,
+}
+
+/// Public declarations of one source file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiModule {
+    /// Module path in the language's notation (`core::walker`, `app.models`)
+    pub name: String,
+    /// Source file
+    pub path: String,
+    pub language: String,
+    pub items: Vec<ApiItem>,
+}
+
+/// API reference of a project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ApiReference {
+    /// Modules with at least one public declaration, by path
+    pub modules: Vec<ApiModule>,
+}
+
+impl ApiReference {
+    /// Build the reference from `(path, content)` pairs
+    pub fn build(files: &[(String, String)]) -> Self {
+        let bridge = AstBridge::new();
+        let mut modules = BTreeMap::new();
+        for (path, content) in files {
+            let language = AstBridge::detect_language(Path::new(path));
+            if matches!(language, LanguageId::Markdown | LanguageId::Html | LanguageId::Unknown) {
+                continue;
+            }
+            let Some(file) = bridge.analyze_file(content, language) else { continue };
+            let items: Vec<ApiItem> =
+                file.declarations.iter().filter_map(|decl| api_item(path, "", decl, None, content)).collect();
+            if !items.is_empty() {
+                let module = ApiModule {
+                    name: module_name(path, language),
+                    path: path.clone(),
+                    language: language.name().to_string(),
+                    items,
+                };
+                modules.insert(path.clone(), module);
+            }
+        }
+        Self { modules: modules.into_values().collect() }
+    }
+
+    /// Number of public declarations, members included
+    pub fn item_count(&self) -> usize {
+        fn count(items: &[ApiItem]) -> usize {
+            items.iter().map(|item| 1 + count(&item.members)).sum()
+        }
+        self.modules.iter().map(|m| count(&m.items)).sum()
+    }
+
+    /// Markdown document: one section per module, one bullet per declaration
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# API Reference\n\n");
+        out.push_str(&format!(
+            "{} public declarations in {} modules.\n",
+            self.item_count(),
+            self.modules.len()
+        ));
+        for module in &self.modules {
+            out.push_str(&format!("\n## {}\n\n`{}`\n\n", module.name, module.path));
+            for item in &module.items {
+                push_item(&mut out, item, 0);
+            }
+        }
+        out
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+fn push_item(out: &mut String, item: &ApiItem, depth: usize) {
+    out.push_str(&"  ".repeat(depth));
+    out.push_str(&format!("- `{}`", item.signature.replace('`', "'")));
+    if let Some(summary) = &item.summary {
+        out.push_str(&format!(" - {}", summary));
+    }
+    out.push('\n');
+    for member in &item.members {
+        push_item(out, member, depth + 1);
+    }
+}
+
+/// The public part of a declaration, or `None` if it is not part of the API
+///
+/// `prefix` is the qualified name of the enclosing declaration (empty at top level).
+fn api_item(path: &str, prefix: &str, decl: &Declaration, parent: Option<&Declaration>, source: &str) -> Option<ApiItem> {
+    if matches!(decl.kind, DeclarationKind::Section | DeclarationKind::Element) {
+        return None;
+    }
+    let qualified = if prefix.is_empty() { decl.name.clone() } else { format!("{}.{}", prefix, decl.name) };
+    let members: Vec<ApiItem> =
+        decl.children.iter().filter_map(|child| api_item(path, &qualified, child, Some(decl), source)).collect();
+    let public = match parent {
+        // `impl` blocks have no visibility of their own: they are API if their members are
+        None if decl.kind == DeclarationKind::Impl => !members.is_empty(),
+        None => decl.visibility == Visibility::Public,
+        Some(parent) => match parent.kind {
+            DeclarationKind::Trait | DeclarationKind::Interface => true,
+            DeclarationKind::Class => matches!(decl.visibility, Visibility::Public | Visibility::Unknown),
+            _ => decl.visibility == Visibility::Public,
+        },
+    };
+    if !public {
+        return None;
+    }
+    Some(ApiItem {
+        id: Anchor::for_declaration(path, &qualified, decl, source).to_string(),
+        name: decl.name.clone(),
+        kind: decl.kind.as_str().to_string(),
+        signature: signature(decl, source),
+        summary: decl.doc_comment.as_ref().and_then(|doc| summary(&doc.text)),
+        line: decl.span.start_line,
+        members,
+    })
+}
+
+/// Declaration header on one line, without the opening of its body
+pub(crate) fn signature(decl: &Declaration, source: &str) -> String {
+    let span = decl.signature_span.unwrap_or(decl.span);
+    let text = source.get(span.start..span.end.min(source.len())).unwrap_or_default();
+    let text = if decl.signature_span.is_some() { text } else { text.lines().next().unwrap_or_default() };
+    let joined = text.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join(" ");
+    let joined = joined.replace("( ", "(").replace(", )", ")").replace(" )", ")");
+    let header = joined.trim_end().trim_end_matches('{').trim_end_matches(':').trim_end();
+    if header.is_empty() {
+        format!("{} {}", decl.kind.as_str(), decl.name)
+    } else {
+        header.to_string()
+    }
+}
+
+/// First non-empty line of a doc comment, without comment decoration
+fn summary(doc: &str) -> Option<String> {
+    doc.lines()
+        .map(|line| line.trim().trim_start_matches("/**").trim_start_matches('*').trim_end_matches("*/").trim())
+        .find(|line| !line.is_empty() && !line.starts_with('@'))
+        .map(str::to_string)
+}
+
+/// Module path of a file in its language's notation
+fn module_name(path: &str, language: LanguageId) -> String {
+    let without_extension = Path::new(path).with_extension("");
+    let mut parts: Vec<&str> = without_extension.iter().filter_map(|p| p.to_str()).collect();
+    match language {
+        LanguageId::Rust => {
+            if parts.first() == Some(&"src") {
+                parts.remove(0);
+            }
+            if matches!(parts.last(), Some(&"mod") | Some(&"lib") | Some(&"main")) {
+                parts.pop();
+            }
+            if parts.is_empty() {
+                "crate".to_string()
+            } else {
+                parts.join("::")
+            }
+        }
+        LanguageId::Python => {
+            if parts.last() == Some(&"__init__") {
+                parts.pop();
+            }
+            parts.join(".")
+        }
+        _ => {
+            if parts.len() > 1 && parts.last() == Some(&"index") {
+                parts.pop();
+            }
+            parts.join("/")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "/// Walks directories.\npub struct Walker {\n    pub root: String,\n    depth: usize,\n}\n\n\
+                       impl Walker {\n    /// Create a walker.\n    pub fn new(\n        root: String,\n    ) -> Self {\n        \
+                       Self { root, depth: 0 }\n    }\n\n    fn helper(&self) {}\n}\n\nfn private() {}\n\n\
+                       pub(crate) fn internal() {}\n";
+
+    #[test]
+    fn test_public_items_with_signatures_and_summaries() {
+        let files = vec![
+            ("src/core/walker.rs".to_string(), LIB.to_string()),
+            ("app/models.py".to_string(), "class User:\n    \"\"\"A user.\"\"\"\n    def name(self):\n        return 1\n\n    def _secret(self):\n        pass\n\ndef _hidden():\n    pass\n".to_string()),
+            ("README.md".to_string(), "# Title\n".to_string()),
+        ];
+        let reference = ApiReference::build(&files);
+
+        let names: Vec<&str> = reference.modules.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["app.models", "core::walker"]);
+
+        let walker = &reference.modules[1];
+        let item = &walker.items[0];
+        assert_eq!(item.signature, "pub struct Walker");
+        assert_eq!(item.summary.as_deref(), Some("Walks directories."));
+        let methods = walker.items.iter().find(|i| i.kind == "impl").unwrap();
+        assert_eq!(methods.members.len(), 1);
+        assert_eq!(methods.members[0].signature, "pub fn new(root: String) -> Self");
+        assert!(methods.members[0].id.starts_with("src/core/walker.rs#Walker.new@"));
+        assert_ne!(item.id, methods.id, "struct and impl are told apart by fingerprint");
+        assert!(!walker.items.iter().any(|i| i.name == "private" || i.name == "internal"));
+
+        let user = &reference.modules[0].items[0];
+        assert_eq!(user.summary.as_deref(), Some("A user."));
+        assert_eq!(user.members.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(), ["name"]);
+
+        let markdown = reference.to_markdown();
+        assert!(markdown.contains("## core::walker\n\n`src/core/walker.rs`\n\n- `pub struct Walker` - Walks directories.\n"));
+        assert!(markdown.contains("  - `pub fn new(root: String) -> Self` - Create a walker.\n"));
+        assert!(!markdown.contains("depth: 0"));
+    }
+}
@@ -12,10 +12,12 @@
 //! - AST results enhance (not replace) existing functionality
 
 use std::path::Path;
+use voyager_ast::heuristic;
 use voyager_ast::{
-    AdapterRegistry, AstError, Declaration, DeclarationKind, File as AstFile,
-    LanguageId, Visibility,
+    AdapterCapabilities, AdapterRegistry, AstError, Capability, ContextWindow, Declaration, DeclarationKind,
+    File as AstFile, LanguageId, Visibility,
 };
+use std::collections::BTreeMap;
 
 /// Bridge for AST-based code analysis
 pub struct AstBridge {
@@ -35,6 +37,34 @@ impl AstBridge {
         self.registry.supports(language)
     }
 
+    /// Languages with an adapter
+    pub fn languages(&self) -> Vec<LanguageId> {
+        self.registry.supported_languages()
+    }
+
+    /// What AST analysis extracts for a language (nothing without an adapter)
+    pub fn capabilities(&self, language: LanguageId) -> AdapterCapabilities {
+        self.registry.capabilities(language).unwrap_or_default()
+    }
+
+    /// Source languages among `paths` whose analysis lacks `capability`, with file counts
+    ///
+    /// Markup and data files are not source code and never count as lacking.
+    pub fn languages_lacking<'a>(
+        &self,
+        paths: impl IntoIterator<Item = &'a str>,
+        capability: Capability,
+    ) -> Vec<(LanguageId, usize)> {
+        let mut lacking: BTreeMap<LanguageId, usize> = BTreeMap::new();
+        for path in paths {
+            let language = Self::detect_language(Path::new(path));
+            if is_source(language) && !self.capabilities(language).supports(capability) {
+                *lacking.entry(language).or_default() += 1;
+            }
+        }
+        lacking.into_iter().collect()
+    }
+
     /// Detect language from file extension
     pub fn detect_language(path: &Path) -> LanguageId {
         path.extension()
@@ -68,19 +98,43 @@ impl AstBridge {
         }
     }
 
+    /// Analyze a file, falling back to the heuristic indexer without an adapter
+    ///
+    /// Heuristic results are low-confidence guesses (see
+    /// [`AstFile::is_heuristic`]); markup and data files are never indexed.
+    pub fn analyze_or_index(&self, source: &str, language: LanguageId) -> Option<AstFile> {
+        if self.supports(language) {
+            return self.analyze_file(source, language);
+        }
+        heuristic::indexes(language).then(|| heuristic::index(source, language))
+    }
+
+    /// Context lines around `start_line..=end_line` (1-indexed), cut at syntax node boundaries
+    pub fn context_window(
+        &self,
+        source: &str,
+        language: LanguageId,
+        start_line: usize,
+        end_line: usize,
+        context_lines: usize,
+    ) -> ContextWindow {
+        self.registry.context_window(source, language, start_line, end_line, context_lines, false)
+    }
+
     /// Extract "Stars" (significant symbols) from an AST file
     ///
     /// Stars are the key navigation points in the code that users and LLMs
     /// care about most. This translates AST declarations into the VO metaphor.
     pub fn extract_stars(&self, file: &AstFile) -> Vec<Star> {
         let mut stars = Vec::new();
+        let file_path = file.path.to_key();
 
         for decl in &file.declarations {
-            stars.push(Star::from_declaration(decl, &file.path));
+            stars.push(Star::from_declaration(decl, &file_path));
 
             // Also extract nested stars (methods, inner types)
             for child in &decl.children {
-                stars.push(Star::from_declaration(child, &file.path));
+                stars.push(Star::from_declaration(child, &file_path));
             }
         }
 
@@ -90,7 +144,7 @@ impl AstBridge {
     /// Get a summary of the file structure for context generation
     pub fn get_file_summary(&self, file: &AstFile) -> FileSummary {
         let mut summary = FileSummary {
-            path: file.path.clone(),
+            path: file.path.to_key(),
             language: file.language.name().to_string(),
             total_declarations: file.total_declarations(),
             import_count: file.imports.len(),
@@ -168,6 +222,8 @@ impl Star {
             DeclarationKind::Namespace => StarKind::Namespace,
             DeclarationKind::Impl => StarKind::Implementation,
             DeclarationKind::Macro => StarKind::Macro,
+            DeclarationKind::Element => StarKind::Element,
+            DeclarationKind::Section => StarKind::Section,
             DeclarationKind::Other => StarKind::Other,
         };
 
@@ -216,6 +272,8 @@ pub enum StarKind {
     Namespace,
     Implementation,
     Macro,
+    Element,
+    Section,
     Other,
 }
 
@@ -237,6 +295,8 @@ impl StarKind {
             StarKind::Namespace => "ns",
             StarKind::Implementation => "impl",
             StarKind::Macro => "macro",
+            StarKind::Element => "<>",
+            StarKind::Section => "§",
             StarKind::Other => "?",
         }
     }
@@ -258,6 +318,8 @@ impl StarKind {
             StarKind::Namespace => "namespace",
             StarKind::Implementation => "implementation",
             StarKind::Macro => "macro",
+            StarKind::Element => "element",
+            StarKind::Section => "section",
             StarKind::Other => "other",
         }
     }
@@ -301,6 +363,8 @@ fn declaration_kind_to_string(kind: DeclarationKind) -> String {
         DeclarationKind::Namespace => "namespace",
         DeclarationKind::Impl => "impl",
         DeclarationKind::Macro => "macro",
+        DeclarationKind::Element => "element",
+        DeclarationKind::Section => "section",
         DeclarationKind::Other => "other",
     }.to_string()
 }
@@ -316,6 +380,20 @@ fn visibility_to_string(vis: Visibility) -> String {
     }.to_string()
 }
 
+/// Whether a language is source code (rather than markup or data)
+fn is_source(language: LanguageId) -> bool {
+    !matches!(
+        language,
+        LanguageId::Unknown
+            | LanguageId::Html
+            | LanguageId::Css
+            | LanguageId::Json
+            | LanguageId::Yaml
+            | LanguageId::Toml
+            | LanguageId::Markdown
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -418,4 +496,12 @@ struct Baz {}
         assert_eq!(summary.total_declarations, 3);
         assert_eq!(summary.stars.len(), 3);
     }
+
+    #[test]
+    fn test_languages_lacking_capability() {
+        let bridge = AstBridge::new();
+        let paths = ["src/lib.rs", "cmd/main.go", "cmd/util.go", "README.md", "data.json"];
+        assert_eq!(bridge.languages_lacking(paths, Capability::Calls), vec![(LanguageId::Go, 2)]);
+        assert_eq!(bridge.capabilities(LanguageId::Go).missing().count(), Capability::ALL.len());
+    }
 }
@@ -0,0 +1,367 @@
+//! Built-in benchmark suite
+//!
+//! Times the hot paths of the pipeline on a deterministic synthetic corpus:
+//! per-language parsing, project indexing, token budgeting, and
+//! serialization. The same corpus backs the criterion benches in `benches/`;
+//! this module exists so `vo --bench` can emit a JSON report that CI compares
+//! run-over-run against a stored baseline (`--bench-baseline`).
+//!
+//! Timings are wall-clock and machine-dependent, so a baseline is only
+//! meaningful on the machine (or CI runner class) that produced it.
+
+use std::hint::black_box;
+use std::io;
+use std::path::Path;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use voyager_ast::{AstProvider, IndexOptions, LanguageId, TreeSitterProvider};
+
+use crate::budgeting::apply_token_budget;
+use crate::core::ast_bridge::AstBridge;
+use crate::core::models::{FileEntry, ProcessedFile};
+use crate::core::serialization::{PlusMinusSerializer, Serializer};
+use crate::lenses::LensManager;
+
+/// Report format version, bumped when case names or fields change
+pub const BENCH_REPORT_VERSION: u32 = 1;
+
+/// Default allowed slowdown before a case counts as a regression (20%)
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 0.20;
+
+/// Languages in the synthetic corpus (those with tree-sitter adapters)
+const CORPUS_LANGUAGES: &[(LanguageId, &str)] = &[
+    (LanguageId::Rust, "rs"),
+    (LanguageId::Python, "py"),
+    (LanguageId::TypeScript, "ts"),
+    (LanguageId::JavaScript, "js"),
+];
+
+/// Size of the corpus and number of timed iterations
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    /// Files generated per language
+    pub files_per_language: usize,
+    /// Functions generated per file
+    pub functions_per_file: usize,
+    /// Timed iterations per case (after one warm-up run)
+    pub iterations: usize,
+    /// Token budget for the budgeting case
+    pub token_budget: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            files_per_language: 20,
+            functions_per_file: 25,
+            iterations: 10,
+            token_budget: 50_000,
+        }
+    }
+}
+
+/// A deterministic multi-language source corpus
+#[derive(Debug, Clone)]
+pub struct SyntheticCorpus {
+    files: Vec<(LanguageId, FileEntry)>,
+}
+
+impl SyntheticCorpus {
+    /// Generate the corpus; identical inputs always give identical files
+    pub fn generate(files_per_language: usize, functions_per_file: usize) -> Self {
+        let mut files = Vec::new();
+        for &(language, ext) in CORPUS_LANGUAGES {
+            for index in 0..files_per_language {
+                let path = format!("{}/module_{:03}.{}", language.name(), index, ext);
+                let content = synthetic_source(language, index, functions_per_file);
+                files.push((language, FileEntry::new(path, content)));
+            }
+        }
+        Self { files }
+    }
+
+    /// All files as entries
+    pub fn entries(&self) -> impl Iterator<Item = &FileEntry> {
+        self.files.iter().map(|(_, entry)| entry)
+    }
+
+    /// Files of one language
+    pub fn files_for(&self, language: LanguageId) -> impl Iterator<Item = &FileEntry> {
+        self.files
+            .iter()
+            .filter(move |(lang, _)| *lang == language)
+            .map(|(_, entry)| entry)
+    }
+
+    /// Languages present in the corpus
+    pub fn languages(&self) -> impl Iterator<Item = LanguageId> {
+        CORPUS_LANGUAGES.iter().map(|(language, _)| *language)
+    }
+
+    /// Total content size in bytes
+    pub fn total_bytes(&self) -> u64 {
+        self.entries().map(|e| e.size).sum()
+    }
+
+    /// Number of files
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Check if the corpus is empty
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Write the corpus below `dir`
+    pub fn write_to(&self, dir: &Path) -> io::Result<()> {
+        for entry in self.entries() {
+            let path = dir.join(&entry.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, &entry.content)?;
+        }
+        Ok(())
+    }
+
+    /// Entries processed for budgeting and serialization
+    pub fn processed(&self) -> Vec<ProcessedFile> {
+        self.files
+            .iter()
+            .map(|(language, entry)| ProcessedFile::from_entry(entry, language.name(), 50))
+            .collect()
+    }
+}
+
+/// Generate one source file with `functions` functions
+fn synthetic_source(language: LanguageId, seed: usize, functions: usize) -> String {
+    let mut out = String::new();
+    for i in 0..functions {
+        let name = format!("compute_{}_{}", seed, i);
+        let body = match language {
+            LanguageId::Rust => format!(
+                "/// Computes step {i}\npub fn {name}(input: &[u64]) -> u64 {{\n    let mut acc = {seed};\n    for (idx, v) in input.iter().enumerate() {{\n        if idx % 2 == 0 {{\n            acc = acc.wrapping_add(*v * {i});\n        }} else {{\n            acc ^= v;\n        }}\n    }}\n    acc\n}}\n\n"
+            ),
+            LanguageId::Python => format!(
+                "def {name}(values):\n    \"\"\"Computes step {i}\"\"\"\n    acc = {seed}\n    for idx, v in enumerate(values):\n        if idx % 2 == 0:\n            acc += v * {i}\n        else:\n            acc ^= v\n    return acc\n\n\n"
+            ),
+            LanguageId::TypeScript => format!(
+                "/** Computes step {i} */\nexport function {name}(values: number[]): number {{\n  let acc = {seed};\n  values.forEach((v, idx) => {{\n    if (idx % 2 === 0) {{\n      acc += v * {i};\n    }} else {{\n      acc ^= v;\n    }}\n  }});\n  return acc;\n}}\n\n"
+            ),
+            _ => format!(
+                "/** Computes step {i} */\nfunction {name}(values) {{\n  let acc = {seed};\n  for (let idx = 0; idx < values.length; idx++) {{\n    acc = idx % 2 === 0 ? acc + values[idx] * {i} : acc ^ values[idx];\n  }}\n  return acc;\n}}\n\n"
+            ),
+        };
+        out.push_str(&body);
+    }
+    out
+}
+
+/// Timing for one benchmark case
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchCase {
+    /// Stable case name, e.g. `parse/rust`
+    pub name: String,
+    /// Timed iterations
+    pub iterations: usize,
+    /// Mean time per iteration (nanoseconds)
+    pub mean_ns: u64,
+    /// Fastest iteration (nanoseconds)
+    pub min_ns: u64,
+    /// Bytes processed per iteration
+    pub bytes: u64,
+}
+
+impl BenchCase {
+    /// Throughput in MiB/s based on the mean time
+    pub fn throughput_mib_s(&self) -> f64 {
+        if self.mean_ns == 0 {
+            return 0.0;
+        }
+        (self.bytes as f64 / (1024.0 * 1024.0)) / (self.mean_ns as f64 / 1e9)
+    }
+}
+
+/// A case that got slower than the baseline allows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchRegression {
+    pub name: String,
+    pub baseline_ns: u64,
+    pub current_ns: u64,
+    /// `current / baseline`
+    pub ratio: f64,
+}
+
+/// Results of a full suite run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub version: u32,
+    pub corpus_files: usize,
+    pub corpus_bytes: u64,
+    pub cases: Vec<BenchCase>,
+}
+
+impl BenchReport {
+    /// Find a case by name
+    pub fn case(&self, name: &str) -> Option<&BenchCase> {
+        self.cases.iter().find(|c| c.name == name)
+    }
+
+    /// Cases whose mean is more than `threshold` (e.g. 0.2 = 20%) slower than
+    /// in `baseline`; cases missing from either side are ignored
+    pub fn regressions(&self, baseline: &BenchReport, threshold: f64) -> Vec<BenchRegression> {
+        self.cases
+            .iter()
+            .filter_map(|current| {
+                let base = baseline.case(&current.name)?;
+                if base.mean_ns == 0 {
+                    return None;
+                }
+                let ratio = current.mean_ns as f64 / base.mean_ns as f64;
+                (ratio > 1.0 + threshold).then(|| BenchRegression {
+                    name: current.name.clone(),
+                    baseline_ns: base.mean_ns,
+                    current_ns: current.mean_ns,
+                    ratio,
+                })
+            })
+            .collect()
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// Parse a report previously written by [`BenchReport::to_json`]
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid benchmark report: {}", e))
+    }
+}
+
+/// Time `f` for `iterations` runs after one warm-up run
+fn measure<F: FnMut()>(name: &str, iterations: usize, bytes: u64, mut f: F) -> BenchCase {
+    f();
+    let iterations = iterations.max(1);
+    let mut total: u128 = 0;
+    let mut min = u128::MAX;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        f();
+        let elapsed = start.elapsed().as_nanos();
+        total += elapsed;
+        min = min.min(elapsed);
+    }
+    BenchCase {
+        name: name.to_string(),
+        iterations,
+        mean_ns: (total / iterations as u128) as u64,
+        min_ns: min as u64,
+        bytes,
+    }
+}
+
+/// Run every case and return the report
+pub fn run_suite(config: &BenchConfig) -> Result<BenchReport, String> {
+    let corpus = SyntheticCorpus::generate(config.files_per_language, config.functions_per_file);
+    let mut cases = Vec::new();
+
+    // Parse throughput per language
+    let bridge = AstBridge::new();
+    for language in corpus.languages() {
+        let files: Vec<&FileEntry> = corpus.files_for(language).collect();
+        let bytes = files.iter().map(|e| e.size).sum();
+        cases.push(measure(&format!("parse/{}", language.name()), config.iterations, bytes, || {
+            for entry in &files {
+                black_box(bridge.analyze_file(&entry.content, language));
+            }
+        }));
+    }
+
+    // Full-project index from disk
+    let dir = std::env::temp_dir().join(format!("pm_encoder-bench-{}", std::process::id()));
+    corpus
+        .write_to(&dir)
+        .map_err(|e| format!("Failed to write benchmark corpus: {}", e))?;
+    let provider = TreeSitterProvider::new();
+    let options = IndexOptions::default();
+    let index = measure("index/project", config.iterations, corpus.total_bytes(), || {
+        black_box(provider.index_project(&dir, &options).ok());
+    });
+    let _ = std::fs::remove_dir_all(&dir);
+    cases.push(index);
+
+    // Token budgeting
+    let lens_manager = LensManager::new();
+    let pairs: Vec<(String, String)> = corpus
+        .entries()
+        .map(|e| (e.path.clone(), e.content.clone()))
+        .collect();
+    cases.push(measure("budget/hybrid", config.iterations, corpus.total_bytes(), || {
+        black_box(apply_token_budget(pairs.clone(), config.token_budget, &lens_manager, "hybrid"));
+    }));
+
+    // Serialization
+    let processed = corpus.processed();
+    let serializer = PlusMinusSerializer::new();
+    cases.push(measure("serialize/plusminus", config.iterations, corpus.total_bytes(), || {
+        black_box(serializer.serialize_files(&processed));
+    }));
+
+    Ok(BenchReport {
+        version: BENCH_REPORT_VERSION,
+        corpus_files: corpus.len(),
+        corpus_bytes: corpus.total_bytes(),
+        cases,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corpus_is_deterministic() {
+        let a = SyntheticCorpus::generate(2, 3);
+        let b = SyntheticCorpus::generate(2, 3);
+        assert_eq!(a.len(), 2 * CORPUS_LANGUAGES.len());
+        let hashes = |c: &SyntheticCorpus| c.entries().map(|e| e.md5.clone()).collect::<Vec<_>>();
+        assert_eq!(hashes(&a), hashes(&b));
+        assert!(a.files_for(LanguageId::Rust).all(|e| e.path.ends_with(".rs")));
+    }
+
+    #[test]
+    fn test_corpus_parses() {
+        let corpus = SyntheticCorpus::generate(1, 2);
+        let bridge = AstBridge::new();
+        for language in corpus.languages() {
+            let entry = corpus.files_for(language).next().unwrap();
+            let file = bridge.analyze_file(&entry.content, language).unwrap();
+            assert!(!file.declarations.is_empty(), "{} corpus has no declarations", language.name());
+        }
+    }
+
+    #[test]
+    fn test_regressions_against_baseline() {
+        let case = |name: &str, mean_ns| BenchCase {
+            name: name.to_string(),
+            iterations: 1,
+            mean_ns,
+            min_ns: mean_ns,
+            bytes: 1024,
+        };
+        let report = |cases| BenchReport { version: BENCH_REPORT_VERSION, corpus_files: 1, corpus_bytes: 1024, cases };
+        let baseline = report(vec![case("parse/rust", 100), case("index/project", 100)]);
+        let current = report(vec![case("parse/rust", 150), case("index/project", 110), case("new/case", 5)]);
+
+        let regressions = current.regressions(&baseline, DEFAULT_REGRESSION_THRESHOLD);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].name, "parse/rust");
+        assert!((regressions[0].ratio - 1.5).abs() < 1e-9);
+
+        let parsed = BenchReport::from_json(&current.to_json()).unwrap();
+        assert_eq!(parsed, current);
+    }
+}
@@ -0,0 +1,400 @@
+//! Content-addressed blob store
+//!
+//! Stores compressed bytes under their SHA-256 so identical contents (the
+//! same file on several branches or worktrees, the same context built twice)
+//! occupy disk once. Cache layers address blobs either directly by
+//! [`BlobId`] or through named refs (`ref name → BlobId`), e.g. "parse of
+//! this source" or "context for this config and file set".
+//!
+//! The default location is per user, not per project, so all checkouts
+//! share it: `$PM_ENCODER_BLOB_DIR`, else `$XDG_CACHE_HOME/pm_encoder/blobs`,
+//! else `~/.cache/pm_encoder/blobs`.
+//!
+//! # Layout
+//! ```text
+//! <root>/objects/ab/abcdef…   compressed blob (zstd, or gzip without the `zstd` feature)
+//! <root>/refs/12/1234…        blob id (ref names are hashed)
+//! ```
+//!
+//! Reading a blob refreshes its mtime, so [`BlobStore::gc`] evicts by last
+//! use: first everything older than `max_age`, then the least recently used
+//! blobs until the store fits `max_bytes`. Refs to evicted blobs are removed.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use voyager_ast::{File, LanguageId, ParseCache, IR_VERSION};
+
+use super::codec::{self, Codec};
+
+/// Environment variable overriding the default store location
+pub const BLOB_DIR_ENV: &str = "PM_ENCODER_BLOB_DIR";
+
+/// SHA-256 of a blob's uncompressed content (lowercase hex)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct BlobId(String);
+
+impl BlobId {
+    /// Id of `bytes`
+    pub fn of(bytes: &[u8]) -> Self {
+        Self(hex(&Sha256::digest(bytes)))
+    }
+
+    /// Parse a hex id
+    pub fn parse(id: &str) -> Option<Self> {
+        let valid = id.len() == 64 && id.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+        valid.then(|| Self(id.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for BlobId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Eviction policy for [`BlobStore::gc`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GcPolicy {
+    /// Remove blobs not used for this long
+    pub max_age: Option<Duration>,
+    /// Then remove least recently used blobs until the store is this small
+    pub max_bytes: Option<u64>,
+}
+
+/// Size of the store
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct BlobStats {
+    pub blobs: usize,
+    /// Compressed bytes on disk
+    pub bytes: u64,
+    pub refs: usize,
+}
+
+/// Result of a GC run
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GcReport {
+    pub removed_blobs: usize,
+    pub freed_bytes: u64,
+    pub removed_refs: usize,
+    pub remaining: BlobStats,
+}
+
+/// A content-addressed store rooted at one directory
+#[derive(Debug, Clone)]
+pub struct BlobStore {
+    root: PathBuf,
+}
+
+/// Distinguishes temp files of concurrent writers in one process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+impl BlobStore {
+    /// Open (creating if needed) a store at `root`
+    pub fn open(root: impl Into<PathBuf>) -> Result<Self, String> {
+        let root = root.into();
+        for dir in ["objects", "refs"] {
+            fs::create_dir_all(root.join(dir))
+                .map_err(|e| format!("Failed to create blob store {}: {}", root.display(), e))?;
+        }
+        Ok(Self { root })
+    }
+
+    /// Open the per-user store (see module docs for the location)
+    pub fn open_default() -> Result<Self, String> {
+        Self::open(Self::default_dir())
+    }
+
+    /// Per-user store location
+    pub fn default_dir() -> PathBuf {
+        if let Some(dir) = std::env::var_os(BLOB_DIR_ENV) {
+            return PathBuf::from(dir);
+        }
+        let cache = std::env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache")))
+            .or_else(|| std::env::var_os("LOCALAPPDATA").map(PathBuf::from))
+            .unwrap_or_else(std::env::temp_dir);
+        cache.join("pm_encoder").join("blobs")
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Store `bytes`, returning their id (no-op if already present)
+    pub fn put(&self, bytes: &[u8]) -> Result<BlobId, String> {
+        let id = BlobId::of(bytes);
+        let path = self.object_path(&id);
+        if path.exists() {
+            touch(&path);
+            return Ok(id);
+        }
+
+        let compressed = codec::compress(bytes, Codec::preferred())
+            .map_err(|e| format!("Failed to compress blob: {}", e))?;
+        self.write_atomic(&path, &compressed)?;
+        Ok(id)
+    }
+
+    /// Content of blob `id`, if present and intact
+    ///
+    /// A blob whose content no longer matches its id is deleted.
+    pub fn get(&self, id: &BlobId) -> Option<Vec<u8>> {
+        let path = self.object_path(id);
+        let stored = fs::read(&path).ok()?;
+        let bytes = codec::decompress(&stored).ok().filter(|b| BlobId::of(b) == *id);
+        let Some(bytes) = bytes else {
+            let _ = fs::remove_file(&path);
+            return None;
+        };
+        touch(&path);
+        Some(bytes)
+    }
+
+    pub fn contains(&self, id: &BlobId) -> bool {
+        self.object_path(id).exists()
+    }
+
+    /// Store `bytes` and point ref `name` at them
+    pub fn put_ref(&self, name: &str, bytes: &[u8]) -> Result<BlobId, String> {
+        let id = self.put(bytes)?;
+        self.write_atomic(&self.ref_path(name), id.as_str().as_bytes())?;
+        Ok(id)
+    }
+
+    /// Content the ref `name` points at
+    pub fn get_ref(&self, name: &str) -> Option<Vec<u8>> {
+        let id = fs::read_to_string(self.ref_path(name)).ok()?;
+        self.get(&BlobId::parse(id.trim())?)
+    }
+
+    /// Count blobs, refs and bytes on disk
+    pub fn stats(&self) -> BlobStats {
+        let objects = self.objects();
+        BlobStats {
+            blobs: objects.len(),
+            bytes: objects.iter().map(|o| o.size).sum(),
+            refs: files_in(&self.root.join("refs")).len(),
+        }
+    }
+
+    /// Evict blobs according to `policy`
+    pub fn gc(&self, policy: &GcPolicy) -> GcReport {
+        let mut objects = self.objects();
+        // Oldest first
+        objects.sort_by_key(|o| o.used);
+        let now = SystemTime::now();
+        let mut total: u64 = objects.iter().map(|o| o.size).sum();
+        let mut report = GcReport::default();
+
+        for object in &objects {
+            let expired = policy.max_age
+                .is_some_and(|age| now.duration_since(object.used).unwrap_or_default() > age);
+            let over_size = policy.max_bytes.is_some_and(|max| total > max);
+            if !expired && !over_size {
+                continue;
+            }
+            if fs::remove_file(&object.path).is_ok() {
+                report.removed_blobs += 1;
+                report.freed_bytes += object.size;
+                total -= object.size;
+            }
+        }
+
+        // Drop refs whose blob is gone
+        for path in files_in(&self.root.join("refs")) {
+            let dangling = fs::read_to_string(&path)
+                .ok()
+                .and_then(|id| BlobId::parse(id.trim()))
+                .is_none_or(|id| !self.contains(&id));
+            if dangling && fs::remove_file(&path).is_ok() {
+                report.removed_refs += 1;
+            }
+        }
+
+        report.remaining = self.stats();
+        report
+    }
+
+    fn object_path(&self, id: &BlobId) -> PathBuf {
+        self.root.join("objects").join(&id.as_str()[..2]).join(id.as_str())
+    }
+
+    fn ref_path(&self, name: &str) -> PathBuf {
+        let key = BlobId::of(name.as_bytes());
+        self.root.join("refs").join(&key.as_str()[..2]).join(key.as_str())
+    }
+
+    /// Write via a temp file and rename, so readers never see partial blobs
+    fn write_atomic(&self, path: &Path, bytes: &[u8]) -> Result<(), String> {
+        let parent = path.parent().unwrap_or(&self.root);
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        let temp = parent.join(format!(
+            ".tmp-{}-{}",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp, bytes)
+            .and_then(|_| fs::rename(&temp, path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&temp);
+                format!("Failed to write {}: {}", path.display(), e)
+            })
+    }
+
+    fn objects(&self) -> Vec<StoredObject> {
+        files_in(&self.root.join("objects"))
+            .into_iter()
+            .filter_map(|path| {
+                let meta = fs::metadata(&path).ok()?;
+                Some(StoredObject {
+                    size: meta.len(),
+                    used: meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    path,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Index cache: parse results keyed by IR version, language and source
+///
+/// Pass the store as [`IndexOptions::parse_cache`](voyager_ast::IndexOptions)
+/// so files with content seen in any checkout are not parsed again.
+impl ParseCache for BlobStore {
+    fn get(&self, language: LanguageId, source: &str) -> Option<File> {
+        serde_json::from_slice(&self.get_ref(&parse_ref(language, source))?).ok()
+    }
+
+    fn put(&self, language: LanguageId, source: &str, file: &File) {
+        if let Ok(json) = serde_json::to_vec(file) {
+            let _ = self.put_ref(&parse_ref(language, source), &json);
+        }
+    }
+}
+
+fn parse_ref(language: LanguageId, source: &str) -> String {
+    format!("parse:{}:{}:{}", IR_VERSION, language.name(), BlobId::of(source.as_bytes()))
+}
+
+struct StoredObject {
+    path: PathBuf,
+    size: u64,
+    used: SystemTime,
+}
+
+/// Files in the two-level fan-out below `dir` (temp files excluded)
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    let Ok(shards) = fs::read_dir(dir) else { return Vec::new() };
+    shards
+        .flatten()
+        .filter_map(|shard| fs::read_dir(shard.path()).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .filter(|p| !p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(".tmp-")))
+        .collect()
+}
+
+/// Mark a blob as recently used (best effort)
+fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().write(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn age(store: &BlobStore, id: &BlobId, secs: u64) {
+        let file = fs::File::options().write(true).open(store.object_path(id)).unwrap();
+        file.set_modified(SystemTime::now() - Duration::from_secs(secs)).unwrap();
+    }
+
+    #[test]
+    fn test_put_get_dedup() {
+        let temp = TempDir::new().unwrap();
+        let store = BlobStore::open(temp.path()).unwrap();
+
+        let a = store.put(b"fn main() {}").unwrap();
+        let b = store.put(b"fn main() {}").unwrap();
+        assert_eq!(a, b);
+        assert_eq!(store.stats().blobs, 1);
+        assert_eq!(store.get(&a).unwrap(), b"fn main() {}");
+        assert!(store.get(&BlobId::of(b"missing")).is_none());
+
+        store.put_ref("context:demo", b"fn main() {}").unwrap();
+        assert_eq!(store.stats().blobs, 1, "refs share the blob");
+        assert_eq!(store.get_ref("context:demo").unwrap(), b"fn main() {}");
+        assert!(store.get_ref("context:other").is_none());
+    }
+
+    #[test]
+    fn test_corrupt_blob_is_dropped() {
+        let temp = TempDir::new().unwrap();
+        let store = BlobStore::open(temp.path()).unwrap();
+        let id = store.put(b"original").unwrap();
+        fs::write(store.object_path(&id), b"garbage").unwrap();
+        assert!(store.get(&id).is_none());
+        assert!(!store.contains(&id));
+    }
+
+    #[test]
+    fn test_gc_by_age_then_size() {
+        let temp = TempDir::new().unwrap();
+        let store = BlobStore::open(temp.path()).unwrap();
+        let old = store.put_ref("old", b"old blob").unwrap();
+        let mid = store.put(&[b'm'; 4096]).unwrap();
+        let new = store.put(&[b'n'; 4096]).unwrap();
+        age(&store, &old, 10 * 86_400);
+        age(&store, &mid, 3_600);
+
+        let report = store.gc(&GcPolicy { max_age: Some(Duration::from_secs(86_400)), max_bytes: None });
+        assert_eq!((report.removed_blobs, report.removed_refs), (1, 1));
+        assert!(!store.contains(&old));
+
+        let one_blob = fs::metadata(store.object_path(&new)).unwrap().len();
+        let report = store.gc(&GcPolicy { max_age: None, max_bytes: Some(one_blob) });
+        assert_eq!(report.removed_blobs, 1);
+        assert!(!store.contains(&mid), "least recently used goes first");
+        assert!(store.contains(&new));
+        assert_eq!(report.remaining.blobs, 1);
+    }
+
+    #[test]
+    fn test_parse_cache_round_trip() {
+        use voyager_ast::{AstProvider, TreeSitterProvider};
+
+        let temp = TempDir::new().unwrap();
+        let store = BlobStore::open(temp.path()).unwrap();
+        let source = "pub fn cached() {}\n";
+        assert!(ParseCache::get(&store, LanguageId::Rust, source).is_none());
+
+        let file = TreeSitterProvider::new().parse_file(source, LanguageId::Rust).unwrap();
+        ParseCache::put(&store, LanguageId::Rust, source, &file);
+        let cached = ParseCache::get(&store, LanguageId::Rust, source).unwrap();
+        assert_eq!(cached.declarations[0].name, "cached");
+        assert!(ParseCache::get(&store, LanguageId::Python, source).is_none());
+    }
+
+    #[test]
+    fn test_blob_id_parse() {
+        let id = BlobId::of(b"x");
+        assert_eq!(BlobId::parse(id.as_str()), Some(id));
+        assert!(BlobId::parse("../etc/passwd").is_none());
+    }
+}
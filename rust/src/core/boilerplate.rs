@@ -0,0 +1,529 @@
+//! Boilerplate - Collapse license headers, import preambles and generated-code banners
+//!
+//! Many codebases open every file with the same 30-line license header and a
+//! long run of imports, and generated sources carry a banner on top of that.
+//! [`BoilerplateStripper`] replaces each of these blocks with a one-line
+//! marker in the file's own comment syntax:
+//!
+//! ```text
+//! // [boilerplate: license header, 30 lines, same in 47 files]
+//! // [boilerplate: import preamble, 14 imports]
+//! ```
+//!
+//! Only the top of a file is considered: an optional shebang, then leading
+//! comment blocks, then preamble lines (`package ...`) and imports. A comment
+//! block is boilerplate when it contains a license or generated-code marker
+//! phrase; other leading comments (module docs) are kept.
+//!
+//! Comment syntax and import prefixes come from per-language [`Dictionary`]
+//! entries keyed by file extension. Projects extend the defaults from the
+//! `boilerplate` section of `.pm_encoder_config.json`:
+//!
+//! ```json
+//! "boilerplate": {
+//!   "license_markers": ["Acme Corp Confidential"],
+//!   "min_import_lines": 8,
+//!   "languages": { "proto": { "line_comments": ["//"], "import_prefixes": ["import "] } }
+//! }
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Phrases that mark a comment block as a license header (matched case-insensitively)
+const LICENSE_MARKERS: &[&str] = &[
+    "copyright",
+    "spdx-license-identifier",
+    "licensed under",
+    "all rights reserved",
+    "permission is hereby granted",
+    "license, version",
+    "general public license",
+];
+
+/// Phrases that mark a comment block as a generated-code banner (matched case-insensitively)
+pub(crate) const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "do not edit",
+    "code generated by",
+    "auto-generated",
+    "autogenerated",
+    "automatically generated",
+];
+
+const C_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+
+/// Default dictionaries: (extensions, line comments, block comment, import prefixes, preamble prefixes)
+#[allow(clippy::type_complexity)]
+const DEFAULT_DICTIONARIES: &[(&[&str], &[&str], Option<(&str, &str)>, &[&str], &[&str])] = &[
+    (&["java", "kt", "kts", "scala", "groovy"], &["//"], C_BLOCK, &["import "], &["package "]),
+    (&["rs"], &["//"], C_BLOCK, &["use ", "pub use ", "extern crate "], &[]),
+    (&["go"], &["//"], C_BLOCK, &["import "], &["package "]),
+    (&["js", "jsx", "mjs", "cjs", "ts", "tsx"], &["//"], C_BLOCK, &["import "], &["\"use strict\"", "'use strict'"]),
+    (&["c", "h", "cc", "cpp", "cxx", "hpp", "hh", "m", "mm"], &["//"], C_BLOCK, &["#include ", "#import "], &["#pragma once"]),
+    (&["cs"], &["//"], C_BLOCK, &["using "], &[]),
+    (&["swift"], &["//"], C_BLOCK, &["import "], &[]),
+    (&["dart"], &["//"], C_BLOCK, &["import ", "export ", "part "], &["library "]),
+    (&["php"], &["//", "#"], C_BLOCK, &["use ", "require", "include"], &["<?php", "namespace ", "declare("]),
+    (&["py", "pyi"], &["#"], None, &["import ", "from "], &[]),
+    (&["rb"], &["#"], None, &["require ", "require_relative "], &[]),
+    (&["sh", "bash", "zsh"], &["#"], None, &["source "], &[]),
+    (&["pl", "pm"], &["#"], None, &["use "], &["package "]),
+    (&["r"], &["#"], None, &["library(", "require("], &[]),
+    (&["yaml", "yml", "toml"], &["#"], None, &[], &[]),
+    (&["sql"], &["--"], C_BLOCK, &[], &[]),
+    (&["lua"], &["--"], None, &[], &[]),
+    (&["hs"], &["--"], Some(("{-", "-}")), &["import "], &["module "]),
+    (&["css"], &[], C_BLOCK, &["@import "], &[]),
+    (&["scss", "less"], &["//"], C_BLOCK, &["@import ", "@use "], &[]),
+    (&["html", "htm", "xml", "vue", "svelte"], &[], Some(("<!--", "-->")), &[], &["<?xml", "<!doctype"]),
+];
+
+/// Kind of collapsed block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoilerplateKind {
+    LicenseHeader,
+    GeneratedBanner,
+    Imports,
+}
+
+impl BoilerplateKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::LicenseHeader => "license header",
+            Self::GeneratedBanner => "generated-code banner",
+            Self::Imports => "import preamble",
+        }
+    }
+}
+
+/// Comment syntax and import conventions of one language
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Dictionary {
+    /// Line comment prefixes (`//`, `#`)
+    pub line_comments: Vec<String>,
+    /// Block comment delimiters (`["/*", "*/"]`)
+    pub block_comment: Option<(String, String)>,
+    /// Prefixes of import statements (`import `, `use `)
+    pub import_prefixes: Vec<String>,
+    /// Lines kept verbatim between the header and the imports (`package `)
+    pub preamble_prefixes: Vec<String>,
+}
+
+impl Dictionary {
+    fn is_line_comment(&self, line: &str) -> bool {
+        self.line_comments.iter().any(|p| line.starts_with(p.as_str()))
+    }
+
+    fn is_import(&self, line: &str) -> bool {
+        self.import_prefixes.iter().any(|p| line.starts_with(p.as_str()))
+    }
+
+    fn is_preamble(&self, line: &str) -> bool {
+        let lower = line.to_lowercase();
+        self.preamble_prefixes.iter().any(|p| lower.starts_with(&p.to_lowercase()))
+    }
+
+    /// Wrap marker text in this language's comment syntax
+    pub(crate) fn comment(&self, text: &str) -> String {
+        match (self.line_comments.first(), &self.block_comment) {
+            (Some(prefix), _) => format!("{} {}", prefix, text),
+            (None, Some((open, close))) => format!("{} {} {}", open, text, close),
+            (None, None) => text.to_string(),
+        }
+    }
+
+    /// Merge a user dictionary into this one
+    fn extend(&mut self, other: &Dictionary) {
+        self.line_comments.extend(other.line_comments.iter().cloned());
+        self.import_prefixes.extend(other.import_prefixes.iter().cloned());
+        self.preamble_prefixes.extend(other.preamble_prefixes.iter().cloned());
+        if other.block_comment.is_some() {
+            self.block_comment = other.block_comment.clone();
+        }
+    }
+}
+
+/// User extensions to the default rules (the `boilerplate` config section)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BoilerplateExtension {
+    /// Collapse boilerplate without `--strip-boilerplate` (default: true)
+    pub enabled: bool,
+    /// Extra license marker phrases
+    pub license_markers: Vec<String>,
+    /// Extra generated-code marker phrases
+    pub generated_markers: Vec<String>,
+    /// Override the minimum comment block size
+    pub min_header_lines: Option<usize>,
+    /// Override the minimum number of imports worth collapsing
+    pub min_import_lines: Option<usize>,
+    /// Per-extension dictionaries, merged into the defaults (`"java": {...}`)
+    pub languages: BTreeMap<String, Dictionary>,
+}
+
+impl Default for BoilerplateExtension {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            license_markers: Vec::new(),
+            generated_markers: Vec::new(),
+            min_header_lines: None,
+            min_import_lines: None,
+            languages: BTreeMap::new(),
+        }
+    }
+}
+
+/// What counts as boilerplate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoilerplateRules {
+    /// License marker phrases (lowercase)
+    pub license_markers: Vec<String>,
+    /// Generated-code marker phrases (lowercase)
+    pub generated_markers: Vec<String>,
+    /// Comment blocks shorter than this are kept
+    pub min_header_lines: usize,
+    /// Import runs with fewer statements than this are kept
+    pub min_import_lines: usize,
+    /// Dictionaries by lowercase file extension
+    pub languages: BTreeMap<String, Dictionary>,
+}
+
+impl Default for BoilerplateRules {
+    fn default() -> Self {
+        let mut languages = BTreeMap::new();
+        for (extensions, line_comments, block_comment, imports, preamble) in DEFAULT_DICTIONARIES {
+            let dictionary = Dictionary {
+                line_comments: line_comments.iter().map(|s| s.to_string()).collect(),
+                block_comment: block_comment.map(|(open, close)| (open.to_string(), close.to_string())),
+                import_prefixes: imports.iter().map(|s| s.to_string()).collect(),
+                preamble_prefixes: preamble.iter().map(|s| s.to_string()).collect(),
+            };
+            for extension in *extensions {
+                languages.insert(extension.to_string(), dictionary.clone());
+            }
+        }
+        Self {
+            license_markers: LICENSE_MARKERS.iter().map(|s| s.to_string()).collect(),
+            generated_markers: GENERATED_MARKERS.iter().map(|s| s.to_string()).collect(),
+            min_header_lines: 3,
+            min_import_lines: 5,
+            languages,
+        }
+    }
+}
+
+impl BoilerplateRules {
+    /// Apply user extensions on top of these rules
+    pub fn extend(&mut self, extension: &BoilerplateExtension) {
+        self.license_markers.extend(extension.license_markers.iter().map(|s| s.to_lowercase()));
+        self.generated_markers.extend(extension.generated_markers.iter().map(|s| s.to_lowercase()));
+        if let Some(lines) = extension.min_header_lines {
+            self.min_header_lines = lines;
+        }
+        if let Some(lines) = extension.min_import_lines {
+            self.min_import_lines = lines;
+        }
+        for (extension, dictionary) in &extension.languages {
+            self.languages
+                .entry(extension.trim_start_matches('.').to_lowercase())
+                .or_default()
+                .extend(dictionary);
+        }
+    }
+
+    /// Dictionary for a path, by extension
+    pub fn dictionary(&self, path: &str) -> Option<&Dictionary> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+        self.languages.get(&extension)
+    }
+
+    fn classify_comment(&self, text: &str) -> Option<BoilerplateKind> {
+        let lower = text.to_lowercase();
+        if self.generated_markers.iter().any(|m| lower.contains(m.as_str())) {
+            Some(BoilerplateKind::GeneratedBanner)
+        } else if self.license_markers.iter().any(|m| lower.contains(m.as_str())) {
+            Some(BoilerplateKind::LicenseHeader)
+        } else {
+            None
+        }
+    }
+}
+
+/// One collapsed block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elision {
+    pub kind: BoilerplateKind,
+    /// First collapsed line (1-indexed)
+    pub start_line: usize,
+    /// Number of collapsed lines
+    pub lines: usize,
+    /// Import statements (imports only)
+    pub statements: usize,
+    /// Files observed with an identical block (1 if none were observed)
+    pub shared_by: usize,
+}
+
+/// A file with its boilerplate collapsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stripped {
+    pub content: String,
+    pub elisions: Vec<Elision>,
+}
+
+/// A boilerplate block found at the top of a file (0-indexed, end exclusive)
+struct Block {
+    kind: BoilerplateKind,
+    start: usize,
+    end: usize,
+    statements: usize,
+    fingerprint: u64,
+}
+
+/// Collapses boilerplate, counting identical blocks across observed files
+#[derive(Debug, Clone, Default)]
+pub struct BoilerplateStripper {
+    rules: BoilerplateRules,
+    shared: HashMap<u64, usize>,
+}
+
+impl BoilerplateStripper {
+    pub fn new(rules: BoilerplateRules) -> Self {
+        Self { rules, shared: HashMap::new() }
+    }
+
+    pub fn rules(&self) -> &BoilerplateRules {
+        &self.rules
+    }
+
+    /// Record a file's blocks so markers can say how many files share them
+    pub fn observe(&mut self, path: &str, content: &str) {
+        let Some(dictionary) = self.rules.dictionary(path) else { return };
+        let lines: Vec<&str> = content.lines().collect();
+        for block in scan(&self.rules, dictionary, &lines) {
+            *self.shared.entry(block.fingerprint).or_insert(0) += 1;
+        }
+    }
+
+    /// Collapse the boilerplate at the top of a file (`None` if there is none)
+    pub fn strip(&self, path: &str, content: &str) -> Option<Stripped> {
+        let dictionary = self.rules.dictionary(path)?;
+        let lines: Vec<&str> = content.lines().collect();
+        let blocks = scan(&self.rules, dictionary, &lines);
+        if blocks.is_empty() {
+            return None;
+        }
+
+        let mut out: Vec<String> = Vec::with_capacity(lines.len());
+        let mut elisions = Vec::with_capacity(blocks.len());
+        let mut cursor = 0;
+        for block in blocks {
+            out.extend(lines[cursor..block.start].iter().map(|l| l.to_string()));
+            let elision = Elision {
+                kind: block.kind,
+                start_line: block.start + 1,
+                lines: block.end - block.start,
+                statements: block.statements,
+                shared_by: self.shared.get(&block.fingerprint).copied().unwrap_or(1).max(1),
+            };
+            out.push(dictionary.comment(&marker(&elision)));
+            elisions.push(elision);
+            cursor = block.end;
+        }
+        out.extend(lines[cursor..].iter().map(|l| l.to_string()));
+
+        let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut stripped = out.join(newline);
+        if content.ends_with('\n') {
+            stripped.push_str(newline);
+        }
+        Some(Stripped { content: stripped, elisions })
+    }
+}
+
+/// Marker text for a collapsed block
+fn marker(elision: &Elision) -> String {
+    let size = match elision.kind {
+        BoilerplateKind::Imports => format!("{} imports", elision.statements),
+        _ => format!("{} lines", elision.lines),
+    };
+    if elision.shared_by > 1 {
+        format!("[boilerplate: {}, {}, same in {} files]", elision.kind.label(), size, elision.shared_by)
+    } else {
+        format!("[boilerplate: {}, {}]", elision.kind.label(), size)
+    }
+}
+
+/// Find the boilerplate blocks at the top of a file, in order
+fn scan(rules: &BoilerplateRules, dictionary: &Dictionary, lines: &[&str]) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut i = usize::from(lines.first().is_some_and(|l| l.starts_with("#!")));
+
+    // Leading comment blocks, separated by blank lines
+    loop {
+        let start = skip_blank(lines, i);
+        let Some(end) = comment_block(dictionary, lines, start) else { break };
+        let Some(kind) = rules.classify_comment(&lines[start..end].join("\n")) else { break };
+        if end - start >= rules.min_header_lines.max(1) {
+            blocks.push(Block { kind, start, end, statements: 0, fingerprint: fingerprint(kind, &lines[start..end]) });
+        }
+        i = end;
+    }
+
+    // Preamble lines and plain comments before the imports are kept
+    let mut start = i;
+    while start < lines.len() {
+        let line = lines[start].trim();
+        if line.is_empty() || dictionary.is_preamble(line) || dictionary.is_line_comment(line) {
+            start += 1;
+        } else {
+            break;
+        }
+    }
+
+    // Imports, possibly spanning lines, with blank lines and comments in between
+    let mut j = start;
+    let mut end = start;
+    let mut statements = 0;
+    while j < lines.len() {
+        let line = lines[j].trim();
+        if dictionary.is_import(line) {
+            j = statement_end(lines, j);
+            end = j;
+            statements += 1;
+        } else if line.is_empty() || dictionary.is_line_comment(line) {
+            j += 1;
+        } else {
+            break;
+        }
+    }
+    if statements > 0 && statements >= rules.min_import_lines {
+        let kind = BoilerplateKind::Imports;
+        blocks.push(Block { kind, start, end, statements, fingerprint: fingerprint(kind, &lines[start..end]) });
+    }
+
+    blocks
+}
+
+fn skip_blank(lines: &[&str], mut i: usize) -> usize {
+    while i < lines.len() && lines[i].trim().is_empty() {
+        i += 1;
+    }
+    i
+}
+
+/// End (exclusive) of the comment block starting at `start`, if one does
+fn comment_block(dictionary: &Dictionary, lines: &[&str], start: usize) -> Option<usize> {
+    let first = lines.get(start)?.trim();
+    if let Some((open, close)) = &dictionary.block_comment {
+        if let Some(rest) = first.strip_prefix(open.as_str()) {
+            if rest.contains(close.as_str()) {
+                return Some(start + 1);
+            }
+            let closing = lines[start + 1..].iter().position(|l| l.contains(close.as_str()))?;
+            return Some(start + 1 + closing + 1);
+        }
+    }
+    if dictionary.is_line_comment(first) {
+        let run = lines[start..].iter().take_while(|l| dictionary.is_line_comment(l.trim())).count();
+        return Some(start + run);
+    }
+    None
+}
+
+/// End (exclusive) of the statement starting at `start`, following open brackets
+fn statement_end(lines: &[&str], start: usize) -> usize {
+    let mut depth = 0i32;
+    for (offset, line) in lines[start..].iter().enumerate() {
+        for c in line.chars() {
+            match c {
+                '(' | '{' | '[' => depth += 1,
+                ')' | '}' | ']' => depth -= 1,
+                _ => {}
+            }
+        }
+        if depth <= 0 {
+            return start + offset + 1;
+        }
+    }
+    lines.len()
+}
+
+/// Identity of a block across files, ignoring indentation and trailing spaces
+fn fingerprint(kind: BoilerplateKind, lines: &[&str]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    kind.hash(&mut hasher);
+    for line in lines {
+        line.trim().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LICENSE: &str = "/*\n * Copyright 2024 Acme Corp.\n *\n * Licensed under the Apache License, Version 2.0\n */\n";
+
+    fn java(body: &str) -> String {
+        format!(
+            "{}package com.acme;\n\nimport java.util.List;\nimport java.util.Map;\nimport java.util.Set;\n\
+             import java.io.File;\nimport java.io.IOException;\n\n{}",
+            LICENSE, body
+        )
+    }
+
+    #[test]
+    fn test_collapses_license_and_imports_with_shared_counts() {
+        let mut stripper = BoilerplateStripper::new(BoilerplateRules::default());
+        let a = java("public class A {}\n");
+        let b = java("public class B {}\n");
+        stripper.observe("A.java", &a);
+        stripper.observe("B.java", &b);
+
+        let stripped = stripper.strip("A.java", &a).unwrap();
+        assert_eq!(
+            stripped.content,
+            "// [boilerplate: license header, 5 lines, same in 2 files]\npackage com.acme;\n\n\
+             // [boilerplate: import preamble, 5 imports, same in 2 files]\n\npublic class A {}\n"
+        );
+        assert_eq!(stripped.elisions.len(), 2);
+        assert_eq!(stripped.elisions[1].start_line, 8);
+
+        // Short import runs and ordinary doc comments are kept
+        let doc = "//! Parser module\n//! Builds the tree\n//! from tokens\nuse std::fmt;\n\nfn main() {}\n";
+        assert!(stripper.strip("src/parser.rs", doc).is_none());
+        assert!(stripper.strip("notes.txt", LICENSE).is_none());
+    }
+
+    #[test]
+    fn test_generated_banner_multiline_imports_and_extensions() {
+        let go = "// Code generated by protoc-gen-go. DO NOT EDIT.\n// versions:\n// \tprotoc v4\n\npackage api\n\n\
+                  import (\n\t\"fmt\"\n\t\"io\"\n)\n\nfunc F() {}\n";
+        let mut rules = BoilerplateRules::default();
+        rules.extend(&BoilerplateExtension {
+            min_import_lines: Some(1),
+            license_markers: vec!["Acme Confidential".to_string()],
+            languages: BTreeMap::from([(
+                "proto".to_string(),
+                Dictionary { line_comments: vec!["//".to_string()], ..Default::default() },
+            )]),
+            ..Default::default()
+        });
+        let stripper = BoilerplateStripper::new(rules);
+
+        let stripped = stripper.strip("api/api.pb.go", go).unwrap();
+        assert!(stripped.content.starts_with("// [boilerplate: generated-code banner, 3 lines]\n\npackage api\n"));
+        assert!(stripped.content.contains("// [boilerplate: import preamble, 1 imports]\n\nfunc F() {}"));
+
+        let proto = "// ACME CONFIDENTIAL\n// Internal use only\n// Do not share\nsyntax = \"proto3\";\n";
+        let stripped = stripper.strip("api/api.proto", proto).unwrap();
+        assert_eq!(stripped.content, "// [boilerplate: license header, 3 lines]\nsyntax = \"proto3\";\n");
+    }
+}
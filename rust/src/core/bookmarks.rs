@@ -0,0 +1,303 @@
+//! Bookmarks - Named zoom targets saved per project
+//!
+//! The important entry points of a project are marked once and referred to
+//! by name afterwards, wherever a zoom target is accepted:
+//!
+//! ```text
+//! vo --bookmark add:payment-flow=function=process_payment
+//! vo --zoom bookmark=payment-flow
+//! ```
+//!
+//! Bookmarks are stored in `.pm_encoder/bookmarks.json` next to the zoom
+//! sessions. Paths in them are relative to the project root, so the file can
+//! be committed and shared. Budgeted contexts keep bookmarked files ahead of
+//! their tier peers ([`BookmarkPrioritizer`]), whichever lens is active.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::error::{EncoderError, Result};
+use super::json_store;
+use super::outline::{find_section, split_section_target};
+use super::sandbox::PathSandbox;
+use super::search::SymbolResolver;
+use super::zoom::ZoomTarget;
+use crate::budgeting::{FilePrioritizer, PathBoost, PriorityInput};
+
+/// Priority boost for files holding a bookmarked symbol
+pub const BOOKMARK_BOOST: i32 = 60;
+
+/// Zoom target prefix naming a bookmark (`bookmark=payment-flow`)
+pub const BOOKMARK_PREFIX: &str = "bookmark=";
+
+/// A saved zoom target
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// Zoom target in `type=value` form
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Unix seconds
+    #[serde(default)]
+    pub created: u64,
+}
+
+/// Where a bookmark points today
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedBookmark {
+    pub name: String,
+    pub target: String,
+    /// File relative to the project root
+    pub path: String,
+    /// First and last line (1-indexed), absent for whole files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<(usize, usize)>,
+}
+
+/// Bookmarks of one project, by name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookmarkStore {
+    #[serde(default = "json_store::default_version")]
+    pub version: String,
+    #[serde(default)]
+    pub bookmarks: BTreeMap<String, Bookmark>,
+    /// Runtime-only: path to store file (not persisted)
+    #[serde(skip)]
+    store_path: Option<PathBuf>,
+}
+
+impl BookmarkStore {
+    /// Default bookmark file location (project-local)
+    pub fn default_path(project_root: &Path) -> PathBuf {
+        json_store::store_path(project_root, "bookmarks.json")
+    }
+
+    /// Load bookmarks from JSON file, or create empty store
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut store: Self = json_store::load(path)?;
+        store.store_path = Some(path.to_path_buf());
+        Ok(store)
+    }
+
+    /// Save bookmarks to JSON file
+    pub fn save(&self) -> Result<()> {
+        json_store::save(self.store_path.as_deref(), self)
+    }
+
+    /// Save `target` as `name`, replacing an existing bookmark of that name
+    ///
+    /// Names are letters, digits, `-`, `_` and `.`. Paths in the target must
+    /// stay inside `root`.
+    pub fn add(&mut self, root: &Path, name: &str, target: &str, note: Option<String>) -> Result<()> {
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || "-_.".contains(c)) {
+            return Err(EncoderError::invalid_config(format!(
+                "invalid bookmark name '{}' (use letters, digits, '-', '_' and '.')",
+                name
+            )));
+        }
+        if target.starts_with(BOOKMARK_PREFIX) {
+            return Err(EncoderError::invalid_config("a bookmark cannot point at another bookmark"));
+        }
+        let parsed = ZoomTarget::parse(target)?;
+        if let Some(path) = target_path(&parsed) {
+            PathSandbox::new(root)?.resolve_relative(path)?;
+        }
+        let created = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.bookmarks.insert(name.to_string(), Bookmark { target: target.to_string(), note, created });
+        Ok(())
+    }
+
+    /// Remove a bookmark, returning whether it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.bookmarks.remove(name).is_some()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Bookmark> {
+        self.bookmarks.get(name)
+    }
+
+    /// Bookmark names, sorted
+    pub fn names(&self) -> Vec<String> {
+        self.bookmarks.keys().cloned().collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bookmarks.is_empty()
+    }
+
+    /// Locate a bookmark's target in the project at `root`
+    ///
+    /// Functions and classes are looked up by name, so a bookmark follows
+    /// its symbol when it moves. Module targets and sections without a
+    /// document cannot be pinned to one file and fail to resolve.
+    pub fn resolve(&self, name: &str, root: &Path, resolver: &SymbolResolver) -> Result<ResolvedBookmark> {
+        let bookmark = self.get(name).ok_or_else(|| missing(name))?;
+        let unresolved = |reason: String| EncoderError::invalid_config(format!("bookmark '{}': {}", name, reason));
+        let (path, lines) = match ZoomTarget::parse(&bookmark.target)? {
+            ZoomTarget::Function(symbol) => {
+                let location = resolver.find_function(&symbol, root).map_err(unresolved)?;
+                (location.path, Some((location.start_line, location.end_line)))
+            }
+            ZoomTarget::Class(symbol) => {
+                let location = resolver.find_class(&symbol, root).map_err(unresolved)?;
+                (location.path, Some((location.start_line, location.end_line)))
+            }
+            ZoomTarget::File { path, start_line, end_line } => {
+                read(root, &path)?;
+                let lines = start_line.map(|start| (start, end_line.unwrap_or(start)));
+                (path, lines)
+            }
+            ZoomTarget::Anchor(anchor) => {
+                let decl = anchor
+                    .locate(&read(root, &anchor.path)?)
+                    .ok_or_else(|| unresolved(format!("{} no longer exists", anchor)))?;
+                (anchor.path.clone(), Some((decl.span.start_line, decl.span.end_line)))
+            }
+            ZoomTarget::Section(section) => match split_section_target(&section) {
+                (Some(doc), heading) => {
+                    let location = find_section(&read(root, doc)?, heading)
+                        .ok_or_else(|| unresolved(format!("section '{}' not found in {}", heading, doc)))?;
+                    (doc.to_string(), Some((location.start_line, location.end_line)))
+                }
+                (None, _) => return Err(unresolved("sections without a document cannot be located".to_string())),
+            },
+            ZoomTarget::Module(_) => return Err(unresolved("module targets cannot be located".to_string())),
+        };
+        Ok(ResolvedBookmark { name: name.to_string(), target: bookmark.target.clone(), path, lines })
+    }
+
+    /// Every bookmark that resolves, skipping the ones that do not
+    pub fn resolve_all(&self, root: &Path, resolver: &SymbolResolver) -> Vec<ResolvedBookmark> {
+        self.bookmarks.keys().filter_map(|name| self.resolve(name, root, resolver).ok()).collect()
+    }
+}
+
+/// Replace a `bookmark=NAME` zoom target with the target it saves
+///
+/// Other targets are returned unchanged.
+pub fn expand_target(root: &Path, target: &str) -> Result<String> {
+    let Some(name) = target.strip_prefix(BOOKMARK_PREFIX) else {
+        return Ok(target.to_string());
+    };
+    let store = BookmarkStore::load(&BookmarkStore::default_path(root))?;
+    store.get(name).map(|b| b.target.clone()).ok_or_else(|| missing(name))
+}
+
+/// Moves files holding bookmarked targets ahead of their tier peers
+pub struct BookmarkPrioritizer {
+    paths: PathBoost,
+}
+
+impl BookmarkPrioritizer {
+    pub fn new(bookmarks: &[ResolvedBookmark]) -> Self {
+        let paths = bookmarks.iter().map(|b| b.path.clone());
+        Self { paths: PathBoost::new(paths, BOOKMARK_BOOST) }
+    }
+}
+
+impl FilePrioritizer for BookmarkPrioritizer {
+    fn name(&self) -> &str {
+        "bookmarks"
+    }
+
+    fn score(&self, file: &PriorityInput<'_>) -> i32 {
+        self.paths.score(file)
+    }
+}
+
+fn missing(name: &str) -> EncoderError {
+    EncoderError::InvalidZoomTarget { target: format!("{}{}", BOOKMARK_PREFIX, name) }
+}
+
+/// The file a target names, when it names one
+fn target_path(target: &ZoomTarget) -> Option<&str> {
+    match target {
+        ZoomTarget::File { path, .. } => Some(path),
+        ZoomTarget::Anchor(anchor) => Some(&anchor.path),
+        ZoomTarget::Section(section) => split_section_target(section).0,
+        ZoomTarget::Function(_) | ZoomTarget::Class(_) | ZoomTarget::Module(_) => None,
+    }
+}
+
+fn read(root: &Path, path: &str) -> Result<String> {
+    let full = root.join(path);
+    if !full.is_file() {
+        return Err(EncoderError::FileNotFound { path: PathBuf::from(path) });
+    }
+    Ok(std::fs::read_to_string(full)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::write(
+            dir.path().join("src/pay.py"),
+            "import os\n\n\ndef process_payment(amount):\n    return amount\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("README.md"), "# Title\n\n## Usage\n\nRun it.\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_add_validates_and_persists() {
+        let dir = project();
+        let path = BookmarkStore::default_path(dir.path());
+        let mut store = BookmarkStore::load(&path).unwrap();
+        assert!(store.is_empty());
+
+        store.add(dir.path(), "payment-flow", "function=process_payment", Some("entry point".into())).unwrap();
+        store.add(dir.path(), "usage", "section=README.md#Usage", None).unwrap();
+        assert!(store.add(dir.path(), "bad name", "function=x", None).is_err());
+        assert!(store.add(dir.path(), "loop", "bookmark=usage", None).is_err());
+        assert!(store.add(dir.path(), "typo", "func=x", None).is_err());
+        let escape = store.add(dir.path(), "escape", "file=../secret.txt", None).unwrap_err();
+        assert!(matches!(escape, EncoderError::PolicyViolation { .. }));
+        store.save().unwrap();
+
+        let reloaded = BookmarkStore::load(&path).unwrap();
+        assert_eq!(reloaded.names(), vec!["payment-flow", "usage"]);
+        assert_eq!(reloaded.get("payment-flow").unwrap().note.as_deref(), Some("entry point"));
+        assert_eq!(expand_target(dir.path(), "bookmark=usage").unwrap(), "section=README.md#Usage");
+        assert_eq!(expand_target(dir.path(), "file=src/pay.py").unwrap(), "file=src/pay.py");
+        assert!(expand_target(dir.path(), "bookmark=nope").is_err());
+    }
+
+    #[test]
+    fn test_resolve_locates_symbols_sections_and_files() {
+        let dir = project();
+        let mut store = BookmarkStore::default();
+        store.add(dir.path(), "pay", "function=process_payment", None).unwrap();
+        store.add(dir.path(), "usage", "section=README.md#Usage", None).unwrap();
+        store.add(dir.path(), "head", "file=src/pay.py:1-2", None).unwrap();
+        store.add(dir.path(), "gone", "file=src/gone.py", None).unwrap();
+        store.add(dir.path(), "core", "module=core", None).unwrap();
+
+        let resolver = SymbolResolver::new();
+        let pay = store.resolve("pay", dir.path(), &resolver).unwrap();
+        assert_eq!((pay.path.as_str(), pay.lines.map(|l| l.0)), ("src/pay.py", Some(4)));
+        let usage = store.resolve("usage", dir.path(), &resolver).unwrap();
+        assert_eq!((usage.path.as_str(), usage.lines), ("README.md", Some((3, 5))));
+        assert_eq!(store.resolve("head", dir.path(), &resolver).unwrap().lines, Some((1, 2)));
+        assert!(store.resolve("gone", dir.path(), &resolver).is_err());
+        assert!(store.resolve("core", dir.path(), &resolver).is_err());
+        assert!(store.resolve("missing", dir.path(), &resolver).is_err());
+
+        let resolved = store.resolve_all(dir.path(), &resolver);
+        assert_eq!(resolved.len(), 3);
+        let prioritizer = BookmarkPrioritizer::new(&resolved);
+        let input = |path| PriorityInput { path, tier: crate::core::FileTier::Core, language: "python", lens_priority: 0, tokens: 10, lines: 1 };
+        assert_eq!(prioritizer.score(&input("src/pay.py")), BOOKMARK_BOOST);
+        assert_eq!(prioritizer.score(&input("src/other.py")), 0);
+    }
+}
@@ -0,0 +1,271 @@
+//! Context bundles (`.pmctx`)
+//!
+//! A bundle packages everything needed to reproduce a context snapshot into
+//! one compressed file: the serialized context, a manifest of the files it
+//! was built from, the instruction file (CLAUDE.md / GEMINI_INSTRUCTIONS.txt)
+//! and, optionally, the Planetarium index as JSON. Bundles can be shared as
+//! "please debug this" snapshots and re-opened offline, e.g. to feed the MCP
+//! server without access to the original checkout.
+//!
+//! # Format
+//!
+//! A gzip stream containing one JSON document ([`ContextBundle`]). The
+//! manifest records a checksum of the context, which is verified on read.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use super::models::calculate_md5;
+
+/// File extension for bundles
+pub const BUNDLE_EXTENSION: &str = "pmctx";
+
+/// Bundle format version written by this build
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// A source file that contributed to the bundled context
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// Path relative to the project root
+    pub path: String,
+    pub md5: String,
+    pub size: u64,
+}
+
+impl BundleFile {
+    /// Record a file from its path and content
+    pub fn new(path: impl Into<String>, content: &str) -> Self {
+        Self {
+            path: path.into(),
+            md5: calculate_md5(content),
+            size: content.len() as u64,
+        }
+    }
+}
+
+/// Bundle metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    /// pm_encoder version that wrote the bundle
+    pub tool_version: String,
+    /// RFC 3339 creation time
+    pub created_at: String,
+    /// Project name (root directory name)
+    pub project: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lens: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_budget: Option<usize>,
+    /// Seed of a `--sample` context, to reproduce the same slice
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sample_seed: Option<u64>,
+    /// Output format of the context (e.g. "plusminus", "xml")
+    pub output_format: String,
+    /// MD5 of the context, verified on read
+    pub context_md5: String,
+    pub context_bytes: usize,
+    /// Files included in the context
+    #[serde(default)]
+    pub files: Vec<BundleFile>,
+}
+
+/// Instruction file stored in a bundle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleInstructions {
+    /// File name (CLAUDE.md or GEMINI_INSTRUCTIONS.txt)
+    pub filename: String,
+    pub content: String,
+}
+
+/// A context snapshot that can be written to and read from a `.pmctx` file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextBundle {
+    pub manifest: BundleManifest,
+    /// Serialized context
+    pub context: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<BundleInstructions>,
+    /// Planetarium index (`PlanetariumModel` JSON)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub planetarium: Option<serde_json::Value>,
+}
+
+impl ContextBundle {
+    /// Create a bundle for `context`
+    pub fn new(project: impl Into<String>, output_format: impl Into<String>, context: String) -> Self {
+        Self {
+            manifest: BundleManifest {
+                format_version: BUNDLE_FORMAT_VERSION,
+                tool_version: crate::VERSION.to_string(),
+                created_at: chrono::Utc::now().to_rfc3339(),
+                project: project.into(),
+                lens: None,
+                token_budget: None,
+                sample_seed: None,
+                output_format: output_format.into(),
+                context_md5: calculate_md5(&context),
+                context_bytes: context.len(),
+                files: Vec::new(),
+            },
+            context,
+            instructions: None,
+            planetarium: None,
+        }
+    }
+
+    /// Record the lens used to build the context
+    pub fn with_lens(mut self, lens: Option<&str>) -> Self {
+        self.manifest.lens = lens.map(str::to_string);
+        self
+    }
+
+    /// Record the token budget used to build the context
+    pub fn with_token_budget(mut self, budget: Option<usize>) -> Self {
+        self.manifest.token_budget = budget;
+        self
+    }
+
+    /// Record the seed of a sampled context
+    pub fn with_sample_seed(mut self, seed: Option<u64>) -> Self {
+        self.manifest.sample_seed = seed;
+        self
+    }
+
+    /// Record the files the context was built from
+    pub fn with_files(mut self, files: Vec<BundleFile>) -> Self {
+        self.manifest.files = files;
+        self
+    }
+
+    /// Attach an instruction file
+    pub fn with_instructions(mut self, filename: impl Into<String>, content: String) -> Self {
+        self.instructions = Some(BundleInstructions { filename: filename.into(), content });
+        self
+    }
+
+    /// Attach the Planetarium index
+    pub fn with_planetarium(mut self, planetarium: serde_json::Value) -> Self {
+        self.planetarium = Some(planetarium);
+        self
+    }
+
+    /// Write the bundle as gzip-compressed JSON
+    pub fn write_to(&self, path: &Path) -> Result<(), String> {
+        let file = fs::File::create(path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut encoder = GzEncoder::new(file, Compression::default());
+        serde_json::to_writer(&mut encoder, self)
+            .map_err(|e| format!("Failed to encode bundle: {}", e))?;
+        encoder.finish()
+            .and_then(|mut file| file.flush())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Read and verify a bundle
+    pub fn read_from(path: &Path) -> Result<Self, String> {
+        let file = fs::File::open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let mut json = String::new();
+        GzDecoder::new(file)
+            .read_to_string(&mut json)
+            .map_err(|e| format!("{} is not a .{} bundle: {}", path.display(), BUNDLE_EXTENSION, e))?;
+        let bundle: Self = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid bundle {}: {}", path.display(), e))?;
+
+        if bundle.manifest.format_version > BUNDLE_FORMAT_VERSION {
+            return Err(format!(
+                "Bundle format v{} is newer than supported v{} (written by pm_encoder {})",
+                bundle.manifest.format_version, BUNDLE_FORMAT_VERSION, bundle.manifest.tool_version
+            ));
+        }
+        if calculate_md5(&bundle.context) != bundle.manifest.context_md5 {
+            return Err(format!("Bundle {} is corrupt: context checksum mismatch", path.display()));
+        }
+        Ok(bundle)
+    }
+
+    /// One-paragraph description for the terminal
+    pub fn summary(&self) -> String {
+        let m = &self.manifest;
+        let mut lines = vec![
+            format!("Bundle: {} (pm_encoder {}, {})", m.project, m.tool_version, m.created_at),
+            format!(
+                "  Context: {} bytes, {} files, format {}",
+                m.context_bytes,
+                m.files.len(),
+                m.output_format
+            ),
+        ];
+        if let Some(lens) = &m.lens {
+            lines.push(format!("  Lens: {}", lens));
+        }
+        if let Some(budget) = m.token_budget {
+            lines.push(format!("  Token budget: {}", budget));
+        }
+        if let Some(instructions) = &self.instructions {
+            lines.push(format!("  Instructions: {}", instructions.filename));
+        }
+        if self.planetarium.is_some() {
+            lines.push("  Planetarium index: included".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn sample() -> ContextBundle {
+        ContextBundle::new("demo", "plusminus", "++++++++++ a.rs ++++++++++\nfn a() {}\n".to_string())
+            .with_lens(Some("architecture"))
+            .with_token_budget(Some(1000))
+            .with_files(vec![BundleFile::new("a.rs", "fn a() {}\n")])
+            .with_instructions("CLAUDE.md", "# demo\n".to_string())
+            .with_planetarium(serde_json::json!({"root": "demo", "files": {}}))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join(format!("snapshot.{}", BUNDLE_EXTENSION));
+        let bundle = sample();
+        bundle.write_to(&path).unwrap();
+
+        let back = ContextBundle::read_from(&path).unwrap();
+        assert_eq!(back.manifest, bundle.manifest);
+        assert_eq!(back.context, bundle.context);
+        assert_eq!(back.instructions, bundle.instructions);
+        assert_eq!(back.planetarium, bundle.planetarium);
+        assert!(back.summary().contains("Lens: architecture"));
+    }
+
+    #[test]
+    fn test_rejects_corrupt_and_foreign_files() {
+        let temp = TempDir::new().unwrap();
+
+        let plain = temp.path().join("plain.pmctx");
+        fs::write(&plain, "not gzip").unwrap();
+        assert!(ContextBundle::read_from(&plain).is_err());
+
+        let mut bundle = sample();
+        bundle.context.push_str("tampered");
+        let tampered = temp.path().join("tampered.pmctx");
+        bundle.write_to(&tampered).unwrap();
+        assert!(ContextBundle::read_from(&tampered).unwrap_err().contains("checksum"));
+
+        let mut future = sample();
+        future.manifest.format_version = BUNDLE_FORMAT_VERSION + 1;
+        let newer = temp.path().join("newer.pmctx");
+        future.write_to(&newer).unwrap();
+        assert!(ContextBundle::read_from(&newer).unwrap_err().contains("newer"));
+    }
+}
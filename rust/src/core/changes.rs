@@ -0,0 +1,642 @@
+//! Declaration-level view of git changes
+//!
+//! Runs `git diff` for a revision range (or the staged index), parses the
+//! unified diff into [`FileDiff`]s and maps every hunk onto the declarations
+//! it touches by parsing both sides with voyager-ast. The result is a
+//! [`ChangeSet`]: which symbols were added, modified or removed, whether they
+//! are public, and whether their signature changed.
+//!
+//! git is invoked as a subprocess so this works without the `temporal`
+//! feature; a missing `git` binary surfaces as an `Err`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::process::Command;
+
+use serde::Serialize;
+use voyager_ast::{AdapterRegistry, Declaration, LanguageId, Visibility};
+
+/// Which changes to diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffSource {
+    /// `base..head` (or `base...head` to diff from the merge base)
+    Range { base: String, head: String, merge_base: bool },
+    /// Staged changes (index against `HEAD`)
+    Staged,
+}
+
+impl DiffSource {
+    /// Parse `base..head`, `base...head` or a lone `base` (meaning `base..HEAD`)
+    pub fn parse_range(range: &str) -> Result<Self, String> {
+        let range = range.trim();
+        let (base, head, merge_base) = if let Some((base, head)) = range.split_once("...") {
+            (base, head, true)
+        } else if let Some((base, head)) = range.split_once("..") {
+            (base, head, false)
+        } else {
+            (range, "HEAD", false)
+        };
+        if base.is_empty() {
+            return Err(format!("Invalid revision range '{}' (expected <base>..<head>)", range));
+        }
+        let head = if head.is_empty() { "HEAD" } else { head };
+        Ok(Self::Range { base: base.to_string(), head: head.to_string(), merge_base })
+    }
+
+    /// Human-readable label (`main..feature`, `staged`)
+    pub fn label(&self) -> String {
+        match self {
+            Self::Range { base, head, merge_base: true } => format!("{}...{}", base, head),
+            Self::Range { base, head, .. } => format!("{}..{}", base, head),
+            Self::Staged => "staged".to_string(),
+        }
+    }
+
+    fn diff_args(&self) -> Vec<String> {
+        match self {
+            Self::Range { .. } => vec![self.label()],
+            Self::Staged => vec!["--cached".to_string()],
+        }
+    }
+
+    /// Revision for the old side of `git show <rev>:<path>`
+    fn old_rev(&self, root: &Path) -> Result<String, String> {
+        match self {
+            Self::Range { base, head, merge_base: true } => {
+                Ok(git(root, &["merge-base", base, head])?.trim().to_string())
+            }
+            Self::Range { base, .. } => Ok(base.clone()),
+            Self::Staged => Ok("HEAD".to_string()),
+        }
+    }
+
+    /// Revision for the new side (empty string = the index)
+    fn new_rev(&self) -> String {
+        match self {
+            Self::Range { head, .. } => head.clone(),
+            Self::Staged => String::new(),
+        }
+    }
+}
+
+/// Run `git` in `root`, returning stdout
+pub fn git(root: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["-c", "core.quotePath=false"])
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// File content at `rev` (`""` = the index); `None` if it does not exist there
+///
+/// `path` is relative to `root`.
+pub fn file_at(root: &Path, rev: &str, path: &str) -> Option<String> {
+    git(root, &["show", &format!("{}:./{}", rev, path)]).ok()
+}
+
+/// How a file changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+impl FileStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Modified => "modified",
+            Self::Deleted => "deleted",
+            Self::Renamed => "renamed",
+        }
+    }
+}
+
+/// One `@@ -a,b +c,d @@` hunk header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+}
+
+/// Changes to one file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileDiff {
+    /// Path on the new side (old path for deletions)
+    pub path: String,
+    /// Previous path for renames
+    pub old_path: Option<String>,
+    pub status: FileStatus,
+    pub hunks: Vec<Hunk>,
+    /// The file's section of the unified diff
+    #[serde(skip)]
+    pub patch: String,
+    pub added: usize,
+    pub removed: usize,
+    /// New-side numbers of the `+` lines
+    #[serde(skip)]
+    pub added_lines: Vec<usize>,
+    /// Old-side numbers of the `-` lines
+    #[serde(skip)]
+    pub removed_lines: Vec<usize>,
+}
+
+impl FileDiff {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            old_path: None,
+            status: FileStatus::Modified,
+            hunks: Vec::new(),
+            patch: String::new(),
+            added: 0,
+            removed: 0,
+            added_lines: Vec::new(),
+            removed_lines: Vec::new(),
+        }
+    }
+
+    /// Path on the old side
+    pub fn base_path(&self) -> &str {
+        self.old_path.as_deref().unwrap_or(&self.path)
+    }
+
+    /// Whether any added line falls in new-side lines `start..=end` (context lines don't count)
+    pub fn touches_new(&self, start: usize, end: usize) -> bool {
+        self.added_lines.iter().any(|line| (start..=end).contains(line))
+    }
+
+    /// Whether any removed line falls in old-side lines `start..=end`
+    pub fn touches_old(&self, start: usize, end: usize) -> bool {
+        self.removed_lines.iter().any(|line| (start..=end).contains(line))
+    }
+}
+
+/// Parse `git diff` output
+pub fn parse_unified_diff(text: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    let mut in_hunk = false;
+    // Line numbers of the next old-side and new-side line inside the current hunk
+    let (mut old_line, mut new_line) = (0, 0);
+
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            // Placeholder path from the header; ---/+++ or rename lines refine it
+            let path = rest.rsplit_once(" b/").map(|(_, b)| b).unwrap_or(rest);
+            files.push(FileDiff::new(path.to_string()));
+            in_hunk = false;
+        }
+        let Some(file) = files.last_mut() else { continue };
+        file.patch.push_str(line);
+        file.patch.push('\n');
+
+        if line.starts_with("@@") {
+            if let Some(hunk) = parse_hunk_header(line) {
+                (old_line, new_line) = (hunk.old_start, hunk.new_start);
+                file.hunks.push(hunk);
+            }
+            in_hunk = true;
+        } else if in_hunk {
+            if line.starts_with('+') {
+                file.added += 1;
+                file.added_lines.push(new_line);
+                new_line += 1;
+            } else if line.starts_with('-') {
+                file.removed += 1;
+                file.removed_lines.push(old_line);
+                old_line += 1;
+            } else if !line.starts_with('\\') {
+                old_line += 1;
+                new_line += 1;
+            }
+        } else if line.starts_with("new file mode") {
+            file.status = FileStatus::Added;
+        } else if line.starts_with("deleted file mode") {
+            file.status = FileStatus::Deleted;
+        } else if let Some(from) = line.strip_prefix("rename from ") {
+            file.status = FileStatus::Renamed;
+            file.old_path = Some(from.to_string());
+        } else if let Some(to) = line.strip_prefix("rename to ") {
+            file.path = to.to_string();
+        } else if let Some(old) = line.strip_prefix("--- ") {
+            if let Some(old) = old.trim_end_matches('\t').strip_prefix("a/") {
+                if file.status == FileStatus::Deleted {
+                    file.path = old.to_string();
+                }
+            }
+        } else if let Some(new) = line.strip_prefix("+++ ") {
+            if let Some(new) = new.trim_end_matches('\t').strip_prefix("b/") {
+                file.path = new.to_string();
+            }
+        }
+    }
+    files
+}
+
+fn parse_hunk_header(line: &str) -> Option<Hunk> {
+    // @@ -old_start[,old_lines] +new_start[,new_lines] @@ context
+    let mut parts = line.split_whitespace().skip(1);
+    let old = parts.next()?.strip_prefix('-')?;
+    let new = parts.next()?.strip_prefix('+')?;
+    let range = |s: &str| -> Option<(usize, usize)> {
+        match s.split_once(',') {
+            Some((start, lines)) => Some((start.parse().ok()?, lines.parse().ok()?)),
+            None => Some((s.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(old)?;
+    let (new_start, new_lines) = range(new)?;
+    Some(Hunk { old_start, old_lines, new_start, new_lines })
+}
+
+/// How a declaration changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolChangeKind {
+    Added,
+    Modified,
+    Removed,
+}
+
+impl SymbolChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Modified => "modified",
+            Self::Removed => "removed",
+        }
+    }
+}
+
+/// A changed declaration
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SymbolChange {
+    pub path: String,
+    /// Qualified name (`Parent::child` for nested declarations)
+    pub name: String,
+    /// Declaration kind (`function`, `struct`, ...)
+    pub kind: String,
+    pub change: SymbolChangeKind,
+    pub public: bool,
+    /// Signature on the new side (old side for removals)
+    pub signature: String,
+    /// Previous signature when it differs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_signature: Option<String>,
+    /// Lines on the new side (old side for removals)
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Full source of the declaration (new side, old side for removals)
+    #[serde(skip)]
+    pub body: String,
+}
+
+impl SymbolChange {
+    /// Whether the declaration's signature differs between the two sides
+    pub fn signature_changed(&self) -> bool {
+        self.old_signature.is_some()
+    }
+
+    /// Unqualified name
+    pub fn short_name(&self) -> &str {
+        self.name.rsplit("::").next().unwrap_or(&self.name)
+    }
+}
+
+/// Diff of a revision range, at file and declaration level
+#[derive(Debug, Clone)]
+pub struct ChangeSet {
+    pub source: DiffSource,
+    pub files: Vec<FileDiff>,
+    pub symbols: Vec<SymbolChange>,
+}
+
+impl ChangeSet {
+    /// Diff `source` in the repository at `root`
+    pub fn load(root: &Path, source: DiffSource) -> Result<Self, String> {
+        // --relative keeps paths relative to `root` when it is a subdirectory
+        let mut args = vec!["diff", "--no-color", "--no-ext-diff", "-M", "--relative"];
+        let extra = source.diff_args();
+        args.extend(extra.iter().map(String::as_str));
+        let files = parse_unified_diff(&git(root, &args)?);
+
+        let old_rev = source.old_rev(root)?;
+        let new_rev = source.new_rev();
+        let registry = AdapterRegistry::new();
+        let mut symbols = Vec::new();
+        for file in &files {
+            let old = match file.status {
+                FileStatus::Added => None,
+                _ => file_at(root, &old_rev, file.base_path()),
+            };
+            let new = match file.status {
+                FileStatus::Deleted => None,
+                _ => file_at(root, &new_rev, &file.path),
+            };
+            symbols.extend(symbol_changes(&registry, file, old.as_deref(), new.as_deref()));
+        }
+        Ok(Self { source, files, symbols })
+    }
+
+    pub fn lines_added(&self) -> usize {
+        self.files.iter().map(|f| f.added).sum()
+    }
+
+    pub fn lines_removed(&self) -> usize {
+        self.files.iter().map(|f| f.removed).sum()
+    }
+
+    /// Symbol changes in one file
+    pub fn symbols_in<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a SymbolChange> + 'a {
+        self.symbols.iter().filter(move |s| s.path == path)
+    }
+}
+
+/// Whether `path` is a package manifest or lockfile
+pub fn is_dependency_manifest(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    matches!(
+        name,
+        "Cargo.toml" | "Cargo.lock" | "package.json" | "package-lock.json" | "yarn.lock" | "pnpm-lock.yaml"
+            | "requirements.txt" | "pyproject.toml" | "poetry.lock" | "Pipfile" | "Pipfile.lock"
+            | "go.mod" | "go.sum" | "Gemfile" | "Gemfile.lock" | "composer.json" | "composer.lock"
+            | "pom.xml" | "build.gradle" | "build.gradle.kts"
+    )
+}
+
+/// A declaration flattened with its qualified name
+struct Flat<'a> {
+    name: String,
+    decl: &'a Declaration,
+    has_children: bool,
+}
+
+/// Identity of a declaration across both sides: qualified name, kind, and
+/// ordinal among same-named declarations of that kind (`struct Counter` and
+/// `impl Counter` are distinct; overloads pair up in source order)
+type DeclKey<'a> = (&'a str, &'static str, usize);
+
+fn keyed<'a>(decls: &'a [Flat<'a>]) -> Vec<(DeclKey<'a>, &'a Flat<'a>)> {
+    let mut seen: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    decls
+        .iter()
+        .map(|flat| {
+            let kind = flat.decl.kind.as_str();
+            let ordinal = seen.entry((flat.name.as_str(), kind)).or_default();
+            let key = (flat.name.as_str(), kind, *ordinal);
+            *ordinal += 1;
+            (key, flat)
+        })
+        .collect()
+}
+
+fn flatten<'a>(decls: &'a [Declaration], prefix: &str, out: &mut Vec<Flat<'a>>) {
+    for decl in decls {
+        let name = if prefix.is_empty() { decl.name.clone() } else { format!("{}::{}", prefix, decl.name) };
+        out.push(Flat { name: name.clone(), decl, has_children: !decl.children.is_empty() });
+        flatten(&decl.children, &name, out);
+    }
+}
+
+/// Declaration signature as written, whitespace collapsed
+pub fn signature_text(decl: &Declaration, source: &str) -> String {
+    let span = decl.signature_span.as_ref().unwrap_or(&decl.span);
+    let text = source.get(span.start..span.end).unwrap_or_default();
+    let text = if decl.signature_span.is_some() { text } else { text.lines().next().unwrap_or_default() };
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches('{')
+        .trim_end()
+        .to_string()
+}
+
+fn parse_declarations(registry: &AdapterRegistry, path: &str, source: Option<&str>) -> Option<voyager_ast::File> {
+    let source = source?;
+    let ext = Path::new(path).extension()?.to_str()?;
+    let language = LanguageId::from_extension(ext);
+    if !registry.supports(language) {
+        return None;
+    }
+    registry.parse(source, language).ok()
+}
+
+/// Map one file's hunks onto the declarations of both sides
+pub fn symbol_changes(
+    registry: &AdapterRegistry,
+    diff: &FileDiff,
+    old_source: Option<&str>,
+    new_source: Option<&str>,
+) -> Vec<SymbolChange> {
+    let old_file = parse_declarations(registry, diff.base_path(), old_source);
+    let new_file = parse_declarations(registry, &diff.path, new_source);
+
+    let mut old_decls = Vec::new();
+    if let Some(file) = &old_file {
+        flatten(&file.declarations, "", &mut old_decls);
+    }
+    let mut new_decls = Vec::new();
+    if let Some(file) = &new_file {
+        flatten(&file.declarations, "", &mut new_decls);
+    }
+    let old_keyed = keyed(&old_decls);
+    let new_keyed = keyed(&new_decls);
+    let old_by_key: BTreeMap<DeclKey, &Flat> = old_keyed.iter().copied().collect();
+    let new_keys: BTreeSet<DeclKey> = new_keyed.iter().map(|(key, _)| *key).collect();
+
+    let build = |flat: &Flat, source: &str, change: SymbolChangeKind| SymbolChange {
+        path: diff.path.clone(),
+        name: flat.name.clone(),
+        kind: flat.decl.kind.as_str().to_string(),
+        change,
+        public: flat.decl.visibility == Visibility::Public,
+        signature: signature_text(flat.decl, source),
+        old_signature: None,
+        start_line: flat.decl.span.start_line,
+        end_line: flat.decl.span.end_line,
+        body: source.get(flat.decl.span.start..flat.decl.span.end).unwrap_or_default().to_string(),
+    };
+
+    let mut changes = Vec::new();
+    let new_source = new_source.unwrap_or_default();
+    let old_source = old_source.unwrap_or_default();
+    for (key, flat) in &new_keyed {
+        let span = &flat.decl.span;
+        match old_by_key.get(key) {
+            None => changes.push(build(flat, new_source, SymbolChangeKind::Added)),
+            Some(old) => {
+                let old_signature = signature_text(old.decl, old_source);
+                let mut change = build(flat, new_source, SymbolChangeKind::Modified);
+                let signature_changed = old_signature != change.signature;
+                let old_span = &old.decl.span;
+                if signature_changed
+                    || diff.touches_new(span.start_line, span.end_line)
+                    || diff.touches_old(old_span.start_line, old_span.end_line)
+                {
+                    change.old_signature = signature_changed.then_some(old_signature);
+                    changes.push(change);
+                }
+            }
+        }
+    }
+    for (key, flat) in &old_keyed {
+        if !new_keys.contains(key) {
+            changes.push(build(flat, old_source, SymbolChangeKind::Removed));
+        }
+    }
+
+    // A modified container whose changes are all inside changed children is noise
+    let containers: BTreeSet<&str> = new_decls.iter().filter(|f| f.has_children).map(|f| f.name.as_str()).collect();
+    let changed: Vec<String> = changes.iter().map(|c| c.name.clone()).collect();
+    changes.retain(|c| {
+        c.change != SymbolChangeKind::Modified
+            || c.signature_changed()
+            || !containers.contains(c.name.as_str())
+            || !changed.iter().any(|n| n.starts_with(&format!("{}::", c.name)))
+    });
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+-pub fn add(a: u32) -> u32 {
++pub fn add(a: u32, b: u32) -> u32 {
+     a
+ }
+diff --git a/tests/new_test.rs b/tests/new_test.rs
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/tests/new_test.rs
+@@ -0,0 +1 @@
++fn it_works() {}
+diff --git a/old.rs b/new.rs
+similarity index 100%
+rename from old.rs
+rename to new.rs
+";
+
+    #[test]
+    fn test_parse_unified_diff() {
+        let files = parse_unified_diff(DIFF);
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].status, FileStatus::Modified);
+        assert_eq!((files[0].added, files[0].removed), (1, 1));
+        assert_eq!(files[0].hunks[0], Hunk { old_start: 1, old_lines: 3, new_start: 1, new_lines: 3 });
+        assert!(files[0].touches_new(1, 1) && files[0].touches_old(1, 1));
+        assert!(!files[0].touches_new(2, 3), "context lines are not changes");
+        assert_eq!(files[1].status, FileStatus::Added);
+        assert_eq!(files[1].hunks[0].new_lines, 1);
+        assert_eq!(files[2].status, FileStatus::Renamed);
+        assert_eq!((files[2].path.as_str(), files[2].base_path()), ("new.rs", "old.rs"));
+    }
+
+    #[test]
+    fn test_symbol_changes() {
+        let old = "pub fn add(a: u32) -> u32 {\n    a\n}\n\nfn gone() {}\n\nfn same() {}\n";
+        let new = "pub fn add(a: u32, b: u32) -> u32 {\n    a\n}\n\nfn same() {}\n\nfn fresh() {}\n";
+        let diff = FileDiff {
+            hunks: vec![
+                Hunk { old_start: 1, old_lines: 1, new_start: 1, new_lines: 1 },
+                Hunk { old_start: 5, old_lines: 2, new_start: 4, new_lines: 0 },
+                Hunk { old_start: 7, old_lines: 0, new_start: 6, new_lines: 2 },
+            ],
+            ..FileDiff::new("src/lib.rs".to_string())
+        };
+        let changes = symbol_changes(&AdapterRegistry::new(), &diff, Some(old), Some(new));
+        let find = |name: &str| changes.iter().find(|c| c.name == name);
+
+        let add = find("add").expect("add changed");
+        assert_eq!(add.change, SymbolChangeKind::Modified);
+        assert!(add.public && add.signature_changed());
+        assert!(add.signature.contains("b: u32"));
+        assert_eq!(find("gone").unwrap().change, SymbolChangeKind::Removed);
+        assert_eq!(find("fresh").unwrap().change, SymbolChangeKind::Added);
+        assert!(find("same").is_none());
+    }
+
+    #[test]
+    fn test_struct_and_impl_are_distinct() {
+        let old = "pub struct Counter {\n    count: u32,\n}\n\nimpl Counter {\n    pub fn get(&self) -> u32 {\n        self.count\n    }\n}\n";
+        let new = old.replace("self.count\n", "self.count + 1\n");
+        let patch = "\
+diff --git a/src/counter.rs b/src/counter.rs
+--- a/src/counter.rs
++++ b/src/counter.rs
+@@ -4,6 +4,6 @@
+ 
+ impl Counter {
+     pub fn get(&self) -> u32 {
+-        self.count
++        self.count + 1
+     }
+ }
+";
+        let diff = &parse_unified_diff(patch)[0];
+        let changes = symbol_changes(&AdapterRegistry::new(), diff, Some(old), Some(&new));
+
+        let names: Vec<(&str, &str)> = changes.iter().map(|c| (c.name.as_str(), c.kind.as_str())).collect();
+        assert_eq!(names, vec![("Counter::get", "method")]);
+        assert!(!changes[0].signature_changed());
+    }
+
+    #[test]
+    fn test_edit_next_to_unchanged_function() {
+        let old = "fn a() -> u32 {\n    1\n}\n\nfn b() -> u32 {\n    2\n}\n";
+        let new = old.replace("    2", "    3");
+        let patch = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -3,5 +3,5 @@
+ }
+ 
+ fn b() -> u32 {
+-    2
++    3
+ }
+";
+        let diff = &parse_unified_diff(patch)[0];
+        let changes = symbol_changes(&AdapterRegistry::new(), diff, Some(old), Some(&new));
+
+        let names: Vec<&str> = changes.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["b"]);
+        assert_eq!(changes[0].change, SymbolChangeKind::Modified);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            DiffSource::parse_range("main..feature").unwrap(),
+            DiffSource::Range { base: "main".into(), head: "feature".into(), merge_base: false }
+        );
+        assert_eq!(DiffSource::parse_range("main...").unwrap().label(), "main...HEAD");
+        assert_eq!(DiffSource::parse_range("v1.0").unwrap().label(), "v1.0..HEAD");
+        assert!(DiffSource::parse_range("..HEAD").is_err());
+    }
+}
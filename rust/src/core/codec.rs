@@ -0,0 +1,127 @@
+//! Compression for on-disk caches and large outputs
+//!
+//! Caches are written with zstd when the `zstd` feature is enabled (the
+//! default) and with gzip otherwise. Readers detect the codec from the
+//! leading magic bytes, so caches written by either kind of build, and
+//! uncompressed files from older versions, stay readable.
+
+use std::io::{Read, Write};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+/// File extension for zstd-compressed outputs
+pub const ZSTD_EXTENSION: &str = "zst";
+
+/// zstd level used for caches and outputs (fast, ~3-5x on source text)
+pub const ZSTD_LEVEL: i32 = 3;
+
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// Compression format of a byte stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Codec {
+    /// Codec of `bytes`, judged by their magic number
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&ZSTD_MAGIC) {
+            Self::Zstd
+        } else if bytes.starts_with(&GZIP_MAGIC) {
+            Self::Gzip
+        } else {
+            Self::None
+        }
+    }
+
+    /// Best codec available in this build
+    pub fn preferred() -> Self {
+        if zstd_available() {
+            Self::Zstd
+        } else {
+            Self::Gzip
+        }
+    }
+}
+
+/// Whether this build can read and write zstd
+pub fn zstd_available() -> bool {
+    cfg!(feature = "zstd")
+}
+
+/// Compress `bytes` with `codec`
+pub fn compress(bytes: &[u8], codec: Codec) -> Result<Vec<u8>, String> {
+    match codec {
+        Codec::None => Ok(bytes.to_vec()),
+        Codec::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(bytes)
+                .and_then(|_| encoder.finish())
+                .map_err(|e| format!("gzip compression failed: {}", e))
+        }
+        Codec::Zstd => zstd_compress(bytes),
+    }
+}
+
+/// Decompress `bytes`, whatever codec (if any) produced them
+pub fn decompress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    match Codec::detect(bytes) {
+        Codec::None => Ok(bytes.to_vec()),
+        Codec::Gzip => {
+            let mut out = Vec::new();
+            GzDecoder::new(bytes)
+                .read_to_end(&mut out)
+                .map_err(|e| format!("gzip decompression failed: {}", e))?;
+            Ok(out)
+        }
+        Codec::Zstd => zstd_decompress(bytes),
+    }
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_compress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    zstd::encode_all(bytes, ZSTD_LEVEL).map_err(|e| format!("zstd compression failed: {}", e))
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_compress(_bytes: &[u8]) -> Result<Vec<u8>, String> {
+    Err("zstd support is not compiled in (enable the `zstd` feature)".to_string())
+}
+
+#[cfg(feature = "zstd")]
+fn zstd_decompress(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    zstd::decode_all(bytes).map_err(|e| format!("zstd decompression failed: {}", e))
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_decompress(_bytes: &[u8]) -> Result<Vec<u8>, String> {
+    Err("zstd-compressed data needs the `zstd` feature".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_detection() {
+        let text = "fn main() {}\n".repeat(200);
+        for codec in [Codec::None, Codec::Gzip, Codec::preferred()] {
+            let packed = compress(text.as_bytes(), codec).unwrap();
+            assert_eq!(Codec::detect(&packed), codec);
+            assert_eq!(decompress(&packed).unwrap(), text.as_bytes());
+        }
+        assert!(compress(text.as_bytes(), Codec::preferred()).unwrap().len() < text.len() / 4);
+    }
+
+    #[test]
+    fn test_zstd_matches_feature() {
+        let result = compress(b"abc", Codec::Zstd);
+        assert_eq!(result.is_ok(), zstd_available());
+    }
+}
@@ -0,0 +1,303 @@
+//! Commit-message input pack
+//!
+//! A compact summary of staged changes for drafting a commit message or
+//! changelog entry: per file, which declarations were added, modified or
+//! removed (instead of the raw diff), plus optional Conventional Commits
+//! hints that map the changes to `feat`/`fix`/`refactor`/... candidates.
+//!
+//! The hints are structural guesses — a new public declaration suggests
+//! `feat`, edits inside existing bodies suggest `fix`, moves and removals
+//! suggest `refactor` — meant to steer the writer, not to decide for them.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::changes::{is_dependency_manifest, ChangeSet, DiffSource, FileDiff, FileStatus, SymbolChangeKind};
+use super::engine::FileTier;
+
+/// Conventional Commits type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CommitType {
+    Feat,
+    Fix,
+    Refactor,
+    Test,
+    Docs,
+    Build,
+    Chore,
+}
+
+impl CommitType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Feat => "feat",
+            Self::Fix => "fix",
+            Self::Refactor => "refactor",
+            Self::Test => "test",
+            Self::Docs => "docs",
+            Self::Build => "build",
+            Self::Chore => "chore",
+        }
+    }
+}
+
+/// One reason to pick a commit type
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConventionalHint {
+    pub kind: CommitType,
+    pub reason: String,
+}
+
+/// Staged changes summarized for commit-message generation
+#[derive(Debug, Clone)]
+pub struct CommitPack {
+    pub changes: ChangeSet,
+    /// Hints ordered by type precedence (feat first)
+    pub hints: Vec<ConventionalHint>,
+    /// Public declarations removed or with a changed signature
+    pub breaking: Vec<String>,
+    /// Most-changed top-level module
+    pub scope: Option<String>,
+}
+
+impl CommitPack {
+    /// Summarize the changes staged in the repository at `root`
+    pub fn staged(root: &Path) -> Result<Self, String> {
+        Ok(Self::from_changes(ChangeSet::load(root, DiffSource::Staged)?))
+    }
+
+    pub fn from_changes(changes: ChangeSet) -> Self {
+        let mut hints = Vec::new();
+        let mut breaking = Vec::new();
+        for file in &changes.files {
+            hints.extend(file_hint(file));
+        }
+        for symbol in &changes.symbols {
+            let target = format!("`{}` in {}", symbol.name, symbol.path);
+            match symbol.change {
+                SymbolChangeKind::Added if symbol.public => hints.push(ConventionalHint {
+                    kind: CommitType::Feat,
+                    reason: format!("adds public {} {}", symbol.kind, target),
+                }),
+                SymbolChangeKind::Added => {}
+                SymbolChangeKind::Removed => hints.push(ConventionalHint {
+                    kind: CommitType::Refactor,
+                    reason: format!("removes {} {}", symbol.kind, target),
+                }),
+                SymbolChangeKind::Modified if symbol.signature_changed() => hints.push(ConventionalHint {
+                    kind: CommitType::Refactor,
+                    reason: format!("changes the signature of {}", target),
+                }),
+                SymbolChangeKind::Modified => hints.push(ConventionalHint {
+                    kind: CommitType::Fix,
+                    reason: format!("changes the body of {}", target),
+                }),
+            }
+            if symbol.public
+                && (symbol.change == SymbolChangeKind::Removed || symbol.signature_changed())
+            {
+                breaking.push(target);
+            }
+        }
+        hints.sort_by_key(|h| h.kind);
+
+        let scope = scope(&changes.files);
+        Self { changes, hints, breaking, scope }
+    }
+
+    /// Most likely commit type (`chore` when nothing points elsewhere)
+    pub fn suggested_type(&self) -> CommitType {
+        self.hints.first().map(|h| h.kind).unwrap_or(CommitType::Chore)
+    }
+
+    /// Header for the suggested message: `type(scope)!:`
+    pub fn suggested_prefix(&self) -> String {
+        let mut prefix = self.suggested_type().as_str().to_string();
+        if let Some(scope) = &self.scope {
+            prefix.push_str(&format!("({})", scope));
+        }
+        if !self.breaking.is_empty() {
+            prefix.push('!');
+        }
+        prefix.push(':');
+        prefix
+    }
+
+    /// Per-file symbol summaries, optionally followed by Conventional Commits hints
+    pub fn render(&self, conventional: bool) -> String {
+        let changes = &self.changes;
+        let mut out = format!(
+            "# Changes ({})\n\n{} files changed (+{} -{})\n",
+            changes.source.label(),
+            changes.files.len(),
+            changes.lines_added(),
+            changes.lines_removed()
+        );
+
+        for file in &changes.files {
+            out.push_str(&format!("\n## {}", file.path));
+            if let Some(old) = &file.old_path {
+                out.push_str(&format!(" (renamed from {})", old));
+            }
+            out.push_str(&format!(" — {}, +{} -{}\n", file.status.as_str(), file.added, file.removed));
+
+            let mut any = false;
+            for symbol in changes.symbols_in(&file.path) {
+                any = true;
+                out.push_str(&format!(
+                    "- {} {}{} `{}`",
+                    symbol.change.as_str(),
+                    if symbol.public { "public " } else { "" },
+                    symbol.kind,
+                    symbol.name
+                ));
+                if let Some(old) = &symbol.old_signature {
+                    out.push_str(&format!(": `{}` → `{}`", old, symbol.signature));
+                }
+                out.push('\n');
+            }
+            if !any && !file.hunks.is_empty() {
+                out.push_str("- no declaration-level changes\n");
+            }
+        }
+
+        if conventional {
+            out.push_str("\n## Conventional commit hints\n\n");
+            out.push_str(&format!("Suggested prefix: `{}`\n\n", self.suggested_prefix()));
+            for hint in &self.hints {
+                out.push_str(&format!("- {}: {}\n", hint.kind.as_str(), hint.reason));
+            }
+            if !self.breaking.is_empty() {
+                out.push_str(&format!("\nBREAKING CHANGE candidates: {}\n", self.breaking.join(", ")));
+            }
+        }
+        out
+    }
+}
+
+/// Commit type implied by the kind of file alone
+fn file_hint(file: &FileDiff) -> Option<ConventionalHint> {
+    let lower = file.path.to_lowercase();
+    let (kind, what) = if is_dependency_manifest(&file.path) {
+        (CommitType::Build, "dependencies")
+    } else if lower.ends_with(".md") || lower.starts_with("docs/") || lower.contains("changelog") {
+        (CommitType::Docs, "documentation")
+    } else if FileTier::classify(&file.path, None) == FileTier::Tests {
+        (CommitType::Test, "tests")
+    } else if file.status == FileStatus::Renamed {
+        (CommitType::Refactor, "file moved")
+    } else if lower.starts_with('.') {
+        (CommitType::Chore, "tooling")
+    } else {
+        return None;
+    };
+    Some(ConventionalHint { kind, reason: format!("{} ({})", what, file.path) })
+}
+
+/// Module with the most changed lines (`src/` and `lib/` are skipped)
+fn scope(files: &[FileDiff]) -> Option<String> {
+    let mut weights: BTreeMap<String, usize> = BTreeMap::new();
+    for file in files {
+        let mut components: Vec<&str> = file.path.split('/').collect();
+        components.pop();
+        if matches!(components.first(), Some(&"src") | Some(&"lib")) {
+            components.remove(0);
+        }
+        if let Some(module) = components.first() {
+            *weights.entry(module.to_string()).or_default() += file.added + file.removed;
+        }
+    }
+    // max_by_key keeps the last maximum; iterate in reverse for the alphabetically first
+    weights.into_iter().rev().max_by_key(|(_, w)| *w).map(|(module, _)| module)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::changes::{parse_unified_diff, symbol_changes, SymbolChange};
+    use voyager_ast::AdapterRegistry;
+
+    fn file(path: &str, status: FileStatus, added: usize) -> FileDiff {
+        FileDiff {
+            path: path.to_string(),
+            old_path: None,
+            status,
+            hunks: Vec::new(),
+            patch: String::new(),
+            added,
+            removed: 0,
+            added_lines: Vec::new(),
+            removed_lines: Vec::new(),
+        }
+    }
+
+    fn symbol(path: &str, name: &str, change: SymbolChangeKind, public: bool, old_signature: Option<&str>) -> SymbolChange {
+        SymbolChange {
+            path: path.to_string(),
+            name: name.to_string(),
+            kind: "function".to_string(),
+            change,
+            public,
+            signature: format!("fn {}()", name),
+            old_signature: old_signature.map(str::to_string),
+            start_line: 1,
+            end_line: 1,
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_commit_pack_hints() {
+        let changes = ChangeSet {
+            source: DiffSource::Staged,
+            files: vec![
+                file("src/core/engine.rs", FileStatus::Modified, 12),
+                file("tests/engine_test.rs", FileStatus::Modified, 4),
+                file("README.md", FileStatus::Modified, 1),
+            ],
+            symbols: vec![
+                symbol("src/core/engine.rs", "run", SymbolChangeKind::Added, true, None),
+                symbol("src/core/engine.rs", "step", SymbolChangeKind::Modified, false, None),
+                symbol("src/core/engine.rs", "start", SymbolChangeKind::Modified, true, Some("fn start(x)")),
+            ],
+        };
+        let pack = CommitPack::from_changes(changes);
+
+        assert_eq!(pack.suggested_type(), CommitType::Feat);
+        assert_eq!(pack.scope.as_deref(), Some("core"));
+        assert_eq!(pack.breaking.len(), 1);
+        assert_eq!(pack.suggested_prefix(), "feat(core)!:");
+        let kinds: Vec<CommitType> = pack.hints.iter().map(|h| h.kind).collect();
+        assert!(kinds.contains(&CommitType::Fix) && kinds.contains(&CommitType::Test) && kinds.contains(&CommitType::Docs));
+
+        let plain = pack.render(false);
+        assert!(plain.contains("## src/core/engine.rs"));
+        assert!(plain.contains("- added public function `run`"));
+        assert!(plain.contains("`fn start(x)` → `fn start()`"));
+        assert!(!plain.contains("Conventional"));
+        assert!(pack.render(true).contains("Suggested prefix: `feat(core)!:`"));
+    }
+
+    #[test]
+    fn test_comment_after_function_is_not_a_fix() {
+        let old = "def main():\n    return 1\n";
+        let new = "def main():\n    return 1\n\n# trailing note\n";
+        let patch = "\
+diff --git a/app.py b/app.py
+--- a/app.py
++++ b/app.py
+@@ -1,2 +1,4 @@
+ def main():
+     return 1
++
++# trailing note
+";
+        let files = parse_unified_diff(patch);
+        let symbols = symbol_changes(&AdapterRegistry::new(), &files[0], Some(old), Some(new));
+        let pack = CommitPack::from_changes(ChangeSet { source: DiffSource::Staged, files, symbols });
+
+        assert!(pack.changes.symbols.is_empty());
+        assert_eq!(pack.suggested_prefix(), "chore:");
+        assert!(pack.render(true).contains("- no declaration-level changes"));
+    }
+}
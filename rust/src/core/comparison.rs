@@ -0,0 +1,229 @@
+//! Format Comparison - Token cost and symbol retention of every output format
+//!
+//! Serializes one project in each output format at several token budgets
+//! and measures, per combination, the tokens and bytes of the output and how
+//! many of the project's declarations are still named in it, broken down by
+//! nesting depth (top-level declarations, their members, deeper ones). The
+//! resulting table is the data for choosing default formats and budgets.
+//!
+//! A declaration counts as retained when its name appears in the output as
+//! a whole word: a skeleton that keeps the signature retains it, a dropped
+//! file does not. Without a project the synthetic benchmark corpus is used.
+
+use std::path::Path;
+
+use serde::Serialize;
+use voyager_ast::Declaration;
+
+use crate::budgeting::TokenEstimator;
+use crate::core::ast_bridge::AstBridge;
+use crate::core::bench::SyntheticCorpus;
+use crate::core::engine::ContextEngine;
+use crate::core::error::{EncoderError, Result};
+use crate::core::models::{EncoderConfig, OutputFormat};
+use crate::core::walker::{DefaultWalker, FileWalker, WalkConfig};
+
+/// Formats compared, in table order
+pub const COMPARED_FORMATS: [OutputFormat; 4] =
+    [OutputFormat::PlusMinus, OutputFormat::Xml, OutputFormat::Markdown, OutputFormat::ClaudeXml];
+
+/// Budgets compared by default (`None` = unlimited)
+pub const DEFAULT_COMPARISON_BUDGETS: [Option<usize>; 4] = [None, Some(32_000), Some(8_000), Some(2_000)];
+
+/// Nesting depths reported separately; deeper declarations count as the last
+const DEPTHS: usize = 3;
+
+/// Declarations of one nesting depth named in an output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Retention {
+    /// Nesting depth (0 = top level; the last depth includes deeper ones)
+    pub depth: usize,
+    pub retained: usize,
+    pub total: usize,
+}
+
+impl Retention {
+    /// Retained share in percent (`None` when there is nothing at this depth)
+    pub fn percent(&self) -> Option<f64> {
+        (self.total > 0).then(|| self.retained as f64 * 100.0 / self.total as f64)
+    }
+}
+
+/// One format at one budget
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComparisonRow {
+    pub format: String,
+    /// Token budget (`None` = unlimited)
+    pub budget: Option<usize>,
+    /// Estimated tokens of the output
+    pub tokens: usize,
+    pub bytes: usize,
+    pub retention: Vec<Retention>,
+}
+
+/// Comparison of all formats and budgets for one project
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FormatComparison {
+    /// Project root, or `synthetic` for the benchmark corpus
+    pub project: String,
+    pub files: usize,
+    /// Estimated tokens of the raw file contents
+    pub source_tokens: usize,
+    pub rows: Vec<ComparisonRow>,
+}
+
+impl FormatComparison {
+    /// Compare formats and budgets on the project at `root`
+    ///
+    /// `base` supplies every other setting (patterns, lens, skeleton mode);
+    /// its format and budget are replaced per row.
+    pub fn run(root: &str, base: &EncoderConfig, budgets: &[Option<usize>]) -> Result<Self> {
+        let walk_config = WalkConfig {
+            ignore_patterns: base.ignore_patterns.clone(),
+            include_patterns: base.include_patterns.clone(),
+            max_file_size: base.max_file_size,
+            case_insensitive: base.case_insensitive_paths,
+        };
+        let entries = DefaultWalker::new().walk(root, &walk_config)?;
+
+        // Names of every declaration, bucketed by depth
+        let bridge = AstBridge::new();
+        let mut symbols: Vec<Vec<String>> = vec![Vec::new(); DEPTHS];
+        for entry in &entries {
+            let language = AstBridge::detect_language(Path::new(&entry.path));
+            if let Some(file) = bridge.analyze_file(&entry.content, language) {
+                collect_names(&file.declarations, 0, &mut symbols);
+            }
+        }
+
+        let mut rows = Vec::new();
+        for &budget in budgets {
+            for format in COMPARED_FORMATS {
+                let mut config = base.clone();
+                config.output_format = format;
+                config.token_budget = budget;
+                let output = ContextEngine::with_config(config).serialize(root)?;
+                let retention = symbols
+                    .iter()
+                    .enumerate()
+                    .map(|(depth, names)| Retention {
+                        depth,
+                        retained: names.iter().filter(|name| contains_word(&output, name)).count(),
+                        total: names.len(),
+                    })
+                    .collect();
+                rows.push(ComparisonRow {
+                    format: format_name(format).to_string(),
+                    budget,
+                    tokens: TokenEstimator::estimate_tokens(&output),
+                    bytes: output.len(),
+                    retention,
+                });
+            }
+        }
+
+        Ok(Self {
+            project: root.to_string(),
+            files: entries.len(),
+            source_tokens: entries.iter().map(|e| TokenEstimator::estimate_tokens(&e.content)).sum(),
+            rows,
+        })
+    }
+
+    /// Compare formats and budgets on the synthetic benchmark corpus
+    pub fn run_synthetic(corpus: &SyntheticCorpus, base: &EncoderConfig, budgets: &[Option<usize>]) -> Result<Self> {
+        let dir = std::env::temp_dir().join(format!("pm_encoder-compare-{}", std::process::id()));
+        corpus
+            .write_to(&dir)
+            .map_err(|e| EncoderError::from(e).with_context("Failed to write comparison corpus"))?;
+        let comparison = Self::run(&dir.to_string_lossy(), base, budgets);
+        let _ = std::fs::remove_dir_all(&dir);
+        comparison.map(|c| Self { project: "synthetic".to_string(), ..c })
+    }
+
+    /// Fixed-width text table, one row per format and budget
+    pub fn render_table(&self) -> String {
+        let mut out = format!(
+            "{}: {} files, ~{} source tokens\n\n",
+            self.project, self.files, self.source_tokens
+        );
+        let header = format!(
+            "{:<11} {:>9} {:>9} {:>10} {:>8} {:>8} {:>8}\n",
+            "Format", "Budget", "Tokens", "Bytes", "Depth 0", "Depth 1", "Depth 2+"
+        );
+        out.push_str(&header);
+        out.push_str(&format!("{}\n", "-".repeat(header.len() - 1)));
+        for row in &self.rows {
+            let budget = row.budget.map_or_else(|| "none".to_string(), |b| b.to_string());
+            out.push_str(&format!("{:<11} {:>9} {:>9} {:>10}", row.format, budget, row.tokens, row.bytes));
+            for retention in &row.retention {
+                match retention.percent() {
+                    Some(percent) => out.push_str(&format!(" {:>7.1}%", percent)),
+                    None => out.push_str(&format!(" {:>8}", "-")),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+fn format_name(format: OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::PlusMinus => "plus-minus",
+        OutputFormat::Xml => "xml",
+        OutputFormat::Markdown => "markdown",
+        OutputFormat::ClaudeXml => "claude-xml",
+    }
+}
+
+fn collect_names(decls: &[Declaration], depth: usize, out: &mut [Vec<String>]) {
+    for decl in decls {
+        out[depth.min(DEPTHS - 1)].push(decl.name.clone());
+        collect_names(&decl.children, depth + 1, out);
+    }
+}
+
+/// Check if `word` occurs in `text` not directly preceded or followed by an identifier character
+fn contains_word(text: &str, word: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    !word.is_empty()
+        && text.match_indices(word).any(|(start, _)| {
+            let before = text[..start].chars().next_back();
+            let after = text[start + word.len()..].chars().next();
+            !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comparison_covers_formats_and_budgets() {
+        let corpus = SyntheticCorpus::generate(2, 8);
+        let comparison =
+            FormatComparison::run_synthetic(&corpus, &EncoderConfig::default(), &[None, Some(200)]).unwrap();
+
+        assert_eq!(comparison.project, "synthetic");
+        assert_eq!(comparison.rows.len(), 2 * COMPARED_FORMATS.len());
+        let full = &comparison.rows[0];
+        assert_eq!((full.format.as_str(), full.budget), ("plus-minus", None));
+        assert_eq!(full.retention[0].percent(), Some(100.0));
+        assert_eq!(full.retention[1].percent(), None, "the corpus has no nested declarations");
+
+        let tight = &comparison.rows[COMPARED_FORMATS.len()];
+        assert!(tight.tokens < full.tokens);
+        assert!(tight.retention[0].retained < full.retention[0].retained);
+
+        let table = comparison.render_table();
+        assert!(table.starts_with("synthetic: 8 files, ~"));
+        assert!(table.contains("claude-xml"));
+        assert!(!contains_word("compute_1_10", "compute_1_1"));
+    }
+}
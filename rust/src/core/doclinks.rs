@@ -0,0 +1,259 @@
+//! Doc Links - Cross-references between documentation and code symbols
+//!
+//! Scans Markdown sections and docstrings for backtick-quoted identifiers
+//! (`` `apply_budget` ``, `` `ContextEngine::zoom` ``, `` `parse()` ``) that
+//! name an indexed declaration, and records the link in both directions:
+//!
+//! - **symbol → docs**: which sections and docstrings discuss a symbol, so a
+//!   zoom can optionally pull them in alongside the code
+//! - **doc → symbols**: which symbols a section or docstring refers to
+//!
+//! Like the tour generator, the linker is pure: it consumes `(path, content)`
+//! pairs and parses them through the [`AstBridge`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use voyager_ast::{Declaration, DeclarationKind};
+
+use crate::core::ast_bridge::AstBridge;
+use crate::core::outline;
+
+/// Where a symbol mention was found
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DocSource {
+    /// A Markdown section (its own text, excluding nested sections)
+    Section { title: String, anchor: String },
+    /// The docstring of a declaration
+    Docstring { owner: String },
+}
+
+/// A documentation passage that mentions a symbol
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct DocMention {
+    /// File containing the passage
+    pub doc_path: String,
+    /// Section or docstring the passage belongs to
+    pub source: DocSource,
+    /// First line of the passage (1-indexed)
+    pub start_line: usize,
+    /// Last line of the passage (inclusive)
+    pub end_line: usize,
+}
+
+/// Bidirectional index of doc ↔ symbol links
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DocLinks {
+    /// Declared symbol name → files that declare it
+    definitions: BTreeMap<String, BTreeSet<String>>,
+    /// Symbol name → passages that mention it
+    mentions: BTreeMap<String, BTreeSet<DocMention>>,
+    /// Passage → symbol names it mentions
+    references: BTreeMap<DocMention, BTreeSet<String>>,
+}
+
+impl DocLinks {
+    /// Build the link index from `(path, content)` pairs
+    pub fn build(files: &[(String, String)]) -> Self {
+        let bridge = AstBridge::new();
+        let mut links = Self::default();
+
+        // Pass 1: index declarations (and remember docstrings for pass 2)
+        let mut docstrings = Vec::new();
+        for (path, content) in files {
+            if outline::is_markdown(path) {
+                continue;
+            }
+            let language = AstBridge::detect_language(Path::new(path));
+            let Some(file) = bridge.analyze_file(content, language) else {
+                continue;
+            };
+            visit_declarations(&file.declarations, &mut |decl| {
+                if matches!(decl.kind, DeclarationKind::Section | DeclarationKind::Element) {
+                    return;
+                }
+                links.definitions.entry(decl.name.clone()).or_default().insert(path.clone());
+                if let Some(doc) = &decl.doc_comment {
+                    let mention = DocMention {
+                        doc_path: path.clone(),
+                        source: DocSource::Docstring { owner: decl.name.clone() },
+                        start_line: doc.span.start_line,
+                        end_line: doc.span.end_line,
+                    };
+                    docstrings.push((mention, decl.name.clone(), doc.text.clone()));
+                }
+            });
+        }
+
+        // Pass 2: scan docs for backtick-quoted identifiers
+        for (mention, owner, text) in docstrings {
+            for name in backtick_identifiers(&text) {
+                if name != owner {
+                    links.link(name, mention.clone());
+                }
+            }
+        }
+        for (path, content) in files.iter().filter(|(p, _)| outline::is_markdown(p)) {
+            let lines: Vec<&str> = content.lines().collect();
+            let mut sections = Vec::new();
+            section_passages(&outline::sections(content), path, &mut sections);
+            for mention in sections {
+                let end = mention.end_line.min(lines.len());
+                let text = lines[mention.start_line.saturating_sub(1)..end].join("\n");
+                for name in backtick_identifiers(&text) {
+                    links.link(name, mention.clone());
+                }
+            }
+        }
+
+        links
+    }
+
+    /// Passages that mention a symbol, in document order
+    pub fn docs_for(&self, symbol: &str) -> Vec<&DocMention> {
+        self.mentions.get(symbol).map(|m| m.iter().collect()).unwrap_or_default()
+    }
+
+    /// Symbols mentioned by a passage
+    pub fn symbols_in(&self, mention: &DocMention) -> Vec<&str> {
+        self.references
+            .get(mention)
+            .map(|s| s.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Files that declare a symbol
+    pub fn definitions(&self, symbol: &str) -> Vec<&str> {
+        self.definitions
+            .get(symbol)
+            .map(|s| s.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Total number of doc ↔ symbol links
+    pub fn len(&self) -> usize {
+        self.mentions.values().map(BTreeSet::len).sum()
+    }
+
+    /// Whether no links were found
+    pub fn is_empty(&self) -> bool {
+        self.mentions.is_empty()
+    }
+
+    fn link(&mut self, name: String, mention: DocMention) {
+        if !self.definitions.contains_key(&name) {
+            return;
+        }
+        self.references.entry(mention.clone()).or_default().insert(name.clone());
+        self.mentions.entry(name).or_default().insert(mention);
+    }
+}
+
+/// Names of the declarations in a parsed file, including nested ones
+pub fn declared_names(path: &str, content: &str) -> Vec<String> {
+    let language = AstBridge::detect_language(Path::new(path));
+    let mut names = Vec::new();
+    if let Some(file) = AstBridge::new().analyze_file(content, language) {
+        visit_declarations(&file.declarations, &mut |decl| names.push(decl.name.clone()));
+    }
+    names
+}
+
+/// Identifiers quoted with single backticks outside fenced code blocks
+///
+/// Paths and calls are reduced to their last segment: `` `Engine::zoom()` ``
+/// and `` `self.zoom` `` both yield `zoom`.
+pub fn backtick_identifiers(text: &str) -> BTreeSet<String> {
+    let mut found = BTreeSet::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        for quoted in line.split('`').skip(1).step_by(2) {
+            let quoted = quoted.trim().trim_end_matches("()");
+            let name = quoted.rsplit(['.', ':']).next().unwrap_or(quoted);
+            let is_identifier = name.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+                && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+            if is_identifier {
+                found.insert(name.to_string());
+            }
+        }
+    }
+    found
+}
+
+/// Flatten sections into passages covering only their own text
+fn section_passages(decls: &[Declaration], path: &str, out: &mut Vec<DocMention>) {
+    for decl in decls {
+        let own_end = decl
+            .children
+            .first()
+            .map_or(decl.span.end_line, |child| child.span.start_line.saturating_sub(1));
+        out.push(DocMention {
+            doc_path: path.to_string(),
+            source: DocSource::Section {
+                title: decl.name.clone(),
+                anchor: decl.metadata.get("anchor").cloned().unwrap_or_default(),
+            },
+            start_line: decl.span.start_line,
+            end_line: own_end.max(decl.span.start_line),
+        });
+        section_passages(&decl.children, path, out);
+    }
+}
+
+fn visit_declarations<'a>(decls: &'a [Declaration], visit: &mut impl FnMut(&'a Declaration)) {
+    for decl in decls {
+        visit(decl);
+        visit_declarations(&decl.children, visit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> Vec<(String, String)> {
+        vec![
+            (
+                "src/budget.rs".to_string(),
+                "/// Wraps `apply_budget` for callers.\npub fn allocate() {}\n\npub fn apply_budget() {}\n".to_string(),
+            ),
+            (
+                "README.md".to_string(),
+                "# Guide\n\nStart here.\n\n## Budgets\n\nCall `apply_budget()` or `Engine::allocate`.\n\n```rust\nlet x = `ignored`;\n```\n\n## Other\n\nMentions `unknown_fn`.\n".to_string(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_backtick_identifiers() {
+        let found = backtick_identifiers("Use `a::b()` and `self.c`, not `1x` or `two words`.\n```\n`d`\n```\n");
+        assert_eq!(found.into_iter().collect::<Vec<_>>(), vec!["b".to_string(), "c".to_string()]);
+    }
+
+    #[test]
+    fn test_links_are_bidirectional() {
+        let links = DocLinks::build(&project());
+
+        let docs = links.docs_for("apply_budget");
+        assert_eq!(docs.len(), 2);
+        let section = docs
+            .iter()
+            .find(|m| matches!(&m.source, DocSource::Section { anchor, .. } if anchor == "budgets"))
+            .unwrap();
+        assert_eq!((section.doc_path.as_str(), section.start_line), ("README.md", 5));
+        assert_eq!(links.symbols_in(section), vec!["allocate", "apply_budget"]);
+        assert!(docs.iter().any(|m| m.source == DocSource::Docstring { owner: "allocate".to_string() }));
+
+        assert!(links.docs_for("unknown_fn").is_empty());
+        assert_eq!(links.definitions("apply_budget"), vec!["src/budget.rs"]);
+    }
+}
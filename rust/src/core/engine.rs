@@ -3,13 +3,36 @@
 //! The ContextEngine is the primary interface for serializing project contexts.
 //! It coordinates file walking, analysis, truncation, and serialization.
 
+use crate::core::blobstore::BlobStore;
+use crate::core::boilerplate::BoilerplateRules;
+use crate::core::cancel::CancellationToken;
 use crate::core::error::{EncoderError, Result};
 use crate::core::manifest::{ProjectManifest, ProjectType};
-use crate::core::models::{CompressionLevel, EncoderConfig, FileEntry, OutputFormat, ProcessedFile};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use voyager_ast::{Capability, Declaration as AstDeclaration, DeclarationKind, File as AstFile, PlanetariumModel, RawPath};
+
+use crate::core::models::{
+    CompressionLevel, EncoderConfig, FileEntry, LanguageTruncation, OutputFormat, ProcessedFile, SymbolRule,
+};
+use crate::core::ast_bridge::AstBridge;
+use crate::core::doclinks::{self, DocLinks};
+use crate::core::ledger::{self, ContentLedger};
+use crate::core::lod::{self, Lod, LodPlan, LOD_INDEX_FILE_NAME};
+use crate::core::outline;
+use crate::core::provenance::Provenance;
+use crate::core::preview::{tier_name, ContextPreview, FileAction, PreviewEntry};
 use crate::core::serialization::{get_serializer, Serializer};
 use crate::core::skeleton::{AdaptiveAllocator, FileAllocation, Language, Skeletonizer};
+use crate::core::summarizer::{summarize_symbols, ExtractiveSummarizer, Summarizer};
+use crate::core::symbol_rules::apply_symbol_rules;
+use crate::core::sandbox::PathSandbox;
+use crate::core::toc::{TableOfContents, TOC_FILE_NAME};
+use crate::core::tour::{tour_file, TOUR_FILE_NAME, TOUR_LENS};
 use crate::core::walker::{DefaultWalker, FileWalker, WalkConfig};
-use crate::core::zoom::{ZoomAction, ZoomConfig, ZoomTarget};
+use crate::core::zoom::{BatchZoomConfig, ZoomAction, ZoomConfig, ZoomTarget, RELATED_FILE_NAME};
+use crate::lenses::LensManager;
+use crate::core::paths::normalize_relative_path;
 #[cfg(test)]
 use crate::core::zoom::ZoomDepth;
 
@@ -182,6 +205,12 @@ pub struct ContextEngine {
     walker: Box<dyn FileWalker>,
     /// Output serializer
     serializer: Box<dyn Serializer>,
+    /// Summarizer for bodies exceeding the per-symbol budget
+    summarizer: Box<dyn Summarizer>,
+    /// Checked between files by serialize and preview
+    cancel: CancellationToken,
+    /// Context cache for serialize (none: disabled)
+    blobs: Option<BlobStore>,
 }
 
 impl ContextEngine {
@@ -197,6 +226,9 @@ impl ContextEngine {
             config,
             walker: Box::new(DefaultWalker::new()),
             serializer,
+            summarizer: Box::new(ExtractiveSummarizer::new()),
+            cancel: CancellationToken::new(),
+            blobs: None,
         }
     }
 
@@ -206,6 +238,28 @@ impl ContextEngine {
         self
     }
 
+    /// Builder: set the summarizer used when a per-symbol budget is configured
+    pub fn with_summarizer(mut self, summarizer: impl Summarizer + 'static) -> Self {
+        self.summarizer = Box::new(summarizer);
+        self
+    }
+
+    /// Builder: stop serialize/preview early when `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Builder: cache serialized contexts in `store`
+    ///
+    /// The cache key covers the configuration and every walked file's path,
+    /// checksum and timestamps, so any change yields a fresh serialization.
+    /// Custom walkers and summarizers are not part of the key.
+    pub fn with_blob_store(mut self, store: BlobStore) -> Self {
+        self.blobs = Some(store);
+        self
+    }
+
     /// Builder: set output format
     pub fn with_format(mut self, format: OutputFormat) -> Self {
         self.config.output_format = format;
@@ -218,64 +272,237 @@ impl ContextEngine {
         &self.config
     }
 
-    /// Serialize a project directory
-    pub fn serialize(&self, root: &str) -> Result<String> {
-        let walk_config = WalkConfig {
+    /// Walker configuration derived from the engine configuration
+    fn walk_config(&self) -> WalkConfig {
+        WalkConfig {
             ignore_patterns: self.config.ignore_patterns.clone(),
             include_patterns: self.config.include_patterns.clone(),
             max_file_size: self.config.max_file_size,
-        };
+            case_insensitive: self.config.case_insensitive_paths,
+        }
+    }
+
+    /// Error if cancellation was requested
+    fn check_cancelled(&self, stage: &'static str, files_processed: usize, files_total: usize) -> Result<()> {
+        if self.cancel.is_cancelled() {
+            Err(EncoderError::Cancelled { stage, files_processed, files_total })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Serialize a project directory
+    ///
+    /// Returns [`EncoderError::Cancelled`] if the engine's cancellation
+    /// token fires before serialization starts.
+    pub fn serialize(&self, root: &str) -> Result<String> {
+        self.serialize_with_ledger(root, None)
+    }
+
+    /// Serialize a project directory, eliding files already delivered
+    ///
+    /// Files whose fingerprint `ledger` already holds from a complete
+    /// delivery are replaced by an `«unchanged since msg #N»` marker; every
+    /// other file is recorded as delivered in `message`. The context cache
+    /// is bypassed, since the output depends on the conversation.
+    pub fn serialize_deduplicated(&self, root: &str, ledger: &mut ContentLedger, message: u64) -> Result<String> {
+        self.serialize_with_ledger(root, Some((ledger, message)))
+    }
+
+    fn serialize_with_ledger(&self, root: &str, ledger: Option<(&mut ContentLedger, u64)>) -> Result<String> {
+        let walk_config = self.walk_config();
 
         // Walk directory
+        self.check_cancelled("walk", 0, 0)?;
         let entries = self.walker.walk(root, &walk_config)?;
 
         // Sort entries
         let sorted = self.sort_entries(entries);
+        self.check_cancelled("walk", 0, sorted.len())?;
+
+        let cache_key = self.blobs.as_ref().filter(|_| ledger.is_none()).map(|_| self.context_cache_key(&sorted));
+        if let (Some(store), Some(key)) = (&self.blobs, &cache_key) {
+            if let Some(cached) = store.get_ref(key).and_then(|b| String::from_utf8(b).ok()) {
+                return Ok(cached);
+            }
+        }
 
         // Process files (language detection, truncation)
         let processed = self.process_files(&sorted);
+        self.check_cancelled("processing", processed.len(), sorted.len())?;
 
         // Apply token budget if set
-        let final_files = if let Some(budget) = self.config.token_budget {
+        let mut final_files = if let Some(budget) = self.config.token_budget {
             self.apply_budget(processed, budget)
         } else {
             processed
         };
 
+        // Onboarding lens: embed the guided tour at the top of the context
+        if self.config.active_lens.as_deref() == Some(TOUR_LENS) {
+            final_files.insert(0, self.tour_file(&sorted));
+        }
+        if self.config.table_of_contents {
+            let toc = self.toc_file(&final_files);
+            final_files.insert(0, toc);
+        }
+        self.check_cancelled("budgeting", sorted.len(), sorted.len())?;
+
+        if let Some((ledger, message)) = ledger {
+            Self::elide_delivered(&mut final_files, ledger, message);
+        }
+
         // Serialize based on format
-        if self.config.output_format == OutputFormat::ClaudeXml {
-            self.serialize_claude_xml(&final_files)
+        let output = if self.config.output_format == OutputFormat::ClaudeXml {
+            self.serialize_claude_xml(&final_files)?
         } else {
-            Ok(self.serializer.serialize_files(&final_files))
+            self.serializer.serialize_files(&final_files)
+        };
+
+        if let (Some(store), Some(key)) = (&self.blobs, &cache_key) {
+            // A failed cache write only costs the next run a re-serialization
+            let _ = store.put_ref(key, output.as_bytes());
+        }
+        Ok(output)
+    }
+
+    /// Replace files the ledger already holds by markers, record the rest
+    fn elide_delivered(files: &mut [ProcessedFile], ledger: &mut ContentLedger, message: u64) {
+        for file in files.iter_mut().filter(|f| f.compression_level != CompressionLevel::Drop) {
+            let complete = !file.truncated && file.compression_level == CompressionLevel::Full;
+            match ledger.delivered_in(&file.path, &file.md5, complete) {
+                Some(delivered) => {
+                    file.content = ledger::unchanged_marker(delivered);
+                    file.tokens = file.content.len() / 4;
+                }
+                None => ledger.record(&file.path, &file.md5, message, complete),
+            }
+        }
+    }
+
+    /// Context cache ref for this configuration and file set
+    fn context_cache_key(&self, entries: &[FileEntry]) -> String {
+        use sha2::{Digest, Sha256};
+
+        // HashMap order is not stable across runs
+        let rules: std::collections::BTreeMap<_, _> = self.config.language_rules.iter().collect();
+        let config = EncoderConfig { language_rules: HashMap::new(), ..self.config.clone() };
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{}|{:?}|{:?}", crate::VERSION, config, rules));
+        for entry in entries {
+            hasher.update(format!("\0{}\0{}\0{}\0{}", entry.path, entry.md5, entry.mtime, entry.ctime));
+        }
+        let digest: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+        format!("context:{}", digest)
+    }
+
+    /// Preview what `serialize` would include, without generating content
+    ///
+    /// Runs the same walk, truncation and budget passes and reports the
+    /// action, estimated tokens and reason for every file.
+    pub fn preview(&self, root: &str) -> Result<ContextPreview> {
+        let walk_config = self.walk_config();
+
+        self.check_cancelled("walk", 0, 0)?;
+        let entries = self.walker.walk(root, &walk_config)?;
+        let sorted = self.sort_entries(entries);
+        let processed = self.process_files(&sorted);
+        self.check_cancelled("processing", processed.len(), sorted.len())?;
+        let budget = self.config.token_budget;
+
+        let mut included = match budget {
+            Some(budget) => self.apply_budget(processed.clone(), budget),
+            None => processed.clone(),
+        };
+        if self.config.active_lens.as_deref() == Some(TOUR_LENS) {
+            included.insert(0, self.tour_file(&sorted));
         }
+
+        let language_rules = self.language_rules();
+        let has_symbol_rules = !self.symbol_rules().is_empty();
+        let included_paths: HashSet<&str> = included.iter().map(|f| f.path.as_str()).collect();
+
+        let mut preview_entries: Vec<PreviewEntry> = included
+            .iter()
+            .map(|file| {
+                let tier = FileTier::classify(&file.path, None);
+                let original_tokens = file.original_tokens.unwrap_or(file.tokens);
+                let (action, reason) = if file.path == TOUR_FILE_NAME {
+                    (FileAction::Full, "guided tour generated by the onboarding lens".to_string())
+                } else if file.compression_level == CompressionLevel::Skeleton {
+                    (FileAction::Skeleton, "compressed to signatures to fit the token budget".to_string())
+                } else if file.truncated {
+                    let (lines, mode) = match language_rules.get(&file.language) {
+                        Some(rule) => rule.resolve(self.config.truncate_lines, &self.config.truncate_mode),
+                        None => (self.config.truncate_lines, self.config.truncate_mode.as_str()),
+                    };
+                    let reason = if self.config.symbol_budget.is_some() && lines == 0 {
+                        "symbol bodies over the per-symbol budget summarized".to_string()
+                    } else if has_symbol_rules && lines == 0 {
+                        "declarations filtered by lens symbol rules".to_string()
+                    } else if mode == "structure" {
+                        "structure truncation (signatures only)".to_string()
+                    } else {
+                        format!("{} truncation at {} lines", mode, lines)
+                    };
+                    (FileAction::Truncated, reason)
+                } else if budget.is_some() {
+                    (FileAction::Full, format!("fits {} tier budget", tier_name(tier)))
+                } else {
+                    (FileAction::Full, "no truncation or budget applies".to_string())
+                };
+                PreviewEntry {
+                    path: file.path.clone(),
+                    action,
+                    tokens: file.tokens,
+                    original_tokens,
+                    tier: tier_name(tier),
+                    reason,
+                }
+            })
+            .collect();
+
+        // Files the budget left out
+        preview_entries.extend(
+            processed
+                .iter()
+                .filter(|file| !included_paths.contains(file.path.as_str()))
+                .map(|file| {
+                    let tier = FileTier::classify(&file.path, None);
+                    PreviewEntry {
+                        path: file.path.clone(),
+                        action: FileAction::Dropped,
+                        tokens: 0,
+                        original_tokens: file.original_tokens.unwrap_or(file.tokens),
+                        tier: tier_name(tier),
+                        reason: format!(
+                            "{} tier file (~{} tokens) exceeds the remaining budget",
+                            tier_name(tier),
+                            file.tokens
+                        ),
+                    }
+                }),
+        );
+
+        Ok(ContextPreview {
+            entries: preview_entries,
+            budget,
+            lens: self.config.active_lens.clone(),
+        })
     }
 
     /// Serialize a zoom target
     pub fn zoom(&self, root: &str, config: &ZoomConfig) -> Result<String> {
         // First, walk and find matching files
-        let walk_config = WalkConfig {
-            ignore_patterns: self.config.ignore_patterns.clone(),
-            include_patterns: self.config.include_patterns.clone(),
-            max_file_size: self.config.max_file_size,
-        };
+        let walk_config = self.walk_config();
 
         let entries = self.walker.walk(root, &walk_config)?;
 
         // Find matching content based on zoom target
-        let filtered = match &config.target {
-            ZoomTarget::Function(name) => {
-                self.find_function(&entries, name)
-            }
-            ZoomTarget::Class(name) => {
-                self.find_class(&entries, name)
-            }
-            ZoomTarget::Module(name) => {
-                self.find_module(&entries, name)
-            }
-            ZoomTarget::File { path, start_line, end_line } => {
-                self.find_file(&entries, path, *start_line, *end_line)
-            }
-        };
+        let sandbox = PathSandbox::new(root)?;
+        let resolved = self.resolve_anchor(&entries, &config.target);
+        let target = self.with_context(&entries, &resolved, config.context_lines);
+        let mut filtered = self.find_sandboxed(&sandbox, &entries, &target)?;
 
         if filtered.is_empty() {
             return Err(EncoderError::InvalidZoomTarget {
@@ -283,11 +510,257 @@ impl ContextEngine {
             });
         }
 
+        let mut related = Vec::new();
+        if config.include_tests {
+            related.extend(self.find_tests(&entries, &config.target, &filtered));
+        }
+        if config.include_docs {
+            related.extend(self.find_docs(&entries, &config.target, &filtered));
+        }
+        related.extend(self.related_symbols(&entries, &resolved));
+        filtered.extend(related);
+
         // Process and serialize
         let processed = self.process_files(&filtered);
         Ok(self.serializer.serialize_files(&processed))
     }
 
+    /// Warnings for capabilities the project's source languages lack
+    ///
+    /// Names each capability (e.g. the calls `include_tests` relies on) that
+    /// the adapters of some languages in the project do not extract, or that
+    /// have no adapter at all, so results can be read as possibly incomplete.
+    pub fn capability_warnings(&self, root: &str, capabilities: &[Capability]) -> Result<Vec<String>> {
+        if capabilities.is_empty() {
+            return Ok(Vec::new());
+        }
+        let entries = self.walker.walk(root, &self.walk_config())?;
+        let bridge = AstBridge::new();
+        let warnings = capabilities
+            .iter()
+            .filter_map(|&capability| {
+                let lacking = bridge.languages_lacking(entries.iter().map(|e| e.path.as_str()), capability);
+                if lacking.is_empty() {
+                    return None;
+                }
+                let languages: Vec<String> = lacking
+                    .iter()
+                    .map(|(language, files)| format!("{} ({} file{})", language.name(), files, if *files == 1 { "" } else { "s" }))
+                    .collect();
+                Some(format!(
+                    "{} not extracted for {}: results may be incomplete",
+                    capability.label(),
+                    languages.join(", ")
+                ))
+            })
+            .collect();
+        Ok(warnings)
+    }
+
+    /// Zoom into several targets at once, packed into one shared budget
+    ///
+    /// The batch is atomic: if any target fails to resolve, nothing is
+    /// returned. Content shared by several targets is emitted once.
+    pub fn zoom_batch(&self, root: &str, config: &BatchZoomConfig) -> Result<String> {
+        use crate::core::serialization::truncation_marker;
+
+        if config.targets.is_empty() {
+            return Err(EncoderError::invalid_config("Batch zoom requires at least one target"));
+        }
+
+        let walk_config = self.walk_config();
+        let entries = self.walker.walk(root, &walk_config)?;
+        let sandbox = PathSandbox::new(root)?;
+
+        // Resolve every target before emitting anything
+        let mut seen: HashSet<(String, String)> = HashSet::new();
+        let mut groups: Vec<Vec<ProcessedFile>> = Vec::new();
+        for target in &config.targets {
+            let matches = self.find_sandboxed(&sandbox, &entries, target)?;
+            if matches.is_empty() {
+                return Err(EncoderError::InvalidZoomTarget {
+                    target: target.to_string(),
+                });
+            }
+            let unique: Vec<FileEntry> = matches
+                .into_iter()
+                .filter(|e| seen.insert((e.path.clone(), e.content.clone())))
+                .collect();
+            groups.push(self.process_files(&unique));
+        }
+
+        let demands: Vec<usize> = groups
+            .iter()
+            .map(|files| files.iter().map(|f| f.tokens).sum())
+            .collect();
+        let allocations = match config.budget {
+            Some(budget) => config.strategy.allocate(&demands, budget),
+            None => demands.clone(),
+        };
+
+        let mut packed: Vec<ProcessedFile> = Vec::new();
+        for (files, allocation) in groups.into_iter().zip(allocations) {
+            let mut remaining = allocation;
+            for file in files {
+                if file.tokens <= remaining {
+                    remaining -= file.tokens;
+                    packed.push(file);
+                    continue;
+                }
+
+                // Cut the file down to what is left of this target's share
+                let lines: Vec<&str> = file.content.lines().collect();
+                let mut kept_chars = 0;
+                let kept_lines = lines
+                    .iter()
+                    .take_while(|line| {
+                        kept_chars += line.len() + 1;
+                        kept_chars / 4 <= remaining
+                    })
+                    .count();
+                let zoom_action = ZoomAction::for_file(&file.path, file.tokens);
+                let mut truncated = lines[..kept_lines].join("\n");
+                truncated.push('\n');
+                truncated.push_str(&truncation_marker(lines.len(), kept_lines, Some(&zoom_action)));
+
+                let original_tokens = file.tokens;
+                remaining = 0;
+                packed.push(file.with_truncation(truncated, original_tokens));
+            }
+        }
+
+        Ok(self.serializer.serialize_files(&packed))
+    }
+
+    /// Render the project at mixed levels of detail
+    ///
+    /// Every file is listed in a leading `FILES.md` index; files at LOD1 and
+    /// above also get a block rendered at their level. With a token budget
+    /// the plan is filled breadth-first (see [`LodPlan::assign`]).
+    pub fn render_lod(&self, root: &str, plan: &LodPlan) -> Result<String> {
+        let entries = self.walker.walk(root, &self.walk_config())?;
+        let sorted = self.sort_entries(entries);
+        let lods = plan.assign(&sorted, self.config.token_budget);
+
+        let index = FileEntry::new(LOD_INDEX_FILE_NAME, lod::file_index(&sorted, &lods));
+        let mut files = vec![ProcessedFile::from_entry(&index, "markdown", i32::MAX)];
+        for (entry, level) in sorted.iter().zip(lods) {
+            if level == Lod::Files {
+                continue;
+            }
+            let file = ProcessedFile::from_entry(entry, &detect_language(&entry.path), 0);
+            let original_tokens = file.tokens;
+            let rendered = plan.render(&entry.path, &entry.content, level);
+            if level != Lod::Full {
+                files.push(file.with_skeleton(rendered, original_tokens));
+            } else if rendered != entry.content {
+                // Symbols over the per-symbol budget were downgraded
+                files.push(file.with_truncation(rendered, original_tokens));
+            } else {
+                files.push(file);
+            }
+        }
+
+        if self.config.output_format == OutputFormat::ClaudeXml {
+            self.serialize_claude_xml(&files)
+        } else {
+            Ok(self.serializer.serialize_files(&files))
+        }
+    }
+
+    /// Find a zoom target, keeping file targets and the files they match inside the root
+    ///
+    /// Rejects `..` escapes, absolute paths and symlinks out of the root with
+    /// [`EncoderError::PolicyViolation`].
+    fn find_sandboxed(&self, sandbox: &PathSandbox, entries: &[FileEntry], target: &ZoomTarget) -> Result<Vec<FileEntry>> {
+        sandbox.check_target(target)?;
+        let matches = self.find_target(entries, target);
+        if matches!(target, ZoomTarget::File { .. }) {
+            for entry in &matches {
+                sandbox.resolve_relative(&entry.path)?;
+            }
+        }
+        Ok(matches)
+    }
+
+    /// Widen a line-range file target by `context_lines` of surrounding context
+    ///
+    /// The range grows to syntax node boundaries, so context never starts or
+    /// ends inside a statement, comment or string.
+    fn with_context(&self, entries: &[FileEntry], target: &ZoomTarget, context_lines: usize) -> ZoomTarget {
+        let ZoomTarget::File { path, start_line: Some(start), end_line } = target else {
+            return target.clone();
+        };
+        let file = self.find_file(entries, path, None, None);
+        let Some(entry) = file.first().filter(|_| context_lines > 0) else {
+            return target.clone();
+        };
+        let end = end_line.unwrap_or_else(|| entry.content.lines().count()).max(*start);
+        let language = AstBridge::detect_language(Path::new(&entry.path));
+        let window = AstBridge::new().context_window(&entry.content, language, *start, end, context_lines);
+        ZoomTarget::File {
+            path: path.clone(),
+            start_line: Some(window.start_line),
+            end_line: end_line.map(|_| window.end_line),
+        }
+    }
+
+    /// Find the entries (or entry slices) matching a zoom target
+    fn find_target(&self, entries: &[FileEntry], target: &ZoomTarget) -> Vec<FileEntry> {
+        match target {
+            ZoomTarget::Function(name) => self.find_function(entries, name),
+            ZoomTarget::Class(name) => self.find_class(entries, name),
+            ZoomTarget::Module(name) => self.find_module(entries, name),
+            ZoomTarget::Section(name) => self.find_section(entries, name),
+            ZoomTarget::Anchor(_) => match self.resolve_anchor(entries, target) {
+                ZoomTarget::File { path, start_line, end_line } => self.find_file(entries, &path, start_line, end_line),
+                _ => Vec::new(),
+            },
+            ZoomTarget::File { path, start_line, end_line } => {
+                self.find_file(entries, path, *start_line, *end_line)
+            }
+        }
+    }
+
+    /// Resolve an anchor target to the line range of its declaration
+    ///
+    /// Other targets, and anchors that address nothing, are returned as is.
+    fn resolve_anchor(&self, entries: &[FileEntry], target: &ZoomTarget) -> ZoomTarget {
+        let ZoomTarget::Anchor(anchor) = target else {
+            return target.clone();
+        };
+        let file = self.find_file(entries, &anchor.path, None, None);
+        match file.first().and_then(|entry| anchor.locate(&entry.content)) {
+            Some(decl) => ZoomTarget::File {
+                path: file[0].path.clone(),
+                start_line: Some(decl.span.start_line),
+                end_line: Some(decl.span.end_line),
+            },
+            None => target.clone(),
+        }
+    }
+
+    /// Build the synthetic tour file embedded by the onboarding lens
+    fn tour_file(&self, entries: &[FileEntry]) -> ProcessedFile {
+        let entry = tour_file(entries.iter().map(|e| (e.path.as_str(), e.content.as_str())));
+        ProcessedFile::from_entry(&entry, "markdown", i32::MAX)
+    }
+
+    /// Build the synthetic `CONTENTS.md` file listing `files` as serialized
+    fn toc_file(&self, files: &[ProcessedFile]) -> ProcessedFile {
+        let mut toc = TableOfContents::new();
+        for file in files.iter().filter(|f| f.compression_level != CompressionLevel::Drop) {
+            let depth = match file.compression_level {
+                CompressionLevel::Skeleton => "skeleton",
+                _ if file.truncated => "truncated",
+                _ => "full",
+            };
+            toc.add_file(&file.path, &file.content, depth);
+        }
+        let entry = FileEntry::new(TOC_FILE_NAME, toc.to_markdown());
+        ProcessedFile::from_entry(&entry, "markdown", i32::MAX)
+    }
+
     /// Sort entries based on configuration
     fn sort_entries(&self, mut entries: Vec<FileEntry>) -> Vec<FileEntry> {
         let is_desc = self.config.sort_order == "desc";
@@ -322,21 +795,107 @@ impl ContextEngine {
         entries
     }
 
+    /// Effective per-language truncation rules
+    ///
+    /// Rules from the active lens apply first; rules set on the config override them.
+    fn language_rules(&self) -> HashMap<String, LanguageTruncation> {
+        let mut rules = self
+            .config
+            .active_lens
+            .as_deref()
+            .and_then(|name| LensManager::new().get_lens(name).map(|lens| lens.languages.clone()))
+            .unwrap_or_default();
+        rules.extend(self.config.language_rules.clone());
+        rules
+    }
+
+    /// Effective declaration-level rules
+    ///
+    /// Rules from the active lens come first (and so win); rules set on the
+    /// config follow them.
+    fn symbol_rules(&self) -> Vec<SymbolRule> {
+        let mut rules = self
+            .config
+            .active_lens
+            .as_deref()
+            .and_then(|name| LensManager::new().get_lens(name).map(|lens| lens.symbols.clone()))
+            .unwrap_or_default();
+        rules.extend(self.config.symbol_rules.iter().cloned());
+        rules
+    }
+
     /// Process files (detect language, apply truncation)
+    ///
+    /// Stops early (returning the files processed so far) when the
+    /// cancellation token fires.
     fn process_files(&self, entries: &[FileEntry]) -> Vec<ProcessedFile> {
         use crate::core::serialization::truncation_marker;
 
-        entries.iter().map(|entry| {
+        let language_rules = self.language_rules();
+        let symbol_rules = self.symbol_rules();
+
+        entries.iter().take_while(|_| !self.cancel.is_cancelled()).map(|entry| {
             let language = detect_language(&entry.path);
             let priority = 50; // TODO: Get from lens manager
 
+            let rule = language_rules.get(&language);
+            let (truncate_lines, truncate_mode) = match rule {
+                Some(rule) => rule.resolve(self.config.truncate_lines, &self.config.truncate_mode),
+                None => (self.config.truncate_lines, self.config.truncate_mode.as_str()),
+            };
+            let keep_docstrings = rule.is_some_and(|r| r.keep_docstrings);
+
             let mut processed = ProcessedFile::from_entry(entry, &language, priority);
+            if self.config.provenance_annotations {
+                processed.provenance = Provenance::classify(&entry.path, &entry.content);
+            }
+
+            // Drop or reduce declarations selected by the lens symbol rules
+            if !symbol_rules.is_empty() {
+                let filtered = apply_symbol_rules(&entry.path, &processed.content, &symbol_rules);
+                if filtered.is_filtered() {
+                    processed = processed.with_truncation(filtered.content, entry.token_estimate());
+                }
+            }
+
+            // Summarize oversized symbol bodies if a per-symbol budget is configured
+            if let Some(symbol_budget) = self.config.symbol_budget {
+                let summarized = summarize_symbols(
+                    self.summarizer.as_ref(),
+                    &entry.path,
+                    &processed.content,
+                    symbol_budget,
+                );
+                if summarized.is_summarized() {
+                    processed = processed.with_truncation(summarized.content, entry.token_estimate());
+                }
+            }
+
+            // Language-aware modes delegate to the shared truncation strategies
+            let delegated = matches!(truncate_mode, "smart" | "structure" | "window");
+            if delegated && (truncate_lines > 0 || truncate_mode == "structure") {
+                let include_summary = self.config.truncate_summary;
+                let (truncated, was_truncated) = match truncate_mode {
+                    "smart" => crate::truncate_smart_with_options(&processed.content, truncate_lines, &entry.path, include_summary),
+                    "structure" => crate::truncate_structure_with_fallback(&processed.content, &entry.path, include_summary, truncate_lines),
+                    "window" => crate::truncate_window_with_options(&processed.content, truncate_lines, &entry.path, include_summary),
+                    _ => (processed.content.clone(), false),
+                };
+                if was_truncated {
+                    processed = processed.with_truncation(truncated, entry.token_estimate());
+                }
+                return processed;
+            }
 
             // Apply truncation if configured
-            if self.config.truncate_lines > 0 {
-                let lines: Vec<&str> = entry.content.lines().collect();
-                if lines.len() > self.config.truncate_lines {
-                    let kept_lines = self.config.truncate_lines;
+            if truncate_lines > 0 {
+                let lines: Vec<&str> = processed.content.lines().collect();
+                if lines.len() > truncate_lines {
+                    let kept_lines = if keep_docstrings {
+                        docstring_safe_cutoff(&lines, truncate_lines)
+                    } else {
+                        truncate_lines
+                    };
                     let original_lines = lines.len();
                     let original_tokens = entry.token_estimate();
 
@@ -354,7 +913,9 @@ impl ContextEngine {
                         ));
                     }
 
-                    processed = processed.with_truncation(truncated, original_tokens);
+                    if kept_lines < original_lines {
+                        processed = processed.with_truncation(truncated, original_tokens);
+                    }
                 }
             }
 
@@ -488,11 +1049,12 @@ impl ContextEngine {
             }
         }
 
-        // Sort each tier by priority (highest first)
-        core_files.sort_by(|a, b| b.priority.cmp(&a.priority));
-        config_files.sort_by(|a, b| b.priority.cmp(&a.priority));
-        test_files.sort_by(|a, b| b.priority.cmp(&a.priority));
-        other_files.sort_by(|a, b| b.priority.cmp(&a.priority));
+        // Sort each tier by priority (highest first), then path, so ties never depend on walk order
+        let by_priority = |a: &ProcessedFile, b: &ProcessedFile| b.priority.cmp(&a.priority).then_with(|| a.path.cmp(&b.path));
+        core_files.sort_by(by_priority);
+        config_files.sort_by(by_priority);
+        test_files.sort_by(by_priority);
+        other_files.sort_by(by_priority);
 
         let mut result = Vec::new();
         let mut used = 0;
@@ -559,6 +1121,7 @@ impl ContextEngine {
             } else {
                 None
             },
+            warnings: Vec::new(),
         };
 
         let mut writer = XmlWriter::new(&mut buffer, xml_config);
@@ -586,12 +1149,18 @@ impl ContextEngine {
                 None
             };
 
+            // Claude-XML file tags have no room for notes: the content opens with it
+            let content = match &file.provenance {
+                Some(provenance) => provenance.annotate(&file.path, &file.content, &BoilerplateRules::default()),
+                None => file.content.clone(),
+            };
+
             writer.write_file(
                 &file.path,
                 &file.language,
                 &file.md5,
                 file.priority,
-                &file.content,
+                &content,
                 file.truncated,
                 file.original_tokens,
                 zoom_cmd.as_deref(),
@@ -634,9 +1203,148 @@ impl ContextEngine {
             .collect()
     }
 
+    /// Symbols a zoom target is about: the named symbol, or those declared in the zoomed content
+    fn zoom_symbols(&self, target: &ZoomTarget, zoomed: &[FileEntry]) -> Vec<String> {
+        match target {
+            ZoomTarget::Function(name) | ZoomTarget::Class(name) => vec![name.clone()],
+            _ => zoomed.iter().flat_map(|e| doclinks::declared_names(&e.path, &e.content)).collect(),
+        }
+    }
+
+    /// Tests that exercise the symbols of a zoom target (see [`PlanetariumModel::tests_for`])
+    fn find_tests(&self, entries: &[FileEntry], target: &ZoomTarget, zoomed: &[FileEntry]) -> Vec<FileEntry> {
+        let bridge = AstBridge::new();
+        let mut model = PlanetariumModel::new("");
+        for entry in entries {
+            let language = AstBridge::detect_language(Path::new(&entry.path));
+            if let Some(file) = bridge.analyze_file(&entry.content, language) {
+                model.files.insert(RawPath::from_key(&entry.path), file);
+            }
+        }
+
+        let mut seen = HashSet::new();
+        self.zoom_symbols(target, zoomed)
+            .iter()
+            .flat_map(|symbol| model.tests_for(symbol))
+            .filter(|link| seen.insert((link.path.to_key(), link.span.start_line)))
+            .flat_map(|link| {
+                self.find_file(entries, &link.path.to_key(), Some(link.span.start_line), Some(link.span.end_line))
+            })
+            // Tests inside the zoomed code are already included
+            .filter(|test| !zoomed.iter().any(|z| z.path == test.path && z.content.contains(&test.content)))
+            .collect()
+    }
+
+    /// Declarations related to a zoomed symbol, as a Markdown list of zoom
+    /// affordances (see [`voyager_ast::related`])
+    ///
+    /// Only symbol targets qualify: `function=`/`class=` names, or a file
+    /// range spanning exactly one declaration (how the MCP server resolves them).
+    fn related_symbols(&self, entries: &[FileEntry], target: &ZoomTarget) -> Option<FileEntry> {
+        let is_callable = |d: &AstDeclaration| matches!(d.kind, DeclarationKind::Function | DeclarationKind::Method);
+        let named = |d: &AstDeclaration, name: &str| d.name == name.rsplit([':', '.']).next().unwrap_or(name);
+        let wanted = |d: &AstDeclaration| match target {
+            ZoomTarget::Function(name) => named(d, name) && is_callable(d),
+            ZoomTarget::Class(name) => named(d, name) && !is_callable(d),
+            ZoomTarget::File { start_line, end_line, .. } => {
+                Some(d.span.start_line) == *start_line && Some(d.span.end_line) == *end_line
+            }
+            _ => false,
+        };
+        let paths: Vec<String> = match target {
+            ZoomTarget::Function(_) | ZoomTarget::Class(_) => entries.iter().map(|e| e.path.clone()).collect(),
+            ZoomTarget::File { path, start_line: Some(_), end_line: Some(_) } => {
+                self.find_file(entries, path, None, None).into_iter().map(|e| e.path).collect()
+            }
+            _ => return None,
+        };
+
+        let bridge = AstBridge::new();
+        let files: Vec<(RawPath, AstFile, &str)> = entries
+            .iter()
+            .filter_map(|e| {
+                let language = AstBridge::detect_language(Path::new(&e.path));
+                let file = bridge.analyze_file(&e.content, language)?;
+                Some((RawPath::from_key(&e.path), file, e.content.as_str()))
+            })
+            .collect();
+        let (path, symbol) = files
+            .iter()
+            .filter(|(path, _, _)| paths.contains(&path.to_key()))
+            .find_map(|(path, file, _)| find_declaration(&file.declarations, &wanted).map(|d| (path, d)))?;
+
+        let related = voyager_ast::related::related_symbols(
+            files.iter().map(|(p, f, s)| (p, f, *s)),
+            path,
+            symbol,
+            MAX_RELATED_SYMBOLS,
+        );
+        if related.is_empty() {
+            return None;
+        }
+        let mut markdown = format!("# Related symbols of `{}`\n\n", symbol.name);
+        for r in &related {
+            let content = files.iter().find(|(p, _, _)| p.to_key() == r.path).map_or("", |(_, _, s)| *s);
+            let action = ZoomAction::for_declaration(&AstDeclaration::new(r.name.clone(), r.kind, r.span), content);
+            markdown.push_str(&format!(
+                "- {} `{}` ({}, {}:{}) {}\n",
+                r.relation.label(),
+                r.name,
+                r.kind.as_str(),
+                r.path,
+                r.span.start_line,
+                action.to_affordance_comment()
+            ));
+        }
+        Some(FileEntry::new(RELATED_FILE_NAME, markdown))
+    }
+
+    /// Doc sections and docstrings that mention the symbols of a zoom target
+    fn find_docs(&self, entries: &[FileEntry], target: &ZoomTarget, zoomed: &[FileEntry]) -> Vec<FileEntry> {
+        let symbols = self.zoom_symbols(target, zoomed);
+        let pairs: Vec<(String, String)> = entries
+            .iter()
+            .map(|e| (e.path.clone(), e.content.clone()))
+            .collect();
+        let links = DocLinks::build(&pairs);
+
+        let mut seen = HashSet::new();
+        symbols.iter()
+            .flat_map(|symbol| links.docs_for(symbol))
+            .filter(|mention| seen.insert((*mention).clone()))
+            .flat_map(|mention| {
+                self.find_file(entries, &mention.doc_path, Some(mention.start_line), Some(mention.end_line))
+            })
+            .collect()
+    }
+
+    fn find_section(&self, entries: &[FileEntry], target: &str) -> Vec<FileEntry> {
+        let (doc, name) = outline::split_section_target(target);
+        let candidates: Vec<FileEntry> = match doc {
+            Some(path) => self.find_file(entries, path, None, None),
+            None => entries.iter().filter(|e| outline::is_markdown(&e.path)).cloned().collect(),
+        };
+        candidates.iter()
+            .filter_map(|e| {
+                let section = outline::find_section(&e.content, name)?;
+                Some(self.find_file(std::slice::from_ref(e), &e.path, Some(section.start_line), Some(section.end_line)))
+            })
+            .flatten()
+            .collect()
+    }
+
     fn find_file(&self, entries: &[FileEntry], path: &str, start: Option<usize>, end: Option<usize>) -> Vec<FileEntry> {
+        // Accept Windows separators, "./" and ".." segments, and (optionally) any case
+        let fold = |p: &str| {
+            let p = normalize_relative_path(p);
+            if self.config.case_insensitive_paths { p.to_lowercase() } else { p }
+        };
+        let wanted = fold(path);
         entries.iter()
-            .filter(|e| e.path == path || e.path.ends_with(path))
+            .filter(|e| {
+                let candidate = fold(&e.path);
+                candidate == wanted || candidate.ends_with(&wanted)
+            })
             .map(|e| {
                 if start.is_some() || end.is_some() {
                     let lines: Vec<&str> = e.content.lines().collect();
@@ -650,6 +1358,7 @@ impl ContextEngine {
                         md5: e.md5.clone(),
                         mtime: e.mtime,
                         ctime: e.ctime,
+                        format: e.format.clone(),
                     }
                 } else {
                     e.clone()
@@ -665,6 +1374,63 @@ impl Default for ContextEngine {
     }
 }
 
+/// Related symbols suggested by a symbol zoom
+const MAX_RELATED_SYMBOLS: usize = 8;
+
+/// First declaration (depth-first) matching `wanted`
+fn find_declaration<'a>(decls: &'a [AstDeclaration], wanted: &impl Fn(&AstDeclaration) -> bool) -> Option<&'a AstDeclaration> {
+    decls.iter().find_map(|d| if wanted(d) { Some(d) } else { find_declaration(&d.children, wanted) })
+}
+
+/// Move a line cutoff past the end of a doc block it would split
+///
+/// Handles Python triple-quoted docstrings, `/** ... */` blocks, and runs of
+/// `///`/`//!` doc comments.
+pub(crate) fn docstring_safe_cutoff(lines: &[&str], cutoff: usize) -> usize {
+    let mut in_triple = false;
+    let mut in_block = false;
+    for line in &lines[..cutoff] {
+        let quotes = line.matches("\"\"\"").count() + line.matches("'''").count();
+        if quotes % 2 == 1 {
+            in_triple = !in_triple;
+        }
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("/**") && !line.contains("*/") {
+            in_block = true;
+        } else if in_block && line.contains("*/") {
+            in_block = false;
+        }
+    }
+
+    let mut end = cutoff;
+    if in_triple {
+        while end < lines.len() {
+            end += 1;
+            if lines[end - 1].contains("\"\"\"") || lines[end - 1].contains("'''") {
+                break;
+            }
+        }
+    } else if in_block {
+        while end < lines.len() {
+            end += 1;
+            if lines[end - 1].contains("*/") {
+                break;
+            }
+        }
+    } else {
+        let is_doc = |l: &str| {
+            let t = l.trim_start();
+            t.starts_with("///") || t.starts_with("//!")
+        };
+        if is_doc(lines[cutoff - 1]) {
+            while end < lines.len() && is_doc(lines[end]) {
+                end += 1;
+            }
+        }
+    }
+    end
+}
+
 /// Detect programming language from file extension
 pub fn detect_language(path: &str) -> String {
     let ext = path.rsplit('.').next().unwrap_or("");
@@ -697,6 +1463,7 @@ pub fn detect_language(path: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::zoom::PackingStrategy;
     use tempfile::TempDir;
     use std::fs;
 
@@ -739,6 +1506,28 @@ mod tests {
         assert!(output.contains("def hello()"));
     }
 
+    #[test]
+    fn test_engine_serialize_deduplicated() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.py"), "def a(): pass").unwrap();
+        fs::write(temp_dir.path().join("b.py"), "def b(): pass").unwrap();
+        let root = temp_dir.path().to_str().unwrap();
+
+        let engine = ContextEngine::new();
+        let mut ledger = ContentLedger::new();
+        let first = engine.serialize_deduplicated(root, &mut ledger, 1).unwrap();
+        assert!(first.contains("def a()") && first.contains("def b()"));
+
+        fs::write(temp_dir.path().join("b.py"), "def b(): return 2").unwrap();
+        let second = engine.serialize_deduplicated(root, &mut ledger, 2).unwrap();
+        assert!(!second.contains("def a()"));
+        assert!(second.contains("«unchanged since msg #1»"));
+        assert!(second.contains("def b(): return 2"));
+
+        let third = engine.serialize_deduplicated(root, &mut ledger, 3).unwrap();
+        assert!(third.contains("«unchanged since msg #2»"));
+    }
+
     #[test]
     fn test_engine_sort_entries() {
         let engine = ContextEngine::new();
@@ -754,6 +1543,46 @@ mod tests {
         assert_eq!(sorted[2].path, "c.txt");
     }
 
+    #[test]
+    fn test_engine_context_cache() {
+        let project = TempDir::new().unwrap();
+        let blobs = TempDir::new().unwrap();
+        fs::write(project.path().join("a.py"), "print('a')\n").unwrap();
+        let root = project.path().to_str().unwrap();
+        let store = BlobStore::open(blobs.path()).unwrap();
+
+        let engine = ContextEngine::new().with_blob_store(store.clone());
+        let first = engine.serialize(root).unwrap();
+        assert_eq!(engine.serialize(root).unwrap(), first);
+        assert_eq!(store.stats().refs, 1);
+
+        // A different configuration is a different cache entry
+        let truncated = ContextEngine::with_config(EncoderConfig::new().with_truncation(1, "simple"))
+            .with_blob_store(store.clone());
+        truncated.serialize(root).unwrap();
+        assert_eq!(store.stats().refs, 2);
+
+        fs::write(project.path().join("a.py"), "print('changed')\n").unwrap();
+        assert!(engine.serialize(root).unwrap().contains("changed"));
+    }
+
+    #[test]
+    fn test_engine_cancellation() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("a.py"), "print('a')\n").unwrap();
+        let root = temp.path().to_str().unwrap();
+
+        let token = CancellationToken::new();
+        let engine = ContextEngine::new().with_cancellation(token.clone());
+        assert!(engine.serialize(root).is_ok());
+
+        token.cancel();
+        assert!(matches!(engine.serialize(root), Err(EncoderError::Cancelled { stage: "walk", .. })));
+        assert!(matches!(engine.preview(root), Err(EncoderError::Cancelled { .. })));
+        // Processing stops before the first file
+        assert!(engine.process_files(&[FileEntry::new("a.py", "x")]).is_empty());
+    }
+
     #[test]
     fn test_engine_process_files_with_truncation() {
         let config = EncoderConfig::new().with_truncation(2, "simple");
@@ -772,6 +1601,77 @@ mod tests {
         assert!(processed[0].content.contains("ZOOM_AFFORDANCE"));
     }
 
+    #[test]
+    fn test_language_rule_never_truncate() {
+        let config = EncoderConfig::new()
+            .with_truncation(2, "simple")
+            .with_language_rule("sql", LanguageTruncation::never());
+        let engine = ContextEngine::with_config(config);
+
+        let entries = vec![
+            FileEntry::new("schema.sql", "a\nb\nc\nd"),
+            FileEntry::new("main.py", "a\nb\nc\nd"),
+        ];
+        let processed = engine.process_files(&entries);
+        assert!(!processed[0].truncated);
+        assert_eq!(processed[0].content, "a\nb\nc\nd");
+        assert!(processed[1].truncated);
+    }
+
+    #[test]
+    fn test_language_rule_overrides_line_limit() {
+        let config = EncoderConfig::new()
+            .with_truncation(10, "simple")
+            .with_language_rule("typescript", LanguageTruncation::lines(1));
+        let engine = ContextEngine::with_config(config);
+
+        let entries = vec![FileEntry::new("gen/api.ts", "l1\nl2\nl3")];
+        let processed = engine.process_files(&entries);
+        assert!(processed[0].truncated);
+        assert!(!processed[0].content.contains("l2"));
+    }
+
+    #[test]
+    fn test_language_rule_keeps_docstrings() {
+        let content = "def f():\n    \"\"\"Doc line one\n    doc line two\n    \"\"\"\n    return 1\n    x = 2\n    y = 3";
+        let config = EncoderConfig::new()
+            .with_truncation(2, "simple")
+            .with_language_rule("python", LanguageTruncation::default().with_keep_docstrings(true));
+        let engine = ContextEngine::with_config(config);
+
+        let processed = engine.process_files(&[FileEntry::new("m.py", content)]);
+        assert!(processed[0].truncated);
+        assert!(processed[0].content.contains("doc line two"));
+        assert!(!processed[0].content.contains("x = 2"));
+    }
+
+    #[test]
+    fn test_lens_language_rules_apply() {
+        // The architecture lens never truncates TOML
+        let config = EncoderConfig::new()
+            .with_truncation(1, "simple")
+            .with_lens("architecture");
+        let engine = ContextEngine::with_config(config);
+
+        let processed = engine.process_files(&[FileEntry::new("Cargo.toml", "[package]\nname = \"x\"")]);
+        assert!(!processed[0].truncated);
+    }
+
+    #[test]
+    fn test_symbol_rules_apply() {
+        use crate::core::models::{SymbolAction, SymbolRule};
+
+        let config = EncoderConfig::new().with_symbol_rule(SymbolRule::new(SymbolAction::Exclude).with_tests(true));
+        let engine = ContextEngine::with_config(config);
+
+        let source = "pub fn load() -> u32 {\n    1\n}\n\n#[test]\nfn test_load() {\n    assert_eq!(load(), 1);\n}\n";
+        let processed = engine.process_files(&[FileEntry::new("src/lib.rs", source), FileEntry::new("notes.txt", "test_load")]);
+        assert!(processed[0].truncated);
+        assert!(processed[0].content.contains("pub fn load() -> u32 {\n    1\n}"));
+        assert!(processed[0].content.contains("LENS: excluded function test_load"));
+        assert!(!processed[1].truncated, "files without an AST adapter are unchanged");
+    }
+
     #[test]
     fn test_engine_apply_budget() {
         use crate::core::models::CompressionLevel;
@@ -793,6 +1693,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
             ProcessedFile {
                 path: "small.py".to_string(),
@@ -805,6 +1706,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
         ];
 
@@ -877,6 +1779,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
             ProcessedFile {
                 path: "src/main.rs".to_string(),
@@ -889,6 +1792,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
             ProcessedFile {
                 path: "README.md".to_string(),
@@ -901,6 +1805,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
         ];
 
@@ -931,6 +1836,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
             ProcessedFile {
                 path: "tests/test.py".to_string(),  // Tests
@@ -943,6 +1849,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
             ProcessedFile {
                 path: "Cargo.toml".to_string(),  // Config
@@ -955,6 +1862,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
             ProcessedFile {
                 path: "src/lib.rs".to_string(),  // Core
@@ -967,6 +1875,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
         ];
 
@@ -997,6 +1906,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
             ProcessedFile {
                 path: "src/lib.rs".to_string(),
@@ -1009,6 +1919,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
             ProcessedFile {
                 path: "Cargo.toml".to_string(),
@@ -1021,6 +1932,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
             ProcessedFile {
                 path: "tests/test.py".to_string(),
@@ -1033,6 +1945,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
         ];
 
@@ -1072,6 +1985,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
             ProcessedFile {
                 path: "src/high_priority.rs".to_string(),
@@ -1084,6 +1998,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
         ];
 
@@ -1154,9 +2069,9 @@ mod tests {
         let engine = ContextEngine::with_config(config);
 
         let entries = vec![
-            FileEntry { path: "a.txt".to_string(), content: "a".to_string(), md5: "a".to_string(), mtime: 300, ctime: 0, size: 1 },
-            FileEntry { path: "b.txt".to_string(), content: "b".to_string(), md5: "b".to_string(), mtime: 100, ctime: 0, size: 1 },
-            FileEntry { path: "c.txt".to_string(), content: "c".to_string(), md5: "c".to_string(), mtime: 200, ctime: 0, size: 1 },
+            FileEntry { path: "a.txt".to_string(), content: "a".to_string(), md5: "a".to_string(), mtime: 300, ctime: 0, size: 1, format: Default::default() },
+            FileEntry { path: "b.txt".to_string(), content: "b".to_string(), md5: "b".to_string(), mtime: 100, ctime: 0, size: 1, format: Default::default() },
+            FileEntry { path: "c.txt".to_string(), content: "c".to_string(), md5: "c".to_string(), mtime: 200, ctime: 0, size: 1, format: Default::default() },
         ];
 
         let sorted = engine.sort_entries(entries);
@@ -1173,8 +2088,8 @@ mod tests {
         let engine = ContextEngine::with_config(config);
 
         let entries = vec![
-            FileEntry { path: "a.txt".to_string(), content: "a".to_string(), md5: "a".to_string(), mtime: 100, ctime: 0, size: 1 },
-            FileEntry { path: "b.txt".to_string(), content: "b".to_string(), md5: "b".to_string(), mtime: 300, ctime: 0, size: 1 },
+            FileEntry { path: "a.txt".to_string(), content: "a".to_string(), md5: "a".to_string(), mtime: 100, ctime: 0, size: 1, format: Default::default() },
+            FileEntry { path: "b.txt".to_string(), content: "b".to_string(), md5: "b".to_string(), mtime: 300, ctime: 0, size: 1, format: Default::default() },
         ];
 
         let sorted = engine.sort_entries(entries);
@@ -1190,8 +2105,8 @@ mod tests {
         let engine = ContextEngine::with_config(config);
 
         let entries = vec![
-            FileEntry { path: "a.txt".to_string(), content: "a".to_string(), md5: "a".to_string(), mtime: 0, ctime: 300, size: 1 },
-            FileEntry { path: "b.txt".to_string(), content: "b".to_string(), md5: "b".to_string(), mtime: 0, ctime: 100, size: 1 },
+            FileEntry { path: "a.txt".to_string(), content: "a".to_string(), md5: "a".to_string(), mtime: 0, ctime: 300, size: 1, format: Default::default() },
+            FileEntry { path: "b.txt".to_string(), content: "b".to_string(), md5: "b".to_string(), mtime: 0, ctime: 100, size: 1, format: Default::default() },
         ];
 
         let sorted = engine.sort_entries(entries);
@@ -1207,8 +2122,8 @@ mod tests {
         let engine = ContextEngine::with_config(config);
 
         let entries = vec![
-            FileEntry { path: "a.txt".to_string(), content: "a".to_string(), md5: "a".to_string(), mtime: 0, ctime: 100, size: 1 },
-            FileEntry { path: "b.txt".to_string(), content: "b".to_string(), md5: "b".to_string(), mtime: 0, ctime: 300, size: 1 },
+            FileEntry { path: "a.txt".to_string(), content: "a".to_string(), md5: "a".to_string(), mtime: 0, ctime: 100, size: 1, format: Default::default() },
+            FileEntry { path: "b.txt".to_string(), content: "b".to_string(), md5: "b".to_string(), mtime: 0, ctime: 300, size: 1, format: Default::default() },
         ];
 
         let sorted = engine.sort_entries(entries);
@@ -1294,6 +2209,7 @@ mod tests {
             budget: None,
             depth: ZoomDepth::Full,
             include_tests: false,
+            include_docs: false,
             context_lines: 0,
         };
 
@@ -1303,6 +2219,33 @@ mod tests {
         assert!(output.contains("target_func"));
     }
 
+    #[test]
+    fn test_zoom_anchor_target() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        fs::write(
+            temp_dir.path().join("src/lib.rs"),
+            "pub struct Engine;\n\nimpl Engine {\n    pub fn run(&self) {\n        println!(\"run\");\n    }\n}\n\nfn other() {}\n"
+        ).unwrap();
+
+        let engine = ContextEngine::new();
+        let zoom_config = ZoomConfig {
+            target: ZoomTarget::parse("anchor=src/lib.rs#Engine.run").unwrap(),
+            budget: None,
+            depth: ZoomDepth::Full,
+            include_tests: false,
+            include_docs: false,
+            context_lines: 0,
+        };
+
+        let output = engine.zoom(temp_dir.path().to_str().unwrap(), &zoom_config).unwrap();
+        assert!(output.contains("println!(\"run\")"));
+        assert!(!output.contains("fn other"));
+
+        let missing = ZoomConfig { target: ZoomTarget::parse("anchor=src/lib.rs#Engine.stop").unwrap(), ..zoom_config };
+        assert!(engine.zoom(temp_dir.path().to_str().unwrap(), &missing).is_err());
+    }
+
     #[test]
     fn test_zoom_class_target() {
         let temp_dir = TempDir::new().unwrap();
@@ -1318,6 +2261,7 @@ mod tests {
             budget: None,
             depth: ZoomDepth::Full,
             include_tests: false,
+            include_docs: false,
             context_lines: 0,
         };
 
@@ -1342,6 +2286,7 @@ mod tests {
             budget: None,
             depth: ZoomDepth::Full,
             include_tests: false,
+            include_docs: false,
             context_lines: 0,
         };
 
@@ -1369,6 +2314,7 @@ mod tests {
             budget: None,
             depth: ZoomDepth::Full,
             include_tests: false,
+            include_docs: false,
             context_lines: 0,
         };
 
@@ -1382,6 +2328,163 @@ mod tests {
         assert!(!output.contains("line1\n"));
     }
 
+    #[test]
+    fn test_zoom_context_lines_complete_nodes() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = "fn before() {\n    let s = \"x\ny\";\n}\nfn target() {}\nfn after() {}\n";
+        fs::write(temp_dir.path().join("lib.rs"), source).unwrap();
+
+        let engine = ContextEngine::new();
+        let zoom = |context_lines| {
+            let config = ZoomConfig {
+                target: ZoomTarget::parse("file=lib.rs:5-5").unwrap(),
+                budget: None,
+                depth: ZoomDepth::Full,
+                include_tests: false,
+                include_docs: false,
+                context_lines,
+            };
+            engine.zoom(temp_dir.path().to_str().unwrap(), &config).unwrap()
+        };
+
+        // One line up would land inside the string of `before`: left out
+        let narrow = zoom(1);
+        assert!(narrow.contains("fn target") && narrow.contains("fn after"));
+        assert!(!narrow.contains("y\";"));
+        // Three lines complete `before`
+        let wide = zoom(3);
+        assert!(wide.contains("+ fn before() {\n+     let s = \"x\n+ y\";\n+ }\n+ fn target"), "{}", wide);
+    }
+
+    #[test]
+    fn test_zoom_function_suggests_related_symbols() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = "fn trim(s: &str) -> &str {\n    s.trim()\n}\n\nfn apply(s: &str) -> &str {\n    trim(s)\n}\n\nfn run() {\n    apply(\"x\");\n}\n";
+        fs::write(temp_dir.path().join("lib.rs"), source).unwrap();
+
+        let engine = ContextEngine::new();
+        let config = ZoomConfig {
+            target: ZoomTarget::Function("apply".to_string()),
+            budget: None,
+            depth: ZoomDepth::Full,
+            include_tests: false,
+            include_docs: false,
+            context_lines: 0,
+        };
+        let output = engine.zoom(temp_dir.path().to_str().unwrap(), &config).unwrap();
+        assert!(output.contains(RELATED_FILE_NAME));
+        assert!(output.contains("- calls `trim` (function, lib.rs:1) /* ZOOM_AFFORDANCE: pm_encoder --zoom function=trim"));
+        assert!(output.contains("- called by `run` (function, lib.rs:9)"));
+    }
+
+    #[test]
+    fn test_zoom_file_windows_path() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("Src")).unwrap();
+        fs::write(temp_dir.path().join("Src/Lib.rs"), "pub fn lib() {}\n").unwrap();
+        let root = temp_dir.path().to_str().unwrap();
+
+        let zoom_config = |path: &str| ZoomConfig {
+            target: ZoomTarget::File {
+                path: path.to_string(),
+                start_line: None,
+                end_line: None,
+            },
+            budget: None,
+            depth: ZoomDepth::Full,
+            include_tests: false,
+            include_docs: false,
+            context_lines: 0,
+        };
+
+        let sensitive = ContextEngine::with_config(EncoderConfig::default().with_case_insensitive_paths(false));
+        assert!(sensitive.zoom(root, &zoom_config(".\\Src\\Lib.rs")).unwrap().contains("pub fn lib"));
+        assert!(sensitive.zoom(root, &zoom_config("src\\lib.rs")).is_err());
+
+        let insensitive = ContextEngine::with_config(EncoderConfig::default().with_case_insensitive_paths(true));
+        assert!(insensitive.zoom(root, &zoom_config("src\\lib.rs")).unwrap().contains("pub fn lib"));
+    }
+
+    #[test]
+    fn test_zoom_include_tests() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("tests")).unwrap();
+        fs::write(temp_dir.path().join("lib.rs"), "pub fn apply_budget() -> u32 {\n    1\n}\n").unwrap();
+        fs::write(
+            temp_dir.path().join("tests/budget.rs"),
+            "fn test_apply_budget() {\n    assert_eq!(apply_budget(), 1);\n}\n\nfn test_other() {\n    other();\n}\n",
+        ).unwrap();
+        let root = temp_dir.path().to_str().unwrap();
+
+        let zoom_config = ZoomConfig {
+            target: ZoomTarget::Function("apply_budget".to_string()),
+            budget: None,
+            depth: ZoomDepth::Full,
+            include_tests: true,
+            include_docs: false,
+            context_lines: 0,
+        };
+        let output = ContextEngine::new().zoom(root, &zoom_config).unwrap();
+        assert!(output.contains("fn test_apply_budget()"));
+        assert!(!output.contains("fn test_other()"));
+    }
+
+    #[test]
+    fn test_zoom_include_docs() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("lib.rs"), "pub fn apply_budget() {}\n").unwrap();
+        fs::write(
+            temp_dir.path().join("GUIDE.md"),
+            "# Guide\n\n## Budgets\n\nCall `apply_budget()` first.\n\n## Other\n\nUnrelated.\n",
+        ).unwrap();
+        let root = temp_dir.path().to_str().unwrap();
+
+        let mut zoom_config = ZoomConfig {
+            target: ZoomTarget::Function("apply_budget".to_string()),
+            budget: None,
+            depth: ZoomDepth::Full,
+            include_tests: false,
+            include_docs: false,
+            context_lines: 0,
+        };
+        let engine = ContextEngine::new();
+        assert!(!engine.zoom(root, &zoom_config).unwrap().contains("Call `apply_budget()` first."));
+
+        zoom_config.include_docs = true;
+        let output = engine.zoom(root, &zoom_config).unwrap();
+        assert!(output.contains("Call `apply_budget()` first."));
+        assert!(!output.contains("Unrelated."));
+    }
+
+    #[test]
+    fn test_render_lod() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("core")).unwrap();
+        fs::write(temp_dir.path().join("core/engine.rs"), "pub fn run() {\n    step();\n}\n").unwrap();
+        fs::write(temp_dir.path().join("util.rs"), "pub fn helper() {\n    work();\n}\n").unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "scratch\n").unwrap();
+        let root = temp_dir.path().to_str().unwrap();
+
+        let plan = LodPlan::new(Lod::Signatures)
+            .with_subtree("core", Lod::Full)
+            .with_subtree("notes.txt", Lod::Files);
+        let output = ContextEngine::new().render_lod(root, &plan).unwrap();
+        assert!(output.contains("- notes.txt (LOD0"));
+        assert!(output.contains("- util.rs (LOD2"));
+        assert!(output.contains("    step();"));
+        assert!(output.contains("pub fn helper() {"));
+        assert!(!output.contains("work();"));
+        assert!(!output.contains("scratch"));
+
+        // A per-symbol budget downgrades an oversized function, not its neighbours
+        let body: String = (0..60).map(|i| format!("    step_{}();\n", i)).collect();
+        fs::write(temp_dir.path().join("core/engine.rs"), format!("pub fn run() {{\n{}}}\n\npub fn stop() {{\n    halt();\n}}\n", body)).unwrap();
+        let output = ContextEngine::new().render_lod(root, &plan.with_symbol_budget(100)).unwrap();
+        assert!(output.contains("+ pub fn run() {\n+ /* LOD2: body elided"), "{}", output);
+        assert!(!output.contains("step_30();"));
+        assert!(output.contains("    halt();"));
+    }
+
     #[test]
     fn test_zoom_invalid_target() {
         let temp_dir = TempDir::new().unwrap();
@@ -1393,6 +2496,7 @@ mod tests {
             budget: None,
             depth: ZoomDepth::Full,
             include_tests: false,
+            include_docs: false,
             context_lines: 0,
         };
 
@@ -1400,6 +2504,65 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_zoom_batch_combines_targets() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.rs"), "fn alpha() {}\n").unwrap();
+        fs::write(temp_dir.path().join("b.rs"), "struct Beta {\n    x: i32,\n}\n").unwrap();
+
+        let engine = ContextEngine::new();
+        let batch = BatchZoomConfig::new(vec![
+            ZoomTarget::Function("alpha".to_string()),
+            ZoomTarget::Class("Beta".to_string()),
+            // Overlaps with the first target: emitted once
+            ZoomTarget::File { path: "a.rs".to_string(), start_line: None, end_line: None },
+        ]);
+
+        let output = engine.zoom_batch(temp_dir.path().to_str().unwrap(), &batch).unwrap();
+        assert!(output.contains("fn alpha"));
+        assert!(output.contains("struct Beta"));
+        assert_eq!(output.matches("fn alpha").count(), 1);
+    }
+
+    #[test]
+    fn test_zoom_batch_is_atomic() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.rs"), "fn alpha() {}\n").unwrap();
+
+        let engine = ContextEngine::new();
+        let batch = BatchZoomConfig::new(vec![
+            ZoomTarget::Function("alpha".to_string()),
+            ZoomTarget::Function("missing".to_string()),
+        ]);
+        assert!(engine.zoom_batch(temp_dir.path().to_str().unwrap(), &batch).is_err());
+
+        let empty = BatchZoomConfig::new(vec![]);
+        assert!(engine.zoom_batch(temp_dir.path().to_str().unwrap(), &empty).is_err());
+    }
+
+    #[test]
+    fn test_zoom_batch_priority_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let big: String = (0..100).map(|i| format!("// filler line {}\n", i)).collect();
+        fs::write(temp_dir.path().join("first.rs"), format!("fn first() {{}}\n{}", big)).unwrap();
+        fs::write(temp_dir.path().join("second.rs"), format!("fn second() {{}}\n{}", big)).unwrap();
+
+        let engine = ContextEngine::new();
+        let batch = BatchZoomConfig::new(vec![
+            ZoomTarget::Function("first".to_string()),
+            ZoomTarget::Function("second".to_string()),
+        ])
+        .with_budget(600)
+        .with_strategy(PackingStrategy::Priority);
+
+        let output = engine.zoom_batch(temp_dir.path().to_str().unwrap(), &batch).unwrap();
+        // First target fits entirely; second is cut down to the remaining share
+        assert!(output.contains("filler line 99"));
+        assert!(output.contains("fn second"));
+        assert!(output.contains("TRUNCATED"));
+        assert!(output.contains("ZOOM_AFFORDANCE"));
+    }
+
     #[test]
     fn test_serialize_claude_xml_format() {
         let temp_dir = TempDir::new().unwrap();
@@ -1438,6 +2601,7 @@ mod tests {
                 original_tokens: None,
                 compression_level: CompressionLevel::Full,
                 utility: None,
+                provenance: None,
             },
         ];
 
@@ -1528,4 +2692,107 @@ mod tests {
         assert_eq!(FileTier::classify("app_test.rs", None), FileTier::Tests);
         assert_eq!(FileTier::classify("app_test.go", None), FileTier::Tests);
     }
+
+    #[test]
+    fn test_onboarding_lens_embeds_tour() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        fs::write(temp_dir.path().join("src/main.rs"), "fn main() {\n    run();\n}\n\nfn run() {}\n").unwrap();
+
+        let config = EncoderConfig::default().with_lens("onboarding");
+        let engine = ContextEngine::with_config(config);
+        let output = engine.serialize(temp_dir.path().to_str().unwrap()).unwrap();
+
+        let tour_pos = output.find("TOUR.md").expect("tour should be embedded");
+        let main_pos = output.find("src/main.rs").unwrap();
+        assert!(tour_pos < main_pos, "Tour should come first");
+        assert!(output.contains("# Guided Tour"));
+    }
+
+    #[test]
+    fn test_table_of_contents_comes_first() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("main.rs"), "fn main() {\n    run();\n}\n\nfn run() {}\n").unwrap();
+
+        let config = EncoderConfig::default().with_table_of_contents(true);
+        let engine = ContextEngine::with_config(config);
+        let output = engine.serialize(temp_dir.path().to_str().unwrap()).unwrap();
+
+        let toc_pos = output.find(TOC_FILE_NAME).expect("contents should be embedded");
+        assert!(toc_pos < output.find("fn main()").unwrap(), "Contents should come first");
+        assert!(output.contains("  - `main.rs#run@"), "{}", output);
+    }
+
+    #[test]
+    fn test_other_lens_has_no_tour() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("main.rs"), "fn main() {}").unwrap();
+
+        let config = EncoderConfig::default().with_lens("architecture");
+        let engine = ContextEngine::with_config(config);
+        let output = engine.serialize(temp_dir.path().to_str().unwrap()).unwrap();
+        assert!(!output.contains("TOUR.md"));
+    }
+
+    #[test]
+    fn test_symbol_budget_summarizes_large_bodies() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut source = String::from("fn big() {\n");
+        for i in 0..50 {
+            source.push_str(&format!("    println!(\"step {}\");\n", i));
+        }
+        source.push_str("}\n");
+        fs::write(temp_dir.path().join("lib.rs"), &source).unwrap();
+
+        let config = EncoderConfig::default().with_symbol_budget(20);
+        let engine = ContextEngine::with_config(config);
+        let output = engine.serialize(temp_dir.path().to_str().unwrap()).unwrap();
+        assert!(output.contains("SUMMARY[extractive]: function big"));
+        assert!(!output.contains("step 25"));
+
+        let engine = ContextEngine::with_config(EncoderConfig::default().with_symbol_budget(20))
+            .with_summarizer(crate::core::summarizer::NoopSummarizer);
+        let output = engine.serialize(temp_dir.path().to_str().unwrap()).unwrap();
+        assert!(output.contains("step 25"));
+    }
+
+    #[test]
+    fn test_preview_matches_budget_decisions() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("tests")).unwrap();
+        fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}\n".repeat(20)).unwrap();
+        fs::write(temp_dir.path().join("tests/test_main.py"), "def test(): pass\n".repeat(40)).unwrap();
+
+        let config = EncoderConfig::default()
+            .with_budget(100)
+            .with_skeleton_mode(crate::core::models::SkeletonMode::Disabled);
+        let engine = ContextEngine::with_config(config);
+        let preview = engine.preview(temp_dir.path().to_str().unwrap()).unwrap();
+
+        assert_eq!(preview.budget, Some(100));
+        let main = preview.entries.iter().find(|e| e.path == "src/main.rs").unwrap();
+        assert_eq!(main.action, FileAction::Full);
+        assert_eq!(main.tier, "core");
+        let test = preview.entries.iter().find(|e| e.path == "tests/test_main.py").unwrap();
+        assert_eq!(test.action, FileAction::Dropped);
+        assert_eq!(test.tokens, 0);
+        assert!(test.reason.contains("exceeds the remaining budget"));
+        assert!(preview.total_tokens() <= 100);
+    }
+
+    #[test]
+    fn test_preview_reports_truncation() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "line\n".repeat(500)).unwrap();
+
+        let config = EncoderConfig::default().with_truncation(10, "simple");
+        let engine = ContextEngine::with_config(config);
+        let preview = engine.preview(temp_dir.path().to_str().unwrap()).unwrap();
+
+        let entry = &preview.entries[0];
+        assert_eq!(entry.action, FileAction::Truncated);
+        assert_eq!(entry.reason, "simple truncation at 10 lines");
+        assert!(entry.tokens < entry.original_tokens);
+    }
 }
@@ -2,6 +2,7 @@
 //!
 //! This module provides structured error handling using thiserror.
 
+use serde::Serialize;
 use thiserror::Error;
 use std::path::PathBuf;
 
@@ -47,6 +48,18 @@ pub enum EncoderError {
     #[error("XML generation error: {message}")]
     XmlError { message: String },
 
+    /// Operation cancelled through a `CancellationToken`
+    #[error("Cancelled during {stage} after {files_processed} of {files_total} files")]
+    Cancelled {
+        stage: &'static str,
+        files_processed: usize,
+        files_total: usize,
+    },
+
+    /// A user-supplied path would leave the sandbox root
+    #[error("Policy violation: {reason} ({path})")]
+    PolicyViolation { path: String, reason: &'static str },
+
     /// UTF-8 encoding error
     #[error("UTF-8 encoding error: {0}")]
     Utf8Error(#[from] std::string::FromUtf8Error),
@@ -76,12 +89,107 @@ impl EncoderError {
         }
     }
 
+    /// Create a policy violation for `path`
+    pub fn policy_violation(path: impl Into<String>, reason: &'static str) -> Self {
+        EncoderError::PolicyViolation {
+            path: path.into(),
+            reason,
+        }
+    }
+
     /// Create an XML error
     pub fn xml_error(message: impl Into<String>) -> Self {
         EncoderError::XmlError {
             message: message.into(),
         }
     }
+
+    /// How this error fails a CLI run
+    pub fn failure_kind(&self) -> FailureKind {
+        match self {
+            EncoderError::BudgetExceeded { .. } => FailureKind::BudgetExceeded,
+            EncoderError::PolicyViolation { .. } => FailureKind::PolicyViolation,
+            EncoderError::Json(_) | EncoderError::Utf8Error(_) => FailureKind::ParseFailed,
+            EncoderError::WithContext { source, .. } => source.failure_kind(),
+            _ => FailureKind::Error,
+        }
+    }
+}
+
+/// Why a CLI run failed, with a distinct process exit code per kind
+///
+/// Exit code 2 is left to usage errors (unknown flags, bad values), which
+/// the argument parser reports on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// Any failure without a kind of its own (exit code 1)
+    Error,
+    /// The command line could not be parsed (exit code 2)
+    Usage,
+    /// Include patterns or the lens selected none of the project's files (exit code 3)
+    NoFilesMatched,
+    /// The context is larger than a hard token budget (exit code 4)
+    BudgetExceeded,
+    /// An input file (CI report, advisory database, ...) could not be parsed (exit code 5)
+    ParseFailed,
+    /// A path was refused by the sandbox (exit code 6)
+    PolicyViolation,
+}
+
+impl FailureKind {
+    /// Every kind, in exit code order
+    pub const ALL: [FailureKind; 6] = [
+        FailureKind::Error,
+        FailureKind::Usage,
+        FailureKind::NoFilesMatched,
+        FailureKind::BudgetExceeded,
+        FailureKind::ParseFailed,
+        FailureKind::PolicyViolation,
+    ];
+
+    /// Process exit code
+    pub fn exit_code(self) -> i32 {
+        match self {
+            FailureKind::Error => 1,
+            FailureKind::Usage => 2,
+            FailureKind::NoFilesMatched => 3,
+            FailureKind::BudgetExceeded => 4,
+            FailureKind::ParseFailed => 5,
+            FailureKind::PolicyViolation => 6,
+        }
+    }
+}
+
+/// A fatal CLI error in the `--json-errors` format
+///
+/// ```text
+/// {"error":{"kind":"no_files_matched","exit_code":3,"message":"..."}}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorReport {
+    pub kind: FailureKind,
+    pub exit_code: i32,
+    pub message: String,
+    /// What to try instead, when there is a suggestion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl ErrorReport {
+    pub fn new(kind: FailureKind, message: impl Into<String>) -> Self {
+        Self { kind, exit_code: kind.exit_code(), message: message.into(), hint: None }
+    }
+
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// One-line JSON object, wrapped in `{"error": ...}`
+    pub fn to_json(&self) -> String {
+        serde_json::json!({ "error": self }).to_string()
+    }
 }
 
 /// Extension trait for adding context to Results
@@ -179,6 +287,23 @@ mod tests {
         assert!(err.to_string().contains("bad=target"));
     }
 
+    #[test]
+    fn test_failure_kinds_and_exit_codes() {
+        let codes: Vec<i32> = FailureKind::ALL.iter().map(|k| k.exit_code()).collect();
+        assert_eq!(codes, vec![1, 2, 3, 4, 5, 6]);
+
+        let escape = EncoderError::policy_violation("../etc", "path escapes the root").with_context("zoom");
+        assert_eq!(escape.failure_kind(), FailureKind::PolicyViolation);
+        assert_eq!(EncoderError::BudgetExceeded { used: 2, budget: 1 }.failure_kind(), FailureKind::BudgetExceeded);
+        assert_eq!(EncoderError::invalid_config("x").failure_kind(), FailureKind::Error);
+
+        let report = ErrorReport::new(FailureKind::NoFilesMatched, "no files matched '*.pyy'").with_hint("check --include");
+        assert_eq!(
+            report.to_json(),
+            r#"{"error":{"exit_code":3,"hint":"check --include","kind":"no_files_matched","message":"no files matched '*.pyy'"}}"#
+        );
+    }
+
     #[test]
     fn test_json_error_conversion() {
         let json_err: serde_json::Error = serde_json::from_str::<i32>("not json").unwrap_err();
@@ -0,0 +1,367 @@
+//! Flaky-area detection from CI test history
+//!
+//! Ingests JUnit XML reports from several CI runs and flags tests that both
+//! passed and failed across them. Through the test-to-subject mapping
+//! ([`PlanetariumModel::tests_for`]) each flaky test is traced back to the
+//! production symbols it exercises, which yields:
+//! - a **report** of flaky areas, worst failure rate first
+//! - a [`FlakyPrioritizer`] that moves the affected files ahead of their tier
+//!   peers, so budgeted context keeps the code most likely behind CI noise
+//!
+//! JUnit parsing is deliberately shallow (regex over `<testcase>` elements);
+//! pytest, cargo-nextest, go-junit-report and jest-junit reports all fit.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use voyager_ast::testmap::is_test_path;
+use voyager_ast::{Declaration, DeclarationKind, PlanetariumModel};
+
+use crate::budgeting::{FilePrioritizer, PathBoost, PriorityInput};
+
+/// Priority boost for files containing flaky symbols or tests
+pub const FLAKY_BOOST: i32 = 50;
+
+lazy_static! {
+    static ref TESTCASE: Regex = Regex::new(r"(?s)<testcase\b([^>]*?)(?:/>|>(.*?)</testcase>)").unwrap();
+    static ref ATTRIBUTE: Regex = Regex::new(r#"([\w:-]+)\s*=\s*"([^"]*)""#).unwrap();
+}
+
+/// Outcome of one test execution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// One `<testcase>` of a JUnit report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JunitCase {
+    pub classname: String,
+    pub name: String,
+    /// `file` attribute, when the reporter writes one
+    pub file: Option<String>,
+    pub outcome: TestOutcome,
+}
+
+/// Parse the test cases of a JUnit XML report
+pub fn parse_junit(xml: &str) -> Result<Vec<JunitCase>, String> {
+    if !xml.contains("<testsuite") && !xml.contains("<testcase") {
+        return Err("Not a JUnit XML report (no <testsuite> or <testcase>)".to_string());
+    }
+    let cases = TESTCASE
+        .captures_iter(xml)
+        .filter_map(|caps| {
+            let attributes: HashMap<&str, String> = ATTRIBUTE
+                .captures_iter(&caps[1])
+                .map(|a| (a.get(1).unwrap().as_str(), unescape(&a[2])))
+                .collect();
+            let body = caps.get(2).map_or("", |b| b.as_str());
+            let outcome = if body.contains("<failure") || body.contains("<error") {
+                TestOutcome::Failed
+            } else if body.contains("<skipped") {
+                TestOutcome::Skipped
+            } else {
+                TestOutcome::Passed
+            };
+            Some(JunitCase {
+                name: attributes.get("name")?.clone(),
+                classname: attributes.get("classname").cloned().unwrap_or_default(),
+                file: attributes.get("file").cloned(),
+                outcome,
+            })
+        })
+        .collect();
+    Ok(cases)
+}
+
+/// Pass/fail counts of one test across runs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TestHistory {
+    pub classname: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    pub passed: usize,
+    pub failed: usize,
+}
+
+impl TestHistory {
+    /// Function name the test is declared as (`mod::tests::test_x` → `test_x`,
+    /// `test_x[param]` → `test_x`, `TestX/subtest` → `TestX`)
+    pub fn function_name(&self) -> &str {
+        let name = self.name.split(['[', '(', '/']).next().unwrap_or(&self.name).trim();
+        name.rsplit(['.', ':']).next().unwrap_or(name)
+    }
+
+    /// Share of executions that failed
+    pub fn failure_rate(&self) -> f64 {
+        let runs = self.passed + self.failed;
+        if runs == 0 { 0.0 } else { self.failed as f64 / runs as f64 }
+    }
+
+    /// Both passed and failed across runs
+    pub fn is_flaky(&self) -> bool {
+        self.passed > 0 && self.failed > 0
+    }
+}
+
+/// Test outcomes accumulated over several CI runs
+#[derive(Debug, Clone, Default)]
+pub struct CiHistory {
+    tests: BTreeMap<(String, String), TestHistory>,
+    runs: usize,
+}
+
+impl CiHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load one report per path (e.g. one per CI run)
+    pub fn load(paths: &[impl AsRef<Path>]) -> Result<Self, String> {
+        let mut history = Self::new();
+        for path in paths {
+            let path = path.as_ref();
+            let xml = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let cases = parse_junit(&xml).map_err(|e| format!("{}: {}", path.display(), e))?;
+            history.add_run(&cases);
+        }
+        Ok(history)
+    }
+
+    /// Record the cases of one run (skipped cases are ignored)
+    pub fn add_run(&mut self, cases: &[JunitCase]) {
+        self.runs += 1;
+        for case in cases {
+            let entry = self.tests
+                .entry((case.classname.clone(), case.name.clone()))
+                .or_insert_with(|| TestHistory {
+                    classname: case.classname.clone(),
+                    name: case.name.clone(),
+                    file: case.file.clone(),
+                    passed: 0,
+                    failed: 0,
+                });
+            match case.outcome {
+                TestOutcome::Passed => entry.passed += 1,
+                TestOutcome::Failed => entry.failed += 1,
+                TestOutcome::Skipped => {}
+            }
+        }
+    }
+
+    /// Number of runs ingested
+    pub fn runs(&self) -> usize {
+        self.runs
+    }
+
+    /// Tests that both passed and failed, worst failure rate first
+    pub fn flaky_tests(&self) -> Vec<&TestHistory> {
+        let mut flaky: Vec<&TestHistory> = self.tests.values().filter(|t| t.is_flaky()).collect();
+        flaky.sort_by(|a, b| b.failure_rate().total_cmp(&a.failure_rate()).then_with(|| a.name.cmp(&b.name)));
+        flaky
+    }
+}
+
+/// A production symbol exercised by flaky tests
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlakyArea {
+    pub path: String,
+    pub symbol: String,
+    pub line: usize,
+    /// Flaky tests exercising the symbol
+    pub tests: Vec<String>,
+    /// Worst failure rate among those tests
+    pub failure_rate: f64,
+}
+
+/// Flaky tests and the areas they point at
+#[derive(Debug, Clone, Serialize)]
+pub struct FlakyReport {
+    pub runs: usize,
+    pub tests: Vec<TestHistory>,
+    pub areas: Vec<FlakyArea>,
+    /// Test files containing flaky tests
+    pub test_files: Vec<String>,
+}
+
+impl FlakyReport {
+    /// Map the flaky tests of `history` onto the symbols of `model`
+    pub fn from_model(model: &PlanetariumModel, history: &CiHistory) -> Self {
+        let tests: Vec<TestHistory> = history.flaky_tests().into_iter().cloned().collect();
+        let rates: HashMap<&str, f64> = tests.iter().map(|t| (t.function_name(), t.failure_rate())).collect();
+
+        let mut areas = Vec::new();
+        let mut test_files = BTreeSet::new();
+        if !rates.is_empty() {
+            for (path, file) in &model.files {
+                let path = path.to_key();
+                if is_test_path(&path) {
+                    continue;
+                }
+                visit_functions(&file.declarations, &mut |decl| {
+                    let links: Vec<_> = model
+                        .tests_for(&decl.name)
+                        .into_iter()
+                        .filter(|link| rates.contains_key(link.test.as_str()))
+                        .collect();
+                    if links.is_empty() {
+                        return;
+                    }
+                    test_files.extend(links.iter().map(|link| link.path.to_key()));
+                    let mut names: Vec<String> = links.iter().map(|link| link.test.clone()).collect();
+                    names.sort();
+                    names.dedup();
+                    areas.push(FlakyArea {
+                        path: path.clone(),
+                        symbol: decl.name.clone(),
+                        line: decl.span.start_line,
+                        failure_rate: names.iter().map(|n| rates[n.as_str()]).fold(0.0, f64::max),
+                        tests: names,
+                    });
+                });
+            }
+        }
+        areas.sort_by(|a, b| {
+            b.failure_rate.total_cmp(&a.failure_rate).then_with(|| (&a.path, a.line).cmp(&(&b.path, b.line)))
+        });
+
+        Self { runs: history.runs(), tests, areas, test_files: test_files.into_iter().collect() }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// Text report: flaky tests, then the areas they exercise
+    pub fn render_text(&self) -> String {
+        let mut out = format!("Flaky tests across {} runs: {}\n", self.runs, self.tests.len());
+        for test in &self.tests {
+            out.push_str(&format!(
+                "  {:>5.1}%  {} ({} passed, {} failed)\n",
+                test.failure_rate() * 100.0,
+                if test.classname.is_empty() { test.name.clone() } else { format!("{}::{}", test.classname, test.name) },
+                test.passed,
+                test.failed
+            ));
+        }
+        out.push_str(&format!("\nFlaky areas: {}\n", self.areas.len()));
+        for area in &self.areas {
+            out.push_str(&format!(
+                "  {:>5.1}%  {}:{} {} <- {}\n",
+                area.failure_rate * 100.0,
+                area.path,
+                area.line,
+                area.symbol,
+                area.tests.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+/// Budget prioritizer boosting flaky areas and their tests
+#[derive(Debug, Clone)]
+pub struct FlakyPrioritizer {
+    paths: PathBoost,
+}
+
+impl FlakyPrioritizer {
+    pub fn new(report: &FlakyReport) -> Self {
+        let paths = report.areas.iter().map(|a| a.path.clone()).chain(report.test_files.iter().cloned());
+        Self { paths: PathBoost::new(paths, FLAKY_BOOST) }
+    }
+}
+
+impl FilePrioritizer for FlakyPrioritizer {
+    fn name(&self) -> &str {
+        "flaky"
+    }
+
+    fn score(&self, file: &PriorityInput<'_>) -> i32 {
+        self.paths.score(file)
+    }
+}
+
+fn visit_functions<'a>(decls: &'a [Declaration], visit: &mut impl FnMut(&'a Declaration)) {
+    for decl in decls {
+        if matches!(decl.kind, DeclarationKind::Function | DeclarationKind::Method) {
+            visit(decl);
+        }
+        visit_functions(&decl.children, visit);
+    }
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voyager_ast::{AstProvider, LanguageId, RawPath, TreeSitterProvider};
+
+    fn report(outcome_of_retry: &str) -> String {
+        format!(
+            r#"<?xml version="1.0"?>
+<testsuites><testsuite name="budget" tests="3">
+  <testcase classname="budget::tests" name="budget::tests::test_apply_budget" time="0.01">{}</testcase>
+  <testcase classname="budget::tests" name="budget::tests::test_stable"/>
+  <testcase classname="budget::tests" name="test_ignored"><skipped/></testcase>
+</testsuite></testsuites>"#,
+            outcome_of_retry
+        )
+    }
+
+    #[test]
+    fn test_parse_junit() {
+        let cases = parse_junit(&report(r#"<failure message="a &lt; b">boom</failure>"#)).unwrap();
+        let outcomes: Vec<(&str, TestOutcome)> = cases.iter().map(|c| (c.name.as_str(), c.outcome)).collect();
+        assert_eq!(outcomes, vec![
+            ("budget::tests::test_apply_budget", TestOutcome::Failed),
+            ("budget::tests::test_stable", TestOutcome::Passed),
+            ("test_ignored", TestOutcome::Skipped),
+        ]);
+        assert!(parse_junit("<html></html>").is_err());
+    }
+
+    #[test]
+    fn test_flaky_report_maps_to_symbols() {
+        let mut history = CiHistory::new();
+        history.add_run(&parse_junit(&report("")).unwrap());
+        history.add_run(&parse_junit(&report("<failure/>")).unwrap());
+        history.add_run(&parse_junit(&report("")).unwrap());
+        let flaky = history.flaky_tests();
+        assert_eq!(flaky.len(), 1);
+        assert_eq!(flaky[0].function_name(), "test_apply_budget");
+
+        let provider = TreeSitterProvider::new();
+        let mut model = PlanetariumModel::new(".");
+        let source = "pub fn apply_budget() {}\npub fn stable() {}\n";
+        let tests = "fn test_apply_budget() {\n    apply_budget();\n}\nfn test_stable() {}\n";
+        model.files.insert(RawPath::from("src/budget.rs"), provider.parse_file(source, LanguageId::Rust).unwrap());
+        model.files.insert(RawPath::from("tests/budget.rs"), provider.parse_file(tests, LanguageId::Rust).unwrap());
+
+        let report = FlakyReport::from_model(&model, &history);
+        assert_eq!(report.areas.len(), 1);
+        assert_eq!((report.areas[0].path.as_str(), report.areas[0].symbol.as_str()), ("src/budget.rs", "apply_budget"));
+        assert!((report.areas[0].failure_rate - 1.0 / 3.0).abs() < 1e-9);
+        assert!(report.render_text().contains("src/budget.rs:1 apply_budget <- test_apply_budget"));
+
+        let prioritizer = FlakyPrioritizer::new(&report);
+        let input = |path| PriorityInput { path, tier: crate::core::FileTier::Core, language: "rust", lens_priority: 0, tokens: 10, lines: 1 };
+        assert_eq!(prioritizer.score(&input("tests/budget.rs")), FLAKY_BOOST);
+        assert_eq!(prioritizer.score(&input("src/other.rs")), 0);
+    }
+}
@@ -0,0 +1,157 @@
+//! GitHub Issues focus provider (`github` feature)
+//!
+//! Fetches an issue (or pull request) through the REST API and turns its
+//! title, body and labels into [`FocusHints`]. Set `GITHUB_TOKEN` (or
+//! `GH_TOKEN`) for private repositories and higher rate limits.
+
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::{FocusHints, FocusProvider};
+
+/// Default API endpoint (override for GitHub Enterprise)
+pub const GITHUB_API: &str = "https://api.github.com";
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A GitHub issue reference
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitHubIssue {
+    pub owner: String,
+    pub repo: String,
+    pub number: u64,
+    /// API base URL
+    pub api: String,
+}
+
+impl GitHubIssue {
+    /// Parse `owner/repo#123` or an issue/pull request URL
+    pub fn parse(reference: &str) -> Result<Self, String> {
+        let reference = reference.trim();
+        let invalid = || {
+            format!(
+                "Invalid issue reference '{}' (expected owner/repo#123 or https://github.com/owner/repo/issues/123)",
+                reference
+            )
+        };
+
+        let (owner, repo, number) = if let Some(rest) = reference
+            .strip_prefix("https://github.com/")
+            .or_else(|| reference.strip_prefix("http://github.com/"))
+        {
+            let parts: Vec<&str> = rest.trim_end_matches('/').split('/').collect();
+            match parts.as_slice() {
+                [owner, repo, "issues" | "pull", number, ..] => (*owner, *repo, *number),
+                _ => return Err(invalid()),
+            }
+        } else {
+            let (path, number) = reference.split_once('#').ok_or_else(invalid)?;
+            let (owner, repo) = path.split_once('/').ok_or_else(invalid)?;
+            (owner, repo, number)
+        };
+
+        let number = number.parse().map_err(|_| invalid())?;
+        if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+            return Err(invalid());
+        }
+        Ok(Self {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            number,
+            api: GITHUB_API.to_string(),
+        })
+    }
+
+    /// Use a different API base URL
+    pub fn with_api(mut self, api: impl Into<String>) -> Self {
+        self.api = api.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    fn url(&self) -> String {
+        format!("{}/repos/{}/{}/issues/{}", self.api, self.owner, self.repo, self.number)
+    }
+
+    fn source(&self) -> String {
+        format!("github:{}/{}#{}", self.owner, self.repo, self.number)
+    }
+
+    /// Hints from an issue JSON document
+    pub fn hints_from_json(&self, issue: &Value) -> FocusHints {
+        let title = issue["title"].as_str().unwrap_or_default();
+        let body = issue["body"].as_str().unwrap_or_default();
+        let labels: Vec<String> = issue["labels"]
+            .as_array()
+            .map(|labels| {
+                labels.iter()
+                    .filter_map(|l| l["name"].as_str().or_else(|| l.as_str()))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut hints = FocusHints::from_issue(title, body, &labels);
+        hints.source = Some(self.source());
+        hints
+    }
+}
+
+impl FocusProvider for GitHubIssue {
+    fn name(&self) -> &str {
+        "github"
+    }
+
+    fn hints(&self) -> Result<FocusHints, String> {
+        let agent = ureq::AgentBuilder::new().timeout(TIMEOUT).build();
+        let mut request = agent
+            .get(&self.url())
+            .set("Accept", "application/vnd.github+json")
+            .set("User-Agent", concat!("pm_encoder/", env!("CARGO_PKG_VERSION")));
+        if let Some(token) = std::env::var("GITHUB_TOKEN").ok().or_else(|| std::env::var("GH_TOKEN").ok()) {
+            request = request.set("Authorization", &format!("Bearer {}", token));
+        }
+
+        let body = request
+            .call()
+            .map_err(|e| format!("Failed to fetch {}: {}", self.source(), e))?
+            .into_string()
+            .map_err(|e| format!("Failed to read {}: {}", self.source(), e))?;
+        let issue: Value = serde_json::from_str(&body)
+            .map_err(|e| format!("Invalid response for {}: {}", self.source(), e))?;
+        Ok(self.hints_from_json(&issue))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_references() {
+        let issue = GitHubIssue::parse("alanbld/pm_encoder#42").unwrap();
+        assert_eq!((issue.owner.as_str(), issue.repo.as_str(), issue.number), ("alanbld", "pm_encoder", 42));
+        assert_eq!(issue.url(), "https://api.github.com/repos/alanbld/pm_encoder/issues/42");
+
+        let pull = GitHubIssue::parse("https://github.com/alanbld/pm_encoder/pull/7/files").unwrap();
+        assert_eq!(pull.number, 7);
+
+        assert!(GitHubIssue::parse("pm_encoder#42").is_err());
+        assert!(GitHubIssue::parse("alanbld/pm_encoder#x").is_err());
+        assert!(GitHubIssue::parse("https://github.com/alanbld").is_err());
+    }
+
+    #[test]
+    fn test_hints_from_json() {
+        let issue = GitHubIssue::parse("o/r#1").unwrap();
+        let hints = issue.hints_from_json(&json!({
+            "title": "Panic in src/lib.rs",
+            "body": null,
+            "labels": [{"name": "bug"}]
+        }));
+        assert_eq!(hints.paths, vec!["src/lib.rs"]);
+        assert_eq!(hints.keywords[0], "bug");
+        assert_eq!(hints.source.as_deref(), Some("github:o/r#1"));
+        assert_eq!(hints.suggest_lens(), Some("debug"));
+    }
+}
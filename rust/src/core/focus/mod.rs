@@ -0,0 +1,350 @@
+//! Focus hints from issue trackers
+//!
+//! A [`FocusProvider`] supplies [`FocusHints`] (file paths, symbol names,
+//! keywords) describing what the current task is about, typically extracted
+//! from an issue. The hints bias planning in two ways:
+//! - **Lens selection**: [`FocusHints::suggest_lens`] picks a built-in lens
+//!   when the user did not choose one (a crash report suggests `debug`).
+//! - **Budget**: [`FocusPrioritizer`] is a [`FilePrioritizer`] that moves
+//!   mentioned files, and files named after mentioned symbols or keywords,
+//!   ahead of their tier peers.
+//!
+//! Any `Fn() -> Result<FocusHints, String>` closure is a provider, so
+//! integrations only need to fetch text and call [`FocusHints::from_text`].
+//! A GitHub Issues provider ships behind the `github` feature.
+
+#[cfg(feature = "github")]
+pub mod github;
+
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::budgeting::{FilePrioritizer, PathBoost, PriorityInput};
+
+/// Priority boost for files mentioned by path
+pub const PATH_BOOST: i32 = 100;
+
+/// Priority boost for files whose name matches a symbol or keyword
+pub const NAME_BOOST: i32 = 25;
+
+/// Keywords kept from free text
+const MAX_KEYWORDS: usize = 12;
+
+lazy_static! {
+    static ref URL: Regex = Regex::new(r"[A-Za-z][A-Za-z0-9+.-]*://\S+").unwrap();
+    static ref BACKTICKED: Regex = Regex::new(r"`([^`\n]+)`").unwrap();
+    static ref PATH_LIKE: Regex =
+        Regex::new(r"(?:[\w.-]+/)*[\w-]+\.[A-Za-z][A-Za-z0-9]{0,5}\b|(?:[\w.-]+/)+[\w.-]*").unwrap();
+    static ref CALL_LIKE: Regex = Regex::new(r"\b([A-Za-z_][A-Za-z0-9_]*(?:::[A-Za-z_][A-Za-z0-9_]*)*)\(\)").unwrap();
+    static ref IDENTIFIER: Regex = Regex::new(r"\b(?:[a-z]+_[a-z0-9_]+|[A-Z][a-z0-9]+(?:[A-Z][a-z0-9]+)+)\b").unwrap();
+    static ref WORD: Regex = Regex::new(r"[A-Za-z][A-Za-z-]{3,}").unwrap();
+}
+
+const STOPWORDS: &[&str] = &[
+    "about", "after", "again", "also", "because", "been", "before", "being", "could", "does",
+    "doesn", "each", "expected", "from", "have", "here", "into", "just", "like", "more", "most",
+    "only", "other", "should", "some", "such", "than", "that", "their", "them", "then", "there",
+    "these", "they", "this", "those", "under", "using", "very", "what", "when", "where", "which",
+    "while", "will", "with", "would", "your", "issue", "please", "thanks", "steps", "reproduce",
+];
+
+/// Lens suggestions: (lens, trigger words), checked in order
+const LENS_TRIGGERS: &[(&str, &[&str])] = &[
+    ("security", &["security", "vulnerability", "cve", "auth", "authentication", "xss", "injection", "secret", "secrets", "exploit"]),
+    ("debug", &["bug", "crash", "crashes", "panic", "panics", "error", "regression", "fails", "failing", "failure", "exception", "stacktrace", "traceback"]),
+    ("onboarding", &["docs", "documentation", "onboarding", "readme", "tutorial", "guide"]),
+];
+
+/// What the current task is about
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FocusHints {
+    /// Files or directories mentioned
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Symbol names mentioned (functions, types)
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    /// Salient words, most frequent first (labels included)
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Where the hints came from (e.g. `github:owner/repo#12`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl FocusHints {
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.symbols.is_empty() && self.keywords.is_empty()
+    }
+
+    /// Extract hints from free text such as an issue body
+    pub fn from_text(text: &str) -> Self {
+        let mut hints = Self::default();
+        let text = URL.replace_all(text, " ");
+        let text = text.as_ref();
+
+        for cap in BACKTICKED.captures_iter(text) {
+            let span = cap[1].trim();
+            if looks_like_path(span, false) {
+                push_unique(&mut hints.paths, clean_path(span));
+            } else if let Some(symbol) = CALL_LIKE.captures(span).map(|c| c[1].to_string()) {
+                push_unique(&mut hints.symbols, symbol);
+            } else if span.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | ':' | '.')) {
+                push_unique(&mut hints.symbols, span.to_string());
+            }
+        }
+        for m in PATH_LIKE.find_iter(text) {
+            if looks_like_path(m.as_str(), true) {
+                push_unique(&mut hints.paths, clean_path(m.as_str()));
+            }
+        }
+        for cap in CALL_LIKE.captures_iter(text) {
+            push_unique(&mut hints.symbols, cap[1].to_string());
+        }
+        for m in IDENTIFIER.find_iter(text) {
+            push_unique(&mut hints.symbols, m.as_str().to_string());
+        }
+
+        let mut counts: HashMap<String, (usize, usize)> = HashMap::new();
+        for (order, m) in WORD.find_iter(text).enumerate() {
+            let word = m.as_str().trim_matches('-').to_lowercase();
+            if word.len() < 4 || STOPWORDS.contains(&word.as_str()) {
+                continue;
+            }
+            counts.entry(word).or_insert((0, order)).0 += 1;
+        }
+        let mut words: Vec<(String, (usize, usize))> = counts.into_iter().collect();
+        words.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(a.1 .1.cmp(&b.1 .1)));
+        hints.keywords = words.into_iter().take(MAX_KEYWORDS).map(|(w, _)| w).collect();
+        hints
+    }
+
+    /// Hints for an issue: title and body text plus its labels
+    pub fn from_issue(title: &str, body: &str, labels: &[String]) -> Self {
+        let mut hints = Self::from_text(&format!("{}\n\n{}", title, body));
+        for label in labels.iter().rev() {
+            let label = label.to_lowercase();
+            hints.keywords.retain(|k| *k != label);
+            hints.keywords.insert(0, label);
+        }
+        hints
+    }
+
+    /// Add `other`'s hints (deduplicated)
+    pub fn merge(&mut self, other: FocusHints) {
+        for path in other.paths {
+            push_unique(&mut self.paths, path);
+        }
+        for symbol in other.symbols {
+            push_unique(&mut self.symbols, symbol);
+        }
+        for keyword in other.keywords {
+            push_unique(&mut self.keywords, keyword);
+        }
+        if self.source.is_none() {
+            self.source = other.source;
+        }
+    }
+
+    /// Built-in lens matching the keywords, if any clearly fits
+    pub fn suggest_lens(&self) -> Option<&'static str> {
+        LENS_TRIGGERS
+            .iter()
+            .map(|(lens, triggers)| {
+                let hits = self.keywords.iter().filter(|k| {
+                    k.split(|c: char| !c.is_alphanumeric()).any(|part| triggers.contains(&part))
+                }).count();
+                (*lens, hits)
+            })
+            .filter(|(_, hits)| *hits > 0)
+            .fold(None, |best: Option<(&'static str, usize)>, (lens, hits)| match best {
+                Some((_, best_hits)) if best_hits >= hits => best,
+                _ => Some((lens, hits)),
+            })
+            .map(|(lens, _)| lens)
+    }
+}
+
+/// Source of focus hints
+pub trait FocusProvider: Send + Sync {
+    /// Short name for messages
+    fn name(&self) -> &str {
+        "custom"
+    }
+
+    /// Fetch the hints
+    fn hints(&self) -> Result<FocusHints, String>;
+}
+
+impl<F> FocusProvider for F
+where
+    F: Fn() -> Result<FocusHints, String> + Send + Sync,
+{
+    fn hints(&self) -> Result<FocusHints, String> {
+        self()
+    }
+}
+
+impl FocusProvider for FocusHints {
+    fn name(&self) -> &str {
+        "static"
+    }
+
+    fn hints(&self) -> Result<FocusHints, String> {
+        Ok(self.clone())
+    }
+}
+
+/// Gather hints from every provider; failures are returned, not fatal
+pub fn collect_hints(providers: &[Box<dyn FocusProvider>]) -> (FocusHints, Vec<String>) {
+    let mut hints = FocusHints::default();
+    let mut errors = Vec::new();
+    for provider in providers {
+        match provider.hints() {
+            Ok(found) => hints.merge(found),
+            Err(e) => errors.push(format!("{}: {}", provider.name(), e)),
+        }
+    }
+    (hints, errors)
+}
+
+/// Budget prioritizer driven by focus hints
+#[derive(Debug, Clone)]
+pub struct FocusPrioritizer {
+    paths: PathBoost,
+    names: Vec<String>,
+}
+
+impl FocusPrioritizer {
+    pub fn new(hints: &FocusHints) -> Self {
+        let names = hints.symbols
+            .iter()
+            .map(|s| s.rsplit([':', '.']).next().unwrap_or(s))
+            .chain(hints.keywords.iter().map(String::as_str))
+            .map(normalize_name)
+            .filter(|n| n.len() >= 4)
+            .collect();
+        Self {
+            paths: PathBoost::new(hints.paths.iter().cloned(), PATH_BOOST),
+            names,
+        }
+    }
+}
+
+impl FilePrioritizer for FocusPrioritizer {
+    fn name(&self) -> &str {
+        "focus"
+    }
+
+    fn score(&self, file: &PriorityInput<'_>) -> i32 {
+        let path_score = self.paths.score(file);
+        let stem = file.path
+            .rsplit('/')
+            .next()
+            .and_then(|name| name.split('.').next())
+            .map(normalize_name)
+            .unwrap_or_default();
+        let matches = |n: &String| stem.contains(n.as_str()) || (stem.len() >= 4 && n.contains(stem.as_str()));
+        let name_score = if !stem.is_empty() && self.names.iter().any(matches) {
+            NAME_BOOST
+        } else {
+            0
+        };
+        path_score + name_score
+    }
+}
+
+/// Lowercase and drop separators so `ContextEngine` matches `context_engine.rs`
+fn normalize_name(name: &str) -> String {
+    name.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Whether `candidate` names a file or directory
+///
+/// In free text (`strict`), a bare `a/b` is too ambiguous ("and/or"), so
+/// directories need a trailing slash or at least three components.
+fn looks_like_path(candidate: &str, strict: bool) -> bool {
+    let candidate = candidate.trim_end_matches(['.', ',', ':', ';']);
+    if candidate.contains("://") || candidate.contains(' ') || candidate.starts_with("www.") {
+        return false;
+    }
+    let file_name = candidate.rsplit('/').next().unwrap_or(candidate);
+    let has_extension = file_name
+        .rsplit_once('.')
+        .is_some_and(|(stem, ext)| !stem.is_empty() && !ext.is_empty() && ext.len() <= 6 && ext.chars().all(|c| c.is_ascii_alphanumeric()) && !ext.chars().all(|c| c.is_ascii_digit()));
+    let components = candidate.split('/').filter(|p| !p.is_empty()).count();
+    let is_directory = if strict {
+        (candidate.ends_with('/') && components >= 1) || components >= 3
+    } else {
+        candidate.contains('/') && components >= 1
+    };
+    has_extension || is_directory
+}
+
+fn clean_path(candidate: &str) -> String {
+    let trimmed = candidate.trim_end_matches(['.', ',', ':', ';']);
+    // Drop ":line" / ":line:col" suffixes
+    let without_line = match trimmed.split_once(':') {
+        Some((path, rest)) if rest.split(':').all(|p| p.chars().all(|c| c.is_ascii_digit())) => path,
+        _ => trimmed,
+    };
+    without_line.trim_start_matches("./").trim_end_matches('/').to_string()
+}
+
+fn push_unique(list: &mut Vec<String>, value: String) {
+    if !value.is_empty() && !list.contains(&value) {
+        list.push(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::engine::FileTier;
+
+    const ISSUE: &str = "Crash in `ContextEngine::serialize()` when budget is tiny\n\n\
+        Running on src/core/engine.rs:120 panics; see https://example.com/logs/run.txt. \
+        The crash happens in apply_token_budget() too. Crash logs attached.";
+
+    #[test]
+    fn test_hints_from_text() {
+        let hints = FocusHints::from_text(ISSUE);
+        assert_eq!(hints.paths, vec!["src/core/engine.rs"]);
+        assert!(hints.symbols.contains(&"ContextEngine::serialize".to_string()));
+        assert!(hints.symbols.contains(&"apply_token_budget".to_string()));
+        assert_eq!(hints.keywords[0], "crash");
+        assert_eq!(hints.suggest_lens(), Some("debug"));
+
+        let labels = vec!["Security".to_string()];
+        let issue = FocusHints::from_issue("Token leak", "Tokens end up in logs", &labels);
+        assert_eq!(issue.keywords[0], "security");
+        assert_eq!(issue.suggest_lens(), Some("security"));
+        assert_eq!(FocusHints::from_text("Improve performance of walking").suggest_lens(), None);
+    }
+
+    #[test]
+    fn test_focus_prioritizer_and_providers() {
+        let providers: Vec<Box<dyn FocusProvider>> = vec![
+            Box::new(|| -> Result<FocusHints, String> { Ok(FocusHints::from_text(ISSUE)) }),
+            Box::new(|| -> Result<FocusHints, String> { Err("offline".to_string()) }),
+        ];
+        let (hints, errors) = collect_hints(&providers);
+        assert_eq!(errors, vec!["custom: offline".to_string()]);
+
+        let prioritizer = FocusPrioritizer::new(&hints);
+        let input = |path| PriorityInput {
+            path,
+            tier: FileTier::Core,
+            language: "rust",
+            lens_priority: 50,
+            tokens: 10,
+            lines: 1,
+        };
+        assert_eq!(prioritizer.score(&input("src/core/engine.rs")), PATH_BOOST + NAME_BOOST);
+        assert_eq!(prioritizer.score(&input("src/context_engine.rs")), NAME_BOOST);
+        assert_eq!(prioritizer.score(&input("src/walker.rs")), 0);
+    }
+}
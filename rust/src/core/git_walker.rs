@@ -0,0 +1,384 @@
+//! Git walker - Walk a commit, the index or the tracked worktree
+//!
+//! [`GitWalker`] is a [`FileWalker`] (`temporal` feature) that reads a
+//! [`GitView`] of the repository instead of the raw directory:
+//!
+//! | View            | Files                       | Content                 |
+//! |-----------------|-----------------------------|-------------------------|
+//! | `Commit(rev)`   | tree of `rev`               | blobs of that commit    |
+//! | `Staged`        | index                       | staged blobs            |
+//! | `StagedChanges` | index entries differing from HEAD | staged blobs      |
+//! | `WorkingTree`   | index                       | disk, unstaged edits included |
+//!
+//! Blobs come from the object database, so nothing is checked out and a busy
+//! worktree can still be serialized exactly as it was at a commit, or exactly
+//! as it is about to be committed. Untracked files are never included.
+//!
+//! The walk root may be a subdirectory of the repository; paths are relative
+//! to it, as with [`DefaultWalker`](crate::core::walker::DefaultWalker).
+//! Timestamps are the commit time (index entry time for staged views).
+//! Symlinks, submodules, conflicted entries and files with non-UTF-8 names
+//! are skipped.
+
+use std::fmt;
+
+/// Which version of the project a [`GitWalker`] reads
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitView {
+    /// The tree of a revision (anything `git rev-parse` accepts)
+    Commit(String),
+    /// The index: HEAD plus staged changes, i.e. what would be committed
+    Staged,
+    /// Only the files whose staged content differs from HEAD
+    StagedChanges,
+    /// Tracked files as they are on disk, including unstaged edits
+    WorkingTree,
+}
+
+impl fmt::Display for GitView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitView::Commit(revision) => write!(f, "commit {}", revision),
+            GitView::Staged => f.write_str("staged files"),
+            GitView::StagedChanges => f.write_str("staged changes"),
+            GitView::WorkingTree => f.write_str("working tree"),
+        }
+    }
+}
+
+#[cfg(feature = "temporal")]
+pub use self::walker::GitWalker;
+
+#[cfg(feature = "temporal")]
+mod walker {
+    use std::path::{Path, PathBuf};
+    use std::time::SystemTime;
+
+    use git2::{ObjectType, Oid, Repository, TreeWalkMode, TreeWalkResult};
+    use voyager_ast::PatternRules;
+
+    use super::GitView;
+    use crate::core::error::{EncoderError, Result};
+    use crate::core::models::FileEntry;
+    use crate::core::walker::{is_binary, read_file_content_with_format, read_text_file_with_format, FileWalker, WalkConfig};
+
+    /// Git file mode of a symbolic link
+    const SYMLINK_MODE: u32 = 0o120000;
+    /// Git file mode of a submodule
+    const GITLINK_MODE: u32 = 0o160000;
+
+    /// Walks a [`GitView`] of the repository instead of the working directory
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct GitWalker {
+        view: GitView,
+    }
+
+    /// A file selected for reading
+    struct Candidate {
+        /// Path relative to the walk root
+        path: String,
+        source: Source,
+        time: u64,
+    }
+
+    enum Source {
+        Blob(Oid),
+        Disk(PathBuf),
+    }
+
+    impl GitWalker {
+        /// Walk the tree of `revision` (sha, tag, `HEAD~3`, ...)
+        pub fn new(revision: impl Into<String>) -> Self {
+            Self::with_view(GitView::Commit(revision.into()))
+        }
+
+        /// Walk any view
+        pub fn with_view(view: GitView) -> Self {
+            Self { view }
+        }
+
+        pub fn view(&self) -> &GitView {
+            &self.view
+        }
+
+        /// Resolve the revision of a `Commit` view (HEAD otherwise) to a full commit id
+        pub fn resolve(&self, root: &str) -> Result<String> {
+            let repo = open(root)?;
+            let revision = match &self.view {
+                GitView::Commit(revision) => revision.as_str(),
+                _ => "HEAD",
+            };
+            let id = commit(&repo, revision)?.id();
+            Ok(id.to_string())
+        }
+
+        fn commit_candidates(
+            &self,
+            repo: &Repository,
+            revision: &str,
+            prefix: &str,
+            ignore: &PatternRules,
+            include: &PatternRules,
+        ) -> Result<Vec<Candidate>> {
+            let commit = commit(repo, revision)?;
+            let time = commit.time().seconds().max(0) as u64;
+            let mut tree = commit.tree().map_err(git_error)?;
+            if !prefix.is_empty() {
+                // The subdirectory did not exist at that commit: nothing to walk
+                let Ok(entry) = tree.get_path(Path::new(prefix)) else {
+                    return Ok(Vec::new());
+                };
+                tree = entry.to_object(repo).and_then(|o| o.peel_to_tree()).map_err(git_error)?;
+            }
+
+            let mut candidates = Vec::new();
+            tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+                let Some(name) = entry.name() else {
+                    return TreeWalkResult::Skip;
+                };
+                let path = format!("{}{}", dir, name);
+                match entry.kind() {
+                    Some(ObjectType::Tree) if ignore.can_prune(&path) => TreeWalkResult::Skip,
+                    Some(ObjectType::Blob) if entry.filemode() as u32 != SYMLINK_MODE => {
+                        if selected(&path, ignore, include) {
+                            candidates.push(Candidate { path, source: Source::Blob(entry.id()), time });
+                        }
+                        TreeWalkResult::Ok
+                    }
+                    _ => TreeWalkResult::Ok,
+                }
+            })
+            .map_err(git_error)?;
+            Ok(candidates)
+        }
+
+        fn index_candidates(
+            &self,
+            repo: &Repository,
+            prefix: &str,
+            ignore: &PatternRules,
+            include: &PatternRules,
+        ) -> Result<Vec<Candidate>> {
+            let index = repo.index().map_err(git_error)?;
+            let head = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+            let workdir = repo.workdir().map(Path::to_path_buf);
+
+            let mut candidates = Vec::new();
+            for entry in index.iter() {
+                let stage = (entry.flags >> 12) & 0x3;
+                if stage != 0 || entry.mode == SYMLINK_MODE || entry.mode == GITLINK_MODE {
+                    continue;
+                }
+                let Ok(repo_path) = String::from_utf8(entry.path.clone()) else { continue };
+                let path = match prefix {
+                    "" => repo_path.clone(),
+                    _ => match repo_path.strip_prefix(prefix).and_then(|rest| rest.strip_prefix('/')) {
+                        Some(rest) => rest.to_string(),
+                        None => continue,
+                    },
+                };
+                if !selected(&path, ignore, include) {
+                    continue;
+                }
+
+                let time = entry.mtime.seconds().max(0) as u64;
+                let candidate = match &self.view {
+                    GitView::StagedChanges => {
+                        let in_head = head.as_ref().and_then(|tree| tree.get_path(Path::new(&repo_path)).ok());
+                        if in_head.is_some_and(|e| e.id() == entry.id) {
+                            continue;
+                        }
+                        Candidate { path, source: Source::Blob(entry.id), time }
+                    }
+                    GitView::WorkingTree => {
+                        let Some(workdir) = &workdir else { continue };
+                        Candidate { path, source: Source::Disk(workdir.join(&repo_path)), time }
+                    }
+                    _ => Candidate { path, source: Source::Blob(entry.id), time },
+                };
+                candidates.push(candidate);
+            }
+            Ok(candidates)
+        }
+
+        fn read(&self, repo: &Repository, candidate: Candidate, max_file_size: u64) -> Result<Option<FileEntry>> {
+            let (content, size, time) = match candidate.source {
+                Source::Blob(id) => {
+                    let blob = repo.find_blob(id).map_err(git_error)?;
+                    let size = blob.size() as u64;
+                    if self.is_too_large(size, max_file_size) || is_binary(blob.content()) {
+                        return Ok(None);
+                    }
+                    (read_file_content_with_format(blob.content()), size, candidate.time)
+                }
+                Source::Disk(path) => {
+                    // Deleted but not yet staged: nothing on disk to show
+                    let Ok(metadata) = std::fs::metadata(&path) else {
+                        return Ok(None);
+                    };
+                    if self.is_too_large(metadata.len(), max_file_size) {
+                        return Ok(None);
+                    }
+                    let time = metadata
+                        .modified()
+                        .ok()
+                        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                        .map_or(candidate.time, |d| d.as_secs());
+                    (read_text_file_with_format(&path, metadata.len()), metadata.len(), time)
+                }
+            };
+            Ok(content.map(|(content, format)| {
+                FileEntry::new(candidate.path, content).with_timestamps(time, time).with_size(size).with_format(format)
+            }))
+        }
+    }
+
+    impl FileWalker for GitWalker {
+        fn walk(&self, root: &str, config: &WalkConfig) -> Result<Vec<FileEntry>> {
+            let repo = open(root)?;
+            let prefix = subdirectory(&repo, Path::new(root));
+            let ignore = PatternRules::with_case(&config.ignore_patterns, config.case_insensitive);
+            let include = PatternRules::with_case(&config.include_patterns, config.case_insensitive);
+
+            let candidates = match &self.view {
+                GitView::Commit(revision) => self.commit_candidates(&repo, revision, &prefix, &ignore, &include)?,
+                _ => self.index_candidates(&repo, &prefix, &ignore, &include)?,
+            };
+            let mut entries = Vec::with_capacity(candidates.len());
+            for candidate in candidates {
+                entries.extend(self.read(&repo, candidate, config.max_file_size)?);
+            }
+            Ok(entries)
+        }
+
+        fn should_ignore(&self, path: &str, patterns: &[String]) -> bool {
+            PatternRules::new(patterns).is_match(path, false)
+        }
+    }
+
+    /// Check a file path against the walk patterns, including its directories
+    fn selected(path: &str, ignore: &PatternRules, include: &PatternRules) -> bool {
+        let pruned = path.match_indices('/').any(|(i, _)| ignore.can_prune(&path[..i]));
+        !pruned && !ignore.is_match(path, false) && (include.is_empty() || include.is_match(path, false))
+    }
+
+    fn commit<'r>(repo: &'r Repository, revision: &str) -> Result<git2::Commit<'r>> {
+        repo.revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| EncoderError::invalid_config(format!("Unknown revision '{}': {}", revision, e.message())))
+    }
+
+    fn open(root: &str) -> Result<Repository> {
+        let root_path = Path::new(root);
+        if !root_path.exists() {
+            return Err(EncoderError::DirectoryNotFound { path: root_path.to_path_buf() });
+        }
+        Repository::discover(root_path)
+            .map_err(|e| EncoderError::invalid_config(format!("'{}' is not in a git repository: {}", root, e.message())))
+    }
+
+    /// Path of `root` below the repository work tree (`""` at the top)
+    fn subdirectory(repo: &Repository, root: &Path) -> String {
+        let (Some(workdir), Ok(root)) = (repo.workdir(), root.canonicalize()) else {
+            return String::new();
+        };
+        let workdir = workdir.canonicalize().unwrap_or_else(|_| workdir.to_path_buf());
+        root.strip_prefix(&workdir)
+            .map(|rest| rest.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"))
+            .unwrap_or_default()
+    }
+
+    fn git_error(e: git2::Error) -> EncoderError {
+        EncoderError::invalid_config(format!("git: {}", e.message()))
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use tempfile::TempDir;
+
+        /// Stage `files`, returning the index tree
+        fn stage(repo: &Repository, files: &[(&str, &str)]) -> Oid {
+            let workdir = repo.workdir().unwrap().to_path_buf();
+            let mut index = repo.index().unwrap();
+            for (path, content) in files {
+                let full = workdir.join(path);
+                std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+                std::fs::write(&full, content).unwrap();
+                index.add_path(Path::new(path)).unwrap();
+            }
+            index.write().unwrap();
+            index.write_tree().unwrap()
+        }
+
+        /// Commit `files` on top of HEAD, returning the commit id
+        fn commit(repo: &Repository, files: &[(&str, &str)], message: &str) -> Oid {
+            let tree = repo.find_tree(stage(repo, files)).unwrap();
+            let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents).unwrap()
+        }
+
+        fn walk(view: GitView, root: &Path) -> Vec<(String, String)> {
+            let config = WalkConfig { ignore_patterns: vec!["build".to_string()], ..WalkConfig::default() };
+            GitWalker::with_view(view)
+                .walk(root.to_str().unwrap(), &config)
+                .unwrap()
+                .into_iter()
+                .map(|e| (e.path, e.content))
+                .collect()
+        }
+
+        #[test]
+        fn test_walk_reads_committed_tree() {
+            let temp = TempDir::new().unwrap();
+            let repo = Repository::init(temp.path()).unwrap();
+            let first = commit(&repo, &[("src/lib.rs", "pub fn v1() {}\n"), ("build/out.txt", "artifact\n")], "first");
+            commit(&repo, &[("src/lib.rs", "pub fn v2() {}\n"), ("src/new.rs", "pub fn added() {}\n")], "second");
+            // Uncommitted edits in the worktree are not visible
+            std::fs::write(temp.path().join("src/lib.rs"), "pub fn dirty() {}\n").unwrap();
+
+            let old = walk(GitView::Commit(first.to_string()), temp.path());
+            assert_eq!(old, vec![("src/lib.rs".to_string(), "pub fn v1() {}\n".to_string())]);
+
+            let head = walk(GitView::Commit("HEAD".to_string()), temp.path());
+            assert_eq!(head.len(), 2);
+            assert!(head.iter().any(|(_, content)| content == "pub fn v2() {}\n"));
+
+            // Subdirectory roots yield paths relative to the subdirectory
+            let sub = walk(GitView::Commit("HEAD~1".to_string()), &temp.path().join("src"));
+            assert_eq!(sub[0].0, "lib.rs");
+
+            let root = temp.path().to_str().unwrap();
+            assert_eq!(GitWalker::new("HEAD~1").resolve(root).unwrap(), first.to_string());
+            assert!(GitWalker::new("no-such-rev").walk(root, &WalkConfig::default()).is_err());
+        }
+
+        #[test]
+        fn test_index_and_worktree_views() {
+            let temp = TempDir::new().unwrap();
+            let repo = Repository::init(temp.path()).unwrap();
+            commit(&repo, &[("a.py", "a = 1\n"), ("b.py", "b = 1\n"), ("build/x.py", "x = 1\n")], "base");
+            stage(&repo, &[("a.py", "a = 2\n")]);
+            std::fs::write(temp.path().join("a.py"), "a = 3\n").unwrap();
+            std::fs::write(temp.path().join("untracked.py"), "u = 1\n").unwrap();
+
+            let content = |files: &[(String, String)], path: &str| {
+                files.iter().find(|(p, _)| p == path).map(|(_, c)| c.clone())
+            };
+
+            let staged = walk(GitView::Staged, temp.path());
+            assert_eq!(staged.len(), 2);
+            assert_eq!(content(&staged, "a.py").as_deref(), Some("a = 2\n"));
+
+            let changes = walk(GitView::StagedChanges, temp.path());
+            assert_eq!(changes, vec![("a.py".to_string(), "a = 2\n".to_string())]);
+
+            let worktree = walk(GitView::WorkingTree, temp.path());
+            assert_eq!(content(&worktree, "a.py").as_deref(), Some("a = 3\n"));
+            assert_eq!(content(&worktree, "b.py").as_deref(), Some("b = 1\n"));
+            assert!(content(&worktree, "untracked.py").is_none());
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! Per-directory density heatmap from a Planetarium index
+//!
+//! Rolls files up into directories (truncated to a depth) with totals for
+//! symbols, lines, estimated tokens and a structural complexity score, then
+//! renders them largest-first as a text treemap or JSON. Humans use it to
+//! spot monolith modules; budget planning can use [`Heatmap::budget_shares`]
+//! to split a token budget in proportion to where the code actually is.
+//!
+//! Complexity is structural, since only the index is available: one point
+//! per declaration, plus each function's parameter count and one point per
+//! [`LINES_PER_POINT`] lines of its body.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use voyager_ast::{Declaration, DeclarationKind, File, PlanetariumModel};
+
+/// Body lines that add one complexity point to a function
+pub const LINES_PER_POINT: usize = 20;
+
+/// Width of the text bar
+const BAR_WIDTH: usize = 20;
+
+/// Totals for one directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DirectoryHeat {
+    /// Directory path ("." for the project root)
+    pub path: String,
+    pub files: usize,
+    pub symbols: usize,
+    pub lines: usize,
+    pub tokens: usize,
+    pub complexity: usize,
+    /// Share of the project's estimated tokens (0.0 - 1.0)
+    pub share: f64,
+}
+
+/// Directory totals, largest first
+#[derive(Debug, Clone, Serialize)]
+pub struct Heatmap {
+    /// Path components kept when grouping files
+    pub depth: usize,
+    pub directories: Vec<DirectoryHeat>,
+    pub total_tokens: usize,
+}
+
+impl Heatmap {
+    /// Aggregate `model`, grouping files by their first `depth` directories
+    pub fn from_model(model: &PlanetariumModel, depth: usize) -> Self {
+        let mut groups: BTreeMap<String, DirectoryHeat> = BTreeMap::new();
+        for (path, file) in &model.files {
+            let dir = directory_at_depth(&path.to_string_lossy(), depth.max(1));
+            let heat = groups.entry(dir.clone()).or_insert_with(|| DirectoryHeat { path: dir, ..Default::default() });
+            heat.files += 1;
+            heat.symbols += file.total_declarations();
+            heat.lines += file.span.end_line;
+            // Same len/4 heuristic as TokenEstimator
+            heat.tokens += file.span.end / 4;
+            heat.complexity += file_complexity(file);
+        }
+
+        let total_tokens: usize = groups.values().map(|h| h.tokens).sum();
+        let mut directories: Vec<_> = groups.into_values().collect();
+        for heat in &mut directories {
+            heat.share = if total_tokens == 0 { 0.0 } else { heat.tokens as f64 / total_tokens as f64 };
+        }
+        directories.sort_by(|a, b| b.tokens.cmp(&a.tokens).then_with(|| a.path.cmp(&b.path)));
+
+        Self { depth: depth.max(1), directories, total_tokens }
+    }
+
+    /// Split `budget` tokens across directories in proportion to their size
+    ///
+    /// Rounding remainders go to the largest directories, so the shares always
+    /// sum to `budget` (unless the project is empty).
+    pub fn budget_shares(&self, budget: usize) -> Vec<(String, usize)> {
+        if self.total_tokens == 0 {
+            return Vec::new();
+        }
+        let mut shares: Vec<(String, usize)> = self.directories
+            .iter()
+            .map(|h| (h.path.clone(), (budget as u128 * h.tokens as u128 / self.total_tokens as u128) as usize))
+            .collect();
+        let mut remainder = budget - shares.iter().map(|(_, s)| s).sum::<usize>();
+        for (_, share) in shares.iter_mut() {
+            if remainder == 0 {
+                break;
+            }
+            *share += 1;
+            remainder -= 1;
+        }
+        shares
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Text treemap: one bar per directory, largest first
+    pub fn render_text(&self) -> String {
+        let mut out = format!(
+            "Heatmap by directory (depth {}, {} estimated tokens)\n\n",
+            self.depth, self.total_tokens
+        );
+        for heat in &self.directories {
+            let filled = (heat.share * BAR_WIDTH as f64).round() as usize;
+            out.push_str(&format!(
+                "{}{} {:>5.1}%  {}  ({} files, {} symbols, {} lines, {} tokens, complexity {})\n",
+                "█".repeat(filled),
+                "░".repeat(BAR_WIDTH - filled.min(BAR_WIDTH)),
+                heat.share * 100.0,
+                heat.path,
+                heat.files,
+                heat.symbols,
+                heat.lines,
+                heat.tokens,
+                heat.complexity
+            ));
+        }
+        out
+    }
+}
+
+/// First `depth` directory components of `path` ("." for root files)
+fn directory_at_depth(path: &str, depth: usize) -> String {
+    let normalized = path.replace('\\', "/");
+    let mut components: Vec<&str> = normalized.split('/').filter(|c| !c.is_empty()).collect();
+    components.pop(); // file name
+    if components.is_empty() {
+        return ".".to_string();
+    }
+    components.truncate(depth);
+    components.join("/")
+}
+
+fn file_complexity(file: &File) -> usize {
+    fn declaration(d: &Declaration) -> usize {
+        let mut points = 1;
+        if matches!(d.kind, DeclarationKind::Function | DeclarationKind::Method) {
+            points += d.parameters.len();
+            if let Some(body) = &d.body_span {
+                points += (body.end_line.saturating_sub(body.start_line) + 1) / LINES_PER_POINT;
+            }
+        }
+        points + d.children.iter().map(declaration).sum::<usize>()
+    }
+    file.declarations.iter().map(declaration).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voyager_ast::{AdapterRegistry, LanguageId, RawPath};
+
+    fn model(files: &[(&str, &str)]) -> PlanetariumModel {
+        let registry = AdapterRegistry::new();
+        let mut model = PlanetariumModel::new(".");
+        for (path, source) in files {
+            let file = registry.parse(source, LanguageId::Rust).unwrap();
+            model.files.insert(RawPath::from_key(path), file);
+        }
+        model
+    }
+
+    #[test]
+    fn test_heatmap_groups_and_sorts() {
+        let big = "pub fn work(a: u32, b: u32) -> u32 {\n    a + b\n}\n".repeat(20);
+        let model = model(&[
+            ("src/core/engine.rs", big.as_str()),
+            ("src/core/deep/nested.rs", "pub fn n() {}\n"),
+            ("src/cli.rs", "fn main() {}\n"),
+            ("build.rs", "fn main() {}\n"),
+        ]);
+        let heatmap = Heatmap::from_model(&model, 2);
+
+        let paths: Vec<&str> = heatmap.directories.iter().map(|h| h.path.as_str()).collect();
+        assert_eq!(paths[0], "src/core", "largest directory first");
+        assert!(paths.contains(&"src") && paths.contains(&"."));
+        let core = &heatmap.directories[0];
+        assert_eq!(core.files, 2);
+        assert!(core.complexity > core.symbols, "parameters add complexity");
+        assert!(core.share > 0.8);
+
+        let shares = heatmap.budget_shares(1000);
+        assert_eq!(shares.iter().map(|(_, s)| s).sum::<usize>(), 1000);
+        assert_eq!(shares[0].0, "src/core");
+
+        assert!(heatmap.render_text().contains("src/core"));
+        let json: serde_json::Value = serde_json::from_str(&heatmap.to_json()).unwrap();
+        assert_eq!(json["directories"][0]["path"], "src/core");
+    }
+
+    #[test]
+    fn test_directory_at_depth() {
+        assert_eq!(directory_at_depth("main.rs", 2), ".");
+        assert_eq!(directory_at_depth("src/a/b/c.rs", 1), "src");
+        assert_eq!(directory_at_depth("src\\a\\c.rs", 2), "src/a");
+    }
+}
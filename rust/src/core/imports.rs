@@ -0,0 +1,383 @@
+//! Import Rollups - One-line dependency summaries in place of import blocks
+//!
+//! Import lines tell a reader what a file depends on, but spelled out one per
+//! line they can cost more tokens than the code below them. An
+//! [`ImportRollup`] groups the imports extracted by voyager-ast into standard
+//! library, internal and external dependencies:
+//!
+//! ```text
+//! // imports: 12 std; 5 internal: core::{models, walker}, lenses; 3 external: regex, serde, tokio
+//! ```
+//!
+//! [`ImportRollups::apply`] replaces a file's top-level import statements with
+//! that line. Imports are internal when they are relative (`crate::`, `./x`,
+//! `.models`) or their root names a directory or module of the project
+//! ([`LocalModules`]). Rust, Python and TypeScript/JavaScript are supported;
+//! other files are left as they are.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::Path;
+
+use voyager_ast::{ImportKind, ImportLike, LanguageId};
+
+use crate::core::ast_bridge::AstBridge;
+
+/// Names listed per group before the rest are counted as `+N`
+const MAX_LISTED: usize = 6;
+
+const RUST_STD: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+const PYTHON_STD: &[&str] = &[
+    "__future__", "abc", "argparse", "ast", "asyncio", "base64", "collections", "contextlib", "copy", "csv",
+    "dataclasses", "datetime", "decimal", "enum", "functools", "glob", "hashlib", "heapq", "http", "importlib",
+    "inspect", "io", "itertools", "json", "logging", "math", "multiprocessing", "operator", "os", "pathlib",
+    "pickle", "platform", "queue", "random", "re", "shutil", "signal", "socket", "sqlite3", "string", "struct",
+    "subprocess", "sys", "tempfile", "textwrap", "threading", "time", "traceback", "types", "typing",
+    "unittest", "urllib", "uuid", "warnings", "weakref", "xml", "zipfile",
+];
+
+const NODE_BUILTINS: &[&str] = &[
+    "assert", "buffer", "child_process", "crypto", "events", "fs", "http", "https", "net", "os", "path",
+    "process", "querystring", "readline", "stream", "url", "util", "worker_threads", "zlib",
+];
+
+/// Where an import points
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportScope {
+    Std,
+    Internal,
+    External,
+}
+
+/// Directory and module names of a project, used to recognize internal imports
+#[derive(Debug, Clone, Default)]
+pub struct LocalModules {
+    names: HashSet<String>,
+}
+
+impl LocalModules {
+    /// Collect directory names and file stems from project-relative paths
+    pub fn from_paths<'a>(paths: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut names = HashSet::new();
+        for path in paths {
+            let path = Path::new(path);
+            let dirs = path.parent().into_iter().flat_map(|p| p.components()).filter_map(|c| c.as_os_str().to_str());
+            let stem = path.file_stem().and_then(|s| s.to_str());
+            for name in dirs.chain(stem) {
+                // Crate directories use dashes, their paths underscores
+                names.insert(name.replace('-', "_"));
+                names.insert(name.to_string());
+            }
+        }
+        Self { names }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains(name)
+    }
+}
+
+/// Imports of one file, grouped by scope
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportRollup {
+    /// Standard library imports
+    pub std: usize,
+    /// Internal imports
+    pub internal: usize,
+    /// External imports
+    pub external: usize,
+    /// Internal modules: first path segment → second segments
+    pub internal_modules: BTreeMap<String, BTreeSet<String>>,
+    /// External packages
+    pub external_packages: BTreeSet<String>,
+}
+
+impl ImportRollup {
+    pub fn total(&self) -> usize {
+        self.std + self.internal + self.external
+    }
+
+    fn add(&mut self, scope: ImportScope, paths: Vec<Vec<String>>) {
+        match scope {
+            ImportScope::Std => self.std += 1,
+            ImportScope::Internal => {
+                self.internal += 1;
+                for path in paths {
+                    if let Some(group) = path.first() {
+                        let members = self.internal_modules.entry(group.clone()).or_default();
+                        members.extend(path.get(1).cloned());
+                    }
+                }
+            }
+            ImportScope::External => {
+                self.external += 1;
+                self.external_packages.extend(paths.into_iter().filter_map(|p| p.into_iter().next()));
+            }
+        }
+    }
+
+    /// One-line summary, joining module segments with `separator` (`::`, `.`, `/`)
+    pub fn summary(&self, separator: &str) -> String {
+        let mut parts = Vec::new();
+        if self.std > 0 {
+            parts.push(format!("{} std", self.std));
+        }
+        if self.internal > 0 {
+            let modules = self.internal_modules.iter().map(|(group, members)| match members.len() {
+                0 => group.clone(),
+                1 => format!("{}{}{}", group, separator, members.iter().next().unwrap()),
+                _ => format!("{}{}{{{}}}", group, separator, listed(members.iter().cloned())),
+            });
+            parts.push(labelled(self.internal, "internal", &listed(modules)));
+        }
+        if self.external > 0 {
+            parts.push(labelled(self.external, "external", &listed(self.external_packages.iter().cloned())));
+        }
+        format!("imports: {}", parts.join("; "))
+    }
+}
+
+fn labelled(count: usize, label: &str, names: &str) -> String {
+    if names.is_empty() {
+        format!("{} {}", count, label)
+    } else {
+        format!("{} {}: {}", count, label, names)
+    }
+}
+
+/// Comma-separated names, the overflow counted as `+N`
+fn listed(names: impl Iterator<Item = String>) -> String {
+    let names: Vec<String> = names.collect();
+    let mut shown: Vec<String> = names.iter().take(MAX_LISTED).cloned().collect();
+    if names.len() > MAX_LISTED {
+        shown.push(format!("+{}", names.len() - MAX_LISTED));
+    }
+    shown.join(", ")
+}
+
+/// Computes and applies import rollups across a project
+pub struct ImportRollups {
+    bridge: AstBridge,
+    local: LocalModules,
+}
+
+impl ImportRollups {
+    pub fn new(local: LocalModules) -> Self {
+        Self { bridge: AstBridge::new(), local }
+    }
+
+    /// Rollup of a file's top-level imports (`None` if unsupported or without imports)
+    pub fn rollup(&self, path: &str, content: &str) -> Option<ImportRollup> {
+        self.collect(path, content).map(|(_, rollup, _)| rollup)
+    }
+
+    /// Replace a file's top-level import statements with a one-line rollup
+    pub fn apply(&self, path: &str, content: &str) -> Option<String> {
+        let (language, rollup, mut removed) = self.collect(path, content)?;
+        let (separator, comment) = syntax(language)?;
+        let lines: Vec<&str> = content.lines().collect();
+        let mut first = *removed.first()?;
+        let last = *removed.last()?;
+
+        // Blank lines between imports go with them; so do attributes on Rust imports
+        for n in first..=last {
+            if lines.get(n - 1).is_some_and(|l| l.trim().is_empty()) {
+                removed.insert(n);
+            }
+        }
+        if language == LanguageId::Rust {
+            for start in removed.clone() {
+                let mut n = start;
+                while n > 1 && !removed.contains(&(n - 1)) && lines[n - 2].trim_start().starts_with("#[") {
+                    n -= 1;
+                    removed.insert(n);
+                }
+                first = first.min(n);
+            }
+        }
+
+        let mut out = Vec::with_capacity(lines.len());
+        for (i, line) in lines.iter().enumerate() {
+            let n = i + 1;
+            if n == first {
+                out.push(format!("{} {}", comment, rollup.summary(separator)));
+            }
+            if !removed.contains(&n) {
+                out.push(line.to_string());
+            }
+        }
+
+        let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut rolled = out.join(newline);
+        if content.ends_with('\n') {
+            rolled.push_str(newline);
+        }
+        Some(rolled)
+    }
+
+    /// Rollup plus the (1-indexed) lines of the rolled-up statements
+    fn collect(&self, path: &str, content: &str) -> Option<(LanguageId, ImportRollup, BTreeSet<usize>)> {
+        let language = AstBridge::detect_language(Path::new(path));
+        syntax(language)?;
+        let file = self.bridge.analyze_file(content, language)?;
+
+        let mut rollup = ImportRollup::default();
+        let mut lines = BTreeSet::new();
+        for import in file.imports.iter().filter(|i| i.span.start_column == 0) {
+            let Some((scope, paths)) = classify(language, import, &self.local) else { continue };
+            rollup.add(scope, paths);
+            lines.extend(import.span.start_line..=import.span.end_line);
+        }
+        (rollup.total() > 0).then_some((language, rollup, lines))
+    }
+}
+
+/// Module separator and line comment of a supported language
+fn syntax(language: LanguageId) -> Option<(&'static str, &'static str)> {
+    match language {
+        LanguageId::Rust => Some(("::", "//")),
+        LanguageId::Python => Some((".", "#")),
+        LanguageId::TypeScript | LanguageId::Tsx | LanguageId::JavaScript | LanguageId::Jsx => Some(("/", "//")),
+        _ => None,
+    }
+}
+
+/// Scope and module paths of one import (several for `use a::{b, c}`)
+fn classify(language: LanguageId, import: &ImportLike, local: &LocalModules) -> Option<(ImportScope, Vec<Vec<String>>)> {
+    match language {
+        LanguageId::Rust => classify_rust(import, local),
+        LanguageId::Python => Some(classify_python(import, local)),
+        _ => Some(classify_script(import, local)),
+    }
+}
+
+fn classify_rust(import: &ImportLike, local: &LocalModules) -> Option<(ImportScope, Vec<Vec<String>>)> {
+    // `mod x;` declares structure rather than a dependency
+    if import.kind == ImportKind::Module {
+        return None;
+    }
+    let source = import.source.trim_start_matches("::");
+    let (prefix, list) = match source.split_once("::{") {
+        Some((prefix, list)) => (prefix, Some(list.trim_end_matches('}'))),
+        None => (source, None),
+    };
+    let mut segments: Vec<String> = prefix
+        .split("::")
+        .map(|s| s.split(" as ").next().unwrap_or(s).trim().to_string())
+        .filter(|s| !s.is_empty() && s != "*")
+        .collect();
+    let root = segments.first().cloned().unwrap_or_default();
+
+    let relative = segments.iter().take_while(|s| matches!(s.as_str(), "crate" | "self" | "super")).count();
+    let scope = if relative > 0 || root.is_empty() {
+        segments.drain(..relative);
+        ImportScope::Internal
+    } else if RUST_STD.contains(&root.as_str()) {
+        ImportScope::Std
+    } else if local.contains(&root) {
+        ImportScope::Internal
+    } else {
+        ImportScope::External
+    };
+
+    let paths = match list {
+        Some(list) => list
+            .split(',')
+            .filter_map(|item| item.trim().split("::").next().map(|head| head.split(" as ").next().unwrap_or(head)))
+            .filter(|head| !head.is_empty() && !matches!(*head, "self" | "*" | "{"))
+            .map(|head| segments.iter().cloned().chain(std::iter::once(head.trim().to_string())).collect())
+            .collect(),
+        None => vec![segments],
+    };
+    Some((scope, paths))
+}
+
+fn classify_python(import: &ImportLike, local: &LocalModules) -> (ImportScope, Vec<Vec<String>>) {
+    let source = import.source.as_str();
+    let segments: Vec<String> =
+        source.trim_start_matches('.').split('.').filter(|s| !s.is_empty()).map(str::to_string).collect();
+    if source.starts_with('.') {
+        // `from . import models` names the modules in its items
+        let paths = if segments.is_empty() {
+            import.items.iter().filter(|i| *i != "*").map(|i| vec![i.clone()]).collect()
+        } else {
+            vec![segments]
+        };
+        return (ImportScope::Internal, paths);
+    }
+    let root = segments.first().map(String::as_str).unwrap_or_default();
+    let scope = if PYTHON_STD.contains(&root) {
+        ImportScope::Std
+    } else if local.contains(root) {
+        ImportScope::Internal
+    } else {
+        ImportScope::External
+    };
+    (scope, vec![segments])
+}
+
+fn classify_script(import: &ImportLike, local: &LocalModules) -> (ImportScope, Vec<Vec<String>>) {
+    let source = import.source.as_str();
+    if source.starts_with('.') || source.starts_with('/') || source.starts_with("@/") || source.starts_with("~/") {
+        let segments = source
+            .split('/')
+            .filter(|s| !matches!(*s, "" | "." | ".." | "@" | "~"))
+            .map(|s| Path::new(s).file_stem().and_then(|s| s.to_str()).unwrap_or(s).to_string())
+            .collect();
+        return (ImportScope::Internal, vec![segments]);
+    }
+    if let Some(builtin) = source.strip_prefix("node:") {
+        return (ImportScope::Std, vec![vec![builtin.to_string()]]);
+    }
+    let mut parts = source.split('/');
+    let package = match parts.next() {
+        Some(scope) if scope.starts_with('@') => format!("{}/{}", scope, parts.next().unwrap_or_default()),
+        Some(name) => name.to_string(),
+        None => String::new(),
+    };
+    let scope = if NODE_BUILTINS.contains(&package.as_str()) {
+        ImportScope::Std
+    } else if local.contains(&package) {
+        ImportScope::Internal
+    } else {
+        ImportScope::External
+    };
+    (scope, vec![vec![package]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rust_rollup_replaces_use_block() {
+        let source = "use std::collections::HashMap;\nuse std::fmt;\n\nuse serde::{Deserialize, Serialize};\n\
+                      use crate::core::{walker, models};\nuse crate::lenses::LensManager;\nuse tokio::sync::Semaphore;\n\n\
+                      fn main() {}\n";
+        let rollups = ImportRollups::new(LocalModules::default());
+
+        let rollup = rollups.rollup("src/main.rs", source).unwrap();
+        assert_eq!(rollup.total(), 6);
+        assert_eq!(
+            rollup.summary("::"),
+            "imports: 2 std; 2 internal: core::{models, walker}, lenses::LensManager; 2 external: serde, tokio"
+        );
+        assert_eq!(rollups.apply("src/main.rs", source).unwrap(), format!("// {}\n\nfn main() {{}}\n", rollup.summary("::")));
+        assert!(rollups.apply("notes.txt", source).is_none());
+    }
+
+    #[test]
+    fn test_python_and_script_scopes() {
+        let local = LocalModules::from_paths(["app/models.py", "web/src/api/client.ts"]);
+        let rollups = ImportRollups::new(local);
+
+        let python = "import os\nimport sys\nfrom app.models import User\nfrom .views import index\nimport requests\n\nx = 1\n";
+        let rollup = rollups.rollup("app/main.py", python).unwrap();
+        assert_eq!((rollup.std, rollup.internal, rollup.external), (2, 2, 1));
+        assert!(rollups.apply("app/main.py", python).unwrap().starts_with("# imports: 2 std; 2 internal: app.models, views; 1 external: requests\n\nx = 1"));
+
+        let script = "import fs from 'node:fs';\nimport React from 'react';\nimport { get } from './api/client';\nimport x from '@scope/pkg/sub';\n";
+        let rollup = rollups.rollup("web/src/app.ts", script).unwrap();
+        assert_eq!(rollup.summary("/"), "imports: 1 std; 1 internal: api/client; 2 external: @scope/pkg, react");
+    }
+}
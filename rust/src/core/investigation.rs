@@ -0,0 +1,397 @@
+//! Investigation - Zooms, search hits and notes gathered into one report
+//!
+//! A debugging session wanders: a zoom into the failing function, a search
+//! for its callers, a note on what was ruled out. An [`Investigation`]
+//! records each step as it happens, with the context the zoom served and the
+//! hits the search found, and renders them at the end as one Markdown report
+//! that can be shared or handed to a model as context:
+//!
+//! ```text
+//! vo --investigate start:refund-bug
+//! vo --investigate zoom:function=process_refund
+//! vo --investigate search:apply_discount
+//! vo --investigate "note:discount is applied twice on partial refunds"
+//! vo --investigate report > refund-bug.md
+//! ```
+//!
+//! Investigations are stored in `.pm_encoder/investigations.json`. Zooms go
+//! through the same engine and symbol resolution as `--zoom` (bookmarks
+//! included); searches list the symbol's definitions and then its usages.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+use super::bookmarks::expand_target;
+use super::engine::ContextEngine;
+use super::error::Result;
+use super::json_store;
+use super::replay::resolve;
+use super::sandbox::PathSandbox;
+use super::search::{SymbolResolver, SymbolType, UsageFinder};
+use super::zoom::{ZoomConfig, ZoomDepth, ZoomTarget};
+
+/// Usages kept per search, after the definitions
+pub const MAX_USAGES: usize = 20;
+
+/// Symbol kinds a search looks for definitions of
+const DEFINITION_TYPES: &[SymbolType] =
+    &[SymbolType::Function, SymbolType::Class, SymbolType::Struct, SymbolType::Trait, SymbolType::Enum];
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// A line found by a search
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub path: String,
+    /// Line number (1-indexed)
+    pub line: usize,
+    pub snippet: String,
+    /// The symbol is defined here rather than used
+    #[serde(default)]
+    pub definition: bool,
+}
+
+/// One step of an investigation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InvestigationEntry {
+    /// A zoom and the context it served
+    Zoom {
+        target: String,
+        /// File the context was taken from, when the target names or resolves to one
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        context: String,
+        timestamp: u64,
+    },
+    /// A symbol search, definitions first
+    Search { query: String, hits: Vec<SearchHit>, timestamp: u64 },
+    /// Free text
+    Note { text: String, timestamp: u64 },
+}
+
+impl InvestigationEntry {
+    pub fn timestamp(&self) -> u64 {
+        match self {
+            Self::Zoom { timestamp, .. } | Self::Search { timestamp, .. } | Self::Note { timestamp, .. } => *timestamp,
+        }
+    }
+
+    /// One-line description (`zoom function=main`, `search parse (3 hits)`, `note`)
+    pub fn title(&self) -> String {
+        match self {
+            Self::Zoom { target, .. } => format!("zoom {}", target),
+            Self::Search { query, hits, .. } => format!("search {} ({} hits)", query, hits.len()),
+            Self::Note { .. } => "note".to_string(),
+        }
+    }
+
+    /// Context a zoom served
+    pub fn context(&self) -> Option<&str> {
+        match self {
+            Self::Zoom { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Hits a search found (none for other steps)
+    pub fn hits(&self) -> &[SearchHit] {
+        match self {
+            Self::Search { hits, .. } => hits,
+            _ => &[],
+        }
+    }
+}
+
+/// Zooms, searches and notes of one investigation, in the order they happened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Investigation {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Unix seconds
+    pub created: u64,
+    #[serde(default)]
+    pub entries: Vec<InvestigationEntry>,
+}
+
+impl Investigation {
+    pub fn new(name: &str, description: Option<String>) -> Self {
+        Self { name: name.to_string(), description, created: now(), entries: Vec::new() }
+    }
+
+    pub fn note(&mut self, text: &str) {
+        self.record(InvestigationEntry::Note { text: text.to_string(), timestamp: now() });
+    }
+
+    fn record(&mut self, entry: InvestigationEntry) -> &InvestigationEntry {
+        self.entries.push(entry);
+        &self.entries[self.entries.len() - 1]
+    }
+
+    /// Zoom into `target` in the project at `root` and record the context served
+    ///
+    /// Targets are parsed and resolved as `--zoom` does; file paths must stay
+    /// inside `root`. Returns the recorded step.
+    pub fn zoom(&mut self, engine: &ContextEngine, root: &Path, target: &str) -> Result<&InvestigationEntry> {
+        let expanded = expand_target(root, target)?;
+        let parsed = ZoomTarget::parse(&expanded)?;
+        PathSandbox::new(root)?.check_target(&parsed)?;
+        let root_str = root.to_string_lossy();
+        let resolved = resolve(&parsed, &root_str);
+        let path = match &resolved {
+            ZoomTarget::File { path, .. } => Some(path.clone()),
+            ZoomTarget::Anchor(anchor) => Some(anchor.path.clone()),
+            _ => None,
+        };
+        let config = ZoomConfig { target: resolved, depth: ZoomDepth::Full, ..ZoomConfig::default() };
+        let context = engine.zoom(&root_str, &config)?;
+        Ok(self.record(InvestigationEntry::Zoom { target: target.to_string(), path, context, timestamp: now() }))
+    }
+
+    /// Search `root` for the definitions and usages of `symbol` and record the hits
+    pub fn search(&mut self, resolver: &SymbolResolver, root: &Path, symbol: &str) -> &InvestigationEntry {
+        let mut hits: Vec<SearchHit> = Vec::new();
+        for symbol_type in DEFINITION_TYPES {
+            for location in resolver.find_all(symbol, *symbol_type, root) {
+                if !hits.iter().any(|h| h.path == location.path && h.line == location.start_line) {
+                    hits.push(SearchHit {
+                        path: location.path,
+                        line: location.start_line,
+                        snippet: location.signature.trim().to_string(),
+                        definition: true,
+                    });
+                }
+            }
+        }
+        let usages = UsageFinder::new().with_max_results(MAX_USAGES).find_usages(symbol, root, None, None);
+        hits.extend(usages.into_iter().map(|usage| SearchHit {
+            path: usage.path,
+            line: usage.line,
+            snippet: usage.snippet,
+            definition: false,
+        }));
+        self.record(InvestigationEntry::Search { query: symbol.to_string(), hits, timestamp: now() })
+    }
+
+    /// Files the zooms and searches touched, sorted
+    pub fn files(&self) -> BTreeSet<&str> {
+        let mut files = BTreeSet::new();
+        for entry in &self.entries {
+            match entry {
+                InvestigationEntry::Zoom { path: Some(path), .. } => {
+                    files.insert(path.as_str());
+                }
+                InvestigationEntry::Search { hits, .. } => files.extend(hits.iter().map(|h| h.path.as_str())),
+                _ => {}
+            }
+        }
+        files
+    }
+
+    /// Markdown report: summary and files involved, then every step in order
+    pub fn render(&self) -> String {
+        let count = |kind: fn(&InvestigationEntry) -> bool| self.entries.iter().filter(|e| kind(e)).count();
+        let mut out = format!("# Investigation: {}\n\n", self.name);
+        if let Some(description) = &self.description {
+            out.push_str(&format!("{}\n\n", description));
+        }
+        out.push_str(&format!(
+            "Started {}. {} zooms, {} searches, {} notes.\n",
+            format_time(self.created),
+            count(|e| matches!(e, InvestigationEntry::Zoom { .. })),
+            count(|e| matches!(e, InvestigationEntry::Search { .. })),
+            count(|e| matches!(e, InvestigationEntry::Note { .. })),
+        ));
+        let files = self.files();
+        if !files.is_empty() {
+            out.push_str("\n## Files involved\n\n");
+            for file in files {
+                out.push_str(&format!("- `{}`\n", file));
+            }
+        }
+
+        for (index, entry) in self.entries.iter().enumerate() {
+            out.push_str(&format!("\n## {}. {} [{}]\n\n", index + 1, entry.title(), format_time(entry.timestamp())));
+            match entry {
+                InvestigationEntry::Zoom { context, .. } => out.push_str(&fenced(context)),
+                InvestigationEntry::Search { hits, .. } if hits.is_empty() => out.push_str("No hits.\n"),
+                InvestigationEntry::Search { hits, .. } => {
+                    for hit in hits {
+                        let role = if hit.definition { " (definition)" } else { "" };
+                        out.push_str(&format!("- `{}:{}`{}: `{}`\n", hit.path, hit.line, role, hit.snippet.replace('`', "'")));
+                    }
+                }
+                InvestigationEntry::Note { text, .. } => out.push_str(&format!("{}\n", text.trim_end())),
+            }
+        }
+        out
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// Investigations of one project, by name, with the one being recorded into
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InvestigationStore {
+    #[serde(default = "json_store::default_version")]
+    pub version: String,
+    #[serde(default)]
+    pub investigations: BTreeMap<String, Investigation>,
+    /// Investigation new steps are recorded into
+    #[serde(default)]
+    pub active: Option<String>,
+    /// Runtime-only: path to store file (not persisted)
+    #[serde(skip)]
+    store_path: Option<PathBuf>,
+}
+
+impl InvestigationStore {
+    /// Default investigation file location (project-local)
+    pub fn default_path(project_root: &Path) -> PathBuf {
+        json_store::store_path(project_root, "investigations.json")
+    }
+
+    /// Load investigations from JSON file, or create empty store
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut store: Self = json_store::load(path)?;
+        store.store_path = Some(path.to_path_buf());
+        Ok(store)
+    }
+
+    /// Save investigations to JSON file
+    pub fn save(&self) -> Result<()> {
+        json_store::save(self.store_path.as_deref(), self)
+    }
+
+    /// Start a new investigation and make it active (an existing one of that name is resumed)
+    pub fn start(&mut self, name: &str, description: Option<String>) -> &mut Investigation {
+        self.active = Some(name.to_string());
+        self.investigations
+            .entry(name.to_string())
+            .or_insert_with(|| Investigation::new(name, description))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Investigation> {
+        self.investigations.get(name)
+    }
+
+    /// The investigation being recorded into
+    pub fn active(&self) -> Option<&Investigation> {
+        self.active.as_deref().and_then(|name| self.investigations.get(name))
+    }
+
+    pub fn active_mut(&mut self) -> Option<&mut Investigation> {
+        let name = self.active.as_deref()?;
+        self.investigations.get_mut(name)
+    }
+
+    /// Delete an investigation, returning whether it existed
+    pub fn remove(&mut self, name: &str) -> bool {
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+        self.investigations.remove(name).is_some()
+    }
+
+    /// Investigation names, sorted
+    pub fn names(&self) -> Vec<String> {
+        self.investigations.keys().cloned().collect()
+    }
+}
+
+fn format_time(timestamp: u64) -> String {
+    DateTime::from_timestamp(timestamp as i64, 0).map_or_else(|| timestamp.to_string(), |t| t.to_rfc3339())
+}
+
+/// `text` in a code fence longer than any backtick run inside it
+fn fenced(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    let newline = if text.ends_with('\n') { "" } else { "\n" };
+    format!("{}text\n{}{}{}\n", fence, text, newline, fence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn project() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("billing.py"),
+            "def apply_discount(total):\n    return total * 0.9\n\n\ndef refund(total):\n    return apply_discount(total)\n",
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_records_zooms_searches_and_notes() {
+        let dir = project();
+        let mut investigation = Investigation::new("refund-bug", Some("Refunds come out 10% short".into()));
+
+        let zoom = investigation.zoom(&ContextEngine::new(), dir.path(), "function=refund").unwrap();
+        assert!(zoom.context().unwrap().contains("return apply_discount(total)"));
+        let hits = investigation.search(&SymbolResolver::new(), dir.path(), "apply_discount").hits().to_vec();
+        assert_eq!(hits[0], SearchHit {
+            path: "billing.py".into(),
+            line: 1,
+            snippet: "def apply_discount(total):".into(),
+            definition: true,
+        });
+        assert!(hits.iter().any(|h| !h.definition && h.line == 6));
+        investigation.note("discount applied on refunds");
+        assert!(investigation.zoom(&ContextEngine::new(), dir.path(), "file=../etc/passwd").is_err());
+        assert_eq!(investigation.entries.len(), 3);
+
+        let report = investigation.render();
+        assert!(report.starts_with("# Investigation: refund-bug\n\nRefunds come out 10% short\n\nStarted "));
+        assert!(report.contains("1 zooms, 1 searches, 1 notes."));
+        assert!(report.contains("## Files involved\n\n- `billing.py`\n"));
+        assert!(report.contains("## 1. zoom function=refund ["));
+        assert!(report.contains("```text\n"));
+        assert!(report.contains("- `billing.py:1` (definition): `def apply_discount(total):`"));
+        assert!(report.contains("discount applied on refunds\n"));
+    }
+
+    #[test]
+    fn test_store_persists_the_active_investigation() {
+        let dir = project();
+        let path = InvestigationStore::default_path(dir.path());
+        let mut store = InvestigationStore::load(&path).unwrap();
+        assert!(store.active().is_none());
+        store.start("refund-bug", None).note("first");
+        store.active_mut().unwrap().note("second");
+        store.save().unwrap();
+
+        let mut reloaded = InvestigationStore::load(&path).unwrap();
+        assert_eq!(reloaded.active().unwrap().entries.len(), 2);
+        // Starting again resumes rather than resets
+        assert_eq!(reloaded.start("refund-bug", None).entries.len(), 2);
+        assert_eq!(reloaded.names(), vec!["refund-bug"]);
+        assert!(reloaded.remove("refund-bug"));
+        assert!(reloaded.active().is_none());
+
+        let json: serde_json::Value = serde_json::from_str(&store.get("refund-bug").unwrap().to_json()).unwrap();
+        assert_eq!(json["entries"][0]["kind"], "note");
+    }
+
+    #[test]
+    fn test_fence_outgrows_backticks_in_context() {
+        assert_eq!(fenced("a\n"), "```text\na\n```\n");
+        assert_eq!(fenced("``` nested\n"), "````text\n``` nested\n````\n");
+    }
+}
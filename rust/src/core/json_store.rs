@@ -0,0 +1,73 @@
+//! JSON Store - Project-local state files under `.pm_encoder/`
+//!
+//! Zoom sessions, bookmarks and investigations are each one pretty-printed
+//! JSON document in the project's `.pm_encoder/` directory. They share the
+//! location, the schema version default, and the load/save rules: a missing
+//! file loads as an empty store, and saving creates the directory first.
+
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::error::{EncoderError, Result};
+
+/// Directory holding project-local state, relative to the project root
+pub const STORE_DIR: &str = ".pm_encoder";
+
+/// Schema version written by new stores
+pub fn default_version() -> String {
+    "1.0".to_string()
+}
+
+/// `file_name` inside the project's store directory
+pub fn store_path(project_root: &Path, file_name: &str) -> PathBuf {
+    project_root.join(STORE_DIR).join(file_name)
+}
+
+/// Read a store from `path`; a missing file loads with every field at its serde default
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    if !path.exists() {
+        return Ok(serde_json::from_str("{}")?);
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+/// Write `store` to `path`, creating the store directory if needed
+pub fn save<T: Serialize>(path: Option<&Path>, store: &T) -> Result<()> {
+    let path = path.ok_or_else(|| EncoderError::invalid_config("No store path configured"))?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(store)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use tempfile::TempDir;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Store {
+        #[serde(default = "default_version")]
+        version: String,
+        #[serde(default)]
+        names: Vec<String>,
+    }
+
+    #[test]
+    fn test_missing_file_loads_empty_and_save_creates_directory() {
+        let dir = TempDir::new().unwrap();
+        let path = store_path(dir.path(), "names.json");
+        let mut store: Store = load(&path).unwrap();
+        assert_eq!(store, Store { version: "1.0".into(), names: Vec::new() });
+
+        store.names.push("first".into());
+        save(Some(&path), &store).unwrap();
+        assert!(dir.path().join(".pm_encoder/names.json").exists());
+        assert_eq!(load::<Store>(&path).unwrap(), store);
+        assert!(save(None, &store).is_err());
+    }
+}
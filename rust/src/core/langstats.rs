@@ -0,0 +1,223 @@
+//! Per-language statistics (cloc-like) from a Planetarium index
+//!
+//! Everything here is already computed while indexing: line counts come from
+//! each file's span, symbols from its declarations and comment lines from the
+//! extracted comments. [`LanguageStatsReport`] only aggregates and renders.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use voyager_ast::{File, PlanetariumModel};
+
+/// Totals for one language (or all of them)
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LanguageSummary {
+    pub language: String,
+    pub files: usize,
+    pub lines: usize,
+    pub comment_lines: usize,
+    pub symbols: usize,
+    pub bytes: usize,
+}
+
+impl LanguageSummary {
+    /// Share of lines that are comments (0.0 - 1.0)
+    pub fn comment_density(&self) -> f64 {
+        if self.lines == 0 {
+            0.0
+        } else {
+            self.comment_lines as f64 / self.lines as f64
+        }
+    }
+
+    fn add(&mut self, file: &File) {
+        self.files += 1;
+        self.lines += file.span.end_line;
+        self.comment_lines += comment_lines(file);
+        self.symbols += file.total_declarations();
+        self.bytes += file.span.end;
+    }
+}
+
+/// One entry of the largest-files list
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileSize {
+    pub path: String,
+    pub language: String,
+    pub lines: usize,
+    pub symbols: usize,
+}
+
+/// Language statistics for an indexed project
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageStatsReport {
+    /// Per-language totals, most lines first
+    pub languages: Vec<LanguageSummary>,
+    /// Totals across all languages
+    pub total: LanguageSummary,
+    /// Largest files by line count
+    pub largest_files: Vec<FileSize>,
+}
+
+impl LanguageStatsReport {
+    /// Aggregate `model`, keeping the `top` largest files
+    pub fn from_model(model: &PlanetariumModel, top: usize) -> Self {
+        let mut by_language: BTreeMap<&str, LanguageSummary> = BTreeMap::new();
+        let mut total = LanguageSummary { language: "Total".to_string(), ..Default::default() };
+        let mut files = Vec::with_capacity(model.files.len());
+
+        for (path, file) in &model.files {
+            let language = file.language.name();
+            by_language
+                .entry(language)
+                .or_insert_with(|| LanguageSummary { language: language.to_string(), ..Default::default() })
+                .add(file);
+            total.add(file);
+            files.push(FileSize {
+                path: path.to_string_lossy(),
+                language: language.to_string(),
+                lines: file.span.end_line,
+                symbols: file.total_declarations(),
+            });
+        }
+
+        let mut languages: Vec<_> = by_language.into_values().collect();
+        languages.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.language.cmp(&b.language)));
+        files.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.path.cmp(&b.path)));
+        files.truncate(top);
+
+        Self { languages, total, largest_files: files }
+    }
+
+    /// Pretty-printed JSON (includes comment density per language)
+    pub fn to_json(&self) -> String {
+        let density = |s: &LanguageSummary| {
+            let mut value = serde_json::to_value(s).unwrap_or_default();
+            value["comment_density"] = serde_json::json!((s.comment_density() * 1000.0).round() / 1000.0);
+            value
+        };
+        let value = serde_json::json!({
+            "languages": self.languages.iter().map(density).collect::<Vec<_>>(),
+            "total": density(&self.total),
+            "largest_files": self.largest_files,
+        });
+        serde_json::to_string_pretty(&value).unwrap_or_default()
+    }
+
+    /// Plain-text table
+    pub fn render_table(&self) -> String {
+        let mut out = String::new();
+        let header = format!(
+            "{:<14} {:>7} {:>9} {:>9} {:>9} {:>9}\n",
+            "Language", "Files", "Lines", "Comments", "Density", "Symbols"
+        );
+        let rule = format!("{}\n", "-".repeat(header.len() - 1));
+        out.push_str(&header);
+        out.push_str(&rule);
+        let row = |summary: &LanguageSummary| {
+            format!(
+                "{:<14} {:>7} {:>9} {:>9} {:>8.1}% {:>9}\n",
+                summary.language,
+                summary.files,
+                summary.lines,
+                summary.comment_lines,
+                summary.comment_density() * 100.0,
+                summary.symbols
+            )
+        };
+        for summary in &self.languages {
+            out.push_str(&row(summary));
+        }
+        out.push_str(&rule);
+        out.push_str(&row(&self.total));
+
+        if !self.largest_files.is_empty() {
+            out.push_str("\nLargest files:\n");
+            for file in &self.largest_files {
+                out.push_str(&format!(
+                    "{:>9} lines {:>5} symbols  {} ({})\n",
+                    file.lines, file.symbols, file.path, file.language
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// Lines covered by comments (overlapping comments counted once)
+///
+/// Line comments that include their newline (Rust `///`, `//!`) end at
+/// column 0 of the next line, which they do not cover.
+fn comment_lines(file: &File) -> usize {
+    let mut spans: Vec<(usize, usize)> = file.comments
+        .iter()
+        .map(|c| {
+            let end = if c.span.end_column == 0 { c.span.end_line.saturating_sub(1) } else { c.span.end_line };
+            (c.span.start_line, end.max(c.span.start_line))
+        })
+        .collect();
+    spans.sort_unstable();
+    let mut count = 0;
+    let mut covered_to = 0;
+    for (start, end) in spans {
+        let start = start.max(covered_to + 1);
+        if end >= start {
+            count += end - start + 1;
+            covered_to = end;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use voyager_ast::{AdapterRegistry, LanguageId, RawPath};
+
+    fn model(files: &[(&str, LanguageId, &str)]) -> PlanetariumModel {
+        let registry = AdapterRegistry::new();
+        let mut model = PlanetariumModel::new(".");
+        for (path, language, source) in files {
+            let mut file = registry.parse(source, *language).unwrap();
+            file.path = RawPath::from_key(path);
+            model.files.insert(RawPath::from_key(path), file);
+        }
+        model
+    }
+
+    #[test]
+    fn test_language_stats_aggregate() {
+        let model = model(&[
+            ("src/lib.rs", LanguageId::Rust, "/// Adds\n/// numbers\npub fn add() {}\n\npub fn sub() {}\n"),
+            ("src/main.rs", LanguageId::Rust, "fn main() {}\n"),
+            ("tool.py", LanguageId::Python, "# helper\ndef run():\n    pass\n"),
+        ]);
+        let report = LanguageStatsReport::from_model(&model, 2);
+
+        assert_eq!(report.languages[0].language, "Rust");
+        assert_eq!(report.languages[0].files, 2);
+        assert_eq!(report.languages[0].lines, 6);
+        assert_eq!(report.languages[0].symbols, 3);
+        assert_eq!(report.total.files, 3);
+        assert_eq!(report.total.lines, 9);
+        assert!(report.total.comment_lines >= 2);
+        assert_eq!(report.largest_files.len(), 2);
+        assert_eq!(report.largest_files[0].path, "src/lib.rs");
+
+        let table = report.render_table();
+        assert!(table.contains("Total"));
+        assert!(table.contains("Largest files:"));
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert!(json["total"]["comment_density"].as_f64().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_doc_comment_lines_counted_once() {
+        let model = model(&[
+            ("src/lib.rs", LanguageId::Rust, "//! Crate docs\n/// Adds\n/// numbers\npub fn add() {}\n"),
+            ("src/main.rs", LanguageId::Rust, "// plain\n// comments\nfn main() {}\n"),
+        ]);
+        let report = LanguageStatsReport::from_model(&model, 2);
+        assert_eq!(report.languages[0].comment_lines, 5);
+    }
+}
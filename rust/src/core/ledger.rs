@@ -0,0 +1,144 @@
+//! Content Ledger - What a conversation has already been shown
+//!
+//! Within one conversation a model often asks for the same content again: a
+//! second get_context after a small edit, or a zoom into a function it has
+//! already read. A [`ContentLedger`] records, per session, the fingerprint of
+//! every file and declaration delivered and the message it went out in.
+//! Content whose fingerprint has not changed since is replaced by a marker:
+//!
+//! ```text
+//! «unchanged since msg #3»
+//! ```
+//!
+//! Fingerprints are MD5 digests: of the original file content for files (the
+//! checksum already printed in file headers) and of the zoomed text for
+//! declarations. Clients that track their own history pass the fingerprints
+//! they hold as [`SeenContent`] instead of relying on a server-side session.
+//!
+//! A delivery only stands in for a later one if it was complete: a file first
+//! sent truncated or as a skeleton is sent again when it is requested in full.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::models::calculate_md5;
+
+/// Content the client reports it already holds
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeenContent {
+    /// File path or declaration key (`function:main`)
+    pub key: String,
+    /// MD5 of the content
+    pub fingerprint: String,
+    /// Message the content was delivered in
+    pub message: u64,
+    /// Whether the full content was delivered (default: true)
+    #[serde(default = "default_complete")]
+    pub complete: bool,
+}
+
+fn default_complete() -> bool {
+    true
+}
+
+/// One recorded delivery
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Delivery {
+    fingerprint: String,
+    message: u64,
+    complete: bool,
+}
+
+/// Deliveries of one conversation, by key
+#[derive(Debug, Clone, Default)]
+pub struct ContentLedger {
+    deliveries: HashMap<String, Delivery>,
+    last_message: u64,
+}
+
+impl ContentLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number for the next message when the client does not give one
+    pub fn next_message(&self) -> u64 {
+        self.last_message + 1
+    }
+
+    /// Number of recorded keys
+    pub fn len(&self) -> usize {
+        self.deliveries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deliveries.is_empty()
+    }
+
+    /// Record what the client reports it already holds
+    pub fn extend_seen(&mut self, seen: impl IntoIterator<Item = SeenContent>) {
+        for item in seen {
+            self.record(&item.key, &item.fingerprint, item.message, item.complete);
+        }
+    }
+
+    /// Message that delivered `key` unchanged, if that delivery covers this one
+    pub fn delivered_in(&self, key: &str, fingerprint: &str, complete: bool) -> Option<u64> {
+        let delivery = self.deliveries.get(key)?;
+        (delivery.fingerprint == fingerprint && (delivery.complete || !complete)).then_some(delivery.message)
+    }
+
+    /// Record a delivery of `key` in `message`
+    ///
+    /// A partial delivery does not replace a complete one of the same content.
+    pub fn record(&mut self, key: &str, fingerprint: &str, message: u64, complete: bool) {
+        self.last_message = self.last_message.max(message);
+        if self.delivered_in(key, fingerprint, complete).is_some() {
+            return;
+        }
+        let delivery = Delivery { fingerprint: fingerprint.to_string(), message, complete };
+        self.deliveries.insert(key.to_string(), delivery);
+    }
+}
+
+/// Fingerprint of a piece of content
+pub fn fingerprint(content: &str) -> String {
+    calculate_md5(content)
+}
+
+/// Marker that replaces content delivered in `message`
+pub fn unchanged_marker(message: u64) -> String {
+    format!("«unchanged since msg #{}»", message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ledger_elides_only_unchanged_complete_deliveries() {
+        let mut ledger = ContentLedger::new();
+        ledger.extend_seen(vec![SeenContent {
+            key: "src/lib.rs".to_string(),
+            fingerprint: "aaa".to_string(),
+            message: 3,
+            complete: true,
+        }]);
+        assert_eq!(ledger.next_message(), 4);
+        assert_eq!(ledger.delivered_in("src/lib.rs", "aaa", true), Some(3));
+        assert_eq!(ledger.delivered_in("src/lib.rs", "bbb", true), None);
+        assert_eq!(ledger.delivered_in("src/main.rs", "aaa", true), None);
+
+        // A skeleton does not stand in for the full file, but the full file covers a skeleton
+        ledger.record("src/main.rs", "ccc", 4, false);
+        assert_eq!(ledger.delivered_in("src/main.rs", "ccc", true), None);
+        assert_eq!(ledger.delivered_in("src/main.rs", "ccc", false), Some(4));
+        ledger.record("src/lib.rs", "aaa", 5, false);
+        assert_eq!(ledger.delivered_in("src/lib.rs", "aaa", true), Some(3));
+
+        assert_eq!(unchanged_marker(3), "«unchanged since msg #3»");
+        let seen: SeenContent = serde_json::from_str(r#"{"key": "k", "fingerprint": "f", "message": 1}"#).unwrap();
+        assert!(seen.complete);
+    }
+}
@@ -0,0 +1,482 @@
+//! Levels of Detail - Breadth-first rendering of a project at mixed detail
+//!
+//! Every file is rendered at one of four levels of detail:
+//!
+//! | Level | Name           | Content                                   |
+//! |-------|----------------|-------------------------------------------|
+//! | LOD0  | `files`        | A line in the file list                   |
+//! | LOD1  | `declarations` | Outline of declarations with line ranges  |
+//! | LOD2  | `signatures`   | Signatures and doc comments, no bodies    |
+//! | LOD3  | `full`         | Full content                              |
+//!
+//! A [`LodPlan`] assigns levels per subtree (`src/core` at LOD3, the rest at
+//! LOD1). Under a token budget the plan is a ceiling: all files start at
+//! LOD0 and are raised one level at a time, breadth-first, so the whole
+//! project gains detail evenly before any single file gets its bodies.
+//!
+//! A per-symbol budget keeps one god-function from taking a whole LOD3
+//! file's share: a function or method over it drops to its signature, then
+//! to its outline line, then to a bare zoom affordance, until it fits.
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::path::Path;
+
+use voyager_ast::{Declaration, LanguageId};
+
+use crate::core::ast_bridge::AstBridge;
+use crate::core::models::FileEntry;
+use crate::core::summarizer::{collect_callables, comment_delimiters};
+use crate::core::zoom::ZoomAction;
+
+/// Name of the synthetic file listing every file and its level
+pub const LOD_INDEX_FILE_NAME: &str = "FILES.md";
+
+/// First line of outlines guessed by the heuristic indexer
+const HEURISTIC_NOTE: &str = "(heuristic outline: no grammar for this language)\n";
+
+/// Level of detail of a rendered file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Lod {
+    /// Listed in the file index only
+    Files,
+    /// Declaration outline
+    Declarations,
+    /// Signatures and doc comments
+    Signatures,
+    /// Full content
+    #[default]
+    Full,
+}
+
+impl Lod {
+    /// All levels, coarsest first
+    pub const ALL: [Lod; 4] = [Lod::Files, Lod::Declarations, Lod::Signatures, Lod::Full];
+
+    /// Parse `0`-`3`, `lod0`-`lod3` or a level name
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "0" | "lod0" | "files" => Some(Lod::Files),
+            "1" | "lod1" | "declarations" | "decls" => Some(Lod::Declarations),
+            "2" | "lod2" | "signatures" => Some(Lod::Signatures),
+            "3" | "lod3" | "full" => Some(Lod::Full),
+            _ => None,
+        }
+    }
+
+    /// Numeric level (0-3)
+    pub fn level(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Lod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LOD{}", self.level())
+    }
+}
+
+/// Level-of-detail assignment per subtree
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LodPlan {
+    /// Level of files outside every subtree
+    pub default: Lod,
+    /// Path prefix → level; the longest matching prefix wins
+    pub subtrees: Vec<(String, Lod)>,
+    /// Token cap per function or method rendered at LOD3
+    pub symbol_budget: Option<usize>,
+}
+
+impl LodPlan {
+    /// Create a plan rendering everything at `default`
+    pub fn new(default: Lod) -> Self {
+        Self { default, subtrees: Vec::new(), symbol_budget: None }
+    }
+
+    /// Builder: downgrade LOD3 functions and methods over `budget` tokens
+    pub fn with_symbol_budget(mut self, budget: usize) -> Self {
+        self.symbol_budget = Some(budget);
+        self
+    }
+
+    /// Builder: render files under `prefix` at `lod`
+    pub fn with_subtree(mut self, prefix: &str, lod: Lod) -> Self {
+        let prefix = prefix.replace('\\', "/").trim_start_matches("./").trim_end_matches('/').to_string();
+        self.subtrees.push((prefix, lod));
+        self
+    }
+
+    /// Parse a `PREFIX=LEVEL` subtree rule
+    pub fn parse_subtree(spec: &str) -> Result<(String, Lod), String> {
+        let (prefix, level) = spec
+            .rsplit_once('=')
+            .ok_or_else(|| format!("Invalid LOD rule '{}': expected PREFIX=LEVEL", spec))?;
+        let lod = Lod::parse(level).ok_or_else(|| format!("Invalid LOD level '{}': expected 0-3", level))?;
+        Ok((prefix.to_string(), lod))
+    }
+
+    /// Level assigned to a path
+    pub fn lod_for(&self, path: &str) -> Lod {
+        let path = path.replace('\\', "/");
+        self.subtrees
+            .iter()
+            .filter(|(prefix, _)| {
+                prefix.is_empty()
+                    || path == *prefix
+                    || path.strip_prefix(prefix.as_str()).is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default, |(_, lod)| *lod)
+    }
+
+    /// Assign a level to every entry, breadth-first under an optional budget
+    ///
+    /// Without a budget each file gets its planned level. With one, the plan
+    /// is a ceiling: every file starts at LOD0, then each level is offered to
+    /// all files (in entry order) before the next, as long as the upgrade
+    /// fits. A file that cannot afford a level stops there.
+    pub fn assign(&self, entries: &[FileEntry], budget: Option<usize>) -> Vec<Lod> {
+        let ceilings: Vec<Lod> = entries.iter().map(|e| self.lod_for(&e.path)).collect();
+        let Some(budget) = budget else {
+            return ceilings;
+        };
+
+        let mut lods = vec![Lod::Files; entries.len()];
+        let mut costs: Vec<usize> = entries.iter().map(|e| self.cost(e, Lod::Files)).collect();
+        let mut remaining = budget.saturating_sub(costs.iter().sum());
+        for lod in &Lod::ALL[1..] {
+            for (i, entry) in entries.iter().enumerate() {
+                if *lod > ceilings[i] || lods[i].level() + 1 != lod.level() {
+                    continue;
+                }
+                let upgraded = self.cost(entry, *lod);
+                if upgraded <= costs[i] + remaining {
+                    remaining = remaining + costs[i] - upgraded;
+                    lods[i] = *lod;
+                    costs[i] = upgraded;
+                }
+            }
+        }
+        lods
+    }
+
+    /// Render a file at a level, applying the per-symbol budget at LOD3
+    pub fn render(&self, path: &str, content: &str, lod: Lod) -> String {
+        match (lod, self.symbol_budget) {
+            (Lod::Full, Some(budget)) => render_capped(path, content, budget),
+            _ => render(path, content, lod),
+        }
+    }
+
+    /// Estimated tokens of an entry at a level under this plan
+    pub fn cost(&self, entry: &FileEntry, lod: Lod) -> usize {
+        match (lod, self.symbol_budget) {
+            (Lod::Full, Some(budget)) => render_capped(&entry.path, &entry.content, budget).len() / 4,
+            _ => cost(entry, lod),
+        }
+    }
+}
+
+/// Estimated tokens of an entry at a level (LOD0: its file list line)
+pub fn cost(entry: &FileEntry, lod: Lod) -> usize {
+    match lod {
+        Lod::Files => index_line(entry, lod).len() / 4,
+        Lod::Full => entry.token_estimate(),
+        _ => render(&entry.path, &entry.content, lod).len() / 4,
+    }
+}
+
+/// Render a file at a level (LOD0 renders to its file list line)
+pub fn render(path: &str, content: &str, lod: Lod) -> String {
+    if lod == Lod::Full {
+        return content.to_string();
+    }
+    if lod == Lod::Files {
+        return index_line(&FileEntry::new(path, content.to_string()), lod);
+    }
+
+    let language = AstBridge::detect_language(Path::new(path));
+    let (heuristic, declarations) = AstBridge::new()
+        .analyze_or_index(content, language)
+        .map(|file| (file.is_heuristic(), file.declarations))
+        .unwrap_or_default();
+    if declarations.is_empty() {
+        return format!("({} lines, no declarations)\n", content.lines().count());
+    }
+    // Guessed structure is labelled as such, never passed off as parsed
+    let mut out = if heuristic { HEURISTIC_NOTE.to_string() } else { String::new() };
+
+    if lod == Lod::Declarations {
+        outline(&declarations, 0, &mut out);
+        return out;
+    }
+
+    // LOD2: signature and doc lines, verbatim, with elided gaps
+    let mut keep = BTreeSet::new();
+    signature_lines(&declarations, content, &mut keep);
+    let lines: Vec<&str> = content.lines().collect();
+    let mut previous = 0;
+    for line in keep.into_iter().filter(|&l| l >= 1 && l <= lines.len()) {
+        if line > previous + 1 && lines[previous..line - 1].iter().any(|l| !l.trim().is_empty()) {
+            out.push_str("...\n");
+        }
+        out.push_str(lines[line - 1]);
+        out.push('\n');
+        previous = line;
+    }
+    if previous < lines.len() && lines[previous..].iter().any(|l| !l.trim().is_empty()) {
+        out.push_str("...\n");
+    }
+    out
+}
+
+/// Render a file at LOD3 with every function or method over `budget`
+/// tokens downgraded one level at a time until it fits
+///
+/// Each downgraded symbol keeps a comment with the level it was shown at and
+/// the zoom affordance restoring it. Files in languages without an AST
+/// adapter are returned unchanged.
+pub fn render_capped(path: &str, content: &str, budget: usize) -> String {
+    let language = AstBridge::detect_language(Path::new(path));
+    let Some(file) = AstBridge::new().analyze_file(content, language) else {
+        return content.to_string();
+    };
+    let mut callables = Vec::new();
+    collect_callables(&file.declarations, &mut callables);
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut replacements: Vec<(usize, usize, String)> = Vec::new();
+    for decl in callables {
+        let (start, end) = (decl.span.start_line, decl.span.end_line.min(lines.len()));
+        if start == 0 || start > end || replacements.last().is_some_and(|(_, last_end, _)| start <= *last_end) {
+            continue;
+        }
+        let tokens = lines[start - 1..end].join("\n").len() / 4;
+        if tokens <= budget {
+            continue;
+        }
+        let rendered = Lod::ALL[..Lod::Full.level()]
+            .iter()
+            .rev()
+            .map(|lod| render_symbol(decl, content, &lines, *lod, tokens, language))
+            .find(|rendered| rendered.len() / 4 <= budget)
+            .unwrap_or_else(|| render_symbol(decl, content, &lines, Lod::Files, tokens, language));
+        replacements.push((start, end, rendered));
+    }
+    if replacements.is_empty() {
+        return content.to_string();
+    }
+
+    let mut out = String::new();
+    let mut next = 1;
+    for (start, end, rendered) in replacements {
+        for line in &lines[next - 1..start - 1] {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str(&rendered);
+        out.push('\n');
+        next = end + 1;
+    }
+    for line in &lines[next - 1..] {
+        out.push_str(line);
+        out.push('\n');
+    }
+    if !content.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+/// A function or method at a level below LOD3, with a note on what was left out
+fn render_symbol(
+    decl: &Declaration,
+    content: &str,
+    lines: &[&str],
+    lod: Lod,
+    tokens: usize,
+    language: LanguageId,
+) -> String {
+    let (start, end) = (decl.span.start_line, decl.span.end_line.min(lines.len()));
+    let first = lines[start - 1];
+    let indent = &first[..first.len() - first.trim_start().len()];
+    let (open, close) = comment_delimiters(language);
+    let action = ZoomAction::for_declaration(decl, content);
+    match lod {
+        Lod::Signatures => {
+            let signature_end = signature_end_line(decl, content);
+            format!(
+                "{}\n{}{}{}: body elided (~{} tokens) - ZOOM_AFFORDANCE: {}{}",
+                lines[start - 1..signature_end.clamp(start, end)].join("\n"),
+                indent,
+                open,
+                lod,
+                tokens,
+                action.command,
+                close
+            )
+        }
+        Lod::Declarations => format!(
+            "{}{}{}: {} {} [lines {}-{}] (~{} tokens) - ZOOM_AFFORDANCE: {}{}",
+            indent,
+            open,
+            lod,
+            decl.kind.as_str(),
+            decl.name,
+            start,
+            end,
+            tokens,
+            action.command,
+            close
+        ),
+        _ => format!("{}{}ZOOM_AFFORDANCE: {}{}", indent, open, action.command, close),
+    }
+}
+
+/// The file list shown for all files, with their levels
+pub fn file_index(entries: &[FileEntry], lods: &[Lod]) -> String {
+    let mut out = String::from("# Files\n\n");
+    for (entry, lod) in entries.iter().zip(lods) {
+        out.push_str(&index_line(entry, *lod));
+    }
+    out
+}
+
+fn index_line(entry: &FileEntry, lod: Lod) -> String {
+    format!(
+        "- {} ({}, {} lines, ~{} tokens)\n",
+        entry.path,
+        lod,
+        entry.content.lines().count(),
+        entry.token_estimate()
+    )
+}
+
+fn outline(decls: &[Declaration], depth: usize, out: &mut String) {
+    for decl in decls {
+        // Structure the parser was unsure of is annotated, not hidden
+        let confidence = if decl.is_uncertain() { format!(" (confidence {:.1})", decl.confidence) } else { String::new() };
+        out.push_str(&format!(
+            "{}{} {} [lines {}-{}]{}\n",
+            "  ".repeat(depth),
+            decl.kind.as_str(),
+            decl.name,
+            decl.span.start_line,
+            decl.span.end_line,
+            confidence
+        ));
+        outline(&decl.children, depth + 1, out);
+    }
+}
+
+fn signature_lines(decls: &[Declaration], content: &str, keep: &mut BTreeSet<usize>) {
+    for decl in decls {
+        if let Some(doc) = &decl.doc_comment {
+            keep.extend(doc.span.start_line..=doc.span.end_line);
+        }
+        keep.extend(decl.span.start_line..=signature_end_line(decl, content));
+        signature_lines(&decl.children, content, keep);
+    }
+}
+
+/// Last line of a declaration's signature
+///
+/// The signature ends at the last code before the body: the `{` line in
+/// brace languages, the `def` line in Python, whose body block (and with it
+/// the parsed signature span) only starts on the next line.
+fn signature_end_line(decl: &Declaration, content: &str) -> usize {
+    let (end_byte, end_line) = match (decl.signature_span, decl.body_span) {
+        (Some(signature), _) => (signature.end, signature.end_line),
+        (None, Some(body)) => (body.start, body.start_line),
+        (None, None) => return decl.span.start_line,
+    };
+    let end = match content.get(..end_byte) {
+        Some(before) => before.trim_end().matches('\n').count() + 1,
+        None => end_line,
+    };
+    end.max(decl.span.start_line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "/// Adds numbers.\npub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\npub struct Engine;\n\nimpl Engine {\n    pub fn run(&self) {\n        println!(\"run\");\n    }\n}\n";
+
+    #[test]
+    fn test_lod_parse_and_plan() {
+        assert_eq!(Lod::parse("lod2"), Some(Lod::Signatures));
+        assert_eq!(Lod::parse("3"), Some(Lod::Full));
+        assert_eq!(Lod::parse("9"), None);
+        assert_eq!(Lod::Declarations.to_string(), "LOD1");
+
+        let plan = LodPlan::new(Lod::Declarations).with_subtree("src/core/", Lod::Full).with_subtree("src", Lod::Signatures);
+        assert_eq!(plan.lod_for("src/core/engine.rs"), Lod::Full);
+        assert_eq!(plan.lod_for("src/lib.rs"), Lod::Signatures);
+        assert_eq!(plan.lod_for("srcs/x.rs"), Lod::Declarations);
+        assert_eq!(LodPlan::parse_subtree("src=0"), Ok(("src".to_string(), Lod::Files)));
+        assert!(LodPlan::parse_subtree("src").is_err());
+    }
+
+    #[test]
+    fn test_render_levels() {
+        let decls = render("lib.rs", SOURCE, Lod::Declarations);
+        assert!(decls.contains("function add [lines 1-4]") || decls.contains("function add [lines 2-4]"));
+        assert!(decls.contains("  method run"));
+        assert!(!decls.contains("confidence"));
+        let broken = render("lib.rs", "pub fn broken() {\n    let x = ;\n}\n", Lod::Declarations);
+        assert!(broken.contains("(confidence 0."), "{}", broken);
+        let perl = render("greet.pl", "sub hello {\n    print \"hi\";\n}\n", Lod::Declarations);
+        assert_eq!(perl, format!("{}function hello [lines 1-3] (confidence 0.4)\n", HEURISTIC_NOTE));
+
+        let signatures = render("lib.rs", SOURCE, Lod::Signatures);
+        assert!(signatures.contains("/// Adds numbers.\npub fn add(a: i32, b: i32) -> i32 {\n...\n"));
+        assert!(signatures.contains("    pub fn run(&self) {"));
+        assert!(!signatures.contains("println!"));
+
+        assert_eq!(render("lib.rs", SOURCE, Lod::Full), SOURCE);
+        assert!(render("lib.rs", SOURCE, Lod::Files).starts_with("- lib.rs (LOD0, 12 lines"));
+    }
+
+    #[test]
+    fn test_assign_is_breadth_first() {
+        let big = FileEntry::new("a.rs", SOURCE.repeat(20));
+        let small = FileEntry::new("b.rs", SOURCE.to_string());
+        let entries = vec![big.clone(), small.clone()];
+        let plan = LodPlan::new(Lod::Full);
+        assert_eq!(plan.assign(&entries, None), vec![Lod::Full, Lod::Full]);
+
+        // Enough for both outlines and signatures, but not the big file's bodies
+        let budget = cost(&big, Lod::Signatures) + cost(&small, Lod::Full) + 10;
+        assert_eq!(plan.assign(&entries, Some(budget)), vec![Lod::Signatures, Lod::Full]);
+
+        let capped = LodPlan::new(Lod::Full).with_subtree("b.rs", Lod::Declarations);
+        assert_eq!(capped.assign(&entries, Some(usize::MAX))[1], Lod::Declarations);
+    }
+
+    #[test]
+    fn test_symbol_budget_downgrades_until_fit() {
+        let params: String = (0..12).map(|i| format!("        item_{}: u32,\n", i)).collect();
+        let body: String = (0..80).map(|i| format!("        println!(\"{}\");\n", i)).collect();
+        let run = format!("    pub fn run(\n        &self,\n{}    ) {{\n{}", params, body);
+        let source = SOURCE.replace("    pub fn run(&self) {\n        println!(\"run\");\n", &run);
+
+        // Room for the signature: the body is elided, `add` is untouched
+        let capped = render_capped("lib.rs", &source, 120);
+        assert!(capped.contains("pub fn add(a: i32, b: i32) -> i32 {\n    a + b\n}"));
+        assert!(capped.contains("        item_11: u32,\n    ) {\n    /* LOD2: body elided (~"), "{}", capped);
+        assert!(capped.contains("ZOOM_AFFORDANCE: pm_encoder --zoom function=run"));
+        assert!(!capped.contains("println!(\"40\")"));
+
+        // No room for the signature: the outline line, then the bare affordance
+        let outline = render_capped("lib.rs", &source, 60);
+        assert!(outline.contains("    /* LOD1: method run [lines 9-104] (~"), "{}", outline);
+        assert!(!outline.contains("item_0"));
+        assert!(render_capped("lib.rs", &source, 20).contains("impl Engine {\n    /* ZOOM_AFFORDANCE: pm_encoder --zoom function=run"));
+
+        let plan = LodPlan::new(Lod::Full).with_symbol_budget(120);
+        let entry = FileEntry::new("lib.rs", source.clone());
+        assert!(plan.cost(&entry, Lod::Full) < cost(&entry, Lod::Full));
+        assert_eq!(plan.render("lib.rs", &source, Lod::Full), capped);
+    }
+}
@@ -0,0 +1,613 @@
+//! Dependency Manifests - Direct dependencies and the files that use them
+//!
+//! Parses the dependency manifests of the common ecosystems:
+//!
+//! | Manifest                              | Ecosystem |
+//! |---------------------------------------|-----------|
+//! | `Cargo.toml`                          | cargo     |
+//! | `package.json`                        | npm       |
+//! | `pyproject.toml`, `requirements*.txt` | python    |
+//! | `go.mod`                              | go        |
+//! | `pom.xml`, `build.gradle(.kts)`       | maven     |
+//! | `Gemfile`                             | ruby      |
+//!
+//! Only direct dependencies are collected (indirect `go.mod` requirements
+//! and lock files are ignored). A [`DependencyModel`] joins them with the
+//! import statements of source files to answer "which files use X".
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+
+/// Dependencies listed per manifest in instruction files
+const MAX_LISTED: usize = 30;
+
+lazy_static! {
+    static ref RUST_IMPORT: Regex = Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:use\s+(?:::)?|extern\s+crate\s+)(\w+)").unwrap();
+    static ref RUST_ATTRIBUTE: Regex = Regex::new(r"^\s*#\[(\w+)::").unwrap();
+    static ref PYTHON_IMPORT: Regex = Regex::new(r"^\s*(?:from\s+([\w.]+)\s+import|import\s+([\w.]+))").unwrap();
+    static ref JS_IMPORT: Regex = Regex::new(r#"(?:\bfrom\s+|\bimport\s*\(?\s*|\brequire\s*\(\s*)['"]([^'"]+)['"]"#).unwrap();
+    static ref GO_IMPORT: Regex = Regex::new(r#"^\s*(?:import\s+)?(?:[\w.]+\s+)?"([^"\s]+)"\s*$"#).unwrap();
+    static ref JAVA_IMPORT: Regex = Regex::new(r"^\s*import\s+(?:static\s+)?([\w.]+)").unwrap();
+    static ref RUBY_IMPORT: Regex = Regex::new(r#"^\s*require\s+['"]([^'"]+)['"]"#).unwrap();
+    static ref POM_DEPENDENCY: Regex = Regex::new(r"(?s)<dependency>(.*?)</dependency>").unwrap();
+    static ref POM_MANAGEMENT: Regex = Regex::new(r"(?s)<dependencyManagement>.*?</dependencyManagement>").unwrap();
+    static ref GRADLE_DEPENDENCY: Regex = Regex::new(r#"^\s*(\w+)\s*\(?\s*['"]([^:'"\s]+):([^:'"\s]+)(?::([^'"\s]+))?['"]"#).unwrap();
+    static ref GEM: Regex = Regex::new(r#"^\s*gem\s+['"]([^'"]+)['"](?:\s*,\s*['"]([^'"]+)['"])?"#).unwrap();
+}
+
+/// Package ecosystem of a manifest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Python,
+    Go,
+    Maven,
+    Ruby,
+}
+
+impl Ecosystem {
+    /// Ecosystem of a manifest path, if it is one
+    pub fn of_manifest(path: &str) -> Option<Self> {
+        match path.rsplit('/').next().unwrap_or(path) {
+            "Cargo.toml" => Some(Self::Cargo),
+            "package.json" => Some(Self::Npm),
+            "pyproject.toml" => Some(Self::Python),
+            name if name.starts_with("requirements") && name.ends_with(".txt") => Some(Self::Python),
+            "go.mod" => Some(Self::Go),
+            "pom.xml" | "build.gradle" | "build.gradle.kts" => Some(Self::Maven),
+            "Gemfile" => Some(Self::Ruby),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Cargo => "cargo",
+            Self::Npm => "npm",
+            Self::Python => "python",
+            Self::Go => "go",
+            Self::Maven => "maven",
+            Self::Ruby => "ruby",
+        }
+    }
+}
+
+impl fmt::Display for Ecosystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A direct dependency declared in a manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Dependency {
+    /// Package name (`group:artifact` for Maven)
+    pub name: String,
+    /// Version requirement as written, when given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub ecosystem: Ecosystem,
+    /// Manifest path the dependency was declared in
+    pub manifest: String,
+    /// Development/test-only dependency
+    pub dev: bool,
+}
+
+impl Dependency {
+    fn new(name: &str, version: Option<&str>, ecosystem: Ecosystem, manifest: &str, dev: bool) -> Self {
+        let version = version.map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+        Self { name: name.trim().to_string(), version, ecosystem, manifest: manifest.to_string(), dev }
+    }
+
+    /// Check whether an imported module (as returned by [`imported_modules`])
+    /// refers to this dependency
+    pub fn is_imported_as(&self, module: &str) -> bool {
+        match self.ecosystem {
+            Ecosystem::Cargo => module == self.name.replace('-', "_"),
+            Ecosystem::Python => module.to_lowercase() == self.name.to_lowercase().replace('-', "_"),
+            Ecosystem::Npm | Ecosystem::Ruby => module == self.name,
+            Ecosystem::Go => module == self.name || module.starts_with(&format!("{}/", self.name)),
+            Ecosystem::Maven => {
+                let group = self.name.split(':').next().unwrap_or(&self.name);
+                module == group || module.starts_with(&format!("{}.", group))
+            }
+        }
+    }
+}
+
+/// Parse the direct dependencies of a manifest (`None` if `path` is not one)
+pub fn parse_manifest(path: &str, content: &str) -> Option<Vec<Dependency>> {
+    let ecosystem = Ecosystem::of_manifest(path)?;
+    let name = path.rsplit('/').next().unwrap_or(path);
+    Some(match name {
+        "Cargo.toml" => parse_cargo(path, content),
+        "package.json" => parse_package_json(path, content),
+        "pyproject.toml" => parse_pyproject(path, content),
+        "go.mod" => parse_go_mod(path, content),
+        "pom.xml" => parse_pom(path, content),
+        "Gemfile" => parse_gemfile(path, content),
+        _ if ecosystem == Ecosystem::Maven => parse_gradle(path, content),
+        _ => content
+            .lines()
+            .filter_map(|line| requirement(line).map(|(n, v)| Dependency::new(&n, v.as_deref(), ecosystem, path, false)))
+            .collect(),
+    })
+}
+
+/// Top-level modules imported by a source file, in the form
+/// [`Dependency::is_imported_as`] expects
+pub fn imported_modules(path: &str, content: &str) -> BTreeSet<String> {
+    let extension = path.rsplit_once('.').map_or("", |(_, ext)| ext);
+    let mut modules = BTreeSet::new();
+    for line in content.lines() {
+        match extension {
+            "rs" => {
+                for re in [&*RUST_IMPORT, &*RUST_ATTRIBUTE] {
+                    if let Some(caps) = re.captures(line) {
+                        modules.insert(caps[1].to_string());
+                    }
+                }
+            }
+            "py" | "pyw" | "pyi" => {
+                if let Some(caps) = PYTHON_IMPORT.captures(line) {
+                    let module = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+                    if !module.starts_with('.') {
+                        modules.insert(module.split('.').next().unwrap_or(module).to_string());
+                    }
+                }
+            }
+            "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" | "vue" | "svelte" => {
+                for caps in JS_IMPORT.captures_iter(line) {
+                    let module = &caps[1];
+                    if module.starts_with('.') || module.starts_with('/') {
+                        continue;
+                    }
+                    // Package name: `react`, `lodash/fp` → `lodash`, `@scope/pkg/x` → `@scope/pkg`
+                    let segments = if module.starts_with('@') { 2 } else { 1 };
+                    modules.insert(module.split('/').take(segments).collect::<Vec<_>>().join("/"));
+                }
+            }
+            "go" => {
+                if let Some(caps) = GO_IMPORT.captures(line) {
+                    modules.insert(caps[1].to_string());
+                }
+            }
+            "java" | "kt" | "kts" | "scala" => {
+                if let Some(caps) = JAVA_IMPORT.captures(line) {
+                    modules.insert(caps[1].to_string());
+                }
+            }
+            "rb" => {
+                if let Some(caps) = RUBY_IMPORT.captures(line) {
+                    modules.insert(caps[1].split('/').next().unwrap_or("").to_string());
+                }
+            }
+            _ => return modules,
+        }
+    }
+    modules
+}
+
+/// Direct dependencies of a project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DependencyModel {
+    /// Dependencies in manifest path order, then declaration order
+    pub dependencies: Vec<Dependency>,
+}
+
+impl DependencyModel {
+    /// Collect the dependencies of every manifest among `(path, content)` pairs
+    pub fn from_files(files: &[(String, String)]) -> Self {
+        let mut manifests: Vec<&(String, String)> =
+            files.iter().filter(|(path, _)| Ecosystem::of_manifest(path).is_some()).collect();
+        manifests.sort_by(|a, b| a.0.cmp(&b.0));
+        let dependencies = manifests
+            .into_iter()
+            .filter_map(|(path, content)| parse_manifest(path, content))
+            .flatten()
+            .collect();
+        Self { dependencies }
+    }
+
+    /// Dependencies with a name (one per declaring manifest)
+    pub fn get(&self, name: &str) -> Vec<&Dependency> {
+        self.dependencies.iter().filter(|d| d.name.eq_ignore_ascii_case(name)).collect()
+    }
+
+    /// Check whether any manifest declares `name`
+    pub fn contains(&self, name: &str) -> bool {
+        !self.get(name).is_empty()
+    }
+
+    /// Distinct dependency names
+    pub fn names(&self) -> BTreeSet<&str> {
+        self.dependencies.iter().map(|d| d.name.as_str()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.dependencies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dependencies.is_empty()
+    }
+
+    /// Source files whose imports refer to dependency `name`
+    pub fn users_of(&self, name: &str, files: &[(String, String)]) -> Vec<String> {
+        let declared = self.get(name);
+        if declared.is_empty() {
+            return Vec::new();
+        }
+        files
+            .iter()
+            .filter(|(path, content)| {
+                imported_modules(path, content)
+                    .iter()
+                    .any(|module| declared.iter().any(|d| d.is_imported_as(module)))
+            })
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    /// Users of every dependency (dependencies nobody imports map to no files)
+    pub fn usage(&self, files: &[(String, String)]) -> BTreeMap<String, Vec<String>> {
+        let imports: Vec<(&String, BTreeSet<String>)> =
+            files.iter().map(|(path, content)| (path, imported_modules(path, content))).collect();
+        let mut usage: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for dependency in &self.dependencies {
+            let users = usage.entry(dependency.name.clone()).or_default();
+            for (path, modules) in &imports {
+                if modules.iter().any(|m| dependency.is_imported_as(m)) && !users.contains(path) {
+                    users.push((*path).clone());
+                }
+            }
+        }
+        usage
+    }
+
+    /// The `## Dependencies` section of instruction files
+    pub fn render_markdown(&self) -> String {
+        let mut out = String::from("## Dependencies\n\n");
+        let mut by_manifest: BTreeMap<&str, Vec<&Dependency>> = BTreeMap::new();
+        for dependency in &self.dependencies {
+            by_manifest.entry(&dependency.manifest).or_default().push(dependency);
+        }
+        for (manifest, dependencies) in by_manifest {
+            out.push_str(&format!("**{}** ({}):\n", manifest, dependencies[0].ecosystem));
+            for dependency in dependencies.iter().take(MAX_LISTED) {
+                out.push_str(&format!("- `{}`", dependency.name));
+                if let Some(version) = &dependency.version {
+                    out.push_str(&format!(" {}", version));
+                }
+                if dependency.dev {
+                    out.push_str(" (dev)");
+                }
+                out.push('\n');
+            }
+            if dependencies.len() > MAX_LISTED {
+                out.push_str(&format!("- ... and {} more\n", dependencies.len() - MAX_LISTED));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Strip quotes from a TOML string value
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches(|c| c == '"' || c == '\'')
+}
+
+/// Value of `key = "..."` inside an inline TOML table
+fn inline_field<'a>(table: &'a str, key: &str) -> Option<&'a str> {
+    table
+        .trim()
+        .trim_matches(|c| c == '{' || c == '}')
+        .split(',')
+        .filter_map(|field| field.split_once('='))
+        .find(|(k, _)| k.trim() == key)
+        .map(|(_, v)| unquote(v))
+}
+
+/// `name = "1.0"`, `name = { version = "1.0" }` or `name.workspace = true`
+fn toml_dependency(line: &str) -> Option<(String, Option<String>)> {
+    let (key, value) = line.split_once('=')?;
+    let key = unquote(key);
+    if key.is_empty() || key.starts_with('#') {
+        return None;
+    }
+    let (name, version) = match key.split_once('.') {
+        Some((name, _)) => (name, None),
+        None if value.trim().starts_with('{') => (key, inline_field(value, "version")),
+        None => (key, Some(unquote(value))),
+    };
+    Some((name.to_string(), version.map(str::to_string)))
+}
+
+fn parse_cargo(path: &str, content: &str) -> Vec<Dependency> {
+    let mut deps: Vec<Dependency> = Vec::new();
+    // (in a dependency table, dev) and the open `[dependencies.NAME]` table
+    let mut table: Option<bool> = None;
+    let mut named: Option<usize> = None;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            let section = line.trim_matches(|c| c == '[' || c == ']');
+            let dev = section.contains("dev-dependencies");
+            named = None;
+            table = None;
+            if let Some((_, name)) = section.split_once("dependencies.") {
+                named = Some(deps.len());
+                deps.push(Dependency::new(unquote(name), None, Ecosystem::Cargo, path, dev));
+            } else if section.ends_with("dependencies") && !section.starts_with("workspace") {
+                table = Some(dev);
+            }
+        } else if let Some(index) = named {
+            if let Some(("version", value)) = line.split_once('=').map(|(k, v)| (k.trim(), v)) {
+                deps[index].version = Some(unquote(value).to_string());
+            }
+        } else if let Some(dev) = table {
+            if let Some((name, version)) = toml_dependency(line) {
+                deps.push(Dependency::new(&name, version.as_deref(), Ecosystem::Cargo, path, dev));
+            }
+        }
+    }
+    deps
+}
+
+fn parse_package_json(path: &str, content: &str) -> Vec<Dependency> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    [("dependencies", false), ("peerDependencies", false), ("devDependencies", true)]
+        .iter()
+        .filter_map(|(key, dev)| json.get(key).and_then(|v| v.as_object()).map(|deps| (deps, *dev)))
+        .flat_map(|(deps, dev)| {
+            deps.iter()
+                .map(move |(name, version)| Dependency::new(name, version.as_str(), Ecosystem::Npm, path, dev))
+        })
+        .collect()
+}
+
+/// Name and version specifier of a PEP 508 requirement (`Django>=4.2 ; python_version...`)
+fn requirement(spec: &str) -> Option<(String, Option<String>)> {
+    let spec = spec.split(';').next().unwrap_or("").split('#').next().unwrap_or("").trim();
+    if spec.is_empty() || spec.starts_with('-') {
+        return None;
+    }
+    let end = spec
+        .find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(spec.len());
+    if end == 0 {
+        return None;
+    }
+    // Skip extras: `uvicorn[standard]>=0.20`
+    let rest = spec[end..].trim_start();
+    let rest = rest.strip_prefix('[').map_or(rest, |r| r.split_once(']').map_or("", |(_, v)| v));
+    Some((spec[..end].to_string(), Some(rest.trim().to_string()).filter(|v| !v.is_empty())))
+}
+
+fn parse_pyproject(path: &str, content: &str) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let mut section = String::new();
+    // Dev flag of the open PEP 621 `dependencies = [...]` array
+    let mut array: Option<bool> = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(dev) = array {
+            for quoted in line.split('"').skip(1).step_by(2) {
+                if let Some((name, version)) = requirement(quoted) {
+                    deps.push(Dependency::new(&name, version.as_deref(), Ecosystem::Python, path, dev));
+                }
+            }
+            if line.contains(']') {
+                array = None;
+            }
+            continue;
+        }
+        if line.starts_with('[') {
+            section = line.trim_matches(|c| c == '[' || c == ']').to_string();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let key = unquote(key);
+        let optional = section == "project.optional-dependencies" || section == "dependency-groups";
+        if (section == "project" && key == "dependencies") || optional {
+            if value.contains('[') {
+                array = Some(optional);
+                // Entries on the opening line: `dependencies = ["a", "b"]`
+                for quoted in value.split('"').skip(1).step_by(2) {
+                    if let Some((name, version)) = requirement(quoted) {
+                        deps.push(Dependency::new(&name, version.as_deref(), Ecosystem::Python, path, optional));
+                    }
+                }
+                if value.contains(']') {
+                    array = None;
+                }
+            }
+        } else if section.starts_with("tool.poetry") && section.ends_with("dependencies") && key != "python" {
+            let dev = section != "tool.poetry.dependencies";
+            if let Some((name, version)) = toml_dependency(line) {
+                deps.push(Dependency::new(&name, version.as_deref(), Ecosystem::Python, path, dev));
+            }
+        }
+    }
+    deps
+}
+
+fn parse_go_mod(path: &str, content: &str) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let mut in_block = false;
+    for line in content.lines().map(str::trim) {
+        let spec = if in_block {
+            if line.starts_with(')') {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if let Some(rest) = line.strip_prefix("require") {
+            let rest = rest.trim();
+            if rest.starts_with('(') {
+                in_block = true;
+                continue;
+            }
+            rest
+        } else {
+            continue;
+        };
+        if spec.is_empty() || spec.starts_with("//") || spec.contains("// indirect") {
+            continue;
+        }
+        let mut parts = spec.split_whitespace();
+        if let Some(module) = parts.next() {
+            deps.push(Dependency::new(module, parts.next(), Ecosystem::Go, path, false));
+        }
+    }
+    deps
+}
+
+/// Text of `<tag>...</tag>` in an XML fragment
+fn xml_tag<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = start + xml[start..].find(&format!("</{}>", tag))?;
+    Some(xml[start..end].trim())
+}
+
+fn parse_pom(path: &str, content: &str) -> Vec<Dependency> {
+    let content = POM_MANAGEMENT.replace_all(content, "");
+    POM_DEPENDENCY
+        .captures_iter(&content)
+        .filter_map(|caps| {
+            let block = caps.get(1)?.as_str();
+            let name = format!("{}:{}", xml_tag(block, "groupId")?, xml_tag(block, "artifactId")?);
+            let dev = xml_tag(block, "scope") == Some("test");
+            Some(Dependency::new(&name, xml_tag(block, "version"), Ecosystem::Maven, path, dev))
+        })
+        .collect()
+}
+
+fn parse_gradle(path: &str, content: &str) -> Vec<Dependency> {
+    content
+        .lines()
+        .filter_map(|line| GRADLE_DEPENDENCY.captures(line))
+        .filter(|caps| caps[1].ends_with("mplementation") || caps[1].ends_with("Only") || &caps[1] == "api")
+        .map(|caps| {
+            let name = format!("{}:{}", &caps[2], &caps[3]);
+            let dev = caps[1].starts_with("test");
+            Dependency::new(&name, caps.get(4).map(|m| m.as_str()), Ecosystem::Maven, path, dev)
+        })
+        .collect()
+}
+
+fn parse_gemfile(path: &str, content: &str) -> Vec<Dependency> {
+    let mut deps = Vec::new();
+    let mut dev_group = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with("group ") && line.ends_with(" do") {
+            dev_group = line.contains(":development") || line.contains(":test");
+        } else if line == "end" {
+            dev_group = false;
+        } else if let Some(caps) = GEM.captures(line) {
+            deps.push(Dependency::new(&caps[1], caps.get(2).map(|m| m.as_str()), Ecosystem::Ruby, path, dev_group));
+        }
+    }
+    deps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> (String, String) {
+        (path.to_string(), content.to_string())
+    }
+
+    fn names(deps: &[Dependency]) -> Vec<(&str, Option<&str>, bool)> {
+        deps.iter().map(|d| (d.name.as_str(), d.version.as_deref(), d.dev)).collect()
+    }
+
+    #[test]
+    fn test_parse_cargo_and_package_json() {
+        let cargo = "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\naxum = \"0.7\"\nserde = { version = \"1.0\", features = [\"derive\"] }\nlocal = { path = \"../local\" }\nclap.workspace = true\n\n[dev-dependencies]\ntempfile = \"3\"\n\n[dependencies.tokio]\nversion = \"1\"\nfeatures = [\"full\"]\n";
+        let deps = parse_manifest("Cargo.toml", cargo).unwrap();
+        assert_eq!(
+            names(&deps),
+            vec![
+                ("axum", Some("0.7"), false),
+                ("serde", Some("1.0"), false),
+                ("local", None, false),
+                ("clap", None, false),
+                ("tempfile", Some("3"), true),
+                ("tokio", Some("1"), false),
+            ]
+        );
+
+        let json = "{\"dependencies\": {\"react\": \"^18.2.0\"}, \"devDependencies\": {\"jest\": \"29\"}}";
+        let deps = parse_manifest("web/package.json", json).unwrap();
+        assert_eq!(names(&deps), vec![("react", Some("^18.2.0"), false), ("jest", Some("29"), true)]);
+        assert!(deps.iter().all(|d| d.ecosystem == Ecosystem::Npm && d.manifest == "web/package.json"));
+        assert!(parse_manifest("src/main.rs", "").is_none());
+    }
+
+    #[test]
+    fn test_parse_python_go_and_maven() {
+        let requirements = "# web\nDjango>=4.2 ; python_version > '3.8'\nuvicorn[standard]==0.29\n-r base.txt\nrequests\n";
+        assert_eq!(
+            names(&parse_manifest("requirements.txt", requirements).unwrap()),
+            vec![("Django", Some(">=4.2"), false), ("uvicorn", Some("==0.29"), false), ("requests", None, false)]
+        );
+
+        let pyproject = "[project]\nname = \"svc\"\ndependencies = [\n  \"fastapi>=0.100\",\n  \"pydantic\",\n]\n\n[project.optional-dependencies]\ntest = [\"pytest>=8\"]\n\n[tool.poetry.dependencies]\npython = \"^3.11\"\nflask = \"^3.0\"\n";
+        assert_eq!(
+            names(&parse_manifest("pyproject.toml", pyproject).unwrap()),
+            vec![
+                ("fastapi", Some(">=0.100"), false),
+                ("pydantic", None, false),
+                ("pytest", Some(">=8"), true),
+                ("flask", Some("^3.0"), false),
+            ]
+        );
+
+        let go_mod = "module example.com/app\n\ngo 1.22\n\nrequire github.com/gin-gonic/gin v1.9.1\n\nrequire (\n\tgolang.org/x/text v0.14.0\n\tgithub.com/x/indirect v1.0.0 // indirect\n)\n";
+        assert_eq!(
+            names(&parse_manifest("go.mod", go_mod).unwrap()),
+            vec![("github.com/gin-gonic/gin", Some("v1.9.1"), false), ("golang.org/x/text", Some("v0.14.0"), false)]
+        );
+
+        let pom = "<project><dependencyManagement><dependencies><dependency><groupId>managed</groupId><artifactId>bom</artifactId></dependency></dependencies></dependencyManagement>\
+                   <dependencies><dependency><groupId>org.springframework.boot</groupId><artifactId>spring-boot-starter-web</artifactId><version>3.2.0</version></dependency>\
+                   <dependency><groupId>junit</groupId><artifactId>junit</artifactId><scope>test</scope></dependency></dependencies></project>";
+        assert_eq!(
+            names(&parse_manifest("pom.xml", pom).unwrap()),
+            vec![("org.springframework.boot:spring-boot-starter-web", Some("3.2.0"), false), ("junit:junit", None, true)]
+        );
+    }
+
+    #[test]
+    fn test_users_of_joins_imports() {
+        let files = vec![
+            file("Cargo.toml", "[dependencies]\nserde-json = \"1\"\ntokio = \"1\"\n"),
+            file("src/main.rs", "use serde_json::Value;\n\n#[tokio::main]\nasync fn main() {}\n"),
+            file("src/lib.rs", "pub use crate::model;\n"),
+            file("web/package.json", "{\"dependencies\": {\"@tanstack/query\": \"5\"}}"),
+            file("web/app.ts", "import { useQuery } from '@tanstack/query/react';\nimport './local';\n"),
+        ];
+        let model = DependencyModel::from_files(&files);
+        assert_eq!(model.len(), 3);
+        assert!(model.contains("tokio"));
+
+        assert_eq!(model.users_of("serde-json", &files), vec!["src/main.rs"]);
+        assert_eq!(model.users_of("tokio", &files), vec!["src/main.rs"]);
+        assert_eq!(model.users_of("@tanstack/query", &files), vec!["web/app.ts"]);
+        assert!(model.users_of("missing", &files).is_empty());
+        assert_eq!(model.usage(&files)["tokio"], vec!["src/main.rs"]);
+
+        let markdown = model.render_markdown();
+        assert!(markdown.starts_with("## Dependencies\n\n**Cargo.toml** (cargo):\n- `serde-json` 1\n"));
+        assert!(markdown.contains("**web/package.json** (npm):\n- `@tanstack/query` 5\n"));
+    }
+}
@@ -240,7 +240,7 @@ mod tests {
 
     fn make_test_file() -> File {
         File {
-            path: "test.rs".to_string(),
+            path: "test.rs".to_string().into(),
             language: LanguageId::Rust,
             declarations: vec![
                 Declaration::new("foo".to_string(), DeclarationKind::Function, Span::default()),
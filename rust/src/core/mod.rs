@@ -17,10 +17,58 @@
 //! - `plugin`: Plugin ecosystem reservation (Phase 2)
 //! - `ast_bridge`: Bridge to voyager-ast structural optics
 //! - `metrics`: AST-based code metrics collection (Phase 3 foundation)
+//! - `tour`: Guided reading path for onboarding (entry points → types → flows)
+//! - `summarizer`: Pluggable summarization of over-budget symbol bodies
+//! - `window`: AST-aware sliding-window body truncation
+//! - `preview`: Dry-run report of what a lens/budget would include
+//! - `bundle`: `.pmctx` context snapshots (context + manifest + instructions)
+//! - `cancel`: Cooperative cancellation of long-running operations
+//! - `blobstore`: Content-addressed store shared by the index and context caches
+//! - `codec`: zstd/gzip compression for caches and outputs
+//! - `langstats`: Per-language file, line, symbol and comment statistics
+//! - `heatmap`: Per-directory symbol/size/complexity totals and budget shares
+//! - `focus`: Focus hints from issue trackers that bias lens choice and budget
+//! - `changes`: git diffs mapped to added/modified/removed declarations
+//! - `review`: PR-review pack (checklist, changed bodies, callers, diff)
+//! - `commitpack`: Symbol-level summary of staged changes for commit messages
+//! - `outline`: Markdown outlines at heading depth and section zoom
+//! - `doclinks`: Backtick mentions linking doc sections and docstrings to symbols
+//! - `flaky`: Flaky tests from JUnit CI history, traced to the symbols they exercise
+//! - `selfcheck`: Re-reads serialized context to flag truncations that broke snippets
+//! - `affordances`: Machine-readable index of the zoom affordances in a context
+//! - `lod`: Levels of detail (file list → declarations → signatures → full) per subtree
+//! - `sample`: Deterministic, seeded sampling of a representative project slice
+//! - `profile`: Language breakdown, framework detection and architecture hints
+//! - `manifests`: Direct dependencies from Cargo/npm/Python/Go/Maven manifests, joined with imports
+//! - `advisories`: OSV advisory matching for dependencies (offline database or opt-in API)
+//! - `git_walker`: FileWalker over a commit, the index or the tracked worktree (GitView)
+//! - `sandbox`: Confines user-supplied paths to a root (traversal, absolute, symlink escapes)
+//! - `boilerplate`: Collapses license headers, import preambles and generated banners into markers
+//! - `imports`: Per-file import rollups (std / internal / external) in place of import blocks
+//! - `apiref`: API reference document (public declarations by module, signatures and doc summaries)
+//! - `ledger`: Per-session content ledger that elides content a conversation has already seen
+//! - `unknowns`: Regions the parsers could not interpret, grouped by language and reason
+//! - `textformat`: Original line endings and BOM of files, with offset translation
+//! - `span_map`: Conversion between byte, char and UTF-16 offsets and line/column positions
+//! - `symbol_rules`: Declaration-level lens rules (exclude tests, keep public structs, signature-only)
+//! - `toc`: Table of contents of included files and declarations, with citation anchors
+//! - `anchor`: Stable declaration ids (`path#Outer.inner@fingerprint`) shared by ToC, API reference and zoom
+//! - `comparison`: Token cost and symbol retention of every output format across budgets
+//! - `questions`: Evaluation question packs (definitions, callers, return types) with ground truth
+//! - `snippet`: One symbol with its signature type closure and imports as a standalone snippet
+//! - `scaffold`: Empty test files in each language's harness, one test per public function
+//! - `todos`: TODO/FIXME comments with symbol and blame age, exported as issue-ready Markdown or CSV
+//! - `provenance`: Generated and vendored file detection, with notes pointing at the real source
+//! - `replay`: Step-by-step replay of a persisted zoom session, with the context each step served
+//! - `preflight`: Warnings for budgets, lenses, include patterns and zoom targets that leave the context empty
+//! - `bookmarks`: Named zoom targets saved per project, usable as `bookmark=name`
+//! - `investigation`: Zooms, search hits and notes of a debugging session, rendered as one report
+//! - `json_store`: Location, load and save shared by the JSON stores under `.pm_encoder/`
 
 pub mod models;
 pub mod error;
 pub mod walker;
+pub mod paths;
 pub mod manifest;
 pub mod serialization;
 pub mod engine;
@@ -41,15 +89,108 @@ pub mod regex_engine;
 pub mod census;
 pub mod temporal;
 pub mod spectrograph;
+pub mod tour;
+pub mod summarizer;
+pub mod window;
+pub mod preview;
+pub mod bench;
+pub mod bundle;
+pub mod cancel;
+pub mod blobstore;
+pub mod codec;
+pub mod langstats;
+pub mod heatmap;
+pub mod focus;
+pub mod changes;
+pub mod review;
+pub mod commitpack;
+pub mod outline;
+pub mod doclinks;
+pub mod flaky;
+pub mod selfcheck;
+pub mod affordances;
+pub mod lod;
+pub mod sample;
+pub mod profile;
+pub mod manifests;
+pub mod advisories;
+pub mod git_walker;
+pub mod sandbox;
+pub mod boilerplate;
+pub mod imports;
+pub mod apiref;
+pub mod ledger;
+pub mod unknowns;
+pub mod textformat;
+pub mod span_map;
+pub mod symbol_rules;
+pub mod toc;
+pub mod anchor;
+pub mod comparison;
+pub mod questions;
+pub mod snippet;
+pub mod scaffold;
+pub mod todos;
+pub mod replay;
+pub mod provenance;
+pub mod preflight;
+pub mod bookmarks;
+pub mod investigation;
+pub mod json_store;
 
 // Re-export commonly used types
-pub use models::{FileEntry, EncoderConfig, ProcessedFile, OutputFormat, Config, SkeletonMode, CompressionLevel};
-pub use error::{EncoderError, Result};
+pub use models::{FileEntry, EncoderConfig, ProcessedFile, OutputFormat, Config, SkeletonMode, CompressionLevel, LanguageTruncation, SymbolAction, SymbolRule};
+pub use error::{EncoderError, ErrorReport, FailureKind, Result};
+pub use cancel::CancellationToken;
+pub use blobstore::{BlobId, BlobStore, GcPolicy, GcReport};
+pub use codec::Codec;
+pub use langstats::{LanguageStatsReport, LanguageSummary};
+pub use heatmap::{DirectoryHeat, Heatmap};
+pub use focus::{FocusHints, FocusPrioritizer, FocusProvider};
+pub use changes::{ChangeSet, DiffSource, FileDiff, SymbolChange, SymbolChangeKind};
+pub use review::{ReviewConfig, ReviewPack};
+pub use commitpack::{CommitPack, CommitType};
+pub use outline::SectionLocation;
+pub use doclinks::{DocLinks, DocMention, DocSource};
+pub use flaky::{CiHistory, FlakyPrioritizer, FlakyReport};
+pub use selfcheck::{QualityReport, SnippetIssue};
+pub use affordances::{Affordance, AffordanceIndex};
+pub use lod::{Lod, LodPlan};
+pub use sample::{Sample, SampleReason, SampledFile, SAMPLE_FILE_NAME};
+pub use profile::{Framework, LanguageShare, ProjectProfile};
+pub use manifests::{Dependency, DependencyModel, Ecosystem};
+pub use advisories::{AdvisoryDb, AdvisoryPrioritizer, AdvisoryReport, OsvAdvisory};
 pub use walker::{FileWalker, DefaultWalker, SmartWalker, SmartWalkConfig, WalkEntry};
+pub use git_walker::GitView;
+pub use sandbox::PathSandbox;
+pub use boilerplate::{BoilerplateExtension, BoilerplateRules, BoilerplateStripper};
+pub use imports::{ImportRollup, ImportRollups, LocalModules};
+pub use apiref::{ApiItem, ApiModule, ApiReference};
+pub use ledger::{ContentLedger, SeenContent};
+pub use unknowns::{UnknownGroup, UnknownRegion, UnknownsReport};
+pub use textformat::{LineEnding, TextFormat};
+pub use span_map::{LineCol, SpanMap, Unit};
+pub use toc::{TableOfContents, TocFile, TocSymbol, TOC_FILE_NAME};
+pub use anchor::Anchor;
+pub use comparison::{ComparisonRow, FormatComparison, Retention, COMPARED_FORMATS, DEFAULT_COMPARISON_BUDGETS};
+pub use questions::{Question, QuestionKind, QuestionPack, DEFAULT_QUESTIONS_PER_KIND};
+pub use snippet::Snippet;
+pub use scaffold::{scaffold_tests, TestScaffold};
+pub use todos::{TodoItem, TodoList, TODO_MARKERS};
+pub use replay::{ReplayStep, SessionReplay};
+pub use provenance::{Provenance, ProvenanceKind};
+pub use preflight::{ConfigWarning, WARNINGS_FILE_NAME};
+pub use bookmarks::{Bookmark, BookmarkPrioritizer, BookmarkStore, ResolvedBookmark};
+pub use investigation::{Investigation, InvestigationEntry, InvestigationStore, SearchHit};
+#[cfg(feature = "temporal")]
+pub use git_walker::GitWalker;
+pub use paths::{normalize_relative_path, relative_key, ProjectPaths};
 pub use manifest::{ProjectManifest, ProjectType};
 pub use engine::{ContextEngine, FileTier, BudgetStats};
 pub use zoom::{
     ZoomAction, ZoomTarget, ZoomConfig, ZoomDepth,
+    // Batch zoom
+    BatchZoomConfig, PackingStrategy,
     // Fractal Protocol v2
     ZoomDirection, ZoomHistory, ZoomHistoryEntry,
     ZoomSession, ZoomSessionStore,
@@ -122,6 +263,30 @@ pub use census::{
     PatternFallbackAnalyzer,
 };
 
+// Guided Tour (onboarding reading path)
+pub use tour::{tour_file, FileSignals, Tour, TourGenerator, TourSection, TourStop, TOUR_FILE_NAME, TOUR_LENS};
+
+// Summarization Hooks (per-symbol budget compression)
+pub use summarizer::{
+    Summarizer, SummaryRequest, NoopSummarizer, ExtractiveSummarizer,
+    SummarizedContent, SummarizedSymbol, summarize_symbols,
+};
+
+// Declaration-Level Lens Rules
+pub use symbol_rules::{apply_symbol_rules, FilteredContent};
+
+// Sliding-Window Truncation (head + error branches + tail)
+pub use window::{WindowConfig, WindowResult, WindowTruncator};
+
+// Context Preview (dry run of lens + budget)
+pub use preview::{ContextPreview, FileAction, PreviewEntry};
+
+// Built-in benchmark suite (vo --bench)
+pub use bench::{run_suite, BenchCase, BenchConfig, BenchRegression, BenchReport, SyntheticCorpus};
+
+// Context bundles (vo --bundle / --import-bundle)
+pub use bundle::{ContextBundle, BundleFile, BundleManifest, BundleInstructions, BUNDLE_EXTENSION};
+
 // Universal Spectrograph (80+ Language Patterns)
 pub use spectrograph::{
     StellarLibrary, SpectralSignature, Hemisphere, STELLAR_LIBRARY,
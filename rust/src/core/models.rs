@@ -3,12 +3,17 @@
 //! This module contains the fundamental data structures used throughout the encoder.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
+use voyager_ast::RawPath;
+
+use crate::core::provenance::Provenance;
+use crate::core::textformat::TextFormat;
 
 /// A file entry with its content and metadata
 #[derive(Debug, Clone)]
 pub struct FileEntry {
-    /// Relative path to the file
+    /// Relative path key (see [`RawPath::to_key`]); lossless for non-UTF-8 names
     pub path: String,
     /// File content as string
     pub content: String,
@@ -20,6 +25,8 @@ pub struct FileEntry {
     pub ctime: u64,
     /// File size in bytes
     pub size: u64,
+    /// Line endings and BOM of the file on disk (see [`TextFormat`])
+    pub format: TextFormat,
 }
 
 impl FileEntry {
@@ -35,6 +42,7 @@ impl FileEntry {
             mtime: 0,
             ctime: 0,
             size,
+            format: TextFormat::default(),
         }
     }
 
@@ -45,6 +53,12 @@ impl FileEntry {
         self
     }
 
+    /// Create a FileEntry with the original text format of its content
+    pub fn with_format(mut self, format: TextFormat) -> Self {
+        self.format = format;
+        self
+    }
+
     /// Create a FileEntry with size (overrides content-based size)
     pub fn with_size(mut self, size: u64) -> Self {
         self.size = size;
@@ -56,6 +70,11 @@ impl FileEntry {
         Path::new(&self.path).extension().and_then(|e| e.to_str())
     }
 
+    /// Decode the path key back to the original OS path
+    pub fn raw_path(&self) -> RawPath {
+        RawPath::from_key(&self.path)
+    }
+
     /// Estimate token count (~4 chars per token)
     pub fn token_estimate(&self) -> usize {
         self.content.len() / 4
@@ -128,7 +147,7 @@ pub struct EncoderConfig {
     pub max_file_size: u64,
     /// Maximum lines before truncation (0 = no limit)
     pub truncate_lines: usize,
-    /// Truncation mode: "simple", "smart", or "structure"
+    /// Truncation mode: "simple", "smart", "structure", or "window"
     pub truncate_mode: String,
     /// Sort field: "name", "mtime", or "ctime"
     pub sort_by: String,
@@ -161,6 +180,167 @@ pub struct EncoderConfig {
     pub metadata_mode: MetadataMode,
     /// Follow symbolic links (default: false, skip broken symlinks silently)
     pub follow_symlinks: bool,
+    /// Per-symbol token budget: function bodies above it are summarized
+    pub symbol_budget: Option<usize>,
+    /// Per-language truncation overrides, keyed by language name ("python", "sql", ...)
+    pub language_rules: HashMap<String, LanguageTruncation>,
+    /// Declaration-level rules, applied after those of the active lens
+    pub symbol_rules: Vec<SymbolRule>,
+    /// Open the context with a table of contents (files, declarations, anchors)
+    pub table_of_contents: bool,
+    /// Mark generated and vendored files with where to edit instead
+    pub provenance_annotations: bool,
+    /// Match ignore/include patterns and zoom paths case-insensitively
+    /// (defaults to true on Windows)
+    pub case_insensitive_paths: bool,
+}
+
+/// Truncation override for one language
+///
+/// Unset fields inherit the global `truncate_lines`/`truncate_mode`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LanguageTruncation {
+    /// Truncation mode override ("simple", "smart", "structure", "window")
+    #[serde(default)]
+    pub truncate_mode: Option<String>,
+    /// Line limit override (0 = no truncation)
+    #[serde(default)]
+    pub truncate: Option<usize>,
+    /// Never truncate files of this language (e.g., SQL migrations, TOML)
+    #[serde(default)]
+    pub never_truncate: bool,
+    /// Never cut a docstring/doc comment block mid-way
+    #[serde(default)]
+    pub keep_docstrings: bool,
+}
+
+impl LanguageTruncation {
+    /// Rule that disables truncation for the language
+    pub fn never() -> Self {
+        Self {
+            never_truncate: true,
+            ..Default::default()
+        }
+    }
+
+    /// Rule with an explicit line limit
+    pub fn lines(limit: usize) -> Self {
+        Self {
+            truncate: Some(limit),
+            ..Default::default()
+        }
+    }
+
+    /// Builder pattern: set truncation mode
+    pub fn with_mode(mut self, mode: &str) -> Self {
+        self.truncate_mode = Some(mode.to_string());
+        self
+    }
+
+    /// Builder pattern: keep docstrings intact
+    pub fn with_keep_docstrings(mut self, keep: bool) -> Self {
+        self.keep_docstrings = keep;
+        self
+    }
+
+    /// Resolve the effective `(lines, mode)` given global settings
+    ///
+    /// Returns `(0, _)` when the language must not be truncated.
+    pub fn resolve<'a>(&'a self, lines: usize, mode: &'a str) -> (usize, &'a str) {
+        if self.never_truncate {
+            return (0, "simple");
+        }
+        (
+            self.truncate.unwrap_or(lines),
+            self.truncate_mode.as_deref().unwrap_or(mode),
+        )
+    }
+}
+
+/// What a [`SymbolRule`] does with the declarations it matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolAction {
+    /// Keep the declaration as is (shields it from later rules)
+    Include,
+    /// Drop the declaration
+    Exclude,
+    /// Keep the signature, replace the body with a zoom marker
+    Signature,
+}
+
+/// Declaration-level lens rule
+///
+/// All set criteria must hold for a declaration to match. Rules are ordered:
+/// the first one matching a declaration decides, and declarations no rule
+/// matches are kept.
+///
+/// ```json
+/// "symbols": [
+///   { "action": "exclude", "tests": true },
+///   { "action": "include", "path": "models/", "kinds": ["struct"], "visibility": "public" },
+///   { "action": "exclude", "path": "models/", "kinds": ["struct"] },
+///   { "action": "signature", "kinds": ["function", "method"], "min_lines": 400 }
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolRule {
+    pub action: SymbolAction,
+    /// Declaration kinds ("function", "method", "struct", "class", ...; empty = any)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<String>,
+    /// Visibility ("public", "private", "protected", "internal")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+    /// Glob on the declaration name (e.g. "test_*")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Path pattern of the file, with the lens `include`/`exclude` syntax
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Match only test functions (true) or only non-test declarations (false)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tests: Option<bool>,
+    /// Match only declarations spanning at least this many lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_lines: Option<usize>,
+}
+
+impl SymbolRule {
+    /// Rule matching every declaration
+    pub fn new(action: SymbolAction) -> Self {
+        Self { action, kinds: Vec::new(), visibility: None, name: None, path: None, tests: None, min_lines: None }
+    }
+
+    /// Builder pattern: restrict to declaration kinds
+    pub fn with_kinds(mut self, kinds: &[&str]) -> Self {
+        self.kinds = kinds.iter().map(|k| k.to_string()).collect();
+        self
+    }
+
+    /// Builder pattern: restrict to a visibility
+    pub fn with_visibility(mut self, visibility: &str) -> Self {
+        self.visibility = Some(visibility.to_string());
+        self
+    }
+
+    /// Builder pattern: restrict to a path pattern
+    pub fn with_path(mut self, pattern: &str) -> Self {
+        self.path = Some(pattern.to_string());
+        self
+    }
+
+    /// Builder pattern: restrict to test (or non-test) declarations
+    pub fn with_tests(mut self, tests: bool) -> Self {
+        self.tests = Some(tests);
+        self
+    }
+
+    /// Builder pattern: restrict to declarations of at least `lines` lines
+    pub fn with_min_lines(mut self, lines: usize) -> Self {
+        self.min_lines = Some(lines);
+        self
+    }
 }
 
 /// Skeleton mode configuration
@@ -255,6 +435,12 @@ impl Default for EncoderConfig {
             skeleton_mode: SkeletonMode::Auto,
             metadata_mode: MetadataMode::Auto,
             follow_symlinks: false,
+            symbol_budget: None,
+            language_rules: HashMap::new(),
+            symbol_rules: Vec::new(),
+            table_of_contents: false,
+            provenance_annotations: false,
+            case_insensitive_paths: cfg!(windows),
         }
     }
 }
@@ -307,6 +493,42 @@ impl EncoderConfig {
         self.metadata_mode = mode;
         self
     }
+
+    /// Builder pattern: set a truncation rule for one language
+    pub fn with_language_rule(mut self, language: &str, rule: LanguageTruncation) -> Self {
+        self.language_rules.insert(language.to_lowercase(), rule);
+        self
+    }
+
+    /// Builder pattern: open the context with a table of contents
+    pub fn with_table_of_contents(mut self, enabled: bool) -> Self {
+        self.table_of_contents = enabled;
+        self
+    }
+
+    /// Builder pattern: mark generated and vendored files
+    pub fn with_provenance_annotations(mut self, enabled: bool) -> Self {
+        self.provenance_annotations = enabled;
+        self
+    }
+
+    /// Builder pattern: add a declaration-level rule
+    pub fn with_symbol_rule(mut self, rule: SymbolRule) -> Self {
+        self.symbol_rules.push(rule);
+        self
+    }
+
+    /// Builder pattern: set per-symbol token budget for summarization
+    pub fn with_symbol_budget(mut self, budget: usize) -> Self {
+        self.symbol_budget = Some(budget);
+        self
+    }
+
+    /// Builder pattern: set case-insensitive path matching
+    pub fn with_case_insensitive_paths(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive_paths = case_insensitive;
+        self
+    }
 }
 
 /// Compression level for skeleton protocol
@@ -345,6 +567,8 @@ pub struct ProcessedFile {
     /// Utility score from Observer's Journal (0.0-1.0)
     /// Stars with utility >= 0.8 are "bright" and display ⭐
     pub utility: Option<f64>,
+    /// Origin of a generated or vendored file
+    pub provenance: Option<Provenance>,
 }
 
 impl ProcessedFile {
@@ -361,6 +585,7 @@ impl ProcessedFile {
             original_tokens: None,
             compression_level: CompressionLevel::Full,
             utility: None,
+            provenance: None,
         }
     }
 
@@ -423,6 +648,7 @@ impl Default for ProcessedFile {
             original_tokens: None,
             compression_level: CompressionLevel::Full,
             utility: None,
+            provenance: None,
         }
     }
 }
@@ -473,6 +699,19 @@ mod tests {
         assert_eq!(config.active_lens, Some("architecture".to_string()));
     }
 
+    #[test]
+    fn test_language_truncation_resolve() {
+        assert_eq!(LanguageTruncation::default().resolve(100, "smart"), (100, "smart"));
+        assert_eq!(LanguageTruncation::never().resolve(100, "smart"), (0, "simple"));
+        assert_eq!(
+            LanguageTruncation::lines(20).with_mode("window").resolve(100, "smart"),
+            (20, "window")
+        );
+
+        let config = EncoderConfig::new().with_language_rule("SQL", LanguageTruncation::never());
+        assert!(config.language_rules["sql"].never_truncate);
+    }
+
     #[test]
     fn test_processed_file_from_entry() {
         let entry = FileEntry::new("src/main.rs", "fn main() {}");
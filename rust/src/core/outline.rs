@@ -0,0 +1,195 @@
+//! Markdown Outlines - Heading-aware serialization and section zoom
+//!
+//! Markdown headings are extracted as `Section` declarations by voyager-ast,
+//! nested by level. This module builds on them to:
+//!
+//! - **Outline** a document at a given depth: sections deeper than the depth
+//!   are collapsed into a marker carrying a zoom affordance, so docs cost a
+//!   fraction of their size until a section is actually needed
+//! - **Locate** a single section by title or anchor for `--zoom section=...`
+//!
+//! Section targets may be scoped to a document: `README.md#Installation`.
+
+use std::path::Path;
+
+use voyager_ast::adapters::markdown_adapter::anchor;
+use voyager_ast::{Declaration, LanguageId};
+
+use crate::core::ast_bridge::AstBridge;
+use crate::core::zoom::{suggested_budget, ZoomAction};
+
+/// Location of a Markdown section (1-indexed, inclusive lines)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionLocation {
+    /// Heading text
+    pub title: String,
+    /// Heading level (1 for `#`, 2 for `##`, ...)
+    pub level: usize,
+    /// GitHub-style anchor
+    pub anchor: String,
+    /// Line of the heading
+    pub start_line: usize,
+    /// Last line of the heading (differs from `start_line` for setext headings)
+    pub heading_end_line: usize,
+    /// Last line of the section, including nested sections
+    pub end_line: usize,
+}
+
+impl SectionLocation {
+    fn from_declaration(decl: &Declaration) -> Self {
+        Self {
+            title: decl.name.clone(),
+            level: section_level(decl),
+            anchor: decl.metadata.get("anchor").cloned().unwrap_or_else(|| anchor(&decl.name)),
+            start_line: decl.span.start_line,
+            heading_end_line: decl.signature_span.map_or(decl.span.start_line, |s| s.end_line),
+            end_line: decl.span.end_line,
+        }
+    }
+}
+
+/// Check whether a path names a Markdown document
+pub fn is_markdown(path: &str) -> bool {
+    AstBridge::detect_language(Path::new(path)) == LanguageId::Markdown
+}
+
+/// Extract the section tree of a Markdown document
+pub fn sections(content: &str) -> Vec<Declaration> {
+    AstBridge::new()
+        .analyze_file(content, LanguageId::Markdown)
+        .map(|file| file.declarations)
+        .unwrap_or_default()
+}
+
+/// Split a section target into an optional document path and a section name
+///
+/// `README.md#Installation` → `(Some("README.md"), "Installation")`, while a
+/// bare `Installation` or `#installation` has no document.
+pub fn split_section_target(target: &str) -> (Option<&str>, &str) {
+    match target.split_once('#') {
+        Some((path, name)) if !path.is_empty() && is_markdown(path) => (Some(path), name),
+        _ => (None, target),
+    }
+}
+
+/// Find a section by title (case-insensitive) or anchor
+pub fn find_section(content: &str, name: &str) -> Option<SectionLocation> {
+    let wanted = name.trim().trim_start_matches('#').trim();
+    let wanted_anchor = anchor(wanted);
+    let mut found = None;
+    visit_sections(&sections(content), &mut |decl| {
+        let matches = decl.name.eq_ignore_ascii_case(wanted)
+            || decl.metadata.get("anchor").is_some_and(|a| *a == wanted_anchor);
+        if matches && found.is_none() {
+            found = Some(SectionLocation::from_declaration(decl));
+        }
+    });
+    found
+}
+
+/// Render a Markdown document at outline depth
+///
+/// Sections up to `depth` are kept verbatim; each deeper section (with
+/// everything nested below it) is replaced by a one-line marker and a zoom
+/// affordance. Returns `None` when nothing would be collapsed.
+pub fn outline(path: &str, content: &str, depth: usize) -> Option<String> {
+    let mut collapsed = Vec::new();
+    collect_collapsed(&sections(content), depth, &mut collapsed);
+    if collapsed.is_empty() {
+        return None;
+    }
+
+    let lines: Vec<&str> = content.lines().collect();
+    let mut output = Vec::new();
+    let mut line = 1;
+    for section in &collapsed {
+        output.extend(lines[line - 1..section.start_line - 1].iter().map(|l| l.to_string()));
+        let tokens = lines[section.start_line - 1..section.end_line].iter().map(|l| l.len() + 1).sum::<usize>() / 4;
+        let action = ZoomAction::for_section(&format!("{}#{}", path, section.anchor), suggested_budget(tokens));
+        output.push(format!(
+            "[... {} {}: {} lines collapsed [lines {}-{}] ...]",
+            "#".repeat(section.level),
+            section.title,
+            section.end_line - section.start_line + 1,
+            section.start_line,
+            section.end_line,
+        ));
+        output.push(action.to_affordance_comment());
+        line = section.end_line + 1;
+    }
+    if line <= lines.len() {
+        output.extend(lines[line - 1..].iter().map(|l| l.to_string()));
+    }
+
+    let mut result = output.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    Some(result)
+}
+
+/// Collect the outermost sections deeper than `depth`, in document order
+fn collect_collapsed(decls: &[Declaration], depth: usize, out: &mut Vec<SectionLocation>) {
+    for decl in decls {
+        if section_level(decl) > depth {
+            out.push(SectionLocation::from_declaration(decl));
+        } else {
+            collect_collapsed(&decl.children, depth, out);
+        }
+    }
+}
+
+fn visit_sections<'a>(decls: &'a [Declaration], visit: &mut impl FnMut(&'a Declaration)) {
+    for decl in decls {
+        visit(decl);
+        visit_sections(&decl.children, visit);
+    }
+}
+
+fn section_level(decl: &Declaration) -> usize {
+    decl.metadata
+        .get("level")
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const README: &str = "# Project\n\nIntro.\n\n## Installation\n\nRun `make`.\n\n### From source\n\nClone it.\n\n## Usage\n\nSee below.\n";
+
+    #[test]
+    fn test_find_section_by_title_and_anchor() {
+        let section = find_section(README, "installation").unwrap();
+        assert_eq!((section.start_line, section.end_line), (5, 11));
+        assert_eq!(section.level, 2);
+
+        let nested = find_section(README, "#from-source").unwrap();
+        assert_eq!(nested.title, "From source");
+        assert!(find_section(README, "Missing").is_none());
+    }
+
+    #[test]
+    fn test_split_section_target() {
+        assert_eq!(split_section_target("README.md#Installation"), (Some("README.md"), "Installation"));
+        assert_eq!(split_section_target("Installation"), (None, "Installation"));
+        assert_eq!(split_section_target("#usage"), (None, "#usage"));
+    }
+
+    #[test]
+    fn test_outline_collapses_deep_sections() {
+        let rendered = outline("README.md", README, 1).unwrap();
+        assert!(rendered.starts_with("# Project\n\nIntro.\n\n"));
+        assert!(rendered.contains("[... ## Installation: 7 lines collapsed [lines 5-11] ...]"));
+        assert!(rendered.contains("/* ZOOM_AFFORDANCE: pm_encoder --zoom section=README.md#installation"));
+        assert!(rendered.contains("## Usage: 3 lines collapsed [lines 13-15]"));
+        assert!(!rendered.contains("Clone it."));
+
+        let deeper = outline("README.md", README, 2).unwrap();
+        assert!(deeper.contains("## Usage\n\nSee below.\n"));
+        assert!(deeper.contains("### From source: 3 lines collapsed"));
+
+        assert!(outline("README.md", README, 3).is_none());
+    }
+}
@@ -0,0 +1,154 @@
+//! Project-relative path normalization
+//!
+//! Every serialized artifact (context output, stores, cache keys, zoom
+//! targets, MCP responses) identifies a file by the same key: a `/`-separated
+//! path relative to the project root with no `./`, `.`/empty segments, or
+//! trailing `/`. How the root was passed (`.`, `../repo`, absolute, or
+//! canonicalized with a `\\?\` prefix) must not change the key, otherwise
+//! cache lookups miss and diffs between runs show spurious renames.
+//!
+//! Keys are built on [`RawPath::to_key`], so non-UTF-8 names stay lossless.
+
+use std::path::{Path, PathBuf};
+use voyager_ast::{strip_verbatim_prefix, RawPath};
+
+/// Normalize a user-supplied relative path (`\` is always a separator)
+///
+/// ```
+/// use pm_encoder::core::paths::normalize_relative_path;
+/// assert_eq!(normalize_relative_path(r".\src\\lib.rs"), "src/lib.rs");
+/// assert_eq!(normalize_relative_path("src/./a/../b.rs/"), "src/b.rs");
+/// ```
+pub fn normalize_relative_path(path: &str) -> String {
+    clean(path, true)
+}
+
+/// Key for a path that is already relative to the project root
+pub fn relative_key(path: &Path) -> String {
+    clean(&RawPath::from(path).to_key(), cfg!(windows))
+}
+
+/// Resolves paths against one project root
+#[derive(Debug, Clone)]
+pub struct ProjectPaths {
+    root: PathBuf,
+    /// Canonical root, when it differs from `root` (e.g. `.` or a symlink)
+    canonical_root: Option<PathBuf>,
+}
+
+impl ProjectPaths {
+    /// Create a resolver for `root`
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        let root = strip_verbatim_prefix(root.as_ref());
+        let canonical_root = root
+            .canonicalize()
+            .ok()
+            .map(|c| strip_verbatim_prefix(&c))
+            .filter(|c| *c != root);
+        Self { root, canonical_root }
+    }
+
+    /// The root as passed (without any `\\?\` prefix)
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Key for `path`, which may be absolute, root-prefixed, or already relative
+    ///
+    /// Paths outside the root keep their (normalized) absolute form.
+    pub fn relative(&self, path: &Path) -> String {
+        let path = strip_verbatim_prefix(path);
+        if let Some(rel) = self.strip_root(&path) {
+            return relative_key(&rel);
+        }
+        if path.is_absolute() {
+            // Either side may have been canonicalized (symlinked temp dirs, `.`)
+            if let Some(rel) = path
+                .canonicalize()
+                .ok()
+                .and_then(|c| self.strip_root(&strip_verbatim_prefix(&c)))
+            {
+                return relative_key(&rel);
+            }
+        }
+        relative_key(&path)
+    }
+
+    fn strip_root(&self, path: &Path) -> Option<PathBuf> {
+        std::iter::once(&self.root)
+            .chain(self.canonical_root.as_ref())
+            .find_map(|root| path.strip_prefix(root).ok().map(Path::to_path_buf))
+    }
+}
+
+/// Lexically clean a `/`-separated path: drop `.` and empty segments,
+/// resolve `..` against preceding segments, and trim the trailing `/`
+fn clean(path: &str, backslash_is_separator: bool) -> String {
+    let path = if backslash_is_separator {
+        path.replace('\\', "/")
+    } else {
+        path.to_string()
+    };
+    let absolute = path.starts_with('/');
+
+    let mut parts: Vec<&str> = Vec::new();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if parts.last().is_some_and(|p| *p != "..") => {
+                parts.pop();
+            }
+            ".." if absolute => {}
+            _ => parts.push(segment),
+        }
+    }
+
+    let joined = parts.join("/");
+    if absolute {
+        format!("/{}", joined)
+    } else {
+        joined
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_normalize_relative_path() {
+        assert_eq!(normalize_relative_path("./src/main.rs"), "src/main.rs");
+        assert_eq!(normalize_relative_path(r"src\core\mod.rs"), "src/core/mod.rs");
+        assert_eq!(normalize_relative_path("src//a/./b/../c.rs"), "src/a/c.rs");
+        assert_eq!(normalize_relative_path("../outside.rs"), "../outside.rs");
+        assert_eq!(normalize_relative_path("docs/"), "docs");
+        assert_eq!(normalize_relative_path("."), "");
+    }
+
+    #[test]
+    fn test_same_key_regardless_of_root_form() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        let file = temp.path().join("src/lib.rs");
+        fs::write(&file, "").unwrap();
+
+        let absolute = ProjectPaths::new(temp.path());
+        let canonical = ProjectPaths::new(temp.path().canonicalize().unwrap());
+        let dotted = ProjectPaths::new(temp.path().join("src/.."));
+
+        assert_eq!(absolute.relative(&file), "src/lib.rs");
+        assert_eq!(canonical.relative(&file), "src/lib.rs");
+        assert_eq!(absolute.relative(&file.canonicalize().unwrap()), "src/lib.rs");
+        assert_eq!(dotted.relative(&temp.path().join("src/../src/lib.rs")), "src/lib.rs");
+        assert_eq!(absolute.relative(Path::new("./src/lib.rs")), "src/lib.rs");
+    }
+
+    #[test]
+    fn test_dot_root() {
+        let paths = ProjectPaths::new(".");
+        assert_eq!(paths.relative(Path::new("./src/main.rs")), "src/main.rs");
+        assert_eq!(paths.relative(Path::new("src/main.rs")), "src/main.rs");
+    }
+}
@@ -0,0 +1,174 @@
+//! Preflight - Warn about configurations that will produce a useless context
+//!
+//! A budget below the context's own overhead, a lens that excludes every
+//! file, an include pattern with a typo: each of these produces an empty or
+//! near-empty context without any error. The checks here name the likely
+//! mistake so the CLI can print it and the context header can carry it:
+//!
+//! ```text
+//! lens 'security' excludes every file (42 found without it)
+//! include pattern '*.pyy' matches no files
+//! zoom target 'function=proces' not found; did you mean 'process'?
+//! ```
+
+use std::fmt;
+
+use serde::Serialize;
+use voyager_ast::PatternRule;
+
+/// Name of the synthetic file carrying warnings in non-XML formats
+pub const WARNINGS_FILE_NAME: &str = "WARNINGS.md";
+
+/// A configuration that is probably not what the user meant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConfigWarning {
+    /// The token budget cannot cover the context header and a single file's markers
+    BudgetBelowOverhead { budget: usize, overhead: usize },
+    /// The lens filtered out every file the walk found
+    LensExcludesAll { lens: String, files: usize },
+    /// An include pattern matches none of the walked files
+    IncludeMatchesNothing { pattern: String },
+    /// A zoom target that does not exist, with the closest one that does
+    ZoomTargetNotFound { target: String, suggestion: String },
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigWarning::BudgetBelowOverhead { budget, overhead } => write!(
+                f,
+                "token budget of {} is below the ~{} tokens of context overhead (header and file markers)",
+                budget, overhead
+            ),
+            ConfigWarning::LensExcludesAll { lens, files } => {
+                write!(f, "lens '{}' excludes every file ({} found without it)", lens, files)
+            }
+            ConfigWarning::IncludeMatchesNothing { pattern } => {
+                write!(f, "include pattern '{}' matches no files", pattern)
+            }
+            ConfigWarning::ZoomTargetNotFound { target, suggestion } => {
+                write!(f, "zoom target '{}' not found; did you mean '{}'?", target, suggestion)
+            }
+        }
+    }
+}
+
+/// Warn when `budget` does not exceed the `overhead` spent before any file content
+pub fn check_budget(budget: usize, overhead: usize) -> Option<ConfigWarning> {
+    (budget <= overhead).then_some(ConfigWarning::BudgetBelowOverhead { budget, overhead })
+}
+
+/// Warn when the lens leaves nothing of the `files` found without it
+pub fn check_lens(lens: &str, files: usize, selected: usize) -> Option<ConfigWarning> {
+    (files > 0 && selected == 0).then(|| ConfigWarning::LensExcludesAll { lens: lens.to_string(), files })
+}
+
+/// Warn for each include pattern matching none of `paths` (negations are not checked)
+pub fn check_include_patterns<S: AsRef<str>>(patterns: &[String], paths: &[S], case_insensitive: bool) -> Vec<ConfigWarning> {
+    patterns
+        .iter()
+        .filter(|pattern| {
+            PatternRule::parse_with_case(pattern, case_insensitive).is_some_and(|rule| {
+                !rule.is_negated() && !paths.iter().any(|path| rule.matches(path.as_ref(), false))
+            })
+        })
+        .map(|pattern| ConfigWarning::IncludeMatchesNothing { pattern: pattern.clone() })
+        .collect()
+}
+
+/// Warn for a zoom `target` whose `name` is missing but close to one of `candidates`
+pub fn check_zoom_target<S: AsRef<str>>(target: &str, name: &str, candidates: &[S]) -> Option<ConfigWarning> {
+    closest_match(name, candidates).map(|suggestion| ConfigWarning::ZoomTargetNotFound {
+        target: target.to_string(),
+        suggestion: suggestion.to_string(),
+    })
+}
+
+/// The candidate closest to `name`, if any is close enough to be a typo
+///
+/// Close means an edit distance of at most a third of the name's length
+/// (ignoring case), or a path ending in `name` (`main.rs` for `src/main.rs`).
+pub fn closest_match<'a, S: AsRef<str>>(name: &str, candidates: &'a [S]) -> Option<&'a str> {
+    let lower = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(1);
+    let suffix = format!("/{}", lower);
+    candidates
+        .iter()
+        .map(AsRef::as_ref)
+        .filter(|candidate| *candidate != name)
+        .filter_map(|candidate| {
+            let candidate_lower = candidate.to_lowercase();
+            let distance = if candidate_lower.ends_with(&suffix) { 0 } else { edit_distance(&lower, &candidate_lower) };
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .min_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.len().cmp(&b.1.len())).then_with(|| a.1.cmp(b.1)))
+        .map(|(_, candidate)| candidate)
+}
+
+/// Warnings as the synthetic `WARNINGS.md` that opens non-XML contexts
+pub fn to_markdown(warnings: &[ConfigWarning]) -> String {
+    let mut out = String::from("# Configuration warnings\n\n");
+    for warning in warnings {
+        out.push_str(&format!("- {}\n", warning));
+    }
+    out
+}
+
+/// Levenshtein distance over chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_lens_and_include_checks() {
+        assert_eq!(
+            check_budget(40, 95).unwrap().to_string(),
+            "token budget of 40 is below the ~95 tokens of context overhead (header and file markers)"
+        );
+        assert!(check_budget(10_000, 95).is_none());
+
+        assert_eq!(check_lens("security", 12, 0).unwrap().to_string(), "lens 'security' excludes every file (12 found without it)");
+        assert!(check_lens("security", 12, 3).is_none());
+        assert!(check_lens("security", 0, 0).is_none(), "an empty project is not the lens's fault");
+
+        let paths = ["src/main.rs", "src/lib.rs", "docs/guide.md"];
+        let patterns = vec!["*.rs".to_string(), "docs/".to_string(), "*.pyy".to_string(), "!*.md".to_string()];
+        assert_eq!(
+            check_include_patterns(&patterns, &paths, false),
+            vec![ConfigWarning::IncludeMatchesNothing { pattern: "*.pyy".to_string() }]
+        );
+        let upper = vec!["*.RS".to_string()];
+        assert_eq!(check_include_patterns(&upper, &paths, false).len(), 1);
+        assert!(check_include_patterns(&upper, &paths, true).is_empty());
+    }
+
+    #[test]
+    fn test_closest_match_suggests_typos_and_path_suffixes() {
+        let names = ["process", "process_all", "parse", "render"];
+        assert_eq!(closest_match("proces", &names), Some("process"));
+        assert_eq!(closest_match("Render", &names), Some("render"));
+        assert_eq!(closest_match("serialize", &names), None);
+
+        let paths = ["src/main.rs", "src/core/engine.rs"];
+        assert_eq!(closest_match("engine.rs", &paths), Some("src/core/engine.rs"));
+        assert_eq!(closest_match("src/mian.rs", &paths), Some("src/main.rs"));
+
+        let warning = check_zoom_target("function=proces", "proces", &names).unwrap();
+        assert_eq!(warning.to_string(), "zoom target 'function=proces' not found; did you mean 'process'?");
+    }
+}
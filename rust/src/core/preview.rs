@@ -0,0 +1,217 @@
+//! Context Preview - what a lens/budget WOULD include, without the content
+//!
+//! Generating a full context just to find out that the budget dropped the one
+//! file you needed is expensive. A preview runs the same walk → truncate →
+//! budget pipeline as `ContextEngine::serialize` but stops before
+//! serialization, reporting for every file the action taken (full, truncated,
+//! skeleton, dropped), its estimated token cost, and why.
+
+use serde::Serialize;
+
+use crate::core::engine::FileTier;
+
+/// What the pipeline would do with a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileAction {
+    /// Included verbatim
+    Full,
+    /// Included with truncated content
+    Truncated,
+    /// Included as signatures only
+    Skeleton,
+    /// Left out of the context
+    Dropped,
+}
+
+impl FileAction {
+    /// Lowercase name used in text and JSON output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileAction::Full => "full",
+            FileAction::Truncated => "truncated",
+            FileAction::Skeleton => "skeleton",
+            FileAction::Dropped => "dropped",
+        }
+    }
+
+    /// Check if the file ends up in the context
+    pub fn is_included(&self) -> bool {
+        !matches!(self, FileAction::Dropped)
+    }
+}
+
+/// Lowercase tier name for output
+pub(crate) fn tier_name(tier: FileTier) -> &'static str {
+    match tier {
+        FileTier::Core => "core",
+        FileTier::Config => "config",
+        FileTier::Tests => "tests",
+        FileTier::Other => "other",
+    }
+}
+
+/// Preview of a single file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PreviewEntry {
+    /// File path relative to the project root
+    pub path: String,
+    /// Action the pipeline would take
+    pub action: FileAction,
+    /// Estimated tokens the file costs in the context (0 when dropped)
+    pub tokens: usize,
+    /// Estimated tokens of the unmodified file
+    pub original_tokens: usize,
+    /// Budget tier the file was classified into
+    pub tier: &'static str,
+    /// Human-readable reason for the action
+    pub reason: String,
+}
+
+/// Preview of a whole context
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ContextPreview {
+    /// Per-file entries, in serialization order (dropped files last)
+    pub entries: Vec<PreviewEntry>,
+    /// Token budget in effect, if any
+    pub budget: Option<usize>,
+    /// Active lens, if any
+    pub lens: Option<String>,
+}
+
+impl ContextPreview {
+    /// Estimated tokens of the generated context
+    pub fn total_tokens(&self) -> usize {
+        self.entries.iter().map(|e| e.tokens).sum()
+    }
+
+    /// Number of files with the given action
+    pub fn count(&self, action: FileAction) -> usize {
+        self.entries.iter().filter(|e| e.action == action).count()
+    }
+
+    /// Number of files that end up in the context
+    pub fn included_count(&self) -> usize {
+        self.entries.iter().filter(|e| e.action.is_included()).count()
+    }
+
+    /// Render as a plain-text table
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!(
+            "Context preview: {} files ({} full, {} truncated, {} skeleton, {} dropped)\n",
+            self.entries.len(),
+            self.count(FileAction::Full),
+            self.count(FileAction::Truncated),
+            self.count(FileAction::Skeleton),
+            self.count(FileAction::Dropped),
+        ));
+        if let Some(lens) = &self.lens {
+            out.push_str(&format!("Lens: {}\n", lens));
+        }
+        match self.budget {
+            Some(budget) => out.push_str(&format!(
+                "Estimated tokens: {} / {} budget\n",
+                self.total_tokens(),
+                budget
+            )),
+            None => out.push_str(&format!("Estimated tokens: {}\n", self.total_tokens())),
+        }
+        out.push('\n');
+
+        let path_width = self.entries.iter().map(|e| e.path.len()).max().unwrap_or(4).max(4);
+        out.push_str(&format!(
+            "{:<10} {:>8} {:<7} {:<width$}  REASON\n",
+            "ACTION",
+            "TOKENS",
+            "TIER",
+            "PATH",
+            width = path_width
+        ));
+        for entry in &self.entries {
+            out.push_str(&format!(
+                "{:<10} {:>8} {:<7} {:<width$}  {}\n",
+                entry.action.as_str(),
+                entry.tokens,
+                entry.tier,
+                entry.path,
+                entry.reason,
+                width = path_width
+            ));
+        }
+        out
+    }
+
+    /// Render as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        let value = serde_json::json!({
+            "lens": self.lens,
+            "budget": self.budget,
+            "total_tokens": self.total_tokens(),
+            "counts": {
+                "full": self.count(FileAction::Full),
+                "truncated": self.count(FileAction::Truncated),
+                "skeleton": self.count(FileAction::Skeleton),
+                "dropped": self.count(FileAction::Dropped),
+            },
+            "files": self.entries,
+        });
+        serde_json::to_string_pretty(&value).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(path: &str, action: FileAction, tokens: usize) -> PreviewEntry {
+        PreviewEntry {
+            path: path.to_string(),
+            action,
+            tokens,
+            original_tokens: tokens.max(10),
+            tier: "core",
+            reason: "test".to_string(),
+        }
+    }
+
+    fn sample() -> ContextPreview {
+        ContextPreview {
+            entries: vec![
+                entry("src/lib.rs", FileAction::Full, 100),
+                entry("src/big.rs", FileAction::Truncated, 40),
+                entry("tests/slow.rs", FileAction::Dropped, 0),
+            ],
+            budget: Some(200),
+            lens: Some("architecture".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_totals_and_counts() {
+        let preview = sample();
+        assert_eq!(preview.total_tokens(), 140);
+        assert_eq!(preview.included_count(), 2);
+        assert_eq!(preview.count(FileAction::Dropped), 1);
+        assert_eq!(preview.count(FileAction::Skeleton), 0);
+    }
+
+    #[test]
+    fn test_text_rendering() {
+        let text = sample().to_text();
+        assert!(text.contains("3 files (1 full, 1 truncated, 0 skeleton, 1 dropped)"));
+        assert!(text.contains("Lens: architecture"));
+        assert!(text.contains("140 / 200 budget"));
+        assert!(text.contains("dropped"));
+        assert!(text.contains("tests/slow.rs"));
+    }
+
+    #[test]
+    fn test_json_rendering() {
+        let json: serde_json::Value = serde_json::from_str(&sample().to_json()).unwrap();
+        assert_eq!(json["total_tokens"], 140);
+        assert_eq!(json["counts"]["truncated"], 1);
+        assert_eq!(json["files"][2]["action"], "dropped");
+        assert_eq!(json["files"][0]["tier"], "core");
+    }
+}
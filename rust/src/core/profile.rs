@@ -0,0 +1,385 @@
+//! Project Profile - Language, framework and architecture summary
+//!
+//! A [`ProjectProfile`] answers "what kind of project is this?" from the
+//! files alone:
+//!
+//! - **Languages**: files and lines per language, largest first
+//! - **Frameworks**: detected from dependency manifests (see
+//!   [`crate::core::manifests`]) and from import statements
+//! - **Architecture**: hints from the top-level layout (`src/`, `tests/`,
+//!   workspaces, migrations, CI, ...)
+//!
+//! The profile feeds the instruction header of `--init-prompt` and can pick
+//! a starting lens via [`ProjectProfile::suggest_lens`]. Output is
+//! deterministic so generated instruction files do not drift.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+use voyager_ast::LanguageId;
+
+use crate::core::ast_bridge::AstBridge;
+use crate::core::manifests::DependencyModel;
+
+/// Lines of a source file scanned for import signals
+const IMPORT_SCAN_LINES: usize = 200;
+
+/// A framework and the signals that identify it
+struct FrameworkRule {
+    name: &'static str,
+    category: &'static str,
+    /// Package names as they appear in dependency manifests
+    dependencies: &'static [&'static str],
+    /// Substrings of import lines in source files
+    imports: &'static [&'static str],
+}
+
+const FRAMEWORKS: &[FrameworkRule] = &[
+    FrameworkRule { name: "React", category: "frontend", dependencies: &["react"], imports: &["from 'react'", "from \"react\"", "require('react')"] },
+    FrameworkRule { name: "Vue", category: "frontend", dependencies: &["vue"], imports: &["from 'vue'", "from \"vue\""] },
+    FrameworkRule { name: "Angular", category: "frontend", dependencies: &["@angular/core"], imports: &["from '@angular/core'", "from \"@angular/core\""] },
+    FrameworkRule { name: "Svelte", category: "frontend", dependencies: &["svelte"], imports: &["from 'svelte'", "from \"svelte\""] },
+    FrameworkRule { name: "Next.js", category: "web", dependencies: &["next"], imports: &["from 'next/", "from \"next/"] },
+    FrameworkRule { name: "Express", category: "web", dependencies: &["express"], imports: &["require('express')", "from 'express'", "from \"express\""] },
+    FrameworkRule { name: "Django", category: "web", dependencies: &["django"], imports: &["from django", "import django"] },
+    FrameworkRule { name: "Flask", category: "web", dependencies: &["flask"], imports: &["from flask", "import flask"] },
+    FrameworkRule { name: "FastAPI", category: "web", dependencies: &["fastapi"], imports: &["from fastapi", "import fastapi"] },
+    FrameworkRule { name: "Axum", category: "web", dependencies: &["axum"], imports: &["use axum"] },
+    FrameworkRule { name: "Actix Web", category: "web", dependencies: &["actix-web"], imports: &["use actix_web"] },
+    FrameworkRule { name: "Rocket", category: "web", dependencies: &["rocket"], imports: &["use rocket"] },
+    FrameworkRule { name: "Gin", category: "web", dependencies: &["github.com/gin-gonic/gin"], imports: &["\"github.com/gin-gonic/gin\""] },
+    FrameworkRule { name: "Rails", category: "web", dependencies: &["rails"], imports: &[] },
+    FrameworkRule { name: "Spring", category: "web", dependencies: &["org.springframework"], imports: &["import org.springframework"] },
+    FrameworkRule { name: "Tokio", category: "async runtime", dependencies: &["tokio"], imports: &["use tokio", "#[tokio::main]"] },
+    FrameworkRule { name: "Clap", category: "cli", dependencies: &["clap"], imports: &["use clap"] },
+    FrameworkRule { name: "pytest", category: "testing", dependencies: &["pytest"], imports: &["import pytest"] },
+    FrameworkRule { name: "Jest", category: "testing", dependencies: &["jest"], imports: &[] },
+];
+
+/// Top-level directory names and the role they suggest
+const LAYOUT: &[(&str, &str)] = &[
+    ("src", "source code"),
+    ("lib", "library code"),
+    ("bin", "executables"),
+    ("cmd", "executables"),
+    ("app", "application code"),
+    ("tests", "tests"),
+    ("test", "tests"),
+    ("spec", "tests"),
+    ("benches", "benchmarks"),
+    ("examples", "examples"),
+    ("docs", "documentation"),
+    ("doc", "documentation"),
+    ("scripts", "tooling"),
+    ("tools", "tooling"),
+    ("migrations", "database migrations"),
+    ("frontend", "frontend"),
+    ("web", "frontend"),
+    ("client", "frontend"),
+    ("backend", "backend"),
+    ("server", "backend"),
+    ("api", "API layer"),
+    ("packages", "workspace members"),
+    ("crates", "workspace members"),
+    ("apps", "workspace members"),
+    ("services", "services"),
+    (".github", "CI configuration"),
+];
+
+/// Share of the project written in one language
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguageShare {
+    pub language: String,
+    pub files: usize,
+    pub lines: usize,
+    /// Percentage of all source lines
+    pub percent: f64,
+}
+
+/// A detected framework or major library
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Framework {
+    pub name: String,
+    /// `frontend`, `web`, `async runtime`, `cli` or `testing`
+    pub category: String,
+    /// Where it was detected ("dependency in Cargo.toml", "imported in 3 files")
+    pub evidence: Vec<String>,
+}
+
+/// Language, framework and architecture summary of a project
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ProjectProfile {
+    pub total_files: usize,
+    /// Languages by line count, largest first
+    pub languages: Vec<LanguageShare>,
+    /// Frameworks in rule order
+    pub frameworks: Vec<Framework>,
+    /// Architecture hints from the project layout
+    pub architecture: Vec<String>,
+}
+
+impl ProjectProfile {
+    /// Analyze `(path, content)` pairs (paths relative to the project root)
+    pub fn analyze(files: &[(String, String)]) -> Self {
+        Self {
+            total_files: files.len(),
+            languages: languages(files),
+            frameworks: frameworks(files),
+            architecture: architecture(files),
+        }
+    }
+
+    /// Language with the most lines
+    pub fn primary_language(&self) -> Option<&str> {
+        self.languages.first().map(|l| l.language.as_str())
+    }
+
+    /// Check whether a framework was detected (case-insensitive)
+    pub fn has_framework(&self, name: &str) -> bool {
+        self.frameworks.iter().any(|f| f.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Built-in lens that suits the project as a starting point
+    ///
+    /// Web and frontend projects start with `architecture` (routing and
+    /// layering matter most); small projects with `onboarding`.
+    pub fn suggest_lens(&self) -> &'static str {
+        let web = self.frameworks.iter().any(|f| f.category == "web" || f.category == "frontend");
+        if !web && self.total_files <= 20 {
+            "onboarding"
+        } else {
+            "architecture"
+        }
+    }
+
+    /// One-line language summary ("Rust 82.1%, Python 17.9%")
+    pub fn language_summary(&self) -> String {
+        self.languages
+            .iter()
+            .map(|l| format!("{} {:.1}%", l.language, l.percent))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Lines for the context meta header
+    pub fn render_header(&self) -> String {
+        let mut out = String::new();
+        if !self.languages.is_empty() {
+            out.push_str(&format!("Languages: {}\n", self.language_summary()));
+        }
+        if !self.frameworks.is_empty() {
+            let names: Vec<&str> = self.frameworks.iter().map(|f| f.name.as_str()).collect();
+            out.push_str(&format!("Frameworks: {}\n", names.join(", ")));
+        }
+        out
+    }
+
+    /// The `## Project Profile` section of instruction files
+    pub fn render_markdown(&self) -> String {
+        let mut out = String::from("## Project Profile\n\n");
+        if !self.languages.is_empty() {
+            out.push_str("**Languages:**\n");
+            for share in &self.languages {
+                out.push_str(&format!(
+                    "- {}: {} files, {} lines ({:.1}%)\n",
+                    share.language, share.files, share.lines, share.percent
+                ));
+            }
+            out.push('\n');
+        }
+        if !self.frameworks.is_empty() {
+            out.push_str("**Frameworks:**\n");
+            for framework in &self.frameworks {
+                out.push_str(&format!(
+                    "- {} ({}) - {}\n",
+                    framework.name,
+                    framework.category,
+                    framework.evidence.join("; ")
+                ));
+            }
+            out.push('\n');
+        }
+        if !self.architecture.is_empty() {
+            out.push_str("**Architecture:**\n");
+            for hint in &self.architecture {
+                out.push_str(&format!("- {}\n", hint));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn languages(files: &[(String, String)]) -> Vec<LanguageShare> {
+    let mut counts: BTreeMap<&'static str, (usize, usize)> = BTreeMap::new();
+    for (path, content) in files {
+        let language = AstBridge::detect_language(Path::new(path));
+        if !is_source(language) {
+            continue;
+        }
+        let entry = counts.entry(language.name()).or_default();
+        entry.0 += 1;
+        entry.1 += content.lines().count();
+    }
+
+    let total: usize = counts.values().map(|(_, lines)| lines).sum();
+    let mut shares: Vec<LanguageShare> = counts
+        .into_iter()
+        .map(|(language, (files, lines))| LanguageShare {
+            language: language.to_string(),
+            files,
+            lines,
+            percent: if total == 0 { 0.0 } else { lines as f64 * 100.0 / total as f64 },
+        })
+        .collect();
+    shares.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| b.files.cmp(&a.files)));
+    shares
+}
+
+/// Code languages; data and documentation formats are not counted
+fn is_source(language: LanguageId) -> bool {
+    !matches!(
+        language,
+        LanguageId::Unknown | LanguageId::Markdown | LanguageId::Json | LanguageId::Toml | LanguageId::Yaml
+    )
+}
+
+fn frameworks(files: &[(String, String)]) -> Vec<Framework> {
+    let model = DependencyModel::from_files(files);
+
+    let mut found = Vec::new();
+    for rule in FRAMEWORKS {
+        let mut manifests: Vec<&str> = model
+            .dependencies
+            .iter()
+            .filter(|dep| {
+                rule.dependencies.iter().any(|d| {
+                    dep.name.eq_ignore_ascii_case(d) || dep.name.starts_with(&format!("{}.", d))
+                })
+            })
+            .map(|dep| dep.manifest.as_str())
+            .collect();
+        manifests.dedup();
+        let mut evidence: Vec<String> = manifests.iter().map(|m| format!("dependency in {}", m)).collect();
+
+        let importers = files
+            .iter()
+            .filter(|(path, _)| is_source(AstBridge::detect_language(Path::new(path))))
+            .filter(|(_, content)| {
+                content
+                    .lines()
+                    .take(IMPORT_SCAN_LINES)
+                    .any(|line| rule.imports.iter().any(|i| line.trim_start().contains(i)))
+            })
+            .count();
+        if importers > 0 {
+            evidence.push(format!("imported in {} file{}", importers, if importers == 1 { "" } else { "s" }));
+        }
+
+        if !evidence.is_empty() {
+            found.push(Framework { name: rule.name.to_string(), category: rule.category.to_string(), evidence });
+        }
+    }
+    found
+}
+
+fn architecture(files: &[(String, String)]) -> Vec<String> {
+    let mut hints = Vec::new();
+
+    let workspace = files.iter().find_map(|(path, content)| match path.as_str() {
+        "Cargo.toml" if content.contains("[workspace]") => Some("Cargo workspace"),
+        "package.json" if content.contains("\"workspaces\"") => Some("npm workspaces"),
+        "pnpm-workspace.yaml" => Some("pnpm workspace"),
+        "go.work" => Some("Go workspace"),
+        _ => None,
+    });
+    if let Some(kind) = workspace {
+        hints.push(format!("Multi-package repository ({})", kind));
+    }
+
+    let mut directories: BTreeMap<&str, usize> = BTreeMap::new();
+    for (path, _) in files {
+        if let Some((top, _)) = path.split_once('/') {
+            *directories.entry(top).or_default() += 1;
+        }
+    }
+    for (name, role) in LAYOUT {
+        if let Some(count) = directories.get(name) {
+            hints.push(format!("`{}/` - {} ({} file{})", name, role, count, if *count == 1 { "" } else { "s" }));
+        }
+    }
+
+    let has = |name: &str| files.iter().any(|(path, _)| path.rsplit('/').next() == Some(name));
+    if has("Dockerfile") || has("docker-compose.yml") || has("compose.yaml") {
+        hints.push("Containerized (Dockerfile/compose)".to_string());
+    }
+    hints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, content: &str) -> (String, String) {
+        (path.to_string(), content.to_string())
+    }
+
+    #[test]
+    fn test_profile_rust_web_service() {
+        let files = vec![
+            file("Cargo.toml", "[workspace]\nmembers = [\"api\"]\n\n[dependencies]\naxum = \"0.7\"\ntokio = { version = \"1\", features = [\"full\"] }\n\n[dev-dependencies]\nclap.workspace = true\n"),
+            file("src/main.rs", "use axum::Router;\n\n#[tokio::main]\nasync fn main() {\n    let _app = Router::new();\n}\n"),
+            file("src/routes.rs", "pub fn index() {}\n"),
+            file("tests/api.rs", "#[test]\nfn test_index() {}\n"),
+            file("scripts/release.py", "import sys\n"),
+            file("README.md", "# Service\n"),
+        ];
+        let profile = ProjectProfile::analyze(&files);
+
+        assert_eq!(profile.primary_language(), Some("Rust"));
+        assert_eq!(profile.languages.len(), 2);
+        let total: f64 = profile.languages.iter().map(|l| l.percent).sum();
+        assert!((total - 100.0).abs() < 0.01);
+
+        assert!(profile.has_framework("axum"));
+        assert!(profile.has_framework("Tokio"));
+        assert!(profile.has_framework("clap"));
+        assert!(!profile.has_framework("React"));
+        let axum = profile.frameworks.iter().find(|f| f.name == "Axum").unwrap();
+        assert_eq!(axum.evidence, vec!["dependency in Cargo.toml", "imported in 1 file"]);
+
+        assert_eq!(profile.architecture[0], "Multi-package repository (Cargo workspace)");
+        assert!(profile.architecture.contains(&"`src/` - source code (2 files)".to_string()));
+        assert!(profile.architecture.contains(&"`tests/` - tests (1 file)".to_string()));
+        assert_eq!(profile.suggest_lens(), "architecture");
+
+        let markdown = profile.render_markdown();
+        assert!(markdown.starts_with("## Project Profile\n\n**Languages:**\n- Rust:"));
+        assert!(markdown.contains("- Axum (web) - dependency in Cargo.toml; imported in 1 file\n"));
+        assert!(profile.render_header().contains("Frameworks: Axum, Tokio, Clap\n"));
+    }
+
+    #[test]
+    fn test_profile_python_and_javascript_manifests() {
+        let files = vec![
+            file("requirements.txt", "# web\nDjango>=4.2\npytest==8.0 ; python_version > '3.8'\n"),
+            file("pyproject.toml", "[project]\ndependencies = [\n  \"fastapi>=0.100\",\n]\n"),
+            file("frontend/package.json", "{\"dependencies\": {\"react\": \"^18\"}, \"devDependencies\": {\"jest\": \"29\"}}"),
+            file("frontend/app.js", "import React from 'react';\n"),
+            file("manage.py", "from django.core import management\n"),
+        ];
+        let profile = ProjectProfile::analyze(&files);
+
+        for name in ["Django", "FastAPI", "pytest", "React", "Jest"] {
+            assert!(profile.has_framework(name), "missing {}", name);
+        }
+        assert!(!profile.has_framework("Flask"));
+        let react = profile.frameworks.iter().find(|f| f.name == "React").unwrap();
+        assert_eq!(react.evidence[0], "dependency in frontend/package.json");
+        assert!(profile.architecture.contains(&"`frontend/` - frontend (2 files)".to_string()));
+
+        assert_eq!(ProjectProfile::analyze(&[]), ProjectProfile::default());
+        assert_eq!(ProjectProfile::default().suggest_lens(), "onboarding");
+    }
+}
@@ -0,0 +1,260 @@
+//! Provenance - Flag generated and vendored files as such in the context
+//!
+//! Models happily propose edits to `api.pb.go` or `vendor/` copies, edits
+//! that the next `protoc` run or dependency update silently reverts.
+//! [`Provenance::classify`] recognizes those files and
+//! [`Provenance::note`] says where the real source is:
+//!
+//! ```text
+//! generated by protoc from api.proto — prefer editing the source
+//! vendored copy of lodash — prefer updating the dependency over editing it here
+//! ```
+//!
+//! A file is vendored when it lives below a vendor directory
+//! (`vendor/`, `third_party/`, `node_modules/`, ...), and generated when its
+//! name has a generated-source suffix (`_pb2.py`, `.pb.go`, `.g.dart`) or a
+//! comment near its top carries a generated-code marker (`Code generated by
+//! ... DO NOT EDIT`, `@generated`). Generator and source are read from the
+//! banner when it names them (`// source: api.proto`).
+
+use serde::Serialize;
+
+use crate::core::boilerplate::{BoilerplateRules, GENERATED_MARKERS};
+
+/// Directories whose contents are third-party copies (matched case-insensitively)
+const VENDOR_DIRS: &[&str] = &[
+    "vendor",
+    "vendored",
+    "third_party",
+    "third-party",
+    "thirdparty",
+    "node_modules",
+    "bower_components",
+];
+
+/// File name suffixes of generated sources, with the generator that writes them
+const GENERATED_SUFFIXES: &[(&str, Option<&str>)] = &[
+    ("_pb2.py", Some("protoc")),
+    ("_pb2.pyi", Some("protoc")),
+    ("_pb2_grpc.py", Some("protoc")),
+    (".pb.go", Some("protoc")),
+    (".pb.cc", Some("protoc")),
+    (".pb.h", Some("protoc")),
+    ("_pb.js", Some("protoc")),
+    ("_pb.d.ts", Some("protoc")),
+    (".g.dart", Some("build_runner")),
+    (".freezed.dart", Some("freezed")),
+    (".designer.cs", None),
+    (".generated.cs", None),
+    (".generated.ts", None),
+];
+
+/// Comment openings that name a generator without a generated-code marker
+/// (`# Generated by Django 4.2 on ...`)
+const GENERATED_OPENINGS: &[&str] = &["generated by ", "this file was generated", "this file is generated"];
+
+/// Lines at the top of a file searched for a generated-code banner
+const HEADER_LINES: usize = 20;
+
+/// Where a file's content really comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProvenanceKind {
+    /// Written by a tool from another source
+    Generated,
+    /// Copied in from a third-party package
+    Vendored,
+}
+
+impl ProvenanceKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Generated => "generated",
+            Self::Vendored => "vendored",
+        }
+    }
+}
+
+/// A generated or vendored file's origin
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Provenance {
+    pub kind: ProvenanceKind,
+    /// Tool that wrote the file (`protoc`), if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub generator: Option<String>,
+    /// Input the file was generated from, or the vendored package
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl Provenance {
+    /// Provenance of a file (`None` for ordinary project sources)
+    pub fn classify(path: &str, content: &str) -> Option<Self> {
+        let normalized = path.replace('\\', "/");
+        let segments: Vec<&str> = normalized.split('/').filter(|s| !s.is_empty()).collect();
+        let (file_name, dirs) = segments.split_last()?;
+
+        if let Some(i) = dirs.iter().position(|d| VENDOR_DIRS.contains(&d.to_ascii_lowercase().as_str())) {
+            // `node_modules/@scope/name` packages span two directories
+            let package = match &dirs[i + 1..] {
+                [scope, name, ..] if scope.starts_with('@') => Some(format!("{}/{}", scope, name)),
+                [name, ..] => Some(name.to_string()),
+                [] => None,
+            };
+            return Some(Self { kind: ProvenanceKind::Vendored, generator: None, source: package });
+        }
+
+        let lower_name = file_name.to_ascii_lowercase();
+        let suffix = GENERATED_SUFFIXES.iter().find(|(s, _)| lower_name.ends_with(s));
+        let header: Vec<&str> = content.lines().take(HEADER_LINES).filter(|l| is_comment_like(l)).collect();
+        let banner = header.iter().any(|l| {
+            let lower = comment_text(l).to_ascii_lowercase();
+            GENERATED_MARKERS.iter().any(|m| lower.contains(m)) || GENERATED_OPENINGS.iter().any(|o| lower.starts_with(o))
+        });
+        if suffix.is_none() && !banner {
+            return None;
+        }
+
+        let generator = header
+            .iter()
+            .find_map(|l| generator_in(l))
+            .or_else(|| suffix.and_then(|(_, g)| g.map(str::to_string)));
+        let source = header.iter().find_map(|l| source_in(l));
+        Some(Self { kind: ProvenanceKind::Generated, generator, source })
+    }
+
+    /// One-line note telling the reader where to make changes instead
+    pub fn note(&self) -> String {
+        match (self.kind, &self.generator, &self.source) {
+            (ProvenanceKind::Generated, Some(generator), Some(source)) => {
+                format!("generated by {} from {} — prefer editing the source", generator, source)
+            }
+            (ProvenanceKind::Generated, Some(generator), None) => {
+                format!("generated by {} — prefer editing its input over this file", generator)
+            }
+            (ProvenanceKind::Generated, None, Some(source)) => {
+                format!("generated from {} — prefer editing the source", source)
+            }
+            (ProvenanceKind::Generated, None, None) => {
+                "generated code — prefer editing the source it is generated from".to_string()
+            }
+            (ProvenanceKind::Vendored, _, Some(package)) => {
+                format!("vendored copy of {} — prefer updating the dependency over editing it here", package)
+            }
+            (ProvenanceKind::Vendored, _, None) => {
+                "vendored third-party code — prefer updating the dependency over editing it here".to_string()
+            }
+        }
+    }
+
+    /// `content` opened with the note, as a comment in the file's own syntax
+    ///
+    /// A shebang stays on the first line. Files without a known comment
+    /// syntax get the bracketed note as a plain line.
+    pub fn annotate(&self, path: &str, content: &str, rules: &BoilerplateRules) -> String {
+        let note = format!("[{}]", self.note());
+        let line = rules.dictionary(path).map_or_else(|| note.clone(), |d| d.comment(&note));
+        let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+        match content.strip_prefix("#!").and_then(|rest| rest.find('\n').map(|i| i + 3)) {
+            Some(split) => format!("{}{}{}{}", &content[..split], line, newline, &content[split..]),
+            None => format!("{}{}{}", line, newline, content),
+        }
+    }
+}
+
+/// Comment lines and banners, not code: `// ...`, `# ...`, ` * ...`, `"""...`
+fn is_comment_like(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    !trimmed.is_empty() && !trimmed.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+}
+
+/// A comment line without its comment markers
+fn comment_text(line: &str) -> &str {
+    line.trim_start_matches(|c: char| c.is_whitespace() || "/#*-;!<\"'".contains(c))
+}
+
+/// Generator named by a banner line (`Code generated by protoc-gen-go. DO NOT EDIT.`)
+fn generator_in(line: &str) -> Option<String> {
+    let lower = line.to_ascii_lowercase();
+    if lower.contains("protocol buffer compiler") {
+        return Some("protoc".to_string());
+    }
+    let start = lower.find("generated by ")? + "generated by ".len();
+    let rest = &lower[start..];
+    let rest = rest.strip_prefix("the ").unwrap_or(rest);
+    let offset = lower.len() - rest.len();
+    let end = [". ", " do not", " on ", " from ", " with ", " (", ",", " -", "*/", "-->"]
+        .iter()
+        .filter_map(|stop| rest.find(stop))
+        .min()
+        .unwrap_or(rest.len());
+    let name = line[offset..offset + end].trim().trim_end_matches('.');
+    (!name.is_empty() && name.len() <= 40).then(|| name.to_string())
+}
+
+/// Input named by a banner line (`// source: api.proto`, `@generated from file api.proto`)
+fn source_in(line: &str) -> Option<String> {
+    let body = comment_text(line).trim_start_matches('@');
+    let lower = body.to_ascii_lowercase();
+    let rest = if lower.starts_with("source:") {
+        &body["source:".len()..]
+    } else {
+        let start = lower.find("generated from ")? + "generated from ".len();
+        let rest = &body[start..];
+        rest.strip_prefix("file ").unwrap_or(rest)
+    };
+    let source = rest.split_whitespace().next()?.trim_end_matches(['.', ',']);
+    (!source.is_empty() && source != "*/" && source != "-->").then(|| source.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_banners_name_generator_and_source() {
+        let go = "// Code generated by protoc-gen-go. DO NOT EDIT.\n// versions:\n// \tprotoc v4.25\n// source: api/v1/api.proto\n\npackage api\n";
+        let provenance = Provenance::classify("api/v1/api.pb.go", go).unwrap();
+        assert_eq!(provenance.kind, ProvenanceKind::Generated);
+        assert_eq!(provenance.generator.as_deref(), Some("protoc-gen-go"));
+        assert_eq!(provenance.source.as_deref(), Some("api/v1/api.proto"));
+        assert_eq!(provenance.note(), "generated by protoc-gen-go from api/v1/api.proto — prefer editing the source");
+
+        let python = "# -*- coding: utf-8 -*-\n# Generated by the protocol buffer compiler.  DO NOT EDIT!\n# source: api.proto\n";
+        let provenance = Provenance::classify("api_pb2.py", python).unwrap();
+        assert_eq!(provenance.note(), "generated by protoc from api.proto — prefer editing the source");
+
+        let django = "# Generated by Django 4.2 on 2024-05-01 10:00\n\nfrom django.db import migrations\n";
+        assert_eq!(Provenance::classify("app/migrations/0001_initial.py", django).unwrap().generator.as_deref(), Some("Django 4.2"));
+
+        // Suffix alone: the generator is known, the source is not
+        let dart = Provenance::classify("lib/user.g.dart", "part of 'user.dart';\n").unwrap();
+        assert_eq!(dart.note(), "generated by build_runner — prefer editing its input over this file");
+    }
+
+    #[test]
+    fn test_vendored_and_ordinary_files() {
+        let lodash = Provenance::classify("web/node_modules/lodash/index.js", "module.exports = {};\n").unwrap();
+        assert_eq!(lodash.kind, ProvenanceKind::Vendored);
+        assert_eq!(lodash.note(), "vendored copy of lodash — prefer updating the dependency over editing it here");
+        let scoped = Provenance::classify("node_modules/@types/node/index.d.ts", "").unwrap();
+        assert_eq!(scoped.source.as_deref(), Some("@types/node"));
+        assert_eq!(Provenance::classify("Vendor\\zlib.c", "").unwrap().source, None);
+
+        // Markers in code rather than comments, and vendor-named files, are not provenance
+        assert!(Provenance::classify("src/main.rs", "fn main() {}\n").is_none());
+        assert!(Provenance::classify("src/vendor.rs", "let msg = \"do not edit\";\n").is_none());
+    }
+
+    #[test]
+    fn test_annotate_uses_comment_syntax_and_keeps_shebang() {
+        let rules = BoilerplateRules::default();
+        let provenance = Provenance { kind: ProvenanceKind::Generated, generator: Some("protoc".to_string()), source: None };
+        assert_eq!(
+            provenance.annotate("api_pb2.py", "#!/usr/bin/env python\nimport sys\n", &rules),
+            "#!/usr/bin/env python\n# [generated by protoc — prefer editing its input over this file]\nimport sys\n"
+        );
+        assert!(provenance.annotate("api.pb.go", "package api\r\n", &rules).starts_with("// [generated by protoc"));
+        assert!(provenance.annotate("schema.json", "{}", &rules).starts_with("[generated by protoc"));
+    }
+}
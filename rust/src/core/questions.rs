@@ -0,0 +1,259 @@
+//! Question Packs - Evaluation questions with ground-truth answers
+//!
+//! Generates question/answer pairs about a codebase from its index, for
+//! benchmarking how well a context configuration lets an LLM answer them:
+//!
+//! - **Definition**: "Where is `X` defined?" - the declaration's file and lines
+//! - **Callers**: "What calls `Y`?" - the functions whose body calls it
+//! - **Returns**: "What does `Z` return?" - the declared return type
+//!
+//! Every answer carries the locations that prove it. Only unambiguous
+//! questions are asked: a name declared more than once gets no definition or
+//! caller question, as the answer would depend on which one is meant. Test
+//! functions are never the subject. Questions are keyed by the subject's
+//! [`Anchor`](crate::core::anchor::Anchor), so packs from the same code
+//! compare question by question.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+use voyager_ast::related::related_symbols;
+use voyager_ast::testmap::is_test_declaration;
+use voyager_ast::{Declaration, DeclarationKind, File, RawPath, Relation};
+
+use crate::core::anchor::anchors;
+use crate::core::ast_bridge::AstBridge;
+
+/// Default number of questions of each kind
+pub const DEFAULT_QUESTIONS_PER_KIND: usize = 20;
+
+/// Callers considered per callers question
+const MAX_CALLERS: usize = 10;
+
+/// What a question asks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionKind {
+    Definition,
+    Callers,
+    Returns,
+}
+
+impl QuestionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Definition => "definition",
+            Self::Callers => "callers",
+            Self::Returns => "returns",
+        }
+    }
+}
+
+/// A line range in a file (1-indexed, inclusive)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Location {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl Location {
+    fn of(path: &str, decl: &Declaration) -> Self {
+        Self {
+            path: path.to_string(),
+            start_line: decl.span.start_line,
+            end_line: decl.span.end_line,
+        }
+    }
+}
+
+/// An evaluation question with its ground truth
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Question {
+    /// Stable id: the kind and the subject's anchor (`callers:src/lib.rs#run@1a2b3c4d`)
+    pub id: String,
+    pub kind: QuestionKind,
+    pub question: String,
+    pub answer: String,
+    /// Where the answer can be read
+    pub locations: Vec<Location>,
+}
+
+/// Questions generated for one project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct QuestionPack {
+    pub questions: Vec<Question>,
+}
+
+/// A declaration with its anchor and file
+struct Subject<'a> {
+    path: &'a str,
+    anchor: String,
+    decl: &'a Declaration,
+}
+
+impl QuestionPack {
+    /// Generate up to `per_kind` questions of each kind from `(path, content)` pairs
+    ///
+    /// Subjects are taken in path and line order, so the same code always
+    /// gives the same pack.
+    pub fn generate(files: &[(String, String)], per_kind: usize) -> Self {
+        let bridge = AstBridge::new();
+        let mut parsed: Vec<(RawPath, File, &str)> = files
+            .iter()
+            .filter_map(|(path, content)| {
+                let language = AstBridge::detect_language(Path::new(path));
+                let file = bridge.analyze_file(content, language)?;
+                Some((RawPath::from_key(path), file, content.as_str()))
+            })
+            .collect();
+        parsed.sort_by_key(|(path, _, _)| path.to_key());
+        let keys: Vec<String> = parsed.iter().map(|(path, _, _)| path.to_key()).collect();
+
+        let mut subjects = Vec::new();
+        let mut declared: BTreeMap<&str, usize> = BTreeMap::new();
+        for ((_, file, source), key) in parsed.iter().zip(&keys) {
+            for (anchor, decl, _) in anchors(key, &file.declarations, source, usize::MAX) {
+                // `impl Config` does not declare another `Config`
+                if matches!(decl.kind, DeclarationKind::Section | DeclarationKind::Element | DeclarationKind::Impl) {
+                    continue;
+                }
+                *declared.entry(decl.name.as_str()).or_default() += 1;
+                if !is_test_declaration(decl) {
+                    subjects.push(Subject { path: key, anchor: anchor.to_string(), decl });
+                }
+            }
+        }
+        let unique = |decl: &Declaration| declared.get(decl.name.as_str()) == Some(&1);
+        let callable = |decl: &Declaration| matches!(decl.kind, DeclarationKind::Function | DeclarationKind::Method);
+
+        let mut questions = Vec::new();
+        for subject in subjects.iter().filter(|s| unique(s.decl)).take(per_kind) {
+            questions.push(Question {
+                id: format!("definition:{}", subject.anchor),
+                kind: QuestionKind::Definition,
+                question: format!("Where is the {} `{}` defined?", subject.decl.kind.as_str(), subject.decl.name),
+                answer: format!("{}:{}-{}", subject.path, subject.decl.span.start_line, subject.decl.span.end_line),
+                locations: vec![Location::of(subject.path, subject.decl)],
+            });
+        }
+
+        let callers_of = |subject: &Subject| {
+            let path = RawPath::from_key(subject.path);
+            let sources = parsed.iter().map(|(p, f, s)| (p, f, *s));
+            related_symbols(sources, &path, subject.decl, usize::MAX)
+                .into_iter()
+                .filter(|r| r.relation == Relation::Caller)
+                .take(MAX_CALLERS)
+                .collect::<Vec<_>>()
+        };
+        let mut asked = 0;
+        for subject in subjects.iter().filter(|s| unique(s.decl) && callable(s.decl)) {
+            if asked == per_kind {
+                break;
+            }
+            let callers = callers_of(subject);
+            if callers.is_empty() {
+                continue;
+            }
+            let names: Vec<String> = callers.iter().map(|c| format!("`{}` ({})", c.name, c.path)).collect();
+            questions.push(Question {
+                id: format!("callers:{}", subject.anchor),
+                kind: QuestionKind::Callers,
+                question: format!("What calls `{}`?", subject.decl.name),
+                answer: names.join(", "),
+                locations: callers
+                    .iter()
+                    .map(|c| Location { path: c.path.clone(), start_line: c.span.start_line, end_line: c.span.end_line })
+                    .collect(),
+            });
+            asked += 1;
+        }
+
+        let returning = subjects
+            .iter()
+            .filter_map(|s| Some((s, s.decl.return_type.as_deref()?.trim())))
+            .filter(|(s, returns)| callable(s.decl) && !returns.is_empty());
+        for (subject, returns) in returning.take(per_kind) {
+            let signature_end = subject.decl.signature_span.map_or(subject.decl.span.start_line, |s| s.end_line);
+            questions.push(Question {
+                id: format!("returns:{}", subject.anchor),
+                kind: QuestionKind::Returns,
+                question: format!("What does `{}` in {} return?", subject.decl.name, subject.path),
+                answer: format!("`{}`", returns.trim_start_matches("->").trim()),
+                locations: vec![Location {
+                    path: subject.path.to_string(),
+                    start_line: subject.decl.span.start_line,
+                    end_line: signature_end,
+                }],
+            });
+        }
+
+        Self { questions }
+    }
+
+    pub fn len(&self) -> usize {
+        self.questions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.questions.is_empty()
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// Markdown document: numbered questions grouped by kind, each with its answer and ground truth
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# Question Pack\n\n{} questions.\n", self.questions.len());
+        let mut current = None;
+        for (number, question) in self.questions.iter().enumerate() {
+            if current != Some(question.kind) {
+                out.push_str(&format!("\n## {}\n\n", question.kind.as_str()));
+                current = Some(question.kind);
+            }
+            let locations: Vec<String> =
+                question.locations.iter().map(|l| format!("{}:{}-{}", l.path, l.start_line, l.end_line)).collect();
+            out.push_str(&format!(
+                "{}. {}\n   - Answer: {}\n   - Ground truth: {}\n",
+                number + 1,
+                question.question,
+                question.answer,
+                locations.join(", ")
+            ));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_question_pack_has_ground_truth() {
+        let files = vec![
+            ("src/config.rs".to_string(), "pub fn load(path: &str) -> usize {\n    parse(path)\n}\n\nfn parse(text: &str) -> usize {\n    text.len()\n}\n\npub struct Config;\n".to_string()),
+            ("src/main.rs".to_string(), "fn main() {\n    let config = load(\"a\");\n}\n\n#[test]\nfn test_load() {\n    load(\"b\");\n}\n".to_string()),
+        ];
+        let pack = QuestionPack::generate(&files, 10);
+
+        let definition = pack.questions.iter().find(|q| q.question == "Where is the struct `Config` defined?").unwrap();
+        assert_eq!(definition.answer, "src/config.rs:9-9");
+        assert!(definition.id.starts_with("definition:src/config.rs#Config@"));
+        assert!(!pack.questions.iter().any(|q| q.question.contains("test_load")), "tests are never the subject");
+
+        let callers = pack.questions.iter().find(|q| q.question == "What calls `load`?").unwrap();
+        assert_eq!(callers.answer, "`main` (src/main.rs), `test_load` (src/main.rs)");
+        assert_eq!(callers.locations[0].start_line, 1);
+
+        let returns = pack.questions.iter().find(|q| q.id.starts_with("returns:src/config.rs#parse@")).unwrap();
+        assert_eq!(returns.answer, "`usize`");
+
+        assert!(QuestionPack::generate(&files, 0).is_empty());
+        assert!(pack.to_markdown().contains("\n## callers\n\n"));
+    }
+}
@@ -0,0 +1,187 @@
+//! Session Replay - The contexts a zoom session served, step by step
+//!
+//! Re-runs the history of a persisted [`ZoomSession`] against the project
+//! (typically checked out, or read through a git view, at the commit the
+//! session ran on) and renders, for every step in order, when it happened,
+//! which zooms were active afterwards, and the exact context an expansion
+//! served. This is the audit trail for "why did the assistant answer that":
+//! it shows what the model was looking at, not what the code is today.
+//!
+//! Function and class targets are resolved to the declaration's lines, as
+//! `--zoom` resolves them before serializing. Steps past the history
+//! position were undone; they are replayed too, since the model saw them,
+//! and marked as undone. A target that no longer resolves records its error
+//! instead of a context.
+
+use std::path::Path;
+
+use chrono::DateTime;
+use serde::Serialize;
+
+use crate::core::engine::ContextEngine;
+use crate::core::search::SymbolResolver;
+use crate::core::zoom::{ZoomConfig, ZoomDepth, ZoomDirection, ZoomSession, ZoomTarget};
+
+/// One replayed history entry
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayStep {
+    /// Position in the history (1-indexed)
+    pub step: usize,
+    /// Unix time of the action
+    pub timestamp: u64,
+    pub direction: ZoomDirection,
+    pub target: ZoomTarget,
+    /// Depth expanded to, if recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth: Option<ZoomDepth>,
+    /// The step was later undone
+    pub undone: bool,
+    /// Zooms active after the step
+    pub active: Vec<ZoomTarget>,
+    /// Context served by an expansion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// Why the expansion could not be reproduced
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Replay of a zoom session
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionReplay {
+    pub session: String,
+    pub steps: Vec<ReplayStep>,
+}
+
+impl SessionReplay {
+    /// Replay `session` against the project at `root`, as `engine` reads it
+    pub fn replay(session: &ZoomSession, engine: &ContextEngine, root: &str) -> Self {
+        let position = session.history.position();
+        let mut active: Vec<ZoomTarget> = Vec::new();
+        let mut steps = Vec::new();
+        for (index, entry) in session.history.entries().iter().enumerate() {
+            let (mut context, mut error) = (None, None);
+            match entry.direction {
+                ZoomDirection::Expand => {
+                    if !active.contains(&entry.target) {
+                        active.push(entry.target.clone());
+                    }
+                    let config = ZoomConfig {
+                        target: resolve(&entry.target, root),
+                        depth: entry.depth.unwrap_or(ZoomDepth::Full),
+                        ..ZoomConfig::default()
+                    };
+                    match engine.zoom(root, &config) {
+                        Ok(served) => context = Some(served),
+                        Err(e) => error = Some(e.to_string()),
+                    }
+                }
+                ZoomDirection::Collapse => active.retain(|t| t != &entry.target),
+            }
+            steps.push(ReplayStep {
+                step: index + 1,
+                timestamp: entry.timestamp,
+                direction: entry.direction,
+                target: entry.target.clone(),
+                depth: entry.depth,
+                undone: index >= position,
+                active: active.clone(),
+                context,
+                error,
+            });
+        }
+        Self { session: session.name.clone(), steps }
+    }
+
+    /// Plain-text transcript: a header per step, then the context it served
+    pub fn render(&self) -> String {
+        let mut out = format!("Replay of zoom session '{}': {} steps\n", self.session, self.steps.len());
+        for step in &self.steps {
+            let time = DateTime::from_timestamp(step.timestamp as i64, 0)
+                .map_or_else(|| step.timestamp.to_string(), |t| t.to_rfc3339());
+            let action = match step.direction {
+                ZoomDirection::Expand => "expand",
+                ZoomDirection::Collapse => "collapse",
+            };
+            out.push_str(&format!("\n=== Step {}/{} [{}] {} {}", step.step, self.steps.len(), time, action, step.target));
+            if let Some(depth) = step.depth {
+                out.push_str(&format!(" ({:?})", depth).to_lowercase());
+            }
+            if step.undone {
+                out.push_str(" (undone)");
+            }
+            out.push_str(" ===\n");
+            let active: Vec<String> = step.active.iter().map(ToString::to_string).collect();
+            out.push_str(&format!("Active: {}\n", if active.is_empty() { "none".to_string() } else { active.join(", ") }));
+            if let Some(context) = &step.context {
+                out.push('\n');
+                out.push_str(context);
+                if !context.ends_with('\n') {
+                    out.push('\n');
+                }
+            }
+            if let Some(error) = &step.error {
+                out.push_str(&format!("Not reproducible: {}\n", error));
+            }
+        }
+        out
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// A function or class target as the declaration's line range, otherwise the target itself
+pub(crate) fn resolve(target: &ZoomTarget, root: &str) -> ZoomTarget {
+    let resolver = SymbolResolver::new();
+    let location = match target {
+        ZoomTarget::Function(name) => resolver.find_function(name, Path::new(root)),
+        ZoomTarget::Class(name) => resolver.find_class(name, Path::new(root)),
+        _ => return target.clone(),
+    };
+    match location {
+        Ok(location) => ZoomTarget::File {
+            path: location.path,
+            start_line: Some(location.start_line),
+            end_line: Some(location.end_line),
+        },
+        Err(_) => target.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_replay_serves_each_expansion() {
+        let dir = TempDir::new().unwrap();
+        // `save` lies beyond the default context lines around `load`
+        let source = "def load():\n    return 1\n\n\ndef middle():\n    a = 1\n    b = 2\n    return a + b\n\n\ndef save():\n    return 2\n";
+        std::fs::write(dir.path().join("lib.py"), source).unwrap();
+
+        let mut session = ZoomSession::new("debug");
+        session.add_zoom(ZoomTarget::Function("load".to_string()), ZoomDepth::Full);
+        session.add_zoom(ZoomTarget::Function("missing".to_string()), ZoomDepth::Full);
+        session.remove_zoom(&ZoomTarget::Function("load".to_string()));
+        session.history.undo();
+
+        let root = dir.path().to_string_lossy();
+        let replay = SessionReplay::replay(&session, &ContextEngine::new(), &root);
+        assert_eq!(replay.steps.len(), 3);
+
+        let first = &replay.steps[0];
+        assert!(first.context.as_deref().unwrap().contains("def load():"));
+        assert!(!first.context.as_deref().unwrap().contains("def save():"));
+        assert!(replay.steps[1].error.is_some());
+        assert_eq!(replay.steps[2].active, vec![ZoomTarget::Function("missing".to_string())]);
+        assert!(replay.steps[2].undone);
+
+        let text = replay.render();
+        assert!(text.starts_with("Replay of zoom session 'debug': 3 steps\n"));
+        assert!(text.contains("collapse function:load (undone) ===\nActive: function:missing\n"));
+    }
+}
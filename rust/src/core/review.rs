@@ -0,0 +1,323 @@
+//! PR-review context pack
+//!
+//! Combines a [`ChangeSet`] with the reverse call graph into one document a
+//! reviewer (human or model) can read top to bottom: a checklist of things
+//! to verify, changed functions no test exercises, the full bodies of
+//! changed declarations, the signatures of their callers, and finally the
+//! raw diff.
+//!
+//! Callers and tests are found in the working tree, so check out `head` for
+//! accurate results.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use voyager_ast::testmap::is_test_path;
+use voyager_ast::{AdapterRegistry, AstProvider, Declaration, IndexOptions, PlanetariumModel, TreeSitterProvider};
+
+use super::changes::{is_dependency_manifest, signature_text, ChangeSet, DiffSource, FileStatus, SymbolChange, SymbolChangeKind};
+use super::engine::FileTier;
+use super::search::UsageFinder;
+
+/// Limits for [`ReviewPack::build`]
+#[derive(Debug, Clone)]
+pub struct ReviewConfig {
+    /// Callers listed per changed symbol
+    pub max_callers: usize,
+    /// Changed symbols whose callers are looked up (public ones first)
+    pub max_symbols_with_callers: usize,
+    /// Longer bodies are cut with a marker
+    pub max_body_lines: usize,
+}
+
+impl Default for ReviewConfig {
+    fn default() -> Self {
+        Self { max_callers: 5, max_symbols_with_callers: 20, max_body_lines: 200 }
+    }
+}
+
+/// A call site of a changed symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallerSignature {
+    pub path: String,
+    pub line: usize,
+    /// Signature of the enclosing declaration (the call line if there is none)
+    pub signature: String,
+}
+
+/// One checklist question and its answer
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChecklistItem {
+    pub question: &'static str,
+    pub answer: bool,
+    /// Evidence (paths or symbols), or a warning
+    pub detail: String,
+}
+
+/// Everything needed to review a revision range
+#[derive(Debug, Clone)]
+pub struct ReviewPack {
+    pub changes: ChangeSet,
+    /// Callers per changed symbol, parallel to `changes.symbols`
+    pub callers: Vec<Vec<CallerSignature>>,
+    pub checklist: Vec<ChecklistItem>,
+    /// Indices into `changes.symbols` of changed functions with no tests
+    pub untested: Vec<usize>,
+    max_body_lines: usize,
+}
+
+impl ReviewPack {
+    /// Diff `range` (`base..head`) in the repository at `root`
+    pub fn build(root: &Path, range: &str, config: &ReviewConfig) -> Result<Self, String> {
+        let changes = ChangeSet::load(root, DiffSource::parse_range(range)?)?;
+
+        let mut order: Vec<usize> = (0..changes.symbols.len())
+            .filter(|&i| changes.symbols[i].change != SymbolChangeKind::Added)
+            .collect();
+        order.sort_by_key(|&i| !changes.symbols[i].public);
+        order.truncate(config.max_symbols_with_callers);
+
+        let finder = UsageFinder::new().with_max_results(config.max_callers);
+        let mut enclosing = EnclosingIndex::new(root);
+        let mut callers = vec![Vec::new(); changes.symbols.len()];
+        for i in order {
+            let symbol = &changes.symbols[i];
+            callers[i] = finder
+                .find_usages(symbol.short_name(), root, Some(&symbol.path), Some(symbol.start_line))
+                .into_iter()
+                .filter(|usage| !(usage.path == symbol.path && (symbol.start_line..=symbol.end_line).contains(&usage.line)))
+                .map(|usage| CallerSignature {
+                    signature: enclosing.signature(&usage.path, usage.line).unwrap_or(usage.snippet),
+                    path: usage.path,
+                    line: usage.line,
+                })
+                .collect();
+        }
+
+        let model = TreeSitterProvider::new().index_project(root, &IndexOptions::default())
+            .map_err(|e| e.to_string())?;
+        let untested = untested(&changes, &model);
+        let mut checklist = checklist(&changes);
+        checklist.push(ChecklistItem {
+            question: "Changed code has tests",
+            answer: untested.is_empty(),
+            detail: if untested.is_empty() {
+                String::new()
+            } else {
+                format!("⚠ {} changed functions with no tests", untested.len())
+            },
+        });
+        Ok(Self { changes, callers, checklist, untested, max_body_lines: config.max_body_lines })
+    }
+
+    /// Markdown document: summary, checklist, declarations with callers, diff
+    pub fn render(&self) -> String {
+        let changes = &self.changes;
+        let mut out = format!("# Review: {}\n\n", changes.source.label());
+        out.push_str(&format!(
+            "{} files changed (+{} -{}), {} declarations changed\n\n",
+            changes.files.len(),
+            changes.lines_added(),
+            changes.lines_removed(),
+            changes.symbols.len()
+        ));
+
+        out.push_str("## Checklist\n\n");
+        for item in &self.checklist {
+            out.push_str(&format!("- [{}] {}", if item.answer { "x" } else { " " }, item.question));
+            if !item.detail.is_empty() {
+                out.push_str(&format!(" — {}", item.detail));
+            }
+            out.push('\n');
+        }
+
+        if !self.untested.is_empty() {
+            out.push_str("\n## Risky changes: no tests\n\n");
+            for &i in &self.untested {
+                let symbol = &changes.symbols[i];
+                out.push_str(&format!("- {}:{} `{}` ({})\n", symbol.path, symbol.start_line, symbol.name, symbol.change.as_str()));
+            }
+        }
+
+        if !changes.symbols.is_empty() {
+            out.push_str("\n## Changed declarations\n");
+            for (symbol, callers) in changes.symbols.iter().zip(&self.callers) {
+                out.push_str(&self.render_symbol(symbol, callers));
+            }
+        }
+
+        out.push_str("\n## Diff\n\n```diff\n");
+        for file in &changes.files {
+            out.push_str(&file.patch);
+        }
+        out.push_str("```\n");
+        out
+    }
+
+    fn render_symbol(&self, symbol: &SymbolChange, callers: &[CallerSignature]) -> String {
+        let mut out = format!(
+            "\n### {} `{}` — {} {}{}\n\n",
+            symbol.path,
+            symbol.name,
+            symbol.change.as_str(),
+            symbol.kind,
+            if symbol.public { ", public" } else { "" }
+        );
+        if let Some(old) = &symbol.old_signature {
+            out.push_str(&format!("Signature changed from `{}`\n\n", old));
+        }
+        let lang = Path::new(&symbol.path).extension().and_then(|e| e.to_str()).unwrap_or("");
+        let lines: Vec<&str> = symbol.body.lines().collect();
+        out.push_str(&format!("```{}\n", lang));
+        for line in lines.iter().take(self.max_body_lines) {
+            out.push_str(line);
+            out.push('\n');
+        }
+        if lines.len() > self.max_body_lines {
+            out.push_str(&format!("// ... {} more lines\n", lines.len() - self.max_body_lines));
+        }
+        out.push_str("```\n");
+
+        if !callers.is_empty() {
+            out.push_str("\nCallers:\n");
+            for caller in callers {
+                out.push_str(&format!("- {}:{} `{}`\n", caller.path, caller.line, caller.signature));
+            }
+        }
+        out
+    }
+}
+
+/// Answer the reviewer checklist from the change set
+fn checklist(changes: &ChangeSet) -> Vec<ChecklistItem> {
+    let paths = |pred: fn(&str) -> bool| -> Vec<String> {
+        changes.files.iter().filter(|f| pred(&f.path)).map(|f| f.path.clone()).collect()
+    };
+    let item = |question: &'static str, found: Vec<String>| ChecklistItem { question, answer: !found.is_empty(), detail: found.join(", ") };
+
+    let core_changed = !paths(|p| FileTier::classify(p, None) == FileTier::Core).is_empty();
+    let mut tests_item = item("Tests touched", paths(|p| FileTier::classify(p, None) == FileTier::Tests));
+    if !tests_item.answer && core_changed {
+        tests_item.detail = "⚠ source changed without test changes".to_string();
+    }
+
+    let api: Vec<String> = changes.symbols
+        .iter()
+        .filter(|s| s.public && (s.change != SymbolChangeKind::Modified || s.signature_changed()))
+        .map(|s| format!("{} `{}` ({})", s.path, s.name, if s.signature_changed() { "signature" } else { s.change.as_str() }))
+        .collect();
+
+    let migrations = paths(|p| {
+        let lower = p.to_lowercase();
+        lower.contains("migration") || lower.contains("migrate/") || lower.ends_with(".sql")
+    });
+    let dependencies = paths(is_dependency_manifest);
+    let docs = paths(|p| {
+        let lower = p.to_lowercase();
+        lower.ends_with(".md") || lower.contains("changelog") || lower.starts_with("docs/")
+    });
+    let deleted: Vec<String> = changes.files
+        .iter()
+        .filter(|f| f.status == FileStatus::Deleted)
+        .map(|f| f.path.clone())
+        .collect();
+
+    vec![
+        tests_item,
+        item("Public API changed", api),
+        item("Migrations present", migrations),
+        item("Dependencies changed", dependencies),
+        item("Docs or changelog updated", docs),
+        item("Files deleted", deleted),
+    ]
+}
+
+/// Added or modified functions outside test files that no test exercises
+fn untested(changes: &ChangeSet, model: &PlanetariumModel) -> Vec<usize> {
+    changes.symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.change != SymbolChangeKind::Removed && matches!(s.kind.as_str(), "function" | "method"))
+        .filter(|(_, s)| !is_test_path(&s.path) && model.tests_for(s.short_name()).is_empty())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Parsed working-tree files, for finding the declaration around a line
+struct EnclosingIndex<'a> {
+    root: &'a Path,
+    registry: AdapterRegistry,
+    files: HashMap<String, Option<(String, voyager_ast::File)>>,
+}
+
+impl<'a> EnclosingIndex<'a> {
+    fn new(root: &'a Path) -> Self {
+        Self { root, registry: AdapterRegistry::new(), files: HashMap::new() }
+    }
+
+    fn signature(&mut self, path: &str, line: usize) -> Option<String> {
+        let (root, registry) = (self.root, &self.registry);
+        let parsed = self.files.entry(path.to_string()).or_insert_with(|| {
+            let source = std::fs::read_to_string(root.join(path)).ok()?;
+            let ext = Path::new(path).extension()?.to_str()?;
+            let file = registry.parse(&source, voyager_ast::LanguageId::from_extension(ext)).ok()?;
+            Some((source, file))
+        });
+        let (source, file) = parsed.as_ref()?;
+        innermost(&file.declarations, line).map(|decl| signature_text(decl, source))
+    }
+}
+
+fn innermost(decls: &[Declaration], line: usize) -> Option<&Declaration> {
+    let decl = decls.iter().find(|d| d.span.start_line <= line && line <= d.span.end_line)?;
+    innermost(&decl.children, line).or(Some(decl))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git").arg("-C").arg(dir).args(args).status().unwrap();
+        assert!(status.success(), "git {:?}", args);
+    }
+
+    #[test]
+    fn test_review_pack() {
+        if Command::new("git").arg("--version").output().is_err() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        git(root, &["config", "user.email", "dev@example.com"]);
+        git(root, &["config", "user.name", "dev"]);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn add(a: u32) -> u32 {\n    a\n}\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {\n    add(1);\n}\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "base"]);
+        git(root, &["tag", "base"]);
+
+        std::fs::write(root.join("src/lib.rs"), "pub fn add(a: u32, b: u32) -> u32 {\n    a + b\n}\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {\n    add(1, 2);\n}\n").unwrap();
+        git(root, &["commit", "-q", "-am", "head"]);
+
+        let pack = ReviewPack::build(root, "base..HEAD", &ReviewConfig::default()).unwrap();
+        let add = pack.changes.symbols.iter().position(|s| s.name == "add").expect("add changed");
+        assert!(pack.changes.symbols[add].signature_changed());
+        assert!(pack.callers[add].iter().any(|c| c.path == "src/main.rs" && c.signature.contains("fn main")));
+
+        let api = pack.checklist.iter().find(|i| i.question == "Public API changed").unwrap();
+        assert!(api.answer);
+        let tests = pack.checklist.iter().find(|i| i.question == "Tests touched").unwrap();
+        assert!(!tests.answer && tests.detail.contains("without test"));
+        assert!(pack.untested.contains(&add));
+
+        let doc = pack.render();
+        assert!(doc.starts_with("# Review: base..HEAD"));
+        assert!(doc.contains("## Checklist") && doc.contains("Callers:") && doc.contains("```diff"));
+        assert!(doc.contains("## Risky changes: no tests\n\n- src/lib.rs:1 `add` (modified)"));
+    }
+}
@@ -0,0 +1,223 @@
+//! Project Sampling - A small, representative, reproducible slice
+//!
+//! For quick "what is this project?" questions the whole codebase is too
+//! much and a truncated prefix is unrepresentative. The sampler fills a token
+//! budget with, in order:
+//!
+//! 1. **Entry points** - where execution starts, shallowest first
+//! 2. **Central files** - the most-imported files (import fan-in)
+//! 3. **Exemplars** - one file per directory not yet represented
+//!
+//! Ties (and exemplar choice among equally central files) are broken by a
+//! seeded hash, so the same seed always yields the same slice. The seed is
+//! recorded in the `SAMPLE.md` manifest at the top of the context (and in
+//! bundle manifests).
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::core::tour::TourGenerator;
+
+/// Path of the synthetic manifest describing the sample
+pub const SAMPLE_FILE_NAME: &str = "SAMPLE.md";
+
+/// Why a file was sampled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SampleReason {
+    EntryPoint,
+    Central { fan_in: usize },
+    Exemplar { directory: String },
+}
+
+impl SampleReason {
+    fn describe(&self) -> String {
+        match self {
+            SampleReason::EntryPoint => "entry point".to_string(),
+            SampleReason::Central { fan_in } => format!("central (imported by {} files)", fan_in),
+            SampleReason::Exemplar { directory } if directory.is_empty() => "exemplar of the project root".to_string(),
+            SampleReason::Exemplar { directory } => format!("exemplar of `{}`", directory),
+        }
+    }
+}
+
+/// A sampled file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SampledFile {
+    pub path: String,
+    pub reason: SampleReason,
+    pub tokens: usize,
+}
+
+/// A representative slice of a project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Sample {
+    pub seed: u64,
+    pub budget: usize,
+    /// Files in the project
+    pub total_files: usize,
+    /// Sampled files, in selection order
+    pub files: Vec<SampledFile>,
+}
+
+impl Sample {
+    /// Estimated tokens of the sampled files
+    pub fn used_tokens(&self) -> usize {
+        self.files.iter().map(|f| f.tokens).sum()
+    }
+
+    /// Check whether a path was sampled
+    pub fn contains(&self, path: &str) -> bool {
+        self.files.iter().any(|f| f.path == path)
+    }
+
+    /// Render the `SAMPLE.md` manifest
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Project Sample\n\n");
+        out.push_str(&format!(
+            "Seed: {} | Budget: {} tokens | Used: ~{} tokens | {} of {} files\n\n",
+            self.seed,
+            self.budget,
+            self.used_tokens(),
+            self.files.len(),
+            self.total_files
+        ));
+        out.push_str(&format!("Reproduce with `--sample {} --seed {}`.\n\n", self.budget, self.seed));
+        for file in &self.files {
+            out.push_str(&format!("- `{}` - {}\n", file.path, file.reason.describe()));
+        }
+        out
+    }
+}
+
+/// Pick a representative slice of `(path, content)` pairs within `budget` tokens
+///
+/// Files that do not fit are skipped, so smaller candidates later in the
+/// order can still be sampled.
+pub fn sample(files: &[(String, String)], budget: usize, seed: u64) -> Sample {
+    let signals = TourGenerator::file_signals(files);
+    let tokens: Vec<usize> = files.iter().map(|(_, content)| content.len() / 4).collect();
+    let rank = |i: usize| seeded_hash(seed, &signals[i].path);
+
+    let mut candidates: Vec<(usize, SampleReason)> = Vec::new();
+
+    let mut entries: Vec<usize> = (0..signals.len()).filter(|&i| signals[i].is_entry).collect();
+    entries.sort_by_key(|&i| (depth(&signals[i].path), rank(i)));
+    candidates.extend(entries.into_iter().map(|i| (i, SampleReason::EntryPoint)));
+
+    let mut central: Vec<usize> = (0..signals.len())
+        .filter(|&i| signals[i].fan_in > 0 && !signals[i].is_test)
+        .collect();
+    central.sort_by_key(|&i| (std::cmp::Reverse(signals[i].fan_in), rank(i)));
+    candidates.extend(central.into_iter().map(|i| (i, SampleReason::Central { fan_in: signals[i].fan_in })));
+
+    let mut directories: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, file) in signals.iter().enumerate().filter(|(_, f)| !f.is_test) {
+        directories.entry(directory(&file.path).to_string()).or_default().push(i);
+    }
+    let mut directories: Vec<(String, Vec<usize>)> = directories.into_iter().collect();
+    directories.sort_by_key(|(dir, _)| (if dir.is_empty() { 0 } else { depth(dir) + 1 }, dir.clone()));
+
+    let mut picked: Vec<SampledFile> = Vec::new();
+    let mut chosen: HashSet<usize> = HashSet::new();
+    let mut remaining = budget;
+    let mut take = |i: usize, reason: SampleReason, picked: &mut Vec<SampledFile>| {
+        if tokens[i] <= remaining && chosen.insert(i) {
+            remaining -= tokens[i];
+            picked.push(SampledFile { path: signals[i].path.clone(), reason, tokens: tokens[i] });
+            true
+        } else {
+            false
+        }
+    };
+
+    for (i, reason) in candidates {
+        take(i, reason, &mut picked);
+    }
+    for (dir, mut members) in directories {
+        if members.iter().any(|i| picked.iter().any(|p| p.path == signals[*i].path)) {
+            continue;
+        }
+        members.sort_by_key(|&i| (std::cmp::Reverse(signals[i].fan_in), rank(i)));
+        for i in members {
+            if take(i, SampleReason::Exemplar { directory: dir.clone() }, &mut picked) {
+                break;
+            }
+        }
+    }
+
+    Sample { seed, budget, total_files: files.len(), files: picked }
+}
+
+fn directory(path: &str) -> &str {
+    path.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+fn depth(path: &str) -> usize {
+    path.matches('/').count()
+}
+
+/// FNV-1a of the path, mixed with the seed (SplitMix64 finalizer)
+fn seeded_hash(seed: u64, path: &str) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in path.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    let mut z = hash ^ seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> Vec<(String, String)> {
+        let mut files = vec![
+            ("src/main.rs".to_string(), "use crate::engine::run;\n\nfn main() {\n    run();\n}\n".to_string()),
+            ("src/engine.rs".to_string(), "pub fn run() {}\n".to_string()),
+            ("tests/engine_test.rs".to_string(), "use engine;\n#[test]\nfn test_run() {}\n".to_string()),
+        ];
+        for name in ["a", "b", "c", "d"] {
+            files.push((format!("src/util/{}.rs", name), format!("pub fn {}() {{}}\n", name)));
+        }
+        files
+    }
+
+    #[test]
+    fn test_sample_order_and_reasons() {
+        let sample = sample(&project(), 10_000, 7);
+        assert_eq!(sample.files[0].path, "src/main.rs");
+        assert_eq!(sample.files[0].reason, SampleReason::EntryPoint);
+        assert!(sample.contains("src/engine.rs"));
+        assert!(!sample.contains("tests/engine_test.rs"));
+
+        let exemplars: Vec<&SampledFile> = sample
+            .files
+            .iter()
+            .filter(|f| matches!(&f.reason, SampleReason::Exemplar { directory } if directory == "src/util"))
+            .collect();
+        assert_eq!(exemplars.len(), 1);
+        assert!(sample.to_markdown().contains("Seed: 7"));
+    }
+
+    #[test]
+    fn test_sample_is_deterministic_and_budgeted() {
+        let files = project();
+        assert_eq!(sample(&files, 10_000, 1), sample(&files, 10_000, 1));
+
+        let picks: HashSet<String> = (0..16)
+            .map(|seed| {
+                let s = sample(&files, 10_000, seed);
+                s.files.iter().find(|f| matches!(f.reason, SampleReason::Exemplar { .. })).unwrap().path.clone()
+            })
+            .collect();
+        assert!(picks.len() > 1, "seed should vary the exemplar");
+
+        let tight = sample(&files, 5, 1);
+        assert!(tight.used_tokens() <= 5);
+    }
+}
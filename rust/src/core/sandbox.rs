@@ -0,0 +1,174 @@
+//! Sandbox - Keep user-supplied paths inside a declared root
+//!
+//! Zoom targets and server parameters name files and directories chosen by
+//! the caller. [`PathSandbox`] resolves them against one root and rejects
+//! anything that would leave it, with [`EncoderError::PolicyViolation`]:
+//!
+//! - absolute paths outside the root (`/etc/passwd`, `C:\...`,
+//!   `\\server\share`); absolute paths inside it resolve to their
+//!   root-relative form, as editors send them
+//! - `..` segments that climb above the root (`src/../../x`); segments that
+//!   stay inside (`src/a/../b.rs`) are normalized away
+//! - symlinks inside the root that point outside it, checked on the
+//!   canonical path of the deepest existing ancestor
+//!
+//! Paths that do not exist yet are allowed as long as their existing prefix
+//! is inside the root, so "not found" errors stay distinct from violations.
+
+use std::path::{Component, Path, PathBuf};
+
+use super::error::{EncoderError, Result};
+use super::paths::normalize_relative_path;
+use super::zoom::ZoomTarget;
+
+/// Resolves untrusted paths against one root
+#[derive(Debug, Clone)]
+pub struct PathSandbox {
+    /// Canonical root
+    root: PathBuf,
+}
+
+impl PathSandbox {
+    /// Sandbox rooted at `root`, which must exist
+    pub fn new(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        let canonical = root
+            .canonicalize()
+            .map_err(|_| EncoderError::DirectoryNotFound { path: root.to_path_buf() })?;
+        Ok(Self { root: canonical })
+    }
+
+    /// Canonical root
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolve a root-relative path, returning its normalized key (`src/lib.rs`)
+    ///
+    /// Absolute paths are accepted when they lie inside the root.
+    pub fn resolve_relative(&self, path: &str) -> Result<String> {
+        if path.contains('\0') {
+            return Err(EncoderError::policy_violation(path, "path contains a NUL byte"));
+        }
+        if is_absolute(path) {
+            let resolved = self.resolve(path)?;
+            let relative = resolved.strip_prefix(&self.root).unwrap_or(&resolved);
+            return Ok(normalize_relative_path(&relative.to_string_lossy()));
+        }
+        let key = normalize_relative_path(path);
+        if key == ".." || key.starts_with("../") {
+            return Err(EncoderError::policy_violation(path, "path escapes the root"));
+        }
+        if !self.canonical(path, &self.root.join(&key))?.starts_with(&self.root) {
+            return Err(EncoderError::policy_violation(path, "symlink points outside the root"));
+        }
+        Ok(key)
+    }
+
+    /// Resolve a path that may be absolute (inside the root) or root-relative
+    ///
+    /// Used for directory overrides such as the MCP `path` parameter.
+    pub fn resolve(&self, path: &str) -> Result<PathBuf> {
+        if !is_absolute(path) {
+            return Ok(self.root.join(self.resolve_relative(path)?));
+        }
+        let candidate = Path::new(path);
+        if candidate.components().any(|c| c == Component::ParentDir) {
+            return Err(EncoderError::policy_violation(path, "path escapes the root"));
+        }
+        let resolved = self.canonical(path, candidate)?;
+        if !resolved.starts_with(&self.root) {
+            return Err(EncoderError::policy_violation(path, "path is outside the root"));
+        }
+        Ok(resolved)
+    }
+
+    /// Check every file target of a zoom request
+    pub fn check_target(&self, target: &ZoomTarget) -> Result<()> {
+        if let ZoomTarget::File { path, .. } = target {
+            self.resolve_relative(path)?;
+        }
+        Ok(())
+    }
+
+    /// Canonical form of `candidate`, resolving symlinks in its deepest existing ancestor
+    fn canonical(&self, original: &str, candidate: &Path) -> Result<PathBuf> {
+        let mut existing = candidate;
+        let mut missing = Vec::new();
+        let canonical = loop {
+            match existing.canonicalize() {
+                Ok(canonical) => break canonical,
+                Err(_) => match (existing.parent(), existing.file_name()) {
+                    (Some(parent), Some(name)) => {
+                        missing.push(name.to_os_string());
+                        existing = parent;
+                    }
+                    _ => return Err(EncoderError::policy_violation(original, "path cannot be resolved")),
+                },
+            }
+        };
+        Ok(missing.into_iter().rev().fold(canonical, |path, name| path.join(name)))
+    }
+}
+
+/// Absolute on any platform: `/x`, `\x`, `C:x`, `C:\x`, `\\server\share`
+fn is_absolute(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with('/')
+        || path.starts_with('\\')
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+        || Path::new(path).is_absolute()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn is_violation(result: Result<String>) -> bool {
+        matches!(result, Err(EncoderError::PolicyViolation { .. }))
+    }
+
+    #[test]
+    fn test_rejects_traversal_and_absolute_paths() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        fs::write(temp.path().join("src/lib.rs"), "").unwrap();
+        let sandbox = PathSandbox::new(temp.path()).unwrap();
+
+        assert_eq!(sandbox.resolve_relative("./src/a/../lib.rs").unwrap(), "src/lib.rs");
+        assert_eq!(sandbox.resolve_relative("src/not_yet.rs").unwrap(), "src/not_yet.rs");
+        assert!(is_violation(sandbox.resolve_relative("../outside.rs")));
+        assert!(is_violation(sandbox.resolve_relative(r"src\..\..\outside.rs")));
+        assert!(is_violation(sandbox.resolve_relative("/etc/passwd")));
+        assert!(is_violation(sandbox.resolve_relative(r"C:\Windows\win.ini")));
+        assert!(is_violation(sandbox.resolve_relative(r"\\server\share\x")));
+
+        let inside = temp.path().join("src");
+        assert_eq!(sandbox.resolve(inside.to_str().unwrap()).unwrap(), sandbox.root().join("src"));
+        // Editors send absolute paths; inside the root they resolve to the relative key
+        let absolute = temp.path().join("src/lib.rs");
+        assert_eq!(sandbox.resolve_relative(absolute.to_str().unwrap()).unwrap(), "src/lib.rs");
+        assert!(sandbox
+            .check_target(&ZoomTarget::File { path: absolute.to_string_lossy().into_owned(), start_line: None, end_line: None })
+            .is_ok());
+        assert!(sandbox.resolve(std::env::temp_dir().to_str().unwrap()).is_err());
+        assert!(sandbox
+            .check_target(&ZoomTarget::File { path: "../x.rs".to_string(), start_line: None, end_line: None })
+            .is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_rejects_symlink_escape() {
+        let temp = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(outside.path(), temp.path().join("link")).unwrap();
+        let sandbox = PathSandbox::new(temp.path()).unwrap();
+
+        assert!(is_violation(sandbox.resolve_relative("link/secret.txt")));
+        assert!(is_violation(sandbox.resolve_relative("link/missing.txt")));
+    }
+}
@@ -0,0 +1,301 @@
+//! Test Scaffolds - Empty test files framed from public signatures
+//!
+//! For the public functions and methods of each source file (as listed by
+//! the [API reference](crate::core::apiref)), emits a test file in the
+//! language's usual harness with one empty test per function: the signature
+//! in a comment and a TODO marker where the test goes. Nothing is inferred
+//! about behavior; the scaffold is the frame an LLM (or a person) fills in.
+//!
+//! | Language | Harness | Suggested file |
+//! |----------|---------|----------------|
+//! | Rust | `#[cfg(test)] mod tests` | the source file (append) |
+//! | Python | pytest | `tests/test_<stem>.py` |
+//! | TypeScript/JavaScript | Jest/Vitest `describe`/`test` | `<stem>.test.<ext>` beside the source |
+//! | Go | `testing` | `<stem>_test.go` beside the source |
+//! | Java | JUnit 5 | `<Stem>Test.java`, under `src/test` for `src/main` sources |
+//!
+//! Files of other languages, and files that are already tests, get none.
+
+use std::path::Path;
+
+use voyager_ast::testmap::is_test_path;
+use voyager_ast::LanguageId;
+
+use crate::core::apiref::{ApiItem, ApiModule, ApiReference};
+use crate::core::ast_bridge::AstBridge;
+
+/// TODO marker placed in every scaffolded test
+const TODO: &str = "TODO: arrange, act, assert";
+
+/// Test scaffold of one source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestScaffold {
+    /// Source file under test
+    pub source: String,
+    /// Suggested path of the test file
+    pub path: String,
+    pub language: LanguageId,
+    /// Anchors of the declarations given a test
+    pub tests: Vec<String>,
+    /// Test file contents
+    pub text: String,
+}
+
+/// A function to test: its test name, anchor, signature and the name to import
+struct Case {
+    test: String,
+    id: String,
+    signature: String,
+    import: String,
+}
+
+/// Scaffold tests for `(path, content)` pairs
+///
+/// `selection` narrows the declarations: a file path, a name, a qualified
+/// name (`Walker.new`) or an anchor; empty selects every public function.
+pub fn scaffold_tests(files: &[(String, String)], selection: &[String]) -> Vec<TestScaffold> {
+    let reference = ApiReference::build(files);
+    let mut scaffolds = Vec::new();
+    for module in &reference.modules {
+        if is_test_path(&module.path) {
+            continue;
+        }
+        let language = AstBridge::detect_language(Path::new(&module.path));
+        let content = files.iter().find(|(path, _)| *path == module.path).map_or("", |(_, c)| c.as_str());
+        let cases = cases(module, language, selection);
+        if !cases.is_empty() {
+            scaffolds.extend(scaffold(module, language, content, &cases));
+        }
+    }
+    scaffolds
+}
+
+/// Public functions of a module, top level and members of its types, in source order
+fn cases(module: &ApiModule, language: LanguageId, selection: &[String]) -> Vec<Case> {
+    let selected = |item: &ApiItem| {
+        let qualified = item.id.split_once('#').map_or("", |(_, q)| q.split('@').next().unwrap_or(q));
+        selection.is_empty()
+            || selection.iter().any(|s| *s == module.path || *s == item.name || s == qualified || *s == item.id)
+    };
+    let callable = |item: &ApiItem| item.kind == "function" || item.kind == "method";
+
+    let mut cases: Vec<Case> = Vec::new();
+    let push = |owner: Option<&str>, item: &ApiItem, cases: &mut Vec<Case>| {
+        let mut test = test_name(language, owner, &item.name);
+        let taken = |name: &str| cases.iter().any(|c: &Case| c.test == name);
+        if taken(&test) {
+            let mut n = 2;
+            while taken(&format!("{}_{}", test, n)) {
+                n += 1;
+            }
+            test = format!("{}_{}", test, n);
+        }
+        cases.push(Case {
+            test,
+            id: item.id.clone(),
+            signature: item.signature.clone(),
+            import: owner.unwrap_or(&item.name).to_string(),
+        });
+    };
+    for item in &module.items {
+        if callable(item) {
+            if selected(item) {
+                push(None, item, &mut cases);
+            }
+            continue;
+        }
+        // Trait and interface members are abstract: their implementations get the tests
+        if matches!(item.kind.as_str(), "trait" | "interface") {
+            continue;
+        }
+        for member in item.members.iter().filter(|m| callable(m) && (selected(m) || selected(item))) {
+            push(Some(&item.name), member, &mut cases);
+        }
+    }
+    cases
+}
+
+/// Test function name in the language's convention
+fn test_name(language: LanguageId, owner: Option<&str>, name: &str) -> String {
+    let words = match owner {
+        Some(owner) => format!("{}_{}", snake_case(owner), snake_case(name)),
+        None => snake_case(name),
+    };
+    match language {
+        LanguageId::Go => {
+            let camel: String = words
+                .split('_')
+                .flat_map(|w| {
+                    let mut chars = w.chars();
+                    chars.next().into_iter().flat_map(char::to_uppercase).chain(chars)
+                })
+                .collect();
+            format!("Test{}", camel)
+        }
+        LanguageId::Java => words,
+        LanguageId::TypeScript | LanguageId::JavaScript | LanguageId::Tsx | LanguageId::Jsx => {
+            owner.map_or_else(|| name.to_string(), |owner| format!("{}.{}", owner, name))
+        }
+        _ => format!("test_{}", words),
+    }
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 && !out.ends_with('_') {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out.trim_matches('_').to_string()
+}
+
+/// `package` declaration of a Go or Java file
+fn package(content: &str) -> Option<&str> {
+    content
+        .lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("package "))
+        .map(|name| name.trim_end_matches(';').trim())
+}
+
+fn scaffold(module: &ApiModule, language: LanguageId, content: &str, cases: &[Case]) -> Option<TestScaffold> {
+    let source = Path::new(&module.path);
+    let dir = source.parent().map_or(String::new(), |d| d.to_string_lossy().into_owned());
+    let join = |name: String| if dir.is_empty() { name } else { format!("{}/{}", dir, name) };
+    let stem = source.file_stem()?.to_string_lossy().into_owned();
+    let mut imports: Vec<&str> = Vec::new();
+    for case in cases {
+        if !imports.contains(&case.import.as_str()) {
+            imports.push(&case.import);
+        }
+    }
+
+    let (path, text) = match language {
+        LanguageId::Rust => {
+            let mut text = format!("// Test scaffold for {}: append to the file\n\n", module.path);
+            text.push_str("#[cfg(test)]\nmod tests {\n    use super::*;\n");
+            for case in cases {
+                text.push_str(&format!(
+                    "\n    #[test]\n    fn {}() {{\n        // {}\n        // {}\n    }}\n",
+                    case.test, case.signature, TODO
+                ));
+            }
+            text.push_str("}\n");
+            (module.path.clone(), text)
+        }
+        LanguageId::Python => {
+            let mut text = format!("# Test scaffold for {}\n\nfrom {} import {}\n", module.path, module.name, imports.join(", "));
+            for case in cases {
+                text.push_str(&format!("\n\ndef {}():\n    # {}\n    # {}\n    pass\n", case.test, case.signature, TODO));
+            }
+            (format!("tests/test_{}.py", stem), text)
+        }
+        LanguageId::TypeScript | LanguageId::JavaScript | LanguageId::Tsx | LanguageId::Jsx => {
+            let extension = source.extension().map_or("js".to_string(), |e| e.to_string_lossy().into_owned());
+            let mut text = format!(
+                "// Test scaffold for {}\n\nimport {{ {} }} from './{}';\n\ndescribe('{}', () => {{\n",
+                module.path,
+                imports.join(", "),
+                stem,
+                module.name
+            );
+            for (i, case) in cases.iter().enumerate() {
+                if i > 0 {
+                    text.push('\n');
+                }
+                text.push_str(&format!(
+                    "  test('{}', () => {{\n    // {}\n    // {}\n  }});\n",
+                    case.test, case.signature, TODO
+                ));
+            }
+            text.push_str("});\n");
+            (join(format!("{}.test.{}", stem, extension)), text)
+        }
+        LanguageId::Go => {
+            let mut text = format!(
+                "// Test scaffold for {}\n\npackage {}\n\nimport \"testing\"\n",
+                module.path,
+                package(content).unwrap_or("main")
+            );
+            for case in cases {
+                text.push_str(&format!(
+                    "\nfunc {}(t *testing.T) {{\n\t// {}\n\t// {}\n\tt.Skip(\"TODO\")\n}}\n",
+                    case.test, case.signature, TODO
+                ));
+            }
+            (join(format!("{}_test.go", stem)), text)
+        }
+        LanguageId::Java => {
+            let mut text = format!("// Test scaffold for {}\n\n", module.path);
+            if let Some(package) = package(content) {
+                text.push_str(&format!("package {};\n\n", package));
+            }
+            text.push_str(&format!("import org.junit.jupiter.api.Test;\n\nclass {}Test {{\n", stem));
+            for (i, case) in cases.iter().enumerate() {
+                if i > 0 {
+                    text.push('\n');
+                }
+                text.push_str(&format!(
+                    "    @Test\n    void {}() {{\n        // {}\n        // {}\n    }}\n",
+                    case.test, case.signature, TODO
+                ));
+            }
+            text.push_str("}\n");
+            (join(format!("{}Test.java", stem)).replacen("src/main/", "src/test/", 1), text)
+        }
+        _ => return None,
+    };
+
+    Some(TestScaffold {
+        source: module.path.clone(),
+        path,
+        language,
+        tests: cases.iter().map(|c| c.id.clone()).collect(),
+        text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scaffold_per_language_harness() {
+        let files = vec![
+            (
+                "src/walker.rs".to_string(),
+                "pub struct Walker;\n\nimpl Walker {\n    pub fn new() -> Self {\n        Walker\n    }\n\n    fn helper(&self) {}\n}\n\npub fn walk(root: &str) -> usize {\n    0\n}\n".to_string(),
+            ),
+            (
+                "app/models.py".to_string(),
+                "class User:\n    def full_name(self, sep: str) -> str:\n        return sep\n\ndef load(path):\n    pass\n\ndef _hidden():\n    pass\n".to_string(),
+            ),
+            ("tests/test_models.py".to_string(), "def test_load():\n    pass\n".to_string()),
+        ];
+        let scaffolds = scaffold_tests(&files, &[]);
+        assert_eq!(scaffolds.iter().map(|s| s.path.as_str()).collect::<Vec<_>>(), ["tests/test_models.py", "src/walker.rs"]);
+
+        let python = &scaffolds[0];
+        assert_eq!(python.source, "app/models.py");
+        assert!(python.text.contains("from app.models import User, load\n"));
+        assert!(python.text.contains("\ndef test_user_full_name():\n    # def full_name(self, sep: str) -> str\n    # TODO: arrange, act, assert\n    pass\n"));
+        assert!(!python.text.contains("hidden"));
+
+        let rust = &scaffolds[1];
+        assert_eq!(rust.tests.len(), 2);
+        assert!(rust.text.contains("    #[test]\n    fn test_walker_new() {\n        // pub fn new() -> Self\n"));
+        assert!(rust.text.contains("fn test_walk() {"));
+        assert!(!rust.text.contains("helper"));
+
+        let selected = scaffold_tests(&files, &["Walker.new".to_string()]);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].tests.len(), 1);
+        assert!(selected[0].tests[0].starts_with("src/walker.rs#Walker.new@"));
+    }
+}
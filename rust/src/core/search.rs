@@ -185,6 +185,31 @@ impl SymbolResolver {
         results
     }
 
+    /// Names of every symbol of `symbol_type`, for suggesting near misses
+    pub fn symbol_names(&self, symbol_type: SymbolType, root: &Path) -> Vec<String> {
+        let config = SmartWalkConfig {
+            max_file_size: 1_048_576,
+            ..Default::default()
+        };
+        let entries = SmartWalker::with_config(root, config).walk_as_file_entries().unwrap_or_default();
+
+        let mut names = Vec::new();
+        for entry in entries {
+            let Some(ext) = Path::new(&entry.path).extension().and_then(|e| e.to_str()) else { continue };
+            let Some(patterns) = Self::patterns_for(ext, symbol_type) else { continue };
+            for line in entry.content.lines() {
+                if let Some(caps) = patterns.iter().find_map(|pattern| pattern.captures(line)) {
+                    if let Some(name) = caps.get(1).or_else(|| caps.get(2)) {
+                        names.push(name.as_str().to_string());
+                    }
+                }
+            }
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
     /// Find a single symbol (returns first match or error)
     pub fn find_symbol(&self, name: &str, symbol_type: SymbolType, root: &Path) -> Result<SymbolLocation, String> {
         // Use SmartWalker to respect hygiene exclusions (.venv, node_modules, etc.)
@@ -245,26 +270,7 @@ impl SymbolResolver {
         ext: &str,
         all_lines: &[&str],
     ) -> Option<SymbolLocation> {
-        let patterns: Vec<&Regex> = match (ext, symbol_type) {
-            ("rs", SymbolType::Function) => vec![&RUST_FN],
-            ("rs", SymbolType::Struct) => vec![&RUST_STRUCT],
-            ("rs", SymbolType::Enum) => vec![&RUST_ENUM],
-            ("rs", SymbolType::Trait) => vec![&RUST_TRAIT],
-            ("rs", SymbolType::Class) => vec![&RUST_STRUCT, &RUST_ENUM], // Rust doesn't have classes
-
-            ("py" | "pyw", SymbolType::Function) => vec![&PYTHON_DEF],
-            ("py" | "pyw", SymbolType::Class) => vec![&PYTHON_CLASS],
-
-            ("js" | "jsx" | "ts" | "tsx" | "mjs", SymbolType::Function) => {
-                vec![&JS_FUNCTION, &JS_CONST_FN, &JS_METHOD]
-            }
-            ("js" | "jsx" | "ts" | "tsx" | "mjs", SymbolType::Class) => vec![&JS_CLASS],
-
-            ("go", SymbolType::Function) => vec![&GO_FUNC],
-            ("go", SymbolType::Class | SymbolType::Struct) => vec![&GO_TYPE],
-
-            _ => return None,
-        };
+        let patterns = Self::patterns_for(ext, symbol_type)?;
 
         for pattern in patterns {
             if let Some(caps) = pattern.captures(line) {
@@ -292,6 +298,31 @@ impl SymbolResolver {
         None
     }
 
+    /// Definition patterns for a file extension and symbol type
+    fn patterns_for(ext: &str, symbol_type: SymbolType) -> Option<Vec<&'static Regex>> {
+        let patterns: Vec<&Regex> = match (ext, symbol_type) {
+            ("rs", SymbolType::Function) => vec![&RUST_FN],
+            ("rs", SymbolType::Struct) => vec![&RUST_STRUCT],
+            ("rs", SymbolType::Enum) => vec![&RUST_ENUM],
+            ("rs", SymbolType::Trait) => vec![&RUST_TRAIT],
+            ("rs", SymbolType::Class) => vec![&RUST_STRUCT, &RUST_ENUM], // Rust doesn't have classes
+
+            ("py" | "pyw", SymbolType::Function) => vec![&PYTHON_DEF],
+            ("py" | "pyw", SymbolType::Class) => vec![&PYTHON_CLASS],
+
+            ("js" | "jsx" | "ts" | "tsx" | "mjs", SymbolType::Function) => {
+                vec![&JS_FUNCTION, &JS_CONST_FN, &JS_METHOD]
+            }
+            ("js" | "jsx" | "ts" | "tsx" | "mjs", SymbolType::Class) => vec![&JS_CLASS],
+
+            ("go", SymbolType::Function) => vec![&GO_FUNC],
+            ("go", SymbolType::Class | SymbolType::Struct) => vec![&GO_TYPE],
+
+            _ => return None,
+        };
+        Some(patterns)
+    }
+
     /// Find the end of a code block (heuristic based on brace/indent matching)
     fn find_block_end(&self, lines: &[&str], start_idx: usize, ext: &str) -> usize {
         if start_idx >= lines.len() {
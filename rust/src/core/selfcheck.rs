@@ -0,0 +1,416 @@
+//! Context Quality Self-Check - Are the embedded snippets still well-formed?
+//!
+//! Truncation cuts files at a line budget, and a cut in the middle of a
+//! string or block leaves a snippet that no longer reads as code. This pass
+//! re-reads serialized context block by block and reports truncated files
+//! whose text ends inside a string or comment, or with unbalanced
+//! `()`/`[]`/`{}`.
+//!
+//! The scan is lexical (delimiters, strings, comments per language family),
+//! not a full parse, so it is fast enough to run on every output. The same
+//! scan finds safe cut points: [`safe_line_limit`] pulls a line budget back
+//! to the nearest line after which nothing is left open.
+
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+use voyager_ast::LanguageId;
+
+use crate::core::ast_bridge::AstBridge;
+
+/// A lexical problem in a snippet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnippetIssue {
+    /// An opening delimiter that is never closed
+    UnclosedDelimiter { delimiter: char, line: usize },
+    /// A closing delimiter without a matching opener
+    UnexpectedCloser { delimiter: char, line: usize },
+    /// A string literal still open at the end of the snippet
+    UnterminatedString { line: usize },
+    /// A block comment still open at the end of the snippet
+    UnterminatedComment { line: usize },
+}
+
+impl fmt::Display for SnippetIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnippetIssue::UnclosedDelimiter { delimiter, line } => write!(f, "unclosed '{}' from line {}", delimiter, line),
+            SnippetIssue::UnexpectedCloser { delimiter, line } => write!(f, "unexpected '{}' at line {}", delimiter, line),
+            SnippetIssue::UnterminatedString { line } => write!(f, "unterminated string from line {}", line),
+            SnippetIssue::UnterminatedComment { line } => write!(f, "unterminated comment from line {}", line),
+        }
+    }
+}
+
+/// Lexical rules of a language family
+struct Syntax {
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    /// Python `"""`/`'''` strings
+    triple_quotes: bool,
+    /// Rust char literals, lifetimes and raw strings
+    rust_literals: bool,
+}
+
+fn syntax_for(language: LanguageId) -> Option<Syntax> {
+    const C_BLOCK: Option<(&str, &str)> = Some(("/*", "*/"));
+    let syntax = match language {
+        LanguageId::Rust => Syntax { line_comments: &["//"], block_comment: C_BLOCK, quotes: &['"'], triple_quotes: false, rust_literals: true },
+        LanguageId::JavaScript | LanguageId::TypeScript | LanguageId::Tsx | LanguageId::Jsx | LanguageId::Go => {
+            Syntax { line_comments: &["//"], block_comment: C_BLOCK, quotes: &['"', '\'', '`'], triple_quotes: false, rust_literals: false }
+        }
+        LanguageId::C | LanguageId::Cpp | LanguageId::Java | LanguageId::CSharp | LanguageId::Swift
+        | LanguageId::Kotlin | LanguageId::Scala => {
+            Syntax { line_comments: &["//"], block_comment: C_BLOCK, quotes: &['"', '\''], triple_quotes: false, rust_literals: false }
+        }
+        LanguageId::Php => Syntax { line_comments: &["//", "#"], block_comment: C_BLOCK, quotes: &['"', '\''], triple_quotes: false, rust_literals: false },
+        LanguageId::Css => Syntax { line_comments: &[], block_comment: C_BLOCK, quotes: &['"', '\''], triple_quotes: false, rust_literals: false },
+        // JSONC/JSON5: comments and single-quoted strings
+        LanguageId::Json => Syntax { line_comments: &["//"], block_comment: C_BLOCK, quotes: &['"', '\''], triple_quotes: false, rust_literals: false },
+        LanguageId::Python => Syntax { line_comments: &["#"], block_comment: None, quotes: &['"', '\''], triple_quotes: true, rust_literals: false },
+        LanguageId::Ruby | LanguageId::Bash => {
+            Syntax { line_comments: &["#"], block_comment: None, quotes: &['"', '\''], triple_quotes: false, rust_literals: false }
+        }
+        _ => return None,
+    };
+    Some(syntax)
+}
+
+/// Result of scanning a snippet
+struct Scan {
+    issues: Vec<SnippetIssue>,
+    /// `clean[i]`: nothing is open after line `i + 1`
+    clean: Vec<bool>,
+}
+
+fn scan(content: &str, syntax: &Syntax) -> Scan {
+    let chars: Vec<char> = content.chars().collect();
+    let starts_with = |i: usize, token: &str| token.chars().enumerate().all(|(k, c)| chars.get(i + k) == Some(&c));
+
+    let mut issues = Vec::new();
+    let mut clean = Vec::new();
+    let mut stack: Vec<(char, usize)> = Vec::new();
+    // Closing delimiter of the open string, and whether escapes apply
+    let mut string: Option<(String, bool, usize)> = None;
+    let mut comment: Option<usize> = None;
+    let mut line = 1;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '\n' {
+            clean.push(stack.is_empty() && string.is_none() && comment.is_none());
+            line += 1;
+            i += 1;
+            continue;
+        }
+
+        if let Some((close, escapes, _)) = &string {
+            if *escapes && c == '\\' && chars.get(i + 1) != Some(&'\n') {
+                i += 2;
+            } else if starts_with(i, close) {
+                i += close.chars().count();
+                string = None;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+        if comment.is_some() {
+            let (_, close) = syntax.block_comment.unwrap_or(("", "*/"));
+            if starts_with(i, close) {
+                i += close.len();
+                comment = None;
+            } else {
+                i += 1;
+            }
+            continue;
+        }
+
+        if syntax.line_comments.iter().any(|t| starts_with(i, t)) {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+        if let Some((open, _)) = syntax.block_comment {
+            if starts_with(i, open) {
+                comment = Some(line);
+                i += open.len();
+                continue;
+            }
+        }
+        if syntax.rust_literals {
+            let prev_ident = i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_');
+            if c == 'r' && !prev_ident {
+                let hashes = chars[i + 1..].iter().take_while(|&&h| h == '#').count();
+                if chars.get(i + 1 + hashes) == Some(&'"') {
+                    string = Some((format!("\"{}", "#".repeat(hashes)), false, line));
+                    i += hashes + 2;
+                    continue;
+                }
+            }
+            if c == '\'' {
+                // 'x' and '\n' are chars; anything else is a lifetime
+                if chars.get(i + 1) == Some(&'\\') {
+                    let end = (i + 2..chars.len().min(i + 12)).find(|&k| chars[k] == '\'');
+                    i = end.map_or(i + 1, |k| k + 1);
+                    continue;
+                }
+                if chars.get(i + 2) == Some(&'\'') {
+                    i += 3;
+                    continue;
+                }
+                i += 1;
+                continue;
+            }
+        }
+        if syntax.triple_quotes && (starts_with(i, "\"\"\"") || starts_with(i, "'''")) {
+            string = Some((chars[i..i + 3].iter().collect(), true, line));
+            i += 3;
+            continue;
+        }
+        if syntax.quotes.contains(&c) {
+            string = Some((c.to_string(), true, line));
+            i += 1;
+            continue;
+        }
+
+        match c {
+            '(' | '[' | '{' => stack.push((c, line)),
+            ')' | ']' | '}' => {
+                let opener = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                if stack.last().map(|(o, _)| *o) == Some(opener) {
+                    stack.pop();
+                } else {
+                    issues.push(SnippetIssue::UnexpectedCloser { delimiter: c, line });
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    if !content.ends_with('\n') && !content.is_empty() {
+        clean.push(stack.is_empty() && string.is_none() && comment.is_none());
+    }
+
+    if let Some((_, _, start)) = string {
+        issues.push(SnippetIssue::UnterminatedString { line: start });
+    }
+    if let Some(start) = comment {
+        issues.push(SnippetIssue::UnterminatedComment { line: start });
+    }
+    issues.extend(stack.into_iter().map(|(delimiter, line)| SnippetIssue::UnclosedDelimiter { delimiter, line }));
+    Scan { issues, clean }
+}
+
+/// Lexical issues of a snippet; empty for well-formed or unsupported languages
+pub fn check_snippet(path: &str, content: &str) -> Vec<SnippetIssue> {
+    match syntax_for(AstBridge::detect_language(Path::new(path))) {
+        Some(syntax) => scan(content, &syntax).issues,
+        None => Vec::new(),
+    }
+}
+
+/// Pull a head-truncation line budget back to the nearest safe cut point
+///
+/// Returns the largest line count `<= max_lines` after which no string,
+/// comment or delimiter is open, as long as that keeps at least half of the
+/// budget; otherwise `max_lines` unchanged. Budgets are never exceeded.
+pub fn safe_line_limit(path: &str, content: &str, max_lines: usize) -> usize {
+    let Some(syntax) = syntax_for(AstBridge::detect_language(Path::new(path))) else {
+        return max_lines;
+    };
+    if max_lines == 0 || content.split('\n').count() <= max_lines {
+        return max_lines;
+    }
+    let clean = scan(content, &syntax).clean;
+    (max_lines.div_ceil(2)..=max_lines)
+        .rev()
+        .find(|&n| n > 0 && clean.get(n - 1).copied().unwrap_or(false))
+        .unwrap_or(max_lines)
+}
+
+/// A file embedded in serialized context
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileBlock {
+    pub path: String,
+    pub content: String,
+    /// Header or content carries a truncation marker
+    pub truncated: bool,
+}
+
+/// Split serialized context into file blocks
+///
+/// Understands both Plus/Minus flavours (`++++++++++ path` … `----------`
+/// and `+++ path` / `+ line` / `--- path`) and Markdown (`## path` or
+/// `### path` plus a fenced block). XML formats are not split, and the
+/// `=====`-fenced truncation markers are dropped from block content.
+pub fn file_blocks(output: &str) -> Vec<FileBlock> {
+    let lines: Vec<&str> = output.lines().collect();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let (header, body_end, prefix): (&str, fn(&str) -> bool, &str) = if let Some(rest) = line.strip_prefix("++++++++++ ") {
+            (rest.trim_end_matches(" ++++++++++"), |l| l.starts_with("---------- "), "")
+        } else if let Some(rest) = line.strip_prefix("+++ ") {
+            (rest, |l| l.starts_with("--- "), "+ ")
+        } else if let Some(rest) = line.strip_prefix("### ").or_else(|| line.strip_prefix("## ")) {
+            if !lines.get(i + 2).is_some_and(|l| l.starts_with("```")) {
+                i += 1;
+                continue;
+            }
+            i += 2;
+            (rest, |l| l == "```", "")
+        } else {
+            i += 1;
+            continue;
+        };
+
+        let mut content = Vec::new();
+        let mut in_marker = false;
+        i += 1;
+        while i < lines.len() && !body_end(lines[i]) {
+            let text = lines[i];
+            let text = text.strip_prefix(prefix).unwrap_or(text.strip_prefix('+').unwrap_or(text));
+            // Truncation markers are fenced by `=====` rules; they are not code
+            if text.len() >= 10 && text.chars().all(|c| c == '=') {
+                in_marker = !in_marker;
+                content.push("TRUNCATED");
+            } else if !in_marker {
+                content.push(text);
+            }
+            i += 1;
+        }
+        i += 1;
+
+        let mut content = content.join("\n");
+        content.push('\n');
+        let truncated = header.contains("TRUNCATED")
+            || header.contains("[SKELETON]")
+            || content.contains("TRUNCATED")
+            || content.contains("lines omitted");
+        blocks.push(FileBlock { path: block_path(header), content, truncated });
+    }
+    blocks
+}
+
+/// Path from a block header: drop `[...]` annotations and brightness markers
+fn block_path(header: &str) -> String {
+    let path = header.split(" [").next().unwrap_or(header).trim();
+    path.rsplit(' ').next().unwrap_or(path).to_string()
+}
+
+/// A truncated file whose snippet no longer reads as code
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BrokenSnippet {
+    pub path: String,
+    pub issues: Vec<SnippetIssue>,
+}
+
+/// Outcome of [`check_context`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct QualityReport {
+    pub files_checked: usize,
+    pub truncated_files: usize,
+    pub broken: Vec<BrokenSnippet>,
+}
+
+impl QualityReport {
+    pub fn is_clean(&self) -> bool {
+        self.broken.is_empty()
+    }
+
+    pub fn render_text(&self) -> String {
+        let mut out = format!(
+            "Context self-check: {} files, {} truncated, {} broken snippets\n",
+            self.files_checked,
+            self.truncated_files,
+            self.broken.len()
+        );
+        for snippet in &self.broken {
+            let issues: Vec<String> = snippet.issues.iter().map(ToString::to_string).collect();
+            out.push_str(&format!("  ⚠ {}: {}\n", snippet.path, issues.join("; ")));
+        }
+        if !self.is_clean() {
+            out.push_str("  Hint: --safe-cuts moves truncation to the nearest safe line\n");
+        }
+        out
+    }
+}
+
+/// Re-read serialized context and report truncations that broke snippets
+///
+/// Untruncated files are checked too but only counted: a file that is
+/// broken on disk is not a truncation problem.
+pub fn check_context(output: &str) -> QualityReport {
+    let mut report = QualityReport::default();
+    for block in file_blocks(output) {
+        report.files_checked += 1;
+        if !block.truncated {
+            continue;
+        }
+        report.truncated_files += 1;
+        let issues = check_snippet(&block.path, &block.content);
+        if !issues.is_empty() {
+            report.broken.push(BrokenSnippet { path: block.path, issues });
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_snippet_rust() {
+        let ok = "fn main() {\n    let s = \"}\"; // }\n    let c = '{';\n    let r = r#\"{\"#;\n    f::<'a>();\n}\n";
+        assert!(check_snippet("main.rs", ok).is_empty());
+
+        let cut = "fn main() {\n    let s = \"abc\n";
+        assert_eq!(check_snippet("main.rs", cut), vec![
+            SnippetIssue::UnterminatedString { line: 2 },
+            SnippetIssue::UnclosedDelimiter { delimiter: '{', line: 1 },
+        ]);
+        assert_eq!(check_snippet("main.rs", "/* open\n"), vec![SnippetIssue::UnterminatedComment { line: 1 }]);
+        assert!(check_snippet("notes.txt", "{{{").is_empty());
+    }
+
+    #[test]
+    fn test_check_snippet_python() {
+        assert!(check_snippet("a.py", "def f():\n    \"\"\"Doc (with paren\"\"\"\n    return [1, 2]  # ]\n").is_empty());
+        assert_eq!(check_snippet("a.py", "def f():\n    \"\"\"Doc\n"), vec![SnippetIssue::UnterminatedString { line: 2 }]);
+    }
+
+    #[test]
+    fn test_safe_line_limit() {
+        let content = "fn a() {\n    1;\n}\n\nfn b() {\n    2;\n    3;\n}\n";
+        assert_eq!(safe_line_limit("lib.rs", content, 6), 4);
+        assert_eq!(safe_line_limit("lib.rs", content, 3), 3);
+        assert_eq!(safe_line_limit("lib.rs", content, 100), 100);
+        assert_eq!(safe_line_limit("notes.txt", content, 6), 6);
+    }
+
+    #[test]
+    fn test_check_context_plus_minus() {
+        let output = "++++++++++ src/lib.rs [TRUNCATED: 9 lines] ++++++++++\nfn a() {\n    1;\n---------- src/lib.rs [TRUNCATED:9→2] abc src/lib.rs ----------\n\
+                      ++++++++++ src/ok.rs ++++++++++\nfn b() {}\n---------- src/ok.rs abc src/ok.rs ----------\n";
+        let report = check_context(output);
+        assert_eq!((report.files_checked, report.truncated_files), (2, 1));
+        assert_eq!(report.broken.len(), 1);
+        assert_eq!(report.broken[0].path, "src/lib.rs");
+        assert!(report.render_text().contains("unclosed '{' from line 1"));
+
+        let core = "+++ src/lib.rs\n+ fn a() {\n+ /* TRUNCATED: 9 lines → 1 lines */\n--- src/lib.rs [md5:abc]\n";
+        assert_eq!(check_context(core).broken[0].issues, vec![SnippetIssue::UnclosedDelimiter { delimiter: '{', line: 1 }]);
+    }
+}
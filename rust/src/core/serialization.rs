@@ -196,6 +196,11 @@ pub trait Serializer: Send + Sync {
     fn extension(&self) -> &'static str;
 }
 
+/// Header suffix noting where a generated or vendored file should be edited
+fn provenance_suffix(file: &ProcessedFile) -> String {
+    file.provenance.as_ref().map(|p| format!(" [{}]", p.note())).unwrap_or_default()
+}
+
 /// Plus/Minus format serializer (default)
 pub struct PlusMinusSerializer;
 
@@ -222,15 +227,17 @@ impl Serializer for PlusMinusSerializer {
         // Build header with optional brightness indicator and [SKELETON] tag
         let header = if file.compression_level == CompressionLevel::Skeleton {
             if let Some(orig) = file.original_tokens {
-                format!("+++ {}{} [SKELETON] (original: {} tokens)\n", brightness, file.path, orig)
+                format!("+++ {}{} [SKELETON] (original: {} tokens)", brightness, file.path, orig)
             } else {
-                format!("+++ {}{} [SKELETON]\n", brightness, file.path)
+                format!("+++ {}{} [SKELETON]", brightness, file.path)
             }
         } else {
-            format!("+++ {}{}\n", brightness, file.path)
+            format!("+++ {}{}", brightness, file.path)
         };
 
         output.push_str(&header);
+        output.push_str(&provenance_suffix(file));
+        output.push('\n');
         for line in file.content.lines() {
             output.push_str(&format!("+ {}\n", line));
         }
@@ -289,13 +296,19 @@ impl Serializer for XmlSerializer {
             String::new()
         };
 
+        let provenance_attr = match &file.provenance {
+            Some(p) => format!(" provenance=\"{}\" provenance_note=\"{}\"", p.kind.label(), Self::escape_xml(&p.note())),
+            None => String::new(),
+        };
+
         output.push_str(&format!(
-            "<file path=\"{}\" md5=\"{}\" language=\"{}\"{}{}>\n",
+            "<file path=\"{}\" md5=\"{}\" language=\"{}\"{}{}{}>\n",
             Self::escape_xml(&file.path),
             file.md5,
             file.language,
             brightness_attr,
-            skeleton_attr
+            skeleton_attr,
+            provenance_attr
         ));
         output.push_str(&Self::escape_xml(&file.content));
         output.push_str("\n</file>\n");
@@ -381,6 +394,9 @@ impl Serializer for MarkdownSerializer {
         };
 
         output.push_str(&header);
+        if let Some(provenance) = &file.provenance {
+            output.push_str(&format!("> {}\n\n", provenance.note()));
+        }
         output.push_str(&format!("```{}\n", lang));
         output.push_str(&file.content);
         if !file.content.ends_with('\n') {
@@ -483,6 +499,20 @@ mod tests {
         assert!(output.ends_with("```\n\n"));
     }
 
+    #[test]
+    fn test_provenance_notes_in_headers() {
+        use crate::core::provenance::Provenance;
+
+        let entry = FileEntry::new("api/api.pb.go", "// Code generated by protoc-gen-go. DO NOT EDIT.\n// source: api.proto\npackage api\n");
+        let mut file = ProcessedFile::from_entry(&entry, "go", 50);
+        file.provenance = Provenance::classify(&entry.path, &entry.content);
+        let note = "generated by protoc-gen-go from api.proto — prefer editing the source";
+
+        assert!(PlusMinusSerializer::new().serialize_file(&file).starts_with(&format!("+++ api/api.pb.go [{}]\n", note)));
+        assert!(MarkdownSerializer::new().serialize_file(&file).starts_with(&format!("## api/api.pb.go\n\n> {}\n\n```go", note)));
+        assert!(XmlSerializer::new().serialize_file(&file).contains(&format!(" provenance=\"generated\" provenance_note=\"{}\">", note)));
+    }
+
     #[test]
     fn test_markdown_detect_language() {
         assert_eq!(MarkdownSerializer::detect_language("test.py"), "python");
@@ -0,0 +1,315 @@
+//! Snippets - One symbol as a standalone, pasteable source file
+//!
+//! Gathers a function (or type) with the minimal context it needs to stand
+//! on its own, for issue reports and test-scaffolding prompts:
+//!
+//! - the type declarations named in its signature, and transitively those
+//!   named in theirs (the signature type closure), dependencies first
+//! - the imports of the contributing files that the gathered code uses
+//! - for a method, the header of its enclosing `impl`/class around it
+//!
+//! Only declarations in the target's language are gathered, bodies of the
+//! target's callees are not, and names resolve by the first declaration
+//! found, so the result is best effort: a starting point that usually
+//! compiles after small edits, not a guarantee.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use voyager_ast::{Declaration, DeclarationKind, File, LanguageId};
+
+use crate::core::anchor::Anchor;
+use crate::core::apiref::signature;
+use crate::core::ast_bridge::AstBridge;
+use crate::core::error::{EncoderError, Result};
+use crate::core::summarizer::comment_delimiters;
+use crate::core::symbol_rules::preamble_start;
+use crate::core::zoom::ZoomTarget;
+
+/// Types gathered at most, so a snippet stays a snippet
+const MAX_SNIPPET_TYPES: usize = 16;
+
+/// A symbol with the types and imports it needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// Anchor of the target declaration
+    pub target: String,
+    pub language: LanguageId,
+    /// Anchors of the gathered types, in output order
+    pub types: Vec<String>,
+    /// Import statements, as written in their files
+    pub imports: Vec<String>,
+    /// The snippet source
+    pub text: String,
+}
+
+/// A parsed file
+struct Source<'a> {
+    path: &'a str,
+    file: File,
+    content: &'a str,
+}
+
+/// A declaration found in a file, with its enclosing declaration and qualified name
+#[derive(Clone, Copy)]
+struct Found<'a> {
+    source: usize,
+    decl: &'a Declaration,
+    parent: Option<&'a Declaration>,
+}
+
+impl Snippet {
+    /// Build the snippet of `target` (a function, class or anchor target)
+    /// from `(path, content)` pairs
+    pub fn extract(files: &[(String, String)], target: &ZoomTarget) -> Result<Self> {
+        let bridge = AstBridge::new();
+        let sources: Vec<Source> = files
+            .iter()
+            .filter_map(|(path, content)| {
+                let language = AstBridge::detect_language(Path::new(path));
+                let file = bridge.analyze_file(content, language)?;
+                Some(Source { path, file, content })
+            })
+            .collect();
+        let mut declared: Vec<(Found, String)> = Vec::new();
+        for (index, source) in sources.iter().enumerate() {
+            walk(index, &source.file.declarations, None, "", &mut declared);
+        }
+
+        let not_found = || EncoderError::InvalidZoomTarget { target: target.to_string() };
+        let found = match target {
+            ZoomTarget::Function(name) => {
+                let name = name.rsplit([':', '.']).next().unwrap_or(name);
+                declared.iter().find(|(f, _)| f.decl.name == name && is_callable(f.decl.kind))
+            }
+            ZoomTarget::Class(name) => {
+                let name = name.rsplit([':', '.']).next().unwrap_or(name);
+                declared.iter().find(|(f, _)| f.decl.name == name && is_type(f.decl.kind))
+            }
+            ZoomTarget::Anchor(anchor) => {
+                let index = sources.iter().position(|s| s.path == anchor.path).ok_or_else(not_found)?;
+                let located = anchor.locate(sources[index].content).ok_or_else(not_found)?;
+                declared.iter().find(|(f, _)| f.source == index && f.decl.span == located.span)
+            }
+            _ => None,
+        };
+        let (target, qualified) = found.ok_or_else(not_found)?;
+        let (target, qualified) = (*target, qualified.clone());
+        let home = &sources[target.source];
+        let language = home.file.language;
+
+        // Signature type closure over the types of the target's language
+        let types: Vec<Found> = declared
+            .iter()
+            .map(|(f, _)| *f)
+            .filter(|f| is_type(f.decl.kind) && sources[f.source].file.language == language)
+            .collect();
+        let scanned = if is_callable(target.decl.kind) {
+            signature(target.decl, home.content)
+        } else {
+            text_of(home.content, target.decl).to_string()
+        };
+        let mut pending: Vec<String> = identifiers(&scanned).into_iter().collect();
+        pending.sort();
+        let mut gathered: Vec<Found> = Vec::new();
+        while let Some(name) = pending.pop() {
+            if gathered.len() == MAX_SNIPPET_TYPES {
+                break;
+            }
+            let known = |f: &Found| f.source == target.source && f.decl.span == target.decl.span
+                || gathered.iter().any(|g| g.source == f.source && g.decl.span == f.decl.span);
+            let Some(found) = types
+                .iter()
+                .filter(|f| f.decl.name == name)
+                .min_by_key(|f| f.source != target.source)
+                .filter(|f| !known(f))
+            else {
+                continue;
+            };
+            let mut names: Vec<String> = identifiers(text_of(sources[found.source].content, found.decl)).into_iter().collect();
+            names.sort();
+            pending.extend(names);
+            gathered.push(*found);
+        }
+        // Dependencies before the types that use them
+        gathered.reverse();
+
+        let target_text = match target.parent.and_then(|parent| wrapper(home.content, parent)) {
+            Some((header, footer)) => format!("{}\n{}\n{}", header, text_of(home.content, target.decl), footer)
+                .trim_end()
+                .to_string(),
+            None => text_of(home.content, target.decl).to_string(),
+        };
+        let type_texts: Vec<&str> = gathered.iter().map(|f| text_of(sources[f.source].content, f.decl)).collect();
+
+        // Imports whose names the gathered code uses, except names it declares itself
+        let mut used = identifiers(&target_text);
+        for text in &type_texts {
+            used.extend(identifiers(text));
+        }
+        for f in &gathered {
+            used.remove(&f.decl.name);
+        }
+        let mut contributing: Vec<usize> = vec![target.source];
+        contributing.extend(gathered.iter().map(|f| f.source));
+        let mut imports: Vec<String> = Vec::new();
+        for &index in &contributing {
+            let source = &sources[index];
+            for import in &source.file.imports {
+                let mut names: Vec<&str> = import.items.iter().map(String::as_str).collect();
+                names.extend(import.alias.as_deref());
+                names.extend(import.source.rsplit([':', '.', '/']).next());
+                let statement = source.content.get(import.span.start..import.span.end).unwrap_or("").trim();
+                if names.iter().any(|name| used.contains(*name)) && !statement.is_empty() && !imports.iter().any(|i| i == statement) {
+                    imports.push(statement.to_string());
+                }
+            }
+        }
+
+        let anchor_of = |f: &Found, qualified: &str| {
+            let source = &sources[f.source];
+            Anchor::for_declaration(source.path, qualified, f.decl, source.content).to_string()
+        };
+        let target_anchor = anchor_of(&target, &qualified);
+        let type_anchors: Vec<String> = gathered
+            .iter()
+            .map(|g| {
+                let qualified = declared
+                    .iter()
+                    .find(|(f, _)| f.source == g.source && f.decl.span == g.decl.span)
+                    .map_or(g.decl.name.as_str(), |(_, q)| q.as_str());
+                anchor_of(g, qualified)
+            })
+            .collect();
+
+        let (open, close) = comment_delimiters(language);
+        let mut text = format!(
+            "{}Snippet of {} {} ({}:{}-{}) with {} type(s) from its signature: best effort{}\n",
+            open,
+            target.decl.kind.as_str(),
+            qualified,
+            home.path,
+            target.decl.span.start_line,
+            target.decl.span.end_line,
+            gathered.len(),
+            close
+        );
+        if !imports.is_empty() {
+            text.push('\n');
+            for import in &imports {
+                text.push_str(import);
+                text.push('\n');
+            }
+        }
+        for type_text in &type_texts {
+            text.push('\n');
+            text.push_str(type_text);
+            text.push('\n');
+        }
+        text.push('\n');
+        text.push_str(&target_text);
+        text.push('\n');
+
+        Ok(Self {
+            target: target_anchor,
+            language,
+            types: type_anchors,
+            imports,
+            text,
+        })
+    }
+}
+
+fn walk<'a>(
+    source: usize,
+    decls: &'a [Declaration],
+    parent: Option<&'a Declaration>,
+    prefix: &str,
+    out: &mut Vec<(Found<'a>, String)>,
+) {
+    for decl in decls {
+        let qualified = if prefix.is_empty() { decl.name.clone() } else { format!("{}.{}", prefix, decl.name) };
+        out.push((Found { source, decl, parent }, qualified.clone()));
+        walk(source, &decl.children, Some(decl), &qualified, out);
+    }
+}
+
+fn is_callable(kind: DeclarationKind) -> bool {
+    matches!(kind, DeclarationKind::Function | DeclarationKind::Method)
+}
+
+fn is_type(kind: DeclarationKind) -> bool {
+    matches!(
+        kind,
+        DeclarationKind::Class
+            | DeclarationKind::Struct
+            | DeclarationKind::Enum
+            | DeclarationKind::Interface
+            | DeclarationKind::Trait
+            | DeclarationKind::Type
+    )
+}
+
+/// Source of a declaration from the start of its first line, with its
+/// attributes, decorators and doc comments
+fn text_of<'a>(content: &'a str, decl: &Declaration) -> &'a str {
+    let end = decl.span.end.min(content.len());
+    let start = decl.span.start.min(end);
+    if !content.is_char_boundary(start) || !content.is_char_boundary(end) {
+        return "";
+    }
+    &content[preamble_start(content, start)..end]
+}
+
+/// Header and footer of an enclosing declaration (`impl Engine {` / `}`, `class Engine:` / ``)
+fn wrapper(content: &str, parent: &Declaration) -> Option<(String, String)> {
+    let body = parent.body_span?;
+    let header = content.get(preamble_start(content, parent.span.start)..body.start)?.trim_end();
+    if content.get(body.start..)?.starts_with('{') {
+        Some((format!("{} {{", header), "}".to_string()))
+    } else {
+        Some((header.to_string(), String::new()))
+    }
+}
+
+/// Identifiers in `text`
+fn identifiers(text: &str) -> HashSet<String> {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| word.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snippet_gathers_signature_type_closure() {
+        let files = vec![
+            (
+                "src/model.rs".to_string(),
+                "use std::collections::HashMap;\n\n/// Settings\n#[derive(Debug)]\npub struct Config {\n    pub limits: Limits,\n    pub names: HashMap<String, String>,\n}\n\npub struct Limits {\n    pub max: usize,\n}\n\npub struct Unused;\n".to_string(),
+            ),
+            (
+                "src/engine.rs".to_string(),
+                "use std::fmt;\nuse crate::model::Config;\n\npub struct Engine;\n\nimpl Engine {\n    pub fn apply(&self, config: &Config) -> usize {\n        config.limits.max\n    }\n}\n".to_string(),
+            ),
+        ];
+        let snippet = Snippet::extract(&files, &ZoomTarget::Function("apply".to_string())).unwrap();
+
+        assert!(snippet.target.starts_with("src/engine.rs#Engine.apply@"));
+        assert_eq!(snippet.types.len(), 2);
+        assert!(snippet.types[0].starts_with("src/model.rs#Limits@"), "dependencies first: {:?}", snippet.types);
+        // `Config` is inlined, so its import would clash
+        assert_eq!(snippet.imports, vec!["use std::collections::HashMap;"]);
+        assert!(snippet.text.starts_with("/* Snippet of method Engine.apply (src/engine.rs:7-9) with 2 type(s)"));
+        assert!(snippet.text.contains("/// Settings\n#[derive(Debug)]\npub struct Config {"));
+        assert!(snippet.text.contains("impl Engine {\n    pub fn apply(&self, config: &Config) -> usize {"));
+        assert!(snippet.text.trim_end().ends_with("}\n}"));
+        assert!(!snippet.text.contains("Unused") && !snippet.text.contains("use std::fmt;"));
+        assert!(!snippet.text.contains("use crate::model::Config;"));
+
+        assert!(Snippet::extract(&files, &ZoomTarget::Function("missing".to_string())).is_err());
+    }
+}
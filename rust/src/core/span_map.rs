@@ -0,0 +1,210 @@
+//! Span Map - Position conversion for non-ASCII sources
+//!
+//! IR spans are byte offsets, editors count characters, and LSP clients
+//! count UTF-16 code units (`😀` is 4 bytes, 1 char, 2 code units). A
+//! [`SpanMap`] converts offsets between these [`Unit`]s and to and from
+//! line/column positions, so every consumer agrees on where a span is.
+//!
+//! The line table is built on first use and shared by later conversions;
+//! each conversion then scans a single line. Offsets inside a character are
+//! rounded down to its start, and offsets past the end clamp to the end.
+
+use std::cell::OnceCell;
+
+use serde::{Deserialize, Serialize};
+use voyager_ast::Span;
+
+/// What an offset or column counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    /// UTF-8 bytes (IR spans, slicing)
+    Byte,
+    /// Unicode scalar values
+    Char,
+    /// UTF-16 code units (LSP positions)
+    Utf16,
+}
+
+impl Unit {
+    fn width(self, c: char) -> usize {
+        match self {
+            Self::Byte => c.len_utf8(),
+            Self::Char => 1,
+            Self::Utf16 => c.len_utf16(),
+        }
+    }
+}
+
+/// A 0-indexed line and column (LSP convention; IR lines are 1-indexed)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LineCol {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Offsets of the start of a line in each unit
+#[derive(Debug, Clone, Copy)]
+struct LineStart {
+    byte: usize,
+    char: usize,
+    utf16: usize,
+}
+
+impl LineStart {
+    fn get(&self, unit: Unit) -> usize {
+        match unit {
+            Unit::Byte => self.byte,
+            Unit::Char => self.char,
+            Unit::Utf16 => self.utf16,
+        }
+    }
+}
+
+/// Lazily indexed position converter over one file's text
+pub struct SpanMap<'a> {
+    text: &'a str,
+    lines: OnceCell<Vec<LineStart>>,
+}
+
+impl<'a> SpanMap<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self { text, lines: OnceCell::new() }
+    }
+
+    fn lines(&self) -> &[LineStart] {
+        self.lines.get_or_init(|| {
+            let mut lines = vec![LineStart { byte: 0, char: 0, utf16: 0 }];
+            let (mut chars, mut utf16) = (0, 0);
+            for (byte, c) in self.text.char_indices() {
+                chars += 1;
+                utf16 += c.len_utf16();
+                if c == '\n' {
+                    lines.push(LineStart { byte: byte + 1, char: chars, utf16 });
+                }
+            }
+            lines
+        })
+    }
+
+    /// Number of lines (a trailing newline starts an empty last line)
+    pub fn line_count(&self) -> usize {
+        self.lines().len()
+    }
+
+    /// Round a byte offset down to a character boundary within the text
+    fn floor(&self, byte: usize) -> usize {
+        let mut byte = byte.min(self.text.len());
+        while !self.text.is_char_boundary(byte) {
+            byte -= 1;
+        }
+        byte
+    }
+
+    /// Index of the line containing an offset in `unit`
+    fn line_of(&self, offset: usize, unit: Unit) -> usize {
+        self.lines().partition_point(|l| l.get(unit) <= offset) - 1
+    }
+
+    /// Text of a line without its line break
+    fn line_text(&self, line: usize) -> &'a str {
+        let lines = self.lines();
+        let end = lines.get(line + 1).map_or(self.text.len(), |next| next.byte);
+        self.text[lines[line].byte..end].trim_end_matches(['\n', '\r'])
+    }
+
+    /// Byte length of the longest prefix of `text` at most `width` wide in `unit`
+    fn prefix_bytes(text: &str, width: usize, unit: Unit) -> usize {
+        let mut measured = 0;
+        for (byte, c) in text.char_indices() {
+            measured += unit.width(c);
+            if measured > width {
+                return byte;
+            }
+        }
+        text.len()
+    }
+
+    fn measure(text: &str, unit: Unit) -> usize {
+        match unit {
+            Unit::Byte => text.len(),
+            Unit::Char => text.chars().count(),
+            Unit::Utf16 => text.encode_utf16().count(),
+        }
+    }
+
+    /// Convert a byte offset to an offset in `unit`
+    pub fn offset_in(&self, byte: usize, unit: Unit) -> usize {
+        let byte = self.floor(byte);
+        let start = self.lines()[self.line_of(byte, Unit::Byte)];
+        start.get(unit) + Self::measure(&self.text[start.byte..byte], unit)
+    }
+
+    /// Convert an offset in `unit` to a byte offset
+    pub fn to_byte(&self, offset: usize, unit: Unit) -> usize {
+        let start = self.lines()[self.line_of(offset, unit)];
+        start.byte + Self::prefix_bytes(&self.text[start.byte..], offset - start.get(unit), unit)
+    }
+
+    /// Line and column of a byte offset, the column counted in `unit`
+    pub fn position(&self, byte: usize, unit: Unit) -> LineCol {
+        let byte = self.floor(byte);
+        let line = self.line_of(byte, Unit::Byte);
+        let start = self.lines()[line].byte;
+        LineCol { line, column: Self::measure(&self.text[start..byte], unit) }
+    }
+
+    /// Byte offset of a position; columns past the end of the line clamp to it
+    ///
+    /// `None` if the line does not exist.
+    pub fn byte_at(&self, position: LineCol, unit: Unit) -> Option<usize> {
+        let start = self.lines().get(position.line)?.byte;
+        Some(start + Self::prefix_bytes(self.line_text(position.line), position.column, unit))
+    }
+
+    /// Start and end positions of an IR span
+    pub fn range(&self, span: &Span, unit: Unit) -> (LineCol, LineCol) {
+        (self.position(span.start, unit), self.position(span.end, unit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions_agree_on_non_ascii_text() {
+        // é: 2 bytes, 1 unit; 😀: 4 bytes, 2 units
+        let text = "aé😀b\r\nx😀\n";
+        let map = SpanMap::new(text);
+        let b = text.find('b').unwrap();
+        assert_eq!(b, 7);
+        assert_eq!(map.offset_in(b, Unit::Char), 3);
+        assert_eq!(map.offset_in(b, Unit::Utf16), 4);
+        assert_eq!(map.to_byte(4, Unit::Utf16), b);
+        assert_eq!(map.to_byte(3, Unit::Char), b);
+        // Inside the surrogate pair / multi-byte char: rounded down
+        assert_eq!(map.to_byte(3, Unit::Utf16), 3);
+        assert_eq!(map.offset_in(4, Unit::Utf16), 2);
+
+        let x = text.find('x').unwrap();
+        assert_eq!(map.position(x + 1, Unit::Utf16), LineCol { line: 1, column: 1 });
+        assert_eq!(map.position(text.len(), Unit::Byte), LineCol { line: 2, column: 0 });
+        assert_eq!(map.byte_at(LineCol { line: 1, column: 3 }, Unit::Utf16), Some(text.len() - 1));
+        assert_eq!(map.byte_at(LineCol { line: 0, column: 99 }, Unit::Char), Some(b + 1), "clamped before \\r\\n");
+        assert_eq!(map.byte_at(LineCol { line: 3, column: 0 }, Unit::Char), None);
+        assert_eq!(map.line_count(), 3);
+
+        for unit in [Unit::Byte, Unit::Char, Unit::Utf16] {
+            for (byte, c) in text.char_indices() {
+                assert_eq!(map.to_byte(map.offset_in(byte, unit), unit), byte);
+                if c != '\n' {
+                    assert_eq!(map.byte_at(map.position(byte, unit), unit), Some(byte));
+                }
+            }
+        }
+
+        let span = Span::new(x, text.len() - 1, 2, 2);
+        assert_eq!(map.range(&span, Unit::Utf16), (LineCol { line: 1, column: 0 }, LineCol { line: 1, column: 3 }));
+    }
+}
@@ -0,0 +1,411 @@
+//! Summarization Hooks - Pluggable compression for oversized symbol bodies
+//!
+//! When a per-symbol token budget is configured, every function/method body
+//! that exceeds it is handed to a [`Summarizer`]. The summarizer receives the
+//! declaration and its source text and returns a replacement summary, which is
+//! embedded between provenance markers so the LLM knows the text is derived:
+//!
+//! ```text
+//! /* SUMMARY[extractive]: function process (lines 10-80, ~900 tokens) */
+//! ...summary...
+//! /* END SUMMARY - ZOOM_AFFORDANCE: pm_encoder --zoom function=process --budget 1000 */
+//! ```
+//!
+//! The crate ships two summarizers:
+//! - [`NoopSummarizer`]: declines every request (bodies are left intact)
+//! - [`ExtractiveSummarizer`]: keeps the first/last lines plus all comments
+//!
+//! Users can plug in an LLM-backed implementation via
+//! [`ContextEngine::with_summarizer`](crate::core::ContextEngine::with_summarizer).
+
+use voyager_ast::{Declaration, DeclarationKind, LanguageId};
+
+use crate::core::ast_bridge::AstBridge;
+use crate::core::zoom::ZoomAction;
+
+/// Everything a summarizer needs to compress one symbol body
+#[derive(Debug, Clone, Copy)]
+pub struct SummaryRequest<'a> {
+    /// Path of the file containing the declaration
+    pub path: &'a str,
+    /// Language of the file
+    pub language: LanguageId,
+    /// The declaration being summarized
+    pub declaration: &'a Declaration,
+    /// Source text of the body (the region that will be replaced)
+    pub body: &'a str,
+    /// Estimated tokens in the body
+    pub body_tokens: usize,
+    /// Per-symbol budget the body exceeded
+    pub budget: usize,
+}
+
+/// Hook for compressing symbol bodies that exceed their budget
+///
+/// Implementations must be deterministic for a given input if the output is
+/// expected to be reproducible (e.g., frozen mode).
+pub trait Summarizer: Send + Sync {
+    /// Short identifier embedded in provenance markers (e.g., "extractive")
+    fn name(&self) -> &str;
+
+    /// Produce a summary for the body, or `None` to keep the body unchanged
+    fn summarize(&self, request: &SummaryRequest<'_>) -> Option<String>;
+}
+
+/// Summarizer that never summarizes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopSummarizer;
+
+impl Summarizer for NoopSummarizer {
+    fn name(&self) -> &str {
+        "noop"
+    }
+
+    fn summarize(&self, _request: &SummaryRequest<'_>) -> Option<String> {
+        None
+    }
+}
+
+/// Extractive summarizer: first/last lines plus every comment line
+#[derive(Debug, Clone, Copy)]
+pub struct ExtractiveSummarizer {
+    /// Lines kept from the start of the body
+    pub head_lines: usize,
+    /// Lines kept from the end of the body
+    pub tail_lines: usize,
+}
+
+impl Default for ExtractiveSummarizer {
+    fn default() -> Self {
+        Self {
+            head_lines: 3,
+            tail_lines: 2,
+        }
+    }
+}
+
+impl ExtractiveSummarizer {
+    /// Create an extractive summarizer with default head/tail sizes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn is_comment(line: &str) -> bool {
+        let trimmed = line.trim_start();
+        trimmed.starts_with("//")
+            || trimmed.starts_with('#')
+            || trimmed.starts_with("/*")
+            || trimmed.starts_with('*')
+            || trimmed.starts_with("\"\"\"")
+            || trimmed.starts_with("'''")
+    }
+}
+
+impl Summarizer for ExtractiveSummarizer {
+    fn name(&self) -> &str {
+        "extractive"
+    }
+
+    fn summarize(&self, request: &SummaryRequest<'_>) -> Option<String> {
+        let lines: Vec<&str> = request.body.lines().collect();
+        if lines.len() <= self.head_lines + self.tail_lines {
+            return None;
+        }
+
+        let (open, close) = comment_delimiters(request.language);
+        let tail_start = lines.len() - self.tail_lines;
+        let mut kept: Vec<String> = Vec::new();
+        let mut elided = 0usize;
+
+        for (i, line) in lines.iter().enumerate() {
+            if i < self.head_lines || i >= tail_start || Self::is_comment(line) {
+                if elided > 0 {
+                    let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+                    kept.push(format!("{}{}... ({} lines elided){}", indent, open, elided, close));
+                    elided = 0;
+                }
+                kept.push((*line).to_string());
+            } else {
+                elided += 1;
+            }
+        }
+
+        Some(kept.join("\n"))
+    }
+}
+
+/// A symbol body that was replaced by a summary
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummarizedSymbol {
+    /// Symbol name
+    pub name: String,
+    /// Start line of the original body (1-indexed)
+    pub start_line: usize,
+    /// End line of the original body (1-indexed)
+    pub end_line: usize,
+    /// Estimated tokens of the original body
+    pub original_tokens: usize,
+}
+
+/// Result of running the summarization pass over a file
+#[derive(Debug, Clone)]
+pub struct SummarizedContent {
+    /// File content with oversized bodies replaced
+    pub content: String,
+    /// Symbols that were summarized (in source order)
+    pub symbols: Vec<SummarizedSymbol>,
+}
+
+impl SummarizedContent {
+    /// Check if any symbol was summarized
+    pub fn is_summarized(&self) -> bool {
+        !self.symbols.is_empty()
+    }
+}
+
+/// Apply a summarizer to every callable body in `content` that exceeds `budget`
+///
+/// Containers (classes, impls, traits) are never summarized as a whole; their
+/// methods are visited instead. Files in languages without an AST adapter are
+/// returned unchanged.
+pub fn summarize_symbols(
+    summarizer: &dyn Summarizer,
+    path: &str,
+    content: &str,
+    budget: usize,
+) -> SummarizedContent {
+    let unchanged = SummarizedContent {
+        content: content.to_string(),
+        symbols: Vec::new(),
+    };
+
+    let language = AstBridge::detect_language(std::path::Path::new(path));
+    let bridge = AstBridge::new();
+    let Some(file) = bridge.analyze_file(content, language) else {
+        return unchanged;
+    };
+
+    // Collect (declaration, body range) for callables, outermost containers excluded
+    let mut targets: Vec<&Declaration> = Vec::new();
+    collect_callables(&file.declarations, &mut targets);
+
+    let mut replacements: Vec<(usize, usize, String, SummarizedSymbol)> = Vec::new();
+    for decl in targets {
+        let Some(body_span) = decl.body_span else { continue };
+        let (start, end) = (body_span.start, body_span.end.min(content.len()));
+        if start >= end || !content.is_char_boundary(start) || !content.is_char_boundary(end) {
+            continue;
+        }
+
+        let body = &content[start..end];
+        let body_tokens = body.len() / 4;
+        if body_tokens <= budget {
+            continue;
+        }
+
+        let request = SummaryRequest {
+            path,
+            language,
+            declaration: decl,
+            body,
+            body_tokens,
+            budget,
+        };
+        let Some(summary) = summarizer.summarize(&request) else { continue };
+
+        let symbol = SummarizedSymbol {
+            name: decl.name.clone(),
+            start_line: body_span.start_line,
+            end_line: body_span.end_line,
+            original_tokens: body_tokens,
+        };
+        let indent = block_indent(content, start);
+        let action = ZoomAction::for_declaration(decl, content);
+        let wrapped = wrap_with_provenance(summarizer.name(), decl, &symbol, &summary, &action, language, indent);
+        replacements.push((start, end, wrapped, symbol));
+    }
+
+    if replacements.is_empty() {
+        return unchanged;
+    }
+
+    // Apply from the end so earlier offsets stay valid
+    replacements.sort_by_key(|(start, _, _, _)| *start);
+    let mut output = content.to_string();
+    for (start, end, text, _) in replacements.iter().rev() {
+        output.replace_range(*start..*end, text);
+    }
+
+    SummarizedContent {
+        content: output,
+        symbols: replacements.into_iter().map(|(_, _, _, s)| s).collect(),
+    }
+}
+
+pub(crate) fn collect_callables<'a>(decls: &'a [Declaration], out: &mut Vec<&'a Declaration>) {
+    for decl in decls {
+        match decl.kind {
+            DeclarationKind::Function | DeclarationKind::Method => out.push(decl),
+            _ => collect_callables(&decl.children, out),
+        }
+    }
+}
+
+/// Indentation to reuse when a body starts its own line (indentation-delimited blocks)
+///
+/// Returns an empty string when the body starts mid-line (e.g., `fn f() {`).
+fn block_indent(content: &str, start: usize) -> &str {
+    let line_start = content[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let prefix = &content[line_start..start];
+    if prefix.chars().all(char::is_whitespace) {
+        prefix
+    } else {
+        ""
+    }
+}
+
+/// Wrap a summary in provenance markers using the language's comment syntax
+fn wrap_with_provenance(
+    summarizer: &str,
+    decl: &Declaration,
+    symbol: &SummarizedSymbol,
+    summary: &str,
+    action: &ZoomAction,
+    language: LanguageId,
+    indent: &str,
+) -> String {
+    let (open, close) = comment_delimiters(language);
+    let header = format!(
+        "{}SUMMARY[{}]: {} {} (lines {}-{}, ~{} tokens){}",
+        open,
+        summarizer,
+        decl.kind.as_str(),
+        symbol.name,
+        symbol.start_line,
+        symbol.end_line,
+        symbol.original_tokens,
+        close
+    );
+    let footer = format!(
+        "{}END SUMMARY - ZOOM_AFFORDANCE: {}{}",
+        open, action.command, close
+    );
+
+    // The replaced region starts after `indent`, so re-indent the first summary line
+    // and the footer to keep indentation-delimited blocks (Python) well-formed
+    format!("{}\n{}{}\n{}{}", header, indent, summary.trim_start(), indent, footer)
+}
+
+pub(crate) fn comment_delimiters(language: LanguageId) -> (&'static str, &'static str) {
+    match language {
+        LanguageId::Python | LanguageId::Ruby | LanguageId::Bash => ("# ", ""),
+        _ => ("/* ", " */"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long_rust_fn() -> String {
+        let mut body = String::from("fn process(items: &[u32]) -> u32 {\n    let mut total = 0;\n");
+        for i in 0..40 {
+            body.push_str(&format!("    total += items.len() as u32 * {};\n", i));
+            if i == 20 {
+                body.push_str("    // Midpoint checkpoint\n");
+            }
+        }
+        body.push_str("    total\n}\n\nfn small() -> u32 {\n    1\n}\n");
+        body
+    }
+
+    #[test]
+    fn test_noop_summarizer_keeps_content() {
+        let source = long_rust_fn();
+        let result = summarize_symbols(&NoopSummarizer, "src/lib.rs", &source, 10);
+        assert!(!result.is_summarized());
+        assert_eq!(result.content, source);
+    }
+
+    #[test]
+    fn test_extractive_summarizer_replaces_large_bodies() {
+        let source = long_rust_fn();
+        let result = summarize_symbols(&ExtractiveSummarizer::new(), "src/lib.rs", &source, 20);
+
+        assert_eq!(result.symbols.len(), 1);
+        assert_eq!(result.symbols[0].name, "process");
+        assert!(result.content.contains("/* SUMMARY[extractive]: function process"));
+        assert!(result.content.contains("END SUMMARY - ZOOM_AFFORDANCE: pm_encoder --zoom function=process"));
+        assert!(result.content.contains("// Midpoint checkpoint"), "Comments are kept");
+        assert!(result.content.contains("lines elided"));
+        // Small function is untouched
+        assert!(result.content.contains("fn small() -> u32 {\n    1\n}"));
+    }
+
+    #[test]
+    fn test_budget_above_body_size_is_noop() {
+        let source = long_rust_fn();
+        let result = summarize_symbols(&ExtractiveSummarizer::new(), "src/lib.rs", &source, 100_000);
+        assert!(!result.is_summarized());
+    }
+
+    #[test]
+    fn test_unsupported_language_unchanged() {
+        let source = "some text\n".repeat(200);
+        let result = summarize_symbols(&ExtractiveSummarizer::new(), "notes.txt", &source, 1);
+        assert_eq!(result.content, source);
+    }
+
+    struct FixedSummarizer;
+
+    impl Summarizer for FixedSummarizer {
+        fn name(&self) -> &str {
+            "llm"
+        }
+
+        fn summarize(&self, request: &SummaryRequest<'_>) -> Option<String> {
+            Some(format!("{{ /* {} sums items */ }}", request.declaration.name))
+        }
+    }
+
+    #[test]
+    fn test_custom_summarizer_receives_declaration() {
+        let source = long_rust_fn();
+        let result = summarize_symbols(&FixedSummarizer, "src/lib.rs", &source, 20);
+        assert!(result.content.contains("SUMMARY[llm]"));
+        assert!(result.content.contains("process sums items"));
+    }
+
+    #[test]
+    fn test_python_summary_stays_indented() {
+        let mut source = String::from("def handler(event):\n    # Validate input\n");
+        for i in 0..30 {
+            source.push_str(&format!("    value_{} = event.get('key_{}')\n", i, i));
+        }
+        source.push_str("    return event\n");
+
+        let result = summarize_symbols(&ExtractiveSummarizer::new(), "app.py", &source, 20);
+        assert!(result.is_summarized());
+        assert!(result.content.contains("    # SUMMARY[extractive]: function handler"));
+        assert!(result.content.contains("\n    # END SUMMARY"));
+        assert!(result.content.contains("    return event"));
+    }
+
+    #[test]
+    fn test_extractive_short_body_declines() {
+        let decl = Declaration::new(
+            "f".to_string(),
+            DeclarationKind::Function,
+            voyager_ast::Span::new(0, 10, 1, 2),
+        );
+        let request = SummaryRequest {
+            path: "a.rs",
+            language: LanguageId::Rust,
+            declaration: &decl,
+            body: "{\n}",
+            body_tokens: 1,
+            budget: 0,
+        };
+        assert!(ExtractiveSummarizer::new().summarize(&request).is_none());
+    }
+}
@@ -0,0 +1,257 @@
+//! Declaration-level lens rules
+//!
+//! Lens `include`/`exclude` patterns pick files; [`SymbolRule`]s pick
+//! declarations inside them. A file is parsed into the voyager-ast IR and
+//! every declaration (outermost first) is checked against the rules in
+//! order: the first matching rule decides whether it is kept, dropped, or
+//! reduced to its signature. Dropped and reduced declarations leave a marker
+//! carrying the zoom affordance (by [`Anchor`]) that restores them, and their
+//! nested declarations are not visited. Files in languages without an AST adapter
+//! are left unchanged.
+
+use globset::Glob;
+use voyager_ast::testmap::is_test_declaration;
+use voyager_ast::{Declaration, LanguageId, PatternRules, Visibility};
+
+use crate::core::anchor::Anchor;
+use crate::core::ast_bridge::AstBridge;
+use crate::core::models::{SymbolAction, SymbolRule};
+use crate::core::summarizer::comment_delimiters;
+use crate::core::zoom::ZoomAction;
+
+/// Result of applying symbol rules to a file
+#[derive(Debug, Clone)]
+pub struct FilteredContent {
+    /// File content with dropped declarations and elided bodies replaced by markers
+    pub content: String,
+    /// Names of dropped declarations (in source order)
+    pub excluded: Vec<String>,
+    /// Names of declarations reduced to their signature (in source order)
+    pub signatures: Vec<String>,
+}
+
+impl FilteredContent {
+    /// Check if any declaration was dropped or reduced
+    pub fn is_filtered(&self) -> bool {
+        !self.excluded.is_empty() || !self.signatures.is_empty()
+    }
+}
+
+/// Apply `rules` to the declarations of `content`
+pub fn apply_symbol_rules(path: &str, content: &str, rules: &[SymbolRule]) -> FilteredContent {
+    let mut filtered = FilteredContent {
+        content: content.to_string(),
+        excluded: Vec::new(),
+        signatures: Vec::new(),
+    };
+    let rules: Vec<&SymbolRule> = rules.iter().filter(|rule| path_matches(rule, path)).collect();
+    if rules.is_empty() {
+        return filtered;
+    }
+
+    let language = AstBridge::detect_language(std::path::Path::new(path));
+    let Some(file) = AstBridge::new().analyze_file(content, language) else {
+        return filtered;
+    };
+
+    let mut replacements: Vec<(usize, usize, String)> = Vec::new();
+    let scope = Scope { path, content, language, rules: &rules };
+    visit(&scope, &file.declarations, "", &mut replacements, &mut filtered);
+    if replacements.is_empty() {
+        return filtered;
+    }
+
+    // Apply from the end so earlier offsets stay valid
+    replacements.sort_by_key(|(start, _, _)| *start);
+    for (start, end, text) in replacements.iter().rev() {
+        filtered.content.replace_range(*start..*end, text);
+    }
+    filtered
+}
+
+/// The file being filtered and the rules that apply to it
+struct Scope<'a> {
+    path: &'a str,
+    content: &'a str,
+    language: LanguageId,
+    rules: &'a [&'a SymbolRule],
+}
+
+impl Scope<'_> {
+    /// Zoom affordance restoring `decl`, addressed by its anchor
+    fn zoom(&self, decl: &Declaration, qualified_name: &str) -> ZoomAction {
+        let anchor = Anchor::for_declaration(self.path, qualified_name, decl, self.content);
+        ZoomAction::for_declaration(decl, self.content).with_anchor(anchor)
+    }
+}
+
+fn visit(
+    scope: &Scope,
+    decls: &[Declaration],
+    prefix: &str,
+    replacements: &mut Vec<(usize, usize, String)>,
+    filtered: &mut FilteredContent,
+) {
+    for decl in decls {
+        let qualified = if prefix.is_empty() { decl.name.clone() } else { format!("{}.{}", prefix, decl.name) };
+        let action = scope.rules.iter().find(|rule| matches(rule, decl)).map(|rule| rule.action);
+        let replacement = match action {
+            Some(SymbolAction::Exclude) => exclusion(decl, scope.content, scope.language, scope.zoom(decl, &qualified)),
+            Some(SymbolAction::Signature) => {
+                signature_only(decl, scope.content, scope.language, scope.zoom(decl, &qualified))
+            }
+            Some(SymbolAction::Include) | None => None,
+        };
+        match (replacement, action) {
+            (Some(replacement), Some(SymbolAction::Exclude)) => {
+                replacements.push(replacement);
+                filtered.excluded.push(decl.name.clone());
+            }
+            (Some(replacement), _) => {
+                replacements.push(replacement);
+                filtered.signatures.push(decl.name.clone());
+            }
+            (None, _) => visit(scope, &decl.children, &qualified, replacements, filtered),
+        }
+    }
+}
+
+fn path_matches(rule: &SymbolRule, path: &str) -> bool {
+    rule.path.as_ref().is_none_or(|pattern| PatternRules::new(&[pattern]).is_match(path, false))
+}
+
+/// Check the declaration criteria of a rule (the path is checked per file)
+fn matches(rule: &SymbolRule, decl: &Declaration) -> bool {
+    let kind_matches = rule.kinds.is_empty() || rule.kinds.iter().any(|kind| kind.eq_ignore_ascii_case(decl.kind.as_str()));
+    let visibility_matches = rule
+        .visibility
+        .as_deref()
+        .is_none_or(|visibility| visibility.eq_ignore_ascii_case(visibility_name(decl.visibility)));
+    let name_matches = rule.name.as_deref().is_none_or(|pattern| match Glob::new(pattern) {
+        Ok(glob) => glob.compile_matcher().is_match(&decl.name),
+        Err(_) => pattern == decl.name,
+    });
+    let tests_match = rule.tests.is_none_or(|tests| tests == is_test_declaration(decl));
+    let lines = decl.span.end_line.saturating_sub(decl.span.start_line) + 1;
+    let size_matches = rule.min_lines.is_none_or(|min| lines >= min);
+    kind_matches && visibility_matches && name_matches && tests_match && size_matches
+}
+
+fn visibility_name(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "public",
+        Visibility::Private => "private",
+        Visibility::Protected => "protected",
+        Visibility::Internal => "internal",
+        Visibility::Unknown => "unknown",
+    }
+}
+
+/// Replace the declaration's lines, with the attributes, decorators and doc
+/// comments directly above it, by a marker line
+fn exclusion(decl: &Declaration, content: &str, language: LanguageId, action: ZoomAction) -> Option<(usize, usize, String)> {
+    let (start, end) = (decl.span.start, decl.span.end.min(content.len()));
+    if start >= end || !content.is_char_boundary(start) || !content.is_char_boundary(end) {
+        return None;
+    }
+    let line_start = preamble_start(content, start);
+    let line_end = content[end..].find('\n').map_or(content.len(), |i| end + i);
+
+    let indent: String = content[line_start..].chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+    let (open, close) = comment_delimiters(language);
+    let marker = format!(
+        "{}{}LENS: excluded {} {} (lines {}-{}) - ZOOM_AFFORDANCE: {}{}",
+        indent,
+        open,
+        decl.kind.as_str(),
+        decl.name,
+        decl.span.start_line,
+        decl.span.end_line,
+        action.command,
+        close
+    );
+    Some((line_start, line_end, marker))
+}
+
+/// Start of the line at `start`, moved up over the attributes, decorators
+/// and doc comments directly above it
+pub(crate) fn preamble_start(content: &str, start: usize) -> usize {
+    let mut line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+    while line_start > 0 {
+        let previous = content[..line_start - 1].rfind('\n').map_or(0, |i| i + 1);
+        let line = content[previous..line_start - 1].trim_start();
+        let preamble = ["#[", "@", "///", "//!", "/**", "* ", "*/"].iter().any(|p| line.starts_with(p)) || line == "*";
+        if !preamble {
+            break;
+        }
+        line_start = previous;
+    }
+    line_start
+}
+
+/// Replace the declaration's body by a marker
+fn signature_only(
+    decl: &Declaration,
+    content: &str,
+    language: LanguageId,
+    action: ZoomAction,
+) -> Option<(usize, usize, String)> {
+    let body_span = decl.body_span?;
+    let (start, end) = (body_span.start, body_span.end.min(content.len()));
+    if start >= end || !content.is_char_boundary(start) || !content.is_char_boundary(end) {
+        return None;
+    }
+    let (open, close) = comment_delimiters(language);
+    let marker = format!(
+        "{}LENS: body elided (lines {}-{}, ~{} tokens) - ZOOM_AFFORDANCE: {}{}",
+        open,
+        body_span.start_line,
+        body_span.end_line,
+        (end - start) / 4,
+        action.command,
+        close
+    );
+    // Keep the body well-formed: braces for C-like blocks, `...` for Python
+    let body = &content[start..end];
+    let replacement = if body.starts_with('{') && body.ends_with('}') {
+        format!("{{ {} }}", marker)
+    } else if language == LanguageId::Python {
+        format!("... {}", marker)
+    } else {
+        marker
+    };
+    Some((start, end, replacement))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "pub struct Model {\n    id: u32,\n}\n\nstruct Cache;\n\nfn load() -> u32 {\n    let x = 1;\n    x + 1\n}\n\n#[test]\nfn test_load() {\n    assert_eq!(load(), 2);\n}\n";
+
+    #[test]
+    fn test_symbol_rules_exclude_include_and_signature() {
+        let rules = vec![
+            SymbolRule::new(SymbolAction::Exclude).with_tests(true),
+            SymbolRule::new(SymbolAction::Include).with_kinds(&["struct"]).with_visibility("public"),
+            SymbolRule::new(SymbolAction::Exclude).with_kinds(&["struct"]),
+            SymbolRule::new(SymbolAction::Signature).with_kinds(&["function"]).with_min_lines(3),
+        ];
+        let filtered = apply_symbol_rules("models/lib.rs", SOURCE, &rules);
+
+        assert_eq!(filtered.excluded, vec!["Cache", "test_load"]);
+        assert_eq!(filtered.signatures, vec!["load"]);
+        assert!(filtered.content.contains("pub struct Model {\n    id: u32,\n}"));
+        assert!(!filtered.content.contains("#[test]"), "attributes go with the declaration");
+        assert!(filtered.content.contains("/* LENS: excluded function test_load (lines 13-15) - ZOOM_AFFORDANCE: pm_encoder --zoom anchor=models/lib.rs#test_load@"));
+        assert!(filtered.content.contains("fn load() -> u32 { /* LENS: body elided (lines 7-10"));
+        assert!(!filtered.content.contains("x + 1"));
+    }
+
+    #[test]
+    fn test_symbol_rules_scoped_by_path() {
+        let rules = vec![SymbolRule::new(SymbolAction::Exclude).with_path("models/")];
+        assert!(!apply_symbol_rules("src/lib.rs", SOURCE, &rules).is_filtered());
+        assert_eq!(apply_symbol_rules("models/lib.rs", SOURCE, &rules).excluded.len(), 4);
+    }
+}
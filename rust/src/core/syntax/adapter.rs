@@ -105,7 +105,7 @@ impl TreeSitterAdapter {
         language: Language,
     ) -> Result<tree_sitter::Language, SyntaxError> {
         let ts_lang = match language {
-            // Core supported languages (17 grammars)
+            // Core supported languages (18 grammars)
             Language::Rust => tree_sitter_rust::LANGUAGE.into(),
             Language::Python => tree_sitter_python::LANGUAGE.into(),
             Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
@@ -121,12 +121,12 @@ impl TreeSitterAdapter {
             Language::Css => tree_sitter_css::LANGUAGE.into(),
             Language::Json => tree_sitter_json::LANGUAGE.into(),
             Language::Bash => tree_sitter_bash::LANGUAGE.into(),
+            Language::Php => tree_sitter_php::LANGUAGE_PHP.into(),
 
             // Reserved for future grammars (Phase 1B)
             // Note: TOML and Markdown require tree-sitter version updates
             Language::Toml
             | Language::Markdown
-            | Language::Php
             | Language::Swift
             | Language::Kotlin
             | Language::Scala
@@ -171,6 +171,7 @@ impl TreeSitterAdapter {
             Language::Ruby => self.extract_ruby_symbols(&mut ast, root, source),
             Language::Php => self.extract_php_symbols(&mut ast, root, source),
             Language::Swift => self.extract_swift_symbols(&mut ast, root, source),
+            Language::Bash => self.extract_bash_symbols(&mut ast, root, source, false),
             _ => self.extract_generic_symbols(&mut ast, root, source),
         }
 
@@ -737,6 +738,21 @@ impl TreeSitterAdapter {
                             SymbolVisibility::Private
                         };
                         symbol.span = Some(self.node_span(child));
+                        symbol.parameters = self.extract_go_params(child.child_by_field_name("parameters"), source);
+                        symbol.return_type = child
+                            .child_by_field_name("result")
+                            .map(|n| self.node_text(n, source));
+                        // func (s *Server) Start() => parent "Server", pointer receiver
+                        if let Some(receiver) = self.extract_go_params(child.child_by_field_name("receiver"), source).pop() {
+                            let receiver_type = receiver.type_annotation.unwrap_or_default();
+                            let kind = if receiver_type.starts_with('*') { "pointer" } else { "value" };
+                            let base = receiver_type.trim_start_matches('*');
+                            let base = base.split('[').next().unwrap_or(base).trim();
+                            if !base.is_empty() {
+                                symbol.parent = Some(base.to_string());
+                                symbol.metadata.insert("receiver".to_string(), kind.to_string());
+                            }
+                        }
                         ast.symbols.push(symbol);
                     }
                 }
@@ -757,6 +773,111 @@ impl TreeSitterAdapter {
                 _ => {}
             }
         }
+
+        self.link_children(ast);
+    }
+
+    fn extract_go_params(&self, params_node: Option<tree_sitter::Node>, source: &[u8]) -> Vec<Parameter> {
+        let mut params = Vec::new();
+        let Some(params_node) = params_node else { return params };
+
+        let mut cursor = params_node.walk();
+        for child in params_node.children(&mut cursor) {
+            if !matches!(child.kind(), "parameter_declaration" | "variadic_parameter_declaration") {
+                continue;
+            }
+            let type_ann = child.child_by_field_name("type").map(|n| self.node_text(n, source));
+            let is_rest = child.kind() == "variadic_parameter_declaration";
+            // `a, b int` declares several names sharing one type
+            let mut name_cursor = child.walk();
+            let names: Vec<String> = child
+                .children_by_field_name("name", &mut name_cursor)
+                .map(|n| self.node_text(n, source))
+                .collect();
+            if names.is_empty() {
+                params.push(Parameter {
+                    name: String::new(),
+                    type_annotation: type_ann,
+                    default_value: None,
+                    is_rest,
+                    is_keyword_only: false,
+                });
+                continue;
+            }
+            for name in names {
+                params.push(Parameter {
+                    name,
+                    type_annotation: type_ann.clone(),
+                    default_value: None,
+                    is_rest,
+                    is_keyword_only: false,
+                });
+            }
+        }
+        params
+    }
+
+    /// Interface method set: one Method child per method, embedded interfaces in metadata
+    fn extract_go_interface_methods(
+        &self,
+        ast: &mut NormalizedAst,
+        interface_node: tree_sitter::Node,
+        source: &[u8],
+        interface_name: &str,
+    ) -> Vec<String> {
+        let mut embeds = Vec::new();
+        let mut cursor = interface_node.walk();
+        for child in interface_node.children(&mut cursor) {
+            match child.kind() {
+                // method_spec in older grammar releases
+                "method_elem" | "method_spec" => {
+                    if let Some(name_node) = child.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        let mut symbol = Symbol::new(name.clone(), SymbolKind::Method, self.node_location(name_node));
+                        symbol.visibility = if name.chars().next().map(|c| c.is_uppercase()).unwrap_or(false) {
+                            SymbolVisibility::Public
+                        } else {
+                            SymbolVisibility::Private
+                        };
+                        symbol.parent = Some(interface_name.to_string());
+                        symbol.span = Some(self.node_span(child));
+                        symbol.signature = Some(self.node_text(child, source));
+                        symbol.parameters = self.extract_go_params(child.child_by_field_name("parameters"), source);
+                        symbol.return_type = child
+                            .child_by_field_name("result")
+                            .map(|n| self.node_text(n, source));
+                        ast.symbols.push(symbol);
+                    }
+                }
+                "type_elem" | "constraint_elem" => {
+                    embeds.push(self.node_text(child, source));
+                }
+                _ => {}
+            }
+        }
+        embeds
+    }
+
+    /// Fill each container's `children` from the `parent` of the other symbols
+    ///
+    /// `parent` is the container's path: its name, prefixed with its own
+    /// parent and `::` when nested (`Outer::Inner`).
+    fn link_children(&self, ast: &mut NormalizedAst) {
+        let links: Vec<(String, String)> = ast.symbols
+            .iter()
+            .filter_map(|s| s.parent.clone().map(|parent| (parent, s.name.clone())))
+            .collect();
+        let path = |s: &Symbol| match &s.parent {
+            Some(parent) => format!("{}::{}", parent, s.name),
+            None => s.name.clone(),
+        };
+        for (parent, child) in links {
+            if let Some(container) = ast.symbols.iter_mut().find(|s| s.is_container() && path(s) == parent) {
+                if !container.children.contains(&child) {
+                    container.children.push(child);
+                }
+            }
+        }
     }
 
     fn extract_go_type(&self, ast: &mut NormalizedAst, node: tree_sitter::Node, source: &[u8]) {
@@ -778,6 +899,15 @@ impl TreeSitterAdapter {
                         SymbolVisibility::Private
                     };
                     symbol.span = Some(self.node_span(child));
+                    let embeds = match type_node {
+                        Some(interface) if kind == SymbolKind::Interface => {
+                            self.extract_go_interface_methods(ast, interface, source, &name)
+                        }
+                        _ => Vec::new(),
+                    };
+                    if !embeds.is_empty() {
+                        symbol.metadata.insert("embeds".to_string(), embeds.join(", "));
+                    }
                     ast.symbols.push(symbol);
                 }
             }
@@ -947,79 +1077,544 @@ impl TreeSitterAdapter {
     }
 
     fn extract_ruby_symbols(&self, ast: &mut NormalizedAst, node: tree_sitter::Node, source: &[u8]) {
+        self.extract_ruby_body(ast, node, source, None, false);
+        self.link_children(ast);
+    }
+
+    /// Walk one Ruby body. `scope` is the enclosing `A::B` path and
+    /// `singleton` is set inside `class << self`.
+    fn extract_ruby_body(
+        &self,
+        ast: &mut NormalizedAst,
+        node: tree_sitter::Node,
+        source: &[u8],
+        scope: Option<&str>,
+        singleton: bool,
+    ) {
+        // Bare `private`/`protected`/`public` changes the default for what follows
+        let mut visibility = SymbolVisibility::Public;
         let mut cursor = node.walk();
 
         for child in node.children(&mut cursor) {
             match child.kind() {
-                "method" => {
+                "class" | "module" => {
                     if let Some(name_node) = child.child_by_field_name("name") {
                         let name = self.node_text(name_node, source);
-                        let mut symbol = Symbol::new(name, SymbolKind::Function, self.node_location(name_node));
+                        let kind = if child.kind() == "class" { SymbolKind::Class } else { SymbolKind::Module };
+                        let mut symbol = Symbol::new(name.clone(), kind, self.node_location(name_node));
+                        symbol.visibility = SymbolVisibility::Public;
+                        symbol.parent = scope.map(str::to_string);
                         symbol.span = Some(self.node_span(child));
+                        if let Some(superclass) = child.child_by_field_name("superclass") {
+                            let superclass = self.node_text(superclass, source);
+                            symbol.metadata.insert(
+                                "superclass".to_string(),
+                                superclass.trim_start_matches('<').trim().to_string(),
+                            );
+                        }
                         ast.symbols.push(symbol);
+
+                        let path = match scope {
+                            Some(scope) => format!("{}::{}", scope, name),
+                            None => name,
+                        };
+                        if let Some(body) = self.ruby_body(child) {
+                            self.extract_ruby_body(ast, body, source, Some(&path), false);
+                        }
                     }
                 }
-                "class" => {
-                    if let Some(name_node) = child.child_by_field_name("name") {
-                        let name = self.node_text(name_node, source);
-                        let mut symbol = Symbol::new(name, SymbolKind::Class, self.node_location(name_node));
-                        symbol.span = Some(self.node_span(child));
-                        ast.symbols.push(symbol);
+                "singleton_class" => {
+                    // class << self ... end
+                    if let Some(body) = self.ruby_body(child) {
+                        self.extract_ruby_body(ast, body, source, scope, true);
                     }
                 }
-                "module" => {
+                "method" | "singleton_method" => {
                     if let Some(name_node) = child.child_by_field_name("name") {
                         let name = self.node_text(name_node, source);
-                        let mut symbol = Symbol::new(name, SymbolKind::Module, self.node_location(name_node));
+                        let class_method = singleton || child.kind() == "singleton_method";
+                        let kind = if scope.is_none() {
+                            SymbolKind::Function
+                        } else if name == "initialize" && !class_method {
+                            SymbolKind::Constructor
+                        } else {
+                            SymbolKind::Method
+                        };
+                        let mut symbol = Symbol::new(name, kind, self.node_location(name_node));
+                        symbol.visibility = if child.kind() == "singleton_method" {
+                            SymbolVisibility::Public
+                        } else {
+                            visibility
+                        };
+                        symbol.parent = scope.map(str::to_string);
                         symbol.span = Some(self.node_span(child));
+                        symbol.parameters = self.extract_ruby_params(&child, source);
+                        if class_method {
+                            symbol.metadata.insert("class_method".to_string(), "true".to_string());
+                        }
                         ast.symbols.push(symbol);
                     }
                 }
+                "identifier" => {
+                    if let Some(modifier) = self.ruby_visibility(&self.node_text(child, source)) {
+                        visibility = modifier;
+                    }
+                }
+                "call" => {
+                    self.extract_ruby_call(ast, child, source, scope, singleton, visibility);
+                }
+                "assignment" => {
+                    if let Some(left) = child.child_by_field_name("left") {
+                        if left.kind() == "constant" {
+                            let mut symbol = Symbol::new(
+                                self.node_text(left, source),
+                                SymbolKind::Constant,
+                                self.node_location(left),
+                            );
+                            symbol.parent = scope.map(str::to_string);
+                            ast.symbols.push(symbol);
+                        }
+                    }
+                }
                 _ => {}
             }
         }
     }
 
+    /// `require`, `attr_*` and `private :name` / `private def ...` calls
+    fn extract_ruby_call(
+        &self,
+        ast: &mut NormalizedAst,
+        call: tree_sitter::Node,
+        source: &[u8],
+        scope: Option<&str>,
+        singleton: bool,
+        visibility: SymbolVisibility,
+    ) {
+        if call.child_by_field_name("receiver").is_some() {
+            return;
+        }
+        let (Some(method), Some(args_node)) = (call.child_by_field_name("method"), call.child_by_field_name("arguments")) else {
+            return;
+        };
+        let method = self.node_text(method, source);
+        let mut cursor = args_node.walk();
+        let args: Vec<tree_sitter::Node> = args_node.named_children(&mut cursor).collect();
+
+        match method.as_str() {
+            "require" | "require_relative" => {
+                for arg in args.iter().filter(|a| a.kind() == "string") {
+                    let path = self.node_text(*arg, source).trim_matches(|c| c == '\'' || c == '"').to_string();
+                    let path = if method == "require_relative" && !path.starts_with('.') {
+                        format!("./{}", path)
+                    } else {
+                        path
+                    };
+                    ast.imports.push(Import {
+                        source: path,
+                        kind: ImportKind::Module,
+                        alias: None,
+                        items: Vec::new(),
+                        location: self.node_location(call),
+                        type_only: false,
+                    });
+                }
+            }
+            "attr_accessor" | "attr_reader" | "attr_writer" if scope.is_some() => {
+                for arg in args.iter().filter(|a| a.kind() == "simple_symbol") {
+                    let name = self.node_text(*arg, source).trim_start_matches(':').to_string();
+                    let mut symbol = Symbol::new(name, SymbolKind::Property, self.node_location(*arg));
+                    symbol.visibility = visibility;
+                    symbol.parent = scope.map(str::to_string);
+                    symbol.metadata.insert(
+                        "accessor".to_string(),
+                        method.trim_start_matches("attr_").to_string(),
+                    );
+                    ast.symbols.push(symbol);
+                }
+            }
+            modifier => {
+                let Some(modifier) = self.ruby_visibility(modifier) else { return };
+                for arg in args {
+                    match arg.kind() {
+                        "simple_symbol" => {
+                            let name = self.node_text(arg, source).trim_start_matches(':').to_string();
+                            if let Some(symbol) = ast.symbols
+                                .iter_mut()
+                                .rev()
+                                .find(|s| s.name == name && s.parent.as_deref() == scope)
+                            {
+                                symbol.visibility = modifier;
+                            }
+                        }
+                        "method" => {
+                            // private def helper ... end
+                            let before = ast.symbols.len();
+                            self.extract_ruby_body(ast, args_node, source, scope, singleton);
+                            for symbol in &mut ast.symbols[before..] {
+                                symbol.visibility = modifier;
+                            }
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    fn extract_ruby_params(&self, method_node: &tree_sitter::Node, source: &[u8]) -> Vec<Parameter> {
+        let mut params = Vec::new();
+        let Some(params_node) = method_node.child_by_field_name("parameters") else { return params };
+
+        let mut cursor = params_node.walk();
+        for child in params_node.named_children(&mut cursor) {
+            let name_node = if child.kind() == "identifier" { Some(child) } else { child.child_by_field_name("name") };
+            let Some(name_node) = name_node else { continue };
+            params.push(Parameter {
+                name: self.node_text(name_node, source),
+                type_annotation: None,
+                default_value: child.child_by_field_name("value").map(|n| self.node_text(n, source)),
+                is_rest: matches!(child.kind(), "splat_parameter" | "hash_splat_parameter"),
+                is_keyword_only: child.kind() == "keyword_parameter",
+            });
+        }
+        params
+    }
+
+    fn ruby_visibility(&self, keyword: &str) -> Option<SymbolVisibility> {
+        match keyword {
+            "private" => Some(SymbolVisibility::Private),
+            "protected" => Some(SymbolVisibility::Protected),
+            "public" => Some(SymbolVisibility::Public),
+            _ => None,
+        }
+    }
+
+    /// Body of a Ruby class/module/singleton class
+    fn ruby_body<'a>(&self, node: tree_sitter::Node<'a>) -> Option<tree_sitter::Node<'a>> {
+        node.child_by_field_name("body").or_else(|| {
+            (0..node.child_count())
+                .filter_map(|i| node.child(i))
+                .find(|c| c.kind() == "body_statement")
+        })
+    }
+
     fn extract_php_symbols(&self, ast: &mut NormalizedAst, node: tree_sitter::Node, source: &[u8]) {
+        self.extract_php_statements(ast, node, source, None);
+        self.link_children(ast);
+    }
+
+    /// Walk PHP statements. `namespace` indexes the enclosing `Namespace`
+    /// symbol, which collects the top-level declarations as children.
+    fn extract_php_statements(
+        &self,
+        ast: &mut NormalizedAst,
+        node: tree_sitter::Node,
+        source: &[u8],
+        mut namespace: Option<usize>,
+    ) {
         let mut cursor = node.walk();
 
         for child in node.children(&mut cursor) {
+            let before = ast.symbols.len();
             match child.kind() {
-                "function_definition" => {
-                    if let Some(name_node) = child.child_by_field_name("name") {
+                "namespace_definition" => {
+                    let body = child.child_by_field_name("body");
+                    let index = child.child_by_field_name("name").map(|name_node| {
                         let name = self.node_text(name_node, source);
-                        let mut symbol = Symbol::new(name, SymbolKind::Function, self.node_location(name_node));
+                        let mut symbol = Symbol::new(name, SymbolKind::Namespace, self.node_location(name_node));
+                        symbol.visibility = SymbolVisibility::Public;
                         symbol.span = Some(self.node_span(child));
                         ast.symbols.push(symbol);
+                        ast.symbols.len() - 1
+                    });
+                    match body {
+                        // namespace Foo { ... } (or the global `namespace { ... }`)
+                        Some(body) => self.extract_php_statements(ast, body, source, index),
+                        // namespace Foo; applies to the rest of the file
+                        None => namespace = index,
                     }
+                    continue;
                 }
-                "class_declaration" => {
+                "namespace_use_declaration" => self.extract_php_use(ast, child, source),
+                "function_definition" => {
                     if let Some(name_node) = child.child_by_field_name("name") {
                         let name = self.node_text(name_node, source);
-                        let mut symbol = Symbol::new(name, SymbolKind::Class, self.node_location(name_node));
+                        let mut symbol = Symbol::new(name, SymbolKind::Function, self.node_location(name_node));
+                        symbol.visibility = SymbolVisibility::Public;
                         symbol.span = Some(self.node_span(child));
+                        symbol.signature = Some(self.php_signature(child, source));
+                        symbol.parameters = self.extract_php_params(child.child_by_field_name("parameters"), source);
+                        symbol.return_type = child
+                            .child_by_field_name("return_type")
+                            .map(|n| self.node_text(n, source));
                         ast.symbols.push(symbol);
                     }
                 }
-                "interface_declaration" => {
-                    if let Some(name_node) = child.child_by_field_name("name") {
-                        let name = self.node_text(name_node, source);
-                        let mut symbol = Symbol::new(name, SymbolKind::Interface, self.node_location(name_node));
-                        symbol.span = Some(self.node_span(child));
-                        ast.symbols.push(symbol);
+                "class_declaration" | "interface_declaration" | "trait_declaration" | "enum_declaration" => {
+                    self.extract_php_type(ast, child, source);
+                }
+                "const_declaration" => self.extract_php_constants(ast, child, source, None),
+                _ => {}
+            }
+
+            if let Some(index) = namespace {
+                let name = ast.symbols[index].name.clone();
+                let mut added = Vec::new();
+                for symbol in ast.symbols[before..].iter_mut().filter(|s| s.parent.is_none()) {
+                    symbol.metadata.insert("namespace".to_string(), name.clone());
+                    added.push(symbol.name.clone());
+                }
+                ast.symbols[index].children.extend(added);
+            }
+        }
+    }
+
+    /// `use A\B;`, `use A\B as C;` and grouped `use A\{B, C};`
+    fn extract_php_use(&self, ast: &mut NormalizedAst, node: tree_sitter::Node, source: &[u8]) {
+        let mut prefix = String::new();
+        let mut clauses = Vec::new();
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            match child.kind() {
+                "namespace_name" => prefix = format!("{}\\", self.node_text(child, source)),
+                "namespace_use_clause" => clauses.push(child),
+                "namespace_use_group" => {
+                    let mut group_cursor = child.walk();
+                    clauses.extend(
+                        child
+                            .named_children(&mut group_cursor)
+                            .filter(|c| matches!(c.kind(), "namespace_use_clause" | "namespace_use_group_clause")),
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        for clause in clauses {
+            let mut name = None;
+            let mut alias = clause.child_by_field_name("alias").map(|n| self.node_text(n, source));
+            let mut clause_cursor = clause.walk();
+            for part in clause.named_children(&mut clause_cursor) {
+                match part.kind() {
+                    "qualified_name" | "namespace_name" | "name" if name.is_none() => {
+                        name = Some(self.node_text(part, source));
+                    }
+                    "name" if alias.is_none() => alias = Some(self.node_text(part, source)),
+                    "namespace_aliasing_clause" => {
+                        alias = part.named_child(0).map(|n| self.node_text(n, source));
                     }
+                    _ => {}
                 }
-                "trait_declaration" => {
-                    if let Some(name_node) = child.child_by_field_name("name") {
-                        let name = self.node_text(name_node, source);
-                        let mut symbol = Symbol::new(name, SymbolKind::Trait, self.node_location(name_node));
-                        symbol.span = Some(self.node_span(child));
-                        ast.symbols.push(symbol);
+            }
+            let Some(name) = name else { continue };
+            ast.imports.push(Import {
+                source: format!("{}{}", prefix, name.trim_start_matches('\\')),
+                kind: ImportKind::Selective,
+                alias,
+                items: Vec::new(),
+                location: self.node_location(clause),
+                type_only: false,
+            });
+        }
+    }
+
+    /// Class, interface, trait or enum with its members
+    fn extract_php_type(&self, ast: &mut NormalizedAst, node: tree_sitter::Node, source: &[u8]) {
+        let Some(name_node) = node.child_by_field_name("name") else { return };
+        let name = self.node_text(name_node, source);
+        let kind = match node.kind() {
+            "interface_declaration" => SymbolKind::Interface,
+            "trait_declaration" => SymbolKind::Trait,
+            "enum_declaration" => SymbolKind::Enum,
+            _ => SymbolKind::Class,
+        };
+        let mut symbol = Symbol::new(name.clone(), kind, self.node_location(name_node));
+        symbol.visibility = SymbolVisibility::Public;
+        symbol.span = Some(self.node_span(node));
+
+        let mut cursor = node.walk();
+        for part in node.named_children(&mut cursor) {
+            let key = match part.kind() {
+                "base_clause" => "extends",
+                "class_interface_clause" => "implements",
+                _ => continue,
+            };
+            let mut names_cursor = part.walk();
+            let names: Vec<String> = part
+                .named_children(&mut names_cursor)
+                .map(|n| self.node_text(n, source))
+                .collect();
+            symbol.metadata.insert(key.to_string(), names.join(", "));
+        }
+        let index = ast.symbols.len();
+        ast.symbols.push(symbol);
+
+        let Some(body) = node.child_by_field_name("body") else { return };
+        let mut traits = Vec::new();
+        let mut body_cursor = body.walk();
+        for member in body.named_children(&mut body_cursor) {
+            match member.kind() {
+                "method_declaration" => {
+                    let Some(method_name) = member.child_by_field_name("name") else { continue };
+                    let method = self.node_text(method_name, source);
+                    let kind = if method == "__construct" { SymbolKind::Constructor } else { SymbolKind::Method };
+                    let mut symbol = Symbol::new(method, kind, self.node_location(method_name));
+                    symbol.visibility = self.php_visibility(member, source);
+                    symbol.parent = Some(name.clone());
+                    symbol.span = Some(self.node_span(member));
+                    symbol.signature = Some(self.php_signature(member, source));
+                    let params = member.child_by_field_name("parameters");
+                    symbol.parameters = self.extract_php_params(params, source);
+                    symbol.return_type = member
+                        .child_by_field_name("return_type")
+                        .map(|n| self.node_text(n, source));
+                    if self.php_has_modifier(member, "static_modifier") {
+                        symbol.metadata.insert("static".to_string(), "true".to_string());
+                    }
+                    if self.php_has_modifier(member, "abstract_modifier") {
+                        symbol.metadata.insert("abstract".to_string(), "true".to_string());
+                    }
+                    ast.symbols.push(symbol);
+
+                    // Constructor property promotion: __construct(private Foo $foo)
+                    if let Some(params) = params {
+                        let mut params_cursor = params.walk();
+                        for param in params.named_children(&mut params_cursor) {
+                            if param.kind() != "property_promotion_parameter" {
+                                continue;
+                            }
+                            let Some(param_name) = param.child_by_field_name("name") else { continue };
+                            let mut property = Symbol::new(
+                                self.node_text(param_name, source).trim_start_matches('$').to_string(),
+                                SymbolKind::Property,
+                                self.node_location(param_name),
+                            );
+                            property.visibility = self.php_visibility(param, source);
+                            property.parent = Some(name.clone());
+                            property.metadata.insert("promoted".to_string(), "true".to_string());
+                            ast.symbols.push(property);
+                        }
                     }
                 }
+                "property_declaration" => {
+                    let visibility = self.php_visibility(member, source);
+                    let mut elements_cursor = member.walk();
+                    for element in member.named_children(&mut elements_cursor) {
+                        if element.kind() != "property_element" {
+                            continue;
+                        }
+                        let mut element_cursor = element.walk();
+                        let variable = element.child_by_field_name("name").or_else(|| {
+                            element.named_children(&mut element_cursor).find(|n| n.kind() == "variable_name")
+                        });
+                        let Some(variable) = variable else { continue };
+                        let mut property = Symbol::new(
+                            self.node_text(variable, source).trim_start_matches('$').to_string(),
+                            SymbolKind::Property,
+                            self.node_location(variable),
+                        );
+                        property.visibility = visibility;
+                        property.parent = Some(name.clone());
+                        property.span = Some(self.node_span(member));
+                        if self.php_has_modifier(member, "static_modifier") {
+                            property.metadata.insert("static".to_string(), "true".to_string());
+                        }
+                        ast.symbols.push(property);
+                    }
+                }
+                "const_declaration" => self.extract_php_constants(ast, member, source, Some(&name)),
+                "enum_case" => {
+                    if let Some(case_name) = member.child_by_field_name("name") {
+                        let mut variant = Symbol::new(
+                            self.node_text(case_name, source),
+                            SymbolKind::EnumVariant,
+                            self.node_location(case_name),
+                        );
+                        variant.visibility = SymbolVisibility::Public;
+                        variant.parent = Some(name.clone());
+                        variant.span = Some(self.node_span(member));
+                        ast.symbols.push(variant);
+                    }
+                }
+                "use_declaration" => {
+                    // Trait uses: use HasFactory, Notifiable;
+                    let mut use_cursor = member.walk();
+                    traits.extend(
+                        member
+                            .named_children(&mut use_cursor)
+                            .filter(|n| matches!(n.kind(), "name" | "qualified_name"))
+                            .map(|n| self.node_text(n, source)),
+                    );
+                }
                 _ => {}
             }
         }
+        if !traits.is_empty() {
+            ast.symbols[index].metadata.insert("traits".to_string(), traits.join(", "));
+        }
+    }
+
+    /// `const A = 1, B = 2;` at top level or in a class body
+    fn extract_php_constants(&self, ast: &mut NormalizedAst, node: tree_sitter::Node, source: &[u8], parent: Option<&str>) {
+        let visibility = self.php_visibility(node, source);
+        let mut cursor = node.walk();
+        for element in node.named_children(&mut cursor) {
+            if element.kind() != "const_element" {
+                continue;
+            }
+            let Some(name_node) = element.named_child(0) else { continue };
+            let mut symbol = Symbol::new(self.node_text(name_node, source), SymbolKind::Constant, self.node_location(name_node));
+            symbol.visibility = visibility;
+            symbol.parent = parent.map(str::to_string);
+            symbol.span = Some(self.node_span(element));
+            ast.symbols.push(symbol);
+        }
+    }
+
+    fn extract_php_params(&self, params_node: Option<tree_sitter::Node>, source: &[u8]) -> Vec<Parameter> {
+        let mut params = Vec::new();
+        let Some(params_node) = params_node else { return params };
+
+        let mut cursor = params_node.walk();
+        for child in params_node.named_children(&mut cursor) {
+            let Some(name_node) = child.child_by_field_name("name") else { continue };
+            params.push(Parameter {
+                name: self.node_text(name_node, source).trim_start_matches('$').to_string(),
+                type_annotation: child.child_by_field_name("type").map(|n| self.node_text(n, source)),
+                default_value: child.child_by_field_name("default_value").map(|n| self.node_text(n, source)),
+                is_rest: child.kind() == "variadic_parameter",
+                is_keyword_only: false,
+            });
+        }
+        params
+    }
+
+    /// Declared visibility; PHP members without a modifier are public
+    fn php_visibility(&self, node: tree_sitter::Node, source: &[u8]) -> SymbolVisibility {
+        let mut cursor = node.walk();
+        let modifier = node
+            .children(&mut cursor)
+            .find(|c| c.kind() == "visibility_modifier")
+            .map(|c| self.node_text(c, source).to_lowercase());
+        match modifier.as_deref() {
+            Some("private") => SymbolVisibility::Private,
+            Some("protected") => SymbolVisibility::Protected,
+            _ => SymbolVisibility::Public,
+        }
+    }
+
+    fn php_has_modifier(&self, node: tree_sitter::Node, kind: &str) -> bool {
+        let mut cursor = node.walk();
+        let found = node.children(&mut cursor).any(|c| c.kind() == kind);
+        found
+    }
+
+    /// Declaration text up to the body
+    fn php_signature(&self, node: tree_sitter::Node, source: &[u8]) -> String {
+        let end = node.child_by_field_name("body").map_or(node.end_byte(), |b| b.start_byte());
+        String::from_utf8_lossy(&source[node.start_byte()..end]).trim().trim_end_matches(';').to_string()
     }
 
     fn extract_swift_symbols(&self, ast: &mut NormalizedAst, node: tree_sitter::Node, source: &[u8]) {
@@ -1072,6 +1667,85 @@ impl TreeSitterAdapter {
         }
     }
 
+    /// Functions, `source`d scripts and exported variables. Declarations
+    /// inside function bodies (`local`, nested `export`) are skipped.
+    fn extract_bash_symbols(&self, ast: &mut NormalizedAst, node: tree_sitter::Node, source: &[u8], in_function: bool) {
+        let mut cursor = node.walk();
+
+        for child in node.children(&mut cursor) {
+            match child.kind() {
+                "function_definition" => {
+                    if let Some(name_node) = child.child_by_field_name("name") {
+                        let name = self.node_text(name_node, source);
+                        let mut symbol = Symbol::new(name.clone(), SymbolKind::Function, self.node_location(name_node));
+                        symbol.visibility = SymbolVisibility::Public;
+                        symbol.span = Some(self.node_span(child));
+                        symbol.signature = Some(format!("{}()", name));
+                        ast.symbols.push(symbol);
+                    }
+                    if let Some(body) = child.child_by_field_name("body") {
+                        self.extract_bash_symbols(ast, body, source, true);
+                    }
+                }
+                "command" => {
+                    let name = child.child_by_field_name("name").map(|n| self.node_text(n, source));
+                    if matches!(name.as_deref(), Some("source") | Some(".")) {
+                        let mut args = child.walk();
+                        let path = child.children_by_field_name("argument", &mut args).next();
+                        if let Some(path) = path {
+                            ast.imports.push(Import {
+                                source: self.node_text(path, source).trim_matches(|c| c == '"' || c == '\'').to_string(),
+                                kind: ImportKind::Module,
+                                alias: None,
+                                items: Vec::new(),
+                                location: self.node_location(child),
+                                type_only: false,
+                            });
+                        }
+                    }
+                }
+                "declaration_command" if !in_function => self.extract_bash_declaration(ast, child, source),
+                _ => self.extract_bash_symbols(ast, child, source, in_function),
+            }
+        }
+    }
+
+    /// `export A=1`, `readonly B=2`, `declare -x C`
+    fn extract_bash_declaration(&self, ast: &mut NormalizedAst, node: tree_sitter::Node, source: &[u8]) {
+        let Some(keyword) = node.child(0).map(|n| self.node_text(n, source)) else { return };
+        let mut cursor = node.walk();
+        let children: Vec<tree_sitter::Node> = node.named_children(&mut cursor).collect();
+        let flags: String = children
+            .iter()
+            .map(|n| self.node_text(*n, source))
+            .filter(|text| text.starts_with('-'))
+            .collect();
+        let (exported, readonly) = match keyword.as_str() {
+            "export" => (true, flags.contains('r')),
+            "readonly" => (flags.contains('x'), true),
+            "declare" | "typeset" => (flags.contains('x'), flags.contains('r')),
+            _ => return,
+        };
+        if !exported && !readonly {
+            return;
+        }
+
+        for child in children {
+            let name_node = match child.kind() {
+                "variable_assignment" => child.child_by_field_name("name"),
+                "variable_name" => Some(child),
+                _ => None,
+            };
+            let Some(name_node) = name_node else { continue };
+            let kind = if readonly { SymbolKind::Constant } else { SymbolKind::Variable };
+            let mut symbol = Symbol::new(self.node_text(name_node, source), kind, self.node_location(name_node));
+            symbol.visibility = if exported { SymbolVisibility::Export } else { SymbolVisibility::Unspecified };
+            symbol.span = Some(self.node_span(child));
+            symbol.signature = Some(self.node_text(child, source));
+            ast.symbols.push(symbol);
+        }
+    }
+
     fn extract_generic_symbols(
         &self,
         ast: &mut NormalizedAst,
@@ -1168,7 +1842,7 @@ impl SyntaxProvider for TreeSitterAdapter {
     }
 
     fn supported_languages(&self) -> &[Language] {
-        // Core supported languages (17 grammars in Phase 1A)
+        // Core supported languages (17 grammars in Phase 1A, plus PHP)
         &[
             Language::Rust,
             Language::Python,
@@ -1187,6 +1861,7 @@ impl SyntaxProvider for TreeSitterAdapter {
             Language::Toml,
             Language::Bash,
             Language::Markdown,
+            Language::Php,
         ]
     }
 
@@ -1337,6 +2012,241 @@ func NewServer(host string, port int) *Server {
         assert!(start.is_some());
     }
 
+    #[test]
+    fn test_go_receivers_and_interface_methods() {
+        let registry = SyntaxRegistry::new();
+        let source = r#"
+package main
+
+func (s Server) name() string { return "" }
+
+type Server struct{}
+
+func (s *Server) Start(host string, port, retries int) error {
+    return nil
+}
+
+type Runner interface {
+    io.Closer
+    Run(ctx context.Context) error
+    stop()
+}
+        "#;
+
+        let ast = registry.parse(source, Language::Go).unwrap();
+
+        let start = ast.find_symbol("Start").unwrap();
+        assert_eq!(start.parent.as_deref(), Some("Server"));
+        assert_eq!(start.qualified_name(), "Server.Start");
+        assert_eq!(start.metadata.get("receiver").map(String::as_str), Some("pointer"));
+        assert_eq!(start.parameters.len(), 3);
+        assert_eq!(start.parameters[2].type_annotation.as_deref(), Some("int"));
+        assert_eq!(ast.find_symbol("name").unwrap().metadata["receiver"], "value");
+
+        let server = ast.find_symbol("Server").unwrap();
+        assert_eq!(server.children, vec!["name", "Start"], "methods declared before the type are linked too");
+
+        let runner = ast.find_symbol("Runner").unwrap();
+        assert_eq!(runner.kind, SymbolKind::Interface);
+        assert_eq!(runner.children, vec!["Run", "stop"]);
+        assert_eq!(runner.metadata.get("embeds").map(String::as_str), Some("io.Closer"));
+        let run = ast.find_symbol("Run").unwrap();
+        assert_eq!((run.kind, run.visibility), (SymbolKind::Method, SymbolVisibility::Public));
+    }
+
+    #[test]
+    fn test_ruby_nesting_and_requires() {
+        let registry = SyntaxRegistry::new();
+        let source = r#"
+require 'json'
+require_relative 'support/helpers'
+
+module Billing
+  VERSION = "1.0"
+
+  class Invoice < ApplicationRecord
+    attr_accessor :total, :currency
+    attr_reader :id
+
+    def self.build(attrs)
+      new(attrs)
+    end
+
+    class << self
+      def cached; end
+    end
+
+    def initialize(total, currency: "EUR", *rest)
+      @total = total
+    end
+
+    def pay!; end
+
+    private
+
+    def audit; end
+
+    private def secret; end
+  end
+end
+
+def helper; end
+        "#;
+
+        let ast = registry.parse(source, Language::Ruby).unwrap();
+
+        let imports: Vec<&str> = ast.imports.iter().map(|i| i.source.as_str()).collect();
+        assert_eq!(imports, vec!["json", "./support/helpers"]);
+
+        let invoice = ast.find_symbol("Invoice").unwrap();
+        assert_eq!(invoice.parent.as_deref(), Some("Billing"));
+        assert_eq!(invoice.metadata.get("superclass").map(String::as_str), Some("ApplicationRecord"));
+        assert!(ast.find_symbol("Billing").unwrap().children.contains(&"Invoice".to_string()));
+        assert_eq!(ast.find_symbol("VERSION").unwrap().kind, SymbolKind::Constant);
+
+        let build = ast.find_symbol("build").unwrap();
+        assert_eq!(build.parent.as_deref(), Some("Billing::Invoice"));
+        assert_eq!(build.metadata.get("class_method").map(String::as_str), Some("true"));
+        assert!(ast.find_symbol("cached").unwrap().metadata.contains_key("class_method"));
+
+        let init = ast.find_symbol("initialize").unwrap();
+        assert_eq!(init.kind, SymbolKind::Constructor);
+        assert_eq!(init.parameters.len(), 3);
+        assert!(init.parameters[1].is_keyword_only && init.parameters[2].is_rest);
+
+        let total = ast.find_symbol("total").unwrap();
+        assert_eq!((total.kind, total.metadata["accessor"].as_str()), (SymbolKind::Property, "accessor"));
+        assert_eq!(ast.find_symbol("id").unwrap().metadata["accessor"], "reader");
+
+        assert_eq!(ast.find_symbol("pay!").unwrap().visibility, SymbolVisibility::Public);
+        assert_eq!(ast.find_symbol("audit").unwrap().visibility, SymbolVisibility::Private);
+        assert_eq!(ast.find_symbol("secret").unwrap().visibility, SymbolVisibility::Private);
+        assert_eq!(ast.find_symbol("helper").unwrap().kind, SymbolKind::Function);
+
+        for member in ["total", "build", "initialize", "audit"] {
+            assert!(invoice.children.iter().any(|c| c == member), "{} linked to Invoice", member);
+        }
+    }
+
+    #[test]
+    fn test_php_namespaces_uses_and_members() {
+        let registry = SyntaxRegistry::new();
+        assert!(registry.supports(Language::Php));
+        let source = r#"<?php
+namespace App\Models;
+
+use Illuminate\Database\Eloquent\Model;
+use App\Contracts\{Billable, Auditable as Audit};
+
+const DEFAULT_CURRENCY = 'EUR';
+
+class Invoice extends Model implements Billable
+{
+    use HasFactory;
+
+    public const STATUS_PAID = 'paid';
+    protected static $table = 'invoices';
+
+    public function __construct(private float $total, string ...$tags) {}
+
+    public static function build(array $attrs = []): self
+    {
+        return new self($attrs['total']);
+    }
+
+    private function audit(): void {}
+}
+
+function helper(int $x) {}
+"#;
+
+        let ast = registry.parse(source, Language::Php).unwrap();
+
+        let imports: Vec<(&str, Option<&str>)> = ast.imports
+            .iter()
+            .map(|i| (i.source.as_str(), i.alias.as_deref()))
+            .collect();
+        assert_eq!(imports, vec![
+            ("Illuminate\\Database\\Eloquent\\Model", None),
+            ("App\\Contracts\\Billable", None),
+            ("App\\Contracts\\Auditable", Some("Audit")),
+        ]);
+
+        let namespace = ast.find_symbol("App\\Models").unwrap();
+        assert_eq!(namespace.kind, SymbolKind::Namespace);
+        assert!(namespace.children.contains(&"Invoice".to_string()));
+        assert!(namespace.children.contains(&"helper".to_string()));
+
+        let invoice = ast.find_symbol("Invoice").unwrap();
+        assert_eq!(invoice.metadata.get("namespace").map(String::as_str), Some("App\\Models"));
+        assert_eq!(invoice.metadata.get("extends").map(String::as_str), Some("Model"));
+        assert_eq!(invoice.metadata.get("implements").map(String::as_str), Some("Billable"));
+        assert_eq!(invoice.metadata.get("traits").map(String::as_str), Some("HasFactory"));
+        for member in ["STATUS_PAID", "table", "__construct", "total", "build", "audit"] {
+            assert!(invoice.children.iter().any(|c| c == member), "{} linked to Invoice", member);
+        }
+
+        assert_eq!(ast.find_symbol("DEFAULT_CURRENCY").unwrap().kind, SymbolKind::Constant);
+        assert_eq!(ast.find_symbol("STATUS_PAID").unwrap().parent.as_deref(), Some("Invoice"));
+
+        let ctor = ast.find_symbol("__construct").unwrap();
+        assert_eq!(ctor.kind, SymbolKind::Constructor);
+        assert_eq!(ctor.parameters.len(), 2);
+        assert!(ctor.parameters[1].is_rest);
+        let total = ast.find_symbol("total").unwrap();
+        assert_eq!((total.kind, total.visibility), (SymbolKind::Property, SymbolVisibility::Private));
+
+        let build = ast.find_symbol("build").unwrap();
+        assert_eq!(build.kind, SymbolKind::Method);
+        assert_eq!(build.return_type.as_deref(), Some("self"));
+        assert_eq!(build.metadata.get("static").map(String::as_str), Some("true"));
+        assert_eq!(build.parameters[0].type_annotation.as_deref(), Some("array"));
+        assert_eq!(build.signature.as_deref(), Some("public static function build(array $attrs = []): self"));
+        assert_eq!(ast.find_symbol("audit").unwrap().visibility, SymbolVisibility::Private);
+        assert_eq!(ast.find_symbol("helper").unwrap().kind, SymbolKind::Function);
+    }
+
+    #[test]
+    fn test_bash_functions_sources_and_exports() {
+        let registry = SyntaxRegistry::new();
+        let source = r#"#!/usr/bin/env bash
+source ./lib/common.sh
+. "$HOME/.deploy.env"
+[ -f extra.sh ] && source 'extra.sh'
+
+export DEPLOY_ENV=staging
+readonly RETRIES=3
+declare -x REGION
+BUILD_DIR=dist
+
+deploy() {
+    local target=$1
+    export INNER=1
+    source ./lib/ssh.sh
+}
+
+function cleanup {
+    rm -rf "$BUILD_DIR"
+}
+"#;
+
+        let ast = registry.parse(source, Language::Bash).unwrap();
+
+        let imports: Vec<&str> = ast.imports.iter().map(|i| i.source.as_str()).collect();
+        assert_eq!(imports, vec!["./lib/common.sh", "$HOME/.deploy.env", "extra.sh", "./lib/ssh.sh"]);
+
+        assert_eq!(ast.find_symbol("deploy").unwrap().kind, SymbolKind::Function);
+        assert_eq!(ast.find_symbol("cleanup").unwrap().kind, SymbolKind::Function);
+
+        let env = ast.find_symbol("DEPLOY_ENV").unwrap();
+        assert_eq!((env.kind, env.visibility), (SymbolKind::Variable, SymbolVisibility::Export));
+        assert_eq!(ast.find_symbol("RETRIES").unwrap().kind, SymbolKind::Constant);
+        assert_eq!(ast.find_symbol("REGION").unwrap().visibility, SymbolVisibility::Export);
+        assert!(ast.find_symbol("BUILD_DIR").is_none());
+        assert!(ast.find_symbol("INNER").is_none());
+        assert!(ast.find_symbol("target").is_none());
+    }
+
     #[test]
     fn test_parse_file_auto_detect() {
         let registry = SyntaxRegistry::new();
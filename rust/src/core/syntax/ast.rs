@@ -492,6 +492,35 @@ impl Span {
         }
         true
     }
+
+    /// Check if `other` lies entirely within this span
+    pub fn contains_span(&self, other: &Span) -> bool {
+        self.contains(&other.start()) && self.contains(&other.end())
+    }
+
+    /// Start position
+    pub fn start(&self) -> Location {
+        Location::new(self.start_line, self.start_column, self.start_offset)
+    }
+
+    /// End position
+    pub fn end(&self) -> Location {
+        Location::new(self.end_line, self.end_column, self.end_offset)
+    }
+
+    /// Smallest span covering both spans
+    pub fn merge(&self, other: &Span) -> Span {
+        let first = if (other.start_line, other.start_column) < (self.start_line, self.start_column) { other } else { self };
+        let last = if (other.end_line, other.end_column) > (self.end_line, self.end_column) { other } else { self };
+        Span {
+            start_line: first.start_line,
+            start_column: first.start_column,
+            end_line: last.end_line,
+            end_column: last.end_column,
+            start_offset: self.start_offset.min(other.start_offset),
+            end_offset: self.end_offset.max(other.end_offset),
+        }
+    }
 }
 
 /// A function/method parameter
@@ -541,6 +570,7 @@ pub enum DiagnosticSeverity {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_normalized_ast_new() {
@@ -593,6 +623,41 @@ mod tests {
         assert!(!span.contains(&Location::new(21, 1, 0)));
     }
 
+    /// Well-formed spans: start position <= end position
+    fn arb_span() -> impl Strategy<Value = Span> {
+        (1usize..200, 1usize..80, 0usize..50, 1usize..80).prop_map(|(line, col, lines, end_col)| {
+            let end_column = if lines == 0 { col.max(end_col) } else { end_col };
+            Span::new(line, col, line + lines, end_column)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_span_contains_is_lexicographic(span in arb_span(), line in 0usize..260, column in 0usize..90) {
+            let inside = (span.start_line, span.start_column) <= (line, column)
+                && (line, column) <= (span.end_line, span.end_column);
+            prop_assert_eq!(span.contains(&Location::new(line, column, 0)), inside);
+        }
+
+        #[test]
+        fn prop_span_contains_endpoints(span in arb_span()) {
+            prop_assert!(span.contains(&span.start()));
+            prop_assert!(span.contains(&span.end()));
+            prop_assert!(span.contains_span(&span));
+            prop_assert_eq!(span.line_count(), span.end_line - span.start_line + 1);
+        }
+
+        #[test]
+        fn prop_span_merge(a in arb_span(), b in arb_span()) {
+            let merged = a.merge(&b);
+            prop_assert_eq!(merged, b.merge(&a));
+            prop_assert_eq!(a.merge(&a), a);
+            prop_assert!(merged.contains_span(&a));
+            prop_assert!(merged.contains_span(&b));
+            prop_assert!(merged.line_count() >= a.line_count().max(b.line_count()));
+        }
+    }
+
     #[test]
     fn test_symbol_kind_labels() {
         assert_eq!(SymbolKind::Function.label(), "fn");
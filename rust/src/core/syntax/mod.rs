@@ -166,7 +166,7 @@ impl Language {
             "swift" => Some(Language::Swift),
 
             // Data
-            "json" | "jsonc" => Some(Language::Json),
+            "json" | "jsonc" | "json5" => Some(Language::Json),
             "yaml" | "yml" => Some(Language::Yaml),
             "toml" => Some(Language::Toml),
             "sql" => Some(Language::Sql),
@@ -6,6 +6,9 @@
 //! - Git directory mtime (any git operation)
 //! - Cache TTL (24 hour max age)
 //!
+//! The cache file is zstd-compressed bincode (gzip without the `zstd`
+//! feature); uncompressed caches from older versions are still read.
+//!
 //! # Celestial Terminology
 //!
 //! - **Warp Engaged**: Cache hit - instant results
@@ -21,6 +24,8 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::core::codec::{self, Codec};
+
 // =============================================================================
 // Constants
 // =============================================================================
@@ -140,7 +145,8 @@ impl ChronosCacheManager {
         let mut buffer = Vec::new();
         file.read_to_end(&mut buffer).ok()?;
 
-        // Deserialize
+        // Decompress (caches from older versions are plain bincode) and deserialize
+        let buffer = codec::decompress(&buffer).ok()?;
         let cache: ChronosCache = bincode::deserialize(&buffer).ok()?;
 
         // Version check
@@ -176,6 +182,7 @@ impl ChronosCacheManager {
         // Serialize
         let buffer = bincode::serialize(cache)
             .map_err(|e| format!("Failed to serialize cache: {}", e))?;
+        let buffer = codec::compress(&buffer, Codec::preferred())?;
 
         // Write atomically (write to temp, then rename)
         let cache_path = self.cache_path();
@@ -426,6 +433,42 @@ mod tests {
         assert_ne!(cache.git_head_hash, current_head);
     }
 
+    #[test]
+    fn test_cache_file_is_compressed() {
+        let temp = tempfile::tempdir().unwrap();
+        let manager = ChronosCacheManager::new(temp.path());
+        let mut file_histories = HashMap::new();
+        file_histories.insert(
+            "src/lib.rs".to_string(),
+            vec![CachedObservation {
+                timestamp_secs: 1_700_000_000,
+                observer_name: "Observer".to_string(),
+                observer_email_hash: "abc123".to_string(),
+                lines_added: 10,
+                lines_removed: 2,
+            }; 50],
+        );
+        let cache = ChronosCache {
+            version: CACHE_VERSION,
+            git_head_hash: "abc123".to_string(),
+            created_at: 0,
+            ttl_seconds: CACHE_TTL_SECONDS,
+            file_histories,
+            galaxy_stats: CachedGalaxyStats::default(),
+            total_observations: 50,
+            hit_depth_limit: false,
+            commit_depth: 1000,
+        };
+        manager.save(&cache).unwrap();
+
+        let stored = fs::read(manager.cache_path()).unwrap();
+        assert_eq!(Codec::detect(&stored), Codec::preferred());
+        let raw = bincode::serialize(&cache).unwrap();
+        assert!(stored.len() < raw.len());
+        let restored: ChronosCache = bincode::deserialize(&codec::decompress(&stored).unwrap()).unwrap();
+        assert_eq!(restored.total_observations, 50);
+    }
+
     #[test]
     fn test_cached_galaxy_stats_default() {
         let stats = CachedGalaxyStats::default();
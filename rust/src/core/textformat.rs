@@ -0,0 +1,141 @@
+//! Text Format - What reading a file normalized away
+//!
+//! The walker turns CRLF line breaks into LF, so offsets into
+//! [`FileEntry::content`](crate::core::FileEntry) (and the spans parsed from
+//! it) are off by one byte per CRLF break before them in the file on disk.
+//! [`TextFormat`] records the original line-ending style, the CRLF breaks and
+//! whether the file starts with a UTF-8 byte order mark, and translates
+//! offsets and spans between the two.
+//!
+//! The BOM is kept in the content, so offsets need no adjustment for it.
+//! Translation is exact for UTF-8 files; lossily decoded files may differ.
+
+use serde::Serialize;
+use voyager_ast::Span;
+
+/// UTF-8 byte order mark
+pub const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Line-ending style of a file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// `\n` (also files without line breaks)
+    #[default]
+    Lf,
+    /// `\r\n`
+    Crlf,
+    /// `\r` alone
+    Cr,
+    /// More than one style
+    Mixed,
+}
+
+impl LineEnding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lf => "lf",
+            Self::Crlf => "crlf",
+            Self::Cr => "cr",
+            Self::Mixed => "mixed",
+        }
+    }
+}
+
+/// Original encoding details of a file's content
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TextFormat {
+    pub line_ending: LineEnding,
+    /// Starts with a UTF-8 byte order mark
+    pub bom: bool,
+    /// Offsets in the normalized content of the `\n` of each CRLF break
+    #[serde(skip)]
+    crlf_breaks: Vec<usize>,
+}
+
+impl TextFormat {
+    /// Detect the format of raw file bytes
+    pub fn detect(bytes: &[u8]) -> Self {
+        let mut crlf_breaks = Vec::new();
+        let (mut lf, mut cr) = (0usize, 0usize);
+        for (offset, &byte) in bytes.iter().enumerate() {
+            match byte {
+                b'\n' if offset > 0 && bytes[offset - 1] == b'\r' => crlf_breaks.push(offset - 1 - crlf_breaks.len()),
+                b'\n' => lf += 1,
+                b'\r' if bytes.get(offset + 1) != Some(&b'\n') => cr += 1,
+                _ => {}
+            }
+        }
+        let line_ending = match (crlf_breaks.is_empty(), lf, cr) {
+            (true, _, 0) => LineEnding::Lf,
+            (false, 0, 0) => LineEnding::Crlf,
+            (true, 0, _) => LineEnding::Cr,
+            _ => LineEnding::Mixed,
+        };
+        Self { line_ending, bom: bytes.starts_with(UTF8_BOM), crlf_breaks }
+    }
+
+    /// Whether normalized offsets differ from the original ones
+    pub fn is_shifted(&self) -> bool {
+        !self.crlf_breaks.is_empty()
+    }
+
+    /// Original byte offset of an offset into the normalized content
+    ///
+    /// An offset at the `\n` of a CRLF break maps to its `\r`.
+    pub fn original_offset(&self, offset: usize) -> usize {
+        offset + self.crlf_breaks.partition_point(|&b| b < offset)
+    }
+
+    /// Normalized offset of an original byte offset
+    ///
+    /// Both bytes of a CRLF break map to its `\n`.
+    pub fn normalized_offset(&self, original: usize) -> usize {
+        let mut low = 0;
+        let mut high = self.crlf_breaks.len();
+        // Breaks whose `\n` lies at or before `original` in the file
+        while low < high {
+            let mid = (low + high) / 2;
+            if self.crlf_breaks[mid] + mid < original {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        original - low
+    }
+
+    /// Span with byte offsets in the original file (lines and columns are unchanged)
+    pub fn original_span(&self, span: &Span) -> Span {
+        Span { start: self.original_offset(span.start), end: self.original_offset(span.end), ..*span }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offsets_map_back_to_original_bytes() {
+        let original = b"\xEF\xBB\xBFa\r\nbc\r\nd\ne";
+        let format = TextFormat::detect(original);
+        assert_eq!(format.line_ending, LineEnding::Mixed);
+        assert!(format.bom);
+
+        let content = crate::core::walker::read_file_content(original).unwrap();
+        assert_eq!(content, "\u{FEFF}a\nbc\nd\ne");
+        for (offset, byte) in content.bytes().enumerate() {
+            let mapped = format.original_offset(offset);
+            assert!(original[mapped] == byte || (byte == b'\n' && original[mapped..].starts_with(b"\r\n")));
+            assert_eq!(format.normalized_offset(mapped), offset);
+        }
+        // "bc" spans 5..7 normalized, 6..8 in the file
+        let span = format.original_span(&Span::new(5, 7, 2, 2));
+        assert_eq!(&original[span.start..span.end], b"bc");
+        assert_eq!((format.normalized_offset(4), format.normalized_offset(5)), (4, 4));
+
+        assert_eq!(TextFormat::detect(b"a\r\nb\r\n").line_ending, LineEnding::Crlf);
+        assert_eq!(TextFormat::detect(b"a\rb").line_ending, LineEnding::Cr);
+        assert_eq!(TextFormat::detect(b"a\nb"), TextFormat::default());
+    }
+}
@@ -0,0 +1,113 @@
+//! Table of Contents - Orientation header for serialized context
+//!
+//! Lists every included file with its depth (full, truncated, skeleton) and
+//! size, and the declarations that made it into the output, so a reader can
+//! orient before the first file. Each file and declaration is listed under
+//! an anchor that citations can refer to: the file path for files, the
+//! [`Anchor`](crate::core::anchor::Anchor) (`path#Outer.inner@fingerprint`)
+//! for declarations.
+
+use crate::core::anchor::anchors;
+use crate::core::ast_bridge::AstBridge;
+
+/// Name of the synthetic file holding the table of contents
+pub const TOC_FILE_NAME: &str = "CONTENTS.md";
+
+/// Declarations listed per file before the rest are counted
+const MAX_SYMBOLS_PER_FILE: usize = 24;
+
+/// Nesting levels listed (top-level declarations and their members)
+const MAX_SYMBOL_DEPTH: usize = 2;
+
+/// A declaration listed in the table of contents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TocSymbol {
+    /// Anchor id (`path#Outer.inner@fingerprint`)
+    pub anchor: String,
+    pub kind: &'static str,
+    /// Nesting depth (0 = top level)
+    pub depth: usize,
+}
+
+/// A file listed in the table of contents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TocFile {
+    pub path: String,
+    /// How much of the file is included ("full", "truncated", "skeleton", ...)
+    pub depth: String,
+    /// Estimated tokens of the included content
+    pub tokens: usize,
+    pub symbols: Vec<TocSymbol>,
+}
+
+/// Table of contents of a serialized context
+#[derive(Debug, Clone, Default)]
+pub struct TableOfContents {
+    pub files: Vec<TocFile>,
+}
+
+impl TableOfContents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// List a file with the declarations found in its included `content`
+    pub fn add_file(&mut self, path: &str, content: &str, depth: &str) {
+        let language = AstBridge::detect_language(std::path::Path::new(path));
+        let mut symbols = Vec::new();
+        if let Some(file) = AstBridge::new().analyze_file(content, language) {
+            for (anchor, decl, depth) in anchors(path, &file.declarations, content, MAX_SYMBOL_DEPTH) {
+                symbols.push(TocSymbol { anchor: anchor.to_string(), kind: decl.kind.as_str(), depth });
+            }
+        }
+        self.files.push(TocFile {
+            path: path.to_string(),
+            depth: depth.to_string(),
+            tokens: content.len() / 4,
+            symbols,
+        });
+    }
+
+    /// Render as Markdown, one nested list item per file and declaration
+    pub fn to_markdown(&self) -> String {
+        let symbols: usize = self.files.iter().map(|f| f.symbols.len()).sum();
+        let tokens: usize = self.files.iter().map(|f| f.tokens).sum();
+        let mut out = String::from("# Contents\n\n");
+        out.push_str(&format!(
+            "{} files, {} declarations, ~{} tokens. Anchors: `path` for a file, `path#Outer.inner@fingerprint` for a declaration.\n\n",
+            self.files.len(),
+            symbols,
+            tokens
+        ));
+        for file in &self.files {
+            out.push_str(&format!("- `{}` ({}, ~{} tokens)\n", file.path, file.depth, file.tokens));
+            for symbol in file.symbols.iter().take(MAX_SYMBOLS_PER_FILE) {
+                out.push_str(&format!("{}- `{}` {}\n", "  ".repeat(symbol.depth + 1), symbol.anchor, symbol.kind));
+            }
+            if file.symbols.len() > MAX_SYMBOLS_PER_FILE {
+                out.push_str(&format!("  - ... {} more declarations\n", file.symbols.len() - MAX_SYMBOLS_PER_FILE));
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_of_contents_lists_files_and_anchors() {
+        let mut toc = TableOfContents::new();
+        toc.add_file("src/engine.rs", "pub struct Engine;\n\nimpl Engine {\n    pub fn run(&self) {}\n}\n", "full");
+        toc.add_file("notes.txt", "scratch\n", "truncated");
+
+        assert!(toc.files[0].symbols[0].anchor.starts_with("src/engine.rs#Engine@"));
+        let markdown = toc.to_markdown();
+        assert!(markdown.starts_with("# Contents\n\n2 files, "));
+        assert!(markdown.contains("- `src/engine.rs` (full, ~"));
+        assert!(markdown.contains(&format!("  - `{}` struct\n", toc.files[0].symbols[0].anchor)));
+        assert!(markdown.contains("    - `src/engine.rs#Engine.run@"), "{}", markdown);
+        assert!(markdown.contains("- `notes.txt` (truncated, ~2 tokens)\n"));
+    }
+}
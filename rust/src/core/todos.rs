@@ -0,0 +1,250 @@
+//! TODO Export - TODO/FIXME comments as issue-ready Markdown or CSV
+//!
+//! Collects `TODO` and `FIXME` markers from the comments the parsers extract
+//! (never from strings or code), each with its file, line and the qualified
+//! name of the innermost enclosing declaration. A marker may carry an owner
+//! or ticket in parentheses (`TODO(alice): ...`), which is kept in the text.
+//!
+//! With the `temporal` feature, [`TodoList::with_blame`] dates every marker
+//! by the commit that last touched its line; lines changed since the last
+//! commit are left undated. The Markdown export has one section per marker,
+//! titled so it can be pasted as a GitHub issue; the CSV export has one row
+//! per marker for spreadsheets and trackers.
+
+use std::path::Path;
+
+use serde::Serialize;
+use voyager_ast::File;
+
+use crate::core::anchor::anchors;
+use crate::core::ast_bridge::AstBridge;
+
+/// Markers collected, in the order they are matched
+pub const TODO_MARKERS: [&str; 2] = ["TODO", "FIXME"];
+
+/// A TODO/FIXME comment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TodoItem {
+    /// `TODO` or `FIXME`
+    pub marker: String,
+    /// Text after the marker (`(owner):` included, if any)
+    pub text: String,
+    pub path: String,
+    /// Line of the marker (1-indexed)
+    pub line: usize,
+    /// Qualified name of the innermost enclosing declaration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// Days since the commit that last changed the line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_days: Option<u64>,
+    /// That commit, abbreviated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// TODO/FIXME comments of a project, by path and line
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct TodoList {
+    pub items: Vec<TodoItem>,
+}
+
+impl TodoList {
+    /// Collect markers from `(path, content)` pairs
+    pub fn collect(files: &[(String, String)]) -> Self {
+        let bridge = AstBridge::new();
+        let mut items = Vec::new();
+        for (path, content) in files {
+            let language = AstBridge::detect_language(Path::new(path));
+            if let Some(file) = bridge.analyze_file(content, language) {
+                collect_file(path, content, &file, &mut items);
+            }
+        }
+        items.sort_by(|a: &TodoItem, b: &TodoItem| (&a.path, a.line).cmp(&(&b.path, b.line)));
+        Self { items }
+    }
+
+    /// Date every marker with `git blame` of its file in the repository containing `root`
+    ///
+    /// `files` are the pairs the list was collected from, so lines edited
+    /// since the last commit are blamed on the working copy and stay undated.
+    /// Outside a repository the list is returned unchanged.
+    #[cfg(feature = "temporal")]
+    pub fn with_blame(mut self, root: &Path, files: &[(String, String)]) -> Self {
+        let Ok(repo) = git2::Repository::discover(root) else { return self };
+        let Some(workdir) = repo.workdir().and_then(|w| w.canonicalize().ok()) else { return self };
+        let prefix = root
+            .canonicalize()
+            .ok()
+            .and_then(|root| root.strip_prefix(&workdir).ok().map(Path::to_path_buf))
+            .unwrap_or_default();
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+
+        for (path, content) in files {
+            if !self.items.iter().any(|item| item.path == *path) {
+                continue;
+            }
+            let Ok(committed) = repo.blame_file(&prefix.join(path), None) else { continue };
+            let Ok(blame) = committed.blame_buffer(content.as_bytes()) else { continue };
+            for item in self.items.iter_mut().filter(|item| item.path == *path) {
+                let Some(hunk) = blame.get_line(item.line) else { continue };
+                let id = hunk.final_commit_id();
+                if id.is_zero() {
+                    continue;
+                }
+                let seconds = hunk.final_signature().when().seconds();
+                item.age_days = Some((now - seconds).max(0) as u64 / 86_400);
+                item.commit = Some(id.to_string()[..7].to_string());
+            }
+        }
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Markdown document: one issue-ready section per marker
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# TODOs\n\n{} markers.\n", self.items.len());
+        for item in &self.items {
+            let title = if item.text.is_empty() { "(no description)" } else { item.text.as_str() };
+            out.push_str(&format!("\n## {}: {}\n\n- Location: `{}:{}`\n", item.marker, title, item.path, item.line));
+            if let Some(symbol) = &item.symbol {
+                out.push_str(&format!("- Symbol: `{}`\n", symbol));
+            }
+            if let (Some(days), Some(commit)) = (item.age_days, &item.commit) {
+                out.push_str(&format!("- Age: {} days (since {})\n", days, commit));
+            }
+        }
+        out
+    }
+
+    /// CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("marker,path,line,symbol,age_days,commit,text\n");
+        for item in &self.items {
+            let fields = [
+                item.marker.clone(),
+                item.path.clone(),
+                item.line.to_string(),
+                item.symbol.clone().unwrap_or_default(),
+                item.age_days.map(|d| d.to_string()).unwrap_or_default(),
+                item.commit.clone().unwrap_or_default(),
+                item.text.clone(),
+            ];
+            let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+fn collect_file(path: &str, content: &str, file: &File, items: &mut Vec<TodoItem>) {
+    let declarations = anchors(path, &file.declarations, content, usize::MAX);
+    // Byte offset of each line start
+    let line_starts: Vec<usize> =
+        std::iter::once(0).chain(content.match_indices('\n').map(|(i, _)| i + 1)).collect();
+
+    for comment in &file.comments {
+        for line in comment.span.start_line..=comment.span.end_line {
+            let Some(&line_start) = line_starts.get(line.saturating_sub(1)) else { break };
+            let line_end = line_starts.get(line).map_or(content.len(), |next| next - 1);
+            // Only the part of the line inside the comment
+            let start = line_start.max(comment.span.start);
+            let end = line_end.min(comment.span.end);
+            let Some(text) = content.get(start..end.max(start)) else { continue };
+            let Some((marker, rest)) = find_marker(text) else { continue };
+            let symbol = declarations
+                .iter()
+                .filter(|(_, decl, _)| decl.span.start_line <= line && line <= decl.span.end_line)
+                .max_by_key(|(_, _, depth)| *depth)
+                .map(|(anchor, _, _)| anchor.qualified_name.clone());
+            items.push(TodoItem {
+                marker: marker.to_string(),
+                text: rest.to_string(),
+                path: path.to_string(),
+                line,
+                symbol,
+                age_days: None,
+                commit: None,
+            });
+        }
+    }
+}
+
+/// The first marker in a comment line, as a whole word, with the text after it
+fn find_marker(text: &str) -> Option<(&'static str, &str)> {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    let (start, marker) = TODO_MARKERS
+        .iter()
+        .filter_map(|marker| {
+            text.match_indices(marker).find(|(i, _)| {
+                let before = text[..*i].chars().next_back();
+                let after = text[i + marker.len()..].chars().next();
+                !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+            })
+        })
+        .min_by_key(|(i, _)| *i)?;
+    let marker = TODO_MARKERS.into_iter().find(|m| *m == marker)?;
+    let rest = text[start + marker.len()..]
+        .trim_end()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim()
+        .trim_start_matches(':')
+        .trim_start();
+    Some((marker, rest))
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_markers_with_symbols() {
+        let files = vec![
+            (
+                "src/engine.rs".to_string(),
+                "// TODO: split this module\npub struct Engine;\n\nimpl Engine {\n    pub fn run(&self) {\n        // FIXME(bob): handle errors, retry\n        let todo_list = \"TODO not a comment\";\n    }\n}\n".to_string(),
+            ),
+            ("app.py".to_string(), "def load():\n    # TODOS are not TODO markers\n    pass\n".to_string()),
+        ];
+        let list = TodoList::collect(&files);
+
+        assert_eq!(list.len(), 3);
+        let first = &list.items[0];
+        assert_eq!((first.path.as_str(), first.line, first.marker.as_str()), ("app.py", 2, "TODO"));
+        assert_eq!(first.text, "markers");
+        assert_eq!(first.symbol.as_deref(), Some("load"));
+
+        let fixme = &list.items[2];
+        assert_eq!((fixme.marker.as_str(), fixme.line), ("FIXME", 6));
+        assert_eq!(fixme.text, "(bob): handle errors, retry");
+        assert_eq!(fixme.symbol.as_deref(), Some("Engine.run"));
+        assert_eq!(list.items[1].symbol, None);
+
+        assert!(list.to_markdown().contains("\n## FIXME: (bob): handle errors, retry\n\n- Location: `src/engine.rs:6`\n- Symbol: `Engine.run`\n"));
+        assert!(list.to_csv().contains("\nFIXME,src/engine.rs,6,Engine.run,,,\"(bob): handle errors, retry\"\n"));
+    }
+}
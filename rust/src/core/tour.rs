@@ -0,0 +1,591 @@
+//! Guided Tour - Ordered reading path through a codebase
+//!
+//! Produces a notebook-style "tour" for newcomers: an ordered list of files and
+//! symbols, each with a one-paragraph structural description. The tour reads
+//! like a chapter outline:
+//!
+//! 1. **Entry points** - where execution starts (`main`, `__main__`, bin targets)
+//! 2. **Core types** - the most-imported structs/classes/traits
+//! 3. **Main flows** - functions reached from the entry points via the call graph
+//!
+//! The generator is pure (no I/O): it consumes `(path, content)` pairs and
+//! combines entry-point detection from the language analyzers, the call graph
+//! from [`CallExtractor`], and a lightweight import topology (fan-in per file).
+//!
+//! The onboarding lens embeds the rendered tour at the top of the context as a
+//! synthetic `TOUR.md` file.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::analyzers::{get_analyzer_for_file, AnalysisResult};
+use crate::core::fractal::relationships::{CallExtractor, CallableKind};
+use crate::core::models::FileEntry;
+
+/// Path of the synthetic file the onboarding lens uses to embed the tour
+pub const TOUR_FILE_NAME: &str = "TOUR.md";
+
+/// Lens name that triggers tour embedding
+pub const TOUR_LENS: &str = "onboarding";
+
+/// Build the synthetic `TOUR.md` file for `(path, content)` pairs
+pub fn tour_file<'a>(files: impl IntoIterator<Item = (&'a str, &'a str)>) -> FileEntry {
+    let pairs: Vec<(String, String)> = files
+        .into_iter()
+        .map(|(path, content)| (path.to_string(), content.to_string()))
+        .collect();
+    FileEntry::new(TOUR_FILE_NAME, TourGenerator::new().generate(&pairs).to_markdown())
+}
+
+/// Section of the tour a stop belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TourSection {
+    /// Where execution starts
+    EntryPoint,
+    /// Central data types, ranked by import fan-in
+    CoreType,
+    /// Functions reached from the entry points
+    MainFlow,
+}
+
+impl TourSection {
+    /// Human-readable section heading
+    pub fn heading(&self) -> &'static str {
+        match self {
+            TourSection::EntryPoint => "Entry Points",
+            TourSection::CoreType => "Core Types",
+            TourSection::MainFlow => "Main Flows",
+        }
+    }
+}
+
+/// A single stop on the tour
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TourStop {
+    /// 1-based position in the reading order
+    pub order: usize,
+    /// Section this stop belongs to
+    pub section: TourSection,
+    /// File path (relative to project root)
+    pub path: String,
+    /// Symbol name, if the stop targets a symbol rather than a whole file
+    pub symbol: Option<String>,
+    /// Line where the symbol is defined (1-indexed)
+    pub line: Option<usize>,
+    /// One-paragraph structural description
+    pub description: String,
+}
+
+impl TourStop {
+    /// Zoom target string for this stop (e.g., `function=run`, `file=src/lib.rs`)
+    pub fn zoom_target(&self) -> String {
+        match (&self.symbol, self.section) {
+            (Some(name), TourSection::CoreType) => format!("class={}", name),
+            (Some(name), _) => format!("function={}", name),
+            (None, _) => format!("file={}", self.path),
+        }
+    }
+}
+
+/// An ordered reading path through a codebase
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Tour {
+    /// Stops in reading order
+    pub stops: Vec<TourStop>,
+}
+
+impl Tour {
+    /// Check if the tour has no stops
+    pub fn is_empty(&self) -> bool {
+        self.stops.is_empty()
+    }
+
+    /// Number of stops
+    pub fn len(&self) -> usize {
+        self.stops.len()
+    }
+
+    /// Stops belonging to a section, in reading order
+    pub fn section(&self, section: TourSection) -> Vec<&TourStop> {
+        self.stops.iter().filter(|s| s.section == section).collect()
+    }
+
+    /// Render the tour as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::from("# Guided Tour\n\n");
+        if self.stops.is_empty() {
+            out.push_str("_No entry points, core types, or flows were detected._\n");
+            return out;
+        }
+
+        out.push_str("Read the stops in order; each one builds on the previous.\n");
+
+        let mut current: Option<TourSection> = None;
+        for stop in &self.stops {
+            if current != Some(stop.section) {
+                out.push_str(&format!("\n## {}\n\n", stop.section.heading()));
+                current = Some(stop.section);
+            }
+
+            let location = match (&stop.symbol, stop.line) {
+                (Some(sym), Some(line)) => format!("`{}` ({}:{})", sym, stop.path, line),
+                (Some(sym), None) => format!("`{}` ({})", sym, stop.path),
+                (None, _) => format!("`{}`", stop.path),
+            };
+            out.push_str(&format!(
+                "{}. {} — {}\n   Zoom: `{}`\n",
+                stop.order,
+                location,
+                stop.description,
+                stop.zoom_target()
+            ));
+        }
+
+        out
+    }
+
+    /// Render the tour as JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+/// Generator for guided tours
+#[derive(Debug, Clone)]
+pub struct TourGenerator {
+    /// Maximum number of entry-point stops
+    pub max_entry_points: usize,
+    /// Maximum number of core-type stops
+    pub max_core_types: usize,
+    /// Maximum number of main-flow stops
+    pub max_flow_steps: usize,
+    /// Maximum call depth followed from entry points
+    pub max_flow_depth: usize,
+}
+
+impl Default for TourGenerator {
+    fn default() -> Self {
+        Self {
+            max_entry_points: 3,
+            max_core_types: 5,
+            max_flow_steps: 8,
+            max_flow_depth: 3,
+        }
+    }
+}
+
+/// Structural signals of a file, as used to order the tour
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileSignals {
+    pub path: String,
+    /// Detected entry point (`main`, `__main__`, bin targets)
+    pub is_entry: bool,
+    /// Test file or test directory
+    pub is_test: bool,
+    /// Number of other files importing this one
+    pub fan_in: usize,
+}
+
+/// Per-file facts gathered before ordering
+struct FileFacts {
+    path: String,
+    analysis: Option<AnalysisResult>,
+    is_entry: bool,
+    fan_in: usize,
+}
+
+impl TourGenerator {
+    /// Create a generator with default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder: set the maximum number of main-flow stops
+    pub fn with_max_flow_steps(mut self, steps: usize) -> Self {
+        self.max_flow_steps = steps;
+        self
+    }
+
+    /// Generate a tour from `(path, content)` pairs
+    pub fn generate(&self, files: &[(String, String)]) -> Tour {
+        let facts = Self::gather_facts(files);
+        let mut stops: Vec<TourStop> = Vec::new();
+
+        // 1. Entry points: detected entries first, then by fan-out (imports)
+        let mut entries: Vec<&FileFacts> = facts.iter().filter(|f| f.is_entry).collect();
+        entries.sort_by(|a, b| {
+            Self::path_depth(&a.path)
+                .cmp(&Self::path_depth(&b.path))
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        entries.truncate(self.max_entry_points);
+
+        for facts in &entries {
+            stops.push(TourStop {
+                order: 0,
+                section: TourSection::EntryPoint,
+                path: facts.path.clone(),
+                symbol: None,
+                line: None,
+                description: Self::describe_entry(facts),
+            });
+        }
+
+        // 2. Core types: types defined in the most-imported non-test files
+        let mut type_files: Vec<&FileFacts> = facts
+            .iter()
+            .filter(|f| !f.is_entry && !Self::is_test_path(&f.path))
+            .filter(|f| f.analysis.as_ref().map(|a| !a.classes.is_empty()).unwrap_or(false))
+            .collect();
+        type_files.sort_by(|a, b| b.fan_in.cmp(&a.fan_in).then_with(|| a.path.cmp(&b.path)));
+
+        let mut seen_types: HashSet<String> = HashSet::new();
+        'types: for facts in type_files {
+            let analysis = facts.analysis.as_ref().expect("filtered above");
+            for class in &analysis.classes {
+                if stops.iter().filter(|s| s.section == TourSection::CoreType).count()
+                    >= self.max_core_types
+                {
+                    break 'types;
+                }
+                if !seen_types.insert(class.clone()) {
+                    continue;
+                }
+                stops.push(TourStop {
+                    order: 0,
+                    section: TourSection::CoreType,
+                    path: facts.path.clone(),
+                    symbol: Some(class.clone()),
+                    line: None,
+                    description: Self::describe_type(class, facts),
+                });
+                // One type per file keeps the tour broad rather than deep
+                break;
+            }
+        }
+
+        // 3. Main flows: breadth-first walk of the call graph from entry files
+        let entry_paths: HashSet<&str> = entries.iter().map(|f| f.path.as_str()).collect();
+        stops.extend(self.main_flows(files, &entry_paths));
+
+        for (i, stop) in stops.iter_mut().enumerate() {
+            stop.order = i + 1;
+        }
+
+        Tour { stops }
+    }
+
+    /// Build the main-flow section from the call graph
+    fn main_flows(&self, files: &[(String, String)], entry_paths: &HashSet<&str>) -> Vec<TourStop> {
+        let extractor = CallExtractor::new();
+        let extractions: Vec<_> = files
+            .iter()
+            .filter(|(path, _)| !Self::is_test_path(path))
+            .map(|(path, content)| extractor.extract_from_file(content, path))
+            .collect();
+        let graph = extractor.build_graph(extractions);
+
+        // Seed the walk with functions defined in entry files (main first)
+        let mut seeds: Vec<&str> = graph
+            .nodes()
+            .iter()
+            .filter(|n| n.kind != CallableKind::External)
+            .filter(|n| n.file_path.as_deref().map(|p| entry_paths.contains(p)).unwrap_or(false))
+            .map(|n| n.id.as_str())
+            .collect();
+        seeds.sort_by_key(|id| (*id != "main", *id));
+
+        let mut visited: HashSet<&str> = seeds.iter().copied().collect();
+        let mut queue: VecDeque<(&str, usize)> = seeds.iter().map(|id| (*id, 0)).collect();
+        let mut reached: Vec<(&str, usize, &str)> = Vec::new();
+
+        while let Some((id, depth)) = queue.pop_front() {
+            if depth >= self.max_flow_depth {
+                continue;
+            }
+            let mut callees = graph.calls_from(id);
+            callees.sort_by_key(|(n, e)| (e.line.unwrap_or(usize::MAX), n.id.clone()));
+            for (callee, _) in callees {
+                if callee.kind == CallableKind::External || callee.file_path.is_none() {
+                    continue;
+                }
+                if visited.insert(callee.id.as_str()) {
+                    reached.push((callee.id.as_str(), depth + 1, id));
+                    queue.push_back((callee.id.as_str(), depth + 1));
+                }
+            }
+        }
+
+        reached
+            .into_iter()
+            .take(self.max_flow_steps)
+            .filter_map(|(id, depth, from)| {
+                let node = graph.get_node(id)?;
+                let mut callees: Vec<String> = graph
+                    .calls_from(id)
+                    .into_iter()
+                    .filter(|(n, _)| n.kind != CallableKind::External)
+                    .map(|(n, _)| n.name.clone())
+                    .collect();
+                callees.sort();
+                callees.dedup();
+
+                let mut description = format!(
+                    "Reached from `{}` at depth {}.",
+                    from, depth
+                );
+                if callees.is_empty() {
+                    description.push_str(" Leaf function: makes no further project calls.");
+                } else {
+                    description.push_str(&format!(" Calls {}.", Self::join_names(&callees, 4)));
+                }
+
+                Some(TourStop {
+                    order: 0,
+                    section: TourSection::MainFlow,
+                    path: node.file_path.clone().unwrap_or_default(),
+                    symbol: Some(node.name.clone()),
+                    line: node.line,
+                    description,
+                })
+            })
+            .collect()
+    }
+
+    /// Entry-point and import fan-in signals for `(path, content)` pairs
+    pub fn file_signals(files: &[(String, String)]) -> Vec<FileSignals> {
+        Self::gather_facts(files)
+            .into_iter()
+            .map(|facts| FileSignals {
+                is_test: Self::is_test_path(&facts.path),
+                path: facts.path,
+                is_entry: facts.is_entry,
+                fan_in: facts.fan_in,
+            })
+            .collect()
+    }
+
+    /// Analyze each file and compute import fan-in
+    fn gather_facts(files: &[(String, String)]) -> Vec<FileFacts> {
+        let mut facts: Vec<FileFacts> = files
+            .iter()
+            .map(|(path, content)| {
+                let analysis = get_analyzer_for_file(path).map(|a| a.analyze(content, path));
+                let detected = analysis.as_ref().map(|a| !a.entry_points.is_empty()).unwrap_or(false);
+                FileFacts {
+                    path: path.clone(),
+                    is_entry: !Self::is_test_path(path) && (detected || Self::is_entry_name(path)),
+                    analysis,
+                    fan_in: 0,
+                }
+            })
+            .collect();
+
+        // Import topology: a file's fan-in is the number of other files whose
+        // imports mention its module stem
+        let stems: Vec<String> = facts.iter().map(|f| Self::module_stem(&f.path)).collect();
+        let mut fan_in: HashMap<usize, usize> = HashMap::new();
+        for (i, importer) in facts.iter().enumerate() {
+            let Some(analysis) = importer.analysis.as_ref() else { continue };
+            for (j, stem) in stems.iter().enumerate() {
+                if i == j || stem.is_empty() {
+                    continue;
+                }
+                if analysis.imports.iter().any(|imp| Self::import_mentions(imp, stem)) {
+                    *fan_in.entry(j).or_insert(0) += 1;
+                }
+            }
+        }
+        for (idx, count) in fan_in {
+            facts[idx].fan_in = count;
+        }
+
+        facts
+    }
+
+    fn describe_entry(facts: &FileFacts) -> String {
+        let Some(analysis) = facts.analysis.as_ref() else {
+            return "Program entry point. Start reading here.".to_string();
+        };
+
+        let mut description = format!("{} entry point", analysis.language);
+        if !analysis.entry_points.is_empty() {
+            description.push_str(&format!(" ({})", analysis.entry_points.join(", ")));
+        }
+        description.push('.');
+        if !analysis.imports.is_empty() {
+            description.push_str(&format!(
+                " Pulls in {} import(s), starting with {}.",
+                analysis.imports.len(),
+                Self::join_names(&analysis.imports, 3)
+            ));
+        }
+        if !analysis.functions.is_empty() {
+            description.push_str(&format!(" Defines {}.", Self::join_names(&analysis.functions, 4)));
+        }
+        description
+    }
+
+    fn describe_type(name: &str, facts: &FileFacts) -> String {
+        let mut description = match facts.fan_in {
+            0 => format!("Defined in `{}`.", facts.path),
+            1 => format!("Defined in `{}`, imported by 1 file.", facts.path),
+            n => format!("Defined in `{}`, imported by {} files.", facts.path, n),
+        };
+        if let Some(analysis) = facts.analysis.as_ref() {
+            let siblings: Vec<String> = analysis.classes.iter().filter(|c| *c != name).cloned().collect();
+            if !siblings.is_empty() {
+                description.push_str(&format!(" Sits alongside {}.", Self::join_names(&siblings, 3)));
+            }
+            if !analysis.functions.is_empty() {
+                description.push_str(&format!(" The file has {} function(s).", analysis.functions.len()));
+            }
+        }
+        description
+    }
+
+    fn join_names(names: &[String], limit: usize) -> String {
+        let shown: Vec<String> = names.iter().take(limit).map(|n| format!("`{}`", n)).collect();
+        if names.len() > limit {
+            format!("{} and {} more", shown.join(", "), names.len() - limit)
+        } else {
+            shown.join(", ")
+        }
+    }
+
+    fn is_entry_name(path: &str) -> bool {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        matches!(
+            file_name,
+            "main.rs" | "main.py" | "__main__.py" | "main.go" | "index.js" | "index.ts" | "app.py" | "cli.py"
+        ) || path.contains("src/bin/")
+    }
+
+    fn is_test_path(path: &str) -> bool {
+        let lower = path.to_lowercase();
+        lower.starts_with("tests/")
+            || lower.starts_with("test/")
+            || lower.contains("/tests/")
+            || lower.contains("/test/")
+            || lower.rsplit('/').next().map(|f| f.starts_with("test_")).unwrap_or(false)
+    }
+
+    fn path_depth(path: &str) -> usize {
+        path.matches('/').count()
+    }
+
+    fn module_stem(path: &str) -> String {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let stem = file_name.split('.').next().unwrap_or(file_name);
+        if matches!(stem, "mod" | "__init__" | "index") {
+            // Directory modules are imported by their directory name
+            path.rsplit('/').nth(1).unwrap_or("").to_string()
+        } else if matches!(stem, "main" | "lib") {
+            String::new()
+        } else {
+            stem.to_string()
+        }
+    }
+
+    fn import_mentions(import: &str, stem: &str) -> bool {
+        import
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .any(|segment| segment == stem)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_project() -> Vec<(String, String)> {
+        vec![
+            (
+                "src/main.rs".to_string(),
+                "use crate::config::Config;\nuse crate::engine;\n\nfn main() {\n    let cfg = load();\n    run(cfg);\n}\n\nfn load() -> Config {\n    Config::default()\n}\n".to_string(),
+            ),
+            (
+                "src/config.rs".to_string(),
+                "pub struct Config {\n    pub name: String,\n}\n".to_string(),
+            ),
+            (
+                "src/engine.rs".to_string(),
+                "use crate::config::Config;\n\npub struct Engine;\n\npub fn run(cfg: Config) {\n    step();\n}\n\nfn step() {}\n".to_string(),
+            ),
+            (
+                "tests/engine_test.rs".to_string(),
+                "use crate::engine;\n\nfn main() {}\n".to_string(),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_tour_orders_sections() {
+        let tour = TourGenerator::new().generate(&sample_project());
+        assert!(!tour.is_empty());
+
+        let sections: Vec<TourSection> = tour.stops.iter().map(|s| s.section).collect();
+        let mut sorted = sections.clone();
+        sorted.sort();
+        assert_eq!(sections, sorted, "Entry points, then types, then flows");
+
+        for (i, stop) in tour.stops.iter().enumerate() {
+            assert_eq!(stop.order, i + 1);
+        }
+    }
+
+    #[test]
+    fn test_tour_entry_point_excludes_tests() {
+        let tour = TourGenerator::new().generate(&sample_project());
+        let entries = tour.section(TourSection::EntryPoint);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "src/main.rs");
+    }
+
+    #[test]
+    fn test_tour_core_types_ranked_by_fan_in() {
+        let tour = TourGenerator::new().generate(&sample_project());
+        let types = tour.section(TourSection::CoreType);
+        assert!(!types.is_empty());
+        assert_eq!(types[0].symbol.as_deref(), Some("Config"));
+        assert!(types[0].description.contains("imported by 2 files"));
+    }
+
+    #[test]
+    fn test_tour_main_flows_follow_call_graph() {
+        let tour = TourGenerator::new().generate(&sample_project());
+        let flows: Vec<&str> = tour
+            .section(TourSection::MainFlow)
+            .iter()
+            .filter_map(|s| s.symbol.as_deref())
+            .collect();
+        assert!(flows.contains(&"run"));
+        assert!(flows.contains(&"step"));
+    }
+
+    #[test]
+    fn test_tour_markdown_rendering() {
+        let tour = TourGenerator::new().generate(&sample_project());
+        let md = tour.to_markdown();
+        assert!(md.starts_with("# Guided Tour"));
+        assert!(md.contains("## Entry Points"));
+        assert!(md.contains("Zoom: `file=src/main.rs`"));
+    }
+
+    #[test]
+    fn test_tour_empty_project() {
+        let tour = TourGenerator::new().generate(&[]);
+        assert!(tour.is_empty());
+        assert!(tour.to_markdown().contains("No entry points"));
+    }
+
+    #[test]
+    fn test_module_stem() {
+        assert_eq!(TourGenerator::module_stem("src/core/mod.rs"), "core");
+        assert_eq!(TourGenerator::module_stem("pkg/__init__.py"), "pkg");
+        assert_eq!(TourGenerator::module_stem("src/config.rs"), "config");
+        assert_eq!(TourGenerator::module_stem("src/lib.rs"), "");
+    }
+}
@@ -0,0 +1,190 @@
+//! Unknowns Report - Where the parsers could not see
+//!
+//! Every adapter records the regions it could not make sense of as unknown
+//! nodes: syntax errors, missing elements, template directives it does not
+//! model. The census only counts them; this report lists each one with its
+//! location, the parser's reason and a zoom command to inspect it, grouped
+//! by language and reason:
+//!
+//! ```text
+//! Rust: Syntax error (2 regions, 3 lines)
+//!   src/broken.rs:4-5  fn oops( {
+//!     pm_encoder --zoom file=src/broken.rs:4-5
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Serialize;
+use voyager_ast::LanguageId;
+
+use crate::core::ast_bridge::AstBridge;
+use crate::core::zoom::ZoomTarget;
+
+/// Reason reported for regions the adapter did not explain
+const UNCLASSIFIED: &str = "Unclassified";
+
+/// Longest snippet shown for a region
+const MAX_SNIPPET_CHARS: usize = 60;
+
+/// One region a parser could not interpret
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnknownRegion {
+    pub path: String,
+    /// First and last line (1-indexed)
+    pub start_line: usize,
+    pub end_line: usize,
+    /// First line of the region's text
+    pub snippet: String,
+    /// Command that shows the region in context
+    pub zoom: String,
+}
+
+/// Regions of one language with the same reason
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnknownGroup {
+    pub language: String,
+    pub reason: String,
+    pub regions: Vec<UnknownRegion>,
+}
+
+impl UnknownGroup {
+    /// Lines covered by the group's regions
+    pub fn line_count(&self) -> usize {
+        self.regions.iter().map(|r| r.end_line + 1 - r.start_line).sum()
+    }
+}
+
+/// Unknown regions of a project
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UnknownsReport {
+    /// Files handed to a parser
+    pub files_scanned: usize,
+    pub files_with_unknowns: usize,
+    /// Groups by language, then reason
+    pub groups: Vec<UnknownGroup>,
+}
+
+impl UnknownsReport {
+    /// Parse `(path, content)` pairs and collect their unknown regions
+    pub fn build(files: &[(String, String)]) -> Self {
+        let bridge = AstBridge::new();
+        let mut report = Self::default();
+        let mut groups: BTreeMap<(String, String), Vec<UnknownRegion>> = BTreeMap::new();
+
+        for (path, content) in files {
+            let language = AstBridge::detect_language(Path::new(path));
+            if language == LanguageId::Unknown {
+                continue;
+            }
+            let Some(file) = bridge.analyze_file(content, language) else { continue };
+            report.files_scanned += 1;
+            if !file.unknown_regions.is_empty() {
+                report.files_with_unknowns += 1;
+            }
+
+            for node in &file.unknown_regions {
+                let start_line = node.span.start_line.max(1);
+                let end_line = node.span.end_line.max(start_line);
+                let text = node.raw_text.as_deref().or_else(|| content.get(node.span.start..node.span.end));
+                let zoom = ZoomTarget::File { path: path.clone(), start_line: Some(start_line), end_line: Some(end_line) };
+                let reason = node.reason.clone().unwrap_or_else(|| UNCLASSIFIED.to_string());
+                groups.entry((language.name().to_string(), reason)).or_default().push(UnknownRegion {
+                    path: path.clone(),
+                    start_line,
+                    end_line,
+                    snippet: snippet(text.unwrap_or_default()),
+                    zoom: zoom.to_command(None),
+                });
+            }
+        }
+
+        report.groups = groups
+            .into_iter()
+            .map(|((language, reason), mut regions)| {
+                regions.sort_by(|a, b| (&a.path, a.start_line).cmp(&(&b.path, b.start_line)));
+                UnknownGroup { language, reason, regions }
+            })
+            .collect();
+        report
+    }
+
+    /// Number of regions across all groups
+    pub fn region_count(&self) -> usize {
+        self.groups.iter().map(|g| g.regions.len()).sum()
+    }
+
+    /// Human-readable listing
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "Unknown regions: {} in {} of {} parsed files\n",
+            self.region_count(),
+            self.files_with_unknowns,
+            self.files_scanned
+        );
+        for group in &self.groups {
+            out.push_str(&format!(
+                "\n{}: {} ({} regions, {} lines)\n",
+                group.language,
+                group.reason,
+                group.regions.len(),
+                group.line_count()
+            ));
+            for region in &group.regions {
+                let location = if region.start_line == region.end_line {
+                    format!("{}:{}", region.path, region.start_line)
+                } else {
+                    format!("{}:{}-{}", region.path, region.start_line, region.end_line)
+                };
+                out.push_str(&format!("  {}  {}\n    {}\n", location, region.snippet, region.zoom));
+            }
+        }
+        out
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// First non-empty line of a region, shortened
+fn snippet(text: &str) -> String {
+    let line = text.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default();
+    if line.chars().count() > MAX_SNIPPET_CHARS {
+        let cut: String = line.chars().take(MAX_SNIPPET_CHARS).collect();
+        format!("{}...", cut)
+    } else {
+        line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_regions_grouped_with_zoom_commands() {
+        let files = vec![
+            ("src/ok.rs".to_string(), "fn fine() {}\n".to_string()),
+            ("src/broken.rs".to_string(), "fn fine() {}\n\nfn oops( {\n    let x = ;\n}\n".to_string()),
+            ("notes.txt".to_string(), "not code (\n".to_string()),
+        ];
+        let report = UnknownsReport::build(&files);
+
+        assert_eq!(report.files_scanned, 2);
+        assert_eq!(report.files_with_unknowns, 1);
+        assert!(report.region_count() > 0);
+        assert!(report.groups.iter().all(|g| g.language == "Rust"));
+
+        let region = &report.groups[0].regions[0];
+        assert_eq!(region.path, "src/broken.rs");
+        assert!(region.start_line >= 3);
+        assert_eq!(region.zoom, format!("pm_encoder --zoom file=src/broken.rs:{}-{}", region.start_line, region.end_line));
+
+        let text = report.to_text();
+        assert!(text.starts_with(&format!("Unknown regions: {} in 1 of 2 parsed files\n", report.region_count())));
+        assert!(text.contains("\nRust: "));
+        assert!(!text.contains("src/ok.rs"));
+    }
+}
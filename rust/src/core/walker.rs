@@ -5,9 +5,14 @@
 
 use crate::core::error::{EncoderError, Result};
 use crate::core::models::FileEntry;
-use globset::{Glob, GlobSet, GlobSetBuilder};
-use std::path::Path;
+use crate::core::textformat::TextFormat;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::SystemTime;
+use crate::core::paths::{relative_key, ProjectPaths};
+use voyager_ast::{extended_length_path, PatternRules, DEFAULT_CASE_INSENSITIVE};
 
 #[cfg(test)]
 use mockall::automock;
@@ -54,6 +59,8 @@ pub struct WalkConfig {
     pub include_patterns: Vec<String>,
     /// Maximum file size in bytes
     pub max_file_size: u64,
+    /// Match patterns case-insensitively (default: on Windows only)
+    pub case_insensitive: bool,
 }
 
 impl Default for WalkConfig {
@@ -69,63 +76,89 @@ impl Default for WalkConfig {
             ],
             include_patterns: vec![],
             max_file_size: 1_048_576,
+            case_insensitive: DEFAULT_CASE_INSENSITIVE,
         }
     }
 }
 
+/// Bytes sniffed from the start of a file for binary detection
+pub const BINARY_SNIFF_BYTES: usize = 8192;
+
+/// Files at least this large are memory-mapped instead of read into a buffer
+pub const MMAP_THRESHOLD: u64 = 256 * 1024;
+
+/// Upper bound on reader threads used by [`DefaultWalker`]
+pub const MAX_WALK_THREADS: usize = 8;
+
 /// Default file walker implementation
-pub struct DefaultWalker;
+///
+/// Directory traversal is sequential (it only touches metadata); reading and
+/// decoding file contents is spread across a bounded pool of scoped threads.
+/// Output order always matches traversal order, regardless of thread count.
+pub struct DefaultWalker {
+    threads: usize,
+}
 
 impl DefaultWalker {
-    /// Create a new DefaultWalker
+    /// Create a new DefaultWalker using the available parallelism (capped)
     pub fn new() -> Self {
-        Self
+        let threads = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+            .min(MAX_WALK_THREADS);
+        Self { threads }
     }
 
-    /// Build a GlobSet from patterns
-    fn build_globset(patterns: &[String]) -> Option<GlobSet> {
-        if patterns.is_empty() {
-            return None;
-        }
+    /// Builder: set the number of reader threads (1 = sequential)
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
 
-        let mut builder = GlobSetBuilder::new();
-        for pattern in patterns {
-            if let Ok(glob) = Glob::new(pattern) {
-                builder.add(glob);
-            }
-        }
-        builder.build().ok()
+    /// Number of reader threads
+    pub fn threads(&self) -> usize {
+        self.threads
     }
 
-    /// Check if path matches any pattern
+    /// Check if path matches the patterns as ordered first-match-wins rules
+    ///
+    /// See [`PatternRules`] for the syntax (`!negation`, `dir/`, anchoring).
     fn matches_patterns(path: &str, patterns: &[String]) -> bool {
-        for pattern in patterns {
-            // Check for exact match
-            if path == pattern {
-                return true;
-            }
+        PatternRules::new(patterns).is_match(path, false)
+    }
 
-            // Check for directory component match
-            for component in path.split('/') {
-                if component == pattern {
-                    return true;
-                }
-            }
+    /// Read candidates on the worker pool, preserving input order
+    fn read_candidates(&self, candidates: Vec<Candidate>) -> Vec<FileEntry> {
+        let threads = self.threads.min(candidates.len()).max(1);
+        if threads == 1 {
+            return candidates.iter().filter_map(Candidate::read).collect();
+        }
 
-            // Check for glob match
-            if let Ok(glob) = Glob::new(pattern) {
-                let matcher = glob.compile_matcher();
-                if matcher.is_match(path) {
-                    return true;
-                }
-            }
+        let next = AtomicUsize::new(0);
+        let mut slots: Vec<(usize, FileEntry)> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut read = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(candidate) = candidates.get(index) else { break };
+                            if let Some(entry) = candidate.read() {
+                                read.push((index, entry));
+                            }
+                        }
+                        read
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap_or_default())
+                .collect()
+        });
 
-            // Check for prefix match (directory)
-            if path.starts_with(&format!("{}/", pattern)) {
-                return true;
-            }
-        }
-        false
+        slots.sort_unstable_by_key(|(index, _)| *index);
+        slots.into_iter().map(|(_, entry)| entry).collect()
     }
 }
 
@@ -135,6 +168,46 @@ impl Default for DefaultWalker {
     }
 }
 
+/// A file selected during traversal, waiting to be read
+struct Candidate {
+    path: PathBuf,
+    relative_path: String,
+    metadata: Option<std::fs::Metadata>,
+}
+
+impl Candidate {
+    fn read(&self) -> Option<FileEntry> {
+        let size_hint = self.metadata.as_ref().map_or(0, |m| m.len());
+        let (content, format) = read_text_file_with_format(&self.path, size_hint)?;
+
+        // Get timestamps and size
+        let (mtime, ctime, size) = self.metadata
+            .as_ref()
+            .map(|m| {
+                let mtime = m.modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                let ctime = m.created()
+                    .ok()
+                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(mtime);
+                let size = m.len();
+                (mtime, ctime, size)
+            })
+            .unwrap_or((0, 0, content.len() as u64));
+
+        Some(
+            FileEntry::new(&self.relative_path, content)
+                .with_timestamps(mtime, ctime)
+                .with_size(size)
+                .with_format(format),
+        )
+    }
+}
+
 impl FileWalker for DefaultWalker {
     fn walk(&self, root: &str, config: &WalkConfig) -> Result<Vec<FileEntry>> {
         let root_path = Path::new(root);
@@ -150,12 +223,19 @@ impl FileWalker for DefaultWalker {
             )));
         }
 
-        let include_set = Self::build_globset(&config.include_patterns);
-        let mut entries = Vec::new();
+        let ignore = PatternRules::with_case(&config.ignore_patterns, config.case_insensitive);
+        let include = PatternRules::with_case(&config.include_patterns, config.case_insensitive);
+        let paths = ProjectPaths::new(root_path);
+        let relative = |path: &Path| paths.relative(path);
+        let mut candidates = Vec::new();
 
         for entry in walkdir::WalkDir::new(root)
             .follow_links(false)
             .into_iter()
+            .filter_entry(|e| {
+                // Never descend into ignored directories unless a negation may re-include below
+                e.depth() == 0 || !e.file_type().is_dir() || !ignore.can_prune(&relative(e.path()))
+            })
             .filter_map(|e| e.ok())
         {
             // Skip directories
@@ -164,23 +244,16 @@ impl FileWalker for DefaultWalker {
             }
 
             let path = entry.path();
-            let relative_path = normalize_path_separators(
-                &path
-                    .strip_prefix(root)
-                    .unwrap_or(path)
-                    .to_string_lossy(),
-            );
+            let relative_path = relative(path);
 
             // Skip ignored files
-            if self.should_ignore(&relative_path, &config.ignore_patterns) {
+            if ignore.is_match(&relative_path, false) {
                 continue;
             }
 
             // Check include patterns if specified
-            if let Some(ref include_set) = include_set {
-                if !include_set.is_match(&relative_path) {
-                    continue;
-                }
+            if !include.is_empty() && !include.is_match(&relative_path, false) {
+                continue;
             }
 
             // Check file size
@@ -191,50 +264,66 @@ impl FileWalker for DefaultWalker {
                 }
             }
 
-            // Read file content
-            let bytes = match std::fs::read(path) {
-                Ok(b) => b,
-                Err(_) => continue,
-            };
+            candidates.push(Candidate {
+                path: path.to_path_buf(),
+                relative_path,
+                metadata,
+            });
+        }
 
-            // Skip binary files
-            if is_binary(&bytes) {
-                continue;
-            }
+        Ok(self.read_candidates(candidates))
+    }
 
-            // Convert to string
-            let content = match read_file_content(&bytes) {
-                Some(c) => c,
-                None => continue,
-            };
+    fn should_ignore(&self, path: &str, patterns: &[String]) -> bool {
+        Self::matches_patterns(path, patterns)
+    }
+}
 
-            // Get timestamps and size
-            let (mtime, ctime, size) = metadata
-                .map(|m| {
-                    let mtime = m.modified()
-                        .ok()
-                        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-                        .map(|d| d.as_secs())
-                        .unwrap_or(0);
-                    let ctime = m.created()
-                        .ok()
-                        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
-                        .map(|d| d.as_secs())
-                        .unwrap_or(mtime);
-                    let size = m.len();
-                    (mtime, ctime, size)
-                })
-                .unwrap_or((0, 0, content.len() as u64));
+/// Read a text file, rejecting binaries after sniffing only the first 8KB
+///
+/// Files of at least [`MMAP_THRESHOLD`] bytes are memory-mapped; smaller files
+/// (and platforms where mapping fails) are read into a buffer. Returns `None`
+/// for unreadable, binary, or undecodable files.
+pub fn read_text_file(path: &Path, size_hint: u64) -> Option<String> {
+    read_text_file_with_format(path, size_hint).map(|(content, _)| content)
+}
 
-            entries.push(FileEntry::new(&relative_path, content).with_timestamps(mtime, ctime).with_size(size));
+/// [`read_text_file`], also returning the line endings and BOM normalization lost
+pub fn read_text_file_with_format(path: &Path, size_hint: u64) -> Option<(String, TextFormat)> {
+    let mut file = File::open(extended_length_path(path)).ok()?;
+
+    let mut head = vec![0u8; BINARY_SNIFF_BYTES];
+    let mut filled = 0;
+    while filled < head.len() {
+        match file.read(&mut head[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => return None,
         }
+    }
+    head.truncate(filled);
 
-        Ok(entries)
+    if is_binary(&head) {
+        return None;
+    }
+    if filled < BINARY_SNIFF_BYTES {
+        return read_file_content_with_format(&head);
     }
 
-    fn should_ignore(&self, path: &str, patterns: &[String]) -> bool {
-        Self::matches_patterns(path, patterns)
+    if size_hint >= MMAP_THRESHOLD {
+        // SAFETY: the mapping is read-only and dropped before returning. A file
+        // truncated by another process while mapped can fault; the walker
+        // accepts that risk for files above the threshold, as ripgrep does.
+        if let Ok(map) = unsafe { memmap2::Mmap::map(&file) } {
+            return read_file_content_with_format(&map);
+        }
     }
+
+    let mut bytes = head;
+    bytes.reserve(size_hint.saturating_sub(bytes.len() as u64) as usize);
+    file.read_to_end(&mut bytes).ok()?;
+    read_file_content_with_format(&bytes)
 }
 
 /// Check if content appears to be binary
@@ -266,6 +355,11 @@ pub fn read_file_content(bytes: &[u8]) -> Option<String> {
     }
 }
 
+/// [`read_file_content`], also returning the original [`TextFormat`]
+pub fn read_file_content_with_format(bytes: &[u8]) -> Option<(String, TextFormat)> {
+    read_file_content(bytes).map(|content| (content, TextFormat::detect(bytes)))
+}
+
 // ============================================================================
 // SmartWalker - Intelligent file walker with boundary awareness
 // ============================================================================
@@ -564,20 +658,10 @@ impl SmartWalker {
         let mut file_entries = Vec::new();
 
         for entry in walk_entries {
-            // Read file content
-            let bytes = match std::fs::read(&entry.path) {
-                Ok(b) => b,
-                Err(_) => continue,
-            };
-
-            // Skip binary files
-            if is_binary(&bytes) {
-                continue;
-            }
-
-            // Convert to string
-            let content = match read_file_content(&bytes) {
-                Some(c) => c,
+            // Read file content (skips binary and undecodable files)
+            let size_hint = std::fs::metadata(&entry.path).map_or(0, |m| m.len());
+            let (content, format) = match read_text_file_with_format(&entry.path, size_hint) {
+                Some(read) => read,
                 None => continue,
             };
 
@@ -602,10 +686,11 @@ impl SmartWalker {
 
             file_entries.push(
                 FileEntry::new(
-                    normalize_path_separators(&entry.relative_path.to_string_lossy()),
+                    relative_key(&entry.relative_path),
                     content,
                 )
-                .with_timestamps(mtime, ctime),
+                .with_timestamps(mtime, ctime)
+                .with_format(format),
             );
         }
 
@@ -697,6 +782,22 @@ mod tests {
         assert_eq!(content, Some("line1\nline2".to_string()));
     }
 
+    #[test]
+    fn test_walk_records_text_format() {
+        use crate::core::textformat::LineEnding;
+
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("dos.txt"), b"\xEF\xBB\xBFone\r\ntwo\r\n").unwrap();
+        fs::write(temp.path().join("unix.txt"), "one\ntwo\n").unwrap();
+
+        let mut entries = DefaultWalker::new().walk(temp.path().to_str().unwrap(), &WalkConfig::default()).unwrap();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(entries[0].content, "\u{FEFF}one\ntwo\n");
+        assert_eq!((entries[0].format.line_ending, entries[0].format.bom), (LineEnding::Crlf, true));
+        assert_eq!(entries[0].format.original_offset(entries[0].content.find("two").unwrap()), 8);
+        assert_eq!(entries[1].format, TextFormat::default());
+    }
+
     #[test]
     fn test_default_walker_nonexistent() {
         let walker = DefaultWalker::new();
@@ -720,6 +821,141 @@ mod tests {
         assert_eq!(entries[0].content, "Hello, world!");
     }
 
+    #[test]
+    fn test_default_walker_parallel_matches_sequential() {
+        let temp_dir = TempDir::new().unwrap();
+        for i in 0..40 {
+            let dir = temp_dir.path().join(format!("mod{}", i % 4));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(format!("file{}.rs", i)), format!("// file {}\n", i)).unwrap();
+        }
+        let root = temp_dir.path().to_str().unwrap();
+        let config = WalkConfig::default();
+
+        let sequential = DefaultWalker::new().with_threads(1).walk(root, &config).unwrap();
+        let parallel = DefaultWalker::new().with_threads(4).walk(root, &config).unwrap();
+
+        assert_eq!(sequential.len(), 40);
+        let paths = |entries: &[FileEntry]| entries.iter().map(|e| e.path.clone()).collect::<Vec<_>>();
+        assert_eq!(paths(&sequential), paths(&parallel));
+    }
+
+    #[test]
+    fn test_read_text_file_binary_sniff() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("blob.bin");
+        let mut bytes = vec![0u8; 16];
+        bytes.extend_from_slice(&[b'a'; BINARY_SNIFF_BYTES * 2]);
+        fs::write(&path, &bytes).unwrap();
+        assert_eq!(read_text_file(&path, bytes.len() as u64), None);
+    }
+
+    #[test]
+    fn test_read_text_file_buffered_and_mapped() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let medium = "let x = 1;\n".repeat(BINARY_SNIFF_BYTES / 4);
+        let medium_path = temp_dir.path().join("medium.rs");
+        fs::write(&medium_path, &medium).unwrap();
+        assert_eq!(read_text_file(&medium_path, medium.len() as u64), Some(medium));
+
+        let large = "line\r\n".repeat(MMAP_THRESHOLD as usize / 4);
+        let large_path = temp_dir.path().join("large.txt");
+        fs::write(&large_path, &large).unwrap();
+        assert_eq!(
+            read_text_file(&large_path, large.len() as u64),
+            Some(large.replace("\r\n", "\n"))
+        );
+    }
+
+    #[test]
+    fn test_default_walker_ordered_rules() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("tests/fixtures")).unwrap();
+        fs::create_dir_all(temp_dir.path().join("src/generated")).unwrap();
+        fs::write(temp_dir.path().join("tests/test_a.py"), "a").unwrap();
+        fs::write(temp_dir.path().join("tests/fixtures/data.json"), "{}").unwrap();
+        fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(temp_dir.path().join("src/generated/api.rs"), "// gen").unwrap();
+
+        let config = WalkConfig {
+            ignore_patterns: vec!["!tests/fixtures/".to_string(), "tests/".to_string()],
+            include_patterns: vec!["!src/generated/".to_string(), "src/**".to_string(), "tests/**".to_string()],
+            max_file_size: 1_048_576,
+            case_insensitive: false,
+        };
+        let entries = DefaultWalker::new().walk(temp_dir.path().to_str().unwrap(), &config).unwrap();
+        let mut paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["src/main.rs", "tests/fixtures/data.json"]);
+    }
+
+    #[test]
+    fn test_default_walker_windows_style_patterns() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("Src/Gen")).unwrap();
+        fs::write(temp_dir.path().join("Src/Main.rs"), "fn main() {}").unwrap();
+        fs::write(temp_dir.path().join("Src/Gen/api.rs"), "// gen").unwrap();
+        let root = temp_dir.path().to_str().unwrap();
+
+        let config = WalkConfig {
+            ignore_patterns: vec!["src\\gen\\".to_string()],
+            include_patterns: vec!["SRC\\**".to_string()],
+            max_file_size: 1_048_576,
+            case_insensitive: true,
+        };
+        let entries = DefaultWalker::new().walk(root, &config).unwrap();
+        let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["Src/Main.rs"]);
+
+        let sensitive = WalkConfig { case_insensitive: false, ..config };
+        let entries = DefaultWalker::new().walk(root, &sensitive).unwrap();
+        assert!(entries.is_empty(), "case-sensitive include must not match Src/");
+    }
+
+    #[test]
+    fn test_default_walker_paths_independent_of_root_form() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+        fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+
+        let canonical = temp_dir.path().canonicalize().unwrap();
+        let roots = [
+            temp_dir.path().to_path_buf(),
+            canonical.clone(),
+            canonical.join("src/.."),
+            canonical.join("."),
+        ];
+        for root in roots {
+            let entries = DefaultWalker::new()
+                .walk(root.to_str().unwrap(), &WalkConfig::default())
+                .unwrap();
+            let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+            assert_eq!(paths, vec!["src/main.rs"], "root {:?}", root);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_default_walker_non_utf8_names_round_trip() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let name = OsStr::from_bytes(b"caf\xe9.rs");
+        if fs::write(temp_dir.path().join(name), "fn cafe() {}").is_err() {
+            return; // filesystem rejects non-UTF-8 names (e.g. some macOS volumes)
+        }
+
+        let entries = DefaultWalker::new()
+            .walk(temp_dir.path().to_str().unwrap(), &WalkConfig::default())
+            .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, "%raw:caf%E9.rs");
+        assert_eq!(entries[0].raw_path().as_path().as_os_str(), name);
+        assert!(temp_dir.path().join(entries[0].raw_path()).exists());
+    }
+
     #[test]
     fn test_should_ignore() {
         let walker = DefaultWalker::new();
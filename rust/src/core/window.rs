@@ -0,0 +1,421 @@
+//! Sliding-Window Truncation - AST-aware body elision for debugging contexts
+//!
+//! Head truncation cuts a file at line N, which routinely drops the part of a
+//! function that matters when debugging: its error paths and its final
+//! statements. The sliding window keeps, for every function/method body:
+//!
+//! - the signature (everything outside the body is untouched)
+//! - the first N statements
+//! - every exit/error branch (`return`, `raise`, `throw`, `Err(..)`, `panic!`,
+//!   `except`/`catch` blocks) together with its enclosing branch header
+//! - the last M statements
+//!
+//! Everything else inside the body is replaced by an elision marker written in
+//! the language's comment syntax.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use voyager_ast::Declaration;
+
+use crate::core::ast_bridge::AstBridge;
+use crate::core::summarizer::{collect_callables, comment_delimiters};
+
+lazy_static! {
+    /// Lines that leave the function or signal an error
+    static ref EXIT_LINE: Regex = Regex::new(
+        r"^\s*\}?\s*(?:return\b|raise\b|throw\b|Err\(|bail!|panic!|unreachable!|todo!|unimplemented!|die\b|exit\b|os\.Exit|log\.Fatal)"
+    ).unwrap();
+    /// Headers of error-handling blocks whose whole body is kept
+    static ref ERROR_BLOCK: Regex = Regex::new(
+        r"^\s*\}?\s*(?:except\b|catch\b|rescue\b|finally\b|Err\s*\(.*=>)"
+    ).unwrap();
+}
+
+/// Sliding window sizes (in statements, approximated by non-blank lines)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowConfig {
+    /// Statements kept from the start of each body
+    pub head_statements: usize,
+    /// Statements kept from the end of each body
+    pub tail_statements: usize,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            head_statements: 5,
+            tail_statements: 3,
+        }
+    }
+}
+
+/// Result of sliding-window truncation over one file
+#[derive(Debug, Clone)]
+pub struct WindowResult {
+    /// Truncated content
+    pub content: String,
+    /// Total lines elided across all bodies
+    pub elided_lines: usize,
+    /// Number of bodies that were windowed
+    pub windowed_bodies: usize,
+}
+
+impl WindowResult {
+    /// Check if anything was elided
+    pub fn was_truncated(&self) -> bool {
+        self.elided_lines > 0
+    }
+}
+
+/// AST-aware sliding-window truncator
+#[derive(Debug, Clone, Default)]
+pub struct WindowTruncator {
+    config: WindowConfig,
+}
+
+impl WindowTruncator {
+    /// Create a truncator with default window sizes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a truncator with custom window sizes
+    pub fn with_config(config: WindowConfig) -> Self {
+        Self { config }
+    }
+
+    /// Window every function body in `content`
+    ///
+    /// Returns `None` when the language has no AST adapter, so callers can
+    /// fall back to a line-based strategy.
+    pub fn truncate(&self, content: &str, path: &str) -> Option<WindowResult> {
+        let language = AstBridge::detect_language(Path::new(path));
+        let file = AstBridge::new().analyze_file(content, language)?;
+        let (open, close) = comment_delimiters(language);
+
+        let mut callables: Vec<&Declaration> = Vec::new();
+        collect_callables(&file.declarations, &mut callables);
+
+        let lines: Vec<&str> = content.split('\n').collect();
+        // Elided lines (0-indexed) across all bodies
+        let mut elided: BTreeSet<usize> = BTreeSet::new();
+        let mut windowed_bodies = 0;
+
+        for decl in callables {
+            let Some((first, last)) = interior_lines(content, &lines, decl) else { continue };
+            let kept = self.kept_lines(&lines, first, last);
+            let before = elided.len();
+            elided.extend((first..=last).filter(|i| !kept.contains(i)));
+            if elided.len() > before {
+                windowed_bodies += 1;
+            }
+        }
+
+        let mut output: Vec<String> = Vec::with_capacity(lines.len());
+        let mut i = 0;
+        while i < lines.len() {
+            if !elided.contains(&i) {
+                output.push(lines[i].to_string());
+                i += 1;
+                continue;
+            }
+            let run_start = i;
+            while i < lines.len() && elided.contains(&i) {
+                i += 1;
+            }
+            let indent = leading_whitespace(lines[run_start]);
+            output.push(format!(
+                "{}{}... {} lines elided (lines {}-{}) ...{}",
+                indent,
+                open,
+                i - run_start,
+                run_start + 1,
+                i,
+                close
+            ));
+        }
+
+        Some(WindowResult {
+            content: output.join("\n"),
+            elided_lines: elided.len(),
+            windowed_bodies,
+        })
+    }
+
+    /// Lines to keep within the interior range `first..=last` (0-indexed)
+    fn kept_lines(&self, lines: &[&str], first: usize, last: usize) -> BTreeSet<usize> {
+        let mut kept = BTreeSet::new();
+        let statements: Vec<usize> = (first..=last).filter(|&i| !lines[i].trim().is_empty()).collect();
+
+        if statements.len() <= self.config.head_statements + self.config.tail_statements {
+            kept.extend(first..=last);
+            return kept;
+        }
+
+        // Head window: through the Nth statement, including blank lines in between
+        if self.config.head_statements > 0 {
+            let head_end = statements[self.config.head_statements - 1];
+            kept.extend(first..=head_end.max(first));
+        }
+
+        // Tail window
+        if self.config.tail_statements > 0 {
+            let tail_start = statements[statements.len() - self.config.tail_statements];
+            kept.extend(tail_start..=last);
+        }
+
+        // Exit and error branches
+        for (i, line) in lines.iter().enumerate().take(last + 1).skip(first) {
+            if ERROR_BLOCK.is_match(line) {
+                kept.insert(i);
+                kept.extend(block_body(lines, i, last));
+            } else if EXIT_LINE.is_match(line) {
+                kept.insert(i);
+                if let Some(header) = branch_header(lines, i, first) {
+                    kept.insert(header);
+                    // Keep the branch's closing brace so the structure reads correctly
+                    if let Some(closer) = branch_closer(lines, header, i, last) {
+                        kept.insert(closer);
+                    }
+                }
+            }
+        }
+
+        kept
+    }
+}
+
+/// Interior line range of a callable body (0-indexed, inclusive)
+///
+/// Excludes the line that opens the body when it also holds the signature
+/// (`fn f() {`) and the line that closes a braced body.
+fn interior_lines(content: &str, lines: &[&str], decl: &Declaration) -> Option<(usize, usize)> {
+    let body = decl.body_span?;
+    if body.start_line == 0 || body.end_line > lines.len() || body.start > content.len() {
+        return None;
+    }
+
+    let line_start = content[..body.start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let braced = content[body.start..].starts_with('{');
+    let starts_own_line = content[line_start..body.start].trim().is_empty();
+
+    let mut first = body.start_line - 1;
+    if braced || !starts_own_line {
+        first += 1;
+    }
+    let mut last = body.end_line - 1;
+    if braced {
+        last = last.checked_sub(1)?;
+    }
+
+    if first > last {
+        None
+    } else {
+        Some((first, last))
+    }
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    let trimmed = line.trim_start();
+    &line[..line.len() - trimmed.len()]
+}
+
+fn indent_width(line: &str) -> usize {
+    leading_whitespace(line).len()
+}
+
+/// Nearest preceding line (within the body) with smaller indentation
+fn branch_header(lines: &[&str], index: usize, first: usize) -> Option<usize> {
+    let indent = indent_width(lines[index]);
+    (first..index)
+        .rev()
+        .find(|&i| !lines[i].trim().is_empty() && indent_width(lines[i]) < indent)
+}
+
+/// Closing brace line of a branch opened at `header`
+fn branch_closer(lines: &[&str], header: usize, from: usize, last: usize) -> Option<usize> {
+    if !lines[header].trim_end().ends_with('{') {
+        return None;
+    }
+    let indent = indent_width(lines[header]);
+    ((from + 1)..=last).find(|&i| indent_width(lines[i]) == indent && lines[i].trim_start().starts_with('}'))
+}
+
+/// Lines belonging to the block opened at `header` (deeper indentation)
+fn block_body(lines: &[&str], header: usize, last: usize) -> Vec<usize> {
+    let indent = indent_width(lines[header]);
+    let mut body = Vec::new();
+    for (i, line) in lines.iter().enumerate().take(last + 1).skip(header + 1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if indent_width(line) <= indent {
+            // Include the closing brace of a braced block
+            if line.trim_start().starts_with('}') {
+                body.push(i);
+            }
+            break;
+        }
+        body.push(i);
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn long_python_function() -> String {
+        let mut source = String::from("def load(path):\n    data = None\n    count = 0\n    name = path\n    mode = 'r'\n    size = 1\n");
+        for i in 0..20 {
+            source.push_str(&format!("    step_{} = compute({})\n", i, i));
+        }
+        source.push_str("    if not data:\n        raise ValueError('empty')\n");
+        for i in 20..40 {
+            source.push_str(&format!("    step_{} = compute({})\n", i, i));
+        }
+        source.push_str("    try:\n        parse(data)\n    except KeyError as e:\n        log(e)\n        return None\n");
+        source.push_str("    cleanup()\n    finalize()\n    return data\n");
+        source
+    }
+
+    #[test]
+    fn test_python_keeps_head_tail_and_error_branches() {
+        let source = long_python_function();
+        let result = WindowTruncator::new().truncate(&source, "loader.py").unwrap();
+
+        assert!(result.was_truncated());
+        assert_eq!(result.windowed_bodies, 1);
+        let out = &result.content;
+        assert!(out.contains("def load(path):"), "signature kept");
+        assert!(out.contains("    data = None"), "head kept");
+        assert!(out.contains("    if not data:"), "branch header kept");
+        assert!(out.contains("raise ValueError('empty')"), "raise kept");
+        assert!(out.contains("except KeyError as e:"), "except kept");
+        assert!(out.contains("        log(e)"), "except body kept");
+        assert!(out.contains("    return data"), "tail kept");
+        assert!(!out.contains("step_10 ="), "middle elided");
+        assert!(out.contains("# ... "), "marker uses Python comments");
+    }
+
+    #[test]
+    fn test_rust_keeps_error_branch_and_braces() {
+        let mut source = String::from("fn run(x: i32) -> Result<i32, String> {\n");
+        for i in 0..15 {
+            source.push_str(&format!("    let v{} = x + {};\n", i, i));
+        }
+        source.push_str("    if x < 0 {\n        return Err(\"negative\".into());\n    }\n");
+        for i in 15..30 {
+            source.push_str(&format!("    let v{} = x + {};\n", i, i));
+        }
+        source.push_str("    Ok(v29)\n}\n");
+
+        let result = WindowTruncator::new().truncate(&source, "src/run.rs").unwrap();
+        let out = &result.content;
+        assert!(out.starts_with("fn run(x: i32) -> Result<i32, String> {"));
+        assert!(out.contains("    if x < 0 {\n        return Err(\"negative\".into());\n    }"));
+        assert!(out.contains("    Ok(v29)\n}"));
+        assert!(!out.contains("let v10 ="));
+        assert!(out.contains("/* ... "));
+    }
+
+    #[test]
+    fn test_short_bodies_untouched() {
+        let source = "fn small() {\n    let a = 1;\n    a + 1;\n}\n";
+        let result = WindowTruncator::new().truncate(source, "lib.rs").unwrap();
+        assert!(!result.was_truncated());
+        assert_eq!(result.content, source);
+    }
+
+    #[test]
+    fn test_empty_head_window_keeps_no_head() {
+        let source = long_python_function();
+        let config = WindowConfig { head_statements: 0, tail_statements: 3 };
+        let result = WindowTruncator::with_config(config).truncate(&source, "loader.py").unwrap();
+        let out = &result.content;
+        assert!(out.contains("def load(path):"));
+        assert!(!out.contains("    data = None"), "no head statements kept");
+        assert!(out.contains("raise ValueError('empty')"));
+        assert!(out.contains("    return data"));
+    }
+
+    #[test]
+    fn test_empty_tail_window_keeps_no_tail() {
+        let source = long_python_function();
+        let config = WindowConfig { head_statements: 5, tail_statements: 0 };
+        let result = WindowTruncator::with_config(config).truncate(&source, "loader.py").unwrap();
+        let out = &result.content;
+        assert!(out.contains("    data = None"));
+        assert!(out.contains("raise ValueError('empty')"));
+        assert!(!out.contains("    finalize()"), "no tail statements kept");
+        assert!(out.contains("    return data"), "exit lines are still kept");
+    }
+
+    /// Rust source with one function per `(body_lines, has_early_return)`
+    fn generated_rust(functions: &[(usize, bool)]) -> (String, Vec<String>) {
+        let mut source = String::new();
+        let mut texts = Vec::new();
+        for (i, &(body_lines, early_return)) in functions.iter().enumerate() {
+            let mut text = format!("fn f{}(x: i32) -> i32 {{\n", i);
+            for k in 0..body_lines {
+                text.push_str(&format!("    let v{} = x + {};\n", k, k));
+            }
+            if early_return {
+                text.push_str("    if x < 0 {\n        return -1;\n    }\n");
+            }
+            text.push_str("    x\n}\n");
+            source.push_str(&text);
+            source.push('\n');
+            texts.push(text);
+        }
+        (source, texts)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_window_never_splits_kept_declarations(
+            functions in proptest::collection::vec((0usize..25, any::<bool>()), 1..6)
+        ) {
+            let (source, texts) = generated_rust(&functions);
+            let config = WindowConfig::default();
+            let result = WindowTruncator::new().truncate(&source, "gen.rs").unwrap();
+
+            prop_assert!(result.content.lines().count() <= source.lines().count());
+            for (i, (text, &(body_lines, early_return))) in texts.iter().zip(&functions).enumerate() {
+                // Signatures always survive
+                let signature = format!("fn f{}(x: i32) -> i32 {{", i);
+                prop_assert!(result.content.contains(&signature), "missing {}", signature);
+
+                // Bodies within the window are kept whole, never split
+                let statements = body_lines + if early_return { 3 } else { 0 } + 1;
+                if statements <= config.head_statements + config.tail_statements {
+                    prop_assert!(result.content.contains(text.as_str()), "short f{} was split", i);
+                }
+            }
+
+            // Exit branches are always kept
+            let returns = functions.iter().filter(|(_, r)| *r).count();
+            prop_assert_eq!(result.content.matches("return -1;").count(), returns);
+        }
+    }
+
+    #[test]
+    fn test_unsupported_language_returns_none() {
+        assert!(WindowTruncator::new().truncate("a\nb\n", "notes.txt").is_none());
+    }
+
+    #[test]
+    fn test_custom_window_sizes() {
+        let source = long_python_function();
+        let wide = WindowTruncator::with_config(WindowConfig {
+            head_statements: 100,
+            tail_statements: 100,
+        });
+        let result = wide.truncate(&source, "loader.py").unwrap();
+        assert!(!result.was_truncated());
+    }
+}
@@ -10,13 +10,20 @@
 //! /* ZOOM_AFFORDANCE: pm_encoder --zoom function=apply_budget --budget=1000 */
 //! ```
 //!
-//! The LLM can then request expansion via MCP or CLI.
+//! The LLM can then request expansion via MCP or CLI. Declarations can also
+//! be addressed by their [`Anchor`] (`--zoom anchor=src/lib.rs#Engine.run@1a2b3c4d`).
 
+use crate::core::anchor::Anchor;
 use crate::core::error::{EncoderError, Result};
+use crate::core::json_store;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::path::{Path, PathBuf};
+use voyager_ast::{Capability, Declaration, DeclarationKind};
+
+/// Granularity of computed affordance budgets (and their minimum)
+const BUDGET_STEP: usize = 50;
 
 /// Target type for zoom operations
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -27,6 +34,11 @@ pub enum ZoomTarget {
     Class(String),
     /// Zoom into a module
     Module(String),
+    /// Zoom into a Markdown section by heading, optionally scoped to a
+    /// document (`README.md#Installation`)
+    Section(String),
+    /// Zoom into the declaration an anchor addresses
+    Anchor(Anchor),
     /// Zoom into a file with optional line range
     File {
         path: String,
@@ -50,6 +62,8 @@ impl ZoomTarget {
             "function" | "fn" => Ok(ZoomTarget::Function(value.to_string())),
             "class" | "struct" => Ok(ZoomTarget::Class(value.to_string())),
             "module" | "mod" => Ok(ZoomTarget::Module(value.to_string())),
+            "section" | "heading" => Ok(ZoomTarget::Section(value.trim_matches('"').to_string())),
+            "anchor" | "id" => Ok(ZoomTarget::Anchor(Anchor::parse(value)?)),
             "file" => {
                 // Parse file path, optionally with line range: path:start-end
                 if let Some(colon_pos) = value.rfind(':') {
@@ -90,6 +104,9 @@ impl ZoomTarget {
             ZoomTarget::Function(name) => format!("function={}", name),
             ZoomTarget::Class(name) => format!("class={}", name),
             ZoomTarget::Module(name) => format!("module={}", name),
+            ZoomTarget::Section(title) if title.contains(char::is_whitespace) => format!("section=\"{}\"", title),
+            ZoomTarget::Section(title) => format!("section={}", title),
+            ZoomTarget::Anchor(anchor) => format!("anchor={}", anchor),
             ZoomTarget::File { path, start_line, end_line } => {
                 match (start_line, end_line) {
                     (Some(s), Some(e)) => format!("file={}:{}-{}", path, s, e),
@@ -112,6 +129,8 @@ impl fmt::Display for ZoomTarget {
             ZoomTarget::Function(name) => write!(f, "function:{}", name),
             ZoomTarget::Class(name) => write!(f, "class:{}", name),
             ZoomTarget::Module(name) => write!(f, "module:{}", name),
+            ZoomTarget::Section(title) => write!(f, "section:{}", title),
+            ZoomTarget::Anchor(anchor) => write!(f, "anchor:{}", anchor),
             ZoomTarget::File { path, start_line, end_line } => {
                 match (start_line, end_line) {
                     (Some(s), Some(e)) => write!(f, "file:{}[{}-{}]", path, s, e),
@@ -134,10 +153,28 @@ pub struct ZoomConfig {
     pub depth: ZoomDepth,
     /// Include related tests
     pub include_tests: bool,
+    /// Include doc sections and docstrings that mention the zoomed symbols
+    pub include_docs: bool,
     /// Context lines around the target
     pub context_lines: usize,
 }
 
+impl ZoomConfig {
+    /// Adapter capabilities the related-content options rely on
+    ///
+    /// Tests are linked through the calls in their bodies, docs through doc comments.
+    pub fn required_capabilities(&self) -> Vec<Capability> {
+        let mut required = Vec::new();
+        if self.include_tests {
+            required.push(Capability::Calls);
+        }
+        if self.include_docs {
+            required.push(Capability::DocComments);
+        }
+        required
+    }
+}
+
 /// Depth of zoom expansion
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ZoomDepth {
@@ -169,11 +206,98 @@ impl Default for ZoomConfig {
             budget: Some(1000),
             depth: ZoomDepth::Implementation,
             include_tests: false,
+            include_docs: false,
             context_lines: 5,
         }
     }
 }
 
+/// How a shared budget is split across the targets of a batch zoom
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PackingStrategy {
+    /// Each target gets a share proportional to its size
+    #[default]
+    Proportional,
+    /// Targets are filled in request order until the budget runs out
+    Priority,
+}
+
+impl PackingStrategy {
+    /// Parse packing strategy from string
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "proportional" | "prop" => Some(PackingStrategy::Proportional),
+            "priority" | "ordered" => Some(PackingStrategy::Priority),
+            _ => None,
+        }
+    }
+
+    /// Split `budget` across targets requesting `demands` tokens each
+    ///
+    /// Allocations never exceed demands, and their sum never exceeds the budget.
+    pub fn allocate(&self, demands: &[usize], budget: usize) -> Vec<usize> {
+        let total: usize = demands.iter().sum();
+        if total <= budget {
+            return demands.to_vec();
+        }
+
+        match self {
+            PackingStrategy::Proportional => demands
+                .iter()
+                .map(|&d| d * budget / total)
+                .collect(),
+            PackingStrategy::Priority => {
+                let mut remaining = budget;
+                demands
+                    .iter()
+                    .map(|&d| {
+                        let take = d.min(remaining);
+                        remaining -= take;
+                        take
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Configuration for zooming into several targets within one budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchZoomConfig {
+    /// Targets to expand (all must resolve, or the batch fails)
+    pub targets: Vec<ZoomTarget>,
+    /// Shared token budget for all targets
+    pub budget: Option<usize>,
+    /// How the budget is split across targets
+    pub strategy: PackingStrategy,
+}
+
+impl BatchZoomConfig {
+    /// Create a batch with no budget limit
+    pub fn new(targets: Vec<ZoomTarget>) -> Self {
+        Self {
+            targets,
+            budget: None,
+            strategy: PackingStrategy::default(),
+        }
+    }
+
+    /// Builder: set shared budget
+    pub fn with_budget(mut self, budget: usize) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Builder: set packing strategy
+    pub fn with_strategy(mut self, strategy: PackingStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+}
+
+/// Name of the related-symbols list a symbol zoom appends
+pub const RELATED_FILE_NAME: &str = "RELATED.md";
+
 /// A zoom action represents a suggested expansion point
 #[derive(Debug, Clone)]
 pub struct ZoomAction {
@@ -185,6 +309,8 @@ pub struct ZoomAction {
     pub description: String,
     /// The CLI command to execute
     pub command: String,
+    /// What a container offers beyond its own text ("children available: 3 methods")
+    pub children_hint: Option<String>,
 }
 
 impl ZoomAction {
@@ -197,6 +323,7 @@ impl ZoomAction {
             suggested_budget: budget,
             description: format!("Expand function '{}' ({} tokens)", name, budget),
             command,
+            children_hint: None,
         }
     }
 
@@ -209,6 +336,20 @@ impl ZoomAction {
             suggested_budget: budget,
             description: format!("Expand class '{}' ({} tokens)", name, budget),
             command,
+            children_hint: None,
+        }
+    }
+
+    /// Create a new zoom action for a Markdown section
+    pub fn for_section(target: &str, budget: usize) -> Self {
+        let target = ZoomTarget::Section(target.to_string());
+        let command = target.to_command(Some(budget));
+        Self {
+            description: format!("Expand section '{}' ({} tokens)", target, budget),
+            target,
+            suggested_budget: budget,
+            command,
+            children_hint: None,
         }
     }
 
@@ -225,23 +366,90 @@ impl ZoomAction {
             suggested_budget: budget,
             description: format!("Expand file '{}' ({} tokens)", path, budget),
             command,
+            children_hint: None,
         }
     }
 
+    /// Create a zoom action sized to a declaration of `content`
+    ///
+    /// The budget is the token estimate of the declaration's span rather than
+    /// a caller-chosen constant; containers also advertise their children.
+    pub fn for_declaration(decl: &Declaration, content: &str) -> Self {
+        let end = decl.span.end.min(content.len());
+        let tokens = content.get(decl.span.start.min(end)..end).map_or(0, |text| text.len() / 4);
+        let budget = suggested_budget(tokens);
+        let mut action = match decl.kind {
+            DeclarationKind::Function | DeclarationKind::Method => Self::for_function(&decl.name, budget),
+            DeclarationKind::Module | DeclarationKind::Namespace => {
+                let target = ZoomTarget::Module(decl.name.clone());
+                Self {
+                    command: target.to_command(Some(budget)),
+                    target,
+                    suggested_budget: budget,
+                    description: format!("Expand module '{}' ({} tokens)", decl.name, budget),
+                    children_hint: None,
+                }
+            }
+            _ => Self::for_class(&decl.name, budget),
+        };
+        action.children_hint = children_hint(decl);
+        action
+    }
+
+    /// Address the action's declaration by `anchor` instead of by name
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.target = ZoomTarget::Anchor(anchor);
+        self.command = self.target.to_command(Some(self.suggested_budget));
+        self
+    }
+
     /// Generate the affordance comment for serialization
     pub fn to_affordance_comment(&self) -> String {
-        format!("/* ZOOM_AFFORDANCE: {} */", self.command)
+        match &self.children_hint {
+            Some(hint) => format!("/* ZOOM_AFFORDANCE: {} | {} */", self.command, hint),
+            None => format!("/* ZOOM_AFFORDANCE: {} */", self.command),
+        }
     }
 
     /// Generate XML representation
     pub fn to_xml(&self) -> String {
+        let children = self
+            .children_hint
+            .as_ref()
+            .map(|hint| format!(" children=\"{}\"", hint))
+            .unwrap_or_default();
         format!(
-            "<action type=\"expand\" target=\"{}\" budget=\"{}\" cmd=\"{}\" />",
-            self.target, self.suggested_budget, self.command
+            "<action type=\"expand\" target=\"{}\" budget=\"{}\" cmd=\"{}\"{} />",
+            self.target, self.suggested_budget, self.command, children
         )
     }
 }
 
+/// Round a token estimate up to a budget worth advertising
+pub fn suggested_budget(tokens: usize) -> usize {
+    tokens.div_ceil(BUDGET_STEP).max(1) * BUDGET_STEP
+}
+
+/// "children available: 2 fields, 3 methods" for declarations with children
+fn children_hint(decl: &Declaration) -> Option<String> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for child in &decl.children {
+        *counts.entry(child.kind.as_str()).or_default() += 1;
+    }
+    if counts.is_empty() {
+        return None;
+    }
+    let parts: Vec<String> = counts
+        .into_iter()
+        .map(|(kind, count)| match (count, kind.ends_with('s')) {
+            (1, _) => format!("1 {}", kind),
+            (_, true) => format!("{} {}es", count, kind),
+            (_, false) => format!("{} {}s", count, kind),
+        })
+        .collect();
+    Some(format!("children available: {}", parts.join(", ")))
+}
+
 // ============================================================================
 // Fractal Protocol v2: Bidirectional Zoom & Sessions
 // ============================================================================
@@ -264,6 +472,9 @@ pub struct ZoomHistoryEntry {
     pub direction: ZoomDirection,
     /// Depth before the zoom (for undo)
     pub previous_depth: ZoomDepth,
+    /// Depth the target was expanded to (absent for collapses and older sessions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<ZoomDepth>,
     /// Timestamp of the action
     pub timestamp: u64,
 }
@@ -436,6 +647,7 @@ impl ZoomSession {
             target: target.clone(),
             direction: ZoomDirection::Expand,
             previous_depth: ZoomDepth::Signature,
+            depth: Some(depth),
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
@@ -462,6 +674,7 @@ impl ZoomSession {
                 target: target.clone(),
                 direction: ZoomDirection::Collapse,
                 previous_depth: prev_depth,
+                depth: None,
                 timestamp: std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap_or_default()
@@ -498,13 +711,11 @@ impl ZoomSession {
     }
 }
 
-fn default_version() -> String { "1.0".to_string() }
-
 /// Session store for managing multiple sessions with persistence
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ZoomSessionStore {
     /// Schema version for future migrations
-    #[serde(default = "default_version")]
+    #[serde(default = "json_store::default_version")]
     pub version: String,
 
     /// All sessions by name
@@ -527,7 +738,7 @@ pub struct ZoomSessionStore {
 impl Default for ZoomSessionStore {
     fn default() -> Self {
         Self {
-            version: default_version(),
+            version: json_store::default_version(),
             sessions: HashMap::new(),
             active_session: None,
             store_path: None,
@@ -544,43 +755,23 @@ impl ZoomSessionStore {
 
     /// Default session file location (project-local)
     pub fn default_path(project_root: &Path) -> PathBuf {
-        project_root.join(".pm_encoder").join("sessions.json")
+        json_store::store_path(project_root, "sessions.json")
     }
 
     /// Load sessions from JSON file, or create empty store
     pub fn load(path: &Path) -> std::result::Result<Self, String> {
-        if !path.exists() {
-            let mut store = Self::default();
-            store.store_path = Some(path.to_path_buf());
-            return Ok(store);
-        }
-
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| format!("Failed to read sessions: {}", e))?;
-
-        let mut store: Self = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse sessions: {}", e))?;
-
+        let mut store: Self = json_store::load(path).map_err(|e| match e {
+            EncoderError::Json(e) => format!("Failed to parse sessions: {}", e),
+            e => format!("Failed to read sessions: {}", e),
+        })?;
         store.store_path = Some(path.to_path_buf());
         Ok(store)
     }
 
     /// Save sessions to JSON file
     pub fn save(&self) -> std::result::Result<(), String> {
-        let path = self.store_path.as_ref()
-            .ok_or_else(|| "No store path configured".to_string())?;
-
-        // Ensure directory exists
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)
-                .map_err(|e| format!("Failed to create directory: {}", e))?;
-        }
-
-        let content = serde_json::to_string_pretty(self)
-            .map_err(|e| format!("Failed to serialize: {}", e))?;
-
-        std::fs::write(path, content)
-            .map_err(|e| format!("Failed to write: {}", e))
+        json_store::save(self.store_path.as_deref(), self)
+            .map_err(|e| format!("Failed to save sessions: {}", e))
     }
 
     /// Enable auto-save on Drop
@@ -706,6 +897,34 @@ impl Drop for ZoomSessionStore {
 mod tests {
     use super::*;
 
+    // --- Batch Zoom Tests ---
+
+    #[test]
+    fn test_packing_strategy_parse() {
+        assert_eq!(PackingStrategy::parse("proportional"), Some(PackingStrategy::Proportional));
+        assert_eq!(PackingStrategy::parse("Priority"), Some(PackingStrategy::Priority));
+        assert_eq!(PackingStrategy::parse("greedy"), None);
+    }
+
+    #[test]
+    fn test_packing_allocate_within_budget() {
+        let demands = [100, 200, 300];
+        assert_eq!(PackingStrategy::Proportional.allocate(&demands, 1000), vec![100, 200, 300]);
+        assert_eq!(PackingStrategy::Priority.allocate(&demands, 1000), vec![100, 200, 300]);
+    }
+
+    #[test]
+    fn test_packing_allocate_proportional() {
+        let alloc = PackingStrategy::Proportional.allocate(&[100, 300], 200);
+        assert_eq!(alloc, vec![50, 150]);
+    }
+
+    #[test]
+    fn test_packing_allocate_priority() {
+        let alloc = PackingStrategy::Priority.allocate(&[100, 300, 50], 250);
+        assert_eq!(alloc, vec![100, 150, 0]);
+    }
+
     // ========================================================================
     // Fractal v2 Tests - TDD: Written first, implementation follows
     // ========================================================================
@@ -720,6 +939,7 @@ mod tests {
             target: ZoomTarget::Function("test".to_string()),
             direction: ZoomDirection::Expand,
             previous_depth: ZoomDepth::Signature,
+            depth: None,
             timestamp: 12345,
         };
 
@@ -742,6 +962,7 @@ mod tests {
             target: ZoomTarget::Function("first".to_string()),
             direction: ZoomDirection::Expand,
             previous_depth: ZoomDepth::Signature,
+            depth: None,
             timestamp: 1,
         });
 
@@ -762,6 +983,7 @@ mod tests {
                 target: ZoomTarget::Function(format!("fn{}", i)),
                 direction: ZoomDirection::Expand,
                 previous_depth: ZoomDepth::Signature,
+                depth: None,
                 timestamp: i as u64,
             });
         }
@@ -781,6 +1003,7 @@ mod tests {
                 target: ZoomTarget::Function(format!("fn{}", i)),
                 direction: ZoomDirection::Expand,
                 previous_depth: ZoomDepth::Signature,
+                depth: None,
                 timestamp: i as u64,
             });
         }
@@ -794,6 +1017,7 @@ mod tests {
             target: ZoomTarget::Function("new".to_string()),
             direction: ZoomDirection::Expand,
             previous_depth: ZoomDepth::Signature,
+            depth: None,
             timestamp: 10,
         });
 
@@ -944,6 +1168,23 @@ mod tests {
         assert!(matches!(target, ZoomTarget::Function(name) if name == "apply_budget"));
     }
 
+    #[test]
+    fn test_zoom_target_parse_section() {
+        let target = ZoomTarget::parse("section=\"Getting Started\"").unwrap();
+        assert_eq!(target, ZoomTarget::Section("Getting Started".to_string()));
+        assert_eq!(target.to_command(None), "pm_encoder --zoom section=\"Getting Started\"");
+        assert_eq!(ZoomTarget::parse("heading=README.md#Usage").unwrap().to_command(None), "pm_encoder --zoom section=README.md#Usage");
+    }
+
+    #[test]
+    fn test_zoom_target_parse_anchor() {
+        let target = ZoomTarget::parse("anchor=src/lib.rs#Engine.run@1A2B3C4D").unwrap();
+        let ZoomTarget::Anchor(anchor) = &target else { panic!("Expected Anchor target") };
+        assert_eq!((anchor.path.as_str(), anchor.qualified_name.as_str()), ("src/lib.rs", "Engine.run"));
+        assert_eq!(target.to_command(Some(50)), "pm_encoder --zoom anchor=src/lib.rs#Engine.run@1a2b3c4d --budget 50");
+        assert!(ZoomTarget::parse("id=src/lib.rs").is_err());
+    }
+
     #[test]
     fn test_zoom_target_parse_file_with_range() {
         let target = ZoomTarget::parse("file=src/main.rs:10-50").unwrap();
@@ -980,6 +1221,27 @@ mod tests {
         assert!(comment.ends_with("*/"));
     }
 
+    #[test]
+    fn test_zoom_action_for_declaration() {
+        let content = "impl Engine {\n    fn zoom(&self) {}\n    fn walk(&self) {}\n}\n".to_string() + &" ".repeat(400);
+        let span = |start, end, line| voyager_ast::Span::new(start, end, line, line);
+        let mut container = Declaration::new("Engine".to_string(), DeclarationKind::Impl, span(0, 460, 1));
+        container.children = vec![
+            Declaration::new("zoom".to_string(), DeclarationKind::Method, span(18, 38, 2)),
+            Declaration::new("walk".to_string(), DeclarationKind::Method, span(43, 63, 3)),
+        ];
+
+        let action = ZoomAction::for_declaration(&container, &content);
+        assert_eq!(action.suggested_budget, 150);
+        assert_eq!(action.children_hint.as_deref(), Some("children available: 2 methods"));
+        assert!(action.to_affordance_comment().ends_with("--budget 150 | children available: 2 methods */"));
+
+        let method = ZoomAction::for_declaration(&container.children[0], &content);
+        assert_eq!((method.command.as_str(), method.children_hint), ("pm_encoder --zoom function=zoom --budget 50", None));
+        assert_eq!(suggested_budget(0), 50);
+        assert_eq!(suggested_budget(101), 150);
+    }
+
     #[test]
     fn test_zoom_action_xml() {
         let action = ZoomAction::for_class("DataProcessor", 2000);
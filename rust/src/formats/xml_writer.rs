@@ -60,6 +60,8 @@ pub struct XmlConfig {
     pub frozen: bool,
     pub allow_sensitive: bool,
     pub snapshot_id: Option<String>,
+    /// Configuration warnings, written to the metadata before any file
+    pub warnings: Vec<String>,
 }
 
 impl Default for XmlConfig {
@@ -73,6 +75,7 @@ impl Default for XmlConfig {
             frozen: false,
             allow_sensitive: false,
             snapshot_id: None,
+            warnings: Vec::new(),
         }
     }
 }
@@ -191,6 +194,14 @@ impl<W: Write> XmlWriter<W> {
             writeln!(self.writer, "    </lens_config>")?;
         }
 
+        if !self.config.warnings.is_empty() {
+            writeln!(self.writer, "    <warnings>")?;
+            for warning in &self.config.warnings {
+                writeln!(self.writer, "      <warning>{}</warning>", escape_xml_attr(warning))?;
+            }
+            writeln!(self.writer, "    </warnings>")?;
+        }
+
         writeln!(self.writer, "  </metadata>")?;
         writeln!(self.writer)?;
 
@@ -494,6 +505,25 @@ mod tests {
         assert!(package_pos < token_pos, "package should come before token_budget");
     }
 
+    #[test]
+    fn test_xml_writer_metadata_warnings() {
+        let mut output = Vec::new();
+        let config = XmlConfig {
+            warnings: vec!["include pattern '<src>' matches no files".to_string()],
+            ..Default::default()
+        };
+
+        let mut writer = XmlWriter::new(&mut output, config);
+        writer.write_metadata(&[]).unwrap();
+
+        let xml = String::from_utf8(output).unwrap();
+        assert!(xml.contains("    <warnings>\n      <warning>include pattern &apos;&lt;src&gt;&apos; matches no files</warning>\n    </warnings>\n"));
+
+        let mut output = Vec::new();
+        XmlWriter::new(&mut output, XmlConfig::default()).write_metadata(&[]).unwrap();
+        assert!(!String::from_utf8(output).unwrap().contains("<warnings>"));
+    }
+
     #[test]
     fn test_xml_writer_frozen_no_timestamp() {
         let mut output = Vec::new();
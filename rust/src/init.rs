@@ -5,10 +5,37 @@
 //! - Context file (CONTEXT.txt): Serialized codebase (separate file)
 //!
 //! The instruction file does NOT contain code, only a pointer to CONTEXT.txt.
+//! With `compress`, the context is written as zstd-compressed CONTEXT.txt.zst
+//! instead and the pointer names that file.
+//!
+//! The generated part of the instruction file sits between
+//! [`GENERATED_BEGIN`] and [`GENERATED_END`] markers. Regenerating replaces
+//! only that section, so notes written above or below it survive, and
+//! [`check_instructions`] can detect a stale section (e.g. in CI or a
+//! pre-commit hook).
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use crate::python_style_split;
+use crate::core::{DependencyModel, ProjectProfile};
+
+/// Start of the generated section of an instruction file
+pub const GENERATED_BEGIN: &str = "<!-- pm_encoder:generated:begin -->";
+
+/// End of the generated section of an instruction file
+pub const GENERATED_END: &str = "<!-- pm_encoder:generated:end -->";
+
+/// Context file name
+pub const CONTEXT_FILE: &str = "CONTEXT.txt";
+
+/// Context file name, with the zstd extension when compressed
+pub fn context_filename(compress: bool) -> String {
+    if compress {
+        format!("{}.{}", CONTEXT_FILE, crate::core::codec::ZSTD_EXTENSION)
+    } else {
+        CONTEXT_FILE.to_string()
+    }
+}
 
 /// Detect common project commands based on project files
 ///
@@ -161,7 +188,7 @@ fn build_tree_recursive(
 /// Generate the .pm_encoder_meta header content
 ///
 /// Matches Python's lens_manager.get_meta_content() output exactly.
-fn generate_meta_header(lens_name: &str, description: &str) -> String {
+fn generate_meta_header(lens_name: &str, description: &str, profile: &ProjectProfile) -> String {
     use chrono::Utc;
 
     let timestamp = Utc::now().format("%Y-%m-%dT%H:%M:%S%.6f").to_string();
@@ -182,6 +209,12 @@ fn generate_meta_header(lens_name: &str, description: &str) -> String {
         content.push('\n');
     }
 
+    let summary = profile.render_header();
+    if !summary.is_empty() {
+        content.push_str(&summary);
+        content.push('\n');
+    }
+
     content.push_str(&format!("Generated: {}\n", timestamp));
     content.push_str(&format!("pm_encoder version: {}\n", crate::VERSION));
 
@@ -220,6 +253,19 @@ fn get_instruction_filename(target: &str) -> &'static str {
     }
 }
 
+/// Instruction and context file contents for a project, not yet written
+#[derive(Debug, Clone)]
+pub struct InstructionSet {
+    /// Instruction file name (CLAUDE.md or GEMINI_INSTRUCTIONS.txt)
+    pub instruction_filename: &'static str,
+    /// Generated instruction content, wrapped in the section markers
+    pub instructions: String,
+    /// Serialized codebase for CONTEXT.txt
+    pub context: String,
+    /// Context file the instructions point to (CONTEXT.txt or CONTEXT.txt.zst)
+    pub context_filename: String,
+}
+
 /// Initialize AI instruction files (Split Brain architecture)
 ///
 /// Creates two files:
@@ -227,11 +273,53 @@ fn get_instruction_filename(target: &str) -> &'static str {
 /// 2. Context file (CONTEXT.txt): Serialized codebase
 ///
 /// The instruction file points to CONTEXT.txt, does NOT contain code.
+/// Text outside the generated section of an existing instruction file is kept.
+///
+/// With `compress`, the context is written zstd-compressed to CONTEXT.txt.zst
+/// and a stale CONTEXT.txt is removed (and vice versa), so the pointer never
+/// names an outdated file.
 pub fn init_prompt(
     root: &str,
     lens_name: &str,
     target: &str,
+    compress: bool,
 ) -> Result<(String, String), String> {
+    use crate::core::codec::{self, Codec};
+
+    let rendered = render_instructions(root, lens_name, target, compress)?;
+    let root_path = Path::new(root);
+
+    // Write CONTEXT.txt (or CONTEXT.txt.zst)
+    let context_path = root_path.join(&rendered.context_filename);
+    let bytes = if compress {
+        codec::compress(rendered.context.as_bytes(), Codec::Zstd)?
+    } else {
+        rendered.context.clone().into_bytes()
+    };
+    fs::write(&context_path, bytes)
+        .map_err(|e| format!("Failed to write {}: {}", rendered.context_filename, e))?;
+    let stale = root_path.join(context_filename(!compress));
+    if stale.exists() {
+        fs::remove_file(&stale)
+            .map_err(|e| format!("Failed to remove stale {}: {}", stale.display(), e))?;
+    }
+
+    // Write (or merge into) the instruction file
+    let (instruction_path, _) = write_instruction_file(root_path, &rendered)?;
+
+    Ok((
+        instruction_path.to_string_lossy().to_string(),
+        context_path.to_string_lossy().to_string(),
+    ))
+}
+
+/// Generate instruction and context content without touching the filesystem
+pub fn render_instructions(
+    root: &str,
+    lens_name: &str,
+    target: &str,
+    compress: bool,
+) -> Result<InstructionSet, String> {
     use crate::{EncoderConfig, LensManager, serialize_project_with_config};
 
     let root_path = Path::new(root);
@@ -252,6 +340,7 @@ pub fn init_prompt(
         "*.pyc".to_string(),
         // Exclude generated files (prevent recursion, matches Python)
         "CONTEXT.txt".to_string(),
+        "CONTEXT.txt.zst".to_string(),
         "CLAUDE.md".to_string(),
         "GEMINI_INSTRUCTIONS.txt".to_string(),
     ];
@@ -269,6 +358,12 @@ pub fn init_prompt(
         "__pycache__".to_string(),
         "*.pyc".to_string(),
         "*.swp".to_string(),
+        // Generated files must not feed back into their own statistics,
+        // otherwise every regeneration would change the output again
+        "CONTEXT.txt".to_string(),
+        "CONTEXT.txt.zst".to_string(),
+        "CLAUDE.md".to_string(),
+        "GEMINI_INSTRUCTIONS.txt".to_string(),
     ];
 
     // Merge default ignores with lens exclude patterns (matches Python)
@@ -290,8 +385,17 @@ pub fn init_prompt(
         ..Default::default()
     };
 
+    // Profile the whole project, not just the files the lens includes
+    let profile_files: Vec<(String, String)> =
+        crate::walk_directory(root, &config.ignore_patterns, &[], config.max_file_size)?
+            .into_iter()
+            .map(|entry| (entry.path, entry.content))
+            .collect();
+    let profile = ProjectProfile::analyze(&profile_files);
+    let dependencies = DependencyModel::from_files(&profile_files);
+
     // Generate meta header (matches Python's lens_manager.get_meta_content())
-    let meta_header = generate_meta_header(lens_name, &applied_lens.description);
+    let meta_header = generate_meta_header(lens_name, &applied_lens.description, &profile);
 
     let serialized_content = serialize_project_with_config(root, &config)?;
 
@@ -300,12 +404,7 @@ pub fn init_prompt(
     let context_lines = python_style_split(&context).len();
     let context_bytes = context.len();
 
-    // Step 4: Write CONTEXT.txt
-    let context_path = root_path.join("CONTEXT.txt");
-    fs::write(&context_path, &context)
-        .map_err(|e| format!("Failed to write CONTEXT.txt: {}", e))?;
-
-    // Step 5: Generate instruction file content
+    // Step 4: Generate instruction file content
     let instruction_filename = get_instruction_filename(target);
 
     // Get project name from directory - handle "." by canonicalizing first
@@ -316,36 +415,159 @@ pub fn init_prompt(
         .and_then(|n| n.to_str())
         .unwrap_or("project");
 
+    let context_filename = context_filename(compress);
     let instructions = generate_instruction_content(
         project_name,
         lens_name,
         &commands,
         &tree,
+        &profile,
+        &dependencies,
         context_lines,
         context_bytes,
+        &context_filename,
     );
 
-    // Step 6: Write instruction file
-    let instruction_path = root_path.join(instruction_filename);
-    fs::write(&instruction_path, &instructions)
-        .map_err(|e| format!("Failed to write {}: {}", instruction_filename, e))?;
+    Ok(InstructionSet {
+        instruction_filename,
+        instructions,
+        context,
+        context_filename,
+    })
+}
 
-    Ok((
-        instruction_path.to_string_lossy().to_string(),
-        context_path.to_string_lossy().to_string(),
-    ))
+/// Write the instruction file, merging into an existing one
+///
+/// Returns the path and whether the file content changed.
+fn write_instruction_file(root: &Path, rendered: &InstructionSet) -> Result<(PathBuf, bool), String> {
+    let path = root.join(rendered.instruction_filename);
+    let existing = fs::read_to_string(&path).ok();
+    let merged = merge_generated(existing.as_deref(), &rendered.instructions);
+    if existing.as_deref() == Some(merged.as_str()) {
+        return Ok((path, false));
+    }
+    fs::write(&path, &merged)
+        .map_err(|e| format!("Failed to write {}: {}", rendered.instruction_filename, e))?;
+    Ok((path, true))
+}
+
+/// Split `content` around its generated section
+///
+/// Returns `(before, section, after)` where `section` spans from the start of
+/// [`GENERATED_BEGIN`] to the end of [`GENERATED_END`], or `None` if either
+/// marker is missing.
+pub fn generated_section(content: &str) -> Option<(&str, &str, &str)> {
+    let begin = content.find(GENERATED_BEGIN)?;
+    let end = begin + content[begin..].find(GENERATED_END)? + GENERATED_END.len();
+    Some((&content[..begin], &content[begin..end], &content[end..]))
+}
+
+/// Replace the generated section of `existing` with the one in `generated`
+///
+/// Text outside the markers is kept. Without an existing file, or one that
+/// has no markers, the result is `generated` itself.
+pub fn merge_generated(existing: Option<&str>, generated: &str) -> String {
+    match (existing.and_then(generated_section), generated_section(generated)) {
+        (Some((before, _, after)), Some((_, section, _))) => format!("{}{}{}", before, section, after),
+        _ => generated.to_string(),
+    }
+}
+
+/// Result of comparing an instruction file against freshly generated content
+#[derive(Debug, Clone, PartialEq)]
+pub enum InstructionDrift {
+    /// The generated section matches
+    UpToDate,
+    /// The instruction file does not exist
+    Missing,
+    /// The instruction file has no generated-section markers
+    NoMarkers,
+    /// The generated section differs, first at `line` (1-indexed, within the section)
+    Stale {
+        line: usize,
+        expected: String,
+        found: String,
+    },
+}
+
+impl InstructionDrift {
+    /// Check if the file is up to date
+    pub fn is_up_to_date(&self) -> bool {
+        matches!(self, Self::UpToDate)
+    }
+}
+
+/// Compare the committed instruction file with freshly generated content
+///
+/// Nothing is written; CONTEXT.txt is not compared (it is usually not
+/// committed). Returns the instruction file path and the drift.
+pub fn check_instructions(
+    root: &str,
+    lens_name: &str,
+    target: &str,
+    compress: bool,
+) -> Result<(PathBuf, InstructionDrift), String> {
+    let rendered = render_instructions(root, lens_name, target, compress)?;
+    let path = Path::new(root).join(rendered.instruction_filename);
+    let Ok(existing) = fs::read_to_string(&path) else {
+        return Ok((path, InstructionDrift::Missing));
+    };
+    let Some((_, found, _)) = generated_section(&existing) else {
+        return Ok((path, InstructionDrift::NoMarkers));
+    };
+    let (_, expected, _) = generated_section(&rendered.instructions)
+        .ok_or_else(|| "Generated instructions are missing section markers".to_string())?;
+
+    if found == expected {
+        return Ok((path, InstructionDrift::UpToDate));
+    }
+    let mut expected_lines = expected.lines();
+    let mut found_lines = found.lines();
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), found_lines.next()) {
+            (Some(e), Some(f)) if e == f => line += 1,
+            (e, f) => {
+                let drift = InstructionDrift::Stale {
+                    line,
+                    expected: e.unwrap_or_default().to_string(),
+                    found: f.unwrap_or_default().to_string(),
+                };
+                return Ok((path, drift));
+            }
+        }
+    }
+}
+
+/// Regenerate only the instruction file (for pre-commit hooks)
+///
+/// Returns the path and whether the file changed.
+pub fn write_instructions(
+    root: &str,
+    lens_name: &str,
+    target: &str,
+    compress: bool,
+) -> Result<(PathBuf, bool), String> {
+    let rendered = render_instructions(root, lens_name, target, compress)?;
+    write_instruction_file(Path::new(root), &rendered)
 }
 
 /// Generate the content for the instruction file
+#[allow(clippy::too_many_arguments)]
 fn generate_instruction_content(
     project_name: &str,
     lens_name: &str,
     commands: &[String],
     tree: &[String],
+    profile: &ProjectProfile,
+    dependencies: &DependencyModel,
     _context_lines: usize,
     context_bytes: usize,
+    context_filename: &str,
 ) -> String {
     let mut content = String::new();
+    content.push_str(GENERATED_BEGIN);
+    content.push('\n');
 
     // Header
     content.push_str(&format!("# {}\n\n", project_name));
@@ -362,6 +584,16 @@ fn generate_instruction_content(
         lens_name
     ));
 
+    // Project Profile (languages, frameworks, architecture)
+    if profile.total_files > 0 {
+        content.push_str(&profile.render_markdown());
+    }
+
+    // Dependencies declared in manifests
+    if !dependencies.is_empty() {
+        content.push_str(&dependencies.render_markdown());
+    }
+
     // Commands
     if !commands.is_empty() {
         content.push_str("## Commands\n\n");
@@ -393,7 +625,15 @@ fn generate_instruction_content(
         bytes_str, context_bytes as f64 / 1024.0));
 
     // Pointer to CONTEXT.txt
-    content.push_str("For the complete codebase context, see `CONTEXT.txt` in this directory.\n\n");
+    if context_filename == CONTEXT_FILE {
+        content.push_str("For the complete codebase context, see `CONTEXT.txt` in this directory.\n\n");
+    } else {
+        content.push_str(&format!(
+            "For the complete codebase context, see `{0}` in this directory \
+             (zstd-compressed; read it with `zstd -dc {0}`).\n\n",
+            context_filename
+        ));
+    }
 
     // Footer
     content.push_str("---\n\n");
@@ -402,6 +642,8 @@ fn generate_instruction_content(
     content.push_str(&format!("./pm_encoder.py . --init-prompt --init-lens {} --target claude\n", lens_name));
     content.push_str("```\n\n");
     content.push_str(&format!("*Generated by pm_encoder v{} using the '{}' lens*\n", crate::VERSION, lens_name));
+    content.push_str(GENERATED_END);
+    content.push('\n');
 
     content
 }
@@ -514,7 +756,7 @@ mod tests {
         fs::create_dir_all(&temp).unwrap();
         fs::write(temp.join("main.py"), "print('hello')").unwrap();
 
-        let result = init_prompt(temp.to_str().unwrap(), "architecture", "claude");
+        let result = init_prompt(temp.to_str().unwrap(), "architecture", "claude", false);
 
         if let Ok((instruction_path, context_path)) = result {
             // Verify CLAUDE.md exists and has correct content
@@ -539,7 +781,7 @@ mod tests {
         fs::create_dir_all(&temp).unwrap();
         fs::write(temp.join("main.py"), "x = 1").unwrap();
 
-        let result = init_prompt(temp.to_str().unwrap(), "architecture", "gemini");
+        let result = init_prompt(temp.to_str().unwrap(), "architecture", "gemini", false);
 
         if let Ok((instruction_path, _)) = result {
             assert!(instruction_path.contains("GEMINI_INSTRUCTIONS.txt"),
@@ -561,7 +803,7 @@ mod tests {
 
     #[test]
     fn test_init_prompt_nonexistent_directory() {
-        let result = init_prompt("/nonexistent/path/xyz", "architecture", "claude");
+        let result = init_prompt("/nonexistent/path/xyz", "architecture", "claude", false);
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("not found"));
     }
@@ -580,7 +822,7 @@ mod tests {
         fs::create_dir_all(&temp).unwrap();
         fs::write(temp.join("main.py"), "x = 1").unwrap();
 
-        let result = init_prompt(temp.to_str().unwrap(), "architecture", "claude");
+        let result = init_prompt(temp.to_str().unwrap(), "architecture", "claude", false);
 
         if let Ok((instruction_path, _)) = result {
             let claude_md = fs::read_to_string(&instruction_path).unwrap();
@@ -597,4 +839,67 @@ mod tests {
 
         let _ = fs::remove_dir_all(&temp);
     }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_init_prompt_compressed_context() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().to_str().unwrap();
+        fs::write(temp.path().join("main.py"), "print('hello')").unwrap();
+        init_prompt(root, "architecture", "claude", false).unwrap();
+
+        let (instruction_path, context_path) = init_prompt(root, "architecture", "claude", true).unwrap();
+        assert!(context_path.ends_with("CONTEXT.txt.zst"));
+        assert!(!temp.path().join("CONTEXT.txt").exists(), "stale CONTEXT.txt is removed");
+        let context = crate::core::codec::decompress(&fs::read(&context_path).unwrap()).unwrap();
+        assert!(String::from_utf8(context).unwrap().contains("print('hello')"));
+
+        let claude_md = fs::read_to_string(&instruction_path).unwrap();
+        assert!(claude_md.contains("see `CONTEXT.txt.zst`"));
+        assert!(claude_md.contains("zstd -dc CONTEXT.txt.zst"));
+        assert!(check_instructions(root, "architecture", "claude", true).unwrap().1.is_up_to_date());
+        assert!(!check_instructions(root, "architecture", "claude", false).unwrap().1.is_up_to_date());
+    }
+
+    #[test]
+    fn test_merge_generated_keeps_user_notes() {
+        let generated = format!("{}\nnew\n{}\n", GENERATED_BEGIN, GENERATED_END);
+        let existing = format!("My notes\n{}\nold\n{}\nMore notes\n", GENERATED_BEGIN, GENERATED_END);
+        assert_eq!(
+            merge_generated(Some(&existing), &generated),
+            format!("My notes\n{}\nnew\n{}\nMore notes\n", GENERATED_BEGIN, GENERATED_END)
+        );
+        assert_eq!(merge_generated(Some("no markers"), &generated), generated);
+        assert_eq!(merge_generated(None, &generated), generated);
+    }
+
+    #[test]
+    fn test_check_instructions_detects_drift() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path().to_str().unwrap();
+        fs::write(temp.path().join("main.py"), "x = 1").unwrap();
+
+        let (_, drift) = check_instructions(root, "architecture", "claude", false).unwrap();
+        assert_eq!(drift, InstructionDrift::Missing);
+
+        let (path, changed) = write_instructions(root, "architecture", "claude", false).unwrap();
+        assert!(changed);
+        assert!(check_instructions(root, "architecture", "claude", false).unwrap().1.is_up_to_date());
+
+        // Regenerating is stable and keeps hand-written notes
+        let with_notes = format!("Team notes\n{}", fs::read_to_string(&path).unwrap());
+        fs::write(&path, &with_notes).unwrap();
+        assert!(!write_instructions(root, "architecture", "claude", false).unwrap().1);
+        assert!(check_instructions(root, "architecture", "claude", false).unwrap().1.is_up_to_date());
+
+        fs::write(temp.path().join("extra.py"), "y = 2").unwrap();
+        let (_, drift) = check_instructions(root, "architecture", "claude", false).unwrap();
+        assert!(matches!(drift, InstructionDrift::Stale { .. }), "{:?}", drift);
+
+        assert!(write_instructions(root, "architecture", "claude", false).unwrap().1);
+        let merged = fs::read_to_string(&path).unwrap();
+        assert!(merged.starts_with("Team notes\n"));
+        assert!(merged.contains("extra.py"));
+        assert!(check_instructions(root, "architecture", "claude", false).unwrap().1.is_up_to_date());
+    }
 }
@@ -0,0 +1,377 @@
+//! Content-Length framed JSON-RPC transport
+//!
+//! The LSP base protocol (also used by stdio JSON-RPC peers in general)
+//! frames every message as a block of `Name: value` headers, a blank line,
+//! and exactly `Content-Length` bytes of body:
+//!
+//! ```text
+//! Content-Length: 52\r\n
+//! \r\n
+//! {"jsonrpc":"2.0","id":1,"method":"initialize",...}
+//! ```
+//!
+//! [`Framed`] reads and writes such messages over any `BufRead`/`Write`;
+//! [`AsyncFramed`] does the same over tokio streams (`tokio` feature).
+//! Reading is lenient about what peers actually send: header names are
+//! case-insensitive, bare `\n` line endings are accepted and unknown headers
+//! (such as `Content-Type`) are ignored. Bodies above the configured maximum
+//! are skipped and reported as [`FrameError::TooLarge`], leaving the stream
+//! positioned at the next message.
+
+use std::fmt;
+use std::io::{self, BufRead, Read, Write};
+
+use serde_json::Value;
+
+/// Default upper bound for a message body (64 MiB)
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Longest header line accepted
+const MAX_HEADER_LINE: u64 = 8 * 1024;
+
+/// Why a frame could not be read
+#[derive(Debug)]
+pub enum FrameError {
+    Io(io::Error),
+    /// The stream ended inside a frame
+    UnexpectedEof,
+    /// Headers ended without a `Content-Length`
+    MissingContentLength,
+    /// A header line was malformed or too long
+    InvalidHeader(String),
+    /// The body exceeds the maximum size (it was skipped)
+    TooLarge { length: usize, max: usize },
+    /// The body is not valid JSON
+    InvalidJson(serde_json::Error),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::UnexpectedEof => write!(f, "Stream ended inside a message"),
+            Self::MissingContentLength => write!(f, "Missing Content-Length header"),
+            Self::InvalidHeader(line) => write!(f, "Invalid header: {}", line),
+            Self::TooLarge { length, max } => write!(f, "Message of {} bytes exceeds the {} byte limit", length, max),
+            Self::InvalidJson(e) => write!(f, "Invalid JSON body: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+impl From<io::Error> for FrameError {
+    fn from(e: io::Error) -> Self {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            Self::UnexpectedEof
+        } else {
+            Self::Io(e)
+        }
+    }
+}
+
+/// Encode `body` as a complete frame
+pub fn encode_frame(body: &[u8]) -> Vec<u8> {
+    let mut frame = format!("Content-Length: {}\r\n\r\n", body.len()).into_bytes();
+    frame.extend_from_slice(body);
+    frame
+}
+
+/// Header parsing shared by the sync and async readers
+#[derive(Default)]
+struct Headers {
+    content_length: Option<usize>,
+    seen: bool,
+}
+
+impl Headers {
+    /// Feed one raw line (with its terminator); `Ok(true)` once the headers are complete
+    fn feed(&mut self, raw: &str) -> Result<bool, FrameError> {
+        if !raw.ends_with('\n') {
+            return Err(if raw.len() as u64 >= MAX_HEADER_LINE {
+                FrameError::InvalidHeader(format!("line longer than {} bytes", MAX_HEADER_LINE))
+            } else {
+                FrameError::UnexpectedEof
+            });
+        }
+        let line = raw.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            // Stray blank lines between messages are tolerated
+            return Ok(self.seen);
+        }
+        self.seen = true;
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| FrameError::InvalidHeader(line.to_string()))?;
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            let length = value.trim().parse().map_err(|_| FrameError::InvalidHeader(line.to_string()))?;
+            self.content_length = Some(length);
+        }
+        Ok(false)
+    }
+
+    fn length(&self, max: usize) -> Result<usize, FrameError> {
+        let length = self.content_length.ok_or(FrameError::MissingContentLength)?;
+        if length > max {
+            return Err(FrameError::TooLarge { length, max });
+        }
+        Ok(length)
+    }
+}
+
+/// Blocking framed transport over a reader and/or writer
+#[derive(Debug)]
+pub struct Framed<T> {
+    inner: T,
+    max_message_size: usize,
+}
+
+impl<T> Framed<T> {
+    pub fn new(inner: T) -> Self {
+        Self { inner, max_message_size: DEFAULT_MAX_MESSAGE_SIZE }
+    }
+
+    /// Reject (and skip) bodies larger than `max` bytes
+    pub fn with_max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = max;
+        self
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<R: BufRead> Framed<R> {
+    /// Read one message body; `Ok(None)` on a clean end of stream
+    pub fn read_message(&mut self) -> Result<Option<Vec<u8>>, FrameError> {
+        let mut headers = Headers::default();
+        loop {
+            let mut line = String::new();
+            let read = (&mut self.inner).take(MAX_HEADER_LINE).read_line(&mut line)?;
+            if read == 0 {
+                return if headers.seen { Err(FrameError::UnexpectedEof) } else { Ok(None) };
+            }
+            if headers.feed(&line)? {
+                break;
+            }
+        }
+
+        let length = match headers.length(self.max_message_size) {
+            Err(FrameError::TooLarge { length, max }) => {
+                let skipped = io::copy(&mut (&mut self.inner).take(length as u64), &mut io::sink())?;
+                if skipped < length as u64 {
+                    return Err(FrameError::UnexpectedEof);
+                }
+                return Err(FrameError::TooLarge { length, max });
+            }
+            other => other?,
+        };
+        let mut body = vec![0u8; length];
+        self.inner.read_exact(&mut body)?;
+        Ok(Some(body))
+    }
+
+    /// Read one message and parse it as JSON
+    pub fn read_json(&mut self) -> Result<Option<Value>, FrameError> {
+        match self.read_message()? {
+            Some(body) => serde_json::from_slice(&body).map(Some).map_err(FrameError::InvalidJson),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<W: Write> Framed<W> {
+    /// Write one framed message and flush
+    pub fn write_message(&mut self, body: &[u8]) -> io::Result<()> {
+        self.inner.write_all(&encode_frame(body))?;
+        self.inner.flush()
+    }
+
+    /// Serialize `value` and write it as one message
+    pub fn write_json(&mut self, value: &Value) -> io::Result<()> {
+        self.write_message(&serde_json::to_vec(value)?)
+    }
+}
+
+/// Async framed transport over tokio streams
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct AsyncFramed<T> {
+    inner: T,
+    max_message_size: usize,
+}
+
+#[cfg(feature = "tokio")]
+impl<T> AsyncFramed<T> {
+    pub fn new(inner: T) -> Self {
+        Self { inner, max_message_size: DEFAULT_MAX_MESSAGE_SIZE }
+    }
+
+    /// Reject (and skip) bodies larger than `max` bytes
+    pub fn with_max_message_size(mut self, max: usize) -> Self {
+        self.max_message_size = max;
+        self
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncBufRead + Unpin> AsyncFramed<R> {
+    /// Read one message body; `Ok(None)` on a clean end of stream
+    pub async fn read_message(&mut self) -> Result<Option<Vec<u8>>, FrameError> {
+        use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+        let mut headers = Headers::default();
+        loop {
+            let mut line = String::new();
+            let read = (&mut self.inner).take(MAX_HEADER_LINE).read_line(&mut line).await?;
+            if read == 0 {
+                return if headers.seen { Err(FrameError::UnexpectedEof) } else { Ok(None) };
+            }
+            if headers.feed(&line)? {
+                break;
+            }
+        }
+
+        let length = match headers.length(self.max_message_size) {
+            Err(FrameError::TooLarge { length, max }) => {
+                let mut skip = (&mut self.inner).take(length as u64);
+                let skipped = tokio::io::copy(&mut skip, &mut tokio::io::sink()).await?;
+                if skipped < length as u64 {
+                    return Err(FrameError::UnexpectedEof);
+                }
+                return Err(FrameError::TooLarge { length, max });
+            }
+            other => other?,
+        };
+        let mut body = vec![0u8; length];
+        self.inner.read_exact(&mut body).await?;
+        Ok(Some(body))
+    }
+
+    /// Read one message and parse it as JSON
+    pub async fn read_json(&mut self) -> Result<Option<Value>, FrameError> {
+        match self.read_message().await? {
+            Some(body) => serde_json::from_slice(&body).map(Some).map_err(FrameError::InvalidJson),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<W: tokio::io::AsyncWrite + Unpin> AsyncFramed<W> {
+    /// Write one framed message and flush
+    pub async fn write_message(&mut self, body: &[u8]) -> io::Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        self.inner.write_all(&encode_frame(body)).await?;
+        self.inner.flush().await
+    }
+
+    /// Serialize `value` and write it as one message
+    pub async fn write_json(&mut self, value: &Value) -> io::Result<()> {
+        self.write_message(&serde_json::to_vec(value)?).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufReader, Cursor};
+
+    /// Yields at most one byte per read, like a slow pipe
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn test_round_trip_and_partial_reads() {
+        let mut out = Framed::new(Vec::new());
+        out.write_json(&serde_json::json!({"id": 1})).unwrap();
+        out.write_message(b"{\"id\":2}").unwrap();
+        let bytes = out.into_inner();
+        assert!(bytes.starts_with(b"Content-Length: 8\r\n\r\n{\"id\":1}"));
+
+        let mut reader = Framed::new(BufReader::with_capacity(1, Trickle(&bytes)));
+        assert_eq!(reader.read_json().unwrap().unwrap()["id"], 1);
+        assert_eq!(reader.read_message().unwrap().unwrap(), b"{\"id\":2}");
+        assert!(reader.read_message().unwrap().is_none(), "clean EOF");
+    }
+
+    #[test]
+    fn test_header_variants() {
+        let input = b"\r\ncontent-length: 2\nContent-Type: application/vscode-jsonrpc; charset=utf-8\n\n{}\
+CONTENT-LENGTH:2\r\n\r\n[]";
+        let mut reader = Framed::new(Cursor::new(&input[..]));
+        assert_eq!(reader.read_message().unwrap().unwrap(), b"{}");
+        assert_eq!(reader.read_message().unwrap().unwrap(), b"[]");
+
+        let mut missing = Framed::new(Cursor::new(&b"Content-Type: x\r\n\r\n{}"[..]));
+        assert!(matches!(missing.read_message(), Err(FrameError::MissingContentLength)));
+        let mut garbage = Framed::new(Cursor::new(&b"hello\r\n\r\n"[..]));
+        assert!(matches!(garbage.read_message(), Err(FrameError::InvalidHeader(_))));
+        let mut truncated = Framed::new(Cursor::new(&b"Content-Length: 10\r\n\r\n{}"[..]));
+        assert!(matches!(truncated.read_message(), Err(FrameError::UnexpectedEof)));
+    }
+
+    #[test]
+    fn test_oversized_message_is_skipped() {
+        let mut bytes = encode_frame(&[b'x'; 100]);
+        bytes.extend(encode_frame(b"{}"));
+        let mut reader = Framed::new(Cursor::new(bytes)).with_max_message_size(10);
+        assert!(matches!(reader.read_message(), Err(FrameError::TooLarge { length: 100, max: 10 })));
+        assert_eq!(reader.read_message().unwrap().unwrap(), b"{}");
+
+        let long_header = format!("X-Padding: {}\r\n", "a".repeat(MAX_HEADER_LINE as usize));
+        let mut reader = Framed::new(Cursor::new(long_header.into_bytes()));
+        assert!(matches!(reader.read_message(), Err(FrameError::InvalidHeader(_))));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_async_round_trip() {
+        let (client, server) = tokio::io::duplex(16);
+        let writer = tokio::spawn(async move {
+            let mut out = AsyncFramed::new(client);
+            out.write_json(&serde_json::json!({"method": "ping"})).await.unwrap();
+            out.write_message(&[b'x'; 64]).await.unwrap();
+            out.write_message(b"{}").await.unwrap();
+        });
+
+        let mut reader = AsyncFramed::new(tokio::io::BufReader::new(server)).with_max_message_size(32);
+        assert_eq!(reader.read_json().await.unwrap().unwrap()["method"], "ping");
+        assert!(matches!(reader.read_message().await, Err(FrameError::TooLarge { length: 64, .. })));
+        assert_eq!(reader.read_message().await.unwrap().unwrap(), b"{}");
+        writer.await.unwrap();
+        assert!(reader.read_message().await.unwrap().is_none());
+    }
+}
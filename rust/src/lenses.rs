@@ -17,6 +17,7 @@ use std::collections::HashMap;
 use std::path::Path;
 use serde::{Deserialize, Serialize};
 
+use crate::core::models::{LanguageTruncation, SymbolRule};
 use crate::core::store::ContextStore;
 
 /// Priority group for file ranking (v1.7.0)
@@ -66,11 +67,11 @@ pub struct LensConfig {
     #[serde(default)]
     pub truncate: Option<usize>,
 
-    /// Patterns to exclude
+    /// Patterns to exclude (ordered, first match wins; `!pattern` re-includes, `dir/` matches directories)
     #[serde(default)]
     pub exclude: Vec<String>,
 
-    /// Patterns to include
+    /// Patterns to include (same rule syntax as `exclude`)
     #[serde(default)]
     pub include: Vec<String>,
 
@@ -89,6 +90,14 @@ pub struct LensConfig {
     /// Fallback config for files matching no groups (v1.7.0)
     #[serde(default)]
     pub fallback: Option<FallbackConfig>,
+
+    /// Per-language truncation overrides, keyed by language name
+    #[serde(default)]
+    pub languages: HashMap<String, LanguageTruncation>,
+
+    /// Declaration-level rules, evaluated against the parsed files (ordered, first match wins)
+    #[serde(default)]
+    pub symbols: Vec<SymbolRule>,
 }
 
 
@@ -161,6 +170,13 @@ impl LensManager {
                 PriorityGroup { pattern: "*.jsx".to_string(), priority: 55, truncate_mode: None, truncate: None },
             ],
             fallback: Some(FallbackConfig { priority: 50 }),
+            languages: HashMap::from([
+                // Configuration and schema files are already "structure"
+                ("toml".to_string(), LanguageTruncation::never()),
+                ("sql".to_string(), LanguageTruncation::never()),
+                ("python".to_string(), LanguageTruncation::default().with_keep_docstrings(true)),
+            ]),
+            symbols: Vec::new(),
         });
 
         // Debug lens - recent changes with full content
@@ -185,6 +201,8 @@ impl LensManager {
                 PriorityGroup { pattern: "test/**".to_string(), priority: 85, truncate_mode: None, truncate: None },
             ],
             fallback: Some(FallbackConfig { priority: 50 }),
+            languages: HashMap::new(),
+            symbols: Vec::new(),
         });
 
         // Security lens - focuses on auth, secrets, and dependencies
@@ -220,6 +238,8 @@ impl LensManager {
                 PriorityGroup { pattern: "Dockerfile".to_string(), priority: 75, truncate_mode: None, truncate: None },
             ],
             fallback: Some(FallbackConfig { priority: 50 }),
+            languages: HashMap::new(),
+            symbols: Vec::new(),
         });
 
         // Onboarding lens
@@ -239,6 +259,8 @@ impl LensManager {
             sort_order: None,
             groups: Vec::new(),
             fallback: None,
+            languages: HashMap::new(),
+            symbols: Vec::new(),
         });
 
         Self {
@@ -324,6 +346,8 @@ impl LensManager {
             sort_order: lens.sort_order.unwrap_or_else(|| "asc".to_string()),
             truncate_lines: lens.truncate.unwrap_or(0),
             truncate_mode: lens.truncate_mode.unwrap_or_else(|| "simple".to_string()),
+            language_rules: lens.languages,
+            symbol_rules: lens.symbols,
         })
     }
 
@@ -353,6 +377,9 @@ impl LensManager {
             if !lens.exclude.is_empty() {
                 eprintln!("║ Exclude Patterns: {:<44} ║", lens.exclude.len());
             }
+            if !lens.symbols.is_empty() {
+                eprintln!("║ Symbol Rules: {:<48} ║", lens.symbols.len());
+            }
 
             eprintln!("╚════════════════════════════════════════════════════════════════╝");
         }
@@ -598,6 +625,8 @@ pub struct AppliedLens {
     pub sort_order: String,
     pub truncate_lines: usize,
     pub truncate_mode: String,
+    pub language_rules: HashMap<String, LanguageTruncation>,
+    pub symbol_rules: Vec<SymbolRule>,
 }
 
 #[cfg(test)]
@@ -969,6 +998,8 @@ mod tests {
             truncate_mode: None,
             groups: vec![],
             fallback: None,
+            languages: HashMap::new(),
+            symbols: Vec::new(),
         };
 
         manager.custom.insert("minimal".to_string(), minimal_lens);
@@ -982,6 +1013,46 @@ mod tests {
         assert_eq!(applied.truncate_lines, 0); // Default
     }
 
+    #[test]
+    fn test_lens_language_rules_deserialize() {
+        let json = r#"{
+            "description": "Generated code",
+            "languages": {
+                "typescript": {"truncate": 20, "truncate_mode": "simple"},
+                "sql": {"never_truncate": true}
+            }
+        }"#;
+        let lens: LensConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(lens.languages["typescript"].truncate, Some(20));
+        assert!(lens.languages["sql"].never_truncate);
+
+        let mut manager = LensManager::new();
+        manager.load_custom(HashMap::from([("generated".to_string(), lens)]));
+        let applied = manager.apply_lens("generated").unwrap();
+        assert_eq!(applied.language_rules.len(), 2);
+    }
+
+    #[test]
+    fn test_lens_symbol_rules_deserialize() {
+        use crate::core::models::SymbolAction;
+
+        let json = r#"{
+            "description": "Models without tests",
+            "symbols": [
+                {"action": "exclude", "tests": true},
+                {"action": "signature", "kinds": ["function"], "min_lines": 400}
+            ]
+        }"#;
+        let lens: LensConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(lens.symbols[0].action, SymbolAction::Exclude);
+        assert_eq!(lens.symbols[0].tests, Some(true));
+        assert_eq!(lens.symbols[1].min_lines, Some(400));
+
+        let mut manager = LensManager::new();
+        manager.load_custom(HashMap::from([("models".to_string(), lens)]));
+        assert_eq!(manager.apply_lens("models").unwrap().symbol_rules.len(), 2);
+    }
+
     #[test]
     fn test_apply_lens_nonexistent() {
         // Test apply_lens with non-existent lens name
@@ -1103,6 +1174,8 @@ mod tests {
             truncate_mode: Some("smart".to_string()),
             groups: vec![],
             fallback: None,
+            languages: HashMap::new(),
+            symbols: Vec::new(),
         };
 
         manager.custom.insert("full".to_string(), lens);
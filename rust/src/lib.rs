@@ -19,7 +19,7 @@ use std::fs;
 use std::path::Path;
 use std::time::SystemTime;
 use serde::{Deserialize, Serialize};
-use globset::Glob;
+use voyager_ast::{extended_length_path, PatternRules, DEFAULT_CASE_INSENSITIVE};
 use walkdir::WalkDir;
 
 pub mod analyzers;
@@ -27,12 +27,16 @@ pub mod budgeting;
 pub mod core;
 pub mod formats;
 pub mod init;
+pub mod jsonrpc;
 pub mod lenses;
 pub mod plugins;
 pub mod server;
 
 pub use lenses::{LensManager, LensConfig, AppliedLens};
-pub use budgeting::{TokenEstimator, BudgetReport, parse_token_budget, apply_token_budget, FileData};
+pub use budgeting::{
+    TokenEstimator, BudgetReport, parse_token_budget, apply_token_budget, apply_token_budget_with, selection_order, FileData,
+    FilePrioritizer, PrioritizerRegistry, PriorityInput, PathBoost,
+};
 pub use formats::{XmlWriter, XmlConfig, XmlError, AttentionEntry, escape_cdata};
 
 // Re-export core types for backwards compatibility
@@ -77,6 +81,21 @@ pub struct Config {
     /// Patterns to include (overrides ignore)
     #[serde(default)]
     pub include_patterns: Vec<String>,
+    /// Boilerplate collapsing: enables it and extends the default dictionaries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub boilerplate: Option<core::BoilerplateExtension>,
+    /// MCP server: zoom the affordances of served contexts in the background
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefetch: Option<server::prefetch::PrefetchConfig>,
+    /// MCP server: named roots served side by side
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub workspaces: std::collections::BTreeMap<String, server::workspaces::WorkspaceConfig>,
+    /// Per-language truncation overrides (`{"sql": {"never_truncate": true}}`)
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub languages: std::collections::HashMap<String, core::LanguageTruncation>,
+    /// Match ignore/include patterns ignoring case (default: on Windows only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case_insensitive_paths: Option<bool>,
 }
 
 
@@ -123,8 +142,11 @@ pub struct EncoderConfig {
     pub sort_order: String,
     /// Maximum lines before truncation (0 = no truncation)
     pub truncate_lines: usize,
-    /// Truncation mode: "simple", "smart", or "structure"
+    /// Truncation mode: "simple", "smart", "structure", or "window"
     pub truncate_mode: String,
+    /// Per-language overrides of `truncate_lines`/`truncate_mode`, keyed by
+    /// language name ("python", "sql", ...); see [`truncation_for`]
+    pub language_rules: std::collections::HashMap<String, core::LanguageTruncation>,
     /// Maximum file size in bytes (default: 5MB)
     pub max_file_size: u64,
     /// Enable streaming mode (immediate output, no global sort)
@@ -145,12 +167,38 @@ pub struct EncoderConfig {
     pub active_lens: Option<String>,
     /// Token budget for metadata injection (v2.0.0)
     pub token_budget: Option<usize>,
+    /// Per-symbol token budget: function bodies above it are summarized
+    /// (see [`serialize_project_with_summarizer`])
+    pub symbol_budget: Option<usize>,
     /// Skeleton mode: 'auto', 'true', or 'false' (v2.2.0)
     pub skeleton_mode: SkeletonMode,
     /// Metadata display mode: 'auto', 'all', 'none', 'size-only' (v2.3.0 Chronos)
     pub metadata_mode: MetadataMode,
     /// Follow symbolic links (default: false, skip broken symlinks silently)
     pub follow_symlinks: bool,
+    /// Serialize Markdown files at this heading depth, collapsing deeper sections
+    pub outline_depth: Option<usize>,
+    /// Move simple truncation back to the nearest line that leaves no string or block open
+    pub safe_cuts: bool,
+    /// Append a machine-readable index of all zoom affordances to the output
+    pub affordance_index: bool,
+    /// Read this git view (commit, index, tracked worktree) instead of the directory
+    pub git_view: Option<core::GitView>,
+    /// Replace each file's import block with a one-line std/internal/external summary
+    pub import_rollup: bool,
+    /// Collapse license headers, import preambles and generated banners into markers
+    pub strip_boilerplate: bool,
+    /// Open the context with a table of contents of files, declarations and their anchors
+    /// (not in stdout streaming, which writes files before all are known)
+    pub table_of_contents: bool,
+    /// Open generated and vendored files with a note on where to edit instead
+    pub provenance_annotations: bool,
+    /// What counts as boilerplate (defaults plus config-file extensions)
+    pub boilerplate: core::BoilerplateRules,
+    /// Configuration warnings for the context header (see [`preflight`])
+    pub warnings: Vec<core::ConfigWarning>,
+    /// Match ignore/include patterns ignoring case
+    pub case_insensitive_paths: bool,
 }
 
 impl Default for EncoderConfig {
@@ -170,6 +218,7 @@ impl Default for EncoderConfig {
             sort_order: "asc".to_string(),
             truncate_lines: 0,
             truncate_mode: "simple".to_string(),
+            language_rules: std::collections::HashMap::new(), // Every language truncates alike by default
             max_file_size: 5 * 1024 * 1024, // 5MB
             stream: false, // Default to batch mode for backward compatibility
             truncate_summary: true, // Include summary markers by default
@@ -180,9 +229,21 @@ impl Default for EncoderConfig {
             allow_sensitive: false, // Default to privacy-safe mode
             active_lens: None, // No lens by default
             token_budget: None, // No budget by default
+            symbol_budget: None, // Symbol bodies are kept whole by default
             skeleton_mode: SkeletonMode::Auto, // Auto-enable if budget is set
             metadata_mode: MetadataMode::Auto, // Smart metadata display (v2.3.0)
             follow_symlinks: false, // Skip broken symlinks silently by default
+            outline_depth: None, // Markdown is serialized in full by default
+            safe_cuts: false, // Cut exactly at truncate_lines by default
+            affordance_index: false, // Affordances are inline comments only by default
+            git_view: None, // Read the directory by default
+            import_rollup: false, // List every import line by default
+            strip_boilerplate: false, // Keep headers and imports verbatim by default
+            table_of_contents: false, // Files start the context by default
+            provenance_annotations: false, // Generated and vendored files are not marked by default
+            boilerplate: core::BoilerplateRules::default(),
+            warnings: vec![], // Found by preflight checks
+            case_insensitive_paths: DEFAULT_CASE_INSENSITIVE, // Case-insensitive on Windows only
         }
     }
 }
@@ -196,10 +257,19 @@ impl EncoderConfig {
         let config: Config = serde_json::from_str(&content)
             .map_err(|e| format!("Failed to parse config file: {}", e))?;
 
+        let mut boilerplate = core::BoilerplateRules::default();
+        if let Some(extension) = &config.boilerplate {
+            boilerplate.extend(extension);
+        }
+
         Ok(Self {
             ignore_patterns: config.ignore_patterns,
             include_patterns: config.include_patterns,
+            language_rules: config.languages.into_iter().map(|(language, rule)| (language.to_lowercase(), rule)).collect(),
             stream: false, // Streaming is only enabled via CLI flag
+            strip_boilerplate: config.boilerplate.as_ref().is_some_and(|b| b.enabled),
+            boilerplate,
+            case_insensitive_paths: config.case_insensitive_paths.unwrap_or(DEFAULT_CASE_INSENSITIVE),
             ..Default::default()
         })
     }
@@ -306,6 +376,12 @@ impl ContextEngine {
                     path,
                     self.config.truncate_summary,
                 ),
+                "window" => truncate_window_with_options(
+                    content,
+                    self.config.truncate_lines,
+                    path,
+                    self.config.truncate_summary,
+                ),
                 "structure" => truncate_structure_with_fallback(
                     content,
                     path,
@@ -515,6 +591,14 @@ impl ContextEngine {
 
     /// Generate Claude-XML header with metadata
     fn generate_claude_xml_header(&self, files: &[ProcessedFile]) -> String {
+        let utilized: usize = files.iter()
+            .map(|f| f.content.len() / 4) // Rough token estimate
+            .sum();
+        self.claude_xml_header(&files[..files.len().min(ATTENTION_HOTSPOTS)], utilized)
+    }
+
+    /// Claude-XML header from the leading hotspot files and the total token estimate
+    fn claude_xml_header(&self, hotspots: &[ProcessedFile], utilized: usize) -> String {
         let mut header = String::new();
 
         // Context root with attributes
@@ -526,9 +610,6 @@ impl ContextEngine {
         }
 
         if let Some(budget) = self.config.token_budget {
-            let utilized: usize = files.iter()
-                .map(|f| f.content.len() / 4) // Rough token estimate
-                .sum();
             header.push_str(&format!("  token_budget=\"{}\"\n", budget));
             header.push_str(&format!("  utilized=\"{}\"\n", utilized));
         }
@@ -553,9 +634,9 @@ impl ContextEngine {
         }
 
         // Attention map with file priorities
-        if !files.is_empty() {
+        if !hotspots.is_empty() {
             header.push_str("    <attention_map>\n");
-            for file in files.iter().take(5) {
+            for file in hotspots {
                 let priority = self.lens_manager.get_file_priority(Path::new(&file.path));
                 let tokens = file.content.len() / 4;
                 if file.was_truncated {
@@ -605,11 +686,54 @@ impl ContextEngine {
         // Serialize
         self.serialize_processed_files(&sorted)
     }
+
+    /// Stream the complete context to a writer (no I/O beyond `out`)
+    ///
+    /// Same output as [`generate_context`](Self::generate_context), but each
+    /// file is processed, serialized and written in turn instead of being
+    /// collected into one String, so memory beyond the inputs stays near the
+    /// largest file. Claude-XML processes files twice: once for the header
+    /// summary, once for output.
+    pub fn write_context<W: std::io::Write>(&self, files: &[(String, String)], out: W) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut order: Vec<&(String, String)> = files.iter().collect();
+        order.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut out = std::io::BufWriter::with_capacity(STREAM_BUFFER_SIZE, out);
+        let claude_xml = self.config.output_format == OutputFormat::ClaudeXml;
+
+        if claude_xml {
+            let mut hotspots = Vec::new();
+            let mut utilized = 0;
+            for (path, content) in &order {
+                let processed = self.process_file_content(path, content);
+                utilized += processed.content.len() / 4;
+                if hotspots.len() < ATTENTION_HOTSPOTS {
+                    hotspots.push(processed);
+                }
+            }
+            out.write_all(self.claude_xml_header(&hotspots, utilized).as_bytes())?;
+        }
+
+        for (path, content) in order {
+            let processed = self.process_file_content(path, content);
+            out.write_all(self.serialize_processed_file(&processed).as_bytes())?;
+        }
+
+        if claude_xml {
+            out.write_all(b"  </files>\n</context>\n")?;
+        }
+        out.flush()
+    }
 }
 
 /// Version of the pm_encoder library
 pub const VERSION: &str = "1.0.0";
 
+/// Files listed in the Claude-XML `<attention_map>` of [`ContextEngine`] output
+const ATTENTION_HOTSPOTS: usize = 5;
+
 /// Returns the version of the pm_encoder library
 pub fn version() -> &'static str {
     VERSION
@@ -767,7 +891,12 @@ pub fn read_file_content(bytes: &[u8]) -> Option<String> {
     Some(content.replace("\r\n", "\n").replace('\r', "\n"))
 }
 
-/// Check if a path matches any of the given glob patterns
+/// Check if a path matches the given patterns
+///
+/// Patterns are ordered, first-match-wins rules shared with the core walker,
+/// lenses and voyager-ast (see [`PatternRules`]): `!pattern` negates, a
+/// trailing `/` matches directories only, and patterns without a `/` match
+/// any path component.
 ///
 /// # Arguments
 ///
@@ -776,47 +905,18 @@ pub fn read_file_content(bytes: &[u8]) -> Option<String> {
 ///
 /// # Returns
 ///
-/// * `true` if path matches any pattern, `false` otherwise
+/// * `true` if the first matching pattern is not a negation, `false` otherwise
 fn matches_patterns(path: &str, patterns: &[String]) -> bool {
-    for pattern_str in patterns {
-        // Try to compile the pattern
-        if let Ok(glob) = Glob::new(pattern_str) {
-            let matcher = glob.compile_matcher();
-
-            // Match against the full path
-            if matcher.is_match(path) {
-                return true;
-            }
-
-            // Also check if any path component or parent path matches
-            // This handles patterns like ".git" matching ".git/config"
-            let parts: Vec<&str> = path.split('/').collect();
-            for i in 0..parts.len() {
-                let component = parts[i];
-                // Check individual component
-                if matcher.is_match(component) {
-                    return true;
-                }
-                // Check partial paths (e.g., ".git" for ".git/config")
-                if i > 0 {
-                    let partial = parts[..=i].join("/");
-                    if matcher.is_match(&partial) {
-                        return true;
-                    }
-                }
-            }
-        }
-    }
-    false
+    PatternRules::new(patterns).is_match(path, false)
 }
 
-/// Determine if a file should be included based on ignore/include patterns
+/// Determine if a file should be included based on ignore/include rules
 ///
 /// # Arguments
 ///
 /// * `path` - Relative path to check
-/// * `ignore_patterns` - Patterns to ignore
-/// * `include_patterns` - Patterns to include
+/// * `ignore_rules` - Rules to ignore
+/// * `include_rules` - Rules to include
 ///
 /// # Returns
 ///
@@ -824,27 +924,28 @@ fn matches_patterns(path: &str, patterns: &[String]) -> bool {
 ///
 /// # Logic (matches Python behavior)
 ///
-/// 1. Check ignore patterns FIRST - if match, EXCLUDE (no override by includes)
-/// 2. Pure whitelist mode: if include_patterns exist AND ignore_patterns is empty,
-///    file must match at least one include pattern
-/// 3. Hybrid mode: if both exist, file just needs to NOT match ignore patterns
+/// 1. Check ignore rules FIRST - if matched, EXCLUDE (no override by includes;
+///    use a `!pattern` ignore rule to carve out exceptions)
+/// 2. Pure whitelist mode: if include rules exist AND ignore rules are empty,
+///    file must match the include rules
+/// 3. Hybrid mode: if both exist, file just needs to NOT match ignore rules
 ///    (include patterns don't act as a filter, they're for explicit inclusion of ignored items)
 /// 4. If no patterns or only ignore patterns, include by default (if not ignored)
 fn should_include_file(
     path: &str,
-    ignore_patterns: &[String],
-    include_patterns: &[String],
+    ignore_rules: &PatternRules,
+    include_rules: &PatternRules,
 ) -> bool {
-    // Check ignore patterns FIRST (they take precedence over includes)
+    // Check ignore rules FIRST (they take precedence over includes)
     // This matches Python behavior where directory-level ignores can't be overridden
-    if matches_patterns(path, ignore_patterns) {
+    if ignore_rules.is_match(path, false) {
         return false;  // Ignored paths are always excluded
     }
 
-    // Pure whitelist mode: only when include_patterns exist AND no ignore_patterns
-    // In this mode, files must match at least one include pattern
-    if !include_patterns.is_empty() && ignore_patterns.is_empty() {
-        return matches_patterns(path, include_patterns);
+    // Pure whitelist mode: only when include rules exist AND no ignore rules
+    // In this mode, files must match the include rules
+    if !include_rules.is_empty() && ignore_rules.is_empty() {
+        return include_rules.is_match(path, false);
     }
 
     // Hybrid mode (both patterns) or blacklist mode (only ignore):
@@ -875,10 +976,25 @@ pub fn walk_directory_iter(
     ignore_patterns: Vec<String>,
     include_patterns: Vec<String>,
     max_size: u64,
+) -> impl Iterator<Item = FileEntry> {
+    walk_directory_iter_with_case(root, ignore_patterns, include_patterns, max_size, DEFAULT_CASE_INSENSITIVE)
+}
+
+/// [`walk_directory_iter`], matching patterns ignoring case when `case_insensitive` is set
+pub fn walk_directory_iter_with_case(
+    root: &str,
+    ignore_patterns: Vec<String>,
+    include_patterns: Vec<String>,
+    max_size: u64,
+    case_insensitive: bool,
 ) -> impl Iterator<Item = FileEntry> {
     let root_path = Path::new(root).to_path_buf();
     let root_path_clone = root_path.clone();
-    let ignore_patterns_clone = ignore_patterns.clone();
+    let ignore_rules = PatternRules::with_case(&ignore_patterns, case_insensitive);
+    let include_rules = PatternRules::with_case(&include_patterns, case_insensitive);
+    let ignore_rules_clone = ignore_rules.clone();
+    let paths = core::paths::ProjectPaths::new(&root_path);
+    let filter_paths = paths.clone();
 
     // Create walker with directory pruning via filter_entry
     // filter_entry is called BEFORE descending into a directory
@@ -901,23 +1017,18 @@ pub fn walk_directory_iter(
                 return false;
             }
 
-            // Get relative path for pattern matching
-            let rel_path = match path.strip_prefix(&root_path_clone) {
-                Ok(p) => p,
-                Err(_) => return false,
-            };
-
-            let path_str = match rel_path.to_str() {
-                Some(s) => s,
-                None => return false,
-            };
+            // Get relative path for pattern matching (lossless for non-UTF-8 names)
+            if path.strip_prefix(&root_path_clone).is_err() {
+                return false;
+            }
+            let path_str = filter_paths.relative(path);
 
             // For directories: check if directory should be pruned (ignored)
             // This prevents entering .git, .llm_archive, node_modules, etc.
             if entry.file_type().is_dir() {
-                // Check if this directory matches any ignore pattern
+                // Check if this directory is ignored (and nothing below it is re-included)
                 // If so, skip the entire tree by returning false
-                !matches_patterns(path_str, &ignore_patterns_clone)
+                !ignore_rules_clone.can_prune(&path_str)
             } else {
                 // For files: always return true here, we'll filter later
                 // (filter_entry affects directory traversal, not file inclusion)
@@ -952,13 +1063,16 @@ pub fn walk_directory_iter(
             let path = entry.path();
 
             // Get relative path for pattern matching and output
-            let rel_path = path.strip_prefix(&root_path).ok()?;
-            let path_str = rel_path.to_str()?;
+            if path.strip_prefix(&root_path).is_err() {
+                return None;
+            }
+            // Forward-slash relative path key, lossless for non-UTF-8 names
+            let path_str = paths.relative(path);
 
             // Check if this file should be included based on patterns
             // Note: ignore patterns already handled by filter_entry for directories,
             // but we still need to check file-level ignores and include patterns
-            if !should_include_file(path_str, &ignore_patterns, &include_patterns) {
+            if !should_include_file(&path_str, &ignore_rules, &include_rules) {
                 return None;
             }
 
@@ -986,7 +1100,7 @@ pub fn walk_directory_iter(
                 .unwrap_or(mtime);
 
             // Read file content (bytes first, then decode)
-            let buffer = fs::read(path).ok()?;
+            let buffer = fs::read(extended_length_path(path)).ok()?;
 
             // Use read_file_content helper (handles binary detection + encoding)
             let content = read_file_content(&buffer)?;
@@ -995,7 +1109,7 @@ pub fn walk_directory_iter(
             let md5 = calculate_md5(&content);
 
             Some(FileEntry {
-                path: path_str.to_string(),
+                path: path_str,
                 size: content.len() as u64,
                 content,
                 md5,
@@ -1029,6 +1143,17 @@ pub fn walk_directory(
     ignore_patterns: &[String],
     include_patterns: &[String],
     max_size: u64,
+) -> Result<Vec<FileEntry>, String> {
+    walk_directory_with_case(root, ignore_patterns, include_patterns, max_size, DEFAULT_CASE_INSENSITIVE)
+}
+
+/// [`walk_directory`], matching patterns ignoring case when `case_insensitive` is set
+pub fn walk_directory_with_case(
+    root: &str,
+    ignore_patterns: &[String],
+    include_patterns: &[String],
+    max_size: u64,
+    case_insensitive: bool,
 ) -> Result<Vec<FileEntry>, String> {
     let root_path = Path::new(root);
     if !root_path.exists() {
@@ -1036,16 +1161,63 @@ pub fn walk_directory(
     }
 
     // Use the iterator version and collect into Vec
-    let entries: Vec<FileEntry> = walk_directory_iter(
+    let entries: Vec<FileEntry> = walk_directory_iter_with_case(
         root,
         ignore_patterns.to_vec(),
         include_patterns.to_vec(),
         max_size,
+        case_insensitive,
     ).collect();
 
     Ok(entries)
 }
 
+/// Walk the directory, or `config.git_view` when it is set
+///
+/// Git views are read through the index and object database without a
+/// checkout (requires the `temporal` feature).
+pub fn walk_project(root: &str, config: &EncoderConfig) -> Result<Vec<FileEntry>, String> {
+    match &config.git_view {
+        None => walk_directory_with_case(
+            root,
+            &config.ignore_patterns,
+            &config.include_patterns,
+            config.max_file_size,
+            config.case_insensitive_paths,
+        ),
+        #[cfg(feature = "temporal")]
+        Some(view) => {
+            use crate::core::FileWalker;
+
+            let walk_config = crate::core::walker::WalkConfig {
+                ignore_patterns: config.ignore_patterns.clone(),
+                include_patterns: config.include_patterns.clone(),
+                max_file_size: config.max_file_size,
+                case_insensitive: config.case_insensitive_paths,
+            };
+            let entries = crate::core::GitWalker::with_view(view.clone())
+                .walk(root, &walk_config)
+                .map_err(|e| e.to_string())?;
+            Ok(entries
+                .into_iter()
+                .map(|e| FileEntry {
+                    path: e.path,
+                    content: e.content,
+                    md5: e.md5,
+                    size: e.size,
+                    mtime: e.mtime,
+                    ctime: e.ctime,
+                })
+                .collect())
+        }
+        #[cfg(not(feature = "temporal"))]
+        Some(view) => Err(format!(
+            "Reading the {} requires a build with the `temporal` feature",
+            view
+        )),
+    }
+}
+
 /// Truncate content to a maximum number of lines (simple mode)
 ///
 /// # Arguments
@@ -1425,6 +1597,62 @@ pub fn truncate_smart_with_options(
     truncate_with_gap_markers(content, max_lines, file_path, include_summary, None)
 }
 
+/// Truncate content using window mode (AST-aware sliding window)
+///
+/// Window mode keeps every function's signature, first statements, exit/error
+/// branches, and last statements, eliding the middle of long bodies.
+pub fn truncate_window(content: &str, max_lines: usize, file_path: &str) -> (String, bool) {
+    truncate_window_with_options(content, max_lines, file_path, true)
+}
+
+/// Truncate content using window mode with options
+///
+/// # Arguments
+///
+/// * `content` - The content to truncate
+/// * `max_lines` - Files at or below this many lines are left untouched
+/// * `file_path` - File path for the truncation marker
+/// * `include_summary` - Whether to include the summary marker
+///
+/// # Returns
+///
+/// * `(truncated_content, was_truncated)` - The truncated content and whether truncation occurred
+///
+/// Falls back to smart mode for languages without an AST adapter or when no
+/// function body is long enough to window.
+pub fn truncate_window_with_options(
+    content: &str,
+    max_lines: usize,
+    file_path: &str,
+    include_summary: bool,
+) -> (String, bool) {
+    let total_lines = python_style_split(content).len();
+
+    if max_lines == 0 || total_lines <= max_lines {
+        return (content.to_string(), false);
+    }
+
+    match core::window::WindowTruncator::new().truncate(content, file_path) {
+        Some(result) if result.was_truncated() => {
+            let mut truncated = result.content;
+            if include_summary {
+                let marker = format!(
+                    "\n\n{}\nWINDOW TRUNCATED: {} lines elided across {} function bodies\nTo get full content: --include \"{}\" --truncate 0\n/* ZOOM_AFFORDANCE: pm_encoder --zoom file={} */\n{}\n",
+                    "=".repeat(70),
+                    result.elided_lines,
+                    result.windowed_bodies,
+                    file_path,
+                    file_path,
+                    "=".repeat(70)
+                );
+                truncated.push_str(&marker);
+            }
+            (truncated, true)
+        }
+        _ => truncate_smart_with_options(content, max_lines, file_path, include_summary),
+    }
+}
+
 /// Truncate content using structure mode (signatures only)
 ///
 /// Structure mode extracts only class/function signatures, removing all bodies.
@@ -1704,23 +1932,7 @@ pub fn serialize_file_with_format_and_metadata(
     let original_lines = count_lines_python_style(&entry.content);
 
     // Apply truncation and track if file was truncated
-    let (content, was_truncated) = if truncate_lines > 0 || truncate_mode == "structure" {
-        match truncate_mode {
-            "simple" => {
-                truncate_simple(&entry.content, truncate_lines, &entry.path)
-            }
-            "smart" => {
-                truncate_smart(&entry.content, truncate_lines, &entry.path)
-            }
-            "structure" => {
-                // Use fallback version that falls back to smart mode when no signatures (Python behavior)
-                truncate_structure_with_fallback(&entry.content, &entry.path, true, truncate_lines)
-            }
-            _ => (entry.content.clone(), false),
-        }
-    } else {
-        (entry.content.clone(), false)
-    };
+    let (content, was_truncated) = truncate_entry(entry, truncate_lines, truncate_mode);
 
     let final_lines = count_lines_python_style(&content);
 
@@ -1732,6 +1944,21 @@ pub fn serialize_file_with_format_and_metadata(
     }
 }
 
+/// Content of an entry as serialized, and whether it was truncated
+pub fn truncate_entry(entry: &FileEntry, truncate_lines: usize, truncate_mode: &str) -> (String, bool) {
+    if truncate_lines == 0 && truncate_mode != "structure" {
+        return (entry.content.clone(), false);
+    }
+    match truncate_mode {
+        "simple" => truncate_simple(&entry.content, truncate_lines, &entry.path),
+        "smart" => truncate_smart(&entry.content, truncate_lines, &entry.path),
+        "window" => truncate_window(&entry.content, truncate_lines, &entry.path),
+        // Falls back to smart mode when there are no signatures (Python behavior)
+        "structure" => truncate_structure_with_fallback(&entry.content, &entry.path, true, truncate_lines),
+        _ => (entry.content.clone(), false),
+    }
+}
+
 /// Serialize to Plus/Minus format (with Chronos metadata support)
 fn serialize_plus_minus_entry(path: &str, content: &str, md5: &str, size: u64, mtime: u64, was_truncated: bool, original_lines: usize, final_lines: usize, metadata_mode: MetadataMode) -> String {
     use core::serialization::format_metadata_suffix;
@@ -1955,70 +2182,382 @@ pub fn serialize_project_with_config(
     root: &str,
     config: &EncoderConfig,
 ) -> Result<String, String> {
+    serialize_project_with_warnings(root, config).map(|(output, _)| output)
+}
+
+/// Serialize a project, also returning the [`preflight`] warnings it found
+///
+/// The warnings are already in the context header (next to any in
+/// `config.warnings`); they are returned so the caller can report them.
+pub fn serialize_project_with_warnings(
+    root: &str,
+    config: &EncoderConfig,
+) -> Result<(String, Vec<core::ConfigWarning>), String> {
+    serialize_project_with_summarizer(root, config, &core::ExtractiveSummarizer::new())
+}
+
+/// Serialize a project, summarizing bodies over `config.symbol_budget` with `summarizer`
+///
+/// [`serialize_project_with_warnings`] uses the [`core::ExtractiveSummarizer`];
+/// this is the hook for plugging in another (e.g. LLM-backed) one.
+pub fn serialize_project_with_summarizer(
+    root: &str,
+    config: &EncoderConfig,
+    summarizer: &dyn core::Summarizer,
+) -> Result<(String, Vec<core::ConfigWarning>), String> {
     // Streaming mode: use iterator, write directly, return empty string
-    if config.stream {
-        return serialize_project_streaming(root, config);
+    // (git views are read through the index in batch mode)
+    if config.stream && config.git_view.is_none() {
+        return serialize_project_streaming(root, config).map(|output| (output, Vec::new()));
     }
 
     // Batch mode: collect, sort, return complete string
-    let entries = walk_directory(
-        root,
-        &config.ignore_patterns,
-        &config.include_patterns,
-        config.max_file_size,
-    )?;
+    serialize_walked_entries(walk_project(root, config)?, config, summarizer)
+}
+
+/// Serialize entries from [`walk_project`] like [`serialize_project_with_summarizer`]
+///
+/// For callers that also need the walked files themselves (bundles, the
+/// empty-selection check), so the project is walked only once.
+pub fn serialize_walked_entries(
+    mut sorted_entries: Vec<FileEntry>,
+    config: &EncoderConfig,
+    summarizer: &dyn core::Summarizer,
+) -> Result<(String, Vec<core::ConfigWarning>), String> {
+    sort_entries(&mut sorted_entries, config);
+
+    let found = preflight(config, &config.include_patterns, &sorted_entries);
+    let warnings: Vec<core::ConfigWarning> = config.warnings.iter().chain(&found).cloned().collect();
 
-    // Sort entries based on config
-    let mut sorted_entries = entries;
+    prepare_entries(&mut sorted_entries, config, summarizer);
+    prepend_tour(&mut sorted_entries, config);
+    prepend_table_of_contents(&mut sorted_entries, config);
+    prepend_config_warnings(&mut sorted_entries, &warnings, config.output_format);
+
+    // Use streaming XmlWriter for ClaudeXml format (Phase 2 refactor)
+    if config.output_format == OutputFormat::ClaudeXml {
+        return write_entries_claude_xml(config, &sorted_entries, &warnings)
+            .map(|output| (append_affordance_index(output, config, &sorted_entries), found));
+    }
+
+    // Serialize each file entry with optional truncation and format (non-XML formats)
+    let mut output = String::new();
+
+    for entry in &sorted_entries {
+        let (truncate_lines, truncate_mode) = truncation_for(entry, config);
+        output.push_str(&serialize_file_with_format_and_metadata(
+            entry,
+            truncate_lines,
+            truncate_mode,
+            config.output_format,
+            config.metadata_mode,
+        ));
+    }
+
+    Ok((append_affordance_index(output, config, &sorted_entries), found))
+}
+
+/// Sort entries by `config.sort_by` / `config.sort_order` (name ascending by default)
+pub fn sort_entries(entries: &mut [FileEntry], config: &EncoderConfig) {
     let is_desc = config.sort_order == "desc";
 
     match config.sort_by.as_str() {
         "name" => {
             if is_desc {
-                sorted_entries.sort_by(|a, b| b.path.cmp(&a.path));
+                entries.sort_by(|a, b| b.path.cmp(&a.path));
             } else {
-                sorted_entries.sort_by(|a, b| a.path.cmp(&b.path));
+                entries.sort_by(|a, b| a.path.cmp(&b.path));
             }
         }
         "mtime" => {
             if is_desc {
-                sorted_entries.sort_by(|a, b| b.mtime.cmp(&a.mtime));
+                entries.sort_by(|a, b| b.mtime.cmp(&a.mtime));
             } else {
-                sorted_entries.sort_by(|a, b| a.mtime.cmp(&b.mtime));
+                entries.sort_by(|a, b| a.mtime.cmp(&b.mtime));
             }
         }
         "ctime" => {
             if is_desc {
-                sorted_entries.sort_by(|a, b| b.ctime.cmp(&a.ctime));
+                entries.sort_by(|a, b| b.ctime.cmp(&a.ctime));
             } else {
-                sorted_entries.sort_by(|a, b| a.ctime.cmp(&b.ctime));
+                entries.sort_by(|a, b| a.ctime.cmp(&b.ctime));
             }
         }
         // Default to name sorting
         _ => {
-            sorted_entries.sort_by(|a, b| a.path.cmp(&b.path));
+            entries.sort_by(|a, b| a.path.cmp(&b.path));
         }
     }
+}
 
-    // Use streaming XmlWriter for ClaudeXml format (Phase 2 refactor)
-    if config.output_format == OutputFormat::ClaudeXml {
-        return serialize_entries_claude_xml(config, &sorted_entries);
+/// Rewrite walked entries into the content that will be serialized
+///
+/// Collapses Markdown below `outline_depth`, summarizes bodies over
+/// `symbol_budget`, rolls up imports, strips boilerplate and adds provenance
+/// notes, as configured. Budgeted runs call this before estimating tokens,
+/// so the budget sees the same content an unbudgeted run writes.
+pub fn prepare_entries(entries: &mut [FileEntry], config: &EncoderConfig, summarizer: &dyn core::Summarizer) {
+    // Outline depth: collapse Markdown sections below the requested heading level
+    for entry in entries.iter_mut() {
+        apply_outline_depth(entry, config);
+    }
+    summarize_entries(entries, config, summarizer);
+
+    // Import rollups: one summary line per file instead of its import block
+    if let Some(rollups) = import_rollups(config, entries) {
+        for entry in entries.iter_mut() {
+            apply_import_rollup(entry, &rollups);
+        }
     }
 
-    // Serialize each file entry with optional truncation and format (non-XML formats)
-    let mut output = String::new();
+    // Provenance: classified before boilerplate stripping collapses generated banners
+    let provenances: Vec<_> = entries.iter().map(|entry| file_provenance(entry, config)).collect();
 
-    for entry in sorted_entries {
-        output.push_str(&serialize_file_with_format_and_metadata(
-            &entry,
-            config.truncate_lines,
-            &config.truncate_mode,
-            config.output_format,
-            config.metadata_mode,
-        ));
+    // Boilerplate: collapse license headers and import preambles, counting repeats
+    if let Some(stripper) = boilerplate_stripper(config, entries) {
+        for entry in entries.iter_mut() {
+            apply_boilerplate(entry, &stripper);
+        }
     }
 
-    Ok(output)
+    for (entry, provenance) in entries.iter_mut().zip(provenances) {
+        if let Some(provenance) = provenance {
+            apply_provenance(entry, &provenance, config);
+        }
+    }
+}
+
+/// Collapse Markdown sections below `config.outline_depth`, if set
+fn apply_outline_depth(entry: &mut FileEntry, config: &EncoderConfig) {
+    let Some(depth) = config.outline_depth else { return };
+    if !core::outline::is_markdown(&entry.path) {
+        return;
+    }
+    if let Some(outlined) = core::outline::outline(&entry.path, &entry.content, depth) {
+        entry.size = outlined.len() as u64;
+        entry.content = outlined;
+    }
+}
+
+/// Replace function bodies over `config.symbol_budget` with summaries, if it is set
+///
+/// Files in languages without an AST adapter are left unchanged.
+fn summarize_entries(entries: &mut [FileEntry], config: &EncoderConfig, summarizer: &dyn core::Summarizer) {
+    for entry in entries.iter_mut() {
+        apply_symbol_budget(entry, config, summarizer);
+    }
+}
+
+/// Replace an entry's function bodies over `config.symbol_budget` with summaries
+fn apply_symbol_budget(entry: &mut FileEntry, config: &EncoderConfig, summarizer: &dyn core::Summarizer) {
+    let Some(budget) = config.symbol_budget else { return };
+    let summarized = core::summarize_symbols(summarizer, &entry.path, &entry.content, budget);
+    if summarized.is_summarized() {
+        entry.size = summarized.content.len() as u64;
+        entry.content = summarized.content;
+    }
+}
+
+/// Import rollups that know the project's modules, when `import_rollup` is set
+fn import_rollups(config: &EncoderConfig, entries: &[FileEntry]) -> Option<core::ImportRollups> {
+    config.import_rollup.then(|| {
+        core::ImportRollups::new(core::LocalModules::from_paths(entries.iter().map(|e| e.path.as_str())))
+    })
+}
+
+/// Replace an entry's import block with its rollup line
+fn apply_import_rollup(entry: &mut FileEntry, rollups: &core::ImportRollups) {
+    if let Some(rolled) = rollups.apply(&entry.path, &entry.content) {
+        entry.size = rolled.len() as u64;
+        entry.content = rolled;
+    }
+}
+
+/// Stripper that has observed `entries`, when `strip_boilerplate` is set
+fn boilerplate_stripper(config: &EncoderConfig, entries: &[FileEntry]) -> Option<core::BoilerplateStripper> {
+    if !config.strip_boilerplate {
+        return None;
+    }
+    let mut stripper = core::BoilerplateStripper::new(config.boilerplate.clone());
+    for entry in entries {
+        stripper.observe(&entry.path, &entry.content);
+    }
+    Some(stripper)
+}
+
+/// Collapse an entry's boilerplate into markers
+fn apply_boilerplate(entry: &mut FileEntry, stripper: &core::BoilerplateStripper) {
+    if let Some(stripped) = stripper.strip(&entry.path, &entry.content) {
+        entry.size = stripped.content.len() as u64;
+        entry.content = stripped.content;
+    }
+}
+
+/// Origin of a generated or vendored entry, when `provenance_annotations` is set
+fn file_provenance(entry: &FileEntry, config: &EncoderConfig) -> Option<core::Provenance> {
+    if !config.provenance_annotations {
+        return None;
+    }
+    core::Provenance::classify(&entry.path, &entry.content)
+}
+
+/// Open an entry with its provenance note, in its own comment syntax
+fn apply_provenance(entry: &mut FileEntry, provenance: &core::Provenance, config: &EncoderConfig) {
+    entry.content = provenance.annotate(&entry.path, &entry.content, &config.boilerplate);
+    entry.size = entry.content.len() as u64;
+}
+
+/// Append the affordance index section when `affordance_index` is set
+pub fn append_affordance_index(mut output: String, config: &EncoderConfig, entries: &[FileEntry]) -> String {
+    if config.affordance_index {
+        let mut index = core::AffordanceIndex::from_output(&output);
+        let files: Vec<(String, String)> = entries.iter().map(|e| (e.path.clone(), e.content.clone())).collect();
+        index.estimate_files(&files);
+        let xml = matches!(config.output_format, OutputFormat::Xml | OutputFormat::ClaudeXml);
+        output.push_str(&index.render_section(xml));
+    }
+    output
+}
+
+/// Line budget and truncation mode for an entry
+///
+/// The rule for the entry's language in `language_rules` overrides the global
+/// settings. Only simple truncation cuts at a fixed line, so only it is
+/// pulled back to a safe cut point (`safe_cuts`) or past the end of a doc
+/// block (`keep_docstrings`); the other modes choose their own boundaries.
+pub fn truncation_for<'a>(entry: &FileEntry, config: &'a EncoderConfig) -> (usize, &'a str) {
+    let rule = config.language_rules.get(&core::engine::detect_language(&entry.path));
+    let (mut lines, mode) = match rule {
+        Some(rule) => rule.resolve(config.truncate_lines, &config.truncate_mode),
+        None => (config.truncate_lines, config.truncate_mode.as_str()),
+    };
+    if mode == "simple" && lines > 0 {
+        if config.safe_cuts {
+            lines = core::selfcheck::safe_line_limit(&entry.path, &entry.content, lines);
+        }
+        if rule.is_some_and(|r| r.keep_docstrings) {
+            let content_lines: Vec<&str> = entry.content.lines().collect();
+            if content_lines.len() > lines {
+                lines = core::engine::docstring_safe_cutoff(&content_lines, lines);
+            }
+        }
+    }
+    (lines, mode)
+}
+
+/// Record in `report` the truncation serialization applies to each of `entries`
+///
+/// `--truncate` and per-language rules cut files after budgeting; `--preview`
+/// runs the same [`truncation_for`] decisions so it lists what is written.
+pub fn record_serialize_truncation(report: &mut BudgetReport, entries: &[FileEntry], config: &EncoderConfig) {
+    for entry in entries {
+        let (lines, mode) = truncation_for(entry, config);
+        let (content, truncated) = truncate_entry(entry, lines, mode);
+        if !truncated {
+            continue;
+        }
+        let tokens = TokenEstimator::estimate_file_tokens(Path::new(&entry.path), &content);
+        let reason = if lines > 0 {
+            format!("truncated to {} lines, {} mode", lines, mode)
+        } else {
+            format!("truncated, {} mode", mode)
+        };
+        report.record_truncation(&entry.path, tokens, reason);
+    }
+}
+
+/// Insert the synthetic `CONTENTS.md` entry first when `table_of_contents` is set
+///
+/// Files are listed as they will be serialized: truncated files with the
+/// declarations that survive truncation only.
+pub fn prepend_table_of_contents(entries: &mut Vec<FileEntry>, config: &EncoderConfig) {
+    if config.table_of_contents {
+        let toc = table_of_contents_entry(entries.iter(), config);
+        entries.insert(0, toc);
+    }
+}
+
+/// Build the synthetic `CONTENTS.md` entry listing `entries` in order
+fn table_of_contents_entry<E: std::borrow::Borrow<FileEntry>>(
+    entries: impl IntoIterator<Item = E>,
+    config: &EncoderConfig,
+) -> FileEntry {
+    let mut toc = core::TableOfContents::new();
+    for entry in entries {
+        let entry = entry.borrow();
+        let (truncate_lines, truncate_mode) = truncation_for(entry, config);
+        let (content, truncated) = truncate_entry(entry, truncate_lines, truncate_mode);
+        let depth = if truncated { format!("{} truncation", truncate_mode) } else { "full".to_string() };
+        toc.add_file(&entry.path, &content, &depth);
+    }
+    let content = toc.to_markdown();
+    FileEntry {
+        path: core::TOC_FILE_NAME.to_string(),
+        md5: calculate_md5(&content),
+        size: content.len() as u64,
+        content,
+        mtime: 0,
+        ctime: 0,
+    }
+}
+
+/// Check the configuration against the walked `entries`
+///
+/// Warns about `include_patterns` that match no entry and, when a token
+/// budget is set, a budget below the context overhead. The caller reports
+/// the returned warnings and adds them to the context header.
+pub fn preflight(config: &EncoderConfig, include_patterns: &[String], entries: &[FileEntry]) -> Vec<core::ConfigWarning> {
+    let paths: Vec<&str> = entries.iter().map(|e| e.path.as_str()).collect();
+    let mut warnings = core::preflight::check_include_patterns(include_patterns, &paths, config.case_insensitive_paths);
+    if let Some(budget) = config.token_budget {
+        warnings.extend(core::preflight::check_budget(budget, context_overhead_tokens(config, &paths)));
+    }
+    warnings
+}
+
+/// Tokens spent before any file content: the Claude-XML header and the cheapest file's markers
+fn context_overhead_tokens(config: &EncoderConfig, paths: &[&str]) -> usize {
+    let header = if config.output_format == OutputFormat::ClaudeXml {
+        serialize_entries_claude_xml(config, &[]).map_or(0, |xml| TokenEstimator::estimate_tokens(&xml))
+    } else {
+        0
+    };
+    let markers = paths.iter().map(|p| TokenEstimator::estimate_file_tokens(Path::new(p), "")).min().unwrap_or(0);
+    header + markers
+}
+
+/// Insert the synthetic `WARNINGS.md` entry first when there are configuration warnings
+///
+/// Claude-XML carries the warnings in its metadata instead.
+pub fn prepend_config_warnings(entries: &mut Vec<FileEntry>, warnings: &[core::ConfigWarning], format: OutputFormat) {
+    if let Some(entry) = config_warnings_entry(warnings, format) {
+        entries.insert(0, entry);
+    }
+}
+
+/// Build the synthetic `WARNINGS.md` entry (`None` without warnings or for Claude-XML)
+fn config_warnings_entry(warnings: &[core::ConfigWarning], format: OutputFormat) -> Option<FileEntry> {
+    if warnings.is_empty() || format == OutputFormat::ClaudeXml {
+        return None;
+    }
+    let content = core::preflight::to_markdown(warnings);
+    Some(FileEntry {
+        path: core::WARNINGS_FILE_NAME.to_string(),
+        md5: calculate_md5(&content),
+        size: content.len() as u64,
+        content,
+        mtime: 0,
+        ctime: 0,
+    })
+}
+
+/// Insert the guided tour of `entries` first when the onboarding lens is active
+pub fn prepend_tour(entries: &mut Vec<FileEntry>, config: &EncoderConfig) {
+    if config.active_lens.as_deref() != Some(core::TOUR_LENS) {
+        return;
+    }
+    let tour = core::tour_file(entries.iter().map(|e| (e.path.as_str(), e.content.as_str())));
+    entries.insert(0, FileEntry { path: tour.path, content: tour.content, md5: tour.md5, mtime: 0, ctime: 0, size: tour.size });
 }
 
 /// Serialize files to Claude-XML format using streaming XmlWriter
@@ -2039,44 +2578,28 @@ pub fn serialize_entries_claude_xml(
     config: &EncoderConfig,
     files: &[FileEntry],
 ) -> Result<String, String> {
-    use crate::formats::{XmlWriter, XmlConfig, AttentionEntry};
-
-    let mut buffer = Vec::new();
+    write_entries_claude_xml(config, files, &config.warnings)
+}
 
-    // Build XmlConfig from EncoderConfig
-    let xml_config = XmlConfig {
-        package: "pm_encoder".to_string(),
-        version: VERSION.to_string(),
-        lens: config.active_lens.clone(),
-        token_budget: config.token_budget,
-        utilized_tokens: Some(files.iter().map(|f| f.content.len() / 4).sum()),
-        frozen: config.frozen,
-        allow_sensitive: config.allow_sensitive,
-        snapshot_id: if config.frozen { Some("FROZEN_SNAPSHOT".to_string()) } else { None },
-    };
+/// [`serialize_entries_claude_xml`] with explicit configuration warnings
+fn write_entries_claude_xml(
+    config: &EncoderConfig,
+    files: &[FileEntry],
+    warnings: &[core::ConfigWarning],
+) -> Result<String, String> {
+    use crate::formats::{XmlWriter, AttentionEntry};
 
-    let mut writer = XmlWriter::new(&mut buffer, xml_config);
+    let mut buffer = Vec::new();
 
     // Build attention entries for metadata
     // Apply active lens for accurate priority calculation
-    let mut lens_manager = LensManager::new();
-    if let Some(ref lens_name) = config.active_lens {
-        let _ = lens_manager.apply_lens(lens_name);
-    }
+    let lens_manager = claude_xml_lens_manager(config);
+    let attention_entries: Vec<AttentionEntry> = files.iter()
+        .map(|f| claude_xml_attention_entry(config, &lens_manager, f))
+        .collect();
+    let utilized = files.iter().map(|f| f.content.len() / 4).sum();
 
-    let attention_entries: Vec<AttentionEntry> = files.iter().map(|f| {
-        let priority = lens_manager.get_file_priority(std::path::Path::new(&f.path));
-        let tokens = f.content.len() / 4;
-        let truncated = config.truncate_lines > 0 && f.content.lines().count() > config.truncate_lines;
-        AttentionEntry {
-            path: f.path.clone(),
-            priority,
-            tokens,
-            truncated,
-            dropped: false,
-            utility_score: None,
-        }
-    }).collect();
+    let mut writer = XmlWriter::new(&mut buffer, claude_xml_config(config, utilized, warnings));
 
     // Write XML structure
     writer.write_context_start().map_err(|e| e.to_string())?;
@@ -2084,39 +2607,7 @@ pub fn serialize_entries_claude_xml(
     writer.write_files_start().map_err(|e| e.to_string())?;
 
     for entry in files {
-        let language = detect_language(&entry.path);
-        let priority = lens_manager.get_static_priority(std::path::Path::new(&entry.path));
-
-        // Apply truncation if configured
-        let (content, truncated) = if config.truncate_lines > 0 {
-            truncate_for_xml(&entry.content, config.truncate_lines, &config.truncate_mode)
-        } else {
-            (entry.content.clone(), false)
-        };
-
-        let original_tokens = if truncated {
-            Some(entry.content.len() / 4)
-        } else {
-            None
-        };
-
-        // Build zoom command for truncated files (Phase 4: Fractal affordances)
-        let zoom_cmd = if truncated {
-            Some(format!("--include {} --truncate 0", entry.path))
-        } else {
-            None
-        };
-
-        writer.write_file(
-            &entry.path,
-            language,
-            &entry.md5,
-            priority,
-            &content,
-            truncated,
-            original_tokens,
-            zoom_cmd.as_deref(),
-        ).map_err(|e| e.to_string())?;
+        write_claude_xml_file(&mut writer, config, &lens_manager, entry).map_err(|e| e.to_string())?;
     }
 
     writer.write_files_end().map_err(|e| e.to_string())?;
@@ -2126,6 +2617,96 @@ pub fn serialize_entries_claude_xml(
     String::from_utf8(buffer).map_err(|e| e.to_string())
 }
 
+/// Build XmlConfig from EncoderConfig
+fn claude_xml_config(
+    config: &EncoderConfig,
+    utilized_tokens: usize,
+    warnings: &[core::ConfigWarning],
+) -> crate::formats::XmlConfig {
+    crate::formats::XmlConfig {
+        package: "pm_encoder".to_string(),
+        version: VERSION.to_string(),
+        lens: config.active_lens.clone(),
+        token_budget: config.token_budget,
+        utilized_tokens: Some(utilized_tokens),
+        frozen: config.frozen,
+        allow_sensitive: config.allow_sensitive,
+        snapshot_id: if config.frozen { Some("FROZEN_SNAPSHOT".to_string()) } else { None },
+        warnings: warnings.iter().map(ToString::to_string).collect(),
+    }
+}
+
+/// Lens manager with the active lens applied, for Claude-XML priorities
+fn claude_xml_lens_manager(config: &EncoderConfig) -> LensManager {
+    let mut lens_manager = LensManager::new();
+    if let Some(ref lens_name) = config.active_lens {
+        let _ = lens_manager.apply_lens(lens_name);
+    }
+    lens_manager
+}
+
+/// Attention map entry for one file
+fn claude_xml_attention_entry(
+    config: &EncoderConfig,
+    lens_manager: &LensManager,
+    f: &FileEntry,
+) -> crate::formats::AttentionEntry {
+    let priority = lens_manager.get_file_priority(std::path::Path::new(&f.path));
+    let tokens = f.content.len() / 4;
+    let truncated = config.truncate_lines > 0 && f.content.lines().count() > config.truncate_lines;
+    crate::formats::AttentionEntry {
+        path: f.path.clone(),
+        priority,
+        tokens,
+        truncated,
+        dropped: false,
+        utility_score: None,
+    }
+}
+
+/// Write one `<file>` element, truncated as configured
+fn write_claude_xml_file<W: std::io::Write>(
+    writer: &mut crate::formats::XmlWriter<W>,
+    config: &EncoderConfig,
+    lens_manager: &LensManager,
+    entry: &FileEntry,
+) -> Result<(), crate::formats::XmlError> {
+    let language = detect_language(&entry.path);
+    let priority = lens_manager.get_static_priority(std::path::Path::new(&entry.path));
+
+    // Apply truncation if configured
+    let (content, truncated) = match truncation_for(entry, config) {
+        (truncate_lines, truncate_mode) if truncate_lines > 0 => {
+            truncate_for_xml(&entry.content, &entry.path, truncate_lines, truncate_mode)
+        }
+        _ => (entry.content.clone(), false),
+    };
+
+    let original_tokens = if truncated {
+        Some(entry.content.len() / 4)
+    } else {
+        None
+    };
+
+    // Build zoom command for truncated files (Phase 4: Fractal affordances)
+    let zoom_cmd = if truncated {
+        Some(format!("--include {} --truncate 0", entry.path))
+    } else {
+        None
+    };
+
+    writer.write_file(
+        &entry.path,
+        language,
+        &entry.md5,
+        priority,
+        &content,
+        truncated,
+        original_tokens,
+        zoom_cmd.as_deref(),
+    )
+}
+
 /// Serialize file entries to Claude-XML format with budget report for dropped files
 ///
 /// This enhanced version includes coldspots (dropped files) from the BudgetReport
@@ -2160,6 +2741,7 @@ pub fn serialize_entries_claude_xml_with_report(
         frozen: config.frozen,
         allow_sensitive: config.allow_sensitive,
         snapshot_id: if config.frozen { Some("FROZEN_SNAPSHOT".to_string()) } else { None },
+        warnings: config.warnings.iter().map(ToString::to_string).collect(),
     };
 
     let mut writer = XmlWriter::new(&mut buffer, xml_config);
@@ -2171,7 +2753,7 @@ pub fn serialize_entries_claude_xml_with_report(
             path: path.clone(),
             priority: *priority,
             tokens: *tokens,
-            truncated: method == "truncated",
+            truncated: method == "truncated" || method == "summarized",
             dropped: false,
             utility_score: None, // Will be populated from ContextStore when available
         }
@@ -2207,19 +2789,26 @@ pub fn serialize_entries_claude_xml_with_report(
         let language = detect_language(&entry.path);
         let priority = lens_manager.get_static_priority(std::path::Path::new(&entry.path));
 
-        // Check if this file was truncated by the budget strategy
-        let was_truncated = report.included_files.iter()
-            .any(|(p, _, _, m)| p == &entry.path && m == "truncated");
+        // Check if this file was truncated or summarized by the budget strategy
+        let method = report.included_files.iter()
+            .find(|(p, _, _, _)| p == &entry.path)
+            .map(|(_, _, _, m)| m.as_str());
 
         // Apply truncation if configured or if budget strategy truncated it
-        let (content, truncated) = if was_truncated {
+        let (content, truncated) = if method == Some("summarized") {
+            // Already an outline - keep it, flagged for zooming
+            (entry.content.clone(), true)
+        } else if method == Some("truncated") {
             // Already truncated by budget strategy - use structure mode
             let (trunc, _) = truncate_structure(&entry.content, &entry.path);
             (trunc, true)
-        } else if config.truncate_lines > 0 {
-            truncate_for_xml(&entry.content, config.truncate_lines, &config.truncate_mode)
         } else {
-            (entry.content.clone(), false)
+            match truncation_for(entry, config) {
+                (truncate_lines, truncate_mode) if truncate_lines > 0 => {
+                    truncate_for_xml(&entry.content, &entry.path, truncate_lines, truncate_mode)
+                }
+                _ => (entry.content.clone(), false),
+            }
         };
 
         let original_tokens = if truncated {
@@ -2255,7 +2844,9 @@ pub fn serialize_entries_claude_xml_with_report(
 }
 
 /// Truncate content for XML output
-fn truncate_for_xml(content: &str, max_lines: usize, mode: &str) -> (String, bool) {
+///
+/// `path` picks the language for AST-aware window truncation.
+fn truncate_for_xml(content: &str, path: &str, max_lines: usize, mode: &str) -> (String, bool) {
     let lines: Vec<&str> = content.lines().collect();
     if lines.len() <= max_lines {
         return (content.to_string(), false);
@@ -2270,6 +2861,10 @@ fn truncate_for_xml(content: &str, max_lines: usize, mode: &str) -> (String, boo
             let (truncated, was_truncated) = truncate_smart(content, max_lines, "file");
             (truncated, was_truncated)
         }
+        "window" => {
+            let (truncated, was_truncated) = truncate_window(content, max_lines, path);
+            (truncated, was_truncated)
+        }
         _ => {
             // Simple truncation
             let truncated: String = lines[..max_lines].join("\n");
@@ -2371,16 +2966,19 @@ pub fn serialize_project_streaming(
     let mut handle = stdout.lock();
 
     // Stream files as they're discovered
-    for entry in walk_directory_iter(
+    for mut entry in walk_directory_iter_with_case(
         root,
         config.ignore_patterns.clone(),
         config.include_patterns.clone(),
         config.max_file_size,
+        config.case_insensitive_paths,
     ) {
+        apply_symbol_budget(&mut entry, config, &core::ExtractiveSummarizer::new());
+        let (truncate_lines, truncate_mode) = truncation_for(&entry, config);
         let serialized = serialize_file_with_format_and_metadata(
             &entry,
-            config.truncate_lines,
-            &config.truncate_mode,
+            truncate_lines,
+            truncate_mode,
             config.output_format,
             config.metadata_mode,
         );
@@ -2396,6 +2994,179 @@ pub fn serialize_project_streaming(
     Ok(String::new())
 }
 
+/// Capacity of the write buffer used by [`serialize_project_to`]
+pub const STREAM_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Serialize a project to any writer with bounded memory
+///
+/// Produces the same files, order and format as
+/// [`serialize_project_with_config`], but never holds the whole context:
+/// a first pass walks the project keeping only paths, timestamps and the
+/// Claude-XML attention map, then each file is re-read, serialized and
+/// written through a [`STREAM_BUFFER_SIZE`] buffer. Peak memory is roughly
+/// the largest file, so multi-hundred-MB contexts can go straight to a
+/// file, socket or compressing sink.
+///
+/// The table of contents and configuration warnings are built from the
+/// index pass, the table of contents by preparing every file once more
+/// before any is written. Features that need the complete output
+/// (onboarding tour, affordance index) are not applied here. Git views are
+/// read in one batch, since the object database has no cheap re-read path.
+///
+/// # Returns
+///
+/// * `Ok((u64, warnings))` - Number of bytes written and the [`preflight`]
+///   warnings found (already in the output, returned for reporting)
+/// * `Err(String)` - Walk or write error
+pub fn serialize_project_to<W: std::io::Write>(
+    root: &str,
+    config: &EncoderConfig,
+    out: W,
+) -> Result<(u64, Vec<core::ConfigWarning>), String> {
+    use std::io::Write;
+
+    let root_path = Path::new(root);
+    if !root_path.exists() {
+        return Err(format!("Directory not found: {}", root));
+    }
+
+    let claude_xml = config.output_format == OutputFormat::ClaudeXml;
+    let lens_manager = claude_xml_lens_manager(config);
+    let mut attention = std::collections::HashMap::new();
+    let mut utilized = 0;
+    let mut stripper = config
+        .strip_boilerplate
+        .then(|| core::BoilerplateStripper::new(config.boilerplate.clone()));
+
+    // Index pass: keep sort keys only, drop contents once the attention map has them
+    let reread = config.git_view.is_none();
+    let walked: Box<dyn Iterator<Item = FileEntry> + '_> = if reread {
+        Box::new(walk_directory_iter_with_case(
+            root,
+            config.ignore_patterns.clone(),
+            config.include_patterns.clone(),
+            config.max_file_size,
+            config.case_insensitive_paths,
+        ))
+    } else {
+        Box::new(walk_project(root, config)?.into_iter())
+    };
+    let mut index: Vec<FileEntry> = walked
+        .map(|mut entry| {
+            apply_outline_depth(&mut entry, config);
+            if let Some(stripper) = stripper.as_mut() {
+                stripper.observe(&entry.path, &entry.content);
+            }
+            if claude_xml {
+                attention.insert(entry.path.clone(), claude_xml_attention_entry(config, &lens_manager, &entry));
+                utilized += entry.content.len() / 4;
+            }
+            if reread {
+                entry.content = String::new();
+            }
+            entry
+        })
+        .collect();
+    sort_entries(&mut index, config);
+    let mut attention_entries: Vec<_> = index.iter().filter_map(|e| attention.remove(&e.path)).collect();
+    let rollups = import_rollups(config, &index);
+    let found = preflight(config, &config.include_patterns, &index);
+    let warnings: Vec<core::ConfigWarning> = config.warnings.iter().chain(&found).cloned().collect();
+
+    fn write_error(e: impl std::fmt::Display) -> String {
+        format!("Failed to write output: {}", e)
+    }
+    let mut writer = CountingWriter {
+        inner: std::io::BufWriter::with_capacity(STREAM_BUFFER_SIZE, out),
+        written: 0,
+    };
+    // Changed since the index pass: files that vanished or turned binary are skipped
+    let prepare = |entry: FileEntry| {
+        let mut entry = if reread { reread_entry(root_path, entry, config)? } else { entry };
+        apply_symbol_budget(&mut entry, config, &core::ExtractiveSummarizer::new());
+        if let Some(rollups) = &rollups {
+            apply_import_rollup(&mut entry, rollups);
+        }
+        let provenance = file_provenance(&entry, config);
+        if let Some(stripper) = &stripper {
+            apply_boilerplate(&mut entry, stripper);
+        }
+        if let Some(provenance) = provenance {
+            apply_provenance(&mut entry, &provenance, config);
+        }
+        Some(entry)
+    };
+
+    // Synthetic entries open the context, in the batch order
+    let toc = config
+        .table_of_contents
+        .then(|| table_of_contents_entry(index.iter().cloned().filter_map(prepare), config));
+    if let (true, Some(toc)) = (claude_xml, &toc) {
+        attention_entries.insert(0, claude_xml_attention_entry(config, &lens_manager, toc));
+        utilized += toc.content.len() / 4;
+    }
+    let files = config_warnings_entry(&warnings, config.output_format)
+        .into_iter()
+        .chain(toc)
+        .chain(index.into_iter().filter_map(prepare));
+
+    if claude_xml {
+        let mut xml = crate::formats::XmlWriter::new(&mut writer, claude_xml_config(config, utilized, &warnings));
+        xml.write_context_start().map_err(write_error)?;
+        xml.write_metadata(&attention_entries).map_err(write_error)?;
+        xml.write_files_start().map_err(write_error)?;
+        for entry in files {
+            write_claude_xml_file(&mut xml, config, &lens_manager, &entry).map_err(write_error)?;
+        }
+        xml.write_files_end().map_err(write_error)?;
+        xml.write_context_end().map_err(write_error)?;
+    } else {
+        for entry in files {
+            let (truncate_lines, truncate_mode) = truncation_for(&entry, config);
+            let serialized = serialize_file_with_format_and_metadata(
+                &entry,
+                truncate_lines,
+                truncate_mode,
+                config.output_format,
+                config.metadata_mode,
+            );
+            writer.write_all(serialized.as_bytes()).map_err(write_error)?;
+        }
+    }
+    writer.flush().map_err(write_error)?;
+
+    Ok((writer.written, found))
+}
+
+/// Reload an indexed entry's content from disk (`None` if unreadable or binary now)
+fn reread_entry(root: &Path, mut entry: FileEntry, config: &EncoderConfig) -> Option<FileEntry> {
+    let bytes = fs::read(extended_length_path(&root.join(&entry.path))).ok()?;
+    let content = read_file_content(&bytes)?;
+    entry.md5 = calculate_md5(&content);
+    entry.size = content.len() as u64;
+    entry.content = content;
+    apply_outline_depth(&mut entry, config);
+    Some(entry)
+}
+
+/// Byte-counting adapter for [`serialize_project_to`]
+struct CountingWriter<W: std::io::Write> {
+    inner: W,
+    written: u64,
+}
+
+impl<W: std::io::Write> std::io::Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -2405,6 +3176,48 @@ mod tests {
         assert_eq!(version(), "1.0.0");
     }
 
+    /// Lines kept from the source, ignoring gap markers (inputs have no blank lines)
+    fn kept_source_lines(output: &str) -> usize {
+        output
+            .lines()
+            .filter(|l| !l.trim().is_empty() && (!l.starts_with("... [") || !l.ends_with("omitted] ...")))
+            .count()
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_truncate_simple_respects_budget(total in 1usize..300, max_lines in 1usize..200) {
+            let content: String = (0..total).map(|i| format!("line {}\n", i)).collect();
+            let (output, truncated) = truncate_simple_with_options(&content, max_lines, "a.txt", false);
+            let out_lines = python_style_split(&output).len();
+            proptest::prop_assert_eq!(truncated, python_style_split(&content).len() > max_lines);
+            if truncated {
+                proptest::prop_assert!(out_lines <= max_lines);
+                proptest::prop_assert!(content.starts_with(&output), "simple mode keeps a prefix");
+            }
+        }
+
+        #[test]
+        fn prop_truncate_smart_respects_budget(defs in 1usize..60, body in 1usize..12, max_lines in 5usize..200) {
+            let mut content = String::new();
+            for d in 0..defs {
+                content.push_str(&format!("def f{}(x):\n", d));
+                for b in 0..body {
+                    content.push_str(&format!("    y{} = x + {}\n", b, b));
+                }
+            }
+            let (output, truncated) = truncate_smart_with_options(&content, max_lines, "gen.py", false);
+            if truncated {
+                proptest::prop_assert!(
+                    kept_source_lines(&output) <= max_lines,
+                    "kept {} lines for budget {}", kept_source_lines(&output), max_lines
+                );
+            } else {
+                proptest::prop_assert_eq!(output, content);
+            }
+        }
+    }
+
     #[test]
     fn test_serialize_project() {
         let result = serialize_project(".");
@@ -2425,6 +3238,77 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_serialize_project_to_matches_batch_output() {
+        let temp_dir = std::env::temp_dir().join("pm_encoder_test_stream_to");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("src")).unwrap();
+        fs::write(temp_dir.join("src/b.py"), "def b():\n    return 2\n").unwrap();
+        fs::write(temp_dir.join("a.rs"), "pub fn a() {}\n").unwrap();
+        fs::write(temp_dir.join("README.md"), "# Title\n\n## Part\n\ntext\n").unwrap();
+        let root = temp_dir.to_str().unwrap();
+
+        for format in [OutputFormat::PlusMinus, OutputFormat::ClaudeXml] {
+            let config = EncoderConfig {
+                output_format: format,
+                frozen: true,
+                outline_depth: Some(1),
+                ..Default::default()
+            };
+            let mut streamed = Vec::new();
+            let (written, _) = serialize_project_to(root, &config, &mut streamed).unwrap();
+            assert_eq!(written, streamed.len() as u64);
+            assert_eq!(String::from_utf8(streamed).unwrap(), serialize_project_with_config(root, &config).unwrap());
+        }
+        assert!(serialize_project_to("/nonexistent/path", &EncoderConfig::default(), std::io::sink()).is_err());
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_serialize_project_to_opens_with_toc_and_warnings() {
+        let temp_dir = std::env::temp_dir().join("pm_encoder_test_stream_to_toc");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("src")).unwrap();
+        fs::write(temp_dir.join("src/b.py"), "def b():\n    return 2\n").unwrap();
+        fs::write(temp_dir.join("a.rs"), "pub fn a() {}\n").unwrap();
+        let root = temp_dir.to_str().unwrap();
+
+        for format in [OutputFormat::PlusMinus, OutputFormat::ClaudeXml] {
+            let config = EncoderConfig {
+                output_format: format,
+                frozen: true,
+                table_of_contents: true,
+                include_patterns: vec!["*.rs".to_string(), "*.py".to_string(), "*.zz".to_string()],
+                ..Default::default()
+            };
+            let mut streamed = Vec::new();
+            let (_, warnings) = serialize_project_to(root, &config, &mut streamed).unwrap();
+            let streamed = String::from_utf8(streamed).unwrap();
+            let (batch, batch_warnings) = serialize_project_with_warnings(root, &config).unwrap();
+            assert_eq!(streamed, batch);
+            assert_eq!(warnings, batch_warnings);
+            assert_eq!(warnings.len(), 1);
+            assert!(streamed.contains(core::TOC_FILE_NAME));
+        }
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_write_context_matches_generate_context() {
+        let files = vec![
+            ("z.py".to_string(), "print('z')\n".to_string()),
+            ("a.rs".to_string(), "fn a() {}\n".to_string()),
+        ];
+        for format in [OutputFormat::PlusMinus, OutputFormat::Markdown, OutputFormat::ClaudeXml] {
+            let engine = ContextEngine::new(EncoderConfig { output_format: format, frozen: true, ..Default::default() });
+            let mut streamed = Vec::new();
+            engine.write_context(&files, &mut streamed).unwrap();
+            assert_eq!(String::from_utf8(streamed).unwrap(), engine.generate_context(&files));
+        }
+    }
+
     #[test]
     fn test_default_config() {
         let config = EncoderConfig::default();
@@ -2692,6 +3576,7 @@ impl Config {
             max_file_size: 1_000_000,
             truncate_lines: 500,
             truncate_mode: "smart".to_string(),
+            language_rules: std::collections::HashMap::new(),
             sort_by: "mtime".to_string(),
             sort_order: "desc".to_string(),
             stream: true,
@@ -2703,9 +3588,21 @@ impl Config {
             allow_sensitive: false,
             active_lens: Some("architecture".to_string()),
             token_budget: Some(100_000),
+            symbol_budget: None,
             skeleton_mode: SkeletonMode::Auto,
             metadata_mode: MetadataMode::Auto,
             follow_symlinks: false,
+            outline_depth: None,
+            safe_cuts: false,
+            affordance_index: false,
+            git_view: None,
+            import_rollup: false,
+            strip_boilerplate: false,
+            table_of_contents: false,
+            provenance_annotations: false,
+            boilerplate: crate::core::BoilerplateRules::default(),
+            warnings: vec![],
+            case_insensitive_paths: false,
         };
 
         assert_eq!(config.truncate_lines, 500);
@@ -2740,6 +3637,28 @@ impl Config {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_walk_project_case_insensitive_paths() {
+        use std::fs;
+        let temp_dir = std::env::temp_dir().join("pm_encoder_test_walk_case");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("Main.PY"), "print('hello')").unwrap();
+        fs::write(temp_dir.join(".pm_encoder_config.json"), r#"{"case_insensitive_paths": true}"#).unwrap();
+        let root = temp_dir.to_str().unwrap();
+
+        let mut config = EncoderConfig::from_file(&temp_dir.join(".pm_encoder_config.json")).unwrap();
+        assert!(config.case_insensitive_paths);
+        config.include_patterns = vec!["*.py".to_string()];
+        let paths: Vec<String> = walk_project(root, &config).unwrap().into_iter().map(|e| e.path).collect();
+        assert_eq!(paths, vec!["Main.PY"]);
+
+        config.case_insensitive_paths = false;
+        assert!(walk_project(root, &config).unwrap().iter().all(|e| e.path != "Main.PY"));
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
     #[test]
     fn test_truncate_modes_all() {
         let python = "def foo():\n    pass\n";
@@ -2755,6 +3674,99 @@ impl Config {
         // Structure mode
         let (result, _) = truncate_structure(python, "test.py");
         assert!(result.contains("def foo"));
+
+        // Window mode
+        let (result, _) = truncate_window(python, 100, "test.py");
+        assert!(result.contains("def foo"));
+    }
+
+    #[test]
+    fn test_truncate_window_keeps_error_branches() {
+        let mut content = String::from("def handler(event):\n");
+        for i in 0..30 {
+            content.push_str(&format!("    a{} = {}\n", i, i));
+            if i == 15 {
+                content.push_str("    if a15 < 0:\n        raise ValueError('bad')\n");
+            }
+        }
+        content.push_str("    return a29\n");
+
+        let (result, truncated) = truncate_window(&content, 10, "handler.py");
+        assert!(truncated);
+        assert!(result.contains("raise ValueError('bad')"));
+        assert!(result.contains("return a29"));
+        assert!(result.contains("WINDOW TRUNCATED"));
+        assert!(!result.contains("a10 = 10"));
+    }
+
+    #[test]
+    fn test_symbol_budget_summarizes_on_the_serialize_path() {
+        let temp_dir = std::env::temp_dir().join("pm_encoder_test_symbol_budget");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        let mut source = String::from("fn big() {\n");
+        for i in 0..50 {
+            source.push_str(&format!("    println!(\"step {}\");\n", i));
+        }
+        source.push_str("}\n");
+        fs::write(temp_dir.join("lib.rs"), &source).unwrap();
+        let root = temp_dir.to_str().unwrap();
+
+        let config = EncoderConfig { symbol_budget: Some(20), ..Default::default() };
+        let output = serialize_project_with_config(root, &config).unwrap();
+        assert!(output.contains("SUMMARY[extractive]: function big"));
+        assert!(!output.contains("step 25"));
+
+        let (output, _) = serialize_project_with_summarizer(root, &config, &core::NoopSummarizer).unwrap();
+        assert!(output.contains("step 25"));
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_truncation_for_applies_language_rules() {
+        let config = EncoderConfig {
+            truncate_lines: 2,
+            language_rules: std::collections::HashMap::from([
+                ("sql".to_string(), core::LanguageTruncation::never()),
+                ("python".to_string(), core::LanguageTruncation::default().with_keep_docstrings(true)),
+            ]),
+            ..Default::default()
+        };
+        let entry = |path: &str, content: &str| FileEntry {
+            path: path.to_string(),
+            content: content.to_string(),
+            md5: String::new(),
+            mtime: 0,
+            ctime: 0,
+            size: content.len() as u64,
+        };
+        assert_eq!(truncation_for(&entry("schema.sql", "a\nb\nc\n"), &config), (0, "simple"));
+        assert_eq!(truncation_for(&entry("main.rs", "a\nb\nc\n"), &config), (2, "simple"));
+        // The cut moves past the end of the docstring it would split
+        let python = entry("app.py", "def f():\n    \"\"\"Doc\n    more\n    \"\"\"\n    return 1\n");
+        assert_eq!(truncation_for(&python, &config), (4, "simple"));
+    }
+
+    #[test]
+    fn test_truncate_for_xml_windows_by_path_language() {
+        let mut content = String::from("def handler(event):\n");
+        for i in 0..30 {
+            content.push_str(&format!("    a{} = {}\n", i, i));
+        }
+        content.push_str("    return a29\n");
+
+        let (result, truncated) = truncate_for_xml(&content, "handler.py", 10, "window");
+        assert!(truncated);
+        assert!(result.contains("# ... "), "AST window marker, not the line fallback");
+        assert!(result.contains("return a29"));
+    }
+
+    #[test]
+    fn test_truncate_window_falls_back_to_smart() {
+        let content = (0..50).map(|i| format!("line{}", i)).collect::<Vec<_>>().join("\n");
+        let (window, _) = truncate_window(&content, 10, "notes.txt");
+        let (smart, _) = truncate_smart(&content, 10, "notes.txt");
+        assert_eq!(window, smart);
     }
 
     #[test]
@@ -4018,6 +5030,7 @@ This is the last section.
             original_tokens: None,
             compression_level: CompressionLevel::Full,
             utility: None,
+            provenance: None,
         };
 
         assert_eq!(pf.path, "test.py");
@@ -4031,6 +5044,7 @@ This is the last section.
         let config = Config {
             ignore_patterns: vec!["*.log".to_string()],
             include_patterns: vec!["*.py".to_string()],
+            ..Default::default()
         };
 
         assert_eq!(config.ignore_patterns.len(), 1);
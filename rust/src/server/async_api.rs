@@ -0,0 +1,249 @@
+//! Async API over the blocking engines (feature `async`)
+//!
+//! Indexing and serialization are synchronous and can run for seconds on a
+//! large tree. Called directly from an async handler they stall a runtime
+//! thread, and with it every other request scheduled there. The functions
+//! here move that work to tokio's blocking pool behind a [`BlockingPool`]:
+//!
+//! - at most `max_jobs` jobs run at once; further callers *await* a slot
+//!   (no runtime thread is parked while waiting)
+//! - at most `max_queue` callers wait; beyond that, or after `queue_timeout`,
+//!   the call fails fast with the same [`Busy`] rejections as the
+//!   synchronous [`Limiter`](super::limits::Limiter)
+//! - a slot is held until the blocking work finishes, even if the awaiting
+//!   future is dropped, so cancelled requests still count against the limit
+//!
+//! ```rust,ignore
+//! let pool = BlockingPool::new(ServerLimits::default());
+//! let context = generate_context_async(&pool, ContextEngine::new(config), files).await?;
+//! ```
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use tokio::sync::Semaphore;
+use voyager_ast::{AstProvider, IndexOptions, PlanetariumModel, TreeSitterProvider};
+
+use super::limits::{Busy, ServerLimits};
+use crate::core::{self, ZoomConfig};
+use crate::ContextEngine;
+
+/// Why an async job did not produce a result
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskError {
+    /// No job slot: the queue is full or the wait timed out
+    Busy(Busy),
+    /// The job ran and failed
+    Failed(String),
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Busy(busy) => busy.fmt(f),
+            Self::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for TaskError {}
+
+impl From<Busy> for TaskError {
+    fn from(busy: Busy) -> Self {
+        Self::Busy(busy)
+    }
+}
+
+/// Bounded gateway from async code to tokio's blocking pool
+#[derive(Debug)]
+pub struct BlockingPool {
+    limits: ServerLimits,
+    slots: Arc<Semaphore>,
+    queued: AtomicUsize,
+}
+
+/// Counts a caller as queued until it gets a slot or gives up
+struct Queued<'a>(&'a AtomicUsize);
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Default for BlockingPool {
+    fn default() -> Self {
+        Self::new(ServerLimits::default())
+    }
+}
+
+impl BlockingPool {
+    pub fn new(limits: ServerLimits) -> Self {
+        let limits = ServerLimits { max_jobs: limits.max_jobs.max(1), ..limits };
+        Self {
+            slots: Arc::new(Semaphore::new(limits.max_jobs)),
+            queued: AtomicUsize::new(0),
+            limits,
+        }
+    }
+
+    /// Effective limits
+    pub fn limits(&self) -> &ServerLimits {
+        &self.limits
+    }
+
+    /// Jobs currently running and queued
+    pub fn load(&self) -> (usize, usize) {
+        (self.running(), self.queued.load(Ordering::SeqCst))
+    }
+
+    /// Run `work` on the blocking pool once a slot is free
+    ///
+    /// A panic in `work` is resumed in the caller.
+    pub async fn run<T, F>(&self, work: F) -> Result<T, TaskError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let permit = match Arc::clone(&self.slots).try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let queued = self.queued.fetch_add(1, Ordering::SeqCst);
+                let _queued = Queued(&self.queued);
+                if queued >= self.limits.max_queue {
+                    return Err(Busy::QueueFull { running: self.running(), queued }.into());
+                }
+                let started = Instant::now();
+                match tokio::time::timeout(self.limits.queue_timeout, Arc::clone(&self.slots).acquire_owned()).await {
+                    Ok(Ok(permit)) => permit,
+                    // The semaphore is never closed; treat it like a timeout anyway
+                    Ok(Err(_)) | Err(_) => {
+                        return Err(Busy::Timeout { waited: started.elapsed(), running: self.running() }.into());
+                    }
+                }
+            }
+        };
+
+        let job = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            work()
+        });
+        match job.await {
+            Ok(value) => Ok(value),
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(TaskError::Failed(format!("Worker failed: {}", e))),
+        }
+    }
+
+    fn running(&self) -> usize {
+        self.limits.max_jobs - self.slots.available_permits()
+    }
+}
+
+/// Parse the project under `root` into a [`PlanetariumModel`] without blocking the runtime
+pub async fn index_project_async(
+    pool: &BlockingPool,
+    root: PathBuf,
+    options: IndexOptions,
+) -> Result<PlanetariumModel, TaskError> {
+    pool.run(move || TreeSitterProvider::new().index_project(&root, &options).map_err(|e| e.to_string()))
+        .await?
+        .map_err(|e| TaskError::Failed(format!("Indexing failed: {}", e)))
+}
+
+/// [`ContextEngine::generate_context`] without blocking the runtime
+pub async fn generate_context_async(
+    pool: &BlockingPool,
+    engine: ContextEngine,
+    files: Vec<(String, String)>,
+) -> Result<String, TaskError> {
+    pool.run(move || engine.generate_context(&files)).await
+}
+
+/// Serialize the project under `root` with the core engine without blocking the runtime
+pub async fn serialize_project_async(
+    pool: &BlockingPool,
+    config: core::EncoderConfig,
+    root: String,
+) -> Result<String, TaskError> {
+    pool.run(move || core::ContextEngine::with_config(config).serialize(&root).map_err(|e| e.to_string()))
+        .await?
+        .map_err(|e| TaskError::Failed(format!("Serialization failed: {}", e)))
+}
+
+/// Zoom into a target under `root` without blocking the runtime
+pub async fn zoom_async(pool: &BlockingPool, root: String, config: ZoomConfig) -> Result<String, TaskError> {
+    pool.run(move || core::ContextEngine::new().zoom(&root, &config).map_err(|e| e.to_string()))
+        .await?
+        .map_err(|e| TaskError::Failed(format!("Zoom failed: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limits(max_jobs: usize, max_queue: usize) -> ServerLimits {
+        ServerLimits {
+            max_jobs,
+            max_queue,
+            queue_timeout: Duration::from_millis(200),
+            requests_per_minute: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generate_context_async_matches_sync() {
+        let pool = BlockingPool::default();
+        let files = vec![("a.py".to_string(), "print('a')\n".to_string())];
+        let expected = ContextEngine::new(crate::EncoderConfig::default()).generate_context(&files);
+        let context = generate_context_async(&pool, ContextEngine::new(crate::EncoderConfig::default()), files)
+            .await
+            .unwrap();
+        assert_eq!(context, expected);
+        assert_eq!(pool.load(), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_full_pool_rejects_without_blocking() {
+        let pool = Arc::new(BlockingPool::new(limits(1, 0)));
+        let (release, wait) = std::sync::mpsc::channel::<()>();
+        let busy = {
+            let pool = Arc::clone(&pool);
+            tokio::spawn(async move { pool.run(move || wait.recv().ok()).await })
+        };
+        while pool.load().0 == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let rejected = pool.run(|| ()).await;
+        assert!(matches!(rejected, Err(TaskError::Busy(Busy::QueueFull { running: 1, .. }))));
+
+        release.send(()).unwrap();
+        assert_eq!(busy.await.unwrap(), Ok(Some(())));
+        assert_eq!(pool.run(|| 42).await, Ok(42));
+    }
+
+    #[tokio::test]
+    async fn test_queued_caller_times_out() {
+        let pool = Arc::new(BlockingPool::new(limits(1, 1)));
+        let (release, wait) = std::sync::mpsc::channel::<()>();
+        let busy = {
+            let pool = Arc::clone(&pool);
+            tokio::spawn(async move { pool.run(move || wait.recv().ok()).await })
+        };
+        while pool.load().0 == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let timed_out = pool.run(|| ()).await;
+        assert!(matches!(timed_out, Err(TaskError::Busy(Busy::Timeout { .. }))));
+        assert_eq!(pool.load(), (1, 0));
+
+        release.send(()).unwrap();
+        busy.await.unwrap().unwrap();
+    }
+}
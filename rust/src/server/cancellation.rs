@@ -0,0 +1,120 @@
+//! MCP request cancellation
+//!
+//! Clients send `notifications/cancelled` with the `requestId` of a request
+//! they no longer need. The stdio transport reads stdin on a separate thread
+//! so the notification is seen while the request is still running; the
+//! request's [`CancellationToken`] then stops the engine between files and
+//! the tool answers with error [`REQUEST_CANCELLED`](super::REQUEST_CANCELLED)
+//! and partial stats.
+//!
+//! A cancellation that arrives before its request starts is remembered, so
+//! the request is cancelled as soon as it begins.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+use crate::core::CancellationToken;
+
+/// Early cancellations remembered for requests not yet started
+const MAX_PENDING: usize = 64;
+
+#[derive(Debug, Default)]
+struct State {
+    /// Request being handled and its token
+    current: Option<(String, CancellationToken)>,
+    /// Cancelled ids whose requests have not started yet
+    pending: VecDeque<String>,
+}
+
+/// Tracks the request in flight so it can be cancelled from another thread
+#[derive(Debug, Default)]
+pub struct InFlight {
+    state: Mutex<State>,
+}
+
+impl InFlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start handling request `id`; the token may already be cancelled
+    pub fn begin(&self, id: &Value) -> CancellationToken {
+        let key = id.to_string();
+        let token = CancellationToken::new();
+        let mut state = self.state();
+        if let Some(pos) = state.pending.iter().position(|p| *p == key) {
+            state.pending.remove(pos);
+            token.cancel();
+        }
+        state.current = Some((key, token.clone()));
+        token
+    }
+
+    /// Finish the current request
+    pub fn finish(&self) {
+        self.state().current = None;
+    }
+
+    /// Cancel request `id`; returns true if it was running
+    pub fn cancel(&self, id: &Value) -> bool {
+        let key = id.to_string();
+        let mut state = self.state();
+        if let Some((current, token)) = &state.current {
+            if *current == key {
+                token.cancel();
+                return true;
+            }
+        }
+        if state.pending.len() == MAX_PENDING {
+            state.pending.pop_front();
+        }
+        state.pending.push_back(key);
+        false
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// `requestId` of a `notifications/cancelled` line, if it is one
+pub fn cancelled_request_id(line: &str) -> Option<Value> {
+    let message: Value = serde_json::from_str(line).ok()?;
+    if message.get("method")?.as_str()? != "notifications/cancelled" {
+        return None;
+    }
+    message.get("params")?.get("requestId").cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_cancel_running_and_pending() {
+        let in_flight = InFlight::new();
+        let token = in_flight.begin(&json!(1));
+        assert!(!in_flight.cancel(&json!("1")), "ids are compared as JSON values");
+        assert!(!token.is_cancelled());
+        assert!(in_flight.cancel(&json!(1)));
+        assert!(token.is_cancelled());
+        in_flight.finish();
+
+        // Cancelled before it started
+        assert!(!in_flight.cancel(&json!(2)));
+        assert!(in_flight.begin(&json!(2)).is_cancelled());
+        in_flight.finish();
+        assert!(!in_flight.begin(&json!(2)).is_cancelled(), "pending entries are consumed");
+    }
+
+    #[test]
+    fn test_cancelled_request_id() {
+        let line = r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":7,"reason":"user"}}"#;
+        assert_eq!(cancelled_request_id(line), Some(json!(7)));
+        assert_eq!(cancelled_request_id(r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#), None);
+        assert_eq!(cancelled_request_id("not json"), None);
+    }
+}
@@ -0,0 +1,146 @@
+//! What this build and server can do, reported up front
+//!
+//! Clients otherwise discover a missing language adapter, a feature left
+//! out of the build or a rate limit by making calls that fail. The
+//! `capabilities` MCP tool and `--capabilities` report it all at once:
+//!
+//! ```json
+//! {"version":"…","ir_version":"v1",
+//!  "languages":[{"language":"Rust","extension":"rs","capabilities":["declarations",…]}],
+//!  "features":{"plugins":false,"temporal":true,…},
+//!  "lenses":["architecture",…],
+//!  "limits":{"max_file_size":5242880,"page_tokens":25000,"jobs":{"max_jobs":8,…}}}
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use voyager_ast::AdapterCapabilities;
+
+use super::limits::ServerLimits;
+use super::pagination::DEFAULT_PAGE_TOKENS;
+use crate::core::AstBridge;
+use crate::{EncoderConfig, LensManager};
+
+/// Cargo features a client may depend on, and whether this build has them
+const FEATURES: &[(&str, bool)] = &[
+    ("async", cfg!(feature = "async")),
+    ("github", cfg!(feature = "github")),
+    ("grpc", cfg!(feature = "grpc")),
+    ("mcp", cfg!(feature = "mcp")),
+    ("osv", cfg!(feature = "osv")),
+    ("plugins", cfg!(feature = "plugins")),
+    ("temporal", cfg!(feature = "temporal")),
+    ("wasm", cfg!(feature = "wasm")),
+    ("zstd", cfg!(feature = "zstd")),
+];
+
+/// Self-description of the build, its analysis and its limits
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Capabilities {
+    /// Crate version
+    pub version: String,
+    /// Version of the voyager-ast IR served in indexes and schemas
+    pub ir_version: String,
+    /// Languages with AST analysis and what each adapter extracts
+    pub languages: Vec<LanguageSupport>,
+    /// Optional features, enabled or not in this build
+    pub features: BTreeMap<String, bool>,
+    /// Lens names accepted by `lens` arguments
+    pub lenses: Vec<String>,
+    pub limits: Limits,
+}
+
+/// One language adapter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LanguageSupport {
+    pub language: String,
+    pub extension: String,
+    pub capabilities: AdapterCapabilities,
+}
+
+/// Limits requests run into
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Limits {
+    /// Larger files are skipped (bytes)
+    pub max_file_size: u64,
+    /// Default page size of paginated get_context results (tokens)
+    pub page_tokens: usize,
+    /// Job and rate limits (absent: unlimited)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<JobLimits>,
+}
+
+/// [`ServerLimits`] in wire units
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JobLimits {
+    pub max_jobs: usize,
+    pub max_queue: usize,
+    pub queue_timeout_secs: u64,
+    /// Per client (0 = unlimited)
+    pub requests_per_minute: u32,
+}
+
+impl Capabilities {
+    /// Capabilities of this build, under `limits` when they are enforced
+    pub fn detect(limits: Option<&ServerLimits>) -> Self {
+        let bridge = AstBridge::new();
+        let mut languages: Vec<LanguageSupport> = bridge
+            .languages()
+            .into_iter()
+            .map(|language| LanguageSupport {
+                language: language.name().to_string(),
+                extension: language.extension().to_string(),
+                capabilities: bridge.capabilities(language),
+            })
+            .collect();
+        languages.sort_by(|a, b| a.language.cmp(&b.language));
+
+        Self {
+            version: crate::VERSION.to_string(),
+            ir_version: voyager_ast::IR_VERSION.to_string(),
+            languages,
+            features: FEATURES.iter().map(|(name, enabled)| (name.to_string(), *enabled)).collect(),
+            lenses: LensManager::new().available_lenses(),
+            limits: Limits {
+                max_file_size: EncoderConfig::default().max_file_size,
+                page_tokens: DEFAULT_PAGE_TOKENS,
+                jobs: limits.map(|l| JobLimits {
+                    max_jobs: l.max_jobs,
+                    max_queue: l.max_queue,
+                    queue_timeout_secs: l.queue_timeout.as_secs(),
+                    requests_per_minute: l.requests_per_minute,
+                }),
+            },
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_detect_reports_adapters_features_and_limits() {
+        let capabilities = Capabilities::detect(None);
+        assert_eq!(capabilities.version, crate::VERSION);
+        assert_eq!(capabilities.ir_version, voyager_ast::IR_VERSION);
+        let rust = capabilities.languages.iter().find(|l| l.language == "Rust").unwrap();
+        assert_eq!(rust.extension, "rs");
+        assert!(rust.capabilities.supports(voyager_ast::Capability::Declarations));
+        assert_eq!(capabilities.features["temporal"], cfg!(feature = "temporal"));
+        assert!(capabilities.lenses.contains(&"architecture".to_string()));
+        assert!(capabilities.limits.jobs.is_none());
+
+        let limits = ServerLimits { max_jobs: 2, max_queue: 4, queue_timeout: Duration::from_secs(10), requests_per_minute: 60 };
+        let json: serde_json::Value = serde_json::from_str(&Capabilities::detect(Some(&limits)).to_json()).unwrap();
+        assert_eq!(json["limits"]["jobs"]["queue_timeout_secs"], 10);
+        assert_eq!(json["limits"]["page_tokens"], DEFAULT_PAGE_TOKENS);
+        assert!(json["languages"][0]["capabilities"].is_array());
+    }
+}
@@ -0,0 +1,545 @@
+//! Daemon mode: JSON-RPC over a Unix domain socket
+//!
+//! Keeps a [`WarmIndex`] of the project in memory so editor plugins and
+//! scripts get millisecond responses instead of a cold scan per invocation.
+//!
+//! # Protocol
+//! - JSON-RPC 2.0, one request per line, one response per line
+//! - Any number of clients; each connection may send many requests
+//! - Daemon methods:
+//!   - `ping` → version, root and indexed file count
+//!   - `stats` → statistics from the last full index
+//!   - `refresh` → re-index the project
+//!   - `outline` `{path}` → declarations of one file (re-parsed if changed)
+//!   - `search` `{query, kind?, limit?}` → matching declarations
+//!   - `shutdown` → stop the daemon
+//! - Editor methods (see [`editor`](super::editor); lines are 1-indexed):
+//!   - `editor/outline` `{path}` → outline tree with reveal lines
+//!   - `editor/enclosing` `{path, start_line, end_line?}` → declarations
+//!     enclosing a selection, outermost first
+//!   - `editor/selectionContext` `{path, start_line, end_line?, expand?}` →
+//!     context of the selection, expanded to its enclosing declaration
+//!   - `editor/fileContext` `{path, lens?, format?}` → context of one file
+//!     under a lens (default: architecture)
+//! - Everything else (`initialize`, `tools/list`, `tools/call`) is handled
+//!   by the MCP server, so all MCP tools are available as well
+//! - Daemon, MCP and index activity share one [`Metrics`], which
+//!   `--metrics-addr` exposes over HTTP
+//! - Each connection is one client for rate limiting; `refresh`, the
+//!   editor context methods and `tools/call` take a job slot (see
+//!   [`limits`](super::limits)). Rejected requests get error `-32000`.
+//!
+//! # Usage
+//! ```bash
+//! pm_encoder --daemon /path/to/project          # .pm_encoder/daemon.sock
+//! echo '{"jsonrpc":"2.0","id":1,"method":"search","params":{"query":"main"}}' \
+//!   | nc -U /path/to/project/.pm_encoder/daemon.sock
+//! ```
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde_json::{json, Value};
+
+use super::editor::{self, DEFAULT_EDITOR_LENS};
+use super::index::WarmIndex;
+use crate::core::BlobStore;
+use super::limits::{Limiter, ServerLimits};
+use super::metrics::Metrics;
+use super::prefetch::PrefetchConfig;
+use super::{
+    JsonRpcRequest, JsonRpcResponse, McpServer, INTERNAL_ERROR, INVALID_PARAMS, INVALID_REQUEST,
+    PARSE_ERROR,
+};
+
+/// Methods that run long enough to need a job slot
+const JOB_METHODS: &[&str] = &["refresh", "editor/selectionContext", "editor/fileContext", "tools/call"];
+
+/// Default socket location for a project
+pub fn default_socket_path(project_root: &Path) -> PathBuf {
+    project_root.join(".pm_encoder").join("daemon.sock")
+}
+
+/// Long-running server state shared by all connections
+pub struct Daemon {
+    index: Mutex<WarmIndex>,
+    mcp: Mutex<McpServer>,
+    stopping: AtomicBool,
+    metrics: Arc<Metrics>,
+    limiter: Limiter,
+    /// Source of per-connection client ids
+    connections: AtomicU64,
+}
+
+impl Daemon {
+    /// Index `project_root` and prepare to serve it
+    pub fn new(project_root: PathBuf) -> Result<Self, String> {
+        Self::open(project_root, None)
+    }
+
+    /// Like [`new`](Self::new), caching parses and contexts in `blobs`
+    pub fn open(project_root: PathBuf, blobs: Option<BlobStore>) -> Result<Self, String> {
+        let metrics = Arc::new(Metrics::new());
+        let index = WarmIndex::build_with(&project_root, Arc::clone(&metrics), blobs.clone())?;
+        let mut mcp = McpServer::new(project_root).with_metrics(Arc::clone(&metrics));
+        if let Some(blobs) = blobs {
+            mcp = mcp.with_blob_store(blobs);
+        }
+        Ok(Self {
+            index: Mutex::new(index),
+            mcp: Mutex::new(mcp),
+            stopping: AtomicBool::new(false),
+            metrics,
+            limiter: Limiter::default(),
+            connections: AtomicU64::new(0),
+        })
+    }
+
+    /// Replace the default job and rate limits
+    pub fn with_limits(mut self, limits: ServerLimits) -> Self {
+        self.limiter = Limiter::new(limits);
+        self
+    }
+
+    /// Prefetch the zoom affordances of served contexts
+    pub fn with_prefetch(self, config: PrefetchConfig) -> Self {
+        let mcp = self.mcp.into_inner().unwrap_or_else(|e| e.into_inner()).with_prefetch(config);
+        Self { mcp: Mutex::new(mcp), ..self }
+    }
+
+    /// Counters for this daemon (including its MCP tools)
+    pub fn metrics(&self) -> &Arc<Metrics> {
+        &self.metrics
+    }
+
+    /// Listen on `socket_path` until a `shutdown` request arrives (blocking)
+    ///
+    /// A stale socket file left by a crashed daemon is replaced; a socket
+    /// with a live daemon behind it is an `AddrInUse` error.
+    pub fn run(self: Arc<Self>, socket_path: &Path) -> io::Result<()> {
+        if socket_path.exists() {
+            if UnixStream::connect(socket_path).is_ok() {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("A daemon is already listening on {}", socket_path.display()),
+                ));
+            }
+            std::fs::remove_file(socket_path)?;
+        }
+        if let Some(parent) = socket_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let listener = UnixListener::bind(socket_path)?;
+        // The socket exposes project contents: owner only
+        std::fs::set_permissions(socket_path, std::fs::Permissions::from_mode(0o600))?;
+        eprintln!("[daemon] Listening on {}", socket_path.display());
+
+        for stream in listener.incoming() {
+            if self.stopping.load(Ordering::SeqCst) {
+                break;
+            }
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    eprintln!("[daemon] Accept failed: {}", e);
+                    continue;
+                }
+            };
+            let daemon = Arc::clone(&self);
+            let socket_path = socket_path.to_path_buf();
+            std::thread::spawn(move || {
+                if let Err(e) = daemon.serve_connection(stream) {
+                    eprintln!("[daemon] Connection error: {}", e);
+                }
+                if daemon.stopping.load(Ordering::SeqCst) {
+                    // Wake the accept loop so it can observe the flag
+                    let _ = UnixStream::connect(&socket_path);
+                }
+            });
+        }
+
+        let _ = std::fs::remove_file(socket_path);
+        eprintln!("[daemon] Shut down");
+        Ok(())
+    }
+
+    fn serve_connection(&self, stream: UnixStream) -> io::Result<()> {
+        let client = format!("conn-{}", self.connections.fetch_add(1, Ordering::Relaxed));
+        let mut writer = stream.try_clone()?;
+        for line in BufReader::new(stream).lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_line(&client, &line) {
+                let response_str = serde_json::to_string(&response).unwrap_or_else(|e| {
+                    serde_json::to_string(&JsonRpcResponse::error(
+                        Value::Null,
+                        INTERNAL_ERROR,
+                        format!("Serialization error: {}", e),
+                    ))
+                    .unwrap()
+                });
+                writeln!(writer, "{}", response_str)?;
+                writer.flush()?;
+            }
+            if self.stopping.load(Ordering::SeqCst) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Handle one request line from `client`. Returns None for notifications (no id).
+    fn handle_line(&self, client: &str, line: &str) -> Option<JsonRpcResponse> {
+        let request: JsonRpcRequest = match serde_json::from_str(line) {
+            Ok(r) => r,
+            Err(e) => {
+                return Some(JsonRpcResponse::error(Value::Null, PARSE_ERROR, format!("Parse error: {}", e)));
+            }
+        };
+        if request.jsonrpc != "2.0" {
+            return Some(JsonRpcResponse::error(
+                request.id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Invalid JSON-RPC version".to_string(),
+            ));
+        }
+
+        let is_notification = request.id.is_none();
+        let id = request.id.clone().unwrap_or(Value::Null);
+        let params = request.params.clone().unwrap_or(Value::Null);
+
+        let method = request.method.as_str();
+
+        // Liveness and shutdown must work even when the daemon is saturated
+        let admitted = match method {
+            "ping" | "shutdown" => Ok(None),
+            _ if JOB_METHODS.contains(&method) => self.limiter.admit(client).map(Some),
+            _ => self.limiter.check_rate(client).map(|()| None),
+        };
+        let _permit = match admitted {
+            Ok(permit) => permit,
+            Err(busy) => {
+                let response = JsonRpcResponse::busy(id, &busy);
+                self.metrics.record_rejected(busy.reason());
+                return (!is_notification).then_some(response);
+            }
+        };
+
+        let response = match method {
+            "ping" => self.method_ping(id),
+            "stats" => JsonRpcResponse::success(id, json!(self.index().stats())),
+            "refresh" => self.method_refresh(id),
+            "outline" => self.method_outline(id, &params),
+            "search" => self.method_search(id, &params),
+            "editor/outline" => self.method_editor_outline(id, &params),
+            "editor/enclosing" => self.method_editor_enclosing(id, &params),
+            "editor/selectionContext" => self.method_editor_selection(id, &params),
+            "editor/fileContext" => self.method_editor_file(id, &params),
+            "shutdown" => {
+                self.stopping.store(true, Ordering::SeqCst);
+                JsonRpcResponse::success(id, json!({}))
+            }
+            // MCP methods share the daemon's connection
+            _ => return lock(&self.mcp).handle_request(line),
+        };
+        // Only reached for daemon methods, so `method` is a known name
+        self.metrics.record_request(method, None, response.result.as_ref(), response.error.is_some());
+
+        if is_notification {
+            None
+        } else {
+            Some(response)
+        }
+    }
+
+    fn index(&self) -> MutexGuard<'_, WarmIndex> {
+        lock(&self.index)
+    }
+
+    fn method_ping(&self, id: Value) -> JsonRpcResponse {
+        let index = self.index();
+        JsonRpcResponse::success(id, json!({
+            "version": crate::version(),
+            "root": index.root().display().to_string(),
+            "files": index.model().files.len(),
+        }))
+    }
+
+    fn method_refresh(&self, id: Value) -> JsonRpcResponse {
+        let mut index = self.index();
+        match index.refresh() {
+            Ok(()) => JsonRpcResponse::success(id, json!(index.stats())),
+            Err(e) => JsonRpcResponse::error(id, INTERNAL_ERROR, e),
+        }
+    }
+
+    fn method_outline(&self, id: Value, params: &Value) -> JsonRpcResponse {
+        let Some(path) = params.get("path").and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing 'path' parameter".to_string());
+        };
+
+        let mut index = self.index();
+        match index.file(path) {
+            Some(file) => JsonRpcResponse::success(id, json!({
+                "path": file.path,
+                "language": file.language.name(),
+                "declarations": file.declarations,
+            })),
+            None => JsonRpcResponse::error(
+                id,
+                INVALID_PARAMS,
+                format!("No parseable source file at '{}'", path),
+            ),
+        }
+    }
+
+    fn method_search(&self, id: Value, params: &Value) -> JsonRpcResponse {
+        let Some(query) = params.get("query").and_then(|v| v.as_str()).filter(|q| !q.is_empty()) else {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing 'query' parameter".to_string());
+        };
+        let kind = params.get("kind").and_then(|v| v.as_str());
+        let limit = params.get("limit").and_then(|v| v.as_u64()).unwrap_or(50) as usize;
+
+        let index = self.index();
+        let matches: Vec<Value> = index.search(query, kind, limit)
+            .into_iter()
+            .map(|m| json!({
+                "path": m.path,
+                "name": m.declaration.name,
+                "kind": m.declaration.kind.as_str(),
+                "start_line": m.declaration.span.start_line,
+                "end_line": m.declaration.span.end_line,
+            }))
+            .collect();
+        JsonRpcResponse::success(id, json!(matches))
+    }
+
+    fn method_editor_outline(&self, id: Value, params: &Value) -> JsonRpcResponse {
+        let Some(path) = params.get("path").and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing 'path' parameter".to_string());
+        };
+        let mut index = self.index();
+        match index.file(path) {
+            Some(file) => JsonRpcResponse::success(id, json!({
+                "path": file.path,
+                "language": file.language.name(),
+                "symbols": editor::outline(file),
+            })),
+            None => JsonRpcResponse::error(id, INVALID_PARAMS, format!("No parseable source file at '{}'", path)),
+        }
+    }
+
+    fn method_editor_enclosing(&self, id: Value, params: &Value) -> JsonRpcResponse {
+        let (path, start_line, end_line) = match selection_params(params) {
+            Ok(selection) => selection,
+            Err(e) => return JsonRpcResponse::error(id, INVALID_PARAMS, e),
+        };
+        let mut index = self.index();
+        match index.file(path) {
+            Some(file) => {
+                let chain = editor::enclosing_declarations(file, start_line, end_line);
+                JsonRpcResponse::success(id, json!({
+                    "path": file.path,
+                    "chain": editor::breadcrumbs(&chain),
+                }))
+            }
+            None => JsonRpcResponse::error(id, INVALID_PARAMS, format!("No parseable source file at '{}'", path)),
+        }
+    }
+
+    fn method_editor_selection(&self, id: Value, params: &Value) -> JsonRpcResponse {
+        let (path, start_line, end_line) = match selection_params(params) {
+            Ok(selection) => selection,
+            Err(e) => return JsonRpcResponse::error(id, INVALID_PARAMS, e),
+        };
+        let expand = params.get("expand").and_then(|v| v.as_bool()).unwrap_or(true);
+
+        let mut index = self.index();
+        let root = index.root().to_path_buf();
+        let Some(file) = index.file(path) else {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, format!("No parseable source file at '{}'", path));
+        };
+        match editor::selection_context(&root, file, start_line, end_line, expand) {
+            Ok(selection) => JsonRpcResponse::success(id, json!(selection)),
+            Err(e) => JsonRpcResponse::error(id, INTERNAL_ERROR, e),
+        }
+    }
+
+    fn method_editor_file(&self, id: Value, params: &Value) -> JsonRpcResponse {
+        let Some(path) = params.get("path").and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing 'path' parameter".to_string());
+        };
+        let lens = params.get("lens").and_then(|v| v.as_str()).unwrap_or(DEFAULT_EDITOR_LENS);
+        let format = params.get("format").and_then(|v| v.as_str()).unwrap_or("plusminus");
+        let key = crate::core::normalize_relative_path(path);
+
+        let root = self.index().root().to_path_buf();
+        match editor::file_context(&root, &key, lens, format) {
+            Ok(context) => JsonRpcResponse::success(id, json!({
+                "path": key,
+                "lens": lens,
+                "context": context,
+            })),
+            Err(e) => JsonRpcResponse::error(id, INVALID_PARAMS, e),
+        }
+    }
+}
+
+/// `{path, start_line, end_line?}` from editor request params
+fn selection_params(params: &Value) -> Result<(&str, usize, usize), String> {
+    let path = params.get("path").and_then(|v| v.as_str())
+        .ok_or_else(|| "Missing 'path' parameter".to_string())?;
+    let start_line = params.get("start_line").and_then(|v| v.as_u64())
+        .filter(|&l| l > 0)
+        .ok_or_else(|| "Missing 'start_line' parameter (1-indexed)".to_string())? as usize;
+    let end_line = params.get("end_line").and_then(|v| v.as_u64())
+        .map_or(start_line, |l| l as usize);
+    Ok((path, start_line, end_line))
+}
+
+/// Lock a mutex, recovering the data if a handler thread panicked
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn sample_daemon() -> (TempDir, Daemon) {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("lib.rs"), "pub fn alpha() {}\n\npub struct Beta;\n").unwrap();
+        let daemon = Daemon::new(temp.path().to_path_buf()).unwrap();
+        (temp, daemon)
+    }
+
+    fn call(daemon: &Daemon, method: &str, params: Value) -> Value {
+        let line = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params}).to_string();
+        serde_json::to_value(daemon.handle_line("test", &line).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_ping_and_search() {
+        let (_temp, daemon) = sample_daemon();
+        assert_eq!(call(&daemon, "ping", Value::Null)["result"]["files"], 1);
+
+        let result = call(&daemon, "search", json!({"query": "alp"}));
+        let matches = result["result"].as_array().unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0]["path"], "lib.rs");
+        assert_eq!(matches[0]["kind"], "function");
+
+        let missing = call(&daemon, "search", json!({}));
+        assert_eq!(missing["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_outline() {
+        let (_temp, daemon) = sample_daemon();
+        let result = call(&daemon, "outline", json!({"path": "lib.rs"}));
+        let names: Vec<&str> = result["result"]["declarations"].as_array().unwrap()
+            .iter()
+            .map(|d| d["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["alpha", "Beta"]);
+
+        let missing = call(&daemon, "outline", json!({"path": "nope.rs"}));
+        assert_eq!(missing["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_editor_methods() {
+        let (_temp, daemon) = sample_daemon();
+
+        let outline = call(&daemon, "editor/outline", json!({"path": "lib.rs"}));
+        assert_eq!(outline["result"]["symbols"][0]["name"], "alpha");
+
+        let enclosing = call(&daemon, "editor/enclosing", json!({"path": "lib.rs", "start_line": 1}));
+        assert_eq!(enclosing["result"]["chain"][0]["name"], "alpha");
+
+        let selection = call(&daemon, "editor/selectionContext", json!({"path": "lib.rs", "start_line": 1}));
+        assert_eq!(selection["result"]["declaration"]["name"], "alpha");
+        assert!(selection["result"]["context"].as_str().unwrap().contains("pub fn alpha"));
+
+        let file = call(&daemon, "editor/fileContext", json!({"path": "./lib.rs"}));
+        assert_eq!(file["result"]["lens"], "architecture");
+        assert!(file["result"]["context"].as_str().unwrap().contains("lib.rs"));
+
+        let missing = call(&daemon, "editor/enclosing", json!({"path": "lib.rs"}));
+        assert_eq!(missing["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_mcp_methods_are_delegated() {
+        let (_temp, daemon) = sample_daemon();
+        let result = call(&daemon, "tools/list", Value::Null);
+        assert!(!result["result"]["tools"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rate_limit_per_connection() {
+        let (_temp, daemon) = sample_daemon();
+        let daemon = daemon.with_limits(ServerLimits { requests_per_minute: 1, ..Default::default() });
+        let search = json!({"jsonrpc": "2.0", "id": 1, "method": "search", "params": {"query": "alpha"}}).to_string();
+
+        assert!(daemon.handle_line("a", &search).unwrap().error.is_none());
+        let busy = serde_json::to_value(daemon.handle_line("a", &search).unwrap()).unwrap();
+        assert_eq!(busy["error"]["code"], crate::server::SERVER_BUSY);
+        assert_eq!(busy["error"]["data"]["reason"], "rate_limited");
+        assert!(daemon.handle_line("b", &search).unwrap().error.is_none());
+        // Liveness checks bypass the limits
+        assert!(daemon.handle_line("a", r#"{"jsonrpc":"2.0","id":2,"method":"ping"}"#).unwrap().error.is_none());
+        assert!(daemon.metrics().render().contains("pm_encoder_rejected_requests_total{reason=\"rate_limited\"} 1"));
+    }
+
+    #[test]
+    fn test_metrics_shared_with_mcp() {
+        let (_temp, daemon) = sample_daemon();
+        call(&daemon, "ping", Value::Null);
+        call(&daemon, "tools/list", Value::Null);
+        call(&daemon, "secret/project/path", Value::Null);
+
+        let text = daemon.metrics().render();
+        assert!(text.contains("pm_encoder_requests_total{method=\"ping\",tool=\"\"} 1"));
+        assert!(text.contains("pm_encoder_requests_total{method=\"tools/list\",tool=\"\"} 1"));
+        assert!(text.contains("pm_encoder_request_errors_total{method=\"other\",tool=\"\"} 1"));
+        assert!(!text.contains("secret"));
+    }
+
+    #[test]
+    fn test_socket_round_trip_and_shutdown() {
+        let (temp, daemon) = sample_daemon();
+        let socket = temp.path().join("d.sock");
+        let daemon = Arc::new(daemon);
+        let server = {
+            let socket = socket.clone();
+            std::thread::spawn(move || daemon.run(&socket))
+        };
+        while UnixStream::connect(&socket).is_err() {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+
+        let mut stream = UnixStream::connect(&socket).unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        for (id, method) in [(1, "ping"), (2, "shutdown")] {
+            writeln!(stream, "{}", json!({"jsonrpc": "2.0", "id": id, "method": method})).unwrap();
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let response: Value = serde_json::from_str(&line).unwrap();
+            assert_eq!(response["id"], id);
+            assert!(response.get("error").is_none());
+        }
+
+        server.join().unwrap().unwrap();
+        assert!(!socket.exists());
+    }
+}
@@ -0,0 +1,252 @@
+//! Editor integration helpers
+//!
+//! Backs the `editor/*` daemon methods so an editor extension (e.g. VS Code)
+//! can offer outline views, "copy context of selection" and "copy context of
+//! current file" without reimplementing traversal or lens handling.
+//!
+//! All line numbers are 1-indexed and inclusive, matching the IR spans;
+//! editors using 0-indexed positions must add one.
+
+use std::path::Path;
+use std::time::SystemTime;
+
+use serde::Serialize;
+use voyager_ast::{Declaration, File};
+
+use crate::core::{ContextEngine, ProjectPaths, ZoomConfig, ZoomDepth, ZoomTarget};
+use crate::{
+    calculate_md5, read_file_content, serialize_file_with_format, FileEntry, LensManager,
+    OutputFormat,
+};
+
+/// Lens used by "copy context of current file" when none is given
+pub const DEFAULT_EDITOR_LENS: &str = "architecture";
+
+/// One node of a file outline
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OutlineNode {
+    pub name: String,
+    pub kind: &'static str,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Line to reveal/select in the editor (the signature's first line)
+    pub selection_line: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<OutlineNode>,
+}
+
+impl OutlineNode {
+    fn from_declaration(declaration: &Declaration, with_children: bool) -> Self {
+        Self {
+            name: declaration.name.clone(),
+            kind: declaration.kind.as_str(),
+            start_line: declaration.span.start_line,
+            end_line: declaration.span.end_line,
+            selection_line: declaration.signature_span
+                .as_ref()
+                .map_or(declaration.span.start_line, |s| s.start_line),
+            children: if with_children {
+                declaration.children.iter().map(|c| Self::from_declaration(c, true)).collect()
+            } else {
+                Vec::new()
+            },
+        }
+    }
+}
+
+/// Outline tree of a parsed file
+pub fn outline(file: &File) -> Vec<OutlineNode> {
+    file.declarations.iter().map(|d| OutlineNode::from_declaration(d, true)).collect()
+}
+
+/// Declarations enclosing the selection, outermost first
+///
+/// A declaration encloses the selection when its span covers every selected
+/// line. The last element is the innermost one (e.g. the method, not its
+/// class); an empty result means the selection is at file level.
+pub fn enclosing_declarations(file: &File, start_line: usize, end_line: usize) -> Vec<&Declaration> {
+    let (start_line, end_line) = (start_line.min(end_line), start_line.max(end_line));
+    let mut chain = Vec::new();
+    let mut level = &file.declarations;
+    while let Some(found) = level
+        .iter()
+        .find(|d| d.span.start_line <= start_line && end_line <= d.span.end_line)
+    {
+        chain.push(found);
+        level = &found.children;
+    }
+    chain
+}
+
+/// Breadcrumb nodes (no children) for an enclosing chain
+pub fn breadcrumbs(chain: &[&Declaration]) -> Vec<OutlineNode> {
+    chain.iter().map(|d| OutlineNode::from_declaration(d, false)).collect()
+}
+
+/// Result of "copy context of selection"
+#[derive(Debug, Clone, Serialize)]
+pub struct SelectionContext {
+    pub path: String,
+    /// Innermost declaration the selection was expanded to, if any
+    pub declaration: Option<OutlineNode>,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub context: String,
+}
+
+/// Context for a selection, expanded to its innermost enclosing declaration
+///
+/// With `expand` false (or a file-level selection) the selected lines are
+/// used as-is. The content comes from a file-range zoom, so it carries the
+/// same headers as the `zoom` tool.
+pub fn selection_context(
+    root: &Path,
+    file: &File,
+    start_line: usize,
+    end_line: usize,
+    expand: bool,
+) -> Result<SelectionContext, String> {
+    // Indexed files carry their absolute path; zoom targets are root-relative
+    let path = ProjectPaths::new(root).relative(file.path.as_path());
+    let chain = if expand { enclosing_declarations(file, start_line, end_line) } else { Vec::new() };
+    let declaration = chain.last().map(|d| OutlineNode::from_declaration(d, false));
+    let (start_line, end_line) = match &declaration {
+        Some(node) => (node.start_line, node.end_line),
+        None => (start_line.min(end_line), start_line.max(end_line)),
+    };
+
+    let config = ZoomConfig {
+        target: ZoomTarget::File {
+            path: path.clone(),
+            start_line: Some(start_line),
+            end_line: Some(end_line),
+        },
+        budget: None,
+        depth: ZoomDepth::Full,
+        include_tests: false,
+        include_docs: false,
+        context_lines: 0,
+    };
+    let context = ContextEngine::new()
+        .zoom(root.to_str().unwrap_or("."), &config)
+        .map_err(|e| format!("Zoom failed: {}", e))?;
+
+    Ok(SelectionContext { path, declaration, start_line, end_line, context })
+}
+
+/// Context for one file as it would appear under `lens`
+///
+/// Uses the lens's truncation settings (including per-group overrides), so
+/// the architecture lens yields the structural skeleton. The lens's include
+/// and exclude patterns are not applied: the user asked for this file.
+pub fn file_context(root: &Path, key: &str, lens: &str, format: &str) -> Result<String, String> {
+    let format = OutputFormat::parse(format)?;
+    let mut lens_manager = LensManager::new();
+    let applied = lens_manager.apply_lens(lens)?;
+    let group = lens_manager.get_file_group_config(Path::new(key));
+    let truncate_mode = group.truncate_mode.unwrap_or(applied.truncate_mode);
+    let truncate_lines = group.truncate.unwrap_or(applied.truncate_lines);
+
+    let full_path = root.join(voyager_ast::RawPath::from_key(key).as_path());
+    let bytes = std::fs::read(voyager_ast::extended_length_path(&full_path))
+        .map_err(|e| format!("Failed to read '{}': {}", key, e))?;
+    let content = read_file_content(&bytes)
+        .ok_or_else(|| format!("'{}' is not a text file", key))?;
+    let mtime = std::fs::metadata(&full_path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+
+    let entry = FileEntry {
+        path: key.to_string(),
+        md5: calculate_md5(&content),
+        size: content.len() as u64,
+        content,
+        mtime,
+        ctime: mtime,
+    };
+    Ok(serialize_file_with_format(&entry, truncate_lines, &truncate_mode, format))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+    use voyager_ast::{AstProvider, LanguageId, TreeSitterProvider};
+
+    const SOURCE: &str = "\
+pub struct Counter {
+    value: u32,
+}
+
+impl Counter {
+    pub fn bump(&mut self) {
+        self.value += 1;
+    }
+}
+
+pub fn standalone() {}
+";
+
+    fn parsed(key: &str) -> File {
+        let mut file = TreeSitterProvider::new().parse_file(SOURCE, LanguageId::Rust).unwrap();
+        file.path = key.into();
+        file
+    }
+
+    #[test]
+    fn test_enclosing_innermost_declaration() {
+        let file = parsed("src/lib.rs");
+
+        let chain = enclosing_declarations(&file, 7, 7);
+        assert_eq!(chain.last().unwrap().name, "bump");
+        assert!(chain.len() >= 2, "method should be nested in its impl");
+
+        assert_eq!(enclosing_declarations(&file, 11, 11).last().unwrap().name, "standalone");
+        assert!(enclosing_declarations(&file, 4, 4).is_empty());
+        // Selections spanning two declarations resolve to neither
+        assert!(enclosing_declarations(&file, 2, 11).is_empty());
+    }
+
+    #[test]
+    fn test_outline_tree() {
+        let file = parsed("src/lib.rs");
+        let nodes = outline(&file);
+        let names: Vec<&str> = nodes.iter().map(|n| n.name.as_str()).collect();
+        assert!(names.contains(&"Counter"));
+        assert!(names.contains(&"standalone"));
+        assert!(nodes.iter().any(|n| n.children.iter().any(|c| c.name == "bump")));
+    }
+
+    #[test]
+    fn test_selection_context_expands_to_declaration() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        fs::write(temp.path().join("src/lib.rs"), SOURCE).unwrap();
+        let file = parsed("src/lib.rs");
+
+        let expanded = selection_context(temp.path(), &file, 7, 7, true).unwrap();
+        assert_eq!(expanded.declaration.as_ref().unwrap().name, "bump");
+        assert_eq!((expanded.start_line, expanded.end_line), (6, 8));
+        assert!(expanded.context.contains("pub fn bump"));
+
+        let literal = selection_context(temp.path(), &file, 7, 7, false).unwrap();
+        assert!(literal.declaration.is_none());
+        assert_eq!((literal.start_line, literal.end_line), (7, 7));
+    }
+
+    #[test]
+    fn test_file_context_with_lens() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("lib.rs"), SOURCE).unwrap();
+
+        let context = file_context(temp.path(), "lib.rs", DEFAULT_EDITOR_LENS, "plusminus").unwrap();
+        assert!(context.contains("lib.rs"));
+        assert!(context.contains("pub fn standalone"));
+
+        assert!(file_context(temp.path(), "lib.rs", "no-such-lens", "plusminus").is_err());
+        assert!(file_context(temp.path(), "missing.rs", DEFAULT_EDITOR_LENS, "plusminus").is_err());
+    }
+}
@@ -0,0 +1,489 @@
+//! gRPC service (feature `grpc`)
+//!
+//! Serves the index, zoom, context and search APIs over gRPC for backend
+//! services that embed pm_encoder as an internal microservice. The service
+//! definition lives in `proto/pm_encoder.proto`.
+//!
+//! Index, context and search results are streamed: the work runs on the
+//! blocking pool and messages are forwarded as they are produced, so a client
+//! that disconnects stops the stream early.
+//!
+//! Every RPC counts against the caller's rate (keyed by peer IP) and runs in
+//! a job slot (see [`limits`](super::limits)); rejections are
+//! `RESOURCE_EXHAUSTED`.
+//!
+//! # Usage
+//! ```bash
+//! cargo build --features grpc --bin pm_encoder_grpc
+//! pm_encoder_grpc /path/to/project --addr 127.0.0.1:50051
+//! ```
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+use voyager_ast::{AstProvider, Declaration, File, IndexOptions, LanguageId, TreeSitterProvider};
+
+use super::index::WarmIndex;
+use super::limits::{Busy, JobPermit, Limiter, ServerLimits};
+use super::{ContextOptions, McpServer};
+use crate::core::{ContextEngine, EncoderError, PathSandbox, ZoomConfig, ZoomDepth};
+
+/// Generated protobuf types and service traits
+pub mod proto {
+    tonic::include_proto!("pm_encoder.v1");
+}
+
+use proto::pm_encoder_server::{PmEncoder, PmEncoderServer};
+use proto::{
+    ContextChunk, GenerateContextRequest, IndexProjectRequest, IndexedFile, SearchMatch,
+    SearchRequest, Symbol, ZoomRequest, ZoomResponse,
+};
+
+/// Default listen address
+pub const DEFAULT_GRPC_ADDR: &str = "127.0.0.1:50051";
+
+/// Maximum size of one GenerateContext chunk in bytes
+pub const CONTEXT_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Messages buffered per stream before the producer waits for the client
+const STREAM_CAPACITY: usize = 32;
+
+type RpcStream<T> = ReceiverStream<Result<T, Status>>;
+
+/// gRPC service state
+#[derive(Debug, Clone)]
+pub struct GrpcService {
+    project_root: PathBuf,
+    limiter: Arc<Limiter>,
+}
+
+impl GrpcService {
+    /// Create a service rooted at `project_root`
+    pub fn new(project_root: PathBuf) -> Self {
+        Self { project_root, limiter: Arc::new(Limiter::default()) }
+    }
+
+    /// Replace the default job and rate limits
+    pub fn with_limits(mut self, limits: ServerLimits) -> Self {
+        self.limiter = Arc::new(Limiter::new(limits));
+        self
+    }
+
+    /// Wrap the service for `tonic::transport::Server::add_service`
+    pub fn into_server(self) -> PmEncoderServer<Self> {
+        PmEncoderServer::new(self)
+    }
+
+    /// Request path confined to the server root, or the server root when empty
+    fn root(&self, path: &str) -> Result<PathBuf, Status> {
+        if path.is_empty() {
+            return Ok(self.project_root.clone());
+        }
+        PathSandbox::new(&self.project_root)
+            .and_then(|sandbox| sandbox.resolve(path))
+            .map_err(|e| engine_status("Invalid path", e))
+    }
+
+    /// Count the request against its peer's rate; the limiter is returned
+    /// so the blocking work can take a job slot
+    fn admit<T>(&self, request: &Request<T>) -> Result<Arc<Limiter>, Status> {
+        let client = request.remote_addr().map_or_else(|| "unknown".to_string(), |a| a.ip().to_string());
+        self.limiter.check_rate(&client).map_err(|b| busy_status(&b))?;
+        Ok(Arc::clone(&self.limiter))
+    }
+}
+
+/// Serve the gRPC API on `addr` until the process is stopped
+pub async fn serve(
+    addr: SocketAddr,
+    project_root: PathBuf,
+    limits: ServerLimits,
+) -> Result<(), tonic::transport::Error> {
+    eprintln!("[gRPC] Listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(GrpcService::new(project_root).with_limits(limits).into_server())
+        .serve(addr)
+        .await
+}
+
+#[tonic::async_trait]
+impl PmEncoder for GrpcService {
+    type IndexProjectStream = RpcStream<IndexedFile>;
+    type GenerateContextStream = RpcStream<ContextChunk>;
+    type SearchStream = RpcStream<SearchMatch>;
+
+    async fn index_project(
+        &self,
+        request: Request<IndexProjectRequest>,
+    ) -> Result<Response<Self::IndexProjectStream>, Status> {
+        let limiter = self.admit(&request)?;
+        let req = request.into_inner();
+        let root = self.root(&req.path)?;
+        let languages = req.languages.iter()
+            .map(String::as_str)
+            .map(parse_language)
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = IndexOptions {
+            max_files: req.max_files as usize,
+            languages,
+            ..Default::default()
+        };
+
+        Ok(Response::new(spawn_stream(move |tx| {
+            let Some(_permit) = job_slot(&limiter, tx) else { return };
+            let model = match TreeSitterProvider::new().index_project(&root, &options) {
+                Ok(model) => model,
+                Err(e) => {
+                    let _ = tx.blocking_send(Err(Status::internal(format!("Indexing failed: {}", e))));
+                    return;
+                }
+            };
+            for (path, file) in &model.files {
+                if tx.blocking_send(Ok(indexed_file(&path.to_key(), file))).is_err() {
+                    return;
+                }
+            }
+        })))
+    }
+
+    async fn zoom(&self, request: Request<ZoomRequest>) -> Result<Response<ZoomResponse>, Status> {
+        let limiter = self.admit(&request)?;
+        let req = request.into_inner();
+        let root = self.root(&req.path)?;
+
+        let content = run_blocking(move || {
+            let _permit = limiter.acquire().map_err(|b| busy_status(&b))?;
+            let (target, _) = McpServer::resolve_zoom_target(&req.target, &root)
+                .map_err(Status::invalid_argument)?;
+            let config = ZoomConfig {
+                target,
+                budget: None,
+                depth: ZoomDepth::Full,
+                include_tests: false,
+                include_docs: false,
+                context_lines: 5,
+            };
+            ContextEngine::new()
+                .zoom(root.to_str().unwrap_or("."), &config)
+                .map_err(|e| engine_status("Zoom failed", e))
+        })
+        .await?;
+
+        Ok(Response::new(ZoomResponse { content }))
+    }
+
+    async fn generate_context(
+        &self,
+        request: Request<GenerateContextRequest>,
+    ) -> Result<Response<Self::GenerateContextStream>, Status> {
+        let limiter = self.admit(&request)?;
+        let req = request.into_inner();
+        let root = self.root(&req.path)?;
+        let options = ContextOptions {
+            lens: non_empty(&req.lens),
+            token_budget: non_empty(&req.token_budget),
+            format: non_empty(&req.format).unwrap_or("plusminus"),
+            skeleton: non_empty(&req.skeleton).unwrap_or("auto"),
+        };
+        let config = options.encoder_config()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        Ok(Response::new(spawn_stream(move |tx| {
+            let Some(_permit) = job_slot(&limiter, tx) else { return };
+            let engine = ContextEngine::with_config(config);
+            let context = match engine.serialize(root.to_str().unwrap_or(".")) {
+                Ok(context) => context,
+                Err(e) => {
+                    let _ = tx.blocking_send(Err(engine_status("Serialization failed", e)));
+                    return;
+                }
+            };
+            for chunk in context_chunks(&context, CONTEXT_CHUNK_BYTES) {
+                if tx.blocking_send(Ok(chunk)).is_err() {
+                    return;
+                }
+            }
+        })))
+    }
+
+    async fn search(
+        &self,
+        request: Request<SearchRequest>,
+    ) -> Result<Response<Self::SearchStream>, Status> {
+        let limiter = self.admit(&request)?;
+        let req = request.into_inner();
+        if req.query.is_empty() {
+            return Err(Status::invalid_argument("Missing 'query'"));
+        }
+        let root = self.root(&req.path)?;
+
+        Ok(Response::new(spawn_stream(move |tx| {
+            let Some(_permit) = job_slot(&limiter, tx) else { return };
+            let index = match WarmIndex::build(&root) {
+                Ok(index) => index,
+                Err(e) => {
+                    let _ = tx.blocking_send(Err(Status::internal(e)));
+                    return;
+                }
+            };
+            for found in index.search(&req.query, non_empty(&req.kind), req.limit as usize) {
+                let mut matched = symbol(found.declaration);
+                matched.children.clear();
+                let found = SearchMatch { path: found.path.to_key(), symbol: Some(matched) };
+                if tx.blocking_send(Ok(found)).is_err() {
+                    return;
+                }
+            }
+        })))
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// Run `produce` on the blocking pool, streaming whatever it sends
+fn spawn_stream<T, F>(produce: F) -> RpcStream<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&mpsc::Sender<Result<T, Status>>) + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(STREAM_CAPACITY);
+    tokio::task::spawn_blocking(move || produce(&tx));
+    ReceiverStream::new(rx)
+}
+
+/// Run a unary handler on the blocking pool
+async fn run_blocking<T, F>(work: F) -> Result<T, Status>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Status> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| Status::internal(format!("Worker failed: {}", e)))?
+}
+
+/// Wait for a job slot, or report the rejection on the stream
+fn job_slot<'a, T>(
+    limiter: &'a Limiter,
+    tx: &mpsc::Sender<Result<T, Status>>,
+) -> Option<JobPermit<'a>> {
+    match limiter.acquire() {
+        Ok(permit) => Some(permit),
+        Err(busy) => {
+            let _ = tx.blocking_send(Err(busy_status(&busy)));
+            None
+        }
+    }
+}
+
+fn busy_status(busy: &Busy) -> Status {
+    Status::resource_exhausted(busy.to_string())
+}
+
+fn engine_status(action: &str, error: EncoderError) -> Status {
+    let message = format!("{}: {}", action, error);
+    match error {
+        EncoderError::DirectoryNotFound { .. }
+        | EncoderError::FileNotFound { .. }
+        | EncoderError::InvalidZoomTarget { .. } => Status::not_found(message),
+        EncoderError::InvalidConfig { .. } | EncoderError::LensNotFound { .. } => {
+            Status::invalid_argument(message)
+        }
+        EncoderError::PolicyViolation { .. } => Status::permission_denied(message),
+        _ => Status::internal(message),
+    }
+}
+
+fn non_empty(value: &str) -> Option<&str> {
+    (!value.is_empty()).then_some(value)
+}
+
+/// Resolve a language by name ("rust", "TypeScript") or extension ("py")
+fn parse_language(name: &str) -> Result<LanguageId, Status> {
+    let language = LanguageId::from_extension(name);
+    TreeSitterProvider::new()
+        .registry()
+        .supported_languages()
+        .into_iter()
+        .find(|l| *l == language || l.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| Status::invalid_argument(format!("Unsupported language '{}'", name)))
+}
+
+fn symbol(declaration: &Declaration) -> Symbol {
+    Symbol {
+        name: declaration.name.clone(),
+        kind: declaration.kind.as_str().to_string(),
+        start_line: declaration.span.start_line as u32,
+        end_line: declaration.span.end_line as u32,
+        children: declaration.children.iter().map(symbol).collect(),
+    }
+}
+
+fn indexed_file(path: &str, file: &File) -> IndexedFile {
+    IndexedFile {
+        path: path.to_string(),
+        language: file.language.name().to_string(),
+        declarations: file.declarations.iter().map(symbol).collect(),
+        imports: file.imports.len() as u32,
+    }
+}
+
+/// Split `context` into chunks of at most `max_bytes`
+///
+/// Chunks end on a line boundary when one is available and never split a
+/// UTF-8 character; an empty context yields a single empty final chunk.
+fn context_chunks(context: &str, max_bytes: usize) -> Vec<ContextChunk> {
+    let max_bytes = max_bytes.max(4);
+    let mut parts = Vec::new();
+    let mut rest = context;
+    while rest.len() > max_bytes {
+        let mut end = max_bytes;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if let Some(newline) = rest[..end].rfind('\n') {
+            end = newline + 1;
+        }
+        let (part, tail) = rest.split_at(end);
+        parts.push(part);
+        rest = tail;
+    }
+    if !rest.is_empty() || parts.is_empty() {
+        parts.push(rest);
+    }
+
+    let count = parts.len();
+    parts.into_iter()
+        .enumerate()
+        .map(|(i, part)| ContextChunk {
+            index: i as u32,
+            content: part.to_string(),
+            last: i + 1 == count,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+    use tokio_stream::StreamExt;
+
+    fn sample_project() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        fs::write(
+            temp.path().join("src/lib.rs"),
+            "pub struct Parser;\n\nimpl Parser {\n    pub fn parse_line(&self) {}\n}\n\npub fn render() {}\n",
+        )
+        .unwrap();
+        fs::write(temp.path().join("app.py"), "def parse_args():\n    pass\n").unwrap();
+        temp
+    }
+
+    #[test]
+    fn test_context_chunks_reassemble() {
+        let context = "line one\nline two\nлиния три\n".repeat(50);
+        let chunks = context_chunks(&context, 64);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.content.len() <= 64));
+        assert!(chunks.last().unwrap().last);
+        assert!(chunks[..chunks.len() - 1].iter().all(|c| !c.last));
+        let joined: String = chunks.iter().map(|c| c.content.as_str()).collect();
+        assert_eq!(joined, context);
+    }
+
+    #[test]
+    fn test_context_chunks_empty() {
+        let chunks = context_chunks("", CONTEXT_CHUNK_BYTES);
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].last);
+        assert!(chunks[0].content.is_empty());
+    }
+
+    #[test]
+    fn test_parse_language() {
+        assert_eq!(parse_language("rust").unwrap(), LanguageId::Rust);
+        assert_eq!(parse_language("py").unwrap(), LanguageId::Python);
+        assert!(parse_language("cobol").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_generate_context_streams_chunks() {
+        let temp = sample_project();
+        let service = GrpcService::new(temp.path().to_path_buf());
+        let stream = service
+            .generate_context(Request::new(GenerateContextRequest::default()))
+            .await
+            .unwrap()
+            .into_inner();
+        let chunks: Vec<ContextChunk> = stream.map(|c| c.unwrap()).collect().await;
+        assert!(chunks.last().unwrap().last);
+        let context: String = chunks.iter().map(|c| c.content.as_str()).collect();
+        assert!(context.contains("src/lib.rs"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_context_rejects_bad_lens() {
+        let service = GrpcService::new(PathBuf::from("."));
+        let request = GenerateContextRequest { lens: "no-such-lens".to_string(), ..Default::default() };
+        let status = service.generate_context(Request::new(request)).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_paths_outside_root_are_denied() {
+        let temp = sample_project();
+        let service = GrpcService::new(temp.path().join("src"));
+        let request = GenerateContextRequest { path: "..".to_string(), ..Default::default() };
+        let status = service.generate_context(Request::new(request)).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+
+        let request = ZoomRequest { path: "/".to_string(), target: "file=lib.rs".to_string() };
+        let status = service.zoom(Request::new(request)).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::PermissionDenied);
+    }
+
+    #[tokio::test]
+    async fn test_index_project_streams_files() {
+        let temp = sample_project();
+        let service = GrpcService::new(temp.path().to_path_buf());
+        let request = IndexProjectRequest { languages: vec!["rust".to_string()], ..Default::default() };
+        let files: Vec<IndexedFile> = service
+            .index_project(Request::new(request))
+            .await
+            .unwrap()
+            .into_inner()
+            .map(|f| f.unwrap())
+            .collect()
+            .await;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert!(files[0].declarations.iter().any(|d| d.name == "render"));
+    }
+
+    #[tokio::test]
+    async fn test_search_streams_matches() {
+        let temp = sample_project();
+        let service = GrpcService::new(temp.path().to_path_buf());
+        let request = SearchRequest { query: "ARGS".to_string(), kind: "function".to_string(), ..Default::default() };
+        let matches: Vec<SearchMatch> = service
+            .search(Request::new(request))
+            .await
+            .unwrap()
+            .into_inner()
+            .map(|m| m.unwrap())
+            .collect()
+            .await;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].path, "app.py");
+        assert_eq!(matches[0].symbol.as_ref().unwrap().name, "parse_args");
+    }
+}
@@ -0,0 +1,270 @@
+//! Warm project index for long-running servers
+//!
+//! One-shot CLI runs re-scan the project on every invocation. The daemon and
+//! gRPC front ends keep a [`WarmIndex`] in memory instead and only re-parse
+//! files whose modification time changed since they were indexed. With a
+//! [`BlobStore`] as parse cache, files whose content was parsed before (in
+//! any checkout) are not parsed again, even on a cold start.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use voyager_ast::{
+    extended_length_path, AstProvider, Declaration, File, IndexOptions, IndexStats, LanguageId,
+    ParseCache, PlanetariumModel, RawPath, TreeSitterProvider,
+};
+
+use crate::core::BlobStore;
+
+use super::metrics::Metrics;
+
+/// A declaration found by [`WarmIndex::search`]
+#[derive(Debug, Clone, Copy)]
+pub struct SymbolMatch<'a> {
+    /// Path key of the containing file
+    pub path: &'a RawPath,
+    pub declaration: &'a Declaration,
+}
+
+/// In-memory Planetarium index of one project
+pub struct WarmIndex {
+    root: PathBuf,
+    provider: TreeSitterProvider,
+    model: PlanetariumModel,
+    /// Modification time of each file when it was parsed
+    mtimes: BTreeMap<RawPath, Option<SystemTime>>,
+    /// Parse error and cache counters
+    metrics: Arc<Metrics>,
+    /// Content-addressed parse cache
+    parse_cache: Option<BlobStore>,
+}
+
+impl WarmIndex {
+    /// Index `root`
+    pub fn build(root: &Path) -> Result<Self, String> {
+        Self::build_with(root, Arc::new(Metrics::new()), None)
+    }
+
+    /// Index `root`, recording parse errors and cache hits into `metrics`
+    /// and reusing parses from `parse_cache`
+    pub fn build_with(root: &Path, metrics: Arc<Metrics>, parse_cache: Option<BlobStore>) -> Result<Self, String> {
+        let mut index = Self {
+            root: root.to_path_buf(),
+            provider: TreeSitterProvider::new(),
+            model: PlanetariumModel::new(root.display().to_string()),
+            mtimes: BTreeMap::new(),
+            metrics,
+            parse_cache,
+        };
+        index.refresh()?;
+        Ok(index)
+    }
+
+    /// Project root
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The current model
+    pub fn model(&self) -> &PlanetariumModel {
+        &self.model
+    }
+
+    /// Statistics from the last full index
+    pub fn stats(&self) -> &IndexStats {
+        &self.model.stats
+    }
+
+    /// Re-index the whole project
+    pub fn refresh(&mut self) -> Result<(), String> {
+        let options = IndexOptions {
+            parse_cache: self.parse_cache.clone().map(|c| Arc::new(c) as Arc<dyn ParseCache>),
+            ..Default::default()
+        };
+        self.model = self.provider
+            .index_project(&self.root, &options)
+            .map_err(|e| format!("Indexing failed: {}", e))?;
+        for error in &self.model.errors {
+            self.metrics.record_parse_error(language_of(Path::new(&error.path)).name());
+        }
+        self.mtimes = self.model.files.keys()
+            .map(|key| (key.clone(), self.mtime(key)))
+            .collect();
+        Ok(())
+    }
+
+    /// Parsed file for a path key, re-parsed if it changed on disk
+    ///
+    /// Files created after the last full index are parsed on first access.
+    /// Returns `None` for unsupported or unreadable files; a file that was
+    /// deleted is dropped from the index.
+    pub fn file(&mut self, key: &str) -> Option<&File> {
+        let key = RawPath::from_key(&crate::core::normalize_relative_path(key));
+        let mtime = self.mtime(&key);
+        if mtime.is_none() {
+            self.model.files.remove(&key);
+            self.mtimes.remove(&key);
+            return None;
+        }
+
+        if self.mtimes.get(&key) == Some(&mtime) {
+            self.metrics.record_cache_hit();
+        } else {
+            self.metrics.record_cache_miss();
+            let file = self.parse(&key)?;
+            self.model.files.insert(key.clone(), file);
+            self.mtimes.insert(key.clone(), mtime);
+        }
+        self.model.files.get(&key)
+    }
+
+    /// Declarations (including nested ones) whose name contains `query`
+    ///
+    /// Matching is case-insensitive; `kind` filters on
+    /// [`DeclarationKind::as_str`](voyager_ast::DeclarationKind::as_str) when
+    /// given, and `limit` caps the results when non-zero.
+    pub fn search(&self, query: &str, kind: Option<&str>, limit: usize) -> Vec<SymbolMatch<'_>> {
+        fn visit<'a>(
+            path: &'a RawPath,
+            declaration: &'a Declaration,
+            query: &str,
+            kind: Option<&str>,
+            matches: &mut Vec<SymbolMatch<'a>>,
+        ) {
+            let kind_matches = kind.is_none_or(|k| declaration.kind.as_str().eq_ignore_ascii_case(k));
+            if kind_matches && declaration.name.to_lowercase().contains(query) {
+                matches.push(SymbolMatch { path, declaration });
+            }
+            for child in &declaration.children {
+                visit(path, child, query, kind, matches);
+            }
+        }
+
+        let query = query.to_lowercase();
+        let mut matches = Vec::new();
+        for (path, file) in &self.model.files {
+            for declaration in &file.declarations {
+                visit(path, declaration, &query, kind, &mut matches);
+            }
+        }
+        if limit > 0 {
+            matches.truncate(limit);
+        }
+        matches
+    }
+
+    fn mtime(&self, key: &RawPath) -> Option<SystemTime> {
+        std::fs::metadata(extended_length_path(&self.root.join(key.as_path())))
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    fn parse(&self, key: &RawPath) -> Option<File> {
+        let language = language_of(key.as_path());
+        if !self.provider.registry().supports(language) {
+            return None;
+        }
+        let source = std::fs::read_to_string(extended_length_path(&self.root.join(key.as_path()))).ok()?;
+        if let Some(mut file) = self.parse_cache.as_ref().and_then(|c| ParseCache::get(c, language, &source)) {
+            file.path = key.clone();
+            return Some(file);
+        }
+        match self.provider.parse_file(&source, language) {
+            Ok(mut file) => {
+                if let Some(cache) = &self.parse_cache {
+                    ParseCache::put(cache, language, &source, &file);
+                }
+                file.path = key.clone();
+                Some(file)
+            }
+            Err(_) => {
+                self.metrics.record_parse_error(language.name());
+                None
+            }
+        }
+    }
+}
+
+fn language_of(path: &Path) -> LanguageId {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map_or(LanguageId::Unknown, LanguageId::from_extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn sample_project() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("src")).unwrap();
+        fs::write(
+            temp.path().join("src/lib.rs"),
+            "pub struct Parser;\n\nimpl Parser {\n    pub fn parse_line(&self) {}\n}\n\npub fn render() {}\n",
+        )
+        .unwrap();
+        fs::write(temp.path().join("app.py"), "def parse_args():\n    pass\n").unwrap();
+        temp
+    }
+
+    fn names(matches: Vec<SymbolMatch<'_>>) -> Vec<String> {
+        matches.into_iter().map(|m| m.declaration.name.clone()).collect()
+    }
+
+    #[test]
+    fn test_search_nested_and_filtered() {
+        let temp = sample_project();
+        let index = WarmIndex::build(temp.path()).unwrap();
+
+        let all = names(index.search("PARSE", None, 0));
+        assert!(all.contains(&"parse_args".to_string()));
+        assert!(all.contains(&"parse_line".to_string()));
+        assert!(all.contains(&"Parser".to_string()));
+
+        assert_eq!(names(index.search("parse", Some("struct"), 0)), vec!["Parser"]);
+        assert_eq!(index.search("parse", None, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_file_reparses_changed_and_new_files() {
+        let temp = sample_project();
+        let mut index = WarmIndex::build(temp.path()).unwrap();
+        assert!(index.file("src/lib.rs").unwrap().declarations.iter().any(|d| d.name == "render"));
+
+        // Force a different mtime even on coarse-grained filesystems
+        let path = temp.path().join("src/lib.rs");
+        fs::write(&path, "pub fn draw() {}\n").unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        fs::File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+
+        let names: Vec<_> = index.file("./src/lib.rs").unwrap().declarations.iter().map(|d| d.name.clone()).collect();
+        assert_eq!(names, vec!["draw"]);
+
+        fs::write(temp.path().join("new.py"), "def fresh():\n    pass\n").unwrap();
+        assert!(index.file("new.py").is_some());
+
+        fs::remove_file(temp.path().join("app.py")).unwrap();
+        assert!(index.file("app.py").is_none());
+        assert!(index.search("parse_args", None, 0).is_empty());
+    }
+
+    #[test]
+    fn test_file_lookups_count_cache_hits() {
+        let temp = sample_project();
+        let metrics = Arc::new(Metrics::new());
+        let mut index = WarmIndex::build_with(temp.path(), Arc::clone(&metrics), None).unwrap();
+
+        index.file("src/lib.rs");
+        index.file("src/lib.rs");
+        fs::write(temp.path().join("later.py"), "def late():\n    pass\n").unwrap();
+        index.file("later.py");
+
+        let text = metrics.render();
+        assert!(text.contains("pm_encoder_index_cache_hits_total 2"));
+        assert!(text.contains("pm_encoder_index_cache_misses_total 1"));
+    }
+}
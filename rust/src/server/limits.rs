@@ -0,0 +1,318 @@
+//! Concurrency and rate limits for shared server deployments
+//!
+//! A [`Limiter`] enforces two independent limits:
+//! - **Jobs**: at most `max_jobs` expensive requests (indexing, serializing,
+//!   zooming) run at once. Further jobs wait in a queue of `max_queue`
+//!   slots for up to `queue_timeout`; beyond that they are rejected.
+//! - **Rate**: each client may send `requests_per_minute` requests, with
+//!   bursts up to one minute's allowance (token bucket). Zero disables it.
+//!
+//! Rejections are [`Busy`] values carrying a retry hint, which the MCP and
+//! daemon servers report as JSON-RPC error [`SERVER_BUSY`](super::SERVER_BUSY)
+//! and gRPC as `RESOURCE_EXHAUSTED`.
+//!
+//! Clients are identified by the front end: the daemon uses one identity per
+//! connection, gRPC the peer IP address and stdio MCP a single `stdio`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+/// Client buckets kept before idle (full) ones are pruned
+const MAX_TRACKED_CLIENTS: usize = 1024;
+
+/// Configured limits
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerLimits {
+    /// Maximum expensive jobs running at once
+    pub max_jobs: usize,
+    /// Jobs allowed to wait for a slot
+    pub max_queue: usize,
+    /// How long a queued job waits before giving up
+    pub queue_timeout: Duration,
+    /// Requests per client per minute (0 = unlimited)
+    pub requests_per_minute: u32,
+}
+
+impl Default for ServerLimits {
+    fn default() -> Self {
+        Self {
+            max_jobs: std::thread::available_parallelism().map_or(2, |n| n.get()),
+            max_queue: 16,
+            queue_timeout: Duration::from_secs(30),
+            requests_per_minute: 0,
+        }
+    }
+}
+
+/// Why a request was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum Busy {
+    /// The client exceeded its request rate
+    RateLimited { retry_after: Duration },
+    /// All job slots and queue slots are taken
+    QueueFull { running: usize, queued: usize },
+    /// The job waited `queue_timeout` without getting a slot
+    Timeout { waited: Duration, running: usize },
+}
+
+impl Busy {
+    /// Machine-readable reason
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Self::RateLimited { .. } => "rate_limited",
+            Self::QueueFull { .. } => "queue_full",
+            Self::Timeout { .. } => "queue_timeout",
+        }
+    }
+
+    /// Suggested delay before retrying
+    pub fn retry_after(&self) -> Duration {
+        match self {
+            Self::RateLimited { retry_after } => *retry_after,
+            Self::QueueFull { .. } | Self::Timeout { .. } => Duration::from_secs(1),
+        }
+    }
+
+    /// JSON-RPC error `data` payload
+    pub fn to_json(&self) -> Value {
+        let mut data = json!({
+            "reason": self.reason(),
+            "retry_after_ms": self.retry_after().as_millis() as u64,
+        });
+        match self {
+            Self::QueueFull { running, queued } => {
+                data["running"] = json!(running);
+                data["queued"] = json!(queued);
+            }
+            Self::Timeout { running, .. } => data["running"] = json!(running),
+            Self::RateLimited { .. } => {}
+        }
+        data
+    }
+}
+
+/// Static label for a [`Busy::reason`] string (for metrics)
+pub fn reason_label(reason: &str) -> &'static str {
+    ["rate_limited", "queue_full", "queue_timeout"]
+        .into_iter()
+        .find(|r| *r == reason)
+        .unwrap_or("other")
+}
+
+impl fmt::Display for Busy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RateLimited { retry_after } => write!(
+                f,
+                "Rate limit exceeded; retry in {:.1}s",
+                retry_after.as_secs_f64()
+            ),
+            Self::QueueFull { running, queued } => write!(
+                f,
+                "Server busy: {} jobs running and {} queued; retry shortly",
+                running, queued
+            ),
+            Self::Timeout { waited, running } => write!(
+                f,
+                "Server busy: waited {}s for one of {} running jobs to finish",
+                waited.as_secs(),
+                running
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Busy {}
+
+#[derive(Debug, Default)]
+struct JobState {
+    running: usize,
+    queued: usize,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Shared limiter for one server process
+#[derive(Debug)]
+pub struct Limiter {
+    limits: ServerLimits,
+    jobs: Mutex<JobState>,
+    released: Condvar,
+    clients: Mutex<HashMap<String, Bucket>>,
+}
+
+/// A running job slot, released on drop
+#[derive(Debug)]
+pub struct JobPermit<'a> {
+    limiter: &'a Limiter,
+}
+
+impl Drop for JobPermit<'_> {
+    fn drop(&mut self) {
+        self.limiter.jobs().running -= 1;
+        self.limiter.released.notify_one();
+    }
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Self::new(ServerLimits::default())
+    }
+}
+
+impl Limiter {
+    pub fn new(limits: ServerLimits) -> Self {
+        Self {
+            limits: ServerLimits { max_jobs: limits.max_jobs.max(1), ..limits },
+            jobs: Mutex::new(JobState::default()),
+            released: Condvar::new(),
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Effective limits
+    pub fn limits(&self) -> &ServerLimits {
+        &self.limits
+    }
+
+    /// Count one request from `client` against its rate
+    pub fn check_rate(&self, client: &str) -> Result<(), Busy> {
+        let per_minute = self.limits.requests_per_minute;
+        if per_minute == 0 {
+            return Ok(());
+        }
+        let capacity = f64::from(per_minute);
+        let per_second = capacity / 60.0;
+        let now = Instant::now();
+
+        let mut clients = self.clients.lock().unwrap_or_else(|e| e.into_inner());
+        if clients.len() >= MAX_TRACKED_CLIENTS && !clients.contains_key(client) {
+            clients.retain(|_, b| {
+                b.tokens + now.duration_since(b.updated).as_secs_f64() * per_second < capacity
+            });
+        }
+        let bucket = clients
+            .entry(client.to_string())
+            .or_insert(Bucket { tokens: capacity, updated: now });
+        bucket.tokens = (bucket.tokens + now.duration_since(bucket.updated).as_secs_f64() * per_second)
+            .min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let retry_after = Duration::from_secs_f64((1.0 - bucket.tokens) / per_second);
+            Err(Busy::RateLimited { retry_after })
+        }
+    }
+
+    /// Take a job slot, waiting in the queue if necessary
+    pub fn acquire(&self) -> Result<JobPermit<'_>, Busy> {
+        let mut jobs = self.jobs();
+        if jobs.running < self.limits.max_jobs {
+            jobs.running += 1;
+            return Ok(JobPermit { limiter: self });
+        }
+        if jobs.queued >= self.limits.max_queue {
+            return Err(Busy::QueueFull { running: jobs.running, queued: jobs.queued });
+        }
+
+        jobs.queued += 1;
+        let started = Instant::now();
+        let (mut jobs, _) = self.released
+            .wait_timeout_while(jobs, self.limits.queue_timeout, |j| j.running >= self.limits.max_jobs)
+            .unwrap_or_else(|e| e.into_inner());
+        jobs.queued -= 1;
+        if jobs.running >= self.limits.max_jobs {
+            return Err(Busy::Timeout { waited: started.elapsed(), running: jobs.running });
+        }
+        jobs.running += 1;
+        Ok(JobPermit { limiter: self })
+    }
+
+    /// Rate check for `client`, then a job slot
+    pub fn admit(&self, client: &str) -> Result<JobPermit<'_>, Busy> {
+        self.check_rate(client)?;
+        self.acquire()
+    }
+
+    /// Jobs currently running and queued
+    pub fn load(&self) -> (usize, usize) {
+        let jobs = self.jobs();
+        (jobs.running, jobs.queued)
+    }
+
+    fn jobs(&self) -> MutexGuard<'_, JobState> {
+        self.jobs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn limits(max_jobs: usize, max_queue: usize, timeout_ms: u64, rpm: u32) -> ServerLimits {
+        ServerLimits {
+            max_jobs,
+            max_queue,
+            queue_timeout: Duration::from_millis(timeout_ms),
+            requests_per_minute: rpm,
+        }
+    }
+
+    #[test]
+    fn test_rate_limit_per_client() {
+        let limiter = Limiter::new(limits(1, 0, 0, 2));
+        assert!(limiter.check_rate("a").is_ok());
+        assert!(limiter.check_rate("a").is_ok());
+        let busy = limiter.check_rate("a").unwrap_err();
+        assert_eq!(busy.reason(), "rate_limited");
+        assert!(busy.retry_after() > Duration::from_secs(20));
+        // Other clients have their own allowance
+        assert!(limiter.check_rate("b").is_ok());
+
+        assert!(Limiter::new(limits(1, 0, 0, 0)).check_rate("a").is_ok());
+    }
+
+    #[test]
+    fn test_queue_full_and_timeout() {
+        let limiter = Limiter::new(limits(1, 0, 10, 0));
+        let permit = limiter.acquire().unwrap();
+        assert!(matches!(limiter.acquire(), Err(Busy::QueueFull { running: 1, queued: 0 })));
+        drop(permit);
+        assert!(limiter.acquire().is_ok());
+
+        let limiter = Limiter::new(limits(1, 1, 10, 0));
+        let _permit = limiter.acquire().unwrap();
+        let busy = limiter.acquire().unwrap_err();
+        assert_eq!(busy.reason(), "queue_timeout");
+        assert_eq!(busy.to_json()["running"], 1);
+        assert_eq!(limiter.load(), (1, 0));
+    }
+
+    #[test]
+    fn test_queued_job_runs_when_slot_frees() {
+        let limiter = Arc::new(Limiter::new(limits(1, 1, 5_000, 0)));
+        let permit = limiter.acquire().unwrap();
+        let waiter = {
+            let limiter = Arc::clone(&limiter);
+            std::thread::spawn(move || limiter.acquire().map(drop).is_ok())
+        };
+        while limiter.load().1 == 0 {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        drop(permit);
+        assert!(waiter.join().unwrap());
+        assert_eq!(limiter.load(), (0, 0));
+    }
+}
@@ -0,0 +1,294 @@
+//! Prometheus metrics for self-hosted servers
+//!
+//! The MCP server and the daemon count requests, tokens served, parse errors
+//! per language and warm-index cache hits in a shared [`Metrics`]. Nothing is
+//! exposed unless `--metrics-addr` is given, in which case [`serve`] answers
+//! `GET /metrics` (Prometheus text format) and `GET /healthz` on that
+//! address. Nothing is ever sent anywhere.
+//!
+//! Labels are anonymized: they carry method, tool and language names only,
+//! never paths, symbols or arguments. Unknown methods and tools are counted
+//! as `other` so clients cannot inflate label cardinality.
+//!
+//! # Usage
+//! ```bash
+//! pm_encoder --daemon --metrics-addr 127.0.0.1:9464 /path/to/project
+//! curl -s http://127.0.0.1:9464/metrics
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::budgeting::TokenEstimator;
+
+/// Default address for `--metrics-addr` examples
+pub const DEFAULT_METRICS_ADDR: &str = "127.0.0.1:9464";
+
+/// Label used for methods and tools the server does not know
+pub const OTHER_LABEL: &str = "other";
+
+/// Counters shared by one server process
+#[derive(Debug)]
+pub struct Metrics {
+    started: Instant,
+    /// (method, tool) → (requests, errors)
+    requests: Mutex<BTreeMap<(String, String), (u64, u64)>>,
+    tokens_served: AtomicU64,
+    /// Limit reason → rejected requests
+    rejected: Mutex<BTreeMap<&'static str, u64>>,
+    /// Language name → parse errors
+    parse_errors: Mutex<BTreeMap<&'static str, u64>>,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            requests: Mutex::new(BTreeMap::new()),
+            tokens_served: AtomicU64::new(0),
+            rejected: Mutex::new(BTreeMap::new()),
+            parse_errors: Mutex::new(BTreeMap::new()),
+            cache_hits: AtomicU64::new(0),
+            cache_misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Count one handled request
+    ///
+    /// `method` and `tool` must already be known names (or [`OTHER_LABEL`]);
+    /// `result` is the JSON-RPC result, used to estimate tokens served.
+    /// Tool results with `isError: true` count as errors.
+    pub fn record_request(&self, method: &str, tool: Option<&str>, result: Option<&Value>, is_error: bool) {
+        let is_error = is_error
+            || result.and_then(|r| r.get("isError")).and_then(Value::as_bool).unwrap_or(false);
+        if let Some(result) = result.filter(|_| !is_error) {
+            self.tokens_served.fetch_add(served_tokens(result) as u64, Ordering::Relaxed);
+        }
+
+        let key = (method.to_string(), tool.unwrap_or("").to_string());
+        let mut requests = self.requests.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = requests.entry(key).or_default();
+        entry.0 += 1;
+        if is_error {
+            entry.1 += 1;
+        }
+    }
+
+    /// Count a request rejected by a rate or concurrency limit
+    pub fn record_rejected(&self, reason: &'static str) {
+        let mut rejected = self.rejected.lock().unwrap_or_else(|e| e.into_inner());
+        *rejected.entry(reason).or_default() += 1;
+    }
+
+    /// Count a file that failed to parse
+    pub fn record_parse_error(&self, language: &'static str) {
+        let mut errors = self.parse_errors.lock().unwrap_or_else(|e| e.into_inner());
+        *errors.entry(language).or_default() += 1;
+    }
+
+    /// Count a warm-index lookup served without re-parsing
+    pub fn record_cache_hit(&self) {
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a warm-index lookup that had to (re-)parse the file
+    pub fn record_cache_miss(&self) {
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Total estimated tokens served so far
+    pub fn tokens_served(&self) -> u64 {
+        self.tokens_served.load(Ordering::Relaxed)
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        metric_header(&mut out, "pm_encoder_build_info", "gauge", "Build information");
+        let _ = writeln!(out, "pm_encoder_build_info{{version=\"{}\"}} 1", crate::VERSION);
+
+        metric_header(&mut out, "pm_encoder_uptime_seconds", "gauge", "Seconds since the server started");
+        let _ = writeln!(out, "pm_encoder_uptime_seconds {}", self.started.elapsed().as_secs());
+
+        let requests = self.requests.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        metric_header(&mut out, "pm_encoder_requests_total", "counter", "Requests handled, by method and tool");
+        for ((method, tool), (count, _)) in &requests {
+            let _ = writeln!(out, "pm_encoder_requests_total{{method=\"{}\",tool=\"{}\"}} {}", method, tool, count);
+        }
+        metric_header(&mut out, "pm_encoder_request_errors_total", "counter", "Requests that returned an error");
+        for ((method, tool), (_, errors)) in &requests {
+            let _ = writeln!(out, "pm_encoder_request_errors_total{{method=\"{}\",tool=\"{}\"}} {}", method, tool, errors);
+        }
+
+        metric_header(&mut out, "pm_encoder_rejected_requests_total", "counter", "Requests rejected by rate or concurrency limits");
+        for (reason, count) in self.rejected.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+            let _ = writeln!(out, "pm_encoder_rejected_requests_total{{reason=\"{}\"}} {}", reason, count);
+        }
+
+        metric_header(&mut out, "pm_encoder_tokens_served_total", "counter", "Estimated tokens returned to clients");
+        let _ = writeln!(out, "pm_encoder_tokens_served_total {}", self.tokens_served());
+
+        metric_header(&mut out, "pm_encoder_parse_errors_total", "counter", "Files that failed to parse, by language");
+        for (language, count) in self.parse_errors.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+            let _ = writeln!(out, "pm_encoder_parse_errors_total{{language=\"{}\"}} {}", language, count);
+        }
+
+        metric_header(&mut out, "pm_encoder_index_cache_hits_total", "counter", "Warm-index lookups served from memory");
+        let _ = writeln!(out, "pm_encoder_index_cache_hits_total {}", self.cache_hits.load(Ordering::Relaxed));
+        metric_header(&mut out, "pm_encoder_index_cache_misses_total", "counter", "Warm-index lookups that re-parsed a file");
+        let _ = writeln!(out, "pm_encoder_index_cache_misses_total {}", self.cache_misses.load(Ordering::Relaxed));
+
+        out
+    }
+}
+
+fn metric_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Estimated tokens in a result: MCP text content plus editor `context`
+fn served_tokens(result: &Value) -> usize {
+    let content = result
+        .get("content")
+        .and_then(Value::as_array)
+        .map_or(0, |items| {
+            items.iter()
+                .filter_map(|item| item.get("text").and_then(Value::as_str))
+                .map(TokenEstimator::estimate_tokens)
+                .sum()
+        });
+    let context = result.get("context").and_then(Value::as_str).map_or(0, TokenEstimator::estimate_tokens);
+    content + context
+}
+
+/// Serve `/metrics` and `/healthz` on `addr` from a background thread
+///
+/// Returns the bound address (useful with port 0). Binding a non-loopback
+/// address is allowed for containerised deployments but logged, since the
+/// endpoint has no authentication.
+pub fn serve(addr: &str, metrics: Arc<Metrics>) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    let local = listener.local_addr()?;
+    if !local.ip().is_loopback() {
+        eprintln!("[metrics] Warning: {} is reachable from other hosts; the endpoint is unauthenticated", local);
+    }
+    eprintln!("[metrics] Serving http://{}/metrics", local);
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Err(e) = handle_http(stream, &metrics) {
+                eprintln!("[metrics] Request failed: {}", e);
+            }
+        }
+    });
+    Ok(local)
+}
+
+fn handle_http(stream: TcpStream, metrics: &Metrics) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain headers; bodies are not expected
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("").split('?').next().unwrap_or("");
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => ("200 OK", "text/plain; version=0.0.4", metrics.render()),
+        ("GET", "/healthz") => ("200 OK", "text/plain", "ok\n".to_string()),
+        ("GET", _) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain", "method not allowed\n".to_string()),
+    };
+
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::io::Read;
+
+    #[test]
+    fn test_render_counters() {
+        let metrics = Metrics::new();
+        metrics.record_request("tools/call", Some("get_context"), Some(&json!({
+            "content": [{"type": "text", "text": "x".repeat(400)}],
+            "isError": false
+        })), false);
+        metrics.record_request("tools/call", Some("zoom"), Some(&json!({
+            "content": [{"type": "text", "text": "bad target"}],
+            "isError": true
+        })), false);
+        metrics.record_request(OTHER_LABEL, None, None, true);
+        metrics.record_parse_error("Rust");
+        metrics.record_cache_hit();
+        metrics.record_cache_miss();
+        metrics.record_cache_miss();
+
+        let text = metrics.render();
+        assert!(text.contains("pm_encoder_requests_total{method=\"tools/call\",tool=\"get_context\"} 1"));
+        assert!(text.contains("pm_encoder_request_errors_total{method=\"tools/call\",tool=\"zoom\"} 1"));
+        assert!(text.contains("pm_encoder_request_errors_total{method=\"other\",tool=\"\"} 1"));
+        assert!(text.contains("pm_encoder_tokens_served_total 100"));
+        assert!(text.contains("pm_encoder_parse_errors_total{language=\"Rust\"} 1"));
+        assert!(text.contains("pm_encoder_index_cache_hits_total 1"));
+        assert!(text.contains("pm_encoder_index_cache_misses_total 2"));
+        assert!(text.contains("# TYPE pm_encoder_requests_total counter"));
+    }
+
+    fn get(addr: SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn test_http_endpoints() {
+        let metrics = Arc::new(Metrics::new());
+        metrics.record_cache_hit();
+        let addr = serve("127.0.0.1:0", Arc::clone(&metrics)).unwrap();
+
+        let health = get(addr, "/healthz");
+        assert!(health.starts_with("HTTP/1.1 200 OK"));
+        assert!(health.ends_with("ok\n"));
+
+        let scrape = get(addr, "/metrics");
+        assert!(scrape.contains("pm_encoder_index_cache_hits_total 1"));
+
+        assert!(get(addr, "/nope").starts_with("HTTP/1.1 404"));
+    }
+}
@@ -6,29 +6,81 @@
 //! # Protocol
 //! - JSON-RPC 2.0 over stdio (line-delimited JSON)
 //! - MCP initialize handshake
-//! - Tools: get_context, zoom, session_list, report_utility
+//! - Tools: get_context, get_context_page, preview_context, zoom, zoom_batch, session_list, report_utility,
+//!   capabilities, bookmark_add, bookmark_list, bookmark_resolve, investigation_start, investigation_add,
+//!   investigation_report
+//! - Oversized contexts are paginated with continuation tokens (see [`pagination`])
+//! - `--daemon` serves these tools plus a warm index over a Unix socket (see `daemon`)
+//! - With the `grpc` feature, the same APIs are served over gRPC (see `grpc`)
+//! - `--metrics-addr` exposes Prometheus metrics and `/healthz` (see [`metrics`])
+//! - Tool calls are subject to job and rate limits (see [`limits`])
+//! - With the `async` feature, async hosts get bounded non-blocking
+//!   variants of the engine calls (see `async_api`)
+//! - `notifications/cancelled` aborts a running get_context/preview_context
+//!   (see [`cancellation`])
+//! - get_context and zoom calls with a `session_id` or `seen` fingerprints
+//!   elide content already delivered in the conversation (see `core::ledger`)
+//! - With a `prefetch` config, the zoom affordances of served contexts are
+//!   zoomed ahead of time in the background (see [`prefetch`])
+//! - With `workspaces` configured, one server serves several named roots
+//!   and tools take a `workspace` argument (see [`workspaces`])
+//! - `capabilities` describes the build, its language adapters and the
+//!   limits in force (see [`capabilities`])
+//! - Bookmarks saved with `bookmark_add` are zoom targets as `bookmark=name`
+//!   (see `core::bookmarks`)
+//! - Investigations collect zooms, searches and notes across calls into one
+//!   report (see `core::investigation`)
 //!
 //! # Usage
 //! ```bash
 //! pm_encoder --server
 //! ```
 
+pub mod pagination;
+pub mod index;
+pub mod editor;
+pub mod metrics;
+pub mod limits;
+pub mod cancellation;
+pub mod prefetch;
+pub mod workspaces;
+pub mod capabilities;
+#[cfg(unix)]
+pub mod daemon;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "async")]
+pub mod async_api;
+
+use std::collections::HashMap;
 use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::Instant;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
 use crate::core::{
-    ContextEngine, EncoderConfig, ZoomConfig, ZoomTarget, ZoomDepth,
+    Anchor, ContextEngine, EncoderConfig, ZoomConfig, ZoomTarget, ZoomDepth,
+    BatchZoomConfig, PackingStrategy,
     SymbolResolver, CallGraphAnalyzer, ZoomSuggestion,
     ZoomSessionStore, ContextStore, DEFAULT_ALPHA, OutputFormat,
-    SkeletonMode,
+    SkeletonMode, ContextBundle, CancellationToken, EncoderError, BlobStore, PathSandbox,
+    ContentLedger, SeenContent, BookmarkStore, InvestigationStore,
     // Phase 2: Rich Context
     UsageFinder, RelatedContext,
     // Phase 2 Week 2: Intent-Driven Exploration
     IntentExplorer, ExplorerConfig, ExplorationIntent,
 };
+use crate::core::ledger;
 use crate::{LensManager, parse_token_budget};
+use cancellation::{cancelled_request_id, InFlight};
+use capabilities::Capabilities;
+use limits::{Busy, Limiter};
+use metrics::{Metrics, OTHER_LABEL};
+use pagination::{PageStore, DEFAULT_PAGE_TOKENS};
+use prefetch::{PrefetchConfig, PrefetchedZoom, Prefetcher};
+use workspaces::{Workspace, Workspaces};
 
 // ============================================================================
 // JSON-RPC 2.0 Types
@@ -86,6 +138,24 @@ impl JsonRpcResponse {
             }),
         }
     }
+
+    fn error_with_data(id: Value, code: i32, message: String, data: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(JsonRpcError {
+                code,
+                message,
+                data: Some(data),
+            }),
+        }
+    }
+
+    /// Server-busy error with a retry hint
+    fn busy(id: Value, busy: &Busy) -> Self {
+        Self::error_with_data(id, SERVER_BUSY, busy.to_string(), busy.to_json())
+    }
 }
 
 // JSON-RPC error codes
@@ -94,6 +164,17 @@ const INVALID_REQUEST: i32 = -32600;
 const METHOD_NOT_FOUND: i32 = -32601;
 const INVALID_PARAMS: i32 = -32602;
 const INTERNAL_ERROR: i32 = -32603;
+/// Implementation-defined: rate or concurrency limit hit, retry later
+pub const SERVER_BUSY: i32 = -32000;
+
+/// Implementation-defined: a path argument left the project root
+pub const POLICY_VIOLATION: i32 = -32001;
+
+/// The request was cancelled by the client (same code as LSP)
+pub const REQUEST_CANCELLED: i32 = -32800;
+
+/// Client identity for the stdio transport
+const STDIO_CLIENT: &str = "stdio";
 
 // ============================================================================
 // MCP Tool Response Helpers
@@ -121,6 +202,102 @@ fn tool_error(id: Value, message: String) -> JsonRpcResponse {
     }))
 }
 
+/// JSON-RPC error for an engine error: POLICY_VIOLATION for sandbox escapes, INVALID_PARAMS otherwise
+fn error_response(id: Value, error: &EncoderError) -> JsonRpcResponse {
+    match error {
+        EncoderError::PolicyViolation { path, reason } => JsonRpcResponse::error_with_data(
+            id,
+            POLICY_VIOLATION,
+            error.to_string(),
+            json!({ "path": path, "reason": reason }),
+        ),
+        _ => JsonRpcResponse::error(id, INVALID_PARAMS, error.to_string()),
+    }
+}
+
+/// REQUEST_CANCELLED error with the partial stats of a cancelled run
+fn cancelled(id: Value, error: &EncoderError, started: Instant) -> JsonRpcResponse {
+    let EncoderError::Cancelled { stage, files_processed, files_total } = error else {
+        return tool_error(id, error.to_string());
+    };
+    JsonRpcResponse::error_with_data(id, REQUEST_CANCELLED, error.to_string(), json!({
+        "stage": stage,
+        "files_processed": files_processed,
+        "files_total": files_total,
+        "elapsed_ms": started.elapsed().as_millis() as u64,
+    }))
+}
+
+// ============================================================================
+// Context Options
+// ============================================================================
+
+/// Context generation options shared by the MCP and gRPC front ends
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ContextOptions<'a> {
+    pub lens: Option<&'a str>,
+    pub token_budget: Option<&'a str>,
+    pub format: &'a str,
+    pub skeleton: &'a str,
+}
+
+/// Why [`ContextOptions`] could not be turned into an engine config
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ContextOptionsError {
+    /// Unknown lens name
+    Lens(String),
+    /// Unparseable token budget
+    Budget(String),
+}
+
+impl std::fmt::Display for ContextOptionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Lens(message) | Self::Budget(message) => f.write_str(message),
+        }
+    }
+}
+
+impl ContextOptions<'_> {
+    #[allow(clippy::field_reassign_with_default)]
+    pub(crate) fn encoder_config(&self) -> Result<EncoderConfig, ContextOptionsError> {
+        // TODO: Load project .pm_encoder_config.json when core::EncoderConfig supports Deserialize
+        // For now, use defaults - the lens will override patterns anyway
+        let mut config = EncoderConfig::default();
+        config.output_format = match self.format {
+            "xml" => OutputFormat::Xml,
+            "markdown" => OutputFormat::Markdown,
+            "claude-xml" => OutputFormat::ClaudeXml,
+            _ => OutputFormat::PlusMinus,
+        };
+
+        // Apply skeleton mode (v2.2.0)
+        config.skeleton_mode = SkeletonMode::parse(self.skeleton).unwrap_or(SkeletonMode::Auto);
+
+        // Apply lens and merge patterns into config
+        if let Some(lens_name) = self.lens {
+            let mut lens_manager = LensManager::new();
+            let applied = lens_manager
+                .apply_lens(lens_name)
+                .map_err(|e| ContextOptionsError::Lens(format!("Invalid lens '{}': {}", lens_name, e)))?;
+            config.ignore_patterns.extend(applied.ignore_patterns);
+            if !applied.include_patterns.is_empty() {
+                config.include_patterns = applied.include_patterns;
+            }
+            config.active_lens = Some(lens_name.to_string());
+        }
+
+        // Parse token budget
+        if let Some(budget_str) = self.token_budget {
+            let budget = parse_token_budget(budget_str)
+                .map_err(|e| ContextOptionsError::Budget(format!("Invalid token budget: {}", e)))?;
+            config.token_budget = Some(budget);
+        }
+
+        Ok(config)
+    }
+}
+
 // ============================================================================
 // MCP Server
 // ============================================================================
@@ -129,6 +306,26 @@ fn tool_error(id: Value, message: String) -> JsonRpcResponse {
 pub struct McpServer {
     initialized: bool,
     project_root: PathBuf,
+    /// Paginated get_context results awaiting get_context_page
+    pages: PageStore,
+    /// Offline snapshot served by get_context/preview_context instead of the project
+    bundle: Option<ContextBundle>,
+    /// Request counters, exposed only with `--metrics-addr`
+    metrics: Arc<Metrics>,
+    /// Job and rate limits for tool calls (none: unlimited)
+    limiter: Option<Arc<Limiter>>,
+    /// Request being handled, cancellable from the stdin reader thread
+    in_flight: Arc<InFlight>,
+    /// Cancellation token of the request being handled
+    cancel: CancellationToken,
+    /// Context cache for get_context
+    blobs: Option<BlobStore>,
+    /// Content delivered per session, for deduplicating get_context/zoom
+    ledgers: HashMap<String, ContentLedger>,
+    /// Background zooms of the affordances in served contexts
+    prefetch: Option<Prefetcher>,
+    /// Named roots served instead of `project_root` (empty: single root)
+    workspaces: Workspaces,
 }
 
 impl McpServer {
@@ -137,16 +334,87 @@ impl McpServer {
         Self {
             initialized: false,
             project_root,
+            pages: PageStore::new(),
+            bundle: None,
+            metrics: Arc::new(Metrics::new()),
+            limiter: None,
+            in_flight: Arc::new(InFlight::new()),
+            cancel: CancellationToken::new(),
+            blobs: None,
+            ledgers: HashMap::new(),
+            prefetch: None,
+            workspaces: Workspaces::default(),
         }
     }
 
+    /// Zoom the affordances of served contexts ahead of the follow-up zoom call
+    pub fn with_prefetch(mut self, config: PrefetchConfig) -> Self {
+        self.prefetch = config.enabled.then(|| Prefetcher::new(config));
+        self
+    }
+
+    /// Serve named workspaces; tools must then name one
+    pub fn with_workspaces(mut self, workspaces: Workspaces) -> Self {
+        self.workspaces = workspaces;
+        self
+    }
+
+    /// Cache get_context results in a content-addressed store
+    pub fn with_blob_store(mut self, store: BlobStore) -> Self {
+        self.blobs = Some(store);
+        self
+    }
+
+    /// Apply job and rate limits to tool calls
+    pub fn with_limiter(mut self, limiter: Arc<Limiter>) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// Record into shared counters (e.g. the daemon's)
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Counters for this server
+    pub fn metrics(&self) -> &Arc<Metrics> {
+        &self.metrics
+    }
+
+    /// Serve get_context and preview_context from a `.pmctx` bundle
+    ///
+    /// Other tools still operate on `project_root`.
+    pub fn with_bundle(mut self, bundle: ContextBundle) -> Self {
+        self.bundle = Some(bundle);
+        self
+    }
+
     /// Run the server loop (blocking)
     pub fn run(&mut self) -> io::Result<()> {
         // Note: No startup logs - wait for initialize before logging
-        let stdin = io::stdin();
         let mut stdout = io::stdout();
 
-        for line in stdin.lock().lines() {
+        // Read stdin on its own thread so cancellations are seen while a
+        // request is running; they are applied here and not queued
+        let (tx, rx) = mpsc::channel();
+        let in_flight = Arc::clone(&self.in_flight);
+        std::thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                if let Ok(line) = &line {
+                    if let Some(request_id) = cancelled_request_id(line) {
+                        in_flight.cancel(&request_id);
+                        continue;
+                    }
+                }
+                let failed = line.is_err();
+                if tx.send(line).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        for line in rx {
             let line = line?;
             if line.is_empty() {
                 continue;
@@ -199,6 +467,14 @@ impl McpServer {
         // Check if this is a notification (no id = no response expected)
         let is_notification = request.id.is_none();
         let id = request.id.clone().unwrap_or(Value::Null);
+        let tool = request.params.as_ref()
+            .and_then(|p| p.get("name"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+
+        if !is_notification {
+            self.cancel = self.in_flight.begin(&id);
+        }
 
         // Route method
         let response = match request.method.as_str() {
@@ -208,6 +484,13 @@ impl McpServer {
                 // This is a notification - no response required
                 return None;
             }
+            "notifications/cancelled" => {
+                // Transports without a reader thread deliver cancellations here
+                if let Some(request_id) = request.params.as_ref().and_then(|p| p.get("requestId")) {
+                    self.in_flight.cancel(request_id);
+                }
+                return None;
+            }
             "shutdown" => {
                 self.initialized = false;
                 JsonRpcResponse::success(id, json!({}))
@@ -217,7 +500,14 @@ impl McpServer {
             "tools/list" => self.handle_tools_list(id),
 
             // MCP tool calls
-            "tools/call" => self.handle_tools_call(id, request.params),
+            "tools/call" => {
+                let limiter = self.limiter.clone();
+                let admitted = limiter.as_deref().map(|l| l.admit(STDIO_CLIENT)).transpose();
+                match admitted {
+                    Ok(_permit) => self.handle_tools_call(id, request.params),
+                    Err(busy) => JsonRpcResponse::busy(id, &busy),
+                }
+            }
 
             _ => JsonRpcResponse::error(
                 id,
@@ -226,6 +516,11 @@ impl McpServer {
             ),
         };
 
+        self.record(&request.method, tool.as_deref(), &response);
+        if !is_notification {
+            self.in_flight.finish();
+        }
+
         // Don't respond to notifications
         if is_notification {
             None
@@ -234,6 +529,27 @@ impl McpServer {
         }
     }
 
+    /// Count a response under anonymized method/tool labels
+    fn record(&self, method: &str, tool: Option<&str>, response: &JsonRpcResponse) {
+        if let Some(reason) = response.error.as_ref()
+            .filter(|e| e.code == SERVER_BUSY)
+            .and_then(|e| e.data.as_ref())
+            .and_then(|d| d["reason"].as_str())
+        {
+            // Rejected before dispatch: the tool name was never validated
+            self.metrics.record_rejected(limits::reason_label(reason));
+            return;
+        }
+        let unknown = response.error.as_ref().is_some_and(|e| e.code == METHOD_NOT_FOUND);
+        let (method, tool) = match (method, unknown) {
+            ("tools/call", true) => ("tools/call", Some(OTHER_LABEL)),
+            ("tools/call", false) => ("tools/call", tool),
+            (_, true) => (OTHER_LABEL, None),
+            (method, false) => (method, None),
+        };
+        self.metrics.record_request(method, tool, response.result.as_ref(), response.error.is_some());
+    }
+
     fn handle_initialize(&mut self, id: Value, _params: Option<Value>) -> JsonRpcResponse {
         self.initialized = true;
         eprintln!("[MCP] Initialized");
@@ -251,7 +567,7 @@ impl McpServer {
     }
 
     fn handle_tools_list(&self, id: Value) -> JsonRpcResponse {
-        let tools = json!({
+        let mut tools = json!({
             "tools": [
                 {
                     "name": "get_context",
@@ -278,6 +594,75 @@ impl McpServer {
                             "skeleton": {
                                 "type": "string",
                                 "description": "Skeleton mode: 'auto' (enable if budget set), 'true', 'false'. Extracts signatures, strips bodies."
+                            },
+                            "page_size": {
+                                "type": "string",
+                                "description": "Maximum tokens per response page (e.g., '20k'). Larger contexts return a continuation token. Default: 25k"
+                            },
+                            "session_id": {
+                                "type": "string",
+                                "description": "Optional conversation ID: files already delivered unchanged in this session are replaced by '«unchanged since msg #N»' markers"
+                            },
+                            "seen": {
+                                "type": "array",
+                                "description": "Optional content the client already holds, elided like session history",
+                                "items": {
+                                    "type": "object",
+                                    "properties": {
+                                        "key": { "type": "string", "description": "File path or zoom target" },
+                                        "fingerprint": { "type": "string", "description": "MD5 of the content (file header checksum)" },
+                                        "message": { "type": "integer", "description": "Message the content was delivered in" },
+                                        "complete": { "type": "boolean", "description": "Whether the full content was delivered (default: true)" }
+                                    },
+                                    "required": ["key", "fingerprint", "message"]
+                                }
+                            },
+                            "message": {
+                                "type": "integer",
+                                "description": "Number of this message in the conversation (default: one past the last recorded)"
+                            }
+                        }
+                    }
+                },
+                {
+                    "name": "get_context_page",
+                    "description": "Fetch the next page of a paginated get_context result",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "token": {
+                                "type": "string",
+                                "description": "Continuation token from the previous page"
+                            }
+                        },
+                        "required": ["token"]
+                    }
+                },
+                {
+                    "name": "preview_context",
+                    "description": "Preview what get_context would include: per-file action (full/truncated/skeleton/dropped), estimated tokens and reasons, without generating content",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "path": {
+                                "type": "string",
+                                "description": "Path to preview (default: project root)"
+                            },
+                            "lens": {
+                                "type": "string",
+                                "description": "Context lens: architecture, debug, security, minimal, onboarding"
+                            },
+                            "token_budget": {
+                                "type": "string",
+                                "description": "Token budget (e.g., '100k', '2M')"
+                            },
+                            "skeleton": {
+                                "type": "string",
+                                "description": "Skeleton mode: 'auto' (enable if budget set), 'true', 'false'"
+                            },
+                            "json": {
+                                "type": "boolean",
+                                "description": "Return the preview as JSON instead of a text table"
                             }
                         }
                     }
@@ -290,7 +675,7 @@ impl McpServer {
                         "properties": {
                             "target": {
                                 "type": "string",
-                                "description": "Zoom target (e.g., 'function=main', 'class=Config', 'file=src/lib.rs:10-50')"
+                                "description": "Zoom target (e.g., 'function=main', 'class=Config', 'section=README.md#Installation', 'anchor=src/lib.rs#Engine.run@1a2b3c4d', 'file=src/lib.rs:10-50', 'bookmark=payment-flow')"
                             },
                             "path": {
                                 "type": "string",
@@ -298,12 +683,55 @@ impl McpServer {
                             },
                             "session_id": {
                                 "type": "string",
-                                "description": "Optional session ID to track zoom history"
+                                "description": "Optional session ID: a target already delivered unchanged in this session is replaced by an '«unchanged since msg #N»' marker"
+                            },
+                            "seen": {
+                                "type": "array",
+                                "description": "Optional content the client already holds ({key, fingerprint, message, complete}), as for get_context"
+                            },
+                            "message": {
+                                "type": "integer",
+                                "description": "Number of this message in the conversation (default: one past the last recorded)"
+                            },
+                            "include_tests": {
+                                "type": "boolean",
+                                "description": "Also include the tests that exercise the target (default: false)"
+                            },
+                            "include_docs": {
+                                "type": "boolean",
+                                "description": "Also include doc sections and docstrings that mention the target (default: false)"
                             }
                         },
                         "required": ["target"]
                     }
                 },
+                {
+                    "name": "zoom_batch",
+                    "description": "Zoom into several targets at once within one shared token budget",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "targets": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Zoom targets (e.g., ['function=main', 'class=Config', 'file=src/lib.rs:10-50'])"
+                            },
+                            "budget": {
+                                "type": "string",
+                                "description": "Optional shared token budget (e.g., '4k', '20000')"
+                            },
+                            "strategy": {
+                                "type": "string",
+                                "description": "Packing strategy: 'proportional' (default) or 'priority' (request order)"
+                            },
+                            "path": {
+                                "type": "string",
+                                "description": "Optional: Override project root path (default: server root)"
+                            }
+                        },
+                        "required": ["targets"]
+                    }
+                },
                 {
                     "name": "session_list",
                     "description": "List all saved zoom sessions",
@@ -381,14 +809,120 @@ impl McpServer {
                         },
                         "required": ["intent"]
                     }
+                },
+                {
+                    "name": "capabilities",
+                    "description": "Describe this server: version, IR version, languages and what each adapter extracts, enabled features, lenses and limits",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "bookmark_add",
+                    "description": "Save a zoom target under a name, usable afterwards as the target 'bookmark=<name>'",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "name": {
+                                "type": "string",
+                                "description": "Bookmark name (letters, digits, '-', '_', '.')"
+                            },
+                            "target": {
+                                "type": "string",
+                                "description": "Zoom target to save (e.g., 'function=process_payment', 'file=src/lib.rs:10-50')"
+                            },
+                            "note": {
+                                "type": "string",
+                                "description": "Optional note on why the target matters"
+                            }
+                        },
+                        "required": ["name", "target"]
+                    }
+                },
+                {
+                    "name": "bookmark_list",
+                    "description": "List the project's bookmarks with their targets and notes",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
+                {
+                    "name": "bookmark_resolve",
+                    "description": "Locate a bookmark's target: the file and line range it points at now",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "name": {
+                                "type": "string",
+                                "description": "Bookmark name"
+                            }
+                        },
+                        "required": ["name"]
+                    }
+                },
+                {
+                    "name": "investigation_start",
+                    "description": "Start (or resume) an investigation that investigation_add records zooms, searches and notes into",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "name": {
+                                "type": "string",
+                                "description": "Investigation name"
+                            },
+                            "description": {
+                                "type": "string",
+                                "description": "Optional: what is being investigated"
+                            }
+                        },
+                        "required": ["name"]
+                    }
+                },
+                {
+                    "name": "investigation_add",
+                    "description": "Record a step in the active investigation: a zoom (returns its context), a symbol search (returns definitions and usages) or a note",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "kind": {
+                                "type": "string",
+                                "description": "'zoom', 'search' or 'note'"
+                            },
+                            "value": {
+                                "type": "string",
+                                "description": "Zoom target (e.g., 'function=main', 'bookmark=payment-flow'), symbol name, or note text"
+                            }
+                        },
+                        "required": ["kind", "value"]
+                    }
+                },
+                {
+                    "name": "investigation_report",
+                    "description": "Render an investigation as one Markdown report: files involved, then every zoom context, search hit and note in order",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "name": {
+                                "type": "string",
+                                "description": "Optional: investigation name (default: the active one)"
+                            },
+                            "format": {
+                                "type": "string",
+                                "description": "Output format: 'markdown' or 'json' (default: 'markdown')"
+                            }
+                        }
+                    }
                 }
             ]
         });
+        self.workspaces.annotate_tools(&mut tools);
 
         JsonRpcResponse::success(id, tools)
     }
 
-    fn handle_tools_call(&self, id: Value, params: Option<Value>) -> JsonRpcResponse {
+    fn handle_tools_call(&mut self, id: Value, params: Option<Value>) -> JsonRpcResponse {
         let params = match params {
             Some(p) => p,
             None => {
@@ -405,11 +939,21 @@ impl McpServer {
 
         match tool_name {
             "get_context" => self.tool_get_context(id, arguments),
+            "get_context_page" => self.tool_get_context_page(id, arguments),
+            "preview_context" => self.tool_preview_context(id, arguments),
             "zoom" => self.tool_zoom(id, arguments),
-            "session_list" => self.tool_session_list(id),
+            "zoom_batch" => self.tool_zoom_batch(id, arguments),
+            "session_list" => self.tool_session_list(id, arguments),
             "session_create" => self.tool_session_create(id, arguments),
             "report_utility" => self.tool_report_utility(id, arguments),
             "explore_with_intent" => self.tool_explore_with_intent(id, arguments),
+            "capabilities" => self.tool_capabilities(id),
+            "bookmark_add" => self.tool_bookmark_add(id, arguments),
+            "bookmark_list" => self.tool_bookmark_list(id, arguments),
+            "bookmark_resolve" => self.tool_bookmark_resolve(id, arguments),
+            "investigation_start" => self.tool_investigation_start(id, arguments),
+            "investigation_add" => self.tool_investigation_add(id, arguments),
+            "investigation_report" => self.tool_investigation_report(id, arguments),
             _ => JsonRpcResponse::error(
                 id,
                 METHOD_NOT_FOUND,
@@ -423,179 +967,269 @@ impl McpServer {
     // ========================================================================
 
     #[allow(clippy::field_reassign_with_default)]
-    fn tool_get_context(&self, id: Value, args: Value) -> JsonRpcResponse {
-        let path = args.get("path")
-            .and_then(|v| v.as_str())
-            .map(PathBuf::from)
-            .unwrap_or_else(|| self.project_root.clone());
+    fn tool_get_context(&mut self, id: Value, args: Value) -> JsonRpcResponse {
+        let page_size = match args.get("page_size") {
+            None => DEFAULT_PAGE_TOKENS,
+            Some(v) => match v.as_str().map(parse_token_budget) {
+                Some(Ok(size)) if size > 0 => size,
+                _ => {
+                    return JsonRpcResponse::error(
+                        id,
+                        INVALID_PARAMS,
+                        "Invalid page_size: expected a token count like '20k'".to_string(),
+                    );
+                }
+            },
+        };
 
-        let lens = args.get("lens").and_then(|v| v.as_str());
-        let token_budget = args.get("token_budget").and_then(|v| v.as_str());
-        let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("plusminus");
-        let skeleton = args.get("skeleton").and_then(|v| v.as_str()).unwrap_or("auto");
+        // Bundled snapshot: the context is fixed, lens/budget arguments do not apply
+        if let Some(bundle) = &self.bundle {
+            let page = self.pages.paginate(bundle.context.clone(), page_size);
+            return tool_success(id, page.render());
+        }
 
-        // TODO: Load project .pm_encoder_config.json when core::EncoderConfig supports Deserialize
-        // For now, use defaults - the lens will override patterns anyway
-        let mut config = EncoderConfig::default();
-        config.output_format = match format {
-            "xml" => OutputFormat::Xml,
-            "markdown" => OutputFormat::Markdown,
-            "claude-xml" => OutputFormat::ClaudeXml,
-            _ => OutputFormat::PlusMinus,
+        let (path, config) = match self.context_config(&id, &args) {
+            Ok(parsed) => parsed,
+            Err(response) => return *response,
         };
 
-        // Apply skeleton mode (v2.2.0)
-        config.skeleton_mode = SkeletonMode::parse(skeleton).unwrap_or(SkeletonMode::Auto);
+        let ledger = match self.take_ledger(&id, &args) {
+            Ok(ledger) => ledger,
+            Err(response) => return *response,
+        };
 
-        // Apply lens and merge patterns into config
-        let mut lens_manager = LensManager::new();
-        if let Some(lens_name) = lens {
-            match lens_manager.apply_lens(lens_name) {
-                Ok(applied) => {
-                    // Merge lens patterns into config
-                    config.ignore_patterns.extend(applied.ignore_patterns);
-                    if !applied.include_patterns.is_empty() {
-                        config.include_patterns = applied.include_patterns;
-                    }
-                    config.active_lens = Some(lens_name.to_string());
-                }
-                Err(e) => {
-                    return tool_error(id, format!("Invalid lens '{}': {}", lens_name, e));
+        // Generate context
+        let started = Instant::now();
+        let mut engine = ContextEngine::with_config(config).with_cancellation(self.cancel.clone());
+        if let Some(store) = &self.blobs {
+            engine = engine.with_blob_store(store.clone());
+        }
+        let root = path.to_str().unwrap_or(".");
+        let result = match ledger {
+            Some((session, mut ledger, message)) => {
+                let result = engine.serialize_deduplicated(root, &mut ledger, message);
+                self.restore_ledger(session, ledger);
+                result
+            }
+            None => engine.serialize(root),
+        };
+        match result {
+            Ok(context) => {
+                if let Some(prefetcher) = &self.prefetch {
+                    prefetcher.prefetch(&path, &context);
                 }
+                let page = self.pages.paginate(context, page_size);
+                tool_success(id, page.render())
             }
+            Err(e @ EncoderError::Cancelled { .. }) => cancelled(id, &e, started),
+            Err(e) => tool_error(id, format!("Serialization failed: {}", e)),
         }
+    }
 
-        // Parse token budget
-        if let Some(budget_str) = token_budget {
-            match parse_token_budget(budget_str) {
-                Ok(budget) => config.token_budget = Some(budget),
-                Err(e) => {
-                    return JsonRpcResponse::error(
-                        id,
-                        INVALID_PARAMS,
-                        format!("Invalid token budget: {}", e),
-                    );
-                }
-            }
+    fn tool_preview_context(&self, id: Value, args: Value) -> JsonRpcResponse {
+        if let Some(bundle) = &self.bundle {
+            return tool_success(id, bundle.summary());
         }
 
-        // Generate context
-        let engine = ContextEngine::with_config(config);
-        match engine.serialize(path.to_str().unwrap_or(".")) {
-            Ok(context) => tool_success(id, context),
-            Err(e) => tool_error(id, format!("Serialization failed: {}", e)),
+        let (path, config) = match self.context_config(&id, &args) {
+            Ok(parsed) => parsed,
+            Err(response) => return *response,
+        };
+        let as_json = args.get("json").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let started = Instant::now();
+        let engine = ContextEngine::with_config(config).with_cancellation(self.cancel.clone());
+        match engine.preview(path.to_str().unwrap_or(".")) {
+            Ok(preview) if as_json => tool_success(id, preview.to_json()),
+            Ok(preview) => tool_success(id, preview.to_text()),
+            Err(e @ EncoderError::Cancelled { .. }) => cancelled(id, &e, started),
+            Err(e) => tool_error(id, format!("Preview failed: {}", e)),
         }
     }
 
-    fn tool_zoom(&self, id: Value, args: Value) -> JsonRpcResponse {
-        let target_str = match args.get("target").and_then(|v| v.as_str()) {
+    /// Content ledger for a get_context/zoom call, taken out of its session
+    ///
+    /// Deduplication applies when the call names a `session_id` or passes
+    /// `seen` fingerprints. Returns the session, its ledger seeded with
+    /// `seen`, and the number of this message; hand the ledger back with
+    /// [`Self::restore_ledger`].
+    #[allow(clippy::type_complexity)]
+    fn take_ledger(
+        &mut self,
+        id: &Value,
+        args: &Value,
+    ) -> Result<Option<(Option<String>, ContentLedger, u64)>, Box<JsonRpcResponse>> {
+        let session = args.get("session_id").and_then(|v| v.as_str()).map(str::to_string);
+        let seen: Vec<SeenContent> = match args.get("seen") {
+            None => Vec::new(),
+            Some(v) => serde_json::from_value(v.clone()).map_err(|e| {
+                Box::new(JsonRpcResponse::error(id.clone(), INVALID_PARAMS, format!("Invalid 'seen' parameter: {}", e)))
+            })?,
+        };
+        if session.is_none() && seen.is_empty() {
+            return Ok(None);
+        }
+
+        let mut ledger = session.as_ref().and_then(|s| self.ledgers.remove(s)).unwrap_or_default();
+        ledger.extend_seen(seen);
+        let message = args.get("message").and_then(|v| v.as_u64()).unwrap_or_else(|| ledger.next_message());
+        Ok(Some((session, ledger, message)))
+    }
+
+    /// Keep a session's ledger for its next call (sessionless ledgers are dropped)
+    fn restore_ledger(&mut self, session: Option<String>, ledger: ContentLedger) {
+        if let Some(session) = session {
+            self.ledgers.insert(session, ledger);
+        }
+    }
+
+    /// The workspace named by the `workspace` argument (`None` with a single root)
+    fn workspace(&self, id: &Value, args: &Value) -> Result<Option<&Workspace>, Box<JsonRpcResponse>> {
+        self.workspaces
+            .resolve(args)
+            .map_err(|e| Box::new(JsonRpcResponse::error(id.clone(), INVALID_PARAMS, e)))
+    }
+
+    /// Root of the requested workspace, or the project root
+    fn workspace_root(&self, id: &Value, args: &Value) -> Result<PathBuf, Box<JsonRpcResponse>> {
+        Ok(self.workspace(id, args)?.map_or_else(|| self.project_root.clone(), |w| w.root.clone()))
+    }
+
+    /// Directory named by the `path` argument (default: workspace or project
+    /// root), confined to that root
+    fn requested_root(&self, id: &Value, args: &Value) -> Result<PathBuf, Box<JsonRpcResponse>> {
+        let root = self.workspace_root(id, args)?;
+        let Some(path) = args.get("path").and_then(|v| v.as_str()) else {
+            return Ok(root);
+        };
+        PathSandbox::new(&root)
+            .and_then(|sandbox| sandbox.resolve(path))
+            .map_err(|e| Box::new(error_response(id.clone(), &e)))
+    }
+
+    /// Build the path and engine config shared by get_context and preview_context
+    fn context_config(&self, id: &Value, args: &Value) -> Result<(PathBuf, EncoderConfig), Box<JsonRpcResponse>> {
+        let path = self.requested_root(id, args)?;
+        let workspace = self.workspace(id, args)?;
+
+        let options = ContextOptions {
+            lens: args.get("lens").and_then(|v| v.as_str()).or(workspace.and_then(|w| w.lens.as_deref())),
+            token_budget: args.get("token_budget").and_then(|v| v.as_str()),
+            format: args.get("format").and_then(|v| v.as_str()).unwrap_or("plusminus"),
+            skeleton: args.get("skeleton").and_then(|v| v.as_str()).unwrap_or("auto"),
+        };
+
+        match options.encoder_config() {
+            Ok(mut config) => {
+                if let Some(workspace) = workspace {
+                    config.token_budget = workspace.cap_budget(config.token_budget);
+                }
+                Ok((path, config))
+            }
+            Err(ContextOptionsError::Lens(e)) => Err(Box::new(tool_error(id.clone(), e))),
+            Err(ContextOptionsError::Budget(e)) => Err(Box::new(JsonRpcResponse::error(
+                id.clone(),
+                INVALID_PARAMS,
+                e,
+            ))),
+        }
+    }
+
+    fn tool_get_context_page(&self, id: Value, args: Value) -> JsonRpcResponse {
+        let token = match args.get("token").and_then(|v| v.as_str()) {
             Some(t) => t,
             None => {
                 return JsonRpcResponse::error(
                     id,
                     INVALID_PARAMS,
-                    "Missing 'target' parameter".to_string(),
+                    "Missing 'token' parameter".to_string(),
                 );
             }
         };
 
-        // Parse optional path override (default: server's project_root)
-        let project_root = args.get("path")
-            .and_then(|v| v.as_str())
-            .map(PathBuf::from)
-            .unwrap_or_else(|| self.project_root.clone());
-
-        // Parse target (e.g., "function=main", "file=src/lib.rs:10-50")
-        let parts: Vec<&str> = target_str.splitn(2, '=').collect();
-        if parts.len() != 2 {
-            return JsonRpcResponse::error(
-                id,
-                INVALID_PARAMS,
-                format!("Invalid target format '{}'. Expected <type>=<value>", target_str),
-            );
+        match self.pages.get(token) {
+            Ok(page) => tool_success(id, page.render()),
+            Err(e) => JsonRpcResponse::error(id, INVALID_PARAMS, e),
         }
+    }
 
-        let (target_type, target_value) = (parts[0], parts[1]);
-
-        // Build ZoomTarget
-        let mut target = match target_type {
-            "function" | "fn" => ZoomTarget::Function(target_value.to_string()),
-            "class" | "struct" => ZoomTarget::Class(target_value.to_string()),
-            "module" | "mod" => ZoomTarget::Module(target_value.to_string()),
-            "file" => {
-                // Parse optional line range
-                if let Some(colon_pos) = target_value.rfind(':') {
-                    let path = target_value[..colon_pos].to_string();
-                    let range = &target_value[colon_pos + 1..];
-                    if let Some(dash_pos) = range.find('-') {
-                        let start = range[..dash_pos].parse().ok();
-                        let end = range[dash_pos + 1..].parse().ok();
-                        ZoomTarget::File { path, start_line: start, end_line: end }
-                    } else {
-                        ZoomTarget::File { path, start_line: range.parse().ok(), end_line: None }
-                    }
-                } else {
-                    ZoomTarget::File { path: target_value.to_string(), start_line: None, end_line: None }
-                }
-            }
-            _ => {
+    fn tool_zoom(&mut self, id: Value, args: Value) -> JsonRpcResponse {
+        let target_str = match args.get("target").and_then(|v| v.as_str()) {
+            Some(t) => t,
+            None => {
                 return JsonRpcResponse::error(
                     id,
                     INVALID_PARAMS,
-                    format!("Unknown target type '{}'. Use: function, class, module, file", target_type),
+                    "Missing 'target' parameter".to_string(),
                 );
             }
         };
 
-        // Symbol resolution for function/class
-        let resolved_name = match &target {
-            ZoomTarget::Function(name) | ZoomTarget::Class(name) => Some(name.clone()),
-            _ => None,
+        // Parse optional path override (default: server's project_root)
+        let project_root = match self.requested_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
         };
 
-        if let ZoomTarget::Function(name) = &target {
-            let resolver = SymbolResolver::new();
-            match resolver.find_function(name, &project_root) {
-                Ok(loc) => {
-                    target = ZoomTarget::File {
-                        path: loc.path,
-                        start_line: Some(loc.start_line),
-                        end_line: Some(loc.end_line),
-                    };
-                }
-                Err(e) => {
-                    return JsonRpcResponse::error(id, INVALID_PARAMS, e);
-                }
-            }
-        } else if let ZoomTarget::Class(name) = &target {
-            let resolver = SymbolResolver::new();
-            match resolver.find_class(name, &project_root) {
-                Ok(loc) => {
-                    target = ZoomTarget::File {
-                        path: loc.path,
-                        start_line: Some(loc.start_line),
-                        end_line: Some(loc.end_line),
-                    };
-                }
-                Err(e) => {
-                    return JsonRpcResponse::error(id, INVALID_PARAMS, e);
-                }
-            }
-        }
+        let include_tests = args.get("include_tests").and_then(|v| v.as_bool()).unwrap_or(false);
+        let include_docs = args.get("include_docs").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        // Prefetched zooms cover the default options only
+        let prefetched = self
+            .prefetch
+            .as_ref()
+            .filter(|_| !include_tests && !include_docs)
+            .and_then(|prefetcher| prefetcher.get(&project_root, target_str));
 
-        // Build zoom config
-        let zoom_config = ZoomConfig {
-            target,
-            budget: None,
-            depth: ZoomDepth::Full,
-            include_tests: false,
-            context_lines: 5,
+        let zoomed = match prefetched {
+            Some(PrefetchedZoom { output, resolved_name }) => Ok((output, resolved_name)),
+            None => {
+                let (target, resolved_name) = match Self::resolve_zoom_target(target_str, &project_root) {
+                    Ok(resolved) => resolved,
+                    Err(e) => return JsonRpcResponse::error(id, INVALID_PARAMS, e),
+                };
+
+                // Build zoom config
+                let zoom_config = ZoomConfig {
+                    target,
+                    budget: None,
+                    depth: ZoomDepth::Full,
+                    include_tests,
+                    include_docs,
+                    context_lines: 5,
+                };
+
+                // Execute zoom
+                let engine = ContextEngine::new();
+                engine
+                    .zoom(project_root.to_str().unwrap_or("."), &zoom_config)
+                    .map(|output| (output, resolved_name))
+            }
         };
 
-        // Execute zoom
-        let engine = ContextEngine::new();
-        match engine.zoom(project_root.to_str().unwrap_or("."), &zoom_config) {
-            Ok(mut output) => {
+        match zoomed {
+            Ok((mut output, resolved_name)) => {
+                let ledger = match self.take_ledger(&id, &args) {
+                    Ok(ledger) => ledger,
+                    Err(response) => return *response,
+                };
+                let mut ledger_tag = None;
+                if let Some((session, mut ledger, message)) = ledger {
+                    let fingerprint = ledger::fingerprint(&output);
+                    let delivered = ledger.delivered_in(target_str, &fingerprint, true);
+                    if delivered.is_none() {
+                        ledger.record(target_str, &fingerprint, message, true);
+                    }
+                    self.restore_ledger(session, ledger);
+                    if let Some(delivered) = delivered {
+                        return tool_success(id, format!("{} {}", target_str, ledger::unchanged_marker(delivered)));
+                    }
+                    ledger_tag = Some(format!(
+                        "<ledger key=\"{}\" fingerprint=\"{}\" message=\"{}\"/>",
+                        target_str, fingerprint, message
+                    ));
+                }
+
                 // Add zoom menu with call graph analysis (callees)
                 let call_analyzer = CallGraphAnalyzer::new().with_max_results(10);
                 let resolver = SymbolResolver::new();
@@ -634,24 +1268,191 @@ impl McpServer {
                         None,  // definition_line
                     );
 
-                    if !callers.is_empty() || !callees.is_empty() {
-                        let related = RelatedContext {
-                            callers,
-                            callees: callees.clone(),
-                        };
-                        output.push('\n');
-                        output.push_str(&related.to_xml());
+                    if !callers.is_empty() || !callees.is_empty() {
+                        let related = RelatedContext {
+                            callers,
+                            callees: callees.clone(),
+                        };
+                        output.push('\n');
+                        output.push_str(&related.to_xml());
+                    }
+                }
+
+                if let Some(tag) = ledger_tag {
+                    output.push('\n');
+                    output.push_str(&tag);
+                }
+
+                tool_success(id, output)
+            }
+            Err(e @ EncoderError::PolicyViolation { .. }) => error_response(id, &e),
+            Err(e) => tool_error(id, format!("Zoom failed: {}", e)),
+        }
+    }
+
+    fn tool_zoom_batch(&self, id: Value, args: Value) -> JsonRpcResponse {
+        let target_strs: Vec<&str> = match args.get("targets").and_then(|v| v.as_array()) {
+            Some(items) if !items.is_empty() => {
+                let mut strs = Vec::with_capacity(items.len());
+                for (index, item) in items.iter().enumerate() {
+                    let Some(target) = item.as_str() else {
+                        return JsonRpcResponse::error(
+                            id,
+                            INVALID_PARAMS,
+                            format!("targets[{}] must be a string, got {}", index, item),
+                        );
+                    };
+                    strs.push(target);
+                }
+                strs
+            }
+            _ => {
+                return JsonRpcResponse::error(
+                    id,
+                    INVALID_PARAMS,
+                    "Missing 'targets' parameter (non-empty array of strings)".to_string(),
+                );
+            }
+        };
+
+        let project_root = match self.requested_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
+        };
+
+        // Resolve all targets up front: the batch is atomic
+        let mut targets = Vec::with_capacity(target_strs.len());
+        for target_str in target_strs {
+            match Self::resolve_zoom_target(target_str, &project_root) {
+                Ok((target, _)) => targets.push(target),
+                Err(e) => {
+                    return JsonRpcResponse::error(id, INVALID_PARAMS, format!("{}: {}", target_str, e));
+                }
+            }
+        }
+
+        let mut batch = BatchZoomConfig::new(targets);
+
+        let mut budget = None;
+        if let Some(budget_str) = args.get("budget").and_then(|v| v.as_str()) {
+            match parse_token_budget(budget_str) {
+                Ok(parsed) => budget = Some(parsed),
+                Err(e) => {
+                    return JsonRpcResponse::error(
+                        id,
+                        INVALID_PARAMS,
+                        format!("Invalid token budget: {}", e),
+                    );
+                }
+            }
+        }
+        if let Ok(Some(workspace)) = self.workspaces.resolve(&args) {
+            budget = workspace.cap_budget(budget);
+        }
+        if let Some(budget) = budget {
+            batch = batch.with_budget(budget);
+        }
+
+        if let Some(strategy_str) = args.get("strategy").and_then(|v| v.as_str()) {
+            match PackingStrategy::parse(strategy_str) {
+                Some(strategy) => batch = batch.with_strategy(strategy),
+                None => {
+                    return JsonRpcResponse::error(
+                        id,
+                        INVALID_PARAMS,
+                        format!("Unknown strategy '{}'. Use: proportional, priority", strategy_str),
+                    );
+                }
+            }
+        }
+
+        let engine = ContextEngine::new();
+        match engine.zoom_batch(project_root.to_str().unwrap_or("."), &batch) {
+            Ok(output) => tool_success(id, output),
+            Err(e @ EncoderError::PolicyViolation { .. }) => error_response(id, &e),
+            Err(e) => tool_error(id, format!("Batch zoom failed: {}", e)),
+        }
+    }
+
+    /// Parse a `<type>=<value>` zoom target and resolve symbols to file ranges
+    ///
+    /// Returns the target plus the original symbol name for function/class targets.
+    fn resolve_zoom_target(
+        target_str: &str,
+        project_root: &Path,
+    ) -> std::result::Result<(ZoomTarget, Option<String>), String> {
+        let expanded = crate::core::bookmarks::expand_target(project_root, target_str).map_err(|e| e.to_string())?;
+        let target_str = expanded.as_str();
+
+        // Parse target (e.g., "function=main", "file=src/lib.rs:10-50")
+        let parts: Vec<&str> = target_str.splitn(2, '=').collect();
+        if parts.len() != 2 {
+            return Err(format!("Invalid target format '{}'. Expected <type>=<value>", target_str));
+        }
+
+        let (target_type, target_value) = (parts[0], parts[1]);
+
+        // Build ZoomTarget
+        let mut target = match target_type {
+            "function" | "fn" => ZoomTarget::Function(target_value.to_string()),
+            "class" | "struct" => ZoomTarget::Class(target_value.to_string()),
+            "module" | "mod" => ZoomTarget::Module(target_value.to_string()),
+            "section" | "heading" => ZoomTarget::Section(target_value.trim_matches('"').to_string()),
+            // Anchors are resolved by the engine
+            "anchor" | "id" => ZoomTarget::Anchor(Anchor::parse(target_value).map_err(|e| e.to_string())?),
+            "file" => {
+                // Parse optional line range
+                if let Some(colon_pos) = target_value.rfind(':') {
+                    let path = target_value[..colon_pos].to_string();
+                    let range = &target_value[colon_pos + 1..];
+                    if let Some(dash_pos) = range.find('-') {
+                        let start = range[..dash_pos].parse().ok();
+                        let end = range[dash_pos + 1..].parse().ok();
+                        ZoomTarget::File { path, start_line: start, end_line: end }
+                    } else {
+                        ZoomTarget::File { path, start_line: range.parse().ok(), end_line: None }
                     }
+                } else {
+                    ZoomTarget::File { path: target_value.to_string(), start_line: None, end_line: None }
                 }
-
-                tool_success(id, output)
             }
-            Err(e) => tool_error(id, format!("Zoom failed: {}", e)),
+            _ => {
+                return Err(format!("Unknown target type '{}'. Use: function, class, module, file, section, anchor", target_type));
+            }
+        };
+
+        // Symbol resolution for function/class
+        let resolved_name = match &target {
+            ZoomTarget::Function(name) | ZoomTarget::Class(name) => Some(name.clone()),
+            ZoomTarget::Anchor(anchor) => Some(anchor.name().to_string()),
+            _ => None,
+        };
+
+        if let ZoomTarget::Function(name) = &target {
+            let loc = SymbolResolver::new().find_function(name, project_root)?;
+            target = ZoomTarget::File {
+                path: loc.path,
+                start_line: Some(loc.start_line),
+                end_line: Some(loc.end_line),
+            };
+        } else if let ZoomTarget::Class(name) = &target {
+            let loc = SymbolResolver::new().find_class(name, project_root)?;
+            target = ZoomTarget::File {
+                path: loc.path,
+                start_line: Some(loc.start_line),
+                end_line: Some(loc.end_line),
+            };
         }
+
+        Ok((target, resolved_name))
     }
 
-    fn tool_session_list(&self, id: Value) -> JsonRpcResponse {
-        let session_path = ZoomSessionStore::default_path(&self.project_root);
+    fn tool_session_list(&self, id: Value, args: Value) -> JsonRpcResponse {
+        let root = match self.workspace_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
+        };
+        let session_path = ZoomSessionStore::default_path(&root);
 
         match ZoomSessionStore::load(&session_path) {
             Ok(store) => {
@@ -685,7 +1486,11 @@ impl McpServer {
         };
 
         let description = args.get("description").and_then(|v| v.as_str());
-        let session_path = ZoomSessionStore::default_path(&self.project_root);
+        let root = match self.workspace_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
+        };
+        let session_path = ZoomSessionStore::default_path(&root);
 
         match ZoomSessionStore::with_persistence(&session_path, |store| {
             if let Some(desc) = description {
@@ -732,19 +1537,170 @@ impl McpServer {
 
         let reason = args.get("reason").and_then(|v| v.as_str()).unwrap_or("MCP feedback");
 
-        let store_path = ContextStore::default_path(&self.project_root);
+        let root = match self.workspace_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
+        };
+        let store_path = ContextStore::default_path(&root);
         let mut store = ContextStore::load_from_file(&store_path);
 
-        store.report_utility(path, utility, DEFAULT_ALPHA);
+        let key = crate::core::normalize_relative_path(path);
+        store.report_utility(&key, utility, DEFAULT_ALPHA);
 
         if let Err(e) = store.save_to_file(&store_path) {
             return tool_error(id, format!("Failed to save: {}", e));
         }
 
-        let current = store.get_utility_score(path);
+        let current = store.get_utility_score(&key);
         tool_success(id, format!("Utility reported for '{}': {:.2} → {:.2} ({})", path, utility, current, reason))
     }
 
+    fn tool_capabilities(&self, id: Value) -> JsonRpcResponse {
+        let limits = self.limiter.as_deref().map(Limiter::limits);
+        tool_success(id, Capabilities::detect(limits).to_json())
+    }
+
+    fn tool_bookmark_add(&self, id: Value, args: Value) -> JsonRpcResponse {
+        let (Some(name), Some(target)) = (
+            args.get("name").and_then(|v| v.as_str()),
+            args.get("target").and_then(|v| v.as_str()),
+        ) else {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing 'name' or 'target' parameter".to_string());
+        };
+        let note = args.get("note").and_then(|v| v.as_str()).map(str::to_string);
+        let root = match self.workspace_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
+        };
+
+        let added = BookmarkStore::load(&BookmarkStore::default_path(&root))
+            .and_then(|mut store| store.add(&root, name, target, note).and_then(|()| store.save()));
+        match added {
+            Ok(()) => tool_success(id, format!("Bookmarked '{}' -> {}. Zoom with target 'bookmark={}'", name, target, name)),
+            Err(e @ (EncoderError::PolicyViolation { .. } | EncoderError::InvalidZoomTarget { .. } | EncoderError::InvalidConfig { .. })) => {
+                error_response(id, &e)
+            }
+            Err(e) => tool_error(id, format!("Failed to save bookmark: {}", e)),
+        }
+    }
+
+    fn tool_bookmark_list(&self, id: Value, args: Value) -> JsonRpcResponse {
+        let root = match self.workspace_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
+        };
+        match BookmarkStore::load(&BookmarkStore::default_path(&root)) {
+            Ok(store) => tool_success(id, serde_json::to_string_pretty(&store.bookmarks).unwrap_or_default()),
+            Err(e) => tool_error(id, format!("Failed to load bookmarks: {}", e)),
+        }
+    }
+
+    fn tool_bookmark_resolve(&self, id: Value, args: Value) -> JsonRpcResponse {
+        let Some(name) = args.get("name").and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing 'name' parameter".to_string());
+        };
+        let root = match self.workspace_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
+        };
+        let resolver = SymbolResolver::new().with_ignore(EncoderConfig::default().ignore_patterns);
+        match BookmarkStore::load(&BookmarkStore::default_path(&root)).and_then(|store| store.resolve(name, &root, &resolver)) {
+            Ok(resolved) => tool_success(id, serde_json::to_string_pretty(&resolved).unwrap_or_default()),
+            Err(e) => tool_error(id, format!("Failed to resolve bookmark: {}", e)),
+        }
+    }
+
+    fn tool_investigation_start(&self, id: Value, args: Value) -> JsonRpcResponse {
+        let Some(name) = args.get("name").and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing 'name' parameter".to_string());
+        };
+        let description = args.get("description").and_then(|v| v.as_str()).map(str::to_string);
+        let root = match self.workspace_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
+        };
+
+        let started = InvestigationStore::load(&InvestigationStore::default_path(&root)).and_then(|mut store| {
+            let entries = store.start(name, description).entries.len();
+            store.save().map(|()| entries)
+        });
+        match started {
+            Ok(0) => tool_success(id, format!("Started investigation '{}'", name)),
+            Ok(entries) => tool_success(id, format!("Resumed investigation '{}' ({} entries)", name, entries)),
+            Err(e) => tool_error(id, format!("Failed to start investigation: {}", e)),
+        }
+    }
+
+    fn tool_investigation_add(&self, id: Value, args: Value) -> JsonRpcResponse {
+        let (Some(kind), Some(value)) = (
+            args.get("kind").and_then(|v| v.as_str()),
+            args.get("value").and_then(|v| v.as_str()),
+        ) else {
+            return JsonRpcResponse::error(id, INVALID_PARAMS, "Missing 'kind' or 'value' parameter".to_string());
+        };
+        let root = match self.workspace_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
+        };
+        let mut store = match InvestigationStore::load(&InvestigationStore::default_path(&root)) {
+            Ok(store) => store,
+            Err(e) => return tool_error(id, format!("Failed to load investigations: {}", e)),
+        };
+        let Some(investigation) = store.active_mut() else {
+            return tool_error(id, "No active investigation. Start one with investigation_start".to_string());
+        };
+
+        let text = match kind {
+            "zoom" => match investigation.zoom(&ContextEngine::new(), &root, value) {
+                Ok(entry) => entry.context().unwrap_or_default().to_string(),
+                Err(e) => return error_response(id, &e),
+            },
+            "search" => {
+                let resolver = SymbolResolver::new().with_ignore(EncoderConfig::default().ignore_patterns);
+                serde_json::to_string_pretty(investigation.search(&resolver, &root, value).hits()).unwrap_or_default()
+            }
+            "note" => {
+                investigation.note(value);
+                format!("Noted in investigation '{}'", investigation.name)
+            }
+            _ => {
+                return JsonRpcResponse::error(
+                    id,
+                    INVALID_PARAMS,
+                    format!("Unknown kind '{}'. Use: zoom, search, note", kind),
+                );
+            }
+        };
+        match store.save() {
+            Ok(()) => tool_success(id, text),
+            Err(e) => tool_error(id, format!("Failed to save investigation: {}", e)),
+        }
+    }
+
+    fn tool_investigation_report(&self, id: Value, args: Value) -> JsonRpcResponse {
+        let root = match self.workspace_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
+        };
+        let store = match InvestigationStore::load(&InvestigationStore::default_path(&root)) {
+            Ok(store) => store,
+            Err(e) => return tool_error(id, format!("Failed to load investigations: {}", e)),
+        };
+        let name = args.get("name").and_then(|v| v.as_str());
+        let Some(investigation) = name.map_or_else(|| store.active(), |name| store.get(name)) else {
+            return tool_error(id, format!("No investigation {}", name.map_or("active".to_string(), |n| format!("'{}'", n))));
+        };
+        match args.get("format").and_then(|v| v.as_str()).unwrap_or("markdown") {
+            "markdown" | "md" => tool_success(id, investigation.render()),
+            "json" => tool_success(id, investigation.to_json()),
+            other => JsonRpcResponse::error(
+                id,
+                INVALID_PARAMS,
+                format!("Unknown format '{}'. Use: markdown, json", other),
+            ),
+        }
+    }
+
     fn tool_explore_with_intent(&self, id: Value, args: Value) -> JsonRpcResponse {
         // Parse intent (required)
         let intent_str = match args.get("intent").and_then(|v| v.as_str()) {
@@ -767,10 +1723,10 @@ impl McpServer {
         };
 
         // Parse optional path override
-        let project_root = args.get("path")
-            .and_then(|v| v.as_str())
-            .map(PathBuf::from)
-            .unwrap_or_else(|| self.project_root.clone());
+        let project_root = match self.requested_root(&id, &args) {
+            Ok(root) => root,
+            Err(response) => return *response,
+        };
 
         // Parse optional parameters
         let include_tests = args.get("include_tests")
@@ -866,19 +1822,29 @@ mod tests {
         let result = resp.result.unwrap();
         let tools = result["tools"].as_array().unwrap();
 
-        // Should have 6 tools
-        assert_eq!(tools.len(), 6);
+        // Should have 16 tools
+        assert_eq!(tools.len(), 16);
 
         // Check tool names
         let tool_names: Vec<&str> = tools.iter()
             .map(|t| t["name"].as_str().unwrap())
             .collect();
         assert!(tool_names.contains(&"get_context"));
+        assert!(tool_names.contains(&"get_context_page"));
+        assert!(tool_names.contains(&"preview_context"));
         assert!(tool_names.contains(&"zoom"));
+        assert!(tool_names.contains(&"zoom_batch"));
         assert!(tool_names.contains(&"session_list"));
         assert!(tool_names.contains(&"session_create"));
         assert!(tool_names.contains(&"report_utility"));
         assert!(tool_names.contains(&"explore_with_intent"));
+        assert!(tool_names.contains(&"capabilities"));
+        assert!(tool_names.contains(&"bookmark_add"));
+        assert!(tool_names.contains(&"bookmark_list"));
+        assert!(tool_names.contains(&"bookmark_resolve"));
+        assert!(tool_names.contains(&"investigation_start"));
+        assert!(tool_names.contains(&"investigation_add"));
+        assert!(tool_names.contains(&"investigation_report"));
     }
 
     #[test]
@@ -1047,6 +2013,129 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_tool_zoom_batch_files() {
+        let temp_dir = std::env::temp_dir().join("pm_mcp_test_zoom_batch");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("a.rs"), "fn alpha() {}\n").unwrap();
+        fs::write(temp_dir.join("b.rs"), "fn beta() {}\n").unwrap();
+
+        let mut server = McpServer::new(temp_dir.clone());
+        let resp = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"zoom_batch","arguments":{"targets":["file=a.rs","file=b.rs"],"budget":"1k","strategy":"priority"}}}"#
+        ).unwrap();
+
+        assert!(resp.error.is_none());
+        let text = resp.result.unwrap()["content"][0]["text"].as_str().unwrap().to_string();
+        assert!(text.contains("fn alpha"));
+        assert!(text.contains("fn beta"));
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_tool_zoom_batch_invalid_args() {
+        let mut server = McpServer::new(PathBuf::from("."));
+
+        let resp = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"zoom_batch","arguments":{"targets":[]}}}"#
+        ).unwrap();
+        assert_eq!(resp.error.unwrap().code, INVALID_PARAMS);
+
+        let resp = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"zoom_batch","arguments":{"targets":["file=x.rs"],"strategy":"greedy"}}}"#
+        ).unwrap();
+        assert_eq!(resp.error.unwrap().code, INVALID_PARAMS);
+
+        // A non-string target is named, not skipped
+        let resp = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"zoom_batch","arguments":{"targets":["file=x.rs",42]}}}"#
+        ).unwrap();
+        let error = resp.error.unwrap();
+        assert_eq!(error.code, INVALID_PARAMS);
+        assert!(error.message.contains("targets[1] must be a string, got 42"));
+    }
+
+    #[test]
+    fn test_session_ledger_elides_unchanged_content() {
+        let temp_dir = std::env::temp_dir().join("pm_mcp_test_ledger");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("a.rs"), "fn alpha() {}\n").unwrap();
+        fs::write(temp_dir.join("b.rs"), "fn beta() {}\n").unwrap();
+
+        let mut server = McpServer::new(temp_dir.clone());
+        let call = |server: &mut McpServer, tool: &str, args: &str| {
+            let request = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"{}","arguments":{}}}}}"#,
+                tool, args
+            );
+            let resp = server.handle_request(&request).unwrap();
+            resp.result.unwrap()["content"][0]["text"].as_str().unwrap().to_string()
+        };
+
+        let first = call(&mut server, "get_context", r#"{"session_id":"s1"}"#);
+        assert!(first.contains("fn alpha") && first.contains("fn beta"));
+        fs::write(temp_dir.join("b.rs"), "fn beta() { 2 }\n").unwrap();
+        let second = call(&mut server, "get_context", r#"{"session_id":"s1"}"#);
+        assert!(!second.contains("fn alpha"));
+        assert!(second.contains("«unchanged since msg #1»"));
+        assert!(second.contains("fn beta() { 2 }"));
+
+        // Other sessions and plain calls are unaffected
+        assert!(call(&mut server, "get_context", r#"{"session_id":"s2"}"#).contains("fn alpha"));
+        assert!(call(&mut server, "get_context", "{}").contains("fn alpha"));
+
+        // Client-held fingerprints work without a session
+        let md5 = crate::core::models::calculate_md5("fn alpha() {}\n");
+        let seen = format!(r#"{{"seen":[{{"key":"a.rs","fingerprint":"{}","message":7}}]}}"#, md5);
+        assert!(call(&mut server, "get_context", &seen).contains("«unchanged since msg #7»"));
+
+        let zoom = call(&mut server, "zoom", r#"{"target":"file=a.rs","session_id":"s1","message":3}"#);
+        assert!(zoom.contains("fn alpha") && zoom.contains("<ledger key=\"file=a.rs\""));
+        let again = call(&mut server, "zoom", r#"{"target":"file=a.rs","session_id":"s1"}"#);
+        assert_eq!(again, "file=a.rs «unchanged since msg #3»");
+
+        let resp = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"get_context","arguments":{"seen":[{"key":"a.rs"}]}}}"#
+        ).unwrap();
+        assert_eq!(resp.error.unwrap().code, INVALID_PARAMS);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_zoom_served_from_prefetch() {
+        let temp_dir = std::env::temp_dir().join("pm_mcp_test_prefetch");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("a.rs"), "fn alpha() {}\n").unwrap();
+
+        let mut server = McpServer::new(temp_dir.clone()).with_prefetch(PrefetchConfig::default());
+        let prefetcher = server.prefetch.clone().unwrap();
+        prefetcher.prefetch(&temp_dir, "/* ZOOM_AFFORDANCE: pm_encoder --zoom file=a.rs */\n");
+        assert!(prefetcher.wait_idle(std::time::Duration::from_secs(30)));
+
+        // The prefetched zoom is served, not a fresh one
+        fs::write(temp_dir.join("a.rs"), "fn changed() {}\n").unwrap();
+        let zoom = |server: &mut McpServer, args: &str| {
+            let request = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"zoom","arguments":{}}}}}"#,
+                args
+            );
+            let resp = server.handle_request(&request).unwrap();
+            resp.result.unwrap()["content"][0]["text"].as_str().unwrap().to_string()
+        };
+        assert!(zoom(&mut server, r#"{"target":"file=a.rs"}"#).contains("fn alpha"));
+        assert!(zoom(&mut server, r#"{"target":"file=a.rs","include_docs":true}"#).contains("fn changed"));
+
+        let disabled = PrefetchConfig { enabled: false, ..Default::default() };
+        assert!(McpServer::new(temp_dir.clone()).with_prefetch(disabled).prefetch.is_none());
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
     #[test]
     fn test_tool_zoom_class_target() {
         let temp_dir = std::env::temp_dir().join("pm_mcp_test_zoom_class");
@@ -1104,6 +2193,73 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_tool_paths_confined_to_project_root() {
+        let temp_dir = std::env::temp_dir().join("pm_mcp_test_sandbox");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("src")).unwrap();
+        fs::write(temp_dir.join("src/test.rs"), "fn main() {}\n").unwrap();
+
+        let mut server = McpServer::new(temp_dir.clone());
+        for arguments in [
+            r#"{"target":"file=../secret.rs"}"#,
+            r#"{"target":"file=/etc/passwd"}"#,
+            r#"{"target":"file=test.rs","path":"/"}"#,
+            r#"{"target":"file=test.rs","path":"../.."}"#,
+        ] {
+            let request = format!(
+                r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"zoom","arguments":{}}}}}"#,
+                arguments
+            );
+            let error = server.handle_request(&request).unwrap().error.expect(arguments);
+            assert_eq!(error.code, POLICY_VIOLATION, "{}", arguments);
+            assert!(error.message.contains("Policy violation"));
+        }
+
+        // Relative overrides inside the root still work
+        let resp = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"zoom","arguments":{"target":"file=test.rs","path":"src"}}}"#
+        ).unwrap();
+        assert!(resp.error.is_none());
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_workspace_argument_selects_root() {
+        let temp_dir = std::env::temp_dir().join("pm_mcp_test_workspaces");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("app")).unwrap();
+        fs::create_dir_all(temp_dir.join("shared")).unwrap();
+        fs::write(temp_dir.join("app/main.rs"), "fn app() {}\n").unwrap();
+        fs::write(temp_dir.join("shared/lib.rs"), "fn shared() {}\n").unwrap();
+
+        let configs = std::collections::BTreeMap::from([
+            ("work".to_string(), workspaces::WorkspaceConfig::new(temp_dir.join("app"))),
+            ("libs".to_string(), workspaces::WorkspaceConfig::new(temp_dir.join("shared"))),
+        ]);
+        let mut server = McpServer::new(temp_dir.clone()).with_workspaces(Workspaces::new(configs).unwrap());
+        let zoom = |arguments: &str| {
+            format!(r#"{{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{{"name":"zoom","arguments":{}}}}}"#, arguments)
+        };
+
+        let error = server.handle_request(&zoom(r#"{"target":"file=lib.rs"}"#)).unwrap().error.unwrap();
+        assert_eq!(error.code, INVALID_PARAMS);
+        assert!(error.message.contains("Workspaces: libs, work"));
+
+        let resp = server.handle_request(&zoom(r#"{"target":"file=lib.rs","workspace":"libs"}"#)).unwrap();
+        assert!(resp.result.unwrap().to_string().contains("fn shared()"));
+        // Each workspace is its own sandbox
+        let error = server.handle_request(&zoom(r#"{"target":"file=lib.rs","workspace":"work","path":"../shared"}"#)).unwrap();
+        assert_eq!(error.error.unwrap().code, POLICY_VIOLATION);
+
+        let tools = server.handle_request(r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#).unwrap().result.unwrap();
+        assert!(tools["tools"].as_array().unwrap().iter().any(|t| t["name"] == "zoom"
+            && t["inputSchema"]["required"].as_array().unwrap().contains(&json!("workspace"))));
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
     #[test]
     fn test_tool_get_context_with_lens() {
         let temp_dir = std::env::temp_dir().join("pm_mcp_test_ctx_lens");
@@ -1124,6 +2280,121 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_tool_preview_context() {
+        let temp_dir = std::env::temp_dir().join("pm_mcp_test_preview");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("main.py"), "def main():\n    pass\n").unwrap();
+
+        let mut server = McpServer::new(temp_dir.clone());
+        let resp = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"preview_context","arguments":{"json":true}}}"#
+        ).unwrap();
+        assert!(resp.error.is_none());
+        let text = resp.result.unwrap()["content"][0]["text"].as_str().unwrap().to_string();
+        let preview: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(preview["files"][0]["path"], "main.py");
+        assert_eq!(preview["files"][0]["action"], "full");
+        assert!(!text.contains("def main"), "preview must not include content");
+
+        let resp = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"preview_context","arguments":{"token_budget":"lots"}}}"#
+        ).unwrap();
+        assert_eq!(resp.error.unwrap().code, INVALID_PARAMS);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_tool_get_context_paginates() {
+        let temp_dir = std::env::temp_dir().join("pm_mcp_test_ctx_pages");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        for name in ["a", "b", "c", "d"] {
+            let body: String = (0..20).map(|i| format!("# {} line {}\n", name, i)).collect();
+            fs::write(temp_dir.join(format!("{}.py", name)), body).unwrap();
+        }
+
+        let mut server = McpServer::new(temp_dir.clone());
+        let resp = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"get_context","arguments":{"page_size":"100"}}}"#
+        ).unwrap();
+        let text = resp.result.unwrap()["content"][0]["text"].as_str().unwrap().to_string();
+        assert!(text.contains("<continuation page=\"1\""));
+
+        let token = text.split("next_token=\"").nth(1).unwrap().split('"').next().unwrap().to_string();
+        let request = json!({
+            "jsonrpc": "2.0", "id": 2, "method": "tools/call",
+            "params": {"name": "get_context_page", "arguments": {"token": token}}
+        });
+        let resp = server.handle_request(&request.to_string()).unwrap();
+        assert!(resp.error.is_none());
+        let text = resp.result.unwrap()["content"][0]["text"].as_str().unwrap().to_string();
+        assert!(text.contains("<continuation page=\"2\""));
+
+        let resp = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":3,"method":"tools/call","params":{"name":"get_context_page","arguments":{"token":"bogus:9"}}}"#
+        ).unwrap();
+        assert_eq!(resp.error.unwrap().code, INVALID_PARAMS);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_tool_get_context_from_bundle() {
+        let bundle = ContextBundle::new("demo", "plusminus", "bundled context\n".to_string());
+        let mut server = McpServer::new(PathBuf::from("/nonexistent")).with_bundle(bundle);
+
+        let response = server.tool_get_context(json!(1), json!({"lens": "architecture"}));
+        let text = response.result.unwrap()["content"][0]["text"].as_str().unwrap().to_string();
+        assert!(text.contains("bundled context"));
+
+        let preview = server.tool_preview_context(json!(2), json!({}));
+        let text = preview.result.unwrap()["content"][0]["text"].as_str().unwrap().to_string();
+        assert!(text.contains("Bundle: demo"));
+    }
+
+    #[test]
+    fn test_get_context_cancelled_with_partial_stats() {
+        let temp = tempfile::TempDir::new().unwrap();
+        fs::write(temp.path().join("a.py"), "print('a')\n").unwrap();
+        let mut server = McpServer::new(temp.path().to_path_buf());
+
+        // Cancellation arrives before the request starts
+        assert!(server.handle_request(
+            r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{"requestId":5}}"#
+        ).is_none());
+        let error = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":5,"method":"tools/call","params":{"name":"get_context","arguments":{}}}"#
+        ).unwrap().error.unwrap();
+        assert_eq!(error.code, REQUEST_CANCELLED);
+        assert_eq!(error.data.unwrap()["stage"], "walk");
+
+        // Other requests are unaffected
+        let ok = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":6,"method":"tools/call","params":{"name":"get_context","arguments":{}}}"#
+        ).unwrap();
+        assert!(ok.error.is_none());
+    }
+
+    #[test]
+    fn test_tools_call_rate_limited() {
+        let limiter = Arc::new(Limiter::new(limits::ServerLimits {
+            requests_per_minute: 1,
+            ..Default::default()
+        }));
+        let mut server = McpServer::new(PathBuf::from(".")).with_limiter(limiter);
+        let call = r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"session_list"}}"#;
+
+        assert!(server.handle_request(call).unwrap().error.is_none());
+        let busy = server.handle_request(call).unwrap().error.unwrap();
+        assert_eq!(busy.code, SERVER_BUSY);
+        assert_eq!(busy.data.unwrap()["reason"], "rate_limited");
+        // Non-tool methods are not limited
+        assert!(server.handle_request(r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#).unwrap().error.is_none());
+    }
+
     #[test]
     fn test_tool_get_context_with_token_budget() {
         let temp_dir = std::env::temp_dir().join("pm_mcp_test_ctx_budget");
@@ -1182,6 +2453,96 @@ mod tests {
         let _ = fs::remove_dir_all(&temp_dir);
     }
 
+    #[test]
+    fn test_tool_capabilities_reports_limits_in_force() {
+        let limiter = Arc::new(Limiter::new(limits::ServerLimits {
+            max_jobs: 3,
+            ..Default::default()
+        }));
+        let mut server = McpServer::new(PathBuf::from(".")).with_limiter(limiter);
+        let resp = server.handle_request(
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"capabilities"}}"#
+        ).unwrap();
+
+        let text = resp.result.unwrap()["content"][0]["text"].as_str().unwrap().to_string();
+        let capabilities: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(capabilities["version"], crate::VERSION);
+        assert_eq!(capabilities["limits"]["jobs"]["max_jobs"], 3);
+        assert!(capabilities["languages"].as_array().is_some_and(|l| !l.is_empty()));
+    }
+
+    #[test]
+    fn test_tool_bookmarks_add_resolve_and_zoom() {
+        let temp_dir = std::env::temp_dir().join("pm_mcp_test_bookmarks");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(temp_dir.join("src")).unwrap();
+        fs::write(temp_dir.join("src/pay.py"), "def process_payment(amount):\n    return amount\n").unwrap();
+
+        let mut server = McpServer::new(temp_dir.clone());
+        let call = |server: &mut McpServer, name: &str, arguments: Value| {
+            let request = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {"name": name, "arguments": arguments}});
+            server.handle_request(&request.to_string()).unwrap()
+        };
+
+        let resp = call(&mut server, "bookmark_add", json!({"name": "payment-flow", "target": "function=process_payment", "note": "entry"}));
+        assert_eq!(resp.result.unwrap()["isError"], false);
+        let resp = call(&mut server, "bookmark_add", json!({"name": "escape", "target": "file=../secret.txt"}));
+        assert_eq!(resp.error.unwrap().code, POLICY_VIOLATION);
+
+        let resp = call(&mut server, "bookmark_list", json!({}));
+        let list: Value = serde_json::from_str(resp.result.unwrap()["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(list["payment-flow"]["note"], "entry");
+
+        let resp = call(&mut server, "bookmark_resolve", json!({"name": "payment-flow"}));
+        let resolved: Value = serde_json::from_str(resp.result.unwrap()["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(resolved["path"], "src/pay.py");
+        assert_eq!(resolved["lines"][0], 1);
+
+        let resp = call(&mut server, "zoom", json!({"target": "bookmark=payment-flow"}));
+        assert!(resp.result.unwrap()["content"][0]["text"].as_str().unwrap().contains("return amount"));
+        let resp = call(&mut server, "zoom", json!({"target": "bookmark=missing"}));
+        assert!(resp.error.is_some());
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
+    #[test]
+    fn test_tool_investigation_records_steps_and_reports() {
+        let temp_dir = std::env::temp_dir().join("pm_mcp_test_investigation");
+        let _ = fs::remove_dir_all(&temp_dir);
+        fs::create_dir_all(&temp_dir).unwrap();
+        fs::write(temp_dir.join("billing.py"), "def refund(total):\n    return apply_discount(total)\n").unwrap();
+
+        let mut server = McpServer::new(temp_dir.clone());
+        let call = |server: &mut McpServer, name: &str, arguments: Value| {
+            let request = json!({"jsonrpc": "2.0", "id": 1, "method": "tools/call", "params": {"name": name, "arguments": arguments}});
+            server.handle_request(&request.to_string()).unwrap()
+        };
+
+        let resp = call(&mut server, "investigation_add", json!({"kind": "note", "value": "too early"}));
+        assert_eq!(resp.result.unwrap()["isError"], true);
+
+        call(&mut server, "investigation_start", json!({"name": "refund-bug"}));
+        let resp = call(&mut server, "investigation_add", json!({"kind": "zoom", "value": "function=refund"}));
+        assert!(resp.result.unwrap()["content"][0]["text"].as_str().unwrap().contains("apply_discount(total)"));
+        let resp = call(&mut server, "investigation_add", json!({"kind": "search", "value": "refund"}));
+        let hits: Value = serde_json::from_str(resp.result.unwrap()["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(hits[0]["definition"], true);
+        call(&mut server, "investigation_add", json!({"kind": "note", "value": "discount applied twice"}));
+        let resp = call(&mut server, "investigation_add", json!({"kind": "zoom", "value": "file=../outside.py"}));
+        assert_eq!(resp.error.unwrap().code, POLICY_VIOLATION);
+
+        let resp = call(&mut server, "investigation_report", json!({}));
+        let report = resp.result.unwrap()["content"][0]["text"].as_str().unwrap().to_string();
+        assert!(report.starts_with("# Investigation: refund-bug"));
+        assert!(report.contains("1 zooms, 1 searches, 1 notes."));
+        let resp = call(&mut server, "investigation_report", json!({"name": "refund-bug", "format": "json"}));
+        let json: Value = serde_json::from_str(resp.result.unwrap()["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(json["entries"].as_array().unwrap().len(), 3);
+
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
+
     #[test]
     fn test_tool_session_create_success() {
         let temp_dir = std::env::temp_dir().join("pm_mcp_test_session_create");
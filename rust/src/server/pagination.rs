@@ -0,0 +1,272 @@
+//! Context pagination for MCP responses
+//!
+//! Large `get_context` results can exceed MCP message limits or the client
+//! model's window. The server splits such results into pages and hands back a
+//! continuation token; `get_context_page(token)` fetches the next page.
+//!
+//! Tokens are deterministic: `<content-hash>:<page-index>`. Requesting the same
+//! context twice yields the same tokens, and a token always maps to the same
+//! page for as long as the context is cached.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::calculate_md5;
+
+/// Default page size in tokens (~4 chars per token)
+pub const DEFAULT_PAGE_TOKENS: usize = 25_000;
+
+/// Number of paginated contexts kept in memory before the oldest is evicted
+pub const MAX_CACHED_CONTEXTS: usize = 8;
+
+/// Markers that start a new file in the supported output formats
+const FILE_BOUNDARIES: &[&str] = &["+++", "<file ", "<document ", "## "];
+
+/// Split content into chunks of at most `max_chars` (at line boundaries)
+///
+/// Prefers breaking right before a file header when one falls in the second
+/// half of the window, so pages rarely start mid-file. A single line longer
+/// than `max_chars` is split at a character boundary.
+pub fn chunk_content(content: &str, max_chars: usize) -> Vec<String> {
+    let max_chars = max_chars.max(1);
+    if content.len() <= max_chars {
+        return vec![content.to_string()];
+    }
+
+    let mut chunks = Vec::new();
+    let mut rest = content;
+
+    while rest.len() > max_chars {
+        let mut window_end = max_chars;
+        while !rest.is_char_boundary(window_end) {
+            window_end -= 1;
+        }
+        if window_end == 0 {
+            // A single character wider than the page: emit it whole
+            window_end = rest.chars().next().map_or(1, char::len_utf8);
+        }
+        let window = &rest[..window_end];
+
+        let file_break = window
+            .match_indices('\n')
+            .rev()
+            .map(|(i, _)| i + 1)
+            .take_while(|&i| i >= window_end / 2)
+            .find(|&i| FILE_BOUNDARIES.iter().any(|m| rest[i..].starts_with(m)));
+        let line_break = window.rfind('\n').map(|i| i + 1);
+
+        let cut = file_break.or(line_break).unwrap_or(window_end);
+        chunks.push(rest[..cut].to_string());
+        rest = &rest[cut..];
+    }
+
+    if !rest.is_empty() {
+        chunks.push(rest.to_string());
+    }
+    chunks
+}
+
+/// A single page returned to the client
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    /// Page content
+    pub content: String,
+    /// 1-based page number
+    pub number: usize,
+    /// Total number of pages
+    pub total: usize,
+    /// Token for the following page, if any
+    pub next_token: Option<String>,
+}
+
+impl Page {
+    /// Render the page with a continuation footer for the client
+    pub fn render(&self) -> String {
+        match &self.next_token {
+            Some(token) => format!(
+                "{}\n<continuation page=\"{}\" total_pages=\"{}\" next_token=\"{}\">Call get_context_page with this token for the next page</continuation>",
+                self.content, self.number, self.total, token
+            ),
+            None if self.total > 1 => format!(
+                "{}\n<continuation page=\"{}\" total_pages=\"{}\" />",
+                self.content, self.number, self.total
+            ),
+            None => self.content.clone(),
+        }
+    }
+}
+
+/// In-memory store of paginated contexts
+#[derive(Debug, Default)]
+pub struct PageStore {
+    contexts: HashMap<String, Vec<String>>,
+    order: VecDeque<String>,
+}
+
+impl PageStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Paginate content and return its first page
+    ///
+    /// Content that fits in one page is returned as-is and not cached.
+    pub fn paginate(&mut self, content: String, page_tokens: usize) -> Page {
+        let chunks = chunk_content(&content, page_tokens.saturating_mul(4));
+        if chunks.len() == 1 {
+            return Page {
+                content,
+                number: 1,
+                total: 1,
+                next_token: None,
+            };
+        }
+
+        let key = calculate_md5(&content)[..12].to_string();
+        if !self.contexts.contains_key(&key) {
+            if self.order.len() >= MAX_CACHED_CONTEXTS {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.contexts.remove(&oldest);
+                }
+            }
+            self.order.push_back(key.clone());
+            self.contexts.insert(key.clone(), chunks);
+        }
+
+        self.page(&key, 0).expect("page 0 of a freshly cached context exists")
+    }
+
+    /// Fetch the page addressed by a continuation token
+    pub fn get(&self, token: &str) -> Result<Page, String> {
+        let (key, index) = token
+            .rsplit_once(':')
+            .and_then(|(k, i)| i.parse::<usize>().ok().map(|i| (k, i)))
+            .ok_or_else(|| format!("Malformed continuation token '{}'", token))?;
+
+        self.page(key, index)
+            .ok_or_else(|| format!("Unknown or expired continuation token '{}'", token))
+    }
+
+    /// Number of cached contexts
+    pub fn len(&self) -> usize {
+        self.contexts.len()
+    }
+
+    /// Check if no contexts are cached
+    pub fn is_empty(&self) -> bool {
+        self.contexts.is_empty()
+    }
+
+    fn page(&self, key: &str, index: usize) -> Option<Page> {
+        let chunks = self.contexts.get(key)?;
+        let content = chunks.get(index)?.clone();
+        let next_token = (index + 1 < chunks.len()).then(|| format!("{}:{}", key, index + 1));
+        Some(Page {
+            content,
+            number: index + 1,
+            total: chunks.len(),
+            next_token,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_context(files: usize) -> String {
+        (0..files)
+            .map(|i| format!("++++++++++ file{}.rs ++++++++++\n{}---------- file{}.rs ----------\n", i, "x\n".repeat(50), i))
+            .collect()
+    }
+
+    #[test]
+    fn test_chunk_small_content_single_page() {
+        assert_eq!(chunk_content("abc\n", 100), vec!["abc\n".to_string()]);
+    }
+
+    #[test]
+    fn test_chunk_rejoins_to_original() {
+        let content = sample_context(10);
+        let chunks = chunk_content(&content, 300);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.len() <= 300));
+        assert_eq!(chunks.concat(), content);
+    }
+
+    #[test]
+    fn test_chunk_prefers_file_boundaries() {
+        let content = sample_context(10);
+        let chunks = chunk_content(&content, 300);
+        for chunk in &chunks[1..] {
+            assert!(chunk.starts_with("++++++++++ "), "page should start at a file header");
+        }
+    }
+
+    #[test]
+    fn test_chunk_long_line() {
+        let content = "y".repeat(250);
+        let chunks = chunk_content(&content, 100);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), content);
+    }
+
+    #[test]
+    fn test_page_store_walks_pages() {
+        let mut store = PageStore::new();
+        let content = sample_context(10);
+        let first = store.paginate(content.clone(), 75);
+        assert_eq!(first.number, 1);
+        assert!(first.total > 1);
+
+        let mut collected = first.content.clone();
+        let mut token = first.next_token.clone();
+        while let Some(t) = token {
+            let page = store.get(&t).unwrap();
+            collected.push_str(&page.content);
+            token = page.next_token;
+        }
+        assert_eq!(collected, content);
+    }
+
+    #[test]
+    fn test_page_store_deterministic_tokens() {
+        let mut store = PageStore::new();
+        let content = sample_context(10);
+        let a = store.paginate(content.clone(), 75).next_token;
+        let b = store.paginate(content, 75).next_token;
+        assert_eq!(a, b);
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_page_store_small_content_not_cached() {
+        let mut store = PageStore::new();
+        let page = store.paginate("tiny".to_string(), 100);
+        assert_eq!(page.total, 1);
+        assert!(page.next_token.is_none());
+        assert_eq!(page.render(), "tiny");
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_page_store_rejects_bad_tokens() {
+        let store = PageStore::new();
+        assert!(store.get("nonsense").is_err());
+        assert!(store.get("abc:1").is_err());
+    }
+
+    #[test]
+    fn test_page_store_evicts_oldest() {
+        let mut store = PageStore::new();
+        let mut first_token = None;
+        for i in 0..=MAX_CACHED_CONTEXTS {
+            let page = store.paginate(format!("{}{}", i, sample_context(5)), 50);
+            if i == 0 {
+                first_token = page.next_token;
+            }
+        }
+        assert_eq!(store.len(), MAX_CACHED_CONTEXTS);
+        assert!(store.get(&first_token.unwrap()).is_err());
+    }
+}
@@ -0,0 +1,217 @@
+//! Speculative prefetch of zoom targets
+//!
+//! A served context embeds `ZOOM_AFFORDANCE` markers, and the model's next
+//! call is usually a zoom into one of them. With prefetching enabled, the
+//! server resolves and zooms the first `max_targets` affordances of every
+//! context it serves on background threads, at most `max_concurrency` at a
+//! time, and keeps the results for `ttl_secs`. The follow-up zoom is then
+//! answered from memory instead of re-walking and re-parsing the project.
+//!
+//! Enabled by a `prefetch` section in `.pm_encoder_config.json`:
+//!
+//! ```json
+//! { "prefetch": { "max_concurrency": 2, "max_targets": 16, "ttl_secs": 300 } }
+//! ```
+//!
+//! Prefetched zooms are not invalidated when files change; the TTL bounds how
+//! stale one can be.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{AffordanceIndex, ContextEngine, ZoomConfig, ZoomDepth, ZoomTarget};
+
+use super::McpServer;
+
+/// `prefetch` section of the config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrefetchConfig {
+    /// Set to false to keep the section but disable prefetching
+    pub enabled: bool,
+    /// Zooms computed at once
+    pub max_concurrency: usize,
+    /// Affordances prefetched per served context, in order of appearance
+    pub max_targets: usize,
+    /// How long a prefetched zoom is served
+    pub ttl_secs: u64,
+}
+
+impl Default for PrefetchConfig {
+    fn default() -> Self {
+        Self { enabled: true, max_concurrency: 2, max_targets: 16, ttl_secs: 300 }
+    }
+}
+
+/// A zoom computed ahead of the request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefetchedZoom {
+    pub output: String,
+    /// Symbol name of function and class targets
+    pub resolved_name: Option<String>,
+}
+
+struct Entry {
+    zoom: PrefetchedZoom,
+    fetched: Instant,
+}
+
+/// Queued target: project root, target as offered, cache key
+type Job = (PathBuf, String, String);
+
+#[derive(Default)]
+struct State {
+    pending: VecDeque<Job>,
+    /// Keys pending or being zoomed
+    queued: HashSet<String>,
+    workers: usize,
+    entries: HashMap<String, Entry>,
+}
+
+/// Background zoomer and cache of its results
+#[derive(Clone)]
+pub struct Prefetcher {
+    config: PrefetchConfig,
+    state: Arc<(Mutex<State>, Condvar)>,
+}
+
+impl Prefetcher {
+    pub fn new(config: PrefetchConfig) -> Self {
+        Self { config, state: Arc::new((Mutex::new(State::default()), Condvar::new())) }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn ttl(&self) -> Duration {
+        Duration::from_secs(self.config.ttl_secs)
+    }
+
+    /// Queue the zoom affordances of a served context; returns how many were queued
+    ///
+    /// Targets already cached or queued are skipped. Returns immediately.
+    pub fn prefetch(&self, root: &Path, output: &str) -> usize {
+        let index = AffordanceIndex::from_output(output);
+        let targets = index.affordances.iter().take(self.config.max_targets);
+
+        let mut state = self.lock();
+        let ttl = self.ttl();
+        state.entries.retain(|_, entry| entry.fetched.elapsed() < ttl);
+        let mut queued = 0;
+        for affordance in targets {
+            let target = format!("{}={}", affordance.kind, affordance.target);
+            let Some(key) = cache_key(root, &target) else { continue };
+            if state.entries.contains_key(&key) || !state.queued.insert(key.clone()) {
+                continue;
+            }
+            state.pending.push_back((root.to_path_buf(), target, key));
+            queued += 1;
+        }
+
+        while state.workers < self.config.max_concurrency.max(1) && state.workers < state.pending.len() {
+            state.workers += 1;
+            let worker = self.clone();
+            thread::spawn(move || worker.work());
+        }
+        queued
+    }
+
+    /// Zoom queued targets until the queue is empty
+    fn work(&self) {
+        loop {
+            let (root, target, key) = {
+                let mut state = self.lock();
+                match state.pending.pop_front() {
+                    Some(job) => job,
+                    None => {
+                        state.workers -= 1;
+                        self.state.1.notify_all();
+                        return;
+                    }
+                }
+            };
+            let zoom = zoom_target(&root, &target);
+            let mut state = self.lock();
+            state.queued.remove(&key);
+            if let Some(zoom) = zoom {
+                state.entries.insert(key, Entry { zoom, fetched: Instant::now() });
+            }
+        }
+    }
+
+    /// Prefetched zoom of `target` (`kind=value`) in `root`, if still fresh
+    pub fn get(&self, root: &Path, target: &str) -> Option<PrefetchedZoom> {
+        let key = cache_key(root, target)?;
+        let state = self.lock();
+        let entry = state.entries.get(&key)?;
+        (entry.fetched.elapsed() < self.ttl()).then(|| entry.zoom.clone())
+    }
+
+    /// Wait until no prefetch is running; false if `timeout` passed first
+    pub fn wait_idle(&self, timeout: Duration) -> bool {
+        let state = self.lock();
+        let (_state, result) = self
+            .state
+            .1
+            .wait_timeout_while(state, timeout, |state| state.workers > 0)
+            .unwrap_or_else(PoisonError::into_inner);
+        !result.timed_out()
+    }
+}
+
+/// Cache key: root plus the target in canonical `--zoom` form
+fn cache_key(root: &Path, target: &str) -> Option<String> {
+    let target = ZoomTarget::parse(target).ok()?;
+    Some(format!("{}\0{}", root.display(), target.to_command(None)))
+}
+
+/// Resolve and zoom a target the way the zoom tool does
+fn zoom_target(root: &Path, target: &str) -> Option<PrefetchedZoom> {
+    let (target, resolved_name) = McpServer::resolve_zoom_target(target, root).ok()?;
+    let config = ZoomConfig {
+        target,
+        budget: None,
+        depth: ZoomDepth::Full,
+        include_tests: false,
+        include_docs: false,
+        context_lines: 5,
+    };
+    let output = ContextEngine::new().zoom(root.to_str()?, &config).ok()?;
+    Some(PrefetchedZoom { output, resolved_name })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_prefetch_zooms_affordances_in_background() {
+        let project = TempDir::new().unwrap();
+        fs::write(project.path().join("lib.rs"), "fn alpha() {}\n").unwrap();
+        let context = "+++ lib.rs\n/* ZOOM_AFFORDANCE: pm_encoder --zoom file=lib.rs */\n\
+                       /* ZOOM_AFFORDANCE: pm_encoder --zoom file=lib.rs */\n";
+
+        let prefetcher = Prefetcher::new(PrefetchConfig { max_concurrency: 1, ..Default::default() });
+        assert_eq!(prefetcher.prefetch(project.path(), context), 1);
+        assert!(prefetcher.wait_idle(Duration::from_secs(30)));
+
+        let zoom = prefetcher.get(project.path(), "file=lib.rs").unwrap();
+        assert!(zoom.output.contains("fn alpha"));
+        assert!(prefetcher.get(project.path(), "file=other.rs").is_none());
+        // Cached targets are not queued again
+        assert_eq!(prefetcher.prefetch(project.path(), context), 0);
+
+        let expired = Prefetcher::new(PrefetchConfig { ttl_secs: 0, ..Default::default() });
+        expired.prefetch(project.path(), context);
+        assert!(expired.wait_idle(Duration::from_secs(30)));
+        assert!(expired.get(project.path(), "file=lib.rs").is_none());
+    }
+}
@@ -0,0 +1,198 @@
+//! Named workspaces for multi-root servers
+//!
+//! One server can serve several repositories side by side instead of a
+//! single project root. Workspaces are declared in a `workspaces` section of
+//! `.pm_encoder_config.json`, with optional per-workspace policies:
+//!
+//! ```json
+//! { "workspaces": {
+//!     "work": { "root": "/src/app", "lens": "architecture" },
+//!     "libs": { "root": "/src/shared", "lens": "minimal", "max_token_budget": "50k" }
+//! } }
+//! ```
+//!
+//! or with `--workspace work=/src/app` (repeatable, overrides the config).
+//! Once any workspace is configured, every tool that reads the project takes
+//! a required `workspace` argument, `path` arguments are confined to that
+//! workspace's root, and sessions and utility scores are kept per workspace.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::parse_token_budget;
+
+/// Tools that do not read a project and take no `workspace` argument
+const WORKSPACE_FREE_TOOLS: &[&str] = &["get_context_page", "capabilities"];
+
+/// A workspace entry of the config file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceConfig {
+    pub root: PathBuf,
+    /// Lens applied when a call names none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lens: Option<String>,
+    /// Largest token budget a call may use (e.g. "50k"); calls without a
+    /// budget get this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_token_budget: Option<String>,
+}
+
+impl WorkspaceConfig {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into(), lens: None, max_token_budget: None }
+    }
+}
+
+/// A validated workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Workspace {
+    pub name: String,
+    pub root: PathBuf,
+    pub lens: Option<String>,
+    pub max_token_budget: Option<usize>,
+}
+
+impl Workspace {
+    /// A requested budget capped by the workspace's maximum
+    pub fn cap_budget(&self, requested: Option<usize>) -> Option<usize> {
+        match (requested, self.max_token_budget) {
+            (Some(requested), Some(max)) => Some(requested.min(max)),
+            (requested, max) => requested.or(max),
+        }
+    }
+}
+
+/// The workspaces a server was configured with (none: single-root mode)
+#[derive(Debug, Clone, Default)]
+pub struct Workspaces {
+    by_name: BTreeMap<String, Workspace>,
+}
+
+impl Workspaces {
+    /// Validate configured workspaces: roots must be directories and budgets parse
+    pub fn new(configs: BTreeMap<String, WorkspaceConfig>) -> Result<Self, String> {
+        let mut by_name = BTreeMap::new();
+        for (name, config) in configs {
+            if name.is_empty() {
+                return Err("Workspace names must not be empty".to_string());
+            }
+            if !config.root.is_dir() {
+                return Err(format!("Workspace '{}': root '{}' is not a directory", name, config.root.display()));
+            }
+            let max_token_budget = config
+                .max_token_budget
+                .as_deref()
+                .map(parse_token_budget)
+                .transpose()
+                .map_err(|e| format!("Workspace '{}': invalid max_token_budget: {}", name, e))?;
+            let workspace = Workspace { name: name.clone(), root: config.root, lens: config.lens, max_token_budget };
+            by_name.insert(name, workspace);
+        }
+        Ok(Self { by_name })
+    }
+
+    /// Parse a `--workspace NAME=PATH` argument
+    pub fn parse_arg(arg: &str) -> Result<(String, WorkspaceConfig), String> {
+        match arg.split_once('=') {
+            Some((name, root)) if !name.is_empty() && !root.is_empty() => {
+                Ok((name.to_string(), WorkspaceConfig::new(root)))
+            }
+            _ => Err(format!("Invalid workspace '{}': expected NAME=PATH", arg)),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_name.is_empty()
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.by_name.keys().map(String::as_str).collect()
+    }
+
+    /// The workspace a tool call names in its `workspace` argument
+    ///
+    /// `None` in single-root mode; an error when workspaces are configured
+    /// and the argument is missing or names none of them.
+    pub fn resolve(&self, args: &Value) -> Result<Option<&Workspace>, String> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let names = self.names().join(", ");
+        let name = args
+            .get("workspace")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("Missing 'workspace' parameter. Workspaces: {}", names))?;
+        self.by_name
+            .get(name)
+            .map(Some)
+            .ok_or_else(|| format!("Unknown workspace '{}'. Workspaces: {}", name, names))
+    }
+
+    /// Add the required `workspace` argument to the input schemas of a
+    /// `tools/list` result
+    pub fn annotate_tools(&self, tools: &mut Value) {
+        if self.is_empty() {
+            return;
+        }
+        let names = self.names();
+        let Some(tools) = tools.get_mut("tools").and_then(|t| t.as_array_mut()) else { return };
+        for tool in tools {
+            if tool.get("name").and_then(|n| n.as_str()).is_some_and(|n| WORKSPACE_FREE_TOOLS.contains(&n)) {
+                continue;
+            }
+            let Some(schema) = tool.get_mut("inputSchema").and_then(|s| s.as_object_mut()) else { continue };
+            if let Some(properties) = schema.entry("properties").or_insert_with(|| Value::Object(Default::default())).as_object_mut() {
+                properties.insert(
+                    "workspace".to_string(),
+                    serde_json::json!({
+                        "type": "string",
+                        "enum": names,
+                        "description": "Workspace to operate on"
+                    }),
+                );
+            }
+            if let Some(required) = schema.entry("required").or_insert_with(|| Value::Array(Vec::new())).as_array_mut() {
+                required.push(Value::from("workspace"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_workspaces_resolve_and_annotate() {
+        let root = std::env::temp_dir();
+        let mut configs = BTreeMap::new();
+        configs.insert("work".to_string(), WorkspaceConfig::new(&root));
+        let libs = WorkspaceConfig { max_token_budget: Some("50k".to_string()), ..WorkspaceConfig::new(&root) };
+        configs.insert("libs".to_string(), libs);
+        let workspaces = Workspaces::new(configs).unwrap();
+
+        let libs = workspaces.resolve(&json!({"workspace": "libs"})).unwrap().unwrap();
+        assert_eq!(libs.cap_budget(Some(100_000)), Some(50_000));
+        assert_eq!(libs.cap_budget(None), Some(50_000));
+        assert_eq!(workspaces.resolve(&json!({})).unwrap_err(), "Missing 'workspace' parameter. Workspaces: libs, work");
+        assert!(workspaces.resolve(&json!({"workspace": "docs"})).unwrap_err().starts_with("Unknown workspace 'docs'"));
+        assert_eq!(Workspaces::default().resolve(&json!({})), Ok(None));
+
+        let mut tools = json!({"tools": [
+            {"name": "zoom", "inputSchema": {"type": "object", "properties": {}, "required": ["target"]}},
+            {"name": "get_context_page", "inputSchema": {"type": "object", "properties": {}}}
+        ]});
+        workspaces.annotate_tools(&mut tools);
+        assert_eq!(tools["tools"][0]["inputSchema"]["required"], json!(["target", "workspace"]));
+        assert_eq!(tools["tools"][0]["inputSchema"]["properties"]["workspace"]["enum"], json!(["libs", "work"]));
+        assert!(tools["tools"][1]["inputSchema"].get("required").is_none());
+
+        assert!(Workspaces::parse_arg("work").is_err());
+        let missing = BTreeMap::from([("x".to_string(), WorkspaceConfig::new("/nonexistent/pm_encoder"))]);
+        assert!(Workspaces::new(missing).is_err());
+    }
+}
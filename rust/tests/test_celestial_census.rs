@@ -344,7 +344,7 @@ fn test_galaxy_census_aggregation() {
     galaxy.add_file("src/lib.rs", metrics1);
 
     let mut file2 = create_clean_file();
-    file2.path = "tests/test.rs".to_string();
+    file2.path = "tests/test.rs".to_string().into();
     let metrics2 = census.analyze(&file2);
     galaxy.add_file("tests/test.rs", metrics2);
 
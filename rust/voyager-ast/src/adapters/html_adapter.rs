@@ -0,0 +1,523 @@
+//! HTML Language Adapter
+//!
+//! Extracts markup landmarks from HTML templates: elements with an `id`,
+//! custom elements and components (`<user-card>`, `<UserCard>`),
+//! `<template>` elements and Jinja-style `{% block %}` regions. Scripts and
+//! stylesheets referenced by `<script src>` / `<link href>` become imports.
+//!
+//! Template-language syntax the HTML grammar only sees as text or plain
+//! attributes (Jinja tags, Vue directives, Angular bindings) is reported as
+//! `UnknownNode`s whose reason names the detected dialect.
+
+use super::{AdapterCapabilities, Capability, collect_syntax_errors, nest_by_span, node_text, node_to_span, offset_span, LanguageAdapter};
+use crate::ir::{
+    confidence, Block, Comment, CommentKind, Declaration, DeclarationKind, ImportKind, ImportLike,
+    LanguageId, Span, UnknownNode, Visibility,
+};
+
+/// Template language layered on top of HTML
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateDialect {
+    /// Jinja, Django, Twig, Nunjucks (`{% %}` / `{{ }}`)
+    Jinja,
+    /// Vue single-file component templates (`v-if`, `:prop`, `@event`)
+    Vue,
+    /// Angular templates (`*ngIf`, `[prop]`, `(event)`)
+    Angular,
+}
+
+impl TemplateDialect {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Jinja => "jinja",
+            Self::Vue => "vue",
+            Self::Angular => "angular",
+        }
+    }
+
+    /// Guess the dialect from markers in the source
+    pub fn detect(source: &str) -> Option<Self> {
+        if source.contains("{%") {
+            Some(Self::Jinja)
+        } else if ["*ng", "[(ngModel)]", "<ng-template", "<ng-container", "(click)="]
+            .iter()
+            .any(|marker| source.contains(marker))
+        {
+            Some(Self::Angular)
+        } else if [" v-if", " v-for", " v-else", " v-show", " v-model", " v-bind", " v-on", " v-slot", "<template #"]
+            .iter()
+            .any(|marker| source.contains(marker))
+        {
+            Some(Self::Vue)
+        } else {
+            None
+        }
+    }
+
+    /// Whether an attribute name is template syntax in this dialect
+    fn is_directive(&self, attribute: &str) -> bool {
+        match self {
+            Self::Jinja => false,
+            Self::Vue => {
+                attribute.starts_with("v-") || attribute.starts_with(':') || attribute.starts_with('@') || attribute.starts_with('#')
+            }
+            Self::Angular => {
+                attribute.starts_with('*') || attribute.starts_with('[') || attribute.starts_with('(') || attribute.starts_with('#')
+            }
+        }
+    }
+}
+
+/// HTML language adapter using Tree-sitter
+pub struct HtmlTreeSitterAdapter {
+    language: tree_sitter::Language,
+}
+
+impl HtmlTreeSitterAdapter {
+    /// Create a new HTML adapter
+    pub fn new() -> Self {
+        Self {
+            language: tree_sitter_html::LANGUAGE.into(),
+        }
+    }
+}
+
+impl Default for HtmlTreeSitterAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageAdapter for HtmlTreeSitterAdapter {
+    fn language(&self) -> LanguageId {
+        LanguageId::Html
+    }
+
+    fn tree_sitter_language(&self) -> tree_sitter::Language {
+        self.language.clone()
+    }
+
+    fn extract_declarations(
+        &self,
+        tree: &tree_sitter::Tree,
+        source: &str,
+    ) -> Vec<Declaration> {
+        let mut landmarks = Vec::new();
+        self.collect_landmarks(&tree.root_node(), source, &mut landmarks);
+        if TemplateDialect::detect(source) == Some(TemplateDialect::Jinja) {
+            landmarks.extend(jinja_blocks(source));
+        }
+        nest_by_span(landmarks)
+    }
+
+    fn extract_imports(&self, tree: &tree_sitter::Tree, source: &str) -> Vec<ImportLike> {
+        let mut imports = Vec::new();
+        self.visit_imports(&tree.root_node(), source, &mut imports);
+        imports
+    }
+
+    fn extract_comments(&self, tree: &tree_sitter::Tree, source: &str) -> Vec<Comment> {
+        let mut comments = Vec::new();
+        self.visit_comments(&tree.root_node(), source, &mut comments);
+        comments
+    }
+
+    fn extract_body(
+        &self,
+        tree: &tree_sitter::Tree,
+        source: &str,
+        declaration: &Declaration,
+    ) -> Option<Block> {
+        let span = declaration.body_span?;
+        Some(Block {
+            span,
+            comments: self
+                .extract_comments(tree, source)
+                .into_iter()
+                .filter(|c| span.contains_span(&c.span))
+                .collect(),
+            unknown_regions: self
+                .extract_errors(tree, source)
+                .into_iter()
+                .filter(|u| span.contains_span(&u.span))
+                .collect(),
+            nested_declarations: declaration.children.clone(),
+            ..Block::default()
+        })
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(&[Capability::Declarations, Capability::Nesting, Capability::Imports, Capability::Comments])
+    }
+
+    fn extract_visibility(&self, _node: &tree_sitter::Node, _source: &str) -> Visibility {
+        // Every landmark is addressable from outside the template
+        Visibility::Public
+    }
+
+    fn extract_errors(&self, tree: &tree_sitter::Tree, source: &str) -> Vec<UnknownNode> {
+        let mut unknown = collect_syntax_errors(tree, source);
+        match TemplateDialect::detect(source) {
+            Some(TemplateDialect::Jinja) => unknown.extend(jinja_regions(source)),
+            Some(dialect) => self.visit_directives(&tree.root_node(), source, dialect, &mut unknown),
+            None => {}
+        }
+        unknown.sort_by_key(|u| u.span.start);
+        unknown
+    }
+}
+
+impl HtmlTreeSitterAdapter {
+    /// Collect landmark elements in document order (not yet nested)
+    fn collect_landmarks(&self, node: &tree_sitter::Node, source: &str, out: &mut Vec<Declaration>) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if matches!(child.kind(), "element" | "script_element") {
+                if let Some(decl) = self.extract_landmark(&child, source) {
+                    out.push(decl);
+                }
+            }
+            self.collect_landmarks(&child, source, out);
+        }
+    }
+
+    /// An element with an `id`, a custom element/component, or a `<template>`
+    fn extract_landmark(&self, node: &tree_sitter::Node, source: &str) -> Option<Declaration> {
+        let tag = start_tag(node)?;
+        let tag_name = tag_name(&tag, source)?;
+        let attributes = attributes(&tag, source);
+        let attribute = |name: &str| {
+            attributes
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.clone().unwrap_or_default())
+        };
+
+        let id = attribute("id").filter(|id| !id.is_empty());
+        let component = tag_name.contains('-') || tag_name.starts_with(|c: char| c.is_ascii_uppercase());
+        let template = tag_name.eq_ignore_ascii_case("template");
+        if id.is_none() && !component && !template {
+            return None;
+        }
+
+        // <template #header>, <template v-slot:header>, <ng-template #ref>
+        let reference = attributes.iter().find_map(|(name, _)| {
+            name.strip_prefix('#')
+                .or_else(|| name.strip_prefix("v-slot:"))
+                .map(str::to_string)
+        });
+        let name = id.clone().or(reference).unwrap_or_else(|| tag_name.clone());
+
+        let mut decl = Declaration::new(name, DeclarationKind::Element, node_to_span(node));
+        decl.visibility = self.extract_visibility(node, source);
+        decl.signature_span = Some(node_to_span(&tag));
+        let mut cursor = node.walk();
+        let end_tag = node.children(&mut cursor).find(|c| c.kind() == "end_tag");
+        decl.body_span = end_tag.map(|end| Span {
+            start: tag.end_byte(),
+            end: end.start_byte(),
+            start_line: tag.end_position().row + 1,
+            end_line: end.start_position().row + 1,
+            start_column: tag.end_position().column,
+            end_column: end.start_position().column,
+        });
+        decl.metadata.insert("tag".to_string(), tag_name);
+        if let Some(id) = id {
+            decl.metadata.insert("id".to_string(), id);
+        }
+        if component {
+            decl.metadata.insert("component".to_string(), "true".to_string());
+        }
+        Some(decl)
+    }
+
+    /// `<script src>` as imports, `<link href>` stylesheets and preloads as includes
+    fn visit_imports(&self, node: &tree_sitter::Node, source: &str, imports: &mut Vec<ImportLike>) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if matches!(child.kind(), "element" | "script_element") {
+                if let Some(tag) = start_tag(&child) {
+                    let attributes = attributes(&tag, source);
+                    let attribute = |name: &str| {
+                        attributes
+                            .iter()
+                            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                            .and_then(|(_, v)| v.clone())
+                    };
+                    let import = match tag_name(&tag, source).as_deref() {
+                        Some("script") => attribute("src").map(|src| (src, ImportKind::Import)),
+                        Some("link") => {
+                            let rel = attribute("rel").unwrap_or_default().to_lowercase();
+                            let linked = ["stylesheet", "modulepreload", "preload", "import"]
+                                .iter()
+                                .any(|r| rel.split_whitespace().any(|part| part == *r));
+                            attribute("href").filter(|_| linked).map(|href| (href, ImportKind::Include))
+                        }
+                        _ => None,
+                    };
+                    if let Some((source, kind)) = import {
+                        imports.push(ImportLike {
+                            source,
+                            kind,
+                            items: Vec::new(),
+                            alias: None,
+                            type_only: false,
+                            span: node_to_span(&tag),
+                        });
+                    }
+                }
+            }
+            self.visit_imports(&child, source, imports);
+        }
+    }
+
+    fn visit_comments(&self, node: &tree_sitter::Node, source: &str, comments: &mut Vec<Comment>) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if child.kind() == "comment" {
+                let text = node_text(&child, source);
+                comments.push(Comment {
+                    text: text.trim_start_matches("<!--").trim_end_matches("-->").trim().to_string(),
+                    kind: CommentKind::Block,
+                    span: node_to_span(&child),
+                    attached_to: None,
+                });
+            } else {
+                self.visit_comments(&child, source, comments);
+            }
+        }
+    }
+
+    /// Vue/Angular directive attributes and `{{ }}` interpolations
+    fn visit_directives(
+        &self,
+        node: &tree_sitter::Node,
+        source: &str,
+        dialect: TemplateDialect,
+        unknown: &mut Vec<UnknownNode>,
+    ) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            match child.kind() {
+                "attribute" => {
+                    let name = child
+                        .child(0)
+                        .filter(|n| n.kind() == "attribute_name")
+                        .map(|n| node_text(&n, source))
+                        .unwrap_or("");
+                    if dialect.is_directive(name) {
+                        unknown.push(UnknownNode {
+                            span: node_to_span(&child),
+                            reason: Some(format!("{} directive", dialect.as_str())),
+                            raw_text: Some(node_text(&child, source).to_string()),
+                        });
+                    }
+                }
+                "text" => {
+                    let offset = child.start_byte();
+                    for (start, end) in delimited(node_text(&child, source), "{{", "}}") {
+                        unknown.push(template_region(source, offset + start, offset + end, dialect, "interpolation"));
+                    }
+                }
+                _ => self.visit_directives(&child, source, dialect, unknown),
+            }
+        }
+    }
+}
+
+fn start_tag<'a>(node: &tree_sitter::Node<'a>) -> Option<tree_sitter::Node<'a>> {
+    let mut cursor = node.walk();
+    let tag = node
+        .children(&mut cursor)
+        .find(|c| matches!(c.kind(), "start_tag" | "self_closing_tag"));
+    tag
+}
+
+fn tag_name(tag: &tree_sitter::Node, source: &str) -> Option<String> {
+    let mut cursor = tag.walk();
+    let name = tag
+        .children(&mut cursor)
+        .find(|c| c.kind() == "tag_name")
+        .map(|n| node_text(&n, source).to_string());
+    name
+}
+
+/// `(name, value)` pairs of a start tag, with quotes stripped
+fn attributes(tag: &tree_sitter::Node, source: &str) -> Vec<(String, Option<String>)> {
+    let mut result = Vec::new();
+    let mut cursor = tag.walk();
+    for attribute in tag.children(&mut cursor).filter(|c| c.kind() == "attribute") {
+        let mut parts = attribute.walk();
+        let mut name = None;
+        let mut value = None;
+        for part in attribute.children(&mut parts) {
+            match part.kind() {
+                "attribute_name" => name = Some(node_text(&part, source).to_string()),
+                "attribute_value" | "quoted_attribute_value" => {
+                    value = Some(node_text(&part, source).trim_matches(|c| c == '"' || c == '\'').to_string());
+                }
+                _ => {}
+            }
+        }
+        if let Some(name) = name {
+            result.push((name, value));
+        }
+    }
+    result
+}
+
+/// Byte ranges of `open ... close` pairs in `text` (unterminated ones are skipped)
+fn delimited(text: &str, open: &str, close: &str) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let mut from = 0;
+    while let Some(start) = text[from..].find(open).map(|i| from + i) {
+        let Some(end) = text[start + open.len()..].find(close).map(|i| start + open.len() + i + close.len()) else {
+            break;
+        };
+        ranges.push((start, end));
+        from = end;
+    }
+    ranges
+}
+
+fn template_region(source: &str, start: usize, end: usize, dialect: TemplateDialect, what: &str) -> UnknownNode {
+    UnknownNode {
+        span: offset_span(source, start, end),
+        reason: Some(format!("{} {}", dialect.as_str(), what)),
+        raw_text: Some(source[start..end].to_string()),
+    }
+}
+
+/// Jinja tags, expressions and comments
+fn jinja_regions(source: &str) -> Vec<UnknownNode> {
+    let mut regions = Vec::new();
+    for (open, close, what) in [("{%", "%}", "tag"), ("{{", "}}", "expression"), ("{#", "#}", "comment")] {
+        for (start, end) in delimited(source, open, close) {
+            regions.push(template_region(source, start, end, TemplateDialect::Jinja, what));
+        }
+    }
+    regions
+}
+
+/// `{% block name %} ... {% endblock %}` regions
+fn jinja_blocks(source: &str) -> Vec<Declaration> {
+    let mut blocks = Vec::new();
+    let mut open: Vec<(String, usize, usize)> = Vec::new();
+    for (start, end) in delimited(source, "{%", "%}") {
+        let tag = source[start + 2..end - 2].trim_matches(|c: char| c == '-' || c == '+' || c.is_whitespace());
+        let mut words = tag.split_whitespace();
+        match words.next() {
+            Some("block") => {
+                if let Some(name) = words.next() {
+                    open.push((name.to_string(), start, end));
+                }
+            }
+            Some("endblock") => {
+                if let Some((name, block_start, tag_end)) = open.pop() {
+                    let mut decl = Declaration::new(name, DeclarationKind::Element, offset_span(source, block_start, end));
+                    decl.visibility = Visibility::Public;
+                    decl.signature_span = Some(offset_span(source, block_start, tag_end));
+                    decl.body_span = Some(offset_span(source, tag_end, start));
+                    decl.metadata.insert("tag".to_string(), "block".to_string());
+                    decl.metadata.insert("template".to_string(), TemplateDialect::Jinja.as_str().to_string());
+                    decl.confidence = confidence::TEXT_MATCH;
+                    blocks.push(decl);
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_html(source: &str) -> tree_sitter::Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&tree_sitter_html::LANGUAGE.into()).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    #[test]
+    fn test_extract_landmarks() {
+        let source = r#"<!DOCTYPE html>
+<html>
+<head>
+  <link rel="stylesheet" href="/css/app.css">
+  <script src="/js/app.js"></script>
+</head>
+<body>
+  <!-- main layout -->
+  <main id="app">
+    <user-card id="profile"></user-card>
+    <section><nav-bar></nav-bar></section>
+    <div class="plain"></div>
+  </main>
+  <template id="row-template"><tr></tr></template>
+</body>
+</html>
+"#;
+        let tree = parse_html(source);
+        let adapter = HtmlTreeSitterAdapter::new();
+        let decls = adapter.extract_declarations(&tree, source);
+
+        let names: Vec<&str> = decls.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "row-template"]);
+        let app = &decls[0];
+        assert_eq!(app.kind, DeclarationKind::Element);
+        assert_eq!(app.metadata.get("tag").map(String::as_str), Some("main"));
+        let children: Vec<&str> = app.children.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(children, vec!["profile", "nav-bar"]);
+        assert_eq!(app.children[0].metadata.get("component").map(String::as_str), Some("true"));
+
+        let body = adapter.extract_body(&tree, source, app).unwrap();
+        assert_eq!(body.nested_declarations.len(), 2);
+
+        let imports = adapter.extract_imports(&tree, source);
+        let sources: Vec<(&str, ImportKind)> = imports.iter().map(|i| (i.source.as_str(), i.kind)).collect();
+        assert_eq!(sources, vec![("/css/app.css", ImportKind::Include), ("/js/app.js", ImportKind::Import)]);
+
+        let comments = adapter.extract_comments(&tree, source);
+        assert_eq!(comments[0].text, "main layout");
+        assert!(adapter.extract_errors(&tree, source).is_empty());
+    }
+
+    #[test]
+    fn test_template_dialects() {
+        let jinja = "{% extends \"base.html\" %}\n{% block content %}\n<div id=\"items\">{{ items|length }}</div>\n{% endblock %}\n";
+        assert_eq!(TemplateDialect::detect(jinja), Some(TemplateDialect::Jinja));
+        let adapter = HtmlTreeSitterAdapter::new();
+        let tree = parse_html(jinja);
+        let decls = adapter.extract_declarations(&tree, jinja);
+        assert_eq!(decls.len(), 1);
+        assert_eq!(decls[0].name, "content");
+        assert_eq!(decls[0].metadata.get("template").map(String::as_str), Some("jinja"));
+        assert_eq!(decls[0].children[0].name, "items");
+        assert_eq!(decls[0].confidence, confidence::TEXT_MATCH);
+        let unknown = adapter.extract_errors(&tree, jinja);
+        let reasons: Vec<&str> = unknown.iter().filter_map(|u| u.reason.as_deref()).collect();
+        assert_eq!(reasons, vec!["jinja tag", "jinja tag", "jinja expression", "jinja tag"]);
+
+        let vue = "<template>\n  <ul v-if=\"items.length\">\n    <TodoItem :item=\"item\" @remove=\"drop\">{{ item.title }}</TodoItem>\n  </ul>\n</template>\n";
+        assert_eq!(TemplateDialect::detect(vue), Some(TemplateDialect::Vue));
+        let tree = parse_html(vue);
+        let decls = adapter.extract_declarations(&tree, vue);
+        assert_eq!(decls[0].name, "template");
+        assert_eq!(decls[0].children[0].name, "TodoItem");
+        let unknown = adapter.extract_errors(&tree, vue);
+        assert_eq!(unknown.iter().filter(|u| u.reason.as_deref() == Some("vue directive")).count(), 3);
+        assert!(unknown.iter().any(|u| u.raw_text.as_deref() == Some("{{ item.title }}")));
+
+        let angular = "<li *ngFor=\"let item of items\" (click)=\"select(item)\">{{ item }}</li>";
+        assert_eq!(TemplateDialect::detect(angular), Some(TemplateDialect::Angular));
+        assert_eq!(TemplateDialect::detect("<p>plain</p>"), None);
+    }
+
+    #[test]
+    fn test_adapter_language() {
+        let adapter = HtmlTreeSitterAdapter::new();
+        assert_eq!(adapter.language(), LanguageId::Html);
+    }
+}
@@ -0,0 +1,258 @@
+//! JSON Language Adapter
+//!
+//! Object keys are declarations: each `"key": value` pair becomes a
+//! `Variable` whose `type` metadata names the kind of value, and keys of
+//! nested objects become its children (up to [`MAX_KEY_DEPTH`] levels).
+//!
+//! Config files such as `tsconfig.json` and `.vscode/settings.json` are
+//! JSONC: they carry comments and trailing commas, and JSON5 files may also
+//! quote strings with `'`. The grammar only knows strict JSON, so before
+//! parsing these are rewritten byte for byte: comments and trailing commas
+//! become spaces (line breaks kept) and single quotes become double quotes.
+//! Every offset and line of the parsed text is thus the same as in the
+//! original, so spans need no mapping back. Comments are extracted from the
+//! original text. Other JSON5 syntax (unquoted keys, hex numbers) still
+//! shows up as error nodes.
+
+use std::borrow::Cow;
+
+use super::{AdapterCapabilities, Capability, node_text, node_to_span, offset_span, LanguageAdapter};
+use crate::ir::{Block, Comment, CommentKind, Declaration, DeclarationKind, ImportLike, LanguageId, Visibility};
+
+/// Deepest level of object keys extracted as declarations
+pub const MAX_KEY_DEPTH: usize = 3;
+
+/// JSON language adapter using Tree-sitter
+pub struct JsonTreeSitterAdapter {
+    language: tree_sitter::Language,
+}
+
+impl JsonTreeSitterAdapter {
+    /// Create a new JSON adapter
+    pub fn new() -> Self {
+        Self {
+            language: tree_sitter_json::LANGUAGE.into(),
+        }
+    }
+}
+
+impl Default for JsonTreeSitterAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageAdapter for JsonTreeSitterAdapter {
+    fn language(&self) -> LanguageId {
+        LanguageId::Json
+    }
+
+    fn tree_sitter_language(&self) -> tree_sitter::Language {
+        self.language.clone()
+    }
+
+    fn parse_text<'a>(&self, source: &'a str) -> Cow<'a, str> {
+        to_strict(source, &scan(source))
+    }
+
+    fn extract_declarations(
+        &self,
+        tree: &tree_sitter::Tree,
+        source: &str,
+    ) -> Vec<Declaration> {
+        let mut cursor = tree.root_node().walk();
+        let top_level = tree.root_node().named_children(&mut cursor).find(|n| n.kind() == "object");
+        top_level.map_or_else(Vec::new, |object| self.extract_pairs(object, source, 1))
+    }
+
+    fn extract_imports(&self, _tree: &tree_sitter::Tree, _source: &str) -> Vec<ImportLike> {
+        Vec::new()
+    }
+
+    fn extract_comments(&self, _tree: &tree_sitter::Tree, source: &str) -> Vec<Comment> {
+        scan(source)
+            .comments
+            .into_iter()
+            .map(|(start, end, kind)| {
+                let raw = &source[start..end];
+                let text = match kind {
+                    CommentKind::Block => raw.trim_start_matches("/*").trim_end_matches("*/"),
+                    _ => raw.trim_start_matches("//"),
+                };
+                Comment {
+                    text: text.trim().to_string(),
+                    kind,
+                    span: offset_span(source, start, end),
+                    attached_to: None,
+                }
+            })
+            .collect()
+    }
+
+    fn extract_body(
+        &self,
+        _tree: &tree_sitter::Tree,
+        _source: &str,
+        declaration: &Declaration,
+    ) -> Option<Block> {
+        Some(Block {
+            span: declaration.body_span?,
+            nested_declarations: declaration.children.clone(),
+            ..Block::default()
+        })
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(&[Capability::Declarations, Capability::Nesting, Capability::Comments])
+    }
+
+    fn extract_visibility(&self, _node: &tree_sitter::Node, _source: &str) -> Visibility {
+        Visibility::Public
+    }
+}
+
+impl JsonTreeSitterAdapter {
+    /// Declarations for the pairs of an object
+    fn extract_pairs(&self, object: tree_sitter::Node, source: &str, depth: usize) -> Vec<Declaration> {
+        let mut cursor = object.walk();
+        object
+            .named_children(&mut cursor)
+            .filter(|child| child.kind() == "pair")
+            .filter_map(|pair| {
+                let key = pair.child_by_field_name("key")?;
+                let value = pair.child_by_field_name("value")?;
+                let name = node_text(&key, source).trim_matches(['"', '\'']).to_string();
+
+                let mut decl = Declaration::new(name, DeclarationKind::Variable, node_to_span(&pair));
+                decl.visibility = Visibility::Public;
+                decl.signature_span = Some(node_to_span(&key));
+                decl.body_span = Some(node_to_span(&value));
+                decl.metadata.insert("type".to_string(), value.kind().to_string());
+                if value.kind() == "object" && depth < MAX_KEY_DEPTH {
+                    decl.children = self.extract_pairs(value, source, depth + 1);
+                }
+                Some(decl)
+            })
+            .collect()
+    }
+}
+
+/// What a JSONC/JSON5 source adds to strict JSON, as byte offsets
+#[derive(Debug, Default, PartialEq)]
+struct Extensions {
+    /// Comment ranges and kinds
+    comments: Vec<(usize, usize, CommentKind)>,
+    trailing_commas: Vec<usize>,
+    /// Opening and closing quotes of single-quoted strings
+    single_quotes: Vec<usize>,
+}
+
+impl Extensions {
+    fn is_empty(&self) -> bool {
+        self.comments.is_empty() && self.trailing_commas.is_empty() && self.single_quotes.is_empty()
+    }
+}
+
+/// Find comments, trailing commas and single-quoted strings
+fn scan(source: &str) -> Extensions {
+    let bytes = source.as_bytes();
+    let mut found = Extensions::default();
+    // A comma seen since the last value, and whether it may be trailing
+    let mut comma: Option<usize> = None;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                let start = i;
+                let mut has_double_quote = false;
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    has_double_quote |= bytes[i] == b'"';
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                // A single-quoted string containing `"` would need escaping: left as is
+                if quote == b'\'' && !has_double_quote && i < bytes.len() {
+                    found.single_quotes.extend([start, i]);
+                }
+                comma = None;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                let end = source[i..].find('\n').map_or(source.len(), |n| i + n);
+                found.comments.push((i, end, CommentKind::Line));
+                i = end;
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                let end = source[i + 2..].find("*/").map_or(source.len(), |n| i + 2 + n + 2);
+                found.comments.push((i, end, CommentKind::Block));
+                i = end;
+                continue;
+            }
+            b',' => comma = Some(i),
+            b'}' | b']' => {
+                if let Some(offset) = comma.take() {
+                    found.trailing_commas.push(offset);
+                }
+            }
+            byte if byte.is_ascii_whitespace() => {}
+            _ => comma = None,
+        }
+        i += 1;
+    }
+    found
+}
+
+/// Strict JSON with the same byte offsets and lines as `source`
+fn to_strict<'a>(source: &'a str, found: &Extensions) -> Cow<'a, str> {
+    if found.is_empty() {
+        return Cow::Borrowed(source);
+    }
+    let mut bytes = source.as_bytes().to_vec();
+    for &(start, end, _) in &found.comments {
+        for byte in &mut bytes[start..end] {
+            if !matches!(*byte, b'\n' | b'\r') {
+                *byte = b' ';
+            }
+        }
+    }
+    for &offset in &found.trailing_commas {
+        bytes[offset] = b' ';
+    }
+    for &offset in &found.single_quotes {
+        bytes[offset] = b'"';
+    }
+    // Only ASCII bytes were written, and only over whole comments or ASCII bytes
+    Cow::Owned(String::from_utf8(bytes).unwrap_or_else(|_| source.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AdapterRegistry;
+
+    #[test]
+    fn test_jsonc_indexes_without_errors() {
+        let source = "// TypeScript settings\n{\n  /* compiler */\n  \"compilerOptions\": {\n    \"strict\": true, // always\n    \"paths\": { 'app/*': ['src/*'], },\n  },\n  \"include\": [\"src\",],\n}\n";
+        let strict = to_strict(source, &scan(source));
+        assert_eq!(strict.len(), source.len());
+        assert_eq!(strict.lines().count(), source.lines().count());
+        assert!(serde_json::from_str::<serde_json::Value>(&strict).is_ok(), "{}", strict);
+
+        let file = AdapterRegistry::new().parse(source, LanguageId::Json).unwrap();
+        assert!(file.unknown_regions.is_empty(), "{:?}", file.unknown_regions);
+        let names: Vec<&str> = file.declarations.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["compilerOptions", "include"]);
+        let options = &file.declarations[0];
+        assert_eq!((options.span.start_line, options.span.end_line), (4, 7));
+        let children: Vec<&str> = options.children.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(children, vec!["strict", "paths"]);
+        assert_eq!(options.children[1].children[0].name, "app/*");
+        assert_eq!(file.declarations[1].metadata.get("type").map(String::as_str), Some("array"));
+
+        let comments: Vec<&str> = file.comments.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(comments, vec!["TypeScript settings", "compiler", "always"]);
+
+        // Strict JSON is parsed as is
+        assert!(matches!(to_strict("{\"a\": \"//\"}", &scan("{\"a\": \"//\"}")), Cow::Borrowed(_)));
+    }
+}
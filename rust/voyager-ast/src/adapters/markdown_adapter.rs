@@ -0,0 +1,215 @@
+//! Markdown Language Adapter
+//!
+//! Treats headings as declarations: every ATX (`## Title`) or setext
+//! heading opens a `Section` that runs until the next heading of the same
+//! or a higher level, so sections nest by level. Each section records its
+//! `level` and GitHub-style `anchor` in the metadata, which lets docs be
+//! serialized at outline depth and zoomed per section.
+
+use std::collections::HashMap;
+
+use super::{AdapterCapabilities, Capability, nest_by_span, node_text, node_to_span, offset_span, LanguageAdapter};
+use crate::ir::{Block, Comment, Declaration, DeclarationKind, ImportLike, LanguageId, Visibility};
+
+/// Markdown language adapter using Tree-sitter
+pub struct MarkdownTreeSitterAdapter {
+    language: tree_sitter::Language,
+}
+
+impl MarkdownTreeSitterAdapter {
+    /// Create a new Markdown adapter
+    pub fn new() -> Self {
+        Self {
+            language: tree_sitter_md::LANGUAGE.into(),
+        }
+    }
+}
+
+impl Default for MarkdownTreeSitterAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A heading found in the document
+struct Heading<'a> {
+    node: tree_sitter::Node<'a>,
+    level: usize,
+    title: String,
+}
+
+impl LanguageAdapter for MarkdownTreeSitterAdapter {
+    fn language(&self) -> LanguageId {
+        LanguageId::Markdown
+    }
+
+    fn tree_sitter_language(&self) -> tree_sitter::Language {
+        self.language.clone()
+    }
+
+    fn extract_declarations(
+        &self,
+        tree: &tree_sitter::Tree,
+        source: &str,
+    ) -> Vec<Declaration> {
+        let mut headings = Vec::new();
+        self.collect_headings(&tree.root_node(), source, &mut headings);
+
+        let mut anchors: HashMap<String, usize> = HashMap::new();
+        let mut sections = Vec::new();
+        for (i, heading) in headings.iter().enumerate() {
+            let start = heading.node.start_byte();
+            let end = headings[i + 1..]
+                .iter()
+                .find(|next| next.level <= heading.level)
+                .map_or(source.len(), |next| next.node.start_byte());
+            // Trailing blank lines belong to no section
+            let end = start + source[start..end].trim_end().len();
+            let body_start = heading.node.end_byte().min(end);
+
+            let mut decl = Declaration::new(heading.title.clone(), DeclarationKind::Section, offset_span(source, start, end));
+            decl.visibility = self.extract_visibility(&heading.node, source);
+            decl.signature_span = Some(node_to_span(&heading.node));
+            decl.body_span = Some(offset_span(source, body_start, end));
+            decl.metadata.insert("level".to_string(), heading.level.to_string());
+            decl.metadata.insert("anchor".to_string(), unique_anchor(&heading.title, &mut anchors));
+            sections.push(decl);
+        }
+        nest_by_span(sections)
+    }
+
+    fn extract_imports(&self, _tree: &tree_sitter::Tree, _source: &str) -> Vec<ImportLike> {
+        Vec::new()
+    }
+
+    fn extract_comments(&self, _tree: &tree_sitter::Tree, _source: &str) -> Vec<Comment> {
+        Vec::new()
+    }
+
+    fn extract_body(
+        &self,
+        _tree: &tree_sitter::Tree,
+        _source: &str,
+        declaration: &Declaration,
+    ) -> Option<Block> {
+        Some(Block {
+            span: declaration.body_span?,
+            nested_declarations: declaration.children.clone(),
+            ..Block::default()
+        })
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(&[Capability::Declarations, Capability::Nesting])
+    }
+
+    fn extract_visibility(&self, _node: &tree_sitter::Node, _source: &str) -> Visibility {
+        Visibility::Public
+    }
+}
+
+impl MarkdownTreeSitterAdapter {
+    /// Collect headings in document order, skipping quotes and lists
+    fn collect_headings<'a>(&self, node: &tree_sitter::Node<'a>, source: &str, out: &mut Vec<Heading<'a>>) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            match child.kind() {
+                "atx_heading" | "setext_heading" => {
+                    if let Some(heading) = self.extract_heading(child, source) {
+                        out.push(heading);
+                    }
+                }
+                "block_quote" | "list" | "fenced_code_block" | "indented_code_block" => {}
+                _ => self.collect_headings(&child, source, out),
+            }
+        }
+    }
+
+    fn extract_heading<'a>(&self, node: tree_sitter::Node<'a>, source: &str) -> Option<Heading<'a>> {
+        let mut cursor = node.walk();
+        let marker = node
+            .children(&mut cursor)
+            .map(|c| c.kind())
+            .find(|kind| kind.starts_with("atx_h") || kind.starts_with("setext_h"))?;
+        // atx_h3_marker -> 3, setext_h1_underline -> 1
+        let level = marker.chars().find(|c| c.is_ascii_digit())?.to_digit(10)? as usize;
+
+        let content = node.child_by_field_name("heading_content")?;
+        let title = node_text(&content, source)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let title = title.trim_end_matches('#').trim_end().to_string();
+        if title.is_empty() {
+            return None;
+        }
+        Some(Heading { node, level, title })
+    }
+}
+
+/// GitHub-style anchor: lowercase, punctuation dropped, spaces to hyphens
+pub fn anchor(title: &str) -> String {
+    title
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Anchor with GitHub's `-1`, `-2`, ... suffixes for repeated titles
+fn unique_anchor(title: &str, seen: &mut HashMap<String, usize>) -> String {
+    let base = anchor(title);
+    let count = seen.entry(base.clone()).or_insert(0);
+    let result = if *count == 0 { base } else { format!("{}-{}", base, count) };
+    *count += 1;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_markdown(source: &str) -> tree_sitter::Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&tree_sitter_md::LANGUAGE.into()).unwrap();
+        parser.parse(source, None).unwrap()
+    }
+
+    #[test]
+    fn test_extract_sections() {
+        let source = "# Project\n\nIntro.\n\n## Installation\n\nRun `make`.\n\n```sh\n# not a heading\n```\n\n### From source\n\nClone it.\n\n## Usage\n\nSee below.\n\nFAQ\n---\n\nAnswers.\n";
+        let tree = parse_markdown(source);
+        let adapter = MarkdownTreeSitterAdapter::new();
+        let decls = adapter.extract_declarations(&tree, source);
+
+        assert_eq!(decls.len(), 1);
+        let project = &decls[0];
+        assert_eq!((project.name.as_str(), project.kind), ("Project", DeclarationKind::Section));
+        assert_eq!(project.span.start_line, 1);
+        assert_eq!(project.span.end_line, 24);
+
+        let names: Vec<&str> = project.children.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, vec!["Installation", "Usage", "FAQ"]);
+        let installation = &project.children[0];
+        assert_eq!((installation.span.start_line, installation.span.end_line), (5, 15));
+        assert_eq!(installation.metadata.get("level").map(String::as_str), Some("2"));
+        assert_eq!(installation.children[0].name, "From source");
+        assert_eq!(installation.children[0].metadata.get("anchor").map(String::as_str), Some("from-source"));
+
+        let body = adapter.extract_body(&tree, source, installation).unwrap();
+        assert!(source[body.span.start..body.span.end].contains("Clone it."));
+    }
+
+    #[test]
+    fn test_anchors() {
+        assert_eq!(anchor("What's New in v2.0?"), "whats-new-in-v20");
+        let mut seen = HashMap::new();
+        assert_eq!(unique_anchor("Usage", &mut seen), "usage");
+        assert_eq!(unique_anchor("Usage", &mut seen), "usage-1");
+    }
+}
@@ -8,20 +8,121 @@
 //! - **Rust**: Full support for functions, structs, enums, traits, impl blocks
 //! - **Python**: Functions (def/async), classes, imports, decorators, docstrings
 //! - **TypeScript/JavaScript**: Functions, classes, interfaces, types, imports/exports
+//!
+//! # Markup
+//!
+//! - **HTML**: Landmarks (ids, custom elements, templates), script/stylesheet links
+//! - **Markdown**: Heading sections, nested by level
+//!
+//! # Data
+//!
+//! - **JSON**: Object keys, nested by object; JSONC comments and trailing commas
 
 pub mod rust_adapter;
 pub mod python_adapter;
 pub mod typescript_adapter;
+pub mod html_adapter;
+pub mod markdown_adapter;
+pub mod json_adapter;
+
+use std::borrow::Cow;
+use std::collections::BTreeSet;
+
+use serde::Serialize;
 
 use crate::error::Result;
 use crate::ir::{
-    Block, Comment, Declaration, ImportLike, LanguageId, Span, UnknownNode, Visibility,
+    confidence, Block, Comment, Declaration, ImportLike, LanguageId, Span, UnknownNode, Visibility,
 };
 
 // Re-export all adapters
 pub use rust_adapter::RustTreeSitterAdapter;
 pub use python_adapter::PythonTreeSitterAdapter;
 pub use typescript_adapter::TypeScriptTreeSitterAdapter;
+pub use html_adapter::{HtmlTreeSitterAdapter, TemplateDialect};
+pub use markdown_adapter::MarkdownTreeSitterAdapter;
+pub use json_adapter::JsonTreeSitterAdapter;
+
+/// Something an adapter can extract into the IR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    Declarations,
+    /// Declarations inside declarations (methods, fields, subsections)
+    Nesting,
+    Visibility,
+    DocComments,
+    Parameters,
+    ReturnTypes,
+    Imports,
+    Comments,
+    /// Calls made in bodies (used to link tests to symbols)
+    Calls,
+    ControlFlow,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 10] = [
+        Self::Declarations,
+        Self::Nesting,
+        Self::Visibility,
+        Self::DocComments,
+        Self::Parameters,
+        Self::ReturnTypes,
+        Self::Imports,
+        Self::Comments,
+        Self::Calls,
+        Self::ControlFlow,
+    ];
+
+    /// Human-readable name
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Declarations => "declarations",
+            Self::Nesting => "nesting",
+            Self::Visibility => "visibility",
+            Self::DocComments => "doc comments",
+            Self::Parameters => "parameters",
+            Self::ReturnTypes => "return types",
+            Self::Imports => "imports",
+            Self::Comments => "comments",
+            Self::Calls => "calls",
+            Self::ControlFlow => "control flow",
+        }
+    }
+}
+
+/// What an adapter actually extracts
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct AdapterCapabilities {
+    supported: BTreeSet<Capability>,
+}
+
+impl AdapterCapabilities {
+    pub fn new(supported: &[Capability]) -> Self {
+        Self { supported: supported.iter().copied().collect() }
+    }
+
+    /// Every capability
+    pub fn all() -> Self {
+        Self::new(&Capability::ALL)
+    }
+
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.supported.contains(&capability)
+    }
+
+    /// Supported capabilities, in [`Capability::ALL`] order
+    pub fn supported(&self) -> impl Iterator<Item = Capability> + '_ {
+        self.supported.iter().copied()
+    }
+
+    /// Capabilities the adapter lacks, in [`Capability::ALL`] order
+    pub fn missing(&self) -> impl Iterator<Item = Capability> + '_ {
+        Capability::ALL.into_iter().filter(|c| !self.supports(*c))
+    }
+}
 
 /// Trait for language-specific adapters
 ///
@@ -37,6 +138,16 @@ pub trait LanguageAdapter: Send + Sync {
     /// Get the Tree-sitter language
     fn tree_sitter_language(&self) -> tree_sitter::Language;
 
+    /// Text handed to the parser in place of `source` (`source` itself unless
+    /// overridden)
+    ///
+    /// Lets an adapter accept dialects its grammar rejects. The result must
+    /// keep every byte offset and line break of `source`: extraction runs on
+    /// the original text with spans taken from the tree of this one.
+    fn parse_text<'a>(&self, source: &'a str) -> Cow<'a, str> {
+        Cow::Borrowed(source)
+    }
+
     /// Extract declarations from a parse tree (Index mode)
     ///
     /// This should extract top-level declarations only.
@@ -66,54 +177,105 @@ pub trait LanguageAdapter: Send + Sync {
 
     /// Extract unknown/error nodes from a parse tree
     fn extract_errors(&self, tree: &tree_sitter::Tree, source: &str) -> Vec<UnknownNode> {
-        let mut errors = Vec::new();
-        let mut cursor = tree.walk();
-
-        fn visit_errors(
-            cursor: &mut tree_sitter::TreeCursor,
-            source: &str,
-            errors: &mut Vec<UnknownNode>,
-        ) {
-            loop {
-                let node = cursor.node();
-
-                if node.is_error() || node.is_missing() {
-                    let span = node_to_span(&node);
-                    let raw_text = if span.len() < 200 {
-                        Some(source[span.start..span.end].to_string())
-                    } else {
-                        Some(format!(
-                            "{}... ({} bytes)",
-                            &source[span.start..span.start.saturating_add(100)],
-                            span.len()
-                        ))
-                    };
-
-                    errors.push(UnknownNode {
-                        span,
-                        reason: Some(if node.is_missing() {
-                            "Missing syntax element".to_string()
-                        } else {
-                            "Syntax error".to_string()
-                        }),
-                        raw_text,
-                    });
-                }
-
-                if cursor.goto_first_child() {
-                    visit_errors(cursor, source, errors);
-                    cursor.goto_parent();
-                }
-
-                if !cursor.goto_next_sibling() {
-                    break;
-                }
+        collect_syntax_errors(tree, source)
+    }
+
+    /// What this adapter extracts (declarations only, unless overridden)
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::new(&[Capability::Declarations])
+    }
+}
+
+/// Reason of unknown nodes for error nodes of the parse tree
+pub const SYNTAX_ERROR: &str = "Syntax error";
+
+/// Reason of unknown nodes for elements the parser inserted
+pub const MISSING_ELEMENT: &str = "Missing syntax element";
+
+/// Whether an unknown node is a syntax error (rather than, say, a template directive)
+fn is_syntax_error(node: &UnknownNode) -> bool {
+    matches!(node.reason.as_deref(), Some(SYNTAX_ERROR) | Some(MISSING_ELEMENT))
+}
+
+/// Lower the confidence of declarations near syntax errors
+///
+/// A declaration whose header (signature, or first line) overlaps an error
+/// was recovered by the parser; one with an error inside it or on an
+/// adjacent line is error-adjacent. Children are checked on their own.
+/// Unknown regions other than syntax errors do not count.
+pub fn apply_error_confidence(declarations: &mut [Declaration], unknown: &[UnknownNode]) {
+    let errors: Vec<&UnknownNode> = unknown.iter().filter(|node| is_syntax_error(node)).collect();
+    if !errors.is_empty() {
+        lower_confidence(declarations, &errors);
+    }
+}
+
+fn lower_confidence(declarations: &mut [Declaration], errors: &[&UnknownNode]) {
+    for decl in declarations.iter_mut() {
+        let header = decl.signature_span.map_or((decl.span.start_line, decl.span.start_line), |s| (s.start_line, s.end_line));
+        let touches = |(first, last): (usize, usize)| {
+            errors.iter().any(|e| e.span.start_line <= last && e.span.end_line >= first)
+        };
+        let level = if touches(header) {
+            confidence::RECOVERED
+        } else if touches((decl.span.start_line.saturating_sub(1), decl.span.end_line + 1)) {
+            confidence::ERROR_ADJACENT
+        } else {
+            confidence::CERTAIN
+        };
+        decl.confidence = decl.confidence.min(level);
+        lower_confidence(&mut decl.children, errors);
+    }
+}
+
+/// Error and missing nodes of a parse tree as [`UnknownNode`]s
+///
+/// The default [`LanguageAdapter::extract_errors`]; adapters that add their
+/// own unknown regions call it first.
+pub fn collect_syntax_errors(tree: &tree_sitter::Tree, source: &str) -> Vec<UnknownNode> {
+    let mut errors = Vec::new();
+    let mut cursor = tree.walk();
+
+    fn visit_errors(
+        cursor: &mut tree_sitter::TreeCursor,
+        source: &str,
+        errors: &mut Vec<UnknownNode>,
+    ) {
+        loop {
+            let node = cursor.node();
+
+            if node.is_error() || node.is_missing() {
+                let span = node_to_span(&node);
+                let raw_text = if span.len() < 200 {
+                    Some(source[span.start..span.end].to_string())
+                } else {
+                    Some(format!(
+                        "{}... ({} bytes)",
+                        &source[span.start..span.start.saturating_add(100)],
+                        span.len()
+                    ))
+                };
+
+                errors.push(UnknownNode {
+                    span,
+                    reason: Some(if node.is_missing() { MISSING_ELEMENT } else { SYNTAX_ERROR }.to_string()),
+                    raw_text,
+                });
             }
-        }
 
-        visit_errors(&mut cursor, source, &mut errors);
-        errors
+            if cursor.goto_first_child() {
+                visit_errors(cursor, source, errors);
+                cursor.goto_parent();
+            }
+
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
     }
+
+    visit_errors(&mut cursor, source, &mut errors);
+    errors
 }
 
 /// Convert a Tree-sitter node to our Span type
@@ -128,6 +290,54 @@ pub fn node_to_span(node: &tree_sitter::Node) -> Span {
     }
 }
 
+/// Span for a byte range of `source`, computing lines and columns
+///
+/// For regions that don't correspond to a single node (template tags
+/// scanned from text, multi-node sections).
+pub fn offset_span(source: &str, start: usize, end: usize) -> Span {
+    let position = |offset: usize| {
+        let before = &source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = offset - before.rfind('\n').map_or(0, |i| i + 1);
+        (line, column)
+    };
+    let (start_line, start_column) = position(start);
+    let (end_line, end_column) = position(end);
+    Span { start, end, start_line, end_line, start_column, end_column }
+}
+
+/// Nest declarations by span containment, keeping document order
+///
+/// For adapters that find declarations flat (markup landmarks, headings)
+/// rather than by walking a nested tree.
+pub fn nest_by_span(mut flat: Vec<Declaration>) -> Vec<Declaration> {
+    flat.sort_by_key(|d| (d.span.start, std::cmp::Reverse(d.span.end)));
+
+    fn attach(stack: &mut [Declaration], roots: &mut Vec<Declaration>, decl: Declaration) {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(decl),
+            None => roots.push(decl),
+        }
+    }
+
+    let mut roots = Vec::new();
+    let mut stack: Vec<Declaration> = Vec::new();
+    for decl in flat {
+        while let Some(top) = stack.last() {
+            if top.span.contains_span(&decl.span) {
+                break;
+            }
+            let done = stack.pop().expect("stack is not empty");
+            attach(&mut stack, &mut roots, done);
+        }
+        stack.push(decl);
+    }
+    while let Some(done) = stack.pop() {
+        attach(&mut stack, &mut roots, done);
+    }
+    roots
+}
+
 /// Get the text content of a Tree-sitter node
 pub fn node_text<'a>(node: &tree_sitter::Node, source: &'a str) -> &'a str {
     &source[node.start_byte()..node.end_byte()]
@@ -171,4 +381,20 @@ mod tests {
         // This would need a real tree-sitter parse to test properly
         // For now, just ensure the function signature is correct
     }
+
+    #[test]
+    fn test_error_confidence() {
+        let span = |first: usize, last: usize| Span { start: 0, end: 0, start_line: first, end_line: last, start_column: 0, end_column: 0 };
+        let error = |line: usize, reason: &str| UnknownNode { span: span(line, line), reason: Some(reason.to_string()), raw_text: None };
+        let mut decls = vec![
+            Declaration::new("recovered".to_string(), crate::ir::DeclarationKind::Function, span(1, 3)),
+            Declaration::new("adjacent".to_string(), crate::ir::DeclarationKind::Function, span(5, 8)),
+            Declaration::new("clean".to_string(), crate::ir::DeclarationKind::Function, span(20, 22)),
+        ];
+        apply_error_confidence(&mut decls, &[error(1, SYNTAX_ERROR), error(9, MISSING_ELEMENT), error(21, "jinja tag")]);
+        let levels: Vec<f32> = decls.iter().map(|d| d.confidence).collect();
+        assert_eq!(levels, vec![confidence::RECOVERED, confidence::ERROR_ADJACENT, confidence::CERTAIN]);
+        assert!(decls[0].is_uncertain() && !decls[2].is_uncertain());
+        assert!(!serde_json::to_string(&decls[2]).unwrap().contains("confidence"));
+    }
 }
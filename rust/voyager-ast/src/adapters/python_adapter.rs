@@ -3,7 +3,7 @@
 //! Extracts structural information from Python source files using Tree-sitter.
 //! Supports functions (def/async def), classes, imports, decorators, and docstrings.
 
-use super::{find_child_by_kind, node_text, node_to_span, LanguageAdapter};
+use super::{AdapterCapabilities, find_child_by_kind, node_text, node_to_span, LanguageAdapter};
 use crate::ir::{
     Block, Call, Comment, CommentKind, ControlFlow, ControlFlowKind, Declaration,
     DeclarationKind, ImportKind, ImportLike, LanguageId, Parameter, Span, Visibility,
@@ -110,6 +110,10 @@ impl LanguageAdapter for PythonTreeSitterAdapter {
         Some(self.extract_block(&body_node, source))
     }
 
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::all()
+    }
+
     fn extract_visibility(&self, node: &tree_sitter::Node, source: &str) -> Visibility {
         // Python uses naming conventions for visibility
         // _name = protected/internal
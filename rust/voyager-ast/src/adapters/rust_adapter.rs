@@ -3,7 +3,7 @@
 //! Extracts structural information from Rust source files using Tree-sitter.
 //! Supports functions, structs, enums, traits, impl blocks, and more.
 
-use super::{find_child_by_kind, find_children_by_kind, node_text, node_to_span, LanguageAdapter};
+use super::{AdapterCapabilities, find_child_by_kind, find_children_by_kind, node_text, node_to_span, LanguageAdapter};
 use crate::ir::{
     Block, Call, Comment, CommentKind, ControlFlow, ControlFlowKind, Declaration,
     DeclarationKind, ImportKind, ImportLike, LanguageId, Parameter, Span, Visibility,
@@ -127,6 +127,10 @@ impl LanguageAdapter for RustTreeSitterAdapter {
         Some(self.extract_block(&body_node, source))
     }
 
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::all()
+    }
+
     fn extract_visibility(&self, node: &tree_sitter::Node, source: &str) -> Visibility {
         // Look for visibility modifier
         let mut cursor = node.walk();
@@ -471,8 +475,10 @@ impl RustTreeSitterAdapter {
 
     /// Extract a use declaration
     fn extract_use_declaration(&self, node: &tree_sitter::Node, source: &str) -> Option<ImportLike> {
-        // Find the use_tree or scoped_identifier
-        let use_tree = find_child_by_kind(node, "use_tree")
+        // The argument is a path, a braced list (`a::{B, C}`), an alias or a glob
+        let use_tree = node
+            .child_by_field_name("argument")
+            .or_else(|| find_child_by_kind(node, "use_tree"))
             .or_else(|| find_child_by_kind(node, "scoped_identifier"))?;
 
         let full_path = node_text(&use_tree, source).to_string();
@@ -498,7 +504,10 @@ impl RustTreeSitterAdapter {
         if let Some(list) = find_child_by_kind(node, "use_list") {
             let mut cursor = list.walk();
             for child in list.children(&mut cursor) {
-                if child.kind() == "identifier" || child.kind() == "use_tree" {
+                if matches!(
+                    child.kind(),
+                    "identifier" | "use_tree" | "scoped_identifier" | "scoped_use_list" | "use_as_clause" | "self"
+                ) {
                     items.push(node_text(&child, source).to_string());
                 }
             }
@@ -509,6 +518,9 @@ impl RustTreeSitterAdapter {
 
     /// Extract alias from use statement
     fn extract_use_alias(&self, node: &tree_sitter::Node, source: &str) -> Option<String> {
+        if node.kind() == "use_as_clause" {
+            return node.child_by_field_name("alias").map(|alias| node_text(&alias, source).to_string());
+        }
         if let Some(alias) = find_child_by_kind(node, "use_as_clause") {
             if let Some(ident) = find_child_by_kind(&alias, "identifier") {
                 return Some(node_text(&ident, source).to_string());
@@ -727,7 +739,7 @@ impl RustTreeSitterAdapter {
                 "ERROR" => {
                     block.unknown_regions.push(crate::ir::UnknownNode {
                         span: node_to_span(&child),
-                        reason: Some("Syntax error".to_string()),
+                        reason: Some(super::SYNTAX_ERROR.to_string()),
                         raw_text: Some(node_text(&child, source).to_string()),
                     });
                 }
@@ -934,6 +946,9 @@ mod utils;
         // Should have at least 2 use statements
         assert!(imports.len() >= 2, "Expected at least 2 imports, got {}", imports.len());
         assert!(imports[0].source.contains("HashMap"));
+        assert!(imports
+            .iter()
+            .any(|i| i.source == "crate::ir::{File, Span}" && i.items == ["File", "Span"]));
 
         // Check if mod was captured (it may be treated as a declaration)
         let has_module = imports.iter().any(|i| i.kind == ImportKind::Module);
@@ -3,7 +3,7 @@
 //! Extracts structural information from TypeScript and JavaScript source files
 //! using Tree-sitter. Supports functions, classes, interfaces, types, imports/exports.
 
-use super::{find_child_by_kind, node_text, node_to_span, LanguageAdapter};
+use super::{AdapterCapabilities, find_child_by_kind, node_text, node_to_span, LanguageAdapter};
 use crate::ir::{
     Block, Call, Comment, CommentKind, ControlFlow, ControlFlowKind, Declaration,
     DeclarationKind, ImportKind, ImportLike, LanguageId, Parameter, Span, Visibility,
@@ -115,6 +115,10 @@ impl LanguageAdapter for TypeScriptTreeSitterAdapter {
         Some(self.extract_block(&body_node, source))
     }
 
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::all()
+    }
+
     fn extract_visibility(&self, node: &tree_sitter::Node, source: &str) -> Visibility {
         // Check for visibility modifiers
         let mut cursor = node.walk();
@@ -0,0 +1,160 @@
+//! Zoom Context Windows
+//!
+//! The lines around a zoomed symbol. A plain cut `context_lines` above and
+//! below the symbol routinely starts inside a doc comment, a statement or a
+//! multi-line string. Given the parse tree, each end of the window is moved
+//! to a node boundary instead: when the cut falls inside a node next to the
+//! symbol (or next to one of its ancestors), the node is included whole if
+//! that costs at most `context_lines` more lines, and left out otherwise.
+//! Comments directly above a node count as part of it. Without a tree the
+//! cut is by lines.
+
+use tree_sitter::{Node, Tree};
+
+use crate::ir::ImportLike;
+use crate::provider::ContextWindow;
+
+/// Context around lines `first_line..=last_line` (1-indexed) of `source`
+///
+/// `imports` are listed in the window unless they are already part of it.
+pub(crate) fn context_window(
+    source: &str,
+    tree: Option<&Tree>,
+    imports: &[ImportLike],
+    first_line: usize,
+    last_line: usize,
+    context_lines: usize,
+) -> ContextWindow {
+    let lines: Vec<&str> = source.lines().collect();
+    let first_row = first_line.saturating_sub(1).min(lines.len());
+    let last_row = last_line.saturating_sub(1).max(first_row);
+
+    let mut before = first_row.saturating_sub(context_lines);
+    let mut after = last_row + context_lines;
+    if let Some(node) = tree.and_then(|tree| covering_node(tree, source, &lines, first_row, last_row)) {
+        before = widen_before(node, before, context_lines).min(first_row);
+        after = widen_after(node, after, context_lines).max(last_row);
+    }
+    let after = after.min(lines.len().saturating_sub(1));
+
+    let text = |rows: std::ops::Range<usize>| -> Vec<String> {
+        lines.get(rows).unwrap_or_default().iter().map(|l| l.to_string()).collect()
+    };
+    let window = before + 1..=after + 1;
+    ContextWindow {
+        before: text(before..first_row),
+        after: text(last_row + 1..after + 1),
+        start_line: before + 1,
+        end_line: after + 1,
+        imports: imports
+            .iter()
+            .filter(|import| !(window.contains(&import.span.start_line) && window.contains(&import.span.end_line)))
+            .filter_map(|import| source.get(import.span.start..import.span.end))
+            .map(str::to_string)
+            .collect(),
+    }
+}
+
+/// Smallest node spanning the non-blank text of rows `first..=last`
+fn covering_node<'t>(tree: &'t Tree, source: &str, lines: &[&str], first: usize, last: usize) -> Option<Node<'t>> {
+    let offset = |line: &str| line.as_ptr() as usize - source.as_ptr() as usize;
+    let first_line = lines.get(first)?;
+    let last_line = lines.get(last.min(lines.len() - 1))?;
+    let start = offset(first_line) + (first_line.len() - first_line.trim_start().len());
+    let end = offset(last_line) + last_line.trim_end().len();
+    tree.root_node().descendant_for_byte_range(start, end.max(start))
+}
+
+/// Last row holding text of a node (nodes ending in a newline end on the next row)
+fn last_row(node: Node<'_>) -> usize {
+    let end = node.end_position();
+    if end.column == 0 && end.row > node.start_position().row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+/// Row ranges of the children of `parent` before (or after) `current`, with
+/// runs of comments merged into the node they precede
+fn sibling_rows(parent: Node<'_>, current: Node<'_>, before: bool) -> Vec<(usize, usize)> {
+    let mut cursor = parent.walk();
+    let mut units: Vec<(usize, usize, bool)> = Vec::new();
+    for sibling in parent.children(&mut cursor) {
+        let side = if before { sibling.end_byte() <= current.start_byte() } else { sibling.start_byte() >= current.end_byte() };
+        if !side {
+            continue;
+        }
+        let (start, end) = (sibling.start_position().row, last_row(sibling));
+        let comment = sibling.kind().contains("comment");
+        match units.last_mut() {
+            Some(unit) if unit.2 && start <= unit.1 + 1 => *unit = (unit.0, unit.1.max(end), comment),
+            _ => units.push((start, end, comment)),
+        }
+    }
+    units.into_iter().map(|(start, end, _)| (start, end)).collect()
+}
+
+/// First row of the window, moved off any node the plain cut `target` splits
+fn widen_before(node: Node<'_>, target: usize, limit: usize) -> usize {
+    let mut current = node;
+    while let Some(parent) = current.parent() {
+        for (start, end) in sibling_rows(parent, current, true) {
+            if start < target && target <= end {
+                return if target - start <= limit { start } else { end + 1 };
+            }
+        }
+        if parent.start_position().row <= target {
+            return target;
+        }
+        current = parent;
+    }
+    target
+}
+
+/// Last row of the window, moved off any node the plain cut `target` splits
+fn widen_after(node: Node<'_>, target: usize, limit: usize) -> usize {
+    let mut current = node;
+    while let Some(parent) = current.parent() {
+        for (start, end) in sibling_rows(parent, current, false) {
+            if start <= target && target < end {
+                return if end - target <= limit { end } else { start.saturating_sub(1) };
+            }
+        }
+        if last_row(parent) >= target {
+            return target;
+        }
+        current = parent;
+    }
+    target
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AdapterRegistry, LanguageId};
+
+    #[test]
+    fn test_context_window_completes_nodes() {
+        let source = "use std::fmt;\n\n/// Adds.\n/// Two numbers.\n/// Carefully.\nfn add(a: i32, b: i32) -> i32 {\n    a + b\n}\n\nfn target() {}\n\nconst GREETING: &str = \"multi\nline\nstring\";\n";
+        let registry = AdapterRegistry::new();
+
+        // A 5-line cut would start inside the doc comment of `add`: the
+        // comment and function are included whole
+        let wide = registry.context_window(source, LanguageId::Rust, 10, 10, 5, true);
+        assert_eq!(wide.start_line, 3);
+        assert_eq!(wide.before.first().map(String::as_str), Some("/// Adds."));
+        assert_eq!(wide.imports, vec!["use std::fmt;".to_string()]);
+
+        // A 2-line cut: `add` would cost 5 more lines and is left out, the
+        // string constant after costs 2 and is completed
+        let tight = registry.context_window(source, LanguageId::Rust, 10, 10, 2, false);
+        assert_eq!(tight.start_line, 9);
+        assert_eq!(tight.end_line, 14);
+        assert_eq!(tight.after.last().map(String::as_str), Some("string\";"));
+        assert!(tight.imports.is_empty());
+
+        // No adapter: plain lines
+        let plain = registry.context_window("a\nb\nc\nd\n", LanguageId::Go, 3, 3, 1, false);
+        assert_eq!((plain.before, plain.after), (vec!["b".to_string()], vec!["d".to_string()]));
+    }
+}
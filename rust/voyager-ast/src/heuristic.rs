@@ -0,0 +1,281 @@
+//! Heuristic Fallback Indexer
+//!
+//! Languages without a grammar (Perl, Fortran, Go, in-house DSLs) would
+//! otherwise have no structure at all. This indexer scans the text instead:
+//!
+//! - **Declarations**: lines opening with a definition keyword
+//!   (`function`, `sub`, `proc`, `subroutine`, `def`, `func`, `class`,
+//!   `module`, `program`, ...) followed by a name
+//! - **Blocks**: a declaration runs over the lines indented deeper than its
+//!   header, plus a closing `}` or `end ...` line at the header's indent (or
+//!   a later `end <keyword>` line, as in Fortran's `END PROGRAM`);
+//!   declarations inside another's block become its children
+//! - **Comments**: runs of lines starting with `#`, `//`, `--`, `;`, `!` or `%`
+//!
+//! Every declaration it produces carries [`confidence::HEURISTIC`], and the
+//! file is marked through its `indexer` metadata (see [`File::is_heuristic`]).
+
+use crate::ir::{confidence, Comment, CommentKind, Declaration, DeclarationKind, File, LanguageId, Span};
+
+/// File metadata key naming the indexer that produced a file
+pub const INDEXER_METADATA: &str = "indexer";
+
+/// `indexer` metadata value of files indexed by this module
+pub const HEURISTIC_INDEXER: &str = "heuristic";
+
+/// Definition keywords and the kind of declaration they introduce
+const KEYWORDS: &[(&str, DeclarationKind)] = &[
+    ("function", DeclarationKind::Function),
+    ("sub", DeclarationKind::Function),
+    ("subroutine", DeclarationKind::Function),
+    ("proc", DeclarationKind::Function),
+    ("procedure", DeclarationKind::Function),
+    ("def", DeclarationKind::Function),
+    ("defun", DeclarationKind::Function),
+    ("func", DeclarationKind::Function),
+    ("fn", DeclarationKind::Function),
+    ("method", DeclarationKind::Method),
+    ("class", DeclarationKind::Class),
+    ("module", DeclarationKind::Module),
+    ("package", DeclarationKind::Module),
+    ("program", DeclarationKind::Module),
+];
+
+/// Words that may precede a definition keyword
+const MODIFIERS: &[&str] = &[
+    "public", "private", "protected", "static", "export", "local", "async", "pure", "elemental", "recursive",
+];
+
+const COMMENT_PREFIXES: &[&str] = &["#", "//", "--", ";", "!", "%"];
+
+/// Languages the heuristic indexer is used for: those without an adapter
+/// that are not markup or data formats
+pub fn indexes(language: LanguageId) -> bool {
+    !matches!(
+        language,
+        LanguageId::Html | LanguageId::Css | LanguageId::Json | LanguageId::Yaml | LanguageId::Toml | LanguageId::Markdown
+    )
+}
+
+/// Index a source file by keyword and indentation heuristics
+pub fn index(source: &str, language: LanguageId) -> File {
+    let lines = Lines::new(source);
+    let mut file = File::new(String::new(), language);
+    file.span = Span::new(0, source.len(), 1, source.lines().count().max(1));
+    file.metadata.insert(INDEXER_METADATA.to_string(), HEURISTIC_INDEXER.to_string());
+    file.comments = comments(&lines);
+
+    // Open declarations, innermost last, with the last line of their block
+    let mut open: Vec<(Declaration, usize)> = Vec::new();
+    for line in 0..lines.len() {
+        let Some((keyword, name, kind)) = definition(lines.text(line)) else { continue };
+        while open.last().is_some_and(|(_, end)| *end < line) {
+            close(&mut open, &mut file.declarations);
+        }
+        let end = block_end(&lines, line, keyword);
+        let mut decl = Declaration::new(name, kind, lines.span(line, end));
+        decl.confidence = confidence::HEURISTIC;
+        open.push((decl, end));
+    }
+    while !open.is_empty() {
+        close(&mut open, &mut file.declarations);
+    }
+    file
+}
+
+/// Move the innermost open declaration into its parent (or the top level)
+fn close(open: &mut Vec<(Declaration, usize)>, top_level: &mut Vec<Declaration>) {
+    if let Some((decl, _)) = open.pop() {
+        match open.last_mut() {
+            Some((parent, _)) => parent.children.push(decl),
+            None => top_level.push(decl),
+        }
+    }
+}
+
+/// Keyword, name and kind of the declaration a line opens, if any
+fn definition(line: &str) -> Option<(&'static str, String, DeclarationKind)> {
+    let mut rest = line.trim_start();
+    loop {
+        let (word, after) = split_word(rest)?;
+        let word = word.to_ascii_lowercase();
+        if MODIFIERS.contains(&word.as_str()) {
+            rest = after;
+            continue;
+        }
+        let &(keyword, kind) = KEYWORDS.iter().find(|(keyword, _)| *keyword == word)?;
+        if !after.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let mut after = after.trim_start();
+        // Go methods name their receiver first: `func (s *Server) Start()`
+        if after.starts_with('(') {
+            after = after[after.find(')')? + 1..].trim_start();
+        }
+        let name: String = after.chars().take_while(|c| c.is_alphanumeric() || matches!(c, '_' | ':' | '.' | '$')).collect();
+        let name = name.trim_end_matches([':', '.']);
+        return name.starts_with(|c: char| c.is_alphabetic() || c == '_').then(|| (keyword, name.to_string(), kind));
+    }
+}
+
+/// Leading identifier of `text` and what follows it
+fn split_word(text: &str) -> Option<(&str, &str)> {
+    let end = text.find(|c: char| !c.is_alphanumeric() && c != '_').unwrap_or(text.len());
+    (end > 0).then(|| text.split_at(end))
+}
+
+/// Last line of the block a declaration header opens
+fn block_end(lines: &Lines<'_>, header: usize, keyword: &str) -> usize {
+    let indent = lines.indent(header);
+    let mut end = header;
+    for line in header + 1..lines.len() {
+        let text = lines.text(line).trim();
+        if text.is_empty() || is_comment(text) {
+            continue;
+        }
+        if lines.indent(line) > indent || text == "{" {
+            end = line;
+            continue;
+        }
+        if is_closer(text) {
+            return line;
+        }
+        return keyword_end(lines, line, indent, keyword).unwrap_or(end);
+    }
+    end
+}
+
+/// `end <keyword>` line at or above `indent` from `from` on, before the next
+/// declaration at that level
+fn keyword_end(lines: &Lines<'_>, from: usize, indent: usize, keyword: &str) -> Option<usize> {
+    for line in from..lines.len() {
+        let text = lines.text(line).trim();
+        if text.is_empty() || lines.indent(line) > indent {
+            continue;
+        }
+        let mut words = text.split(|c: char| !c.is_alphanumeric() && c != '_').filter(|w| !w.is_empty());
+        if words.next().is_some_and(|w| w.eq_ignore_ascii_case("end")) {
+            if words.next().is_some_and(|w| w.eq_ignore_ascii_case(keyword)) {
+                return Some(line);
+            }
+        } else if definition(text).is_some() {
+            return None;
+        }
+    }
+    None
+}
+
+/// `}`, `end`, `END SUBROUTINE foo`, `end;` ...
+fn is_closer(text: &str) -> bool {
+    text.starts_with('}') || split_word(text).is_some_and(|(word, _)| word.eq_ignore_ascii_case("end"))
+}
+
+fn is_comment(text: &str) -> bool {
+    COMMENT_PREFIXES.iter().any(|prefix| text.starts_with(prefix))
+}
+
+/// Runs of comment lines, one comment per run
+fn comments(lines: &Lines<'_>) -> Vec<Comment> {
+    let mut comments = Vec::new();
+    let mut line = 0;
+    while line < lines.len() {
+        if !is_comment(lines.text(line).trim_start()) {
+            line += 1;
+            continue;
+        }
+        let start = line;
+        while line + 1 < lines.len() && is_comment(lines.text(line + 1).trim_start()) {
+            line += 1;
+        }
+        let text: Vec<&str> = (start..=line)
+            .map(|l| {
+                let text = lines.text(l).trim_start();
+                text.trim_start_matches(['#', '/', '-', ';', '!', '%']).trim()
+            })
+            .collect();
+        comments.push(Comment {
+            text: text.join("\n"),
+            kind: CommentKind::Line,
+            span: lines.span(start, line),
+            attached_to: None,
+        });
+        line += 1;
+    }
+    comments
+}
+
+/// Lines of a source with their byte offsets
+struct Lines<'a> {
+    source: &'a str,
+    /// Byte range of each line, without its terminator
+    ranges: Vec<(usize, usize)>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for line in source.split_inclusive('\n') {
+            let text = line.trim_end_matches(['\n', '\r']);
+            ranges.push((start, start + text.len()));
+            start += line.len();
+        }
+        Self { source, ranges }
+    }
+
+    fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    fn text(&self, line: usize) -> &'a str {
+        let (start, end) = self.ranges[line];
+        &self.source[start..end]
+    }
+
+    /// Width of the leading whitespace (tabs count as four)
+    fn indent(&self, line: usize) -> usize {
+        self.text(line)
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum()
+    }
+
+    /// Span from the start of line `first` to the end of line `last` (0-indexed)
+    fn span(&self, first: usize, last: usize) -> Span {
+        let mut span = Span::new(self.ranges[first].0, self.ranges[last].1, first + 1, last + 1);
+        span.end_column = self.ranges[last].1 - self.ranges[last].0;
+        span
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heuristic_index_of_unsupported_languages() {
+        let perl = "#!/usr/bin/perl\n# Greets people\n\npackage Greeter;\n\nsub hello {\n    my $name = shift;\n    print \"hi $name\\n\";\n}\n\nsub bye { print \"bye\\n\" }\n";
+        let file = index(perl, LanguageId::Unknown);
+        assert!(file.is_heuristic());
+        assert!(file.span_violations(perl.len()).is_empty());
+        let names: Vec<(&str, usize, usize)> =
+            file.declarations.iter().map(|d| (d.name.as_str(), d.span.start_line, d.span.end_line)).collect();
+        assert_eq!(names, vec![("Greeter", 4, 4), ("hello", 6, 9), ("bye", 11, 11)]);
+        assert!(file.declarations.iter().all(|d| d.confidence == confidence::HEURISTIC));
+        assert_eq!(file.comments.len(), 1);
+        assert_eq!(file.comments[0].text, "usr/bin/perl\nGreets people");
+
+        let fortran = "PROGRAM main\n  CALL greet()\nCONTAINS\n  SUBROUTINE greet()\n    PRINT *, 'hi'\n  END SUBROUTINE greet\nEND PROGRAM main\n";
+        let file = index(fortran, LanguageId::Unknown);
+        assert_eq!(file.declarations.len(), 1);
+        let program = &file.declarations[0];
+        assert_eq!((program.name.as_str(), program.kind, program.span.end_line), ("main", DeclarationKind::Module, 7));
+        assert_eq!(program.children[0].name, "greet");
+        assert_eq!(program.children[0].span.end_line, 6);
+
+        let go = "package main\n\nfunc (s *Server) Start() error {\n\treturn nil\n}\n";
+        let names: Vec<String> = index(go, LanguageId::Go).declarations.iter().map(|d| d.name.clone()).collect();
+        assert_eq!(names, vec!["main", "Start"]);
+    }
+}
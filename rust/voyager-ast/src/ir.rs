@@ -10,12 +10,15 @@
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+use crate::path::RawPath;
+
 // ============================================================================
 // Language Identification
 // ============================================================================
 
 /// Language identifier for source files
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum LanguageId {
     Rust,
@@ -69,7 +72,7 @@ impl LanguageId {
             "scala" | "sc" => Self::Scala,
             "html" | "htm" => Self::Html,
             "css" | "scss" | "sass" => Self::Css,
-            "json" | "jsonc" => Self::Json,
+            "json" | "jsonc" | "json5" => Self::Json,
             "yaml" | "yml" => Self::Yaml,
             "toml" => Self::Toml,
             "md" | "markdown" => Self::Markdown,
@@ -150,6 +153,7 @@ impl LanguageId {
 
 /// A contiguous region in source code
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Span {
     /// Start byte offset (inclusive)
     pub start: usize,
@@ -202,10 +206,35 @@ impl Span {
     pub fn is_empty(&self) -> bool {
         self.start >= self.end
     }
+
+    /// Check if `other` lies entirely within this span (bytes and lines)
+    pub fn contains_span(&self, other: &Span) -> bool {
+        other.start >= self.start
+            && other.end <= self.end
+            && other.start_line >= self.start_line
+            && other.end_line <= self.end_line
+    }
+
+    /// Smallest span covering both spans
+    ///
+    /// Columns follow the span that supplies the start (or end) offset.
+    pub fn merge(&self, other: &Span) -> Span {
+        let first = if (other.start, other.start_column) < (self.start, self.start_column) { other } else { self };
+        let last = if (other.end, other.end_column) > (self.end, self.end_column) { other } else { self };
+        Span {
+            start: first.start,
+            end: last.end,
+            start_line: self.start_line.min(other.start_line),
+            end_line: self.end_line.max(other.end_line),
+            start_column: first.start_column,
+            end_column: last.end_column,
+        }
+    }
 }
 
 /// A source region with optional language override (for embedded languages)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Region {
     pub span: Span,
     pub language: Option<LanguageId>,
@@ -217,9 +246,10 @@ pub struct Region {
 
 /// A parsed source file with its structural elements
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct File {
-    /// Canonical path to the file
-    pub path: String,
+    /// Canonical path to the file (lossless for non-UTF-8 names)
+    pub path: RawPath,
 
     /// Detected language identifier
     pub language: LanguageId,
@@ -246,9 +276,9 @@ pub struct File {
 
 impl File {
     /// Create a new empty file
-    pub fn new(path: String, language: LanguageId) -> Self {
+    pub fn new(path: impl Into<RawPath>, language: LanguageId) -> Self {
         Self {
-            path,
+            path: path.into(),
             language,
             declarations: Vec::new(),
             imports: Vec::new(),
@@ -259,6 +289,11 @@ impl File {
         }
     }
 
+    /// Whether the structure was guessed by the heuristic indexer, not parsed
+    pub fn is_heuristic(&self) -> bool {
+        self.metadata.get(crate::heuristic::INDEXER_METADATA).map(String::as_str) == Some(crate::heuristic::HEURISTIC_INDEXER)
+    }
+
     /// Check if the file has any parse errors
     pub fn has_errors(&self) -> bool {
         !self.unknown_regions.is_empty()
@@ -271,6 +306,72 @@ impl File {
         }
         count_nested(&self.declarations)
     }
+
+    /// Check every span in the file against the source it was parsed from
+    ///
+    /// Returns one message per violation (`start > end`, `end` past the end
+    /// of the source, or `start_line > end_line`). An empty result means the
+    /// IR is safe to slice `source` with. Used by the fuzz targets.
+    pub fn span_violations(&self, source_len: usize) -> Vec<String> {
+        let mut checker = SpanChecker { source_len, violations: Vec::new() };
+        checker.check("file", &self.span);
+        for decl in &self.declarations {
+            checker.check_declaration(decl);
+        }
+        for import in &self.imports {
+            checker.check("import", &import.span);
+        }
+        for comment in &self.comments {
+            checker.check("comment", &comment.span);
+        }
+        for unknown in &self.unknown_regions {
+            checker.check("unknown region", &unknown.span);
+        }
+        checker.violations
+    }
+}
+
+/// Accumulates span invariant violations for [`File::span_violations`]
+struct SpanChecker {
+    source_len: usize,
+    violations: Vec<String>,
+}
+
+impl SpanChecker {
+    fn check(&mut self, what: &str, span: &Span) {
+        if span.start > span.end {
+            self.violations.push(format!("{} span starts after it ends: {:?}", what, span));
+        }
+        if span.end > self.source_len {
+            self.violations.push(format!(
+                "{} span ends at {} past source length {}",
+                what, span.end, self.source_len
+            ));
+        }
+        if span.start_line > span.end_line {
+            self.violations.push(format!("{} span lines are reversed: {:?}", what, span));
+        }
+    }
+
+    fn check_declaration(&mut self, decl: &Declaration) {
+        let what = format!("declaration '{}'", decl.name);
+        self.check(&what, &decl.span);
+        if let Some(span) = &decl.signature_span {
+            self.check(&format!("{} signature", what), span);
+        }
+        if let Some(span) = &decl.body_span {
+            self.check(&format!("{} body", what), span);
+        }
+        if let Some(doc) = &decl.doc_comment {
+            self.check(&format!("{} doc comment", what), &doc.span);
+        }
+        for param in &decl.parameters {
+            self.check(&format!("{} parameter '{}'", what, param.name), &param.span);
+        }
+        for child in &decl.children {
+            self.check_declaration(child);
+        }
+    }
 }
 
 // ============================================================================
@@ -279,6 +380,7 @@ impl File {
 
 /// A named declaration (function, class, struct, type, constant)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Declaration {
     /// The declaration's name
     pub name: String,
@@ -319,6 +421,37 @@ pub struct Declaration {
     /// Additional metadata
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub metadata: BTreeMap<String, String>,
+
+    /// How sure the adapter is of this declaration, from 0.0 to 1.0 (see
+    /// [`confidence`]); omitted when certain
+    #[serde(default = "certain", skip_serializing_if = "is_certain")]
+    pub confidence: f32,
+}
+
+/// Confidence levels adapters assign to declarations
+///
+/// A declaration read from a clean region of the parse tree is certain.
+/// Consumers that weigh structure (API references, outlines, search) should
+/// treat lower values as hints rather than facts.
+pub mod confidence {
+    /// Read from an error-free region of the parse tree
+    pub const CERTAIN: f32 = 1.0;
+    /// Matched by scanning text the grammar does not model (template blocks)
+    pub const TEXT_MATCH: f32 = 0.8;
+    /// A syntax error lies inside or right next to the declaration
+    pub const ERROR_ADJACENT: f32 = 0.6;
+    /// The declaration's header overlaps a syntax error: the parser recovered it
+    pub const RECOVERED: f32 = 0.3;
+    /// Found by the heuristic indexer of languages without a grammar
+    pub const HEURISTIC: f32 = 0.4;
+}
+
+fn certain() -> f32 {
+    confidence::CERTAIN
+}
+
+fn is_certain(value: &f32) -> bool {
+    *value >= confidence::CERTAIN
 }
 
 impl Declaration {
@@ -336,9 +469,15 @@ impl Declaration {
             parameters: Vec::new(),
             return_type: None,
             metadata: BTreeMap::new(),
+            confidence: confidence::CERTAIN,
         }
     }
 
+    /// Whether the adapter is less than certain of this declaration
+    pub fn is_uncertain(&self) -> bool {
+        !is_certain(&self.confidence)
+    }
+
     /// Create a unique identifier for this declaration
     pub fn id(&self) -> String {
         format!("{}:{}:{}", self.kind.as_str(), self.name, self.span.start_line)
@@ -347,6 +486,7 @@ impl Declaration {
 
 /// Kind of declaration
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum DeclarationKind {
     Function,
@@ -363,6 +503,10 @@ pub enum DeclarationKind {
     Namespace,
     Impl,
     Macro,
+    /// Markup landmark: element with an id, custom element, template block
+    Element,
+    /// Document section introduced by a heading
+    Section,
     Other,
 }
 
@@ -384,6 +528,8 @@ impl DeclarationKind {
             Self::Namespace => "namespace",
             Self::Impl => "impl",
             Self::Macro => "macro",
+            Self::Element => "element",
+            Self::Section => "section",
             Self::Other => "other",
         }
     }
@@ -391,6 +537,7 @@ impl DeclarationKind {
 
 /// Visibility of a declaration
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum Visibility {
     Public,
@@ -403,6 +550,7 @@ pub enum Visibility {
 
 /// A function/method parameter
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Parameter {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -418,6 +566,7 @@ pub struct Parameter {
 
 /// A code block (function body, if body, loop body, etc.)
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Block {
     /// The block's span
     pub span: Span,
@@ -445,6 +594,7 @@ pub struct Block {
 
 /// Control flow constructs
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ControlFlow {
     pub kind: ControlFlowKind,
     pub span: Span,
@@ -458,6 +608,7 @@ pub struct ControlFlow {
 
 /// Kind of control flow
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum ControlFlowKind {
     If,
@@ -480,6 +631,7 @@ pub enum ControlFlowKind {
 
 /// A function or method call
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Call {
     /// The callee expression (function name, method chain, etc.)
     pub callee: String,
@@ -501,6 +653,7 @@ pub struct Call {
 
 /// Import, require, include, using, or module reference
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ImportLike {
     /// What is being imported (module path, file, etc.)
     pub source: String,
@@ -525,6 +678,7 @@ pub struct ImportLike {
 
 /// Kind of import statement
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum ImportKind {
     /// import x from 'y' or import 'y'
@@ -550,6 +704,7 @@ pub enum ImportKind {
 
 /// A comment in source code
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Comment {
     /// The comment text (without delimiters)
     pub text: String,
@@ -567,6 +722,7 @@ pub struct Comment {
 
 /// Kind of comment
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum CommentKind {
     /// Single-line comment (// or #)
@@ -583,6 +739,7 @@ pub enum CommentKind {
 
 /// A region that couldn't be parsed or is syntactically invalid
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct UnknownNode {
     pub span: Span,
     /// Optional description of why this region is unknown
@@ -595,6 +752,7 @@ pub struct UnknownNode {
 
 /// An unparsed block (larger region with syntax errors)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct UnparsedBlock {
     pub span: Span,
     pub reason: String,
@@ -607,6 +765,7 @@ pub struct UnparsedBlock {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_language_from_extension() {
@@ -627,6 +786,64 @@ mod tests {
         assert!(!span.contains(5));
     }
 
+    #[test]
+    fn test_span_merge() {
+        let a = Span::new(10, 20, 2, 3);
+        let b = Span::new(15, 40, 3, 5);
+        let merged = a.merge(&b);
+        assert_eq!(merged, Span::new(10, 40, 2, 5));
+        assert!(merged.contains_span(&a) && merged.contains_span(&b));
+        assert!(!a.contains_span(&b));
+    }
+
+    /// Well-formed spans: `start <= end`, `start_line <= end_line`
+    fn arb_span() -> impl Strategy<Value = Span> {
+        (0usize..10_000, 0usize..1_000, 1usize..500, 0usize..100).prop_map(|(start, len, line, lines)| {
+            Span::new(start, start + len, line, line + lines)
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_span_len_and_empty(span in arb_span()) {
+            prop_assert_eq!(span.len(), span.end - span.start);
+            prop_assert_eq!(span.is_empty(), span.len() == 0);
+        }
+
+        #[test]
+        fn prop_span_contains_matches_bounds(span in arb_span(), offset in 0usize..12_000) {
+            prop_assert_eq!(span.contains(offset), offset >= span.start && offset < span.end);
+            prop_assert!(!span.contains(span.end), "end is exclusive");
+            prop_assert_eq!(span.contains(span.start), !span.is_empty());
+            // Counting the offsets it contains gives its length
+            let inside = (span.start.saturating_sub(2)..span.end + 2).filter(|o| span.contains(*o)).count();
+            prop_assert_eq!(inside, span.len());
+        }
+
+        #[test]
+        fn prop_span_contains_line_is_inclusive(span in arb_span(), line in 0usize..700) {
+            prop_assert!(span.contains_line(span.start_line));
+            prop_assert!(span.contains_line(span.end_line));
+            prop_assert_eq!(span.contains_line(line), line >= span.start_line && line <= span.end_line);
+        }
+
+        #[test]
+        fn prop_span_merge(a in arb_span(), b in arb_span()) {
+            let merged = a.merge(&b);
+            prop_assert_eq!(merged, b.merge(&a));
+            prop_assert_eq!(a.merge(&a), a);
+            prop_assert!(merged.contains_span(&a));
+            prop_assert!(merged.contains_span(&b));
+            prop_assert!(merged.len() >= a.len().max(b.len()));
+            prop_assert!(merged.len() <= a.len() + b.len() + a.start.abs_diff(b.start));
+            for offset in [a.start, b.start, a.end.saturating_sub(1), b.end.saturating_sub(1)] {
+                if a.contains(offset) || b.contains(offset) {
+                    prop_assert!(merged.contains(offset));
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_declaration_id() {
         let decl = Declaration::new(
@@ -637,6 +854,25 @@ mod tests {
         assert_eq!(decl.id(), "function:my_function:5");
     }
 
+    #[test]
+    fn test_span_violations() {
+        let mut file = File::new("test.rs".to_string(), LanguageId::Rust);
+        file.span = Span::new(0, 40, 1, 3);
+        let mut decl = Declaration::new("f".to_string(), DeclarationKind::Function, Span::new(0, 30, 1, 2));
+        decl.body_span = Some(Span::new(10, 50, 1, 3));
+        decl.children.push(Declaration::new(
+            "g".to_string(),
+            DeclarationKind::Function,
+            Span::new(20, 10, 3, 2),
+        ));
+        file.declarations.push(decl);
+
+        assert_eq!(file.span_violations(40).len(), 3, "{:?}", file.span_violations(40));
+        file.declarations.clear();
+        assert!(file.span_violations(40).is_empty());
+        assert_eq!(file.span_violations(10).len(), 1, "file span past end");
+    }
+
     #[test]
     fn test_file_serialization_deterministic() {
         let file = File::new("test.rs".to_string(), LanguageId::Rust);
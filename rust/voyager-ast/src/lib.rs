@@ -39,7 +39,16 @@ pub mod ir;
 pub mod error;
 pub mod provider;
 pub mod adapters;
+pub mod patterns;
+pub mod path;
+pub mod testmap;
+pub mod heuristic;
+pub mod related;
+#[cfg(feature = "schema")]
+pub mod schema;
 mod registry;
+mod context;
+mod snapshot;
 
 // Re-export core types for convenience
 pub use ir::{
@@ -61,11 +70,17 @@ pub use ir::{
 
 pub use error::AstError;
 pub use provider::{
-    AstProvider, IndexOptions, ZoomOptions,
+    AstProvider, IndexOptions, ParseCache, ZoomOptions,
     PlanetariumModel, MicroscopeModel, IndexStats, IndexError, ContextWindow,
 };
-pub use adapters::LanguageAdapter;
-pub use registry::AdapterRegistry;
+pub use adapters::{AdapterCapabilities, Capability, LanguageAdapter};
+pub use patterns::{PatternRule, PatternRules, normalize_match_path, DEFAULT_CASE_INSENSITIVE};
+pub use path::{RawPath, strip_verbatim_prefix, extended_length_path};
+pub use registry::{AdapterRegistry, TreeSitterProvider};
+pub use testmap::{TestEvidence, TestLink};
+pub use related::{RelatedSymbol, Relation};
+#[cfg(feature = "schema")]
+pub use schema::ir_schema;
 
 /// Version of the IR schema
 pub const IR_VERSION: &str = "v1";
@@ -0,0 +1,459 @@
+//! Lossless path handling
+//!
+//! File names are not guaranteed to be UTF-8: Unix allows arbitrary bytes and
+//! Windows allows unpaired UTF-16 surrogates. Converting them with
+//! `to_string_lossy` replaces the offending units with `U+FFFD`, after which
+//! the path no longer opens the file it came from.
+//!
+//! [`RawPath`] keeps the original `OsString` and serializes to a string *key*:
+//!
+//! - Valid UTF-8 paths serialize unchanged (with `/` separators), so JSON
+//!   output for ordinary projects is unaffected.
+//! - Anything else serializes as `%raw:` followed by the path with `%` escaped
+//!   as `%25`, invalid bytes as `%XX`, and unpaired surrogates as `%uXXXX`.
+//!
+//! [`RawPath::from_key`] reverses the encoding, so keys round-trip through
+//! JSON, MCP arguments, and map lookups.
+//!
+//! This module also handles Windows extended-length (`\\?\`) prefixes:
+//! [`strip_verbatim_prefix`] removes them for display and relative-path
+//! computation, and [`extended_length_path`] adds them when opening files
+//! whose absolute path exceeds `MAX_PATH`.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Prefix marking a key that carries escaped non-UTF-8 content
+pub const RAW_KEY_PREFIX: &str = "%raw:";
+
+/// Windows `MAX_PATH`; longer absolute paths need the `\\?\` prefix
+pub const WINDOWS_MAX_PATH: usize = 260;
+
+/// A path that preserves its original OS encoding
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RawPath(PathBuf);
+
+impl RawPath {
+    /// Wrap a path as-is
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self(path.into())
+    }
+
+    /// Path of `path` relative to `root`, ignoring `\\?\` prefixes on either side
+    ///
+    /// Falls back to `path` itself when it is not below `root`.
+    pub fn relative_to(path: &Path, root: &Path) -> Self {
+        let path = strip_verbatim_prefix(path);
+        let root = strip_verbatim_prefix(root);
+        Self(path.strip_prefix(&root).map(Path::to_path_buf).unwrap_or(path))
+    }
+
+    /// Decode a key produced by [`RawPath::to_key`]
+    ///
+    /// Strings without the `%raw:` prefix are taken literally. Escapes that
+    /// cannot be represented on the current platform (surrogates on Unix,
+    /// raw bytes on Windows) decode lossily.
+    pub fn from_key(key: &str) -> Self {
+        match key.strip_prefix(RAW_KEY_PREFIX) {
+            Some(escaped) => Self(PathBuf::from(decode_escaped(escaped))),
+            None => Self(PathBuf::from(key)),
+        }
+    }
+
+    /// Borrow the underlying path
+    pub fn as_path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Take the underlying path
+    pub fn into_path_buf(self) -> PathBuf {
+        self.0
+    }
+
+    /// The path as `&str`, if it is valid UTF-8
+    pub fn as_str(&self) -> Option<&str> {
+        self.0.to_str()
+    }
+
+    /// Check if the path is valid UTF-8
+    pub fn is_utf8(&self) -> bool {
+        self.as_str().is_some()
+    }
+
+    /// Lossless string key (see the [module docs](self))
+    pub fn to_key(&self) -> String {
+        let os = strip_verbatim_prefix(&self.0).into_os_string();
+        match os.to_str() {
+            Some(s) if !s.starts_with(RAW_KEY_PREFIX) => normalize_separators(s),
+            _ => format!("{}{}", RAW_KEY_PREFIX, encode_escaped(&os)),
+        }
+    }
+
+    /// Human-readable form with `/` separators; lossy for non-UTF-8 paths
+    pub fn to_string_lossy(&self) -> String {
+        normalize_separators(&strip_verbatim_prefix(&self.0).to_string_lossy())
+    }
+}
+
+impl fmt::Display for RawPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_string_lossy())
+    }
+}
+
+impl AsRef<Path> for RawPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl From<PathBuf> for RawPath {
+    fn from(path: PathBuf) -> Self {
+        Self(path)
+    }
+}
+
+impl From<&Path> for RawPath {
+    fn from(path: &Path) -> Self {
+        Self(path.to_path_buf())
+    }
+}
+
+impl From<OsString> for RawPath {
+    fn from(path: OsString) -> Self {
+        Self(PathBuf::from(path))
+    }
+}
+
+impl From<String> for RawPath {
+    fn from(key: String) -> Self {
+        Self::from_key(&key)
+    }
+}
+
+impl From<&str> for RawPath {
+    fn from(key: &str) -> Self {
+        Self::from_key(key)
+    }
+}
+
+impl PartialEq<str> for RawPath {
+    fn eq(&self, other: &str) -> bool {
+        self.to_key() == other
+    }
+}
+
+impl PartialEq<&str> for RawPath {
+    fn eq(&self, other: &&str) -> bool {
+        self.to_key() == *other
+    }
+}
+
+impl Serialize for RawPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_key())
+    }
+}
+
+impl<'de> Deserialize<'de> for RawPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        Ok(Self::from_key(&key))
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for RawPath {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "RawPath".into()
+    }
+
+    fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "type": "string",
+            "description": "Relative path key: the path itself when valid UTF-8, otherwise `%raw:` followed by the percent-escaped OS path"
+        })
+    }
+}
+
+/// Remove a Windows `\\?\` or `\\?\UNC\` prefix
+///
+/// `\\?\C:\repo` becomes `C:\repo` and `\\?\UNC\server\share` becomes
+/// `\\server\share`. Other paths (and all paths on other platforms, where the
+/// prefix never appears) are returned unchanged.
+pub fn strip_verbatim_prefix(path: &Path) -> PathBuf {
+    let Some(s) = path.to_str() else {
+        return path.to_path_buf();
+    };
+    if let Some(rest) = s.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", rest))
+    } else if let Some(rest) = s.strip_prefix(r"\\?\") {
+        PathBuf::from(rest)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Add the `\\?\` prefix to absolute Windows paths longer than `MAX_PATH`
+///
+/// Returns the path unchanged on other platforms, for relative paths, and
+/// for paths that are already prefixed.
+pub fn extended_length_path(path: &Path) -> PathBuf {
+    if !cfg!(windows) || !path.is_absolute() || path.as_os_str().len() < WINDOWS_MAX_PATH {
+        return path.to_path_buf();
+    }
+    let Some(s) = path.to_str() else {
+        return path.to_path_buf();
+    };
+    if s.starts_with(r"\\?\") {
+        return path.to_path_buf();
+    }
+    // Verbatim paths bypass normalization, so separators must be native
+    let s = s.replace('/', "\\");
+    match s.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+        None => PathBuf::from(format!(r"\\?\{}", s)),
+    }
+}
+
+fn normalize_separators(s: &str) -> String {
+    if cfg!(windows) {
+        s.replace('\\', "/")
+    } else {
+        s.to_string()
+    }
+}
+
+fn push_escaped_str(out: &mut String, s: &str) {
+    for c in s.chars() {
+        match c {
+            '%' => out.push_str("%25"),
+            '\\' if cfg!(windows) => out.push('/'),
+            _ => out.push(c),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn encode_escaped(os: &OsStr) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    let mut out = String::new();
+    let mut rest = os.as_bytes();
+    loop {
+        match std::str::from_utf8(rest) {
+            Ok(valid) => {
+                push_escaped_str(&mut out, valid);
+                return out;
+            }
+            Err(e) => {
+                let (valid, after) = rest.split_at(e.valid_up_to());
+                push_escaped_str(&mut out, std::str::from_utf8(valid).unwrap_or_default());
+                let bad = e.error_len().unwrap_or(after.len());
+                for byte in &after[..bad] {
+                    out.push_str(&format!("%{:02X}", byte));
+                }
+                rest = &after[bad..];
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+fn encode_escaped(os: &OsStr) -> String {
+    use std::os::windows::ffi::OsStrExt;
+
+    let mut out = String::new();
+    for unit in char::decode_utf16(os.encode_wide()) {
+        match unit {
+            Ok(c) => {
+                let mut buf = [0u8; 4];
+                push_escaped_str(&mut out, c.encode_utf8(&mut buf));
+            }
+            Err(e) => out.push_str(&format!("%u{:04X}", e.unpaired_surrogate())),
+        }
+    }
+    out
+}
+
+#[cfg(not(any(unix, windows)))]
+fn encode_escaped(os: &OsStr) -> String {
+    let mut out = String::new();
+    push_escaped_str(&mut out, &os.to_string_lossy());
+    out
+}
+
+/// One decoded unit of an escaped key
+enum Unit {
+    Byte(u8),
+    Wide(u16),
+}
+
+fn decode_units(escaped: &str) -> Vec<Unit> {
+    let bytes = escaped.as_bytes();
+    let hex = |range: std::ops::Range<usize>| {
+        bytes
+            .get(range)
+            .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u16::from_str_radix(h, 16).ok())
+    };
+
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            if bytes.get(i + 1) == Some(&b'u') {
+                if let Some(wide) = hex(i + 2..i + 6) {
+                    units.push(Unit::Wide(wide));
+                    i += 6;
+                    continue;
+                }
+            } else if let Some(byte) = hex(i + 1..i + 3) {
+                units.push(Unit::Byte(byte as u8));
+                i += 3;
+                continue;
+            }
+        }
+        units.push(Unit::Byte(bytes[i]));
+        i += 1;
+    }
+    units
+}
+
+#[cfg(unix)]
+fn decode_escaped(escaped: &str) -> OsString {
+    use std::os::unix::ffi::OsStringExt;
+
+    let mut bytes = Vec::with_capacity(escaped.len());
+    for unit in decode_units(escaped) {
+        match unit {
+            Unit::Byte(b) => bytes.push(b),
+            Unit::Wide(w) => {
+                let c = char::from_u32(u32::from(w)).unwrap_or(char::REPLACEMENT_CHARACTER);
+                bytes.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+        }
+    }
+    OsString::from_vec(bytes)
+}
+
+#[cfg(windows)]
+fn decode_escaped(escaped: &str) -> OsString {
+    use std::os::windows::ffi::OsStringExt;
+
+    let mut wide = Vec::with_capacity(escaped.len());
+    let mut pending = Vec::new();
+    for unit in decode_units(escaped) {
+        match unit {
+            Unit::Byte(b) => pending.push(b),
+            Unit::Wide(w) => {
+                wide.extend(String::from_utf8_lossy(&pending).encode_utf16());
+                pending.clear();
+                wide.push(w);
+            }
+        }
+    }
+    wide.extend(String::from_utf8_lossy(&pending).encode_utf16());
+    OsString::from_wide(&wide)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn decode_escaped(escaped: &str) -> OsString {
+    let bytes: Vec<u8> = decode_units(escaped)
+        .into_iter()
+        .map(|unit| match unit {
+            Unit::Byte(b) => b,
+            Unit::Wide(_) => b'?',
+        })
+        .collect();
+    OsString::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_key_is_unchanged() {
+        let path = RawPath::from("src/main.rs");
+        assert_eq!(path.to_key(), "src/main.rs");
+        assert_eq!(path.as_str(), Some("src/main.rs"));
+        assert_eq!(path, "src/main.rs");
+        assert_eq!(serde_json::to_string(&path).unwrap(), "\"src/main.rs\"");
+    }
+
+    #[test]
+    fn test_prefix_collision_is_escaped() {
+        let path = RawPath::new("%raw:odd%name");
+        let key = path.to_key();
+        assert_eq!(key, "%raw:%25raw:odd%25name");
+        assert_eq!(RawPath::from_key(&key), path);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_round_trip() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let raw = OsStr::from_bytes(b"src/caf\xe9.rs");
+        let path = RawPath::new(raw);
+        assert!(!path.is_utf8());
+
+        let key = path.to_key();
+        assert_eq!(key, "%raw:src/caf%E9.rs");
+        assert_eq!(RawPath::from_key(&key).as_path().as_os_str(), raw);
+
+        let json = serde_json::to_string(&path).unwrap();
+        let back: RawPath = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, path);
+        assert_eq!(path.to_string_lossy(), "src/caf\u{FFFD}.rs");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_map_keys() {
+        use std::collections::BTreeMap;
+        use std::os::unix::ffi::OsStrExt;
+
+        let mut map = BTreeMap::new();
+        map.insert(RawPath::new(OsStr::from_bytes(b"a\xff")), 1);
+        map.insert(RawPath::new(OsStr::from_bytes(b"a\xfe")), 2);
+        let json = serde_json::to_string(&map).unwrap();
+        let back: BTreeMap<RawPath, i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, map);
+    }
+
+    #[test]
+    fn test_strip_verbatim_prefix() {
+        assert_eq!(
+            strip_verbatim_prefix(Path::new(r"\\?\C:\repo\src")),
+            PathBuf::from(r"C:\repo\src")
+        );
+        assert_eq!(
+            strip_verbatim_prefix(Path::new(r"\\?\UNC\server\share\a")),
+            PathBuf::from(r"\\server\share\a")
+        );
+        assert_eq!(strip_verbatim_prefix(Path::new("/repo/src")), PathBuf::from("/repo/src"));
+    }
+
+    #[test]
+    fn test_relative_to() {
+        let rel = RawPath::relative_to(Path::new("/repo/src/lib.rs"), Path::new("/repo"));
+        assert_eq!(rel.to_key(), "src/lib.rs");
+        let outside = RawPath::relative_to(Path::new("/other/x.rs"), Path::new("/repo"));
+        assert_eq!(outside.to_key(), "/other/x.rs");
+    }
+
+    #[test]
+    fn test_extended_length_path() {
+        let short = Path::new("/repo/a.rs");
+        assert_eq!(extended_length_path(short), short.to_path_buf());
+        if !cfg!(windows) {
+            let long = PathBuf::from(format!("/{}", "a".repeat(400)));
+            assert_eq!(extended_length_path(&long), long);
+        }
+    }
+}
@@ -0,0 +1,353 @@
+//! Ordered path-pattern rules (gitignore-style, first match wins)
+//!
+//! Shared by every component that filters paths: the encoder walkers, lens
+//! include/exclude lists, and [`IndexOptions`](crate::IndexOptions).
+//!
+//! # Syntax
+//!
+//! - `*.pyc`, `__pycache__` - unanchored: matches the file name or any
+//!   directory component anywhere in the path
+//! - `docs/*.md`, `/build` - anchored (contains `/`): matched against the path
+//!   from the root
+//! - `build/` - directory-only: matches directories (and everything below
+//!   them), never a plain file named `build`
+//! - `!tests/fixtures/` - negation: a match counts as "not matched"
+//!
+//! Rules are evaluated in order and the **first** matching rule decides. To
+//! exclude tests except their fixtures, put the exception first:
+//! `["!tests/fixtures/", "tests/"]`.
+//!
+//! Matching is case-insensitive by default on Windows and case-sensitive
+//! elsewhere. On Windows, and whenever case-insensitive matching is on,
+//! backslashes in both patterns and paths are treated as separators, so
+//! Windows-style input matches the same files. Otherwise a backslash escapes
+//! the next character as in gitignore: `\#notes`, `\!keep` and `\*.log` match
+//! those literal names.
+
+use globset::{GlobBuilder, GlobMatcher};
+
+/// Whether matching ignores case unless configured otherwise
+pub const DEFAULT_CASE_INSENSITIVE: bool = cfg!(windows);
+
+/// Convert a path to the `/`-separated relative form rules are matched against
+///
+/// Backslashes are translated to `/` on Windows only.
+pub fn normalize_match_path(path: &str) -> String {
+    clean_match_path(path, cfg!(windows))
+}
+
+fn clean_match_path(path: &str, backslash_is_separator: bool) -> String {
+    let path = if backslash_is_separator {
+        path.replace('\\', "/")
+    } else {
+        path.to_string()
+    };
+    let mut path = path.as_str();
+    while let Some(rest) = path.strip_prefix("./") {
+        path = rest;
+    }
+    path.trim_end_matches('/').to_string()
+}
+
+/// A single parsed pattern rule
+#[derive(Debug, Clone)]
+pub struct PatternRule {
+    pattern: String,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+    case_insensitive: bool,
+    backslash_is_separator: bool,
+    /// Literal leading part of an anchored pattern (before any glob metachar)
+    literal_prefix: String,
+    matcher: GlobMatcher,
+}
+
+impl PatternRule {
+    /// Parse a rule; returns `None` for blank lines, comments, and invalid globs
+    pub fn parse(raw: &str) -> Option<Self> {
+        Self::parse_with_case(raw, DEFAULT_CASE_INSENSITIVE)
+    }
+
+    /// Parse a rule with explicit case sensitivity
+    ///
+    /// Case-insensitive rules also treat `\` as a separator, as on Windows.
+    pub fn parse_with_case(raw: &str, case_insensitive: bool) -> Option<Self> {
+        let backslash_is_separator = cfg!(windows) || case_insensitive;
+        let raw = raw.trim();
+        if raw.is_empty() || raw.starts_with('#') {
+            return None;
+        }
+
+        let (negated, body) = match raw.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, raw),
+        };
+        let body = if backslash_is_separator {
+            body.replace('\\', "/")
+        } else {
+            body.to_string()
+        };
+        let dir_only = body.ends_with('/');
+        let body = body.trim_end_matches('/');
+        let body = body.strip_prefix("./").unwrap_or(body);
+        let anchored = body.starts_with('/') || body.contains('/');
+        let body = body.trim_start_matches('/');
+        if body.is_empty() {
+            return None;
+        }
+
+        let matcher = GlobBuilder::new(body)
+            .case_insensitive(case_insensitive)
+            .backslash_escape(!backslash_is_separator)
+            .build()
+            .ok()?
+            .compile_matcher();
+        let literal_prefix = body
+            .find(['*', '?', '[', '{'])
+            .map_or(body, |i| &body[..i])
+            .to_string();
+        let literal_prefix = if case_insensitive {
+            literal_prefix.to_lowercase()
+        } else {
+            literal_prefix
+        };
+
+        Some(Self {
+            pattern: raw.to_string(),
+            negated,
+            dir_only,
+            anchored,
+            case_insensitive,
+            backslash_is_separator,
+            literal_prefix,
+            matcher,
+        })
+    }
+
+    /// The rule as written
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Whether the rule starts with `!`
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    /// Whether the rule ends with `/`
+    pub fn is_dir_only(&self) -> bool {
+        self.dir_only
+    }
+
+    /// Whether the rule is matched from the root rather than at any depth
+    pub fn is_anchored(&self) -> bool {
+        self.anchored
+    }
+
+    /// Whether the rule ignores case
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    /// Check if the rule's pattern matches `path` or any of its parent directories
+    ///
+    /// `path` is relative; a leading `./` is accepted, and `\` separators
+    /// where the rule treats them as such. Negation is not applied here.
+    pub fn matches(&self, path: &str, is_dir: bool) -> bool {
+        let path = clean_match_path(path, self.backslash_is_separator);
+        let path = path.as_str();
+        for (i, _) in path.match_indices('/') {
+            if self.matches_candidate(&path[..i], true) {
+                return true;
+            }
+        }
+        self.matches_candidate(path, is_dir)
+    }
+
+    fn matches_candidate(&self, candidate: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        if self.matcher.is_match(candidate) {
+            return true;
+        }
+        if !self.anchored {
+            let name = candidate.rsplit('/').next().unwrap_or(candidate);
+            return self.matcher.is_match(name);
+        }
+        false
+    }
+
+    /// Check if the rule could match something strictly below `dir`
+    fn may_match_below(&self, dir: &str) -> bool {
+        if !self.anchored {
+            return true;
+        }
+        let mut dir = format!("{}/", clean_match_path(dir, self.backslash_is_separator));
+        if self.case_insensitive {
+            dir = dir.to_lowercase();
+        }
+        self.literal_prefix.starts_with(&dir) || dir.starts_with(&self.literal_prefix)
+    }
+}
+
+/// An ordered list of pattern rules
+#[derive(Debug, Clone, Default)]
+pub struct PatternRules {
+    rules: Vec<PatternRule>,
+}
+
+impl PatternRules {
+    /// Parse rules in order, skipping blanks, comments, and invalid globs
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        Self::with_case(patterns, DEFAULT_CASE_INSENSITIVE)
+    }
+
+    /// Parse rules with explicit case sensitivity
+    pub fn with_case<S: AsRef<str>>(patterns: &[S], case_insensitive: bool) -> Self {
+        Self {
+            rules: patterns
+                .iter()
+                .filter_map(|p| PatternRule::parse_with_case(p.as_ref(), case_insensitive))
+                .collect(),
+        }
+    }
+
+    /// Parsed rules in evaluation order
+    pub fn rules(&self) -> &[PatternRule] {
+        &self.rules
+    }
+
+    /// Number of rules
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Check if there are no rules
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Evaluate `path` against the rules
+    ///
+    /// Returns `Some(true)` if the first matching rule is positive,
+    /// `Some(false)` if it is a negation, and `None` if no rule matches.
+    pub fn evaluate(&self, path: &str, is_dir: bool) -> Option<bool> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(path, is_dir))
+            .map(|rule| !rule.negated)
+    }
+
+    /// Check if `path` is matched (first matching rule is not a negation)
+    pub fn is_match(&self, path: &str, is_dir: bool) -> bool {
+        self.evaluate(path, is_dir) == Some(true)
+    }
+
+    /// Check if a walker may skip the directory `dir` entirely
+    ///
+    /// True only when the directory is matched and no negation rule could
+    /// re-include something beneath it.
+    pub fn can_prune(&self, dir: &str) -> bool {
+        self.is_match(dir, true)
+            && !self
+                .rules
+                .iter()
+                .any(|rule| rule.negated && rule.may_match_below(dir))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unanchored_matches_any_component() {
+        let rules = PatternRules::new(&["__pycache__", "*.pyc"]);
+        assert!(rules.is_match("__pycache__/a.py", false));
+        assert!(rules.is_match("src/__pycache__/a.py", false));
+        assert!(rules.is_match("src/cache.pyc", false));
+        assert!(!rules.is_match("src/pycache/a.py", false));
+    }
+
+    #[test]
+    fn test_anchored_matches_from_root() {
+        let rules = PatternRules::new(&["docs/*.md", "/build"]);
+        assert!(rules.is_match("docs/intro.md", false));
+        assert!(!rules.is_match("src/docs/intro.md", false));
+        assert!(rules.is_match("build/out.o", false));
+        assert!(!rules.is_match("src/build/out.o", false));
+    }
+
+    #[test]
+    fn test_directory_only() {
+        let rules = PatternRules::new(&["build/"]);
+        assert!(rules.is_match("build", true));
+        assert!(rules.is_match("build/out.o", false));
+        assert!(rules.is_match("src/build/out.o", false));
+        assert!(!rules.is_match("build", false), "plain file named build is kept");
+    }
+
+    #[test]
+    fn test_first_match_wins_with_negation() {
+        let rules = PatternRules::new(&["!tests/fixtures/", "tests/"]);
+        assert!(rules.is_match("tests/test_main.py", false));
+        assert!(!rules.is_match("tests/fixtures/data.json", false));
+        assert_eq!(rules.evaluate("tests/fixtures/data.json", false), Some(false));
+        assert_eq!(rules.evaluate("src/main.rs", false), None);
+
+        // Reversed order: the broad rule matches first
+        let rules = PatternRules::new(&["tests/", "!tests/fixtures/"]);
+        assert!(rules.is_match("tests/fixtures/data.json", false));
+    }
+
+    #[test]
+    fn test_can_prune_respects_negations() {
+        let rules = PatternRules::new(&["!tests/fixtures/", "tests/", "node_modules"]);
+        assert!(!rules.can_prune("tests"), "fixtures live below tests");
+        assert!(rules.can_prune("tests/unit"));
+        assert!(rules.can_prune("node_modules"));
+        assert!(!rules.can_prune("src"));
+    }
+
+    #[test]
+    fn test_skips_blank_comment_and_invalid() {
+        let rules = PatternRules::new(&["", "# comment", "[", "*.log"]);
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules.rules()[0].pattern(), "*.log");
+    }
+
+    #[test]
+    fn test_windows_separators() {
+        let rules = PatternRules::with_case(&["build\\", "docs\\*.md"], true);
+        assert!(rules.is_match("build\\out.o", false));
+        assert!(rules.is_match(".\\docs\\intro.md", false));
+        assert!(rules.is_match("./build/out.o", false));
+        assert!(!rules.is_match("src\\main.rs", false));
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_backslash_escapes_when_case_sensitive() {
+        let rules = PatternRules::with_case(&["\\#notes", "\\!keep", "\\*.log"], false);
+        assert!(rules.rules().iter().all(|rule| !rule.is_anchored() && !rule.is_negated()));
+        assert!(rules.is_match("#notes", false));
+        assert!(rules.is_match("docs/!keep", false));
+        assert!(rules.is_match("*.log", false));
+        assert!(!rules.is_match("debug.log", false));
+    }
+
+    #[test]
+    fn test_case_sensitivity() {
+        let sensitive = PatternRules::with_case(&["Build/", "*.MD"], false);
+        assert!(!sensitive.is_match("build/out.o", false));
+        assert!(!sensitive.is_match("readme.md", false));
+
+        let insensitive = PatternRules::with_case(&["!Tests/Fixtures/", "Build/", "*.MD", "tests/"], true);
+        assert!(insensitive.is_match("BUILD/out.o", false));
+        assert!(insensitive.is_match("README.md", false));
+        assert!(!insensitive.is_match("TESTS/fixtures/a.json", false));
+        assert!(!insensitive.can_prune("TESTS"));
+    }
+}
@@ -7,9 +7,14 @@
 
 use crate::error::{AstError, Result};
 use crate::ir::{Block, Declaration, File, LanguageId};
+use crate::path::RawPath;
+use crate::related::RelatedSymbol;
+use crate::testmap::TestLink;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::fmt;
 use std::path::Path;
+use std::sync::Arc;
 
 // ============================================================================
 // Options
@@ -38,6 +43,27 @@ pub struct IndexOptions {
 
     /// Whether to extract nested declarations in Index mode
     pub extract_nested: bool,
+
+    /// Reuse parse results for previously seen file contents
+    pub parse_cache: Option<Arc<dyn ParseCache>>,
+
+    /// Read every file before parsing any, so the model matches one capture
+    /// of the project; files edited during the scan are listed in
+    /// [`IndexStats::changed_during_scan`]
+    pub snapshot: bool,
+}
+
+/// Cache of parse results keyed by file content
+///
+/// Implementations must key on the language and the exact source text (and
+/// should include [`IR_VERSION`](crate::IR_VERSION)); the returned file's
+/// `path` is overwritten by the caller.
+pub trait ParseCache: Send + Sync + fmt::Debug {
+    /// Previously stored parse of `source`
+    fn get(&self, language: LanguageId, source: &str) -> Option<File>;
+
+    /// Store the parse of `source`
+    fn put(&self, language: LanguageId, source: &str, file: &File);
 }
 
 /// Options for symbol zoom (Microscope mode)
@@ -52,11 +78,18 @@ pub struct ZoomOptions {
     /// Whether to extract control flow structures
     pub extract_control_flow: bool,
 
-    /// Include surrounding context lines (before/after)
+    /// Include surrounding context lines (before/after), widened or narrowed
+    /// to syntax node boundaries
     pub context_lines: usize,
 
+    /// List the file's imports in the context window
+    pub include_imports: bool,
+
     /// Whether to include nested declarations
     pub extract_nested: bool,
+
+    /// Maximum related symbols to suggest (0 for none)
+    pub max_related: usize,
 }
 
 impl Default for ZoomOptions {
@@ -66,7 +99,9 @@ impl Default for ZoomOptions {
             extract_calls: true,
             extract_control_flow: true,
             context_lines: 0,
+            include_imports: false,
             extract_nested: true,
+            max_related: 8,
         }
     }
 }
@@ -77,12 +112,15 @@ impl Default for ZoomOptions {
 
 /// The result of indexing a project (Planetarium View)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct PlanetariumModel {
     /// Root path of the indexed project
     pub root: String,
 
     /// All indexed files, keyed by relative path (BTreeMap for determinism)
-    pub files: BTreeMap<String, File>,
+    ///
+    /// Keys keep the original OS encoding and serialize losslessly.
+    pub files: BTreeMap<RawPath, File>,
 
     /// Statistics about the indexing run
     pub stats: IndexStats,
@@ -104,14 +142,19 @@ impl PlanetariumModel {
     }
 
     /// Get all declarations across all files
-    pub fn all_declarations(&self) -> impl Iterator<Item = (&str, &Declaration)> {
+    pub fn all_declarations(&self) -> impl Iterator<Item = (&RawPath, &Declaration)> {
         self.files.iter().flat_map(|(path, file)| {
-            file.declarations.iter().map(move |d| (path.as_str(), d))
+            file.declarations.iter().map(move |d| (path, d))
         })
     }
 
+    /// Look up a file by its relative path key
+    pub fn file(&self, key: &str) -> Option<&File> {
+        self.files.get(&RawPath::from_key(key))
+    }
+
     /// Find declarations by name
-    pub fn find_by_name(&self, name: &str) -> Vec<(&str, &Declaration)> {
+    pub fn find_by_name(&self, name: &str) -> Vec<(&RawPath, &Declaration)> {
         self.all_declarations()
             .filter(|(_, d)| d.name == name)
             .collect()
@@ -121,10 +164,19 @@ impl PlanetariumModel {
     pub fn total_declarations(&self) -> usize {
         self.files.values().map(|f| f.total_declarations()).sum()
     }
+
+    /// Find the tests that exercise a symbol, strongest evidence first
+    ///
+    /// Links come from test names, calls in test bodies and imports of the
+    /// symbol by test files (see [`crate::testmap`]).
+    pub fn tests_for(&self, symbol: &str) -> Vec<TestLink> {
+        crate::testmap::tests_for(self.files.iter(), symbol)
+    }
 }
 
 /// Statistics from an indexing run
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct IndexStats {
     /// Number of files processed
     pub files_processed: usize,
@@ -147,10 +199,20 @@ pub struct IndexStats {
     /// Per-language statistics
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub by_language: BTreeMap<String, LanguageStats>,
+
+    /// Whether files were parsed from a snapshot taken at scan start
+    #[serde(default)]
+    pub snapshot: bool,
+
+    /// Files (relative paths) edited while a snapshot scan ran; the model
+    /// reflects their content at scan start
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed_during_scan: Vec<String>,
 }
 
 /// Per-language statistics
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LanguageStats {
     pub files: usize,
     pub declarations: usize,
@@ -159,6 +221,7 @@ pub struct LanguageStats {
 
 /// An error that occurred during indexing
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct IndexError {
     /// Path to the file that caused the error
     pub path: String,
@@ -176,6 +239,7 @@ pub struct IndexError {
 
 /// The result of zooming into a symbol (Microscope View)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MicroscopeModel {
     /// The file containing the symbol
     pub file_path: String,
@@ -194,16 +258,33 @@ pub struct MicroscopeModel {
     /// Source code of the symbol
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_text: Option<String>,
+
+    /// Declarations in the same file worth zooming into next
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related: Vec<RelatedSymbol>,
 }
 
 /// Surrounding context for a zoomed symbol
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ContextWindow {
     /// Lines before the symbol
     pub before: Vec<String>,
 
     /// Lines after the symbol
     pub after: Vec<String>,
+
+    /// First line of the window (1-indexed; `before` starts here)
+    #[serde(default)]
+    pub start_line: usize,
+
+    /// Last line of the window (1-indexed; `after` ends here)
+    #[serde(default)]
+    pub end_line: usize,
+
+    /// Import statements of the file outside the window (if requested)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<String>,
 }
 
 // ============================================================================
@@ -271,17 +352,35 @@ mod tests {
     fn test_planetarium_model_determinism() {
         let mut model = PlanetariumModel::new("/test");
         model.files.insert(
-            "a.rs".to_string(),
+            RawPath::from("a.rs"),
             File::new("a.rs".to_string(), LanguageId::Rust),
         );
         model.files.insert(
-            "b.py".to_string(),
+            RawPath::from("b.py"),
             File::new("b.py".to_string(), LanguageId::Python),
         );
 
         let json1 = serde_json::to_string(&model).unwrap();
         let json2 = serde_json::to_string(&model).unwrap();
         assert_eq!(json1, json2, "Model serialization must be deterministic");
+        assert!(model.file("b.py").is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_planetarium_model_non_utf8_keys() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let raw = RawPath::new(OsStr::from_bytes(b"caf\xe9.rs"));
+        let mut model = PlanetariumModel::new("/test");
+        model.files.insert(raw.clone(), File::new(raw.clone(), LanguageId::Rust));
+
+        let json = serde_json::to_string(&model).unwrap();
+        let back: PlanetariumModel = serde_json::from_str(&json).unwrap();
+        let (key, file) = back.files.iter().next().unwrap();
+        assert_eq!(key, &raw);
+        assert_eq!(file.path, raw);
     }
 
     #[test]
@@ -4,12 +4,16 @@
 //! for parsing files across languages.
 
 use crate::adapters::{
-    LanguageAdapter, PythonTreeSitterAdapter, RustTreeSitterAdapter, TypeScriptTreeSitterAdapter,
+    AdapterCapabilities, HtmlTreeSitterAdapter, JsonTreeSitterAdapter, LanguageAdapter, MarkdownTreeSitterAdapter, PythonTreeSitterAdapter,
+    RustTreeSitterAdapter, TypeScriptTreeSitterAdapter,
 };
 use crate::error::{AstError, Result};
+use crate::path::{extended_length_path, RawPath};
+use crate::patterns::PatternRules;
+use crate::snapshot::Snapshot;
 use crate::ir::{File, LanguageId, Span, UnknownNode};
 use crate::provider::{
-    AstProvider, IndexError, IndexOptions, IndexStats, LanguageStats, MicroscopeModel,
+    AstProvider, ContextWindow, IndexError, IndexOptions, IndexStats, LanguageStats, MicroscopeModel,
     PlanetariumModel, ZoomOptions,
 };
 use std::collections::BTreeMap;
@@ -36,6 +40,9 @@ impl AdapterRegistry {
         registry.register(Box::new(TypeScriptTreeSitterAdapter::javascript())); // .js, .mjs, .cjs
         // Note: JSX (.jsx) uses same JavaScript grammar but with different LanguageId
         // For now, JSX files will use JavaScript adapter
+        registry.register(Box::new(HtmlTreeSitterAdapter::new()));
+        registry.register(Box::new(MarkdownTreeSitterAdapter::new()));
+        registry.register(Box::new(JsonTreeSitterAdapter::new()));
 
         registry
     }
@@ -60,6 +67,44 @@ impl AdapterRegistry {
         self.adapters.contains_key(&language)
     }
 
+    /// What the adapter for a language extracts (`None`: no adapter)
+    pub fn capabilities(&self, language: LanguageId) -> Option<AdapterCapabilities> {
+        self.get(language).map(|adapter| adapter.capabilities())
+    }
+
+    /// Context around lines `start_line..=end_line` (1-indexed) of a source
+    ///
+    /// The window's ends are moved to syntax node boundaries so it never
+    /// cuts a statement, comment or string in half; languages without an
+    /// adapter get plain lines.
+    pub fn context_window(
+        &self,
+        source: &str,
+        language: LanguageId,
+        start_line: usize,
+        end_line: usize,
+        context_lines: usize,
+        include_imports: bool,
+    ) -> ContextWindow {
+        let tree = self.get(language).and_then(|adapter| {
+            let mut parser = tree_sitter::Parser::new();
+            parser.set_language(&adapter.tree_sitter_language()).ok()?;
+            parser.parse(adapter.parse_text(source).as_ref(), None).map(|tree| (adapter, tree))
+        });
+        let imports = match &tree {
+            Some((adapter, tree)) if include_imports => adapter.extract_imports(tree, source),
+            _ => Vec::new(),
+        };
+        crate::context::context_window(
+            source,
+            tree.as_ref().map(|(_, tree)| tree),
+            &imports,
+            start_line,
+            end_line,
+            context_lines,
+        )
+    }
+
     /// Parse a source file
     pub fn parse(&self, source: &str, language: LanguageId) -> Result<File> {
         let adapter = self
@@ -74,7 +119,7 @@ impl AdapterRegistry {
 
         // Parse source
         let tree = parser
-            .parse(source, None)
+            .parse(adapter.parse_text(source).as_ref(), None)
             .ok_or_else(|| AstError::parse_error("Failed to parse source"))?;
 
         // Extract file structure
@@ -83,13 +128,14 @@ impl AdapterRegistry {
             start: 0,
             end: source.len(),
             start_line: 1,
-            end_line: source.lines().count(),
+            end_line: source.lines().count().max(1), // An empty file still has one line
             start_column: 0,
             end_column: 0,
         };
 
         // Extract declarations
         file.declarations = adapter.extract_declarations(&tree, source);
+        crate::testmap::annotate_calls(adapter, &tree, source, &mut file.declarations);
 
         // Extract imports
         file.imports = adapter.extract_imports(&tree, source);
@@ -99,6 +145,7 @@ impl AdapterRegistry {
 
         // Extract error regions
         file.unknown_regions = adapter.extract_errors(&tree, source);
+        crate::adapters::apply_error_confidence(&mut file.declarations, &file.unknown_regions);
 
         Ok(file)
     }
@@ -152,6 +199,7 @@ impl AstProvider for TreeSitterProvider {
 
         // Collect files to process
         let files = self.collect_files(root, options)?;
+        let mut snapshot = options.snapshot.then(|| Snapshot::capture(&files));
 
         let mut stats = IndexStats::default();
 
@@ -160,7 +208,11 @@ impl AstProvider for TreeSitterProvider {
                 break;
             }
 
-            match self.process_file(&file_path, root, options) {
+            let source = match &mut snapshot {
+                Some(snapshot) => snapshot.take(&file_path),
+                None => std::fs::read_to_string(extended_length_path(&file_path)),
+            };
+            match self.process_file(&file_path, source, options) {
                 Ok(Some(file)) => {
                     // Update stats
                     stats.files_processed += 1;
@@ -178,12 +230,7 @@ impl AstProvider for TreeSitterProvider {
                     lang_stats.imports += file.imports.len();
 
                     // Store file
-                    let relative_path = file_path
-                        .strip_prefix(root)
-                        .unwrap_or(&file_path)
-                        .to_string_lossy()
-                        .to_string();
-                    model.files.insert(relative_path, file);
+                    model.files.insert(RawPath::relative_to(&file_path, root), file);
                 }
                 Ok(None) => {
                     stats.files_skipped += 1;
@@ -197,17 +244,21 @@ impl AstProvider for TreeSitterProvider {
 
                     // Still add partial results if available
                     if let Some(partial) = e.take_partial() {
-                        let relative_path = file_path
-                            .strip_prefix(root)
-                            .unwrap_or(&file_path)
-                            .to_string_lossy()
-                            .to_string();
-                        model.files.insert(relative_path, partial);
+                        model.files.insert(RawPath::relative_to(&file_path, root), partial);
                     }
                 }
             }
         }
 
+        if let Some(snapshot) = snapshot {
+            stats.snapshot = true;
+            stats.changed_during_scan = snapshot
+                .changed()
+                .into_iter()
+                .map(|path| RawPath::relative_to(path, root).to_key())
+                .collect();
+        }
+
         stats.parse_time_ms = start.elapsed().as_millis() as u64;
         model.stats = stats;
 
@@ -221,7 +272,7 @@ impl AstProvider for TreeSitterProvider {
         options: &ZoomOptions,
     ) -> Result<MicroscopeModel> {
         // Read the file
-        let source = std::fs::read_to_string(file_path)
+        let source = std::fs::read_to_string(extended_length_path(file_path))
             .map_err(|e| AstError::IoError(e.to_string()))?;
 
         // Detect language
@@ -263,7 +314,7 @@ impl AstProvider for TreeSitterProvider {
             .set_language(&adapter.tree_sitter_language())
             .map_err(|e| AstError::TreeSitterError(e.to_string()))?;
         let tree = parser
-            .parse(&source, None)
+            .parse(adapter.parse_text(&source).as_ref(), None)
             .ok_or_else(|| AstError::parse_error("Failed to parse for zoom"))?;
 
         let body = if options.extract_control_flow || options.extract_calls {
@@ -273,39 +324,33 @@ impl AstProvider for TreeSitterProvider {
         };
 
         // Extract context if requested
-        let context = if options.context_lines > 0 {
-            let lines: Vec<&str> = source.lines().collect();
-            let start_line = declaration.span.start_line.saturating_sub(1);
-            let end_line = declaration.span.end_line;
-
-            let before_start = start_line.saturating_sub(options.context_lines);
-            let after_end = (end_line + options.context_lines).min(lines.len());
-
-            Some(crate::provider::ContextWindow {
-                before: lines[before_start..start_line]
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect(),
-                after: lines[end_line..after_end]
-                    .iter()
-                    .map(|s| s.to_string())
-                    .collect(),
-            })
-        } else {
-            None
-        };
+        let context = (options.context_lines > 0 || options.include_imports).then(|| {
+            let imports = if options.include_imports { file.imports.as_slice() } else { &[] };
+            crate::context::context_window(
+                &source,
+                Some(&tree),
+                imports,
+                declaration.span.start_line,
+                declaration.span.end_line,
+                options.context_lines,
+            )
+        });
 
         // Extract source text
         let source_text = Some(
             source[declaration.span.start..declaration.span.end].to_string(),
         );
 
+        let path = RawPath::from(file_path);
+        let related = crate::related::related_symbols([(&path, &file, source.as_str())], &path, &declaration, options.max_related);
+
         Ok(MicroscopeModel {
             file_path: file_path.display().to_string(),
             symbol: declaration,
             body,
             context,
             source_text,
+            related,
         })
     }
 
@@ -314,13 +359,16 @@ impl AstProvider for TreeSitterProvider {
     }
 
     fn supported_languages(&self) -> &[LanguageId] {
-        // Core Fleet (Phase 1B): Rust, Python, TypeScript, TSX, JavaScript
+        // Core Fleet (Phase 1B): Rust, Python, TypeScript, TSX, JavaScript; markup: HTML, Markdown; data: JSON
         static LANGUAGES: &[LanguageId] = &[
             LanguageId::Rust,
             LanguageId::Python,
             LanguageId::TypeScript,
             LanguageId::Tsx,
             LanguageId::JavaScript,
+            LanguageId::Html,
+            LanguageId::Markdown,
+            LanguageId::Json,
         ];
         LANGUAGES
     }
@@ -332,11 +380,29 @@ impl TreeSitterProvider {
         use std::fs;
 
         let mut files = Vec::new();
+        let rules = PathRules {
+            root,
+            include: PatternRules::new(&options.include_patterns),
+            exclude: PatternRules::new(&options.exclude_patterns),
+        };
+
+        struct PathRules<'a> {
+            root: &'a Path,
+            include: PatternRules,
+            exclude: PatternRules,
+        }
+
+        impl PathRules<'_> {
+            fn relative(&self, path: &Path) -> String {
+                RawPath::relative_to(path, self.root).to_key()
+            }
+        }
 
         fn visit_dir(
             dir: &Path,
             files: &mut Vec<std::path::PathBuf>,
             options: &IndexOptions,
+            rules: &PathRules<'_>,
             registry: &AdapterRegistry,
         ) -> std::io::Result<()> {
             for entry in fs::read_dir(dir)? {
@@ -360,36 +426,27 @@ impl TreeSitterProvider {
                         continue;
                     }
 
+                    if rules.exclude.can_prune(&rules.relative(&path)) {
+                        continue;
+                    }
+
                     if options.follow_symlinks || !path.is_symlink() {
-                        visit_dir(&path, files, options, registry)?;
+                        visit_dir(&path, files, options, rules, registry)?;
                     }
                 } else if path.is_file() {
                     // Check if we support this file type
                     let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
                     let language = LanguageId::from_extension(ext);
 
-                    if registry.supports(language) {
-                        // Check include patterns
-                        if !options.include_patterns.is_empty() {
-                            // Simple glob matching (could use globset for full support)
-                            let path_str = path.to_string_lossy();
-                            let matches = options.include_patterns.iter().any(|p| {
-                                path_str.contains(p.trim_start_matches('*').trim_end_matches('*'))
-                            });
-                            if !matches {
-                                continue;
-                            }
+                    let wanted = options.languages.is_empty() || options.languages.contains(&language);
+                    if registry.supports(language) && wanted {
+                        // Include/exclude rules are matched on the root-relative path
+                        let relative = rules.relative(&path);
+                        if !rules.include.is_empty() && !rules.include.is_match(&relative, false) {
+                            continue;
                         }
-
-                        // Check exclude patterns
-                        if !options.exclude_patterns.is_empty() {
-                            let path_str = path.to_string_lossy();
-                            let excluded = options.exclude_patterns.iter().any(|p| {
-                                path_str.contains(p.trim_start_matches('*').trim_end_matches('*'))
-                            });
-                            if excluded {
-                                continue;
-                            }
+                        if rules.exclude.is_match(&relative, false) {
+                            continue;
                         }
 
                         files.push(path);
@@ -399,7 +456,7 @@ impl TreeSitterProvider {
             Ok(())
         }
 
-        visit_dir(root, &mut files, options, &self.registry)
+        visit_dir(root, &mut files, options, &rules, &self.registry)
             .map_err(|e| AstError::IoError(e.to_string()))?;
 
         // Sort for determinism
@@ -408,15 +465,14 @@ impl TreeSitterProvider {
         Ok(files)
     }
 
-    /// Process a single file
+    /// Process a single file, given the result of reading it
     fn process_file(
         &self,
         path: &Path,
-        _root: &Path,
-        _options: &IndexOptions,
+        source: std::io::Result<String>,
+        options: &IndexOptions,
     ) -> Result<Option<File>> {
-        // Read file
-        let source = match std::fs::read_to_string(path) {
+        let source = match source {
             Ok(s) => s,
             Err(e) => {
                 // Skip binary files silently
@@ -440,9 +496,19 @@ impl TreeSitterProvider {
             return Ok(None);
         }
 
-        // Parse
-        let mut file = self.registry.parse(&source, language)?;
-        file.path = path.display().to_string();
+        // Parse, reusing a cached result for identical content
+        let cache = options.parse_cache.as_deref();
+        let mut file = match cache.and_then(|c| c.get(language, &source)) {
+            Some(file) => file,
+            None => {
+                let file = self.registry.parse(&source, language)?;
+                if let Some(cache) = cache {
+                    cache.put(language, &source, &file);
+                }
+                file
+            }
+        };
+        file.path = RawPath::new(path);
 
         Ok(Some(file))
     }
@@ -461,9 +527,27 @@ mod tests {
         assert!(registry.supports(LanguageId::TypeScript));
         assert!(registry.supports(LanguageId::Tsx));
         assert!(registry.supports(LanguageId::JavaScript));
+        assert!(registry.supports(LanguageId::Html));
+        assert!(registry.supports(LanguageId::Markdown));
         assert!(!registry.supports(LanguageId::Unknown));
     }
 
+    #[test]
+    fn test_adapter_capabilities() {
+        use crate::adapters::Capability;
+
+        let registry = AdapterRegistry::new();
+        let rust = registry.capabilities(LanguageId::Rust).unwrap();
+        assert!(rust.supports(Capability::Calls) && rust.supports(Capability::DocComments));
+        assert_eq!(rust.missing().count(), 0);
+
+        let markdown = registry.capabilities(LanguageId::Markdown).unwrap();
+        assert!(markdown.supports(Capability::Nesting));
+        assert!(!markdown.supports(Capability::Calls));
+        assert_eq!(serde_json::to_string(&markdown).unwrap(), r#"["declarations","nesting"]"#);
+        assert!(registry.capabilities(LanguageId::Go).is_none());
+    }
+
     #[test]
     fn test_provider_parse_rust() {
         let provider = TreeSitterProvider::new();
@@ -546,4 +630,123 @@ class Calculator {
         let result = provider.parse_file("some code", LanguageId::Unknown);
         assert!(matches!(result, Err(AstError::UnsupportedLanguage(_))));
     }
+
+    /// Cheap in-tree version of the fuzz targets: every prefix of a real
+    /// source (mostly invalid syntax) must parse without panicking and yield
+    /// in-bounds spans
+    #[test]
+    fn test_truncated_sources_keep_span_invariants() {
+        let provider = TreeSitterProvider::new();
+        let samples = [
+            (LanguageId::Rust, "/// Doc\npub struct P { x: i32 }\nimpl P { pub fn new(x: i32) -> Self { Self { x } } }\n"),
+            (LanguageId::Python, "class A:\n    \"\"\"Doc\"\"\"\n    def f(self, x=1):\n        return x\n"),
+            (LanguageId::TypeScript, "export interface U { id: number }\nexport class R { add(u: U): void {} }\n"),
+            (LanguageId::JavaScript, "class C { m() { return 1; } }\nfunction f(a) { return a.map(x => x); }\n"),
+        ];
+
+        for (language, source) in samples {
+            for end in (0..=source.len()).filter(|i| source.is_char_boundary(*i)) {
+                let prefix = &source[..end];
+                let file = match provider.parse_file(prefix, language) {
+                    Ok(file) => file,
+                    Err(e) => match e.take_partial() {
+                        Some(file) => file,
+                        None => continue,
+                    },
+                };
+                let violations = file.span_violations(prefix.len());
+                assert!(violations.is_empty(), "{:?} prefix {:?}: {:?}", language, prefix, violations);
+            }
+        }
+    }
+
+    #[test]
+    fn test_collect_files_ordered_exclude_rules() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("tests/fixtures")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn a() {}").unwrap();
+        std::fs::write(root.join("tests/it.rs"), "fn b() {}").unwrap();
+        std::fs::write(root.join("tests/fixtures/sample.rs"), "fn c() {}").unwrap();
+
+        let provider = TreeSitterProvider::new();
+        let options = IndexOptions {
+            exclude_patterns: vec!["!tests/fixtures/".to_string(), "tests/".to_string()],
+            ..Default::default()
+        };
+        let files = provider.collect_files(root, &options).unwrap();
+        let relative: Vec<String> = files
+            .iter()
+            .map(|p| p.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        assert_eq!(relative, vec!["src/lib.rs", "tests/fixtures/sample.rs"]);
+    }
+
+    #[test]
+    fn test_index_project_from_snapshot() {
+        let temp = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp.path().join("lib.rs"), "fn a() {}").unwrap();
+
+        let options = IndexOptions { snapshot: true, ..Default::default() };
+        let model = TreeSitterProvider::new().index_project(temp.path(), &options).unwrap();
+        assert!(model.stats.snapshot);
+        assert!(model.stats.changed_during_scan.is_empty());
+        assert_eq!(model.total_declarations(), 1);
+    }
+
+    #[test]
+    fn test_collect_files_language_filter() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        std::fs::write(root.join("lib.rs"), "fn a() {}").unwrap();
+        std::fs::write(root.join("app.py"), "def b(): pass").unwrap();
+
+        let provider = TreeSitterProvider::new();
+        let options = IndexOptions {
+            languages: vec![LanguageId::Python],
+            ..Default::default()
+        };
+        let files = provider.collect_files(root, &options).unwrap();
+        assert_eq!(files, vec![root.join("app.py")]);
+    }
+    #[test]
+    fn test_index_uses_parse_cache() {
+        use crate::provider::ParseCache;
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Debug, Default)]
+        struct MapCache {
+            files: Mutex<std::collections::HashMap<String, File>>,
+            hits: Mutex<usize>,
+        }
+        impl ParseCache for MapCache {
+            fn get(&self, _language: LanguageId, source: &str) -> Option<File> {
+                let file = self.files.lock().unwrap().get(source).cloned();
+                if file.is_some() {
+                    *self.hits.lock().unwrap() += 1;
+                }
+                file
+            }
+            fn put(&self, _language: LanguageId, source: &str, file: &File) {
+                self.files.lock().unwrap().insert(source.to_string(), file.clone());
+            }
+        }
+
+        let temp = tempfile::TempDir::new().unwrap();
+        let root = temp.path();
+        // Identical content in two files (e.g. two worktrees) is parsed once
+        std::fs::write(root.join("a.rs"), "fn shared() {}").unwrap();
+        std::fs::write(root.join("b.rs"), "fn shared() {}").unwrap();
+
+        let cache = Arc::new(MapCache::default());
+        let options = IndexOptions {
+            parse_cache: Some(cache.clone()),
+            ..Default::default()
+        };
+        let model = TreeSitterProvider::new().index_project(root, &options).unwrap();
+        assert_eq!(model.files.len(), 2);
+        assert_eq!(*cache.hits.lock().unwrap(), 1);
+        assert!(model.files.keys().any(|k| k.to_key() == "b.rs"), "cached files get their own path");
+    }
 }
@@ -0,0 +1,223 @@
+//! Related Symbols
+//!
+//! A shortlist of declarations worth zooming into next from a zoomed
+//! symbol, so the model can navigate outward without another index query.
+//! In order of priority:
+//!
+//! - **Callees**: declarations the symbol's body calls (`name(` or `.name(`)
+//! - **Callers**: functions and methods whose body calls the symbol
+//! - **Signature types**: declared types named in its parameter and return types
+//! - **Siblings**: declarations next to it in the same file, nearest first
+//!
+//! Calls are found in the source text rather than the parse tree, so every
+//! language (including heuristically indexed ones) gets the same treatment;
+//! a name shared by several declarations matches all of them.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ir::{Declaration, DeclarationKind, File, Span};
+use crate::path::RawPath;
+
+/// How a related declaration relates to the zoomed symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Relation {
+    /// Called by the symbol
+    Callee,
+    /// Calls the symbol
+    Caller,
+    /// Named in the symbol's signature
+    SignatureType,
+    /// Declared next to the symbol
+    Sibling,
+}
+
+impl Relation {
+    /// Short label for rendering (`calls `x``, `called by `y``)
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Callee => "calls",
+            Self::Caller => "called by",
+            Self::SignatureType => "signature type",
+            Self::Sibling => "sibling",
+        }
+    }
+}
+
+/// A lightweight reference to a declaration related to a zoomed symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RelatedSymbol {
+    /// File declaring it
+    pub path: String,
+    pub name: String,
+    pub kind: DeclarationKind,
+    pub relation: Relation,
+    pub span: Span,
+}
+
+/// Up to `limit` declarations related to `symbol`, declared in the file at
+/// `path` among `files` (path, parsed file, source)
+pub fn related_symbols<'a>(
+    files: impl IntoIterator<Item = (&'a RawPath, &'a File, &'a str)>,
+    path: &RawPath,
+    symbol: &Declaration,
+    limit: usize,
+) -> Vec<RelatedSymbol> {
+    let files: Vec<(&RawPath, &File, &str)> = files.into_iter().collect();
+    let mut declared: Vec<(&RawPath, &Declaration, &str)> = Vec::new();
+    for &(file_path, file, source) in &files {
+        flatten(&file.declarations, &mut |decl| declared.push((file_path, decl, source)));
+    }
+    let is_symbol = |p: &RawPath, d: &Declaration| p == path && d.span == symbol.span && d.name == symbol.name;
+    let body = files
+        .iter()
+        .find(|(p, _, _)| *p == path)
+        .and_then(|(_, _, source)| source.get(symbol.span.start..symbol.span.end))
+        .unwrap_or("");
+
+    let mut related = Vec::new();
+    let mut push = |p: &RawPath, d: &Declaration, relation: Relation| {
+        related.push(RelatedSymbol {
+            path: p.to_key(),
+            name: d.name.clone(),
+            kind: d.kind,
+            relation,
+            span: d.span,
+        });
+    };
+
+    let called = called_names(body);
+    for &(p, d, _) in &declared {
+        if is_callable(d.kind) && called.contains(d.name.as_str()) && !is_symbol(p, d) {
+            push(p, d, Relation::Callee);
+        }
+    }
+    for &(p, d, source) in &declared {
+        let text = source.get(d.span.start..d.span.end).unwrap_or("");
+        if matches!(d.kind, DeclarationKind::Function | DeclarationKind::Method)
+            && !is_symbol(p, d)
+            && called_names(text).contains(symbol.name.as_str())
+        {
+            push(p, d, Relation::Caller);
+        }
+    }
+    let signature = signature_names(symbol);
+    for &(p, d, _) in &declared {
+        if is_type(d.kind) && signature.contains(d.name.as_str()) {
+            push(p, d, Relation::SignatureType);
+        }
+    }
+    if let Some(siblings) = files.iter().find(|(p, _, _)| *p == path).and_then(|(_, f, _)| siblings_of(&f.declarations, symbol)) {
+        let position = siblings.iter().position(|d| d.span == symbol.span).unwrap_or(0);
+        let mut nearest: Vec<&Declaration> = siblings.iter().filter(|d| d.span != symbol.span).collect();
+        nearest.sort_by_key(|d| {
+            let index = siblings.iter().position(|s| s.span == d.span).unwrap_or(0);
+            index.abs_diff(position)
+        });
+        for d in nearest {
+            push(path, d, Relation::Sibling);
+        }
+    }
+
+    // A declaration is listed once, under its first relation
+    let mut seen = HashSet::new();
+    related.retain(|r| seen.insert((r.path.clone(), r.span.start, r.name.clone())));
+    related.truncate(limit);
+    related
+}
+
+/// Visit declarations depth-first
+fn flatten<'a>(decls: &'a [Declaration], visit: &mut impl FnMut(&'a Declaration)) {
+    for decl in decls {
+        visit(decl);
+        flatten(&decl.children, visit);
+    }
+}
+
+/// The declaration list holding `symbol`
+fn siblings_of<'a>(decls: &'a [Declaration], symbol: &Declaration) -> Option<&'a [Declaration]> {
+    if decls.iter().any(|d| d.span == symbol.span && d.name == symbol.name) {
+        return Some(decls);
+    }
+    decls.iter().find_map(|d| siblings_of(&d.children, symbol))
+}
+
+fn is_callable(kind: DeclarationKind) -> bool {
+    matches!(
+        kind,
+        DeclarationKind::Function | DeclarationKind::Method | DeclarationKind::Class | DeclarationKind::Struct | DeclarationKind::Macro
+    )
+}
+
+fn is_type(kind: DeclarationKind) -> bool {
+    matches!(
+        kind,
+        DeclarationKind::Class
+            | DeclarationKind::Struct
+            | DeclarationKind::Enum
+            | DeclarationKind::Interface
+            | DeclarationKind::Trait
+            | DeclarationKind::Type
+    )
+}
+
+/// Identifiers directly followed by `(` (or `!(` for macros)
+fn called_names(text: &str) -> HashSet<&str> {
+    let mut names = HashSet::new();
+    for (index, _) in text.match_indices('(') {
+        let head = text[..index].trim_end_matches('!');
+        let start = head.rfind(|c: char| !is_identifier(c)).map_or(0, |i| i + 1);
+        let name = &head[start..];
+        if name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            names.insert(name);
+        }
+    }
+    names
+}
+
+/// Identifiers in the parameter and return types of a declaration
+fn signature_names(decl: &Declaration) -> HashSet<&str> {
+    decl.parameters
+        .iter()
+        .filter_map(|p| p.type_annotation.as_deref())
+        .chain(decl.return_type.as_deref())
+        .flat_map(|ty| ty.split(|c: char| !is_identifier(c)))
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn is_identifier(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AdapterRegistry, LanguageId};
+
+    #[test]
+    fn test_related_symbols_of_a_function() {
+        let source = "struct Budget { limit: usize }\n\nfn trim(text: &str) -> String {\n    text.trim().to_string()\n}\n\nfn apply(budget: &Budget, text: &str) -> String {\n    trim(text)\n}\n\nfn run() {\n    apply(&Budget { limit: 1 }, \"x\");\n}\n\nfn unrelated() {}\n";
+        let file = AdapterRegistry::new().parse(source, LanguageId::Rust).unwrap();
+        let path = RawPath::from_key("src/lib.rs");
+        let apply = file.declarations.iter().find(|d| d.name == "apply").unwrap();
+
+        let related = related_symbols([(&path, &file, source)], &path, apply, 8);
+        let pairs: Vec<(&str, Relation)> = related.iter().map(|r| (r.name.as_str(), r.relation)).collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("trim", Relation::Callee),
+                ("run", Relation::Caller),
+                ("Budget", Relation::SignatureType),
+                ("unrelated", Relation::Sibling),
+            ]
+        );
+        assert_eq!(related[0].path, "src/lib.rs");
+        assert_eq!(related_symbols([(&path, &file, source)], &path, apply, 2).len(), 2);
+    }
+}
@@ -0,0 +1,84 @@
+//! JSON Schema for the IR (requires the `schema` feature)
+//!
+//! Non-Rust consumers validate and generate types against this schema, so it
+//! is derived from the same types that serialize the IR: a field added to
+//! [`File`] shows up here without a separate hand-maintained definition.
+//!
+//! The root schema accepts any of the four top-level documents the crate
+//! emits ([`File`], [`Declaration`], [`PlanetariumModel`],
+//! [`MicroscopeModel`]); each one is also available under `$defs`.
+
+use schemars::JsonSchema;
+use serde_json::{json, Value};
+
+use crate::ir::{Declaration, File};
+use crate::provider::{MicroscopeModel, PlanetariumModel};
+use crate::IR_VERSION;
+
+/// Top-level IR documents
+#[derive(JsonSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+enum IrDocument {
+    File(File),
+    Declaration(Declaration),
+    PlanetariumModel(PlanetariumModel),
+    MicroscopeModel(Box<MicroscopeModel>),
+}
+
+/// `$id` of the generated schema
+pub fn ir_schema_id() -> String {
+    format!("https://voyager-observatory.dev/schema/ir/{}.json", IR_VERSION)
+}
+
+/// Generate the canonical JSON Schema (draft 2020-12) for the IR
+pub fn ir_schema() -> Value {
+    let mut schema = serde_json::to_value(schemars::schema_for!(IrDocument)).unwrap_or(Value::Null);
+    if let Value::Object(root) = &mut schema {
+        root.insert("$id".to_string(), json!(ir_schema_id()));
+        root.insert("title".to_string(), json!(format!("voyager-ast IR {}", IR_VERSION)));
+        root.insert(
+            "description".to_string(),
+            json!("A File, Declaration, PlanetariumModel (index), or MicroscopeModel (zoom) as emitted by voyager-ast"),
+        );
+    }
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{DeclarationKind, LanguageId, Span};
+
+    #[test]
+    fn test_schema_defines_top_level_types() {
+        let schema = ir_schema();
+        assert_eq!(schema["$id"], ir_schema_id());
+        let defs = schema["$defs"].as_object().expect("schema has $defs");
+        for name in ["File", "Declaration", "PlanetariumModel", "MicroscopeModel", "Span", "RawPath"] {
+            assert!(defs.contains_key(name), "missing $defs/{}", name);
+        }
+        assert_eq!(schema["anyOf"].as_array().map(Vec::len), Some(4));
+    }
+
+    #[test]
+    fn test_schema_matches_serialized_fields() {
+        let schema = ir_schema();
+        let mut file = File::new("src/lib.rs", LanguageId::Rust);
+        file.declarations.push(Declaration::new(
+            "main".to_string(),
+            DeclarationKind::Function,
+            Span::new(0, 10, 1, 1),
+        ));
+
+        // Every field the IR emits must be declared in the schema
+        let properties = schema["$defs"]["File"]["properties"].as_object().unwrap();
+        for key in serde_json::to_value(&file).unwrap().as_object().unwrap().keys() {
+            assert!(properties.contains_key(key), "File.{} missing from schema", key);
+        }
+        let properties = schema["$defs"]["Declaration"]["properties"].as_object().unwrap();
+        for key in serde_json::to_value(&file.declarations[0]).unwrap().as_object().unwrap().keys() {
+            assert!(properties.contains_key(key), "Declaration.{} missing from schema", key);
+        }
+    }
+}
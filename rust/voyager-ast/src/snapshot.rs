@@ -0,0 +1,96 @@
+//! Scan Snapshots
+//!
+//! A long indexing run reads files one by one while they may still be
+//! edited, so spans parsed early can disagree with bytes written later. In
+//! snapshot mode every file is read and hashed before any is parsed, parsing
+//! works on the captured bytes only, and the files are hashed again once the
+//! scan is done: the model is consistent with one capture, and files edited
+//! in the meantime are reported in
+//! [`IndexStats::changed_during_scan`](crate::IndexStats::changed_during_scan).
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::path::extended_length_path;
+
+/// File contents captured at scan start
+pub(crate) struct Snapshot {
+    files: BTreeMap<PathBuf, Captured>,
+}
+
+struct Captured {
+    /// Hash of the captured bytes (`None`: unreadable)
+    fingerprint: Option<u64>,
+    /// Captured text, until parsed
+    source: Option<io::Result<String>>,
+}
+
+impl Snapshot {
+    /// Read and fingerprint every file
+    pub(crate) fn capture(paths: &[PathBuf]) -> Self {
+        let files = paths
+            .iter()
+            .map(|path| {
+                let bytes = std::fs::read(extended_length_path(path));
+                let fingerprint = bytes.as_deref().ok().map(fingerprint);
+                let source = bytes.and_then(|bytes| {
+                    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+                });
+                (path.clone(), Captured { fingerprint, source: Some(source) })
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// Captured text of a file, as `std::fs::read_to_string` would return it
+    ///
+    /// Each file's text is handed out once.
+    pub(crate) fn take(&mut self, path: &Path) -> io::Result<String> {
+        self.files
+            .get_mut(path)
+            .and_then(|captured| captured.source.take())
+            .unwrap_or_else(|| Err(io::Error::new(io::ErrorKind::NotFound, "file not in snapshot")))
+    }
+
+    /// Files whose current content differs from the capture (including
+    /// files deleted or made unreadable since)
+    pub(crate) fn changed(&self) -> Vec<&Path> {
+        self.files
+            .iter()
+            .filter(|(path, captured)| {
+                let now = std::fs::read(extended_length_path(path)).ok().map(|bytes| fingerprint(&bytes));
+                now != captured.fingerprint
+            })
+            .map(|(path, _)| path.as_path())
+            .collect()
+    }
+}
+
+fn fingerprint(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_serves_captured_content() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let (edited, stable) = (temp.path().join("a.rs"), temp.path().join("b.rs"));
+        std::fs::write(&edited, "fn a() {}\n").unwrap();
+        std::fs::write(&stable, "fn b() {}\n").unwrap();
+
+        let mut snapshot = Snapshot::capture(&[edited.clone(), stable.clone()]);
+        std::fs::write(&edited, "fn a_renamed() {}\n").unwrap();
+
+        assert_eq!(snapshot.take(&edited).unwrap(), "fn a() {}\n");
+        assert!(snapshot.take(&edited).is_err(), "handed out once");
+        assert_eq!(snapshot.changed(), vec![edited.as_path()]);
+    }
+}
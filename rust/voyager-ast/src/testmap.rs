@@ -0,0 +1,209 @@
+//! Test-to-Subject Mapping
+//!
+//! Heuristics linking test declarations to the production symbols they
+//! exercise. A test is linked to a symbol by any of:
+//!
+//! - **Name**: `test_apply_budget`, `test_apply_budget_zero` and
+//!   `TestApplyBudget` all name `apply_budget` / `ApplyBudget`
+//! - **Call**: the test body calls the symbol (recorded at parse time in the
+//!   `calls` metadata of test declarations)
+//! - **Import**: the test file imports the symbol by name
+//!
+//! Exposed through [`PlanetariumModel::tests_for`](crate::PlanetariumModel::tests_for).
+
+use crate::adapters::LanguageAdapter;
+use crate::ir::{Declaration, DeclarationKind, File, Span};
+use crate::path::RawPath;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+/// Metadata key holding the callees of a test declaration (comma-separated)
+pub const CALLS_METADATA: &str = "calls";
+
+/// Why a test was linked to a symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum TestEvidence {
+    /// The test body calls the symbol
+    Call,
+    /// The test name names the symbol
+    Name,
+    /// The test file imports the symbol
+    Import,
+}
+
+/// A test that exercises a symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TestLink {
+    /// File containing the test
+    pub path: RawPath,
+    /// Name of the test declaration
+    pub test: String,
+    /// Span of the test declaration
+    pub span: Span,
+    /// Evidence for the link, strongest first
+    pub evidence: Vec<TestEvidence>,
+}
+
+/// Check whether a path looks like a test file or lives in a test directory
+pub fn is_test_path(path: &str) -> bool {
+    let lower = path.replace('\\', "/").to_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or(&lower);
+    let stem = name.split('.').next().unwrap_or(name);
+    lower.split('/').any(|part| matches!(part, "test" | "tests" | "__tests__" | "spec"))
+        || stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+}
+
+/// Check whether a declaration is a test function (`test_x`, `TestX`, `testX`)
+pub fn is_test_declaration(decl: &Declaration) -> bool {
+    matches!(decl.kind, DeclarationKind::Function | DeclarationKind::Method) && subject_name(&decl.name).is_some()
+}
+
+/// The part of a test name after its `test` prefix
+fn subject_name(test: &str) -> Option<&str> {
+    let rest = test.strip_prefix("test_").or_else(|| {
+        let rest = test.strip_prefix("test").or_else(|| test.strip_prefix("Test"))?;
+        rest.starts_with(|c: char| c.is_ascii_uppercase()).then_some(rest)
+    })?;
+    (!rest.is_empty()).then_some(rest)
+}
+
+/// Record the callees of every test declaration in its `calls` metadata
+pub(crate) fn annotate_calls(
+    adapter: &dyn LanguageAdapter,
+    tree: &tree_sitter::Tree,
+    source: &str,
+    decls: &mut [Declaration],
+) {
+    for decl in decls {
+        if is_test_declaration(decl) {
+            if let Some(body) = adapter.extract_body(tree, source, decl) {
+                let callees: BTreeSet<&str> = body.calls.iter().map(|c| last_segment(&c.callee)).collect();
+                if !callees.is_empty() {
+                    let callees: Vec<&str> = callees.into_iter().collect();
+                    decl.metadata.insert(CALLS_METADATA.to_string(), callees.join(","));
+                }
+            }
+        }
+        annotate_calls(adapter, tree, source, &mut decl.children);
+    }
+}
+
+/// Find the tests in `files` that exercise `symbol`
+pub(crate) fn tests_for<'a>(files: impl Iterator<Item = (&'a RawPath, &'a File)>, symbol: &str) -> Vec<TestLink> {
+    let symbol = last_segment(symbol);
+    let mut links = Vec::new();
+    for (path, file) in files {
+        let imported = file.imports.iter().any(|import| {
+            import.items.iter().any(|item| item == symbol) || last_segment(&import.source) == symbol
+        });
+        let test_file = is_test_path(&path.to_string_lossy());
+        collect_tests(&file.declarations, test_file, &mut |decl| {
+            let mut evidence = Vec::new();
+            let calls = decl.metadata.get(CALLS_METADATA).map(String::as_str).unwrap_or("");
+            if calls.split(',').any(|callee| callee == symbol) {
+                evidence.push(TestEvidence::Call);
+            }
+            if subject_name(&decl.name).is_some_and(|subject| names_symbol(subject, symbol)) {
+                evidence.push(TestEvidence::Name);
+            }
+            if imported {
+                evidence.push(TestEvidence::Import);
+            }
+            if !evidence.is_empty() {
+                links.push(TestLink {
+                    path: path.clone(),
+                    test: decl.name.clone(),
+                    span: decl.span,
+                    evidence,
+                });
+            }
+        });
+    }
+    // Most evidence first, then document order
+    links.sort_by(|a, b| {
+        b.evidence
+            .len()
+            .cmp(&a.evidence.len())
+            .then_with(|| a.evidence.cmp(&b.evidence))
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.span.start.cmp(&b.span.start))
+    });
+    links
+}
+
+/// Visit test declarations; outside test files only `test`-prefixed
+/// functions nested in a test module count
+fn collect_tests<'a>(decls: &'a [Declaration], test_scope: bool, visit: &mut impl FnMut(&'a Declaration)) {
+    for decl in decls {
+        if test_scope && is_test_declaration(decl) {
+            visit(decl);
+        }
+        let nested_scope = test_scope || (decl.kind == DeclarationKind::Module && decl.name.starts_with("test"));
+        collect_tests(&decl.children, nested_scope, visit);
+    }
+}
+
+/// `test_apply_budget`, `test_apply_budget_zero` and `ApplyBudget` name `apply_budget`
+fn names_symbol(subject: &str, symbol: &str) -> bool {
+    let normalize = |s: &str| s.replace('_', "").to_lowercase();
+    let subject_lower = subject.to_lowercase();
+    let symbol_lower = symbol.to_lowercase();
+    normalize(subject) == normalize(symbol) || subject_lower.starts_with(&format!("{}_", symbol_lower))
+}
+
+/// `self.budget.apply` → `apply`, `crate::budget::apply_budget` → `apply_budget`
+fn last_segment(path: &str) -> &str {
+    path.rsplit(['.', ':', '/']).next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::LanguageId;
+
+    fn decl(name: &str, line: usize) -> Declaration {
+        let span = Span { start: line * 10, end: line * 10 + 5, start_line: line, end_line: line, start_column: 0, end_column: 0 };
+        Declaration::new(name.to_string(), DeclarationKind::Function, span)
+    }
+
+    #[test]
+    fn test_is_test_path() {
+        assert!(is_test_path("tests/budget.rs"));
+        assert!(is_test_path("src/test_budget.py"));
+        assert!(is_test_path("pkg/budget_test.go"));
+        assert!(is_test_path("web/budget.spec.ts"));
+        assert!(!is_test_path("src/budget.rs"));
+        assert!(!is_test_path("src/contest.rs"));
+    }
+
+    #[test]
+    fn test_tests_for_heuristics() {
+        let mut called = decl("test_allocation_round_trip", 3);
+        called.metadata.insert(CALLS_METADATA.to_string(), "apply_budget,len".to_string());
+        let mut test_file = File::new("tests/budget.rs".to_string(), LanguageId::Rust);
+        test_file.declarations = vec![decl("test_apply_budget_zero", 1), called, decl("helper", 5), decl("test_other", 7)];
+
+        let mut source = File::new("src/budget.rs".to_string(), LanguageId::Rust);
+        let mut module = decl("tests", 10);
+        module.kind = DeclarationKind::Module;
+        module.children = vec![decl("TestApplyBudget", 11)];
+        source.declarations = vec![decl("apply_budget", 1), module];
+
+        let files = [(RawPath::from("tests/budget.rs"), test_file), (RawPath::from("src/budget.rs"), source)];
+        let links = tests_for(files.iter().map(|(p, f)| (p, f)), "crate::budget::apply_budget");
+
+        let found: Vec<(&str, &[TestEvidence])> = links.iter().map(|l| (l.test.as_str(), l.evidence.as_slice())).collect();
+        assert_eq!(found, vec![
+            ("test_allocation_round_trip", &[TestEvidence::Call][..]),
+            ("TestApplyBudget", &[TestEvidence::Name][..]),
+            ("test_apply_budget_zero", &[TestEvidence::Name][..]),
+        ]);
+    }
+}